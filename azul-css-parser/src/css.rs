@@ -2,18 +2,25 @@
 use std::{
     num::ParseIntError,
     fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
 };
 pub use simplecss::Error as CssSyntaxError;
 use simplecss::Tokenizer;
 
-use crate::css_parser;
+use crate::css_parser::{self, InvalidValueErr};
 pub use crate::css_parser::CssParsingError;
 use azul_css::{
     Css, CssDeclaration, Stylesheet,
     DynamicCssProperty, DynamicCssPropertyDefault,
     CssPropertyType, CssRuleBlock, CssPath, CssPathSelector,
-    CssNthChildSelector, CssPathPseudoSelector, CssNthChildSelector::*,
-    NodeTypePath, NodeTypePathParseError,
+    CssNthChildSelector, CssPathPseudoSelector, CssPathPseudoElement, CssNthChildSelector::*,
+    CssAttributeSelector, CssAttributeMatcher,
+    NodeTypePath, NodeTypePathParseError, CustomCssProperty, UnresolvedCssProperty,
+    CssKeyframes, CssKeyframe, FloatValue, PixelValue,
+    MediaQueryList, MediaQuery, MediaType, MediaFeature, MediaOrientation, ColorScheme,
+    CssFontFace, FontFaceSource, FontFaceProperties,
 };
 
 /// Error that can happen during the parsing of a CSS value
@@ -38,8 +45,18 @@ pub enum CssParseErrorInner<'a> {
     PseudoSelectorParseError(CssPseudoSelectorParseError<'a>),
     /// The path has to be either `*`, `div`, `p` or something like that
     NodeTypePath(NodeTypePathParseError<'a>),
+    /// Invalid attribute selector, such as `[=value]` or `[type="text]`
+    InvalidAttributeSelector(&'a str),
     /// A certain property has an unknown key, for example: `alsdfkj: 500px` = `unknown CSS key "alsdfkj: 500px"`
     UnknownPropertyKey(&'a str, &'a str),
+    /// Error while parsing a `@keyframes` rule
+    KeyframesParseError(CssKeyframesParseError<'a>),
+    /// Error while parsing the query of a `@media` rule
+    MediaQueryParseError(CssMediaQueryParseError<'a>),
+    /// Error while parsing a `@font-face` rule
+    FontFaceParseError(CssFontFaceParseError<'a>),
+    /// The file of an `@import` rule has to be a string or `url()`, i.e. `@import "a.css";`
+    InvalidImport(&'a str),
 }
 
 impl_display!{ CssParseErrorInner<'a>, {
@@ -49,7 +66,12 @@ impl_display!{ CssParseErrorInner<'a>, {
     DynamicCssParseError(e) => format!("Error parsing dynamic CSS property: {}", e),
     PseudoSelectorParseError(e) => format!("Failed to parse pseudo-selector: {}", e),
     NodeTypePath(e) => format!("Failed to parse CSS selector path: {}", e),
+    InvalidAttributeSelector(a) => format!("Invalid attribute selector: \"[{}]\"", a),
     UnknownPropertyKey(k, v) => format!("Unknown CSS key: \"{}: {}\"", k, v),
+    KeyframesParseError(e) => format!("Failed to parse @keyframes: {}", e),
+    MediaQueryParseError(e) => format!("Failed to parse @media query: {}", e),
+    FontFaceParseError(e) => format!("Failed to parse @font-face: {}", e),
+    InvalidImport(i) => format!("Invalid @import: \"{}\" - expected a string or url()", i),
 }}
 
 impl<'a> From<CssSyntaxError> for CssParseErrorInner<'a> {
@@ -61,6 +83,9 @@ impl<'a> From<CssSyntaxError> for CssParseErrorInner<'a> {
 impl_from! { DynamicCssParseError<'a>, CssParseErrorInner::DynamicCssParseError }
 impl_from! { NodeTypePathParseError<'a>, CssParseErrorInner::NodeTypePath }
 impl_from! { CssPseudoSelectorParseError<'a>, CssParseErrorInner::PseudoSelectorParseError }
+impl_from! { CssKeyframesParseError<'a>, CssParseErrorInner::KeyframesParseError }
+impl_from! { CssMediaQueryParseError<'a>, CssParseErrorInner::MediaQueryParseError }
+impl_from! { CssFontFaceParseError<'a>, CssParseErrorInner::FontFaceParseError }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CssPseudoSelectorParseError<'a> {
@@ -68,6 +93,9 @@ pub enum CssPseudoSelectorParseError<'a> {
     UnknownSelector(&'a str, Option<&'a str>),
     InvalidNthChildPattern(&'a str),
     InvalidNthChild(ParseIntError),
+    /// The argument of `:not()` has to be a list of simple selectors without
+    /// combinators or pseudo-elements, i.e. `:not(.a)` or `:not(div[disabled])`
+    InvalidNotSelector(&'a str),
 }
 
 impl<'a> From<ParseIntError> for CssPseudoSelectorParseError<'a> {
@@ -91,6 +119,10 @@ impl_display! { CssPseudoSelectorParseError<'a>, {
         number, \"even\" or \"odd\" or a pattern such as \"2n+3\"", selector
     ),
     InvalidNthChild(e) => format!("Invalid :nth-child pseudo-selector: ':{}'", e),
+    InvalidNotSelector(value) => format!(
+        "Invalid pseudo-selector :not({}) - the argument may only contain \
+        simple selectors, such as \".class\", \"div#id\" or \"[disabled]\"", value
+    ),
 }}
 
 /// "selector" contains the actual selector such as "nth-child" while "value" contains
@@ -101,13 +133,28 @@ fn pseudo_selector_from_str<'a>(selector: &'a str, value: Option<&'a str>)
     match selector {
         "first" => Ok(CssPathPseudoSelector::First),
         "last" => Ok(CssPathPseudoSelector::Last),
+        "first-child" => Ok(CssPathPseudoSelector::FirstChild),
+        "last-child" => Ok(CssPathPseudoSelector::LastChild),
+        "only-child" => Ok(CssPathPseudoSelector::OnlyChild),
+        "first-of-type" => Ok(CssPathPseudoSelector::FirstOfType),
+        "last-of-type" => Ok(CssPathPseudoSelector::LastOfType),
+        "only-of-type" => Ok(CssPathPseudoSelector::OnlyOfType),
+        "empty" => Ok(CssPathPseudoSelector::Empty),
+        "root" => Ok(CssPathPseudoSelector::Root),
         "hover" => Ok(CssPathPseudoSelector::Hover),
         "active" => Ok(CssPathPseudoSelector::Active),
         "focus" => Ok(CssPathPseudoSelector::Focus),
-        "nth-child" => {
+        "horizontal" => Ok(CssPathPseudoSelector::Horizontal),
+        "vertical" => Ok(CssPathPseudoSelector::Vertical),
+        "nth-child" | "nth-last-child" | "nth-of-type" | "nth-last-of-type" => {
             let value = value.ok_or(CssPseudoSelectorParseError::EmptyNthChild)?;
             let parsed = parse_nth_child_selector(value)?;
-            Ok(CssPathPseudoSelector::NthChild(parsed))
+            Ok(match selector {
+                "nth-child" => CssPathPseudoSelector::NthChild(parsed),
+                "nth-last-child" => CssPathPseudoSelector::NthLastChild(parsed),
+                "nth-of-type" => CssPathPseudoSelector::NthOfType(parsed),
+                _ => CssPathPseudoSelector::NthLastOfType(parsed),
+            })
         },
         _ => {
             Err(CssPseudoSelectorParseError::UnknownSelector(selector, value))
@@ -115,6 +162,98 @@ fn pseudo_selector_from_str<'a>(selector: &'a str, value: Option<&'a str>)
     }
 }
 
+/// Parses the name of a `::-webkit-scrollbar` pseudo-element, `None` if the name isn't a pseudo-element
+fn pseudo_element_from_str(selector: &str) -> Option<CssPathPseudoElement> {
+    match selector {
+        "-webkit-scrollbar" => Some(CssPathPseudoElement::Scrollbar),
+        "-webkit-scrollbar-track" => Some(CssPathPseudoElement::ScrollbarTrack),
+        "-webkit-scrollbar-track-piece" => Some(CssPathPseudoElement::ScrollbarTrackPiece),
+        "-webkit-scrollbar-thumb" => Some(CssPathPseudoElement::ScrollbarThumb),
+        "-webkit-scrollbar-button" => Some(CssPathPseudoElement::ScrollbarButton),
+        "-webkit-scrollbar-corner" => Some(CssPathPseudoElement::ScrollbarCorner),
+        "-webkit-resizer" => Some(CssPathPseudoElement::Resizer),
+        _ => None,
+    }
+}
+
+/// Parses a pseudo-class (`:hover`) or a pseudo-element (`::-webkit-scrollbar`) selector.
+///
+/// The tokenizer reports the second colon of a `::pseudo-element` as an
+/// empty pseudo-class, which is skipped by returning `None`.
+fn pseudo_class_or_element_from_str<'a>(selector: &'a str, value: Option<&'a str>)
+-> Result<Option<CssPathSelector>, CssPseudoSelectorParseError<'a>>
+{
+    let selector = selector.trim_start_matches(':');
+
+    if selector.is_empty() && value.is_none() {
+        return Ok(None);
+    }
+
+    if let Some(pseudo_element) = pseudo_element_from_str(selector) {
+        return Ok(Some(CssPathSelector::PseudoElement(pseudo_element)));
+    }
+
+    if selector == "not" {
+        let value = value.unwrap_or("");
+        return Ok(Some(CssPathSelector::Not(parse_not_selector(value)?)));
+    }
+
+    Ok(Some(CssPathSelector::PseudoSelector(pseudo_selector_from_str(selector, value)?)))
+}
+
+/// Parses the argument of a `:not()` selector, i.e. `div.a` in `:not(div.a)`
+fn parse_not_selector<'a>(value: &'a str) -> Result<CssPath, CssPseudoSelectorParseError<'a>> {
+    use azul_css::CssPathSelector::*;
+
+    let path = parse_css_path(value).map_err(|_| CssPseudoSelectorParseError::InvalidNotSelector(value))?;
+
+    let is_simple = path.selectors.iter().all(|selector| match selector {
+        Children | DirectChildren | AdjacentSibling | GeneralSibling | PseudoElement(_) => false,
+        _ => true,
+    });
+
+    if is_simple {
+        Ok(path)
+    } else {
+        Err(CssPseudoSelectorParseError::InvalidNotSelector(value))
+    }
+}
+
+/// Parses the inner value of an attribute selector, i.e. `type="text"` in `[type="text"]`
+fn parse_attribute_selector(input: &str) -> Option<CssAttributeSelector> {
+
+    let input = input.trim();
+
+    let (name, matcher) = match input.find('=') {
+        None => (input, CssAttributeMatcher::Exists),
+        Some(equals_pos) => {
+            let value = strip_optional_quotes(&input[(equals_pos + 1)..]);
+            // unbalanced quotes are not stripped, i.e. `[type="text]`
+            if value.starts_with(|c| c == '"' || c == '\'') || value.ends_with(|c| c == '"' || c == '\'') {
+                return None;
+            }
+            let value = value.to_string();
+            let name = &input[..equals_pos];
+            match name.chars().last() {
+                Some('~') => (&name[..(name.len() - 1)], CssAttributeMatcher::Includes(value)),
+                Some('|') => (&name[..(name.len() - 1)], CssAttributeMatcher::DashMatch(value)),
+                Some('^') => (&name[..(name.len() - 1)], CssAttributeMatcher::Prefix(value)),
+                Some('$') => (&name[..(name.len() - 1)], CssAttributeMatcher::Suffix(value)),
+                Some('*') => (&name[..(name.len() - 1)], CssAttributeMatcher::Substring(value)),
+                _ => (name, CssAttributeMatcher::Equals(value)),
+            }
+        }
+    };
+
+    let name = name.trim();
+
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return None;
+    }
+
+    Some(CssAttributeSelector { name: name.to_string(), matcher })
+}
+
 /// Parses the inner value of the `:nth-child` selector, including numbers and patterns.
 ///
 /// I.e.: `"2n+3"` -> `Pattern { repeat: 2, offset: 3 }`
@@ -138,7 +277,9 @@ fn parse_nth_child_selector<'a>(value: &'a str) -> Result<CssNthChildSelector, C
     }
 }
 
-/// Parses the pattern between the braces of a "nth-child" (such as "2n+3").
+/// Parses the `An+B` pattern between the braces of a "nth-child" (such as "2n+3", "-n + 3" or "2n-1").
+///
+/// Whitespace is only allowed around the sign between `An` and `B`, i.e. `2 n` is invalid.
 fn parse_nth_child_pattern<'a>(value: &'a str) -> Result<CssNthChildSelector, CssPseudoSelectorParseError<'a>> {
 
     let value = value.trim();
@@ -147,33 +288,48 @@ fn parse_nth_child_pattern<'a>(value: &'a str) -> Result<CssNthChildSelector, Cs
         return Err(CssPseudoSelectorParseError::EmptyNthChild);
     }
 
-    // TODO: Test for "+"
-    let repeat = value.split("n").next()
-        .ok_or(CssPseudoSelectorParseError::InvalidNthChildPattern(value))?
-        .trim()
-        .parse::<usize>()?;
+    let n_position = match value.find(|c| c == 'n' || c == 'N') {
+        Some(p) => p,
+        // Only `B`, i.e. `-3` (positive numbers are already parsed as `Number`)
+        None => return Ok(Pattern { repeat: 0, offset: parse_nth_child_integer(value)? }),
+    };
 
-    // In a "2n+3" form, the first .next() yields the "2n", the second .next() yields the "3"
-    let mut offset_iterator = value.split("+");
+    // In a "2n+3" form, the "2" is the repeat, the "+3" is the offset
+    let repeat = match &value[..n_position] {
+        "" | "+" => 1,
+        "-" => -1,
+        other => parse_nth_child_integer(other)?,
+    };
 
-    // has to succeed, since the string is verified to not be empty
-    offset_iterator.next().unwrap();
+    let offset_string = value[(n_position + 1)..].trim_start();
 
-    let offset = match offset_iterator.next() {
-        Some(offset_string) => {
-            let offset_string = offset_string.trim();
-            if offset_string.is_empty() {
-                return Err(CssPseudoSelectorParseError::InvalidNthChildPattern(value));
-            } else {
-                offset_string.parse::<usize>()?
-            }
-        },
-        None => 0,
+    let offset = if offset_string.is_empty() {
+        0
+    } else {
+        let sign = match offset_string.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return Err(CssPseudoSelectorParseError::InvalidNthChildPattern(value)),
+        };
+        let digits = offset_string[1..].trim_start();
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(CssPseudoSelectorParseError::InvalidNthChildPattern(value));
+        }
+        sign * digits.parse::<isize>()?
     };
 
     Ok(Pattern { repeat, offset })
 }
 
+/// Parses a signed integer without whitespace, i.e. the "-2" of "-2n+3"
+fn parse_nth_child_integer<'a>(value: &'a str) -> Result<isize, CssPseudoSelectorParseError<'a>> {
+    let digits = value.trim_start_matches(|c| c == '+' || c == '-');
+    if digits.is_empty() || value.len() - digits.len() > 1 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(CssPseudoSelectorParseError::InvalidNthChildPattern(value));
+    }
+    Ok(value.parse::<isize>()?)
+}
+
 #[test]
 fn test_css_pseudo_selector_parse() {
 
@@ -186,17 +342,40 @@ fn test_css_pseudo_selector_parse() {
         (("hover", None), Hover),
         (("active", None), Active),
         (("focus", None), Focus),
+        (("horizontal", None), Horizontal),
+        (("vertical", None), Vertical),
         (("nth-child", Some("4")), NthChild(Number(4))),
         (("nth-child", Some("even")), NthChild(Even)),
         (("nth-child", Some("odd")), NthChild(Odd)),
         (("nth-child", Some("5n")), NthChild(Pattern { repeat: 5, offset: 0 })),
         (("nth-child", Some("2n+3")), NthChild(Pattern { repeat: 2, offset: 3 })),
+        (("nth-child", Some("2n-1")), NthChild(Pattern { repeat: 2, offset: -1 })),
+        (("nth-child", Some("-n+3")), NthChild(Pattern { repeat: -1, offset: 3 })),
+        (("nth-child", Some("+n")), NthChild(Pattern { repeat: 1, offset: 0 })),
+        (("nth-child", Some(" -2n + 4 ")), NthChild(Pattern { repeat: -2, offset: 4 })),
+        (("nth-child", Some("-3")), NthChild(Pattern { repeat: 0, offset: -3 })),
+        (("nth-last-child", Some("2")), NthLastChild(Number(2))),
+        (("nth-of-type", Some("odd")), NthOfType(Odd)),
+        (("nth-last-of-type", Some("n-1")), NthLastOfType(Pattern { repeat: 1, offset: -1 })),
+        (("first-child", None), FirstChild),
+        (("last-child", None), LastChild),
+        (("only-child", None), OnlyChild),
+        (("first-of-type", None), FirstOfType),
+        (("last-of-type", None), LastOfType),
+        (("only-of-type", None), OnlyOfType),
+        (("empty", None), Empty),
+        (("root", None), Root),
     ];
 
     let err = [
         (("asdf", None), UnknownSelector("asdf", None)),
         (("", None), UnknownSelector("", None)),
         (("nth-child", Some("2n+")), InvalidNthChildPattern("2n+")),
+        (("nth-child", Some("2 n")), InvalidNthChildPattern("2 ")),
+        (("nth-child", Some("2n+-1")), InvalidNthChildPattern("2n+-1")),
+        (("nth-child", Some("--n")), InvalidNthChildPattern("--")),
+        (("nth-child", Some("2n 1")), InvalidNthChildPattern("2n 1")),
+        (("nth-last-child", None), EmptyNthChild),
         // Can't test for ParseIntError because the fields are private.
         // This is an example on why you shouldn't use std::error::Error!
     ];
@@ -222,34 +401,770 @@ impl<'a> fmt::Display for CssParseError<'a> {
     }
 }
 
+/// Parses a CSS string into a single stylesheet.
+///
+/// `@import` rules are ignored, since there is no file that the imports could be
+/// resolved against - use `new_from_file` to load a CSS file with imports.
 pub fn new_from_str<'a>(css_string: &'a str) -> Result<Css, CssParseError<'a>> {
-    let mut tokenizer = Tokenizer::new(css_string);
-    match new_from_str_inner(css_string, &mut tokenizer) {
-        Ok(stylesheet) => Ok(Css {
-            stylesheets: vec![
-                stylesheet
-            ],
-        }),
-        Err(e) => {
-            let error_location = tokenizer.pos().saturating_sub(1);
-            let line_number: usize = css_string[0..error_location].lines().count();
-
-            // Rust doesn't count "\n" as a character, so we have to add the line number count on top
-            let total_characters: usize = css_string[0..error_location].lines().take(line_number.saturating_sub(1)).map(|line| line.chars().count()).sum();
-            let total_characters = total_characters + line_number;
-            /*println!("line_number: {} error location: {}, total characters: {}", line_number,
-                     error_location, total_characters);*/
-            let characters_in_line = (error_location + 2) - total_characters;
-
-            let error_location = ErrorLocation {
-                line: line_number,
-                column: characters_in_line,
+    let (stylesheet, _imports) = parse_stylesheet(css_string)?;
+    Ok(Css {
+        stylesheets: vec![
+            stylesheet
+        ],
+    })
+}
+
+/// Error that can happen while loading a CSS file with `new_from_file`
+#[derive(Debug)]
+pub enum CssFileLoadError {
+    /// The file (or one of the files it imports) could not be read
+    Io(PathBuf, io::Error),
+    /// The file (or one of the files it imports) contains invalid CSS. The error is
+    /// stored as a string, since a `CssParseError` borrows the content of the file.
+    ParseError(PathBuf, String),
+    /// The file (indirectly) imports itself
+    RecursiveImport(PathBuf),
+}
+
+impl_display!{ CssFileLoadError, {
+    Io(path, e) => format!("Io error: Could not load \"{}\": \"{}\"", path.display(), e),
+    ParseError(path, e) => format!("{}: {}", path.display(), e),
+    RecursiveImport(path) => format!("{}: recursive @import - the file imports itself", path.display()),
+}}
+
+/// Loads and parses a CSS file. Other than `new_from_str`, this resolves the `@import` rules
+/// of the file (relative to the directory of the file) and makes the `url()`s of
+/// `@font-face` rules relative to the file they are declared in.
+///
+/// Imported rules are inserted before the rules of the importing file.
+pub fn new_from_file<P: AsRef<Path>>(file_path: P) -> Result<Css, CssFileLoadError> {
+    let mut import_stack = Vec::new();
+    let stylesheet = load_stylesheet_file(file_path.as_ref(), &mut import_stack)?;
+    Ok(Css {
+        stylesheets: vec![
+            stylesheet
+        ],
+    })
+}
+
+/// Loads a CSS file and (recursively) all files it imports into one stylesheet.
+/// `import_stack` contains the files that are currently being loaded, to detect recursive imports.
+fn load_stylesheet_file(file_path: &Path, import_stack: &mut Vec<PathBuf>) -> Result<Stylesheet, CssFileLoadError> {
+
+    let io_error = |e| CssFileLoadError::Io(file_path.to_path_buf(), e);
+    let css_string = fs::read_to_string(file_path).map_err(io_error)?;
+    let canonical_path = fs::canonicalize(file_path).map_err(io_error)?;
+
+    if import_stack.contains(&canonical_path) {
+        return Err(CssFileLoadError::RecursiveImport(file_path.to_path_buf()));
+    }
+
+    let (mut stylesheet, imports) = parse_stylesheet(&css_string)
+        .map_err(|e| CssFileLoadError::ParseError(file_path.to_path_buf(), e.to_string()))?;
+
+    let base_directory = file_path.parent().unwrap_or_else(|| Path::new(""));
+
+    for font_face in &mut stylesheet.font_faces {
+        for source in &mut font_face.sources {
+            if let FontFaceSource::Url(font_path) = source {
+                *font_path = base_directory.join(&font_path);
+            }
+        }
+    }
+
+    let mut merged_stylesheet = Stylesheet::default();
+
+    import_stack.push(canonical_path);
+    for import in imports {
+        let imported_stylesheet = load_stylesheet_file(&base_directory.join(import.file_path), import_stack)?;
+        merged_stylesheet.rules.extend(imported_stylesheet.rules.into_iter().map(|mut rule| {
+            if let Some(media_query) = &import.media_query {
+                rule.media_queries.insert(0, media_query.clone());
+            }
+            rule
+        }));
+        merged_stylesheet.keyframes.extend(imported_stylesheet.keyframes);
+        merged_stylesheet.font_faces.extend(imported_stylesheet.font_faces);
+    }
+    import_stack.pop();
+
+    merged_stylesheet.rules.extend(stylesheet.rules);
+    merged_stylesheet.keyframes.extend(stylesheet.keyframes);
+    merged_stylesheet.font_faces.extend(stylesheet.font_faces);
+
+    Ok(merged_stylesheet)
+}
+
+/// An `@import` rule, such as `@import "dark.css" (prefers-color-scheme: dark);`
+#[derive(Debug, Clone, PartialEq)]
+struct CssImport<'a> {
+    /// Path of the imported file, relative to the importing file
+    file_path: &'a str,
+    /// If set, the imported rules only apply if the query matches
+    media_query: Option<MediaQueryList>,
+}
+
+/// Parses a CSS string into a stylesheet, returns the (unresolved) `@import` rules separately
+fn parse_stylesheet<'a>(css_string: &'a str) -> Result<(Stylesheet, Vec<CssImport<'a>>), CssParseError<'a>> {
+    let mut stylesheet = Stylesheet::default();
+    let mut imports = Vec::new();
+    parse_rule_blocks(css_string, 0, css_string.len(), &[], &mut stylesheet, &mut imports)?;
+    Ok((stylesheet, imports))
+}
+
+/// Parses the rules and @-rules in `css_string[start..end]` into the `stylesheet`.
+/// `media_queries` are the queries of the `@media` blocks that the rules are nested in.
+fn parse_rule_blocks<'a>(
+    css_string: &'a str,
+    start: usize,
+    end: usize,
+    media_queries: &[MediaQueryList],
+    stylesheet: &mut Stylesheet,
+    imports: &mut Vec<CssImport<'a>>,
+) -> Result<(), CssParseError<'a>> {
+
+    let block = &css_string[start..end];
+
+    let at_rules = find_at_rules(block).map_err(|(error, position)| CssParseError {
+        error,
+        location: get_error_location(css_string, start + position),
+    })?;
+
+    // The tokenizer can't handle nested blocks, so only the
+    // CSS between the @-rules is tokenized as regular rule blocks
+    let mut segment_start = 0;
+    let segment_ends = at_rules.iter().map(|at_rule| (at_rule.start, Some(at_rule))).chain(Some((block.len(), None)));
+
+    for (segment_end, at_rule) in segment_ends {
+
+        let segment = &block[segment_start..segment_end];
+        let mut tokenizer = Tokenizer::new(segment);
+        match new_from_str_inner(segment, &mut tokenizer) {
+            Ok(rules) => {
+                stylesheet.rules.extend(rules.into_iter().map(|mut rule| {
+                    rule.media_queries = media_queries.to_vec();
+                    rule
+                }));
+            },
+            Err(e) => {
+                let error_location = start + segment_start + tokenizer.pos().saturating_sub(1);
+                return Err(CssParseError {
+                    error: e,
+                    location: get_error_location(css_string, error_location),
+                });
+            }
+        }
+
+        let at_rule = match at_rule {
+            Some(s) => s,
+            None => break,
+        };
+
+        let at_rule_location = get_error_location(css_string, start + at_rule.start);
+
+        match at_rule.name {
+            "keyframes" => {
+                // NOTE: @keyframes inside of @media blocks are always defined
+                let keyframes = parse_keyframes(at_rule.prelude, at_rule.block.unwrap_or("")).map_err(|e| CssParseError {
+                    error: e.into(),
+                    location: at_rule_location,
+                })?;
+                stylesheet.keyframes.push(keyframes);
+            },
+            "media" => {
+                let query_list = parse_media_query_list(at_rule.prelude).map_err(|e| CssParseError {
+                    error: e.into(),
+                    location: at_rule_location,
+                })?;
+                if let Some(media_block) = at_rule.block {
+                    // at_rule.end is the position after the closing brace of the block
+                    let block_end = start + at_rule.end - 1;
+                    let block_start = block_end - media_block.len();
+                    let mut nested_media_queries = media_queries.to_vec();
+                    nested_media_queries.push(query_list);
+                    parse_rule_blocks(css_string, block_start, block_end, &nested_media_queries, stylesheet, imports)?;
+                }
+            },
+            "font-face" => {
+                // NOTE: like @keyframes, fonts inside of @media blocks are always defined
+                let font_face = parse_font_face(at_rule.block.unwrap_or("")).map_err(|e| CssParseError {
+                    error: e.into(),
+                    location: at_rule_location,
+                })?;
+                stylesheet.font_faces.push(font_face);
+            },
+            // @import is only allowed at the top level of the stylesheet
+            "import" if media_queries.is_empty() => {
+                let import = parse_import(at_rule.prelude).map_err(|e| CssParseError {
+                    error: e,
+                    location: at_rule_location,
+                })?;
+                imports.push(import);
+            },
+            _ => {
+                // Unknown @-rules are ignored, like in browsers
+            }
+        }
+
+        segment_start = at_rule.end;
+    }
+
+    Ok(())
+}
+
+/// Converts a byte offset into the CSS string to a line and column number
+fn get_error_location(css_string: &str, error_location: usize) -> ErrorLocation {
+
+    let line_number: usize = css_string[0..error_location].lines().count();
+
+    // Rust doesn't count "\n" as a character, so we have to add the line number count on top
+    let total_characters: usize = css_string[0..error_location].lines().take(line_number.saturating_sub(1)).map(|line| line.chars().count()).sum();
+    let total_characters = total_characters + line_number;
+    /*println!("line_number: {} error location: {}, total characters: {}", line_number,
+             error_location, total_characters);*/
+    let characters_in_line = (error_location + 2) - total_characters;
+
+    ErrorLocation {
+        line: line_number,
+        column: characters_in_line,
+    }
+}
+
+/// An `@`-rule, such as `@keyframes fade-in { ... }`
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct CssAtRule<'a> {
+    /// Name of the rule without the `@`, i.e. `keyframes`
+    name: &'a str,
+    /// Everything between the name and the block (or the semicolon), i.e. `fade-in`
+    prelude: &'a str,
+    /// Content of the block without the outer braces, `None` for rules without a
+    /// block, such as `@import "a.css";`
+    block: Option<&'a str>,
+    /// Byte offset of the `@` in the CSS string
+    start: usize,
+    /// Byte offset of the first character after the rule
+    end: usize,
+}
+
+/// Finds all top-level `@`-rules of a CSS string (ignoring comments and strings).
+/// On failure, returns the error and the byte offset where the error happened.
+fn find_at_rules<'a>(css_string: &'a str) -> Result<Vec<CssAtRule<'a>>, (CssParseErrorInner<'a>, usize)> {
+
+    let mut at_rules = Vec::new();
+    let mut block_nesting = 0_usize;
+    let mut position = 0;
+
+    while let Some(next) = skip_comments_and_strings(css_string, position) {
+        match css_string.as_bytes()[next] {
+            b'{' => block_nesting += 1,
+            b'}' => block_nesting = block_nesting.saturating_sub(1),
+            b'@' if block_nesting == 0 => {
+                let at_rule = parse_at_rule(css_string, next).ok_or((CssParseErrorInner::UnclosedBlock, next))?;
+                position = at_rule.end;
+                at_rules.push(at_rule);
+                continue;
+            },
+            _ => { },
+        }
+        position = next + 1;
+    }
+
+    Ok(at_rules)
+}
+
+/// Returns the byte offset of the next character at or after `position` that is not inside
+/// of a comment or a quoted string, `None` if the end of the string has been reached
+fn skip_comments_and_strings(input: &str, mut position: usize) -> Option<usize> {
+    let bytes = input.as_bytes();
+    while position < bytes.len() {
+        match bytes[position] {
+            b'/' if bytes.get(position + 1) == Some(&b'*') => {
+                position = input[(position + 2)..].find("*/").map(|end| position + 2 + end + 2).unwrap_or(bytes.len());
+            },
+            quote @ b'"' | quote @ b'\'' => {
+                position = input[(position + 1)..].find(quote as char).map(|end| position + 1 + end + 1).unwrap_or(bytes.len());
+            },
+            _ => return Some(position),
+        }
+    }
+    None
+}
+
+/// Parses the `@`-rule starting at `start`, returns `None` if the block of the rule is not closed
+fn parse_at_rule<'a>(css_string: &'a str, start: usize) -> Option<CssAtRule<'a>> {
+
+    let name_start = start + 1;
+    let name_len = css_string[name_start..].find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_')).unwrap_or(css_string.len() - name_start);
+    let name = &css_string[name_start..(name_start + name_len)];
+
+    // Find the end of the prelude, either a semicolon or the start of the block
+    let prelude_start = name_start + name_len;
+    let mut position = prelude_start;
+    let prelude_end = loop {
+        let next = match skip_comments_and_strings(css_string, position) {
+            Some(s) => s,
+            // Rules without a block can be terminated by the end of the stylesheet
+            None => return Some(CssAtRule { name, prelude: &css_string[prelude_start..], block: None, start, end: css_string.len() }),
+        };
+        match css_string.as_bytes()[next] {
+            b';' => return Some(CssAtRule { name, prelude: &css_string[prelude_start..next], block: None, start, end: next + 1 }),
+            b'{' => break next,
+            _ => position = next + 1,
+        }
+    };
+
+    let mut block_nesting = 0_usize;
+    let mut position = prelude_end;
+    while let Some(next) = skip_comments_and_strings(css_string, position) {
+        match css_string.as_bytes()[next] {
+            b'{' => block_nesting += 1,
+            b'}' => {
+                block_nesting -= 1;
+                if block_nesting == 0 {
+                    return Some(CssAtRule {
+                        name,
+                        prelude: &css_string[prelude_start..prelude_end],
+                        block: Some(&css_string[(prelude_end + 1)..next]),
+                        start,
+                        end: next + 1,
+                    });
+                }
+            },
+            _ => { },
+        }
+        position = next + 1;
+    }
+
+    None
+}
+
+/// Error that can happen while parsing a `@keyframes` rule
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssKeyframesParseError<'a> {
+    /// The `@keyframes` rule has no name, i.e. `@keyframes { ... }`
+    MissingName,
+    /// The selector of a keyframe has to be `from`, `to` or a percentage, i.e. `50%`
+    InvalidKeyframeSelector(&'a str),
+    /// Content that isn't part of a keyframe block, i.e. `@keyframes a { opacity: 0; }`
+    MalformedKeyframe(&'a str),
+    /// A declaration without a colon, i.e. `from { opacity }`
+    MalformedDeclaration(&'a str),
+    /// Unknown CSS key inside of a keyframe
+    UnknownPropertyKey(&'a str, &'a str),
+    /// Dynamic CSS properties and `var()` references can't be used in keyframes
+    UnsupportedValue(&'a str),
+    /// Error while parsing the value of a property
+    PropertyParseError(CssParsingError<'a>),
+}
+
+impl_display!{ CssKeyframesParseError<'a>, {
+    MissingName => "Missing name of the @keyframes rule",
+    InvalidKeyframeSelector(s) => format!("Invalid keyframe selector: \"{}\" - expected \"from\", \"to\" or a percentage", s),
+    MalformedKeyframe(s) => format!("Malformed keyframe: \"{}\"", s),
+    MalformedDeclaration(s) => format!("Malformed declaration: \"{}\"", s),
+    UnknownPropertyKey(k, v) => format!("Unknown CSS key: \"{}: {}\"", k, v),
+    UnsupportedValue(v) => format!("Dynamic properties and var() are not supported in keyframes: \"{}\"", v),
+    PropertyParseError(e) => format!("{}", e),
+}}
+
+impl_from! { CssParsingError<'a>, CssKeyframesParseError::PropertyParseError }
+
+/// Parses the name and the block of a `@keyframes name { ... }` rule
+fn parse_keyframes<'a>(prelude: &'a str, block: &'a str) -> Result<CssKeyframes, CssKeyframesParseError<'a>> {
+
+    use self::CssKeyframesParseError::*;
+
+    let name = trim_comments(prelude).trim_matches('"').trim_matches('\'');
+    if name.is_empty() {
+        return Err(MissingName);
+    }
+
+    let css_property_map = azul_css::get_css_key_map();
+    let mut keyframes = Vec::new();
+    let mut remaining = block;
+
+    loop {
+        remaining = trim_comments(remaining);
+        if remaining.is_empty() {
+            break;
+        }
+
+        let block_start = remaining.find('{').ok_or(MalformedKeyframe(remaining))?;
+        let block_end = remaining.find('}').ok_or(MalformedKeyframe(remaining))?;
+        if block_end < block_start {
+            return Err(MalformedKeyframe(remaining));
+        }
+
+        let mut declarations = Vec::new();
+        for declaration in remaining[(block_start + 1)..block_end].split(';').map(trim_comments).filter(|d| !d.is_empty()) {
+            let colon = declaration.find(':').ok_or(MalformedDeclaration(declaration))?;
+            let (key, value) = (declaration[..colon].trim(), declaration[(colon + 1)..].trim());
+            if value.starts_with(START_BRACE) || value.contains("var(") {
+                return Err(UnsupportedValue(value));
+            }
+            let parsed_key = CssPropertyType::from_str(key, &css_property_map).ok_or(UnknownPropertyKey(key, value))?;
+            declarations.push(css_parser::parse_key_value_pair(parsed_key, value)?);
+        }
+
+        // "0%, 100% { ... }" creates two keyframes with the same declarations
+        for selector in remaining[..block_start].split(',').map(trim_comments) {
+            let offset = match selector {
+                "from" => 0.0,
+                "to" => 1.0,
+                percentage if percentage.ends_with('%') => {
+                    match percentage[..(percentage.len() - 1)].trim().parse::<f32>() {
+                        Ok(p) if p >= 0.0 && p <= 100.0 => p / 100.0,
+                        _ => return Err(InvalidKeyframeSelector(selector)),
+                    }
+                },
+                _ => return Err(InvalidKeyframeSelector(selector)),
             };
+            keyframes.push(CssKeyframe { offset: FloatValue::new(offset), declarations: declarations.clone() });
+        }
 
-            Err(CssParseError {
-                error: e,
-                location: error_location,
-            })
+        remaining = &remaining[(block_end + 1)..];
+    }
+
+    // Stable sort, so that later keyframes with the same offset still take precedence
+    keyframes.sort_by_key(|keyframe| keyframe.offset);
+
+    Ok(CssKeyframes {
+        name: name.to_string(),
+        keyframes,
+    })
+}
+
+/// Parses the prelude of an `@import` rule, i.e. `url("a.css") screen and (max-width: 600px)`
+fn parse_import<'a>(prelude: &'a str) -> Result<CssImport<'a>, CssParseErrorInner<'a>> {
+
+    let prelude = trim_comments(prelude);
+
+    let (file_path, media_query) = if prelude.starts_with("url(") {
+        let url_end = prelude.find(')').ok_or(CssParseErrorInner::InvalidImport(prelude))?;
+        (strip_optional_quotes(&prelude[4..url_end]), &prelude[(url_end + 1)..])
+    } else if prelude.starts_with('"') || prelude.starts_with('\'') {
+        let quote = &prelude[..1];
+        let string_end = prelude[1..].find(quote).ok_or(CssParseErrorInner::InvalidImport(prelude))? + 1;
+        (&prelude[1..string_end], &prelude[(string_end + 1)..])
+    } else {
+        return Err(CssParseErrorInner::InvalidImport(prelude));
+    };
+
+    if file_path.is_empty() {
+        return Err(CssParseErrorInner::InvalidImport(prelude));
+    }
+
+    let media_query = trim_comments(media_query);
+    let media_query = if media_query.is_empty() { None } else { Some(parse_media_query_list(media_query)?) };
+
+    Ok(CssImport { file_path, media_query })
+}
+
+/// Error that can happen while parsing a `@font-face` rule
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssFontFaceParseError<'a> {
+    /// The `font-family` descriptor is missing or empty
+    MissingFontFamily,
+    /// The `src` descriptor is missing or empty
+    MissingSource,
+    /// A source of the `src` that isn't `url()` or `local()`
+    InvalidSource(&'a str),
+    /// A declaration without a colon, i.e. `font-family Roboto`
+    MalformedDeclaration(&'a str),
+    /// Invalid value of the `font-weight`, `font-style` or `font-stretch` descriptor
+    InvalidDescriptor(InvalidValueErr<'a>),
+}
+
+impl_display!{ CssFontFaceParseError<'a>, {
+    MissingFontFamily => "Missing font-family",
+    MissingSource => "Missing src",
+    InvalidSource(s) => format!("Invalid font source: \"{}\" - expected url() or local()", s),
+    MalformedDeclaration(s) => format!("Malformed declaration: \"{}\"", s),
+    InvalidDescriptor(e) => format!("Invalid descriptor value: \"{}\"", e.0),
+}}
+
+/// Parses the block of a `@font-face` rule. Only `font-family`, `src`, `font-weight`,
+/// `font-style` and `font-stretch` are used, other descriptors (such as `font-display`
+/// or `unicode-range`) are ignored.
+fn parse_font_face<'a>(block: &'a str) -> Result<CssFontFace, CssFontFaceParseError<'a>> {
+
+    use self::CssFontFaceParseError::*;
+
+    let mut font_family = None;
+    let mut sources = Vec::new();
+    let mut properties = FontFaceProperties::default();
+
+    for declaration in block.split(';').map(trim_comments).filter(|d| !d.is_empty()) {
+        let colon = declaration.find(':').ok_or(MalformedDeclaration(declaration))?;
+        let (key, value) = (declaration[..colon].trim(), declaration[(colon + 1)..].trim());
+        match key {
+            "font-family" => font_family = Some(strip_optional_quotes(value)),
+            "font-weight" => properties.weight = css_parser::parse_style_font_weight(value).map_err(InvalidDescriptor)?,
+            "font-style" => properties.style = css_parser::parse_style_font_style(value).map_err(InvalidDescriptor)?,
+            "font-stretch" => properties.stretch = css_parser::parse_style_font_stretch(value).map_err(InvalidDescriptor)?,
+            "src" => {
+                sources.clear();
+                for source in css_parser::split_commas_outside_braces(value) {
+                    sources.push(parse_font_face_source(source).ok_or(InvalidSource(source))?);
+                }
+            },
+            _ => { },
+        }
+    }
+
+    let font_family = font_family.filter(|f| !f.is_empty()).ok_or(MissingFontFamily)?;
+
+    if sources.is_empty() {
+        return Err(MissingSource);
+    }
+
+    Ok(CssFontFace {
+        font_family: font_family.to_string(),
+        sources,
+        properties,
+    })
+}
+
+/// Parses one source of a `src` descriptor, i.e. `url("a.ttf") format("truetype")` or `local(Arial)`
+fn parse_font_face_source(source: &str) -> Option<FontFaceSource> {
+    // The format() hint is ignored, the font is loaded either way
+    let function_end = source.find(')')?;
+    let function = &source[..function_end];
+    if function.starts_with("url(") {
+        let url = strip_optional_quotes(&function[4..]);
+        if url.is_empty() { None } else { Some(FontFaceSource::Url(PathBuf::from(url))) }
+    } else if function.starts_with("local(") {
+        let name = strip_optional_quotes(&function[6..]);
+        if name.is_empty() { None } else { Some(FontFaceSource::Local(name.to_string())) }
+    } else {
+        None
+    }
+}
+
+/// Trims whitespace and removes matching quotes around the input, i.e. `"Arial"` or `'Arial'`
+fn strip_optional_quotes(input: &str) -> &str {
+    let input = input.trim();
+    let is_quoted = input.len() >= 2 && (
+        (input.starts_with('"') && input.ends_with('"')) ||
+        (input.starts_with('\'') && input.ends_with('\''))
+    );
+    if is_quoted { &input[1..(input.len() - 1)] } else { input }
+}
+
+/// Removes a trailing `!important` (case-insensitive, i.e. `"red ! IMPORTANT"`) from
+/// the value of a declaration, returns the remaining value and whether it was important
+fn strip_important(value: &str) -> (&str, bool) {
+    let trimmed = value.trim_end();
+    let keyword = "important";
+    if trimmed.len() < keyword.len() || !trimmed.is_char_boundary(trimmed.len() - keyword.len()) {
+        return (value, false);
+    }
+    let (rest, suffix) = trimmed.split_at(trimmed.len() - keyword.len());
+    if !suffix.eq_ignore_ascii_case(keyword) {
+        return (value, false);
+    }
+    let rest = rest.trim_end();
+    if rest.ends_with('!') {
+        (rest[..(rest.len() - 1)].trim_end(), true)
+    } else {
+        (value, false)
+    }
+}
+
+/// Error that can happen while parsing the query of a `@media` rule
+#[derive(Debug, Clone, PartialEq)]
+pub enum CssMediaQueryParseError<'a> {
+    /// Empty query, i.e. `@media { ... }` or `@media screen, { ... }`
+    EmptyQuery,
+    /// Media type other than `all`, `screen` or `print`, i.e. `@media tv`
+    UnknownMediaType(&'a str),
+    /// Unsupported media feature, i.e. `(hover: none)`
+    UnknownMediaFeature(&'a str),
+    /// Media feature without a value, i.e. `(max-width)`
+    MissingFeatureValue(&'a str),
+    /// Invalid value of a media feature, i.e. `(orientation: up)`
+    InvalidFeatureValue(&'a str, &'a str),
+    /// Parenthesis of a media feature isn't closed, i.e. `(max-width: 600px`
+    UnclosedParenthesis(&'a str),
+    /// Conditions have to be joined with `and`, i.e. `screen (max-width: 600px)`
+    MalformedQuery(&'a str),
+}
+
+impl_display!{ CssMediaQueryParseError<'a>, {
+    EmptyQuery => "Empty media query",
+    UnknownMediaType(t) => format!("Unknown media type: \"{}\" - expected \"all\", \"screen\" or \"print\"", t),
+    UnknownMediaFeature(f) => format!("Unknown media feature: \"{}\"", f),
+    MissingFeatureValue(f) => format!("Media feature \"{}\" has no value", f),
+    InvalidFeatureValue(f, v) => format!("Invalid value for media feature \"{}\": \"{}\"", f, v),
+    UnclosedParenthesis(s) => format!("Unclosed parenthesis: \"{}\"", s),
+    MalformedQuery(q) => format!("Malformed media query: \"{}\"", q),
+}}
+
+/// Parses the query of a `@media` rule, i.e. `screen and (max-width: 600px), (orientation: portrait)`
+///
+/// Supported media features are `min-width`, `max-width`, `min-height`, `max-height`,
+/// `orientation`, `resolution`, `min-resolution`, `max-resolution` and `prefers-color-scheme`.
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_media_query_list;
+/// # use azul_css::{MediaQueryList, MediaQuery, MediaType, MediaFeature, PixelValue};
+/// assert_eq!(
+///     parse_media_query_list("screen and (max-width: 600px)"),
+///     Ok(MediaQueryList(vec![MediaQuery {
+///         negated: false,
+///         media_type: MediaType::Screen,
+///         features: vec![MediaFeature::MaxWidth(PixelValue::px(600.0))],
+///     }]))
+/// );
+/// ```
+pub fn parse_media_query_list<'a>(input: &'a str) -> Result<MediaQueryList, CssMediaQueryParseError<'a>> {
+    let queries = input.split(',').map(parse_media_query).collect::<Result<Vec<_>, _>>()?;
+    Ok(MediaQueryList(queries))
+}
+
+/// Parses one query of a comma-separated media query list
+fn parse_media_query<'a>(input: &'a str) -> Result<MediaQuery, CssMediaQueryParseError<'a>> {
+
+    use self::CssMediaQueryParseError::*;
+
+    let input = trim_comments(input);
+    if input.is_empty() {
+        return Err(EmptyQuery);
+    }
+
+    let mut negated = false;
+    let mut has_prefix = false;
+    let mut media_type = None;
+    let mut features = Vec::new();
+    // true after a media type or feature, false after an `and`
+    let mut expect_and = false;
+    let mut remaining = input;
+
+    loop {
+        remaining = remaining.trim_start();
+        if remaining.is_empty() {
+            break;
+        }
+
+        if remaining.starts_with('(') {
+            if expect_and {
+                return Err(MalformedQuery(input));
+            }
+            let feature_end = remaining.find(')').ok_or(UnclosedParenthesis(remaining))?;
+            features.push(parse_media_feature(&remaining[1..feature_end])?);
+            remaining = &remaining[(feature_end + 1)..];
+            expect_and = true;
+            continue;
+        }
+
+        let word_end = remaining.find(|c: char| c.is_whitespace() || c == '(').unwrap_or(remaining.len());
+        let word = &remaining[..word_end];
+        remaining = &remaining[word_end..];
+
+        let is_start_of_query = !has_prefix && media_type.is_none() && features.is_empty();
+
+        match word {
+            "and" if expect_and => { expect_and = false; },
+            "not" | "only" if is_start_of_query => {
+                // `only` only exists to hide the query from old browsers
+                negated = word == "not";
+                has_prefix = true;
+            },
+            _ if media_type.is_none() && features.is_empty() && !expect_and => {
+                media_type = Some(match word {
+                    "all" => MediaType::All,
+                    "screen" => MediaType::Screen,
+                    "print" => MediaType::Print,
+                    other => return Err(UnknownMediaType(other)),
+                });
+                expect_and = true;
+            },
+            _ => return Err(MalformedQuery(input)),
+        }
+    }
+
+    // Query ends with "and" or only consists of "not" / "only"
+    if !expect_and {
+        return Err(MalformedQuery(input));
+    }
+
+    Ok(MediaQuery {
+        negated,
+        media_type: media_type.unwrap_or(MediaType::All),
+        features,
+    })
+}
+
+/// Parses the content of the parenthesis of a media feature, i.e. `max-width: 600px`
+fn parse_media_feature<'a>(input: &'a str) -> Result<MediaFeature, CssMediaQueryParseError<'a>> {
+
+    use self::CssMediaQueryParseError::*;
+
+    let mut split = input.splitn(2, ':');
+    let name = split.next().unwrap_or("").trim();
+    let value = split.next().map(|v| v.trim()).ok_or(MissingFeatureValue(name))?;
+    let invalid_value = || InvalidFeatureValue(name, value);
+
+    let feature = match name {
+        "min-width" => MediaFeature::MinWidth(parse_media_length(value).ok_or_else(invalid_value)?),
+        "max-width" => MediaFeature::MaxWidth(parse_media_length(value).ok_or_else(invalid_value)?),
+        "min-height" => MediaFeature::MinHeight(parse_media_length(value).ok_or_else(invalid_value)?),
+        "max-height" => MediaFeature::MaxHeight(parse_media_length(value).ok_or_else(invalid_value)?),
+        "resolution" => MediaFeature::Resolution(parse_media_resolution(value).ok_or_else(invalid_value)?),
+        "min-resolution" => MediaFeature::MinResolution(parse_media_resolution(value).ok_or_else(invalid_value)?),
+        "max-resolution" => MediaFeature::MaxResolution(parse_media_resolution(value).ok_or_else(invalid_value)?),
+        "orientation" => MediaFeature::Orientation(match value {
+            "portrait" => MediaOrientation::Portrait,
+            "landscape" => MediaOrientation::Landscape,
+            _ => return Err(invalid_value()),
+        }),
+        "prefers-color-scheme" => MediaFeature::PrefersColorScheme(match value {
+            "light" => ColorScheme::Light,
+            "dark" => ColorScheme::Dark,
+            _ => return Err(invalid_value()),
+        }),
+        _ => return Err(UnknownMediaFeature(name)),
+    };
+
+    Ok(feature)
+}
+
+/// Parses the length of a `width` / `height` media feature, percentages aren't allowed
+fn parse_media_length(value: &str) -> Option<PixelValue> {
+    css_parser::parse_pixel_value(value).ok().filter(|pixel_value| !pixel_value.is_percentage())
+}
+
+/// Parses a resolution (`2dppx`, `2x`, `192dpi` or `75.6dpcm`) into `dppx`
+fn parse_media_resolution(value: &str) -> Option<FloatValue> {
+    let unit_start = value.find(|c: char| c.is_alphabetic())?;
+    let number = value[..unit_start].trim().parse::<f32>().ok()?;
+    let dppx = match &value[unit_start..] {
+        "dppx" | "x" => number,
+        "dpi" => number / 96.0,
+        "dpcm" => number * 2.54 / 96.0,
+        _ => return None,
+    };
+    Some(FloatValue::new(dppx))
+}
+
+/// Trims whitespace and `/* comments */` from the start and the end of the string
+fn trim_comments(input: &str) -> &str {
+    let mut input = input.trim();
+    loop {
+        if input.starts_with("/*") {
+            input = match input.find("*/") {
+                Some(end) => input[(end + 2)..].trim(),
+                None => "",
+            };
+        } else if input.ends_with("*/") {
+            input = match input.rfind("/*") {
+                Some(start) => input[..start].trim(),
+                None => return input,
+            };
+        } else {
+            return input;
         }
     }
 }
@@ -265,6 +1180,8 @@ pub enum CssPathParseError<'a> {
     NodeTypePath(NodeTypePathParseError<'a>),
     /// Error while parsing a pseudo selector (like `:aldkfja`)
     PseudoSelectorParseError(CssPseudoSelectorParseError<'a>),
+    /// Invalid attribute selector, such as `[=value]`
+    InvalidAttributeSelector(&'a str),
 }
 
 impl_from! { NodeTypePathParseError<'a>, CssPathParseError::NodeTypePath }
@@ -330,8 +1247,19 @@ pub fn parse_css_path<'a>(input: &'a str) -> Result<CssPath, CssPathParseError<'
             Token::Combinator(Combinator::Space) => {
                 selectors.push(CssPathSelector::Children);
             },
+            Token::Combinator(Combinator::Plus) => {
+                selectors.push(CssPathSelector::AdjacentSibling);
+            },
+            Token::Combinator(Combinator::Tilde) => {
+                selectors.push(CssPathSelector::GeneralSibling);
+            },
+            Token::AttributeSelector(attribute) => {
+                let attribute_selector = parse_attribute_selector(attribute)
+                    .ok_or(CssPathParseError::InvalidAttributeSelector(attribute))?;
+                selectors.push(CssPathSelector::Attribute(attribute_selector));
+            },
             Token::PseudoClass { selector, value } => {
-                selectors.push(CssPathSelector::PseudoSelector(pseudo_selector_from_str(selector, value)?));
+                selectors.extend(pseudo_class_or_element_from_str(selector, value)?);
             },
             Token::EndOfStream => {
                 break;
@@ -350,7 +1278,7 @@ pub fn parse_css_path<'a>(input: &'a str) -> Result<CssPath, CssPathParseError<'
 }
 
 /// Parses a CSS string (single-threaded) and returns the parsed rules in blocks
-fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) -> Result<Stylesheet, CssParseErrorInner<'a>> {
+fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) -> Result<Vec<CssRuleBlock>, CssParseErrorInner<'a>> {
     use simplecss::{Token, Combinator};
 
     let mut css_blocks = Vec::new();
@@ -365,6 +1293,10 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
     let mut current_paths = Vec::new();
     // Current CSS declarations
     let mut current_rules = Vec::new();
+    // Current CSS declarations marked with `!important`
+    let mut current_important_rules = Vec::new();
+    // Custom properties (`--my-var: value`) declared in the current block
+    let mut current_custom_properties = Vec::new();
     // Keep track of the current path during parsing
     let mut last_path = Vec::new();
 
@@ -396,9 +1328,14 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
                     css_blocks.push(CssRuleBlock {
                         path: CssPath { selectors: path },
                         declarations: current_rules.clone(),
+                        important_declarations: current_important_rules.clone(),
+                        custom_properties: current_custom_properties.clone(),
+                        media_queries: Vec::new(),
                     })
                 }
                 current_rules.clear();
+                current_important_rules.clear();
+                current_custom_properties.clear();
                 last_path.clear(); // technically unnecessary, but just to be sure
             },
 
@@ -439,27 +1376,64 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
                 }
                 last_path.push(CssPathSelector::Children);
             },
+            Token::Combinator(Combinator::Plus) => {
+                if parser_in_block {
+                    return Err(CssParseErrorInner::MalformedCss);
+                }
+                last_path.push(CssPathSelector::AdjacentSibling);
+            },
+            Token::Combinator(Combinator::Tilde) => {
+                if parser_in_block {
+                    return Err(CssParseErrorInner::MalformedCss);
+                }
+                last_path.push(CssPathSelector::GeneralSibling);
+            },
+            Token::AttributeSelector(attribute) => {
+                if parser_in_block {
+                    return Err(CssParseErrorInner::MalformedCss);
+                }
+                let attribute_selector = parse_attribute_selector(attribute)
+                    .ok_or(CssParseErrorInner::InvalidAttributeSelector(attribute))?;
+                last_path.push(CssPathSelector::Attribute(attribute_selector));
+            },
             Token::PseudoClass { selector, value } => {
                 if parser_in_block {
                     return Err(CssParseErrorInner::MalformedCss);
                 }
-                last_path.push(CssPathSelector::PseudoSelector(pseudo_selector_from_str(selector, value)?));
+                last_path.extend(pseudo_class_or_element_from_str(selector, value)?);
             },
             Token::Declaration(key, val) => {
                 if !parser_in_block {
                     return Err(CssParseErrorInner::MalformedCss);
                 }
 
+                let (val, is_important) = strip_important(val);
+
+                // custom properties are stored as raw strings, their value is
+                // only known after the cascade (see `var()`)
+                if key.starts_with("--") {
+                    current_custom_properties.push(CustomCssProperty {
+                        name: key.to_string(),
+                        value: val.trim().to_string(),
+                    });
+                    continue;
+                }
+
                 let parsed_key = CssPropertyType::from_str(key, &css_property_map)
                     .ok_or(CssParseErrorInner::UnknownPropertyKey(key, val))?;
 
-                current_rules.push(determine_static_or_dynamic_css_property(parsed_key, val)?);
+                let declaration = determine_static_or_dynamic_css_property(parsed_key, val)?;
+                if is_important {
+                    current_important_rules.push(declaration);
+                } else {
+                    current_rules.push(declaration);
+                }
             },
             Token::EndOfStream => {
                 break;
             },
             _ => {
-                // attributes, lang-attributes and @keyframes are not supported
+                // @-rules are already handled in `new_from_str`
             }
         }
     }
@@ -469,7 +1443,7 @@ fn new_from_str_inner<'a>(css_string: &'a str, tokenizer: &mut Tokenizer<'a>) ->
         return Err(CssParseErrorInner::UnclosedBlock);
     }
 
-    Ok(css_blocks.into())
+    Ok(css_blocks)
 }
 
 /// Error that can happen during `css_parser::parse_key_value_pair`
@@ -524,8 +1498,15 @@ pub fn determine_static_or_dynamic_css_property<'a>(key: CssPropertyType, value:
         (true, true) => {
             parse_dynamic_css_property(key, value).and_then(|val| Ok(CssDeclaration::Dynamic(val)))
         },
+        (false, false) if value.contains("var(") => {
+            // can't be parsed until the custom properties of the node are known
+            Ok(CssDeclaration::Unresolved(UnresolvedCssProperty {
+                property_type: key,
+                value: value.to_string(),
+            }))
+        },
         (false, false) => {
-            Ok(CssDeclaration::Static(css_parser::parse_key_value_pair(key, value)?))
+            Ok(css_parser::parse_css_declaration(key, value)?)
         }
     }
 }
@@ -569,6 +1550,7 @@ pub fn parse_dynamic_css_property<'a>(key: CssPropertyType, value: &'a str) -> R
     }
 
     if dynamic_id.starts_with(char::is_numeric) ||
+       css_parser::parse_css_wide_keyword(dynamic_id).is_some() ||
        css_parser::parse_key_value_pair(key, dynamic_id).is_ok() {
         return Err(DynamicCssParseError::InvalidId);
     }
@@ -587,7 +1569,7 @@ pub fn parse_dynamic_css_property<'a>(key: CssPropertyType, value: &'a str) -> R
 
 #[test]
 fn test_detect_static_or_dynamic_property() {
-    use azul_css::{CssProperty, StyleTextAlignmentHorz};
+    use azul_css::{CssProperty, StyleTextAlignmentHorz, CssKeywordProperty, CssWideKeyword};
     use crate::css_parser::InvalidValueErr;
     assert_eq!(
         determine_static_or_dynamic_css_property(CssPropertyType::TextAlign, " center   "),
@@ -648,6 +1630,19 @@ fn test_detect_static_or_dynamic_property() {
         Err(DynamicCssParseError::NoId)
     );
 
+    assert_eq!(
+        determine_static_or_dynamic_css_property(CssPropertyType::PaddingLeft, " Initial "),
+        Ok(CssDeclaration::Keyword(CssKeywordProperty {
+            property_type: CssPropertyType::PaddingLeft,
+            keyword: CssWideKeyword::Initial,
+        }))
+    );
+
+    assert_eq!(
+        determine_static_or_dynamic_css_property(CssPropertyType::TextAlign, "[[ unset | center ]]"),
+        Err(DynamicCssParseError::InvalidId)
+    );
+
     assert_eq!(
         determine_static_or_dynamic_css_property(CssPropertyType::TextAlign, "[[ hello |  ]]"),
         Err(DynamicCssParseError::NoDefaultCase)
@@ -684,7 +1679,10 @@ fn test_css_parse_1() {
                     CssPathSelector::PseudoSelector(CssPathPseudoSelector::First),
                 ],
             },
-            declarations: vec![CssDeclaration::Static(CssProperty::Background(StyleBackground::Color(ColorU { r: 255, g: 0, b: 0, a: 255 })))],
+            declarations: vec![CssDeclaration::Static(CssProperty::Background(StyleBackground::Color(ColorU { r: 255, g: 0, b: 0, a: 255 }).into()))],
+            important_declarations: Vec::new(),
+            custom_properties: Vec::new(),
+            media_queries: Vec::new(),
         }
     ];
 
@@ -710,7 +1708,13 @@ fn test_css_simple_selector_parse() {
             rules: vec![CssRuleBlock {
                 path: CssPath { selectors: parsed },
                 declarations: Vec::new(),
+                important_declarations: Vec::new(),
+                custom_properties: Vec::new(),
+                media_queries: Vec::new(),
             }],
+            keyframes: Vec::new(),
+            font_faces: Vec::new(),
+            origin: azul_css::CssOrigin::Author,
         }],
     });
 }
@@ -730,9 +1734,9 @@ mod stylesheet_parse {
     #[test]
     fn test_apply_css_pure_class() {
 
-        let red = CssProperty::Background(StyleBackground::Color(ColorU { r: 255, g: 0, b: 0, a: 255 }));
-        let blue = CssProperty::Background(StyleBackground::Color(ColorU { r: 0, g: 0, b: 255, a: 255 }));
-        let black = CssProperty::Background(StyleBackground::Color(ColorU { r: 0, g: 0, b: 0, a: 255 }));
+        let red = CssProperty::Background(StyleBackground::Color(ColorU { r: 255, g: 0, b: 0, a: 255 }).into());
+        let blue = CssProperty::Background(StyleBackground::Color(ColorU { r: 0, g: 0, b: 255, a: 255 }).into());
+        let black = CssProperty::Background(StyleBackground::Color(ColorU { r: 0, g: 0, b: 0, a: 255 }).into());
 
         // Simple example
         {
@@ -743,6 +1747,9 @@ mod stylesheet_parse {
                     declarations: vec![
                         CssDeclaration::Static(red.clone())
                     ],
+                    important_declarations: Vec::new(),
+                    custom_properties: Vec::new(),
+                    media_queries: Vec::new(),
                 },
             ];
             test_css(css_1, expected_rules);
@@ -754,11 +1761,17 @@ mod stylesheet_parse {
             let expected_rules = vec![
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Id("my_id".into())] },
-                    declarations: vec![CssDeclaration::Static(red.clone())]
+                    declarations: vec![CssDeclaration::Static(red.clone())],
+                    important_declarations: Vec::new(),
+                    custom_properties: Vec::new(),
+                    media_queries: Vec::new(),
                 },
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Class("my_class".into())] },
-                    declarations: vec![CssDeclaration::Static(blue.clone())]
+                    declarations: vec![CssDeclaration::Static(blue.clone())],
+                    important_declarations: Vec::new(),
+                    custom_properties: Vec::new(),
+                    media_queries: Vec::new(),
                 },
             ];
             test_css(css_2, expected_rules);
@@ -770,15 +1783,24 @@ mod stylesheet_parse {
             let expected_rules = vec![
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Global] },
-                    declarations: vec![CssDeclaration::Static(black.clone())]
+                    declarations: vec![CssDeclaration::Static(black.clone())],
+                    important_declarations: Vec::new(),
+                    custom_properties: Vec::new(),
+                    media_queries: Vec::new(),
                 },
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Class("my_class".into()), CssPathSelector::Id("my_id".into())] },
-                    declarations: vec![CssDeclaration::Static(red.clone())]
+                    declarations: vec![CssDeclaration::Static(red.clone())],
+                    important_declarations: Vec::new(),
+                    custom_properties: Vec::new(),
+                    media_queries: Vec::new(),
                 },
                 CssRuleBlock {
                     path: CssPath { selectors: vec![CssPathSelector::Class("my_class".into())] },
-                    declarations: vec![CssDeclaration::Static(blue.clone())]
+                    declarations: vec![CssDeclaration::Static(blue.clone())],
+                    important_declarations: Vec::new(),
+                    custom_properties: Vec::new(),
+                    media_queries: Vec::new(),
                 },
             ];
             test_css(css_3, expected_rules);
@@ -802,11 +1824,11 @@ fn test_multiple_rules() {
 
     let expected_rules = vec![
         // Rules are sorted by order of appearance in source string
-        CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Id("my_id".into())] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new() },
-        CssRuleBlock { path: CssPath { selectors: vec![Type(NodeTypePath::Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new() },
+        CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+        CssRuleBlock { path: CssPath { selectors: vec![Global, Type(NodeTypePath::Div), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+        CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+        CssRuleBlock { path: CssPath { selectors: vec![Type(NodeTypePath::Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
     ];
 
     assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()] });
@@ -838,6 +1860,9 @@ fn test_case_issue_93() {
             declarations: vec![
                 CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(color))),
             ],
+            important_declarations: Vec::new(),
+            custom_properties: Vec::new(),
+            media_queries: Vec::new(),
         }
     }
 
@@ -848,4 +1873,335 @@ fn test_case_issue_93() {
     ];
 
     assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()] });
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_css_parse_scrollbar_pseudo_elements() {
+
+    use azul_css::*;
+    use self::CssPathSelector::*;
+
+    let parsed_css = new_from_str("
+        ::-webkit-scrollbar { width: 10px; }
+        .list::-webkit-scrollbar-thumb:horizontal { background-color: red; }
+    ").unwrap();
+
+    let expected_rules = vec![
+        CssRuleBlock {
+            path: CssPath { selectors: vec![PseudoElement(CssPathPseudoElement::Scrollbar)] },
+            declarations: vec![CssDeclaration::Static(CssProperty::Width(LayoutWidth::Exact(PixelValue::px(10.0))))],
+            important_declarations: Vec::new(),
+            custom_properties: Vec::new(),
+            media_queries: Vec::new(),
+        },
+        CssRuleBlock {
+            path: CssPath { selectors: vec![
+                Class("list".into()),
+                PseudoElement(CssPathPseudoElement::ScrollbarThumb),
+                PseudoSelector(CssPathPseudoSelector::Horizontal),
+            ]},
+            declarations: vec![CssDeclaration::Static(CssProperty::Background(StyleBackground::Color(ColorU { r: 255, g: 0, b: 0, a: 255 }).into()))],
+            important_declarations: Vec::new(),
+            custom_properties: Vec::new(),
+            media_queries: Vec::new(),
+        },
+    ];
+
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()] });
+    assert_eq!(
+        parse_css_path("div::-webkit-resizer"),
+        Ok(CssPath { selectors: vec![Type(NodeTypePath::Div), PseudoElement(CssPathPseudoElement::Resizer)] })
+    );
+}
+
+#[test]
+fn test_css_parse_attribute_and_sibling_selectors() {
+
+    use azul_css::*;
+    use self::CssPathSelector::*;
+
+    let attribute = |name: &str, matcher| Attribute(CssAttributeSelector { name: name.into(), matcher });
+
+    assert_eq!(
+        parse_css_path("div[type=\"text\"] + p ~ .a:not(.b[disabled])"),
+        Ok(CssPath { selectors: vec![
+            Type(NodeTypePath::Div),
+            attribute("type", CssAttributeMatcher::Equals("text".into())),
+            AdjacentSibling,
+            Type(NodeTypePath::P),
+            GeneralSibling,
+            Class("a".into()),
+            Not(CssPath { selectors: vec![Class("b".into()), attribute("disabled", CssAttributeMatcher::Exists)] }),
+        ]})
+    );
+
+    let ok_res = [
+        ("disabled", attribute("disabled", CssAttributeMatcher::Exists)),
+        ("lang|=en", attribute("lang", CssAttributeMatcher::DashMatch("en".into()))),
+        ("class~='a'", attribute("class", CssAttributeMatcher::Includes("a".into()))),
+        ("href^=\"http\"", attribute("href", CssAttributeMatcher::Prefix("http".into()))),
+        ("href$=.png", attribute("href", CssAttributeMatcher::Suffix(".png".into()))),
+        ("data-x *= \"y z\"", attribute("data-x", CssAttributeMatcher::Substring("y z".into()))),
+    ];
+
+    for (input, expected) in &ok_res {
+        assert_eq!(parse_attribute_selector(input).map(Attribute).as_ref(), Some(expected));
+    }
+
+    assert_eq!(parse_attribute_selector("=value"), None);
+    assert_eq!(parse_attribute_selector("type=\"text"), None);
+    assert_eq!(
+        parse_css_path("div:not(.a > .b)"),
+        Err(CssPathParseError::PseudoSelectorParseError(CssPseudoSelectorParseError::InvalidNotSelector(".a > .b")))
+    );
+}
+
+#[test]
+fn test_css_parse_custom_properties() {
+
+    use azul_css::*;
+
+    let parsed_css = new_from_str("
+        .root {
+            --main-color: #FF0000;
+            color: var(--main-color, black);
+        }
+    ").unwrap();
+
+    let expected_rules = vec![
+        CssRuleBlock {
+            path: CssPath { selectors: vec![CssPathSelector::Class("root".into())] },
+            declarations: vec![
+                CssDeclaration::Unresolved(UnresolvedCssProperty {
+                    property_type: CssPropertyType::TextColor,
+                    value: "var(--main-color, black)".into(),
+                }),
+            ],
+            important_declarations: Vec::new(),
+            custom_properties: vec![
+                CustomCssProperty { name: "--main-color".into(), value: "#FF0000".into() },
+            ],
+            media_queries: Vec::new(),
+        },
+    ];
+
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()] });
+}
+
+#[test]
+fn test_css_parse_important() {
+
+    use azul_css::*;
+
+    let parsed_css = new_from_str("
+        .root {
+            color: red !important;
+            width: 10px;
+            height: 5px ! IMPORTANT;
+        }
+    ").unwrap();
+
+    let expected_rules = vec![
+        CssRuleBlock {
+            path: CssPath { selectors: vec![CssPathSelector::Class("root".into())] },
+            declarations: vec![
+                CssDeclaration::Static(CssProperty::Width(LayoutWidth::Exact(PixelValue::px(10.0)))),
+            ],
+            important_declarations: vec![
+                CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(ColorU { r: 255, g: 0, b: 0, a: 255 }))),
+                CssDeclaration::Static(CssProperty::Height(LayoutHeight::Exact(PixelValue::px(5.0)))),
+            ],
+            custom_properties: Vec::new(),
+            media_queries: Vec::new(),
+        },
+    ];
+
+    assert_eq!(parsed_css, Css { stylesheets: vec![expected_rules.into()] });
+    assert_eq!(strip_important("red"), ("red", false));
+    assert_eq!(strip_important("unimportant"), ("unimportant", false));
+}
+
+#[test]
+fn test_css_parse_keyframes() {
+
+    use azul_css::*;
+
+    let parsed_css = new_from_str("
+        @keyframes fade-in {
+            /* fully transparent */
+            from { opacity: 0; }
+            50%, 75% { opacity: 0.8; color: red; }
+            to { opacity: 1; }
+        }
+        .fading {
+            animation: fade-in 1s;
+        }
+    ").unwrap();
+
+    let stylesheet = &parsed_css.stylesheets[0];
+    assert_eq!(stylesheet.rules.len(), 1);
+    assert_eq!(stylesheet.rules[0].path, CssPath { selectors: vec![CssPathSelector::Class("fading".into())] });
+
+    let keyframes = parsed_css.get_keyframes("fade-in").unwrap();
+    let offsets = keyframes.keyframes.iter().map(|k| k.offset.get()).collect::<Vec<f32>>();
+    assert_eq!(offsets, vec![0.0, 0.5, 0.75, 1.0]);
+    assert_eq!(keyframes.keyframes[1].declarations, vec![
+        CssProperty::Opacity(StyleOpacity(FloatValue::new(0.8))),
+        CssProperty::TextColor(StyleTextColor(ColorU { r: 255, g: 0, b: 0, a: 255 })),
+    ]);
+
+    assert_eq!(
+        parse_keyframes("a", "from { opacity: 0; } 110% { opacity: 1; }"),
+        Err(CssKeyframesParseError::InvalidKeyframeSelector("110%"))
+    );
+    assert_eq!(parse_keyframes(" ", "from { opacity: 0; }"), Err(CssKeyframesParseError::MissingName));
+    assert_eq!(new_from_str("@keyframes a { from { opacity: 0; }").unwrap_err().error, CssParseErrorInner::UnclosedBlock);
+}
+
+#[test]
+fn test_css_parse_media_queries() {
+
+    use azul_css::*;
+
+    let parsed_css = new_from_str("
+        .sidebar { width: 300px; }
+        @media screen and (max-width: 600px), (orientation: portrait) {
+            .sidebar { width: 100%; }
+            @media (prefers-color-scheme: dark) {
+                .sidebar { color: white; }
+            }
+        }
+        .footer { height: 20px; }
+    ").unwrap();
+
+    let narrow_query = MediaQueryList(vec![
+        MediaQuery { negated: false, media_type: MediaType::Screen, features: vec![MediaFeature::MaxWidth(PixelValue::px(600.0))] },
+        MediaQuery { negated: false, media_type: MediaType::All, features: vec![MediaFeature::Orientation(MediaOrientation::Portrait)] },
+    ]);
+    let dark_query = MediaQueryList(vec![
+        MediaQuery { negated: false, media_type: MediaType::All, features: vec![MediaFeature::PrefersColorScheme(ColorScheme::Dark)] },
+    ]);
+
+    let rules = &parsed_css.stylesheets[0].rules;
+    let media_queries = rules.iter().map(|rule| rule.media_queries.clone()).collect::<Vec<_>>();
+    assert_eq!(media_queries, vec![
+        vec![],
+        vec![narrow_query.clone()],
+        vec![narrow_query.clone(), dark_query.clone()],
+        vec![],
+    ]);
+
+    let wide_light_window = MediaQueryContext { width: 1000.0, height: 600.0, .. MediaQueryContext::default() };
+    let narrow_dark_window = MediaQueryContext { width: 500.0, height: 300.0, color_scheme: ColorScheme::Dark, .. MediaQueryContext::default() };
+    assert_eq!(rules.iter().map(|r| r.matches_media(&wide_light_window)).collect::<Vec<_>>(), vec![true, false, false, true]);
+    assert_eq!(rules.iter().map(|r| r.matches_media(&narrow_dark_window)).collect::<Vec<_>>(), vec![true, true, true, true]);
+
+    assert_eq!(
+        parse_media_query_list("not print and (min-resolution: 192dpi)"),
+        Ok(MediaQueryList(vec![MediaQuery {
+            negated: true,
+            media_type: MediaType::Print,
+            features: vec![MediaFeature::MinResolution(FloatValue::new(2.0))],
+        }]))
+    );
+    assert_eq!(parse_media_query_list("screen (max-width: 600px)"), Err(CssMediaQueryParseError::MalformedQuery("screen (max-width: 600px)")));
+    assert_eq!(parse_media_query_list("screen and"), Err(CssMediaQueryParseError::MalformedQuery("screen and")));
+    assert_eq!(parse_media_query_list("tv"), Err(CssMediaQueryParseError::UnknownMediaType("tv")));
+    assert_eq!(parse_media_query_list("(hover: none)"), Err(CssMediaQueryParseError::UnknownMediaFeature("hover")));
+    assert_eq!(parse_media_query_list("(max-width: 50%)"), Err(CssMediaQueryParseError::InvalidFeatureValue("max-width", "50%")));
+    assert_eq!(parse_media_query_list(""), Err(CssMediaQueryParseError::EmptyQuery));
+}
+
+#[test]
+fn test_css_parse_font_face() {
+
+    use azul_css::*;
+
+    let parsed_css = new_from_str("
+        @font-face {
+            font-family: \"Open Sans\";
+            src: url(\"fonts/OpenSans.woff2\") format(\"woff2\"), local('Open Sans');
+            font-display: swap;
+        }
+    ").unwrap();
+
+    assert_eq!(parsed_css.stylesheets[0].font_faces, vec![CssFontFace {
+        font_family: "Open Sans".into(),
+        sources: vec![
+            FontFaceSource::Url("fonts/OpenSans.woff2".into()),
+            FontFaceSource::Local("Open Sans".into()),
+        ],
+        properties: FontFaceProperties::default(),
+    }]);
+
+    assert_eq!(parse_font_face("src: local(Arial);"), Err(CssFontFaceParseError::MissingFontFamily));
+    assert_eq!(parse_font_face("font-family: Arial;"), Err(CssFontFaceParseError::MissingSource));
+    assert_eq!(parse_font_face("font-family: Arial; src: Arial.ttf;"), Err(CssFontFaceParseError::InvalidSource("Arial.ttf")));
+
+    assert_eq!(parse_font_face("font-family: Arial; src: local('Arial Bold Italic'); font-weight: bold; font-style: italic;").map(|f| f.properties), Ok(FontFaceProperties {
+        weight: StyleFontWeight::BOLD,
+        style: StyleFontStyle::Italic,
+        stretch: StyleFontStretch::Normal,
+    }));
+    assert_eq!(
+        parse_font_face("font-family: Arial; src: local(Arial); font-weight: heavy;"),
+        Err(CssFontFaceParseError::InvalidDescriptor(InvalidValueErr("heavy")))
+    );
+}
+
+#[test]
+fn test_css_load_file_with_imports() {
+
+    use azul_css::*;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Removes the test directory again, even if an assertion fails
+    struct TempDir(PathBuf);
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    static TEMP_DIR_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    // Unique per process and per call, so that concurrent test runs don't overwrite each others files
+    let temp_dir = TempDir(::std::env::temp_dir().join(format!(
+        "azul_css_parser_test_imports_{}_{}",
+        ::std::process::id(),
+        TEMP_DIR_COUNTER.fetch_add(1, Ordering::SeqCst),
+    )));
+    let directory = &temp_dir.0;
+    fs::create_dir_all(directory.join("themes")).unwrap();
+    fs::write(directory.join("main.css"), "
+        @import \"themes/dark.css\" (prefers-color-scheme: dark);
+        .main { color: red; }
+    ").unwrap();
+    fs::write(directory.join("themes").join("dark.css"), "
+        @font-face { font-family: Mono; src: url(Mono.ttf); }
+        .main { color: white; }
+    ").unwrap();
+
+    let css = new_from_file(directory.join("main.css")).unwrap();
+    let stylesheet = &css.stylesheets[0];
+
+    // Imported rules come first, with the media query of the @import
+    assert_eq!(stylesheet.rules.len(), 2);
+    assert_eq!(stylesheet.rules[0].media_queries, vec![MediaQueryList(vec![MediaQuery {
+        negated: false,
+        media_type: MediaType::All,
+        features: vec![MediaFeature::PrefersColorScheme(ColorScheme::Dark)],
+    }])]);
+    assert_eq!(stylesheet.rules[1].media_queries, vec![]);
+
+    // Font URLs are relative to the file that declares the @font-face
+    assert_eq!(stylesheet.font_faces[0].sources, vec![FontFaceSource::Url(directory.join("themes").join("Mono.ttf"))]);
+
+    fs::write(directory.join("themes").join("dark.css"), "@import '../main.css';").unwrap();
+    match new_from_file(directory.join("main.css")) {
+        Err(CssFileLoadError::RecursiveImport(_)) => { },
+        other => panic!("expected recursive import error, got {:?}", other),
+    }
+}
@@ -1,20 +1,35 @@
 //! Contains utilities to convert strings (CSS strings) to servo types
 
 use std::num::{ParseIntError, ParseFloatError};
+use std::time::Duration;
 use azul_css::{
     CssPropertyType,
     StyleTextAlignmentHorz, Overflow,
     LayoutAlignItems, LayoutAlignContent, LayoutJustifyContent, Shape,
-    LayoutWrap, LayoutDirection, LayoutPosition, CssProperty, LayoutOverflow,
+    LayoutWrap, LayoutDirection, LayoutPosition, LayoutFloat, LayoutDisplay, StyleVisibility, CssProperty, LayoutOverflow,
     StyleFontFamily, StyleFontSize, StyleLineHeight, LayoutFlexShrink, LayoutFlexGrow,
     LayoutLeft, LayoutRight, LayoutTop, LayoutBottom, StyleCursor, StyleWordSpacing, StyleTabWidth,
     LayoutMaxHeight, LayoutMinHeight, LayoutHeight, LayoutMaxWidth, LayoutMinWidth, LayoutWidth,
     StyleBorderRadius, PixelValue, PercentageValue, FloatValue,
     ColorU, LayoutMargin, StyleLetterSpacing, StyleTextColor, StyleBackground, StyleBoxShadow,
     GradientStopPre, RadialGradient, StyleBackgroundSize, StyleBackgroundRepeat,
+    StyleBackgrounds, StyleBackgroundSizes, StyleBackgroundRepeats, StyleBackgroundPosition,
+    StyleBackgroundPositions, StyleBackgroundOrigin, StyleBackgroundOrigins,
+    StyleBackgroundClip, StyleBackgroundClips,
     DirectionCorner, StyleBorder, Direction, CssImageId, LinearGradient,
     BoxShadowPreDisplayItem, BorderStyle, LayoutPadding, StyleBorderSide, BorderRadius, PixelSize,
-    BackgroundType,
+    StyleBorderStyle, StyleBorderImage, BorderImageOffsets, BorderImageRepeat,
+    BackgroundType, StyleTransform, StyleTransformOrigin, TransformFunction,
+    TransformOriginOffset, StyleOpacity, AngleValue, AngleMetric, LayoutZIndex,
+    StyleTransition, Transition, StyleAnimation, Animation, AnimationTimingFunction, StepPosition,
+    AnimationIterationCount, AnimationDirection, AnimationFillMode, get_css_key_map,
+    StyleFontWeight, StyleFontStyle, StyleFontStretch,
+    StyleTextDecoration, TextDecorationLine, TextDecorationStyle, TextDecorationThickness,
+    StyleTextShadow, TextShadow,
+    CssDeclaration, CssKeywordProperty, CssWideKeyword,
+    LayoutGridTemplateColumns, LayoutGridTemplateRows, LayoutGridTemplateAreas, LayoutGridGap,
+    LayoutGridRow, LayoutGridColumn, LayoutGridArea, GridTrackSize, GridTrackBreadth, GridLine, GridArea,
+    LayoutFlex, LayoutFlexBasis, LayoutOrder, LayoutAlignSelf, LayoutGap,
 
     SizeMetric, BoxShadowClipMode, ExtendMode, FontId,
 };
@@ -101,34 +116,47 @@ macro_rules! typed_pixel_value_parser {
 /// # use azul_css::{LayoutWidth, PixelValue, CssPropertyType, CssProperty};
 /// assert_eq!(
 ///     azul_css_parser::parse_key_value_pair(CssPropertyType::Width, "500px"),
-///     Ok(CssProperty::Width(LayoutWidth(PixelValue::px(500.0))))
+///     Ok(CssProperty::Width(LayoutWidth::Exact(PixelValue::px(500.0))))
 /// )
 /// ```
 pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<CssProperty, CssParsingError<'a>> {
     use self::CssPropertyType::*;
     let value = value.trim();
     match key {
-        Background       => Ok(parse_style_background(value)?.into()),
+        Background       => Ok(StyleBackgrounds(parse_background_layers(value, parse_style_background)?).into()),
         BackgroundColor  => Ok(StyleBackground::Color(parse_css_color(value)?).into()),
-        BackgroundImage  => Ok(StyleBackground::Image(parse_image(value)?).into()),
-        BackgroundSize   => Ok(parse_style_background_size(value)?.into()),
-        BackgroundRepeat => Ok(parse_style_background_repeat(value)?.into()),
+        BackgroundImage  => Ok(StyleBackgrounds(parse_background_layers(value, parse_background_image)?).into()),
+        BackgroundSize   => Ok(StyleBackgroundSizes(parse_background_layers(value, parse_style_background_size)?).into()),
+        BackgroundRepeat => Ok(StyleBackgroundRepeats(parse_background_layers(value, parse_style_background_repeat)?).into()),
+        BackgroundPosition => Ok(StyleBackgroundPositions(parse_background_layers(value, parse_style_background_position)?).into()),
+        BackgroundOrigin => Ok(StyleBackgroundOrigins(parse_background_layers(value, parse_style_background_origin)?).into()),
+        BackgroundClip   => Ok(StyleBackgroundClips(parse_background_layers(value, parse_style_background_clip)?).into()),
 
         TextColor        => Ok(parse_style_text_color(value)?.into()),
         BorderRadius     => Ok(parse_style_border_radius(value)?.into()),
         FontSize         => Ok(parse_style_font_size(value)?.into()),
         FontFamily       => Ok(parse_style_font_family(value)?.into()),
+        FontWeight       => Ok(parse_style_font_weight(value)?.into()),
+        FontStyle        => Ok(parse_style_font_style(value)?.into()),
+        FontStretch      => Ok(parse_style_font_stretch(value)?.into()),
         LetterSpacing    => Ok(parse_style_letter_spacing(value)?.into()),
         WordSpacing      => Ok(parse_style_word_spacing(value)?.into()),
         TabWidth         => Ok(parse_style_tab_width(value)?.into()),
         LineHeight       => Ok(parse_style_line_height(value)?.into()),
         Cursor           => Ok(parse_style_cursor(value)?.into()),
+        Visibility       => Ok(parse_style_visibility(value)?.into()),
 
         Border           => Ok(StyleBorder::all(parse_css_border(value)?).into()),
         BorderTop        => Ok(border_parser::parse_top(value)?.into()),
         BorderBottom     => Ok(border_parser::parse_bottom(value)?.into()),
         BorderLeft       => Ok(border_parser::parse_left(value)?.into()),
         BorderRight      => Ok(border_parser::parse_right(value)?.into()),
+        BorderStyle      => Ok(parse_style_border_style(value)?.into()),
+        BorderTopStyle   => Ok(StyleBorderStyle { top: Some(parse_border_style(value)?), .. Default::default() }.into()),
+        BorderBottomStyle => Ok(StyleBorderStyle { bottom: Some(parse_border_style(value)?), .. Default::default() }.into()),
+        BorderLeftStyle  => Ok(StyleBorderStyle { left: Some(parse_border_style(value)?), .. Default::default() }.into()),
+        BorderRightStyle => Ok(StyleBorderStyle { right: Some(parse_border_style(value)?), .. Default::default() }.into()),
+        BorderImage      => Ok(parse_style_border_image(value)?.into()),
 
         Width            => Ok(parse_layout_width(value)?.into()),
         Height           => Ok(parse_layout_height(value)?.into()),
@@ -138,11 +166,29 @@ pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<
         MaxHeight        => Ok(parse_layout_max_height(value)?.into()),
 
         Position         => Ok(parse_layout_position(value)?.into()),
+        Float            => Ok(parse_layout_float(value)?.into()),
+        Display          => Ok(parse_layout_display(value)?.into()),
+
+        GridTemplateColumns => Ok(parse_layout_grid_template_columns(value)?.into()),
+        GridTemplateRows => Ok(parse_layout_grid_template_rows(value)?.into()),
+        GridTemplateAreas => Ok(parse_layout_grid_template_areas(value)?.into()),
+        GridGap          => Ok(parse_layout_grid_gap(value)?.into()),
+        GridRow          => Ok(parse_layout_grid_row(value)?.into()),
+        GridColumn       => Ok(parse_layout_grid_column(value)?.into()),
+        GridArea         => Ok(parse_layout_grid_area(value)?.into()),
+
+        ZIndex           => Ok(parse_layout_z_index(value)?.into()),
         Top              => Ok(parse_layout_top(value)?.into()),
         Right            => Ok(parse_layout_right(value)?.into()),
         Left             => Ok(parse_layout_left(value)?.into()),
         Bottom           => Ok(parse_layout_bottom(value)?.into()),
         TextAlign        => Ok(parse_layout_text_align(value)?.into()),
+        TextDecoration   => Ok(parse_style_text_decoration(value)?.into()),
+        TextDecorationLine => Ok(StyleTextDecoration { line: Some(parse_text_decoration_line(value)?), .. Default::default() }.into()),
+        TextDecorationColor => Ok(StyleTextDecoration { color: Some(parse_css_color(value)?), .. Default::default() }.into()),
+        TextDecorationStyle => Ok(StyleTextDecoration { style: Some(parse_text_decoration_style(value)?), .. Default::default() }.into()),
+        TextDecorationThickness => Ok(StyleTextDecoration { thickness: Some(parse_text_decoration_thickness(value)?), .. Default::default() }.into()),
+        TextShadow       => Ok(parse_style_text_shadow(value)?.into()),
 
         BoxShadow        => Ok(StyleBoxShadow::all(parse_css_box_shadow(value)?).into()),
         BoxShadowTop     => Ok(box_shadow_parser::parse_top(value)?.into()),
@@ -164,13 +210,27 @@ pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<
 
         FlexWrap         => Ok(parse_layout_wrap(value)?.into()),
         FlexDirection    => Ok(parse_layout_direction(value)?.into()),
+        Flex             => Ok(parse_layout_flex(value)?.into()),
         FlexGrow         => Ok(parse_layout_flex_grow(value)?.into()),
         FlexShrink       => Ok(parse_layout_flex_shrink(value)?.into()),
+        FlexBasis        => Ok(parse_layout_flex_basis(value)?.into()),
+        Order            => Ok(parse_layout_order(value)?.into()),
 
         JustifyContent   => Ok(parse_layout_justify_content(value)?.into()),
         AlignItems       => Ok(parse_layout_align_items(value)?.into()),
+        AlignSelf        => Ok(parse_layout_align_self(value)?.into()),
         AlignContent     => Ok(parse_layout_align_content(value)?.into()),
 
+        Gap              => Ok(parse_layout_gap(value)?.into()),
+        RowGap           => Ok(LayoutGap { row: Some(parse_pixel_value(value)?), column: None }.into()),
+        ColumnGap        => Ok(LayoutGap { row: None, column: Some(parse_pixel_value(value)?) }.into()),
+
+        Transform        => Ok(parse_style_transform(value)?.into()),
+        TransformOrigin  => Ok(parse_style_transform_origin(value)?.into()),
+        Opacity          => Ok(parse_style_opacity(value)?.into()),
+        Transition       => Ok(parse_style_transition(value)?.into()),
+        Animation        => Ok(parse_style_animation(value)?.into()),
+
         Overflow         => {
             let overflow_both_directions = parse_layout_text_overflow(value)?;
             Ok(LayoutOverflow {
@@ -195,6 +255,42 @@ pub fn parse_key_value_pair<'a>(key: CssPropertyType, value: &'a str) -> Result<
     }
 }
 
+/// Parses a CSS-wide keyword (`inherit`, `initial` or `unset`), case-insensitive
+pub fn parse_css_wide_keyword(input: &str) -> Option<CssWideKeyword> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("inherit") {
+        Some(CssWideKeyword::Inherit)
+    } else if input.eq_ignore_ascii_case("initial") {
+        Some(CssWideKeyword::Initial)
+    } else if input.eq_ignore_ascii_case("unset") {
+        Some(CssWideKeyword::Unset)
+    } else {
+        None
+    }
+}
+
+/// Same as `parse_key_value_pair`, but also accepts the CSS-wide keywords
+/// (`inherit`, `initial`, `unset`) for every key, which are resolved during the cascade
+///
+/// ```rust
+/// # extern crate azul_css_parser;
+/// # extern crate azul_css;
+/// # use azul_css::{CssPropertyType, CssDeclaration, CssKeywordProperty, CssWideKeyword};
+/// assert_eq!(
+///     azul_css_parser::parse_css_declaration(CssPropertyType::Width, "inherit"),
+///     Ok(CssDeclaration::Keyword(CssKeywordProperty {
+///         property_type: CssPropertyType::Width,
+///         keyword: CssWideKeyword::Inherit,
+///     }))
+/// )
+/// ```
+pub fn parse_css_declaration<'a>(key: CssPropertyType, value: &'a str) -> Result<CssDeclaration, CssParsingError<'a>> {
+    match parse_css_wide_keyword(value) {
+        Some(keyword) => Ok(CssDeclaration::Keyword(CssKeywordProperty { property_type: key, keyword })),
+        None => Ok(CssDeclaration::Static(parse_key_value_pair(key, value)?)),
+    }
+}
+
 /// Error containing all sub-errors that could happen during CSS parsing
 ///
 /// Usually we want to crash on the first error, to notify the user of the problem.
@@ -214,6 +310,18 @@ pub enum CssParsingError<'a> {
     MarginParseError(LayoutMarginParseError<'a>),
     FlexShrinkParseError(FlexShrinkParseError<'a>),
     FlexGrowParseError(FlexGrowParseError<'a>),
+    FlexParseError(FlexParseError<'a>),
+    OrderParseError(OrderParseError<'a>),
+    GapParseError(GapParseError<'a>),
+    ZIndexParseError(ZIndexParseError<'a>),
+    TransformParseError(CssStyleTransformParseError<'a>),
+    TransformOriginParseError(CssStyleTransformOriginParseError<'a>),
+    OpacityParseError(OpacityParseError<'a>),
+    AnimationParseError(CssAnimationParseError<'a>),
+    BackgroundPositionParseError(CssBackgroundPositionParseError<'a>),
+    BorderImageParseError(CssBorderImageParseError<'a>),
+    TextDecorationParseError(CssTextDecorationParseError<'a>),
+    GridParseError(CssGridParseError<'a>),
 }
 
 impl_debug_as_display!(CssParsingError<'a>);
@@ -232,6 +340,18 @@ impl_display!{ CssParsingError<'a>, {
     MarginParseError(e) => format!("{}", e),
     FlexShrinkParseError(e) => format!("{}", e),
     FlexGrowParseError(e) => format!("{}", e),
+    FlexParseError(e) => format!("Invalid flex: {}", e),
+    OrderParseError(e) => format!("{}", e),
+    GapParseError(e) => format!("Invalid gap: {}", e),
+    ZIndexParseError(e) => format!("{}", e),
+    TransformParseError(e) => format!("{}", e),
+    TransformOriginParseError(e) => format!("{}", e),
+    OpacityParseError(e) => format!("{}", e),
+    AnimationParseError(e) => format!("{}", e),
+    BackgroundPositionParseError(e) => format!("{}", e),
+    BorderImageParseError(e) => format!("Invalid border-image: {}", e),
+    TextDecorationParseError(e) => format!("Invalid text-decoration: {}", e),
+    GridParseError(e) => format!("Invalid grid property: {}", e),
 }}
 
 impl_from!(CssBorderParseError<'a>, CssParsingError::CssBorderParseError);
@@ -242,11 +362,23 @@ impl_from!(PixelParseError<'a>, CssParsingError::PixelParseError);
 impl_from!(CssImageParseError<'a>, CssParsingError::CssImageParseError);
 impl_from!(CssStyleFontFamilyParseError<'a>, CssParsingError::CssStyleFontFamilyParseError);
 impl_from!(CssBackgroundParseError<'a>, CssParsingError::CssBackgroundParseError);
+impl_from!(CssBackgroundPositionParseError<'a>, CssParsingError::BackgroundPositionParseError);
+impl_from!(CssBorderImageParseError<'a>, CssParsingError::BorderImageParseError);
+impl_from!(CssTextDecorationParseError<'a>, CssParsingError::TextDecorationParseError);
+impl_from!(CssGridParseError<'a>, CssParsingError::GridParseError);
 impl_from!(CssStyleBorderRadiusParseError<'a>, CssParsingError::CssStyleBorderRadiusParseError);
 impl_from!(LayoutPaddingParseError<'a>, CssParsingError::PaddingParseError);
 impl_from!(LayoutMarginParseError<'a>, CssParsingError::MarginParseError);
 impl_from!(FlexShrinkParseError<'a>, CssParsingError::FlexShrinkParseError);
 impl_from!(FlexGrowParseError<'a>, CssParsingError::FlexGrowParseError);
+impl_from!(FlexParseError<'a>, CssParsingError::FlexParseError);
+impl_from!(OrderParseError<'a>, CssParsingError::OrderParseError);
+impl_from!(GapParseError<'a>, CssParsingError::GapParseError);
+impl_from!(ZIndexParseError<'a>, CssParsingError::ZIndexParseError);
+impl_from!(CssStyleTransformParseError<'a>, CssParsingError::TransformParseError);
+impl_from!(CssStyleTransformOriginParseError<'a>, CssParsingError::TransformOriginParseError);
+impl_from!(OpacityParseError<'a>, CssParsingError::OpacityParseError);
+impl_from!(CssAnimationParseError<'a>, CssParsingError::AnimationParseError);
 
 impl<'a> From<PercentageParseError> for CssParsingError<'a> {
     fn from(e: PercentageParseError) -> Self {
@@ -390,7 +522,7 @@ impl_from!(CssColorParseError<'a>, CssShadowParseError::ColorParseError);
 pub fn parse_style_border_radius<'a>(input: &'a str)
 -> Result<StyleBorderRadius, CssStyleBorderRadiusParseError<'a>>
 {
-    let mut components = input.split_whitespace();
+    let mut components = split_whitespace_outside_braces(input).into_iter();
     let len = components.clone().count();
 
     match len {
@@ -462,6 +594,7 @@ pub enum PixelParseError<'a> {
     NoValueGiven(&'a str),
     UnsupportedMetric(f32, String, &'a str),
     ValueParseErr(ParseFloatError, String),
+    CalcParseError(CalcParseError<'a>),
 }
 
 impl_debug_as_display!(PixelParseError<'a>);
@@ -471,8 +604,11 @@ impl_display!{ PixelParseError<'a>, {
     NoValueGiven(input) => format!("Expected floating-point pixel value, got: \"{}\"", input),
     UnsupportedMetric(_, metric, input) => format!("Could not parse \"{}\": Metric \"{}\" is not (yet) implemented.", input, metric),
     ValueParseErr(err, number_str) => format!("Could not parse \"{}\" as floating-point value: \"{}\"", number_str, err),
+    CalcParseError(e) => format!("Invalid calc() expression: {}", e),
 }}
 
+impl_from!(CalcParseError<'a>, PixelParseError::CalcParseError);
+
 /// parse a single value such as "15px"
 pub fn parse_pixel_value<'a>(input: &'a str)
 -> Result<PixelValue, PixelParseError<'a>>
@@ -483,6 +619,10 @@ pub fn parse_pixel_value<'a>(input: &'a str)
         return Err(PixelParseError::EmptyString);
     }
 
+    if input.starts_with("calc(") {
+        return Ok(parse_calc_expression(input)?);
+    }
+
     let is_part_of_number = |ch: &char| ch.is_numeric() || *ch == '.' || *ch == '-';
 
     // You can't sub-string pixel values, have to call collect() here!
@@ -499,12 +639,294 @@ pub fn parse_pixel_value<'a>(input: &'a str)
         "px" => SizeMetric::Px,
         "em" => SizeMetric::Em,
         "pt" => SizeMetric::Pt,
+        "%" => SizeMetric::Percent,
+        "rem" => SizeMetric::Rem,
+        "vw" => SizeMetric::Vw,
+        "vh" => SizeMetric::Vh,
+        "vmin" => SizeMetric::Vmin,
+        "vmax" => SizeMetric::Vmax,
         _ => return Err(PixelParseError::UnsupportedMetric(number, unit_str, input)),
     };
 
     Ok(PixelValue::from_metric(unit, number))
 }
 
+#[derive(Clone, PartialEq)]
+pub enum CalcParseError<'a> {
+    /// The expression doesn't start with `calc(`
+    NoCalcFunction(&'a str),
+    UnclosedBraces(&'a str),
+    UnexpectedEnd(&'a str),
+    UnexpectedToken(&'a str),
+    /// Token is neither a number nor a length
+    InvalidOperand(&'a str),
+    /// Operation is not defined for the operands, such as multiplying two lengths
+    InvalidOperation(&'a str, &'a str),
+    DivisionByZero(&'a str),
+    /// The expression results in a plain number instead of a length
+    NotALength(&'a str),
+}
+
+impl_debug_as_display!(CalcParseError<'a>);
+
+impl_display!{ CalcParseError<'a>, {
+    NoCalcFunction(input) => format!("Expected \"calc(\", got: \"{}\"", input),
+    UnclosedBraces(input) => format!("Unclosed braces in \"{}\"", input),
+    UnexpectedEnd(input) => format!("Unexpected end of expression: \"{}\"", input),
+    UnexpectedToken(token) => format!("Unexpected token: \"{}\"", token),
+    InvalidOperand(token) => format!("Expected a number or length, got: \"{}\"", token),
+    InvalidOperation(operator, input) => format!("Operator \"{}\" can't be applied to the operands in \"{}\"", operator, input),
+    DivisionByZero(input) => format!("Division by zero in \"{}\"", input),
+    NotALength(input) => format!("Expression \"{}\" does not result in a length", input),
+}}
+
+/// Parses a `calc()` expression such as `calc(100% - 240px)` or `calc((1em + 2px) * 2)`.
+///
+/// Lengths can be added to and subtracted from each other and multiplied or divided
+/// by plain numbers. As in CSS, `+` and `-` have to be surrounded by whitespace.
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_calc_expression;
+/// # use azul_css::{PixelValue, SizeMetric};
+/// assert_eq!(
+///     parse_calc_expression("calc(100% - 240px)"),
+///     Ok(PixelValue::calc(&[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -240.0)]))
+/// );
+/// ```
+pub fn parse_calc_expression<'a>(input: &'a str)
+-> Result<PixelValue, CalcParseError<'a>>
+{
+    let input = input.trim();
+    let mut parser = CalcParser { input, tokens: tokenize_calc_expression(input), position: 0 };
+
+    if parser.next() != Some("calc") || parser.next() != Some("(") {
+        return Err(CalcParseError::NoCalcFunction(input));
+    }
+
+    let result = parser.parse_braces()?;
+
+    if let Some(token) = parser.next() {
+        return Err(CalcParseError::UnexpectedToken(token));
+    }
+
+    match result {
+        CalcOperand::Length(terms) => Ok(PixelValue::calc(&terms)),
+        CalcOperand::Number(_) => Err(CalcParseError::NotALength(input)),
+    }
+}
+
+/// Splits a `calc()` expression into numbers, lengths, operators and braces
+fn tokenize_calc_expression<'a>(input: &'a str) -> Vec<&'a str> {
+
+    let mut tokens = Vec::new();
+    let mut token_start = None;
+
+    for (idx, ch) in input.char_indices() {
+        match ch {
+            '(' | ')' | '*' | '/' => {
+                if let Some(start) = token_start.take() {
+                    tokens.push(&input[start..idx]);
+                }
+                tokens.push(&input[idx..(idx + ch.len_utf8())]);
+            },
+            ch if ch.is_whitespace() => {
+                if let Some(start) = token_start.take() {
+                    tokens.push(&input[start..idx]);
+                }
+            },
+            _ => {
+                if token_start.is_none() {
+                    token_start = Some(idx);
+                }
+            },
+        }
+    }
+
+    if let Some(start) = token_start {
+        tokens.push(&input[start..]);
+    }
+
+    tokens
+}
+
+/// Intermediate result of a `calc()` expression: Either a plain number or a sum of lengths
+#[derive(Debug, Clone, PartialEq)]
+enum CalcOperand {
+    Number(f32),
+    Length(Vec<(SizeMetric, f32)>),
+}
+
+/// Recursive descent parser for `calc()` expressions
+struct CalcParser<'a> {
+    input: &'a str,
+    tokens: Vec<&'a str>,
+    position: usize,
+}
+
+impl<'a> CalcParser<'a> {
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.peek();
+        if token.is_some() {
+            self.position += 1;
+        }
+        token
+    }
+
+    /// sum = product (("+" | "-") product)*
+    fn parse_sum(&mut self) -> Result<CalcOperand, CalcParseError<'a>> {
+        use self::CalcOperand::*;
+
+        let mut result = self.parse_product()?;
+
+        while let Some(operator) = self.peek() {
+            let sign = match operator {
+                "+" => 1.0,
+                "-" => -1.0,
+                _ => break,
+            };
+            self.position += 1;
+
+            result = match (result, self.parse_product()?) {
+                (Number(a), Number(b)) => Number(a + sign * b),
+                (Length(mut a), Length(b)) => {
+                    a.extend(b.into_iter().map(|(metric, number)| (metric, sign * number)));
+                    Length(a)
+                },
+                _ => return Err(CalcParseError::InvalidOperation(operator, self.input)),
+            };
+        }
+
+        Ok(result)
+    }
+
+    /// product = factor (("*" | "/") factor)*
+    fn parse_product(&mut self) -> Result<CalcOperand, CalcParseError<'a>> {
+        use self::CalcOperand::*;
+
+        let mut result = self.parse_factor()?;
+
+        while let Some(operator) = self.peek() {
+            if operator != "*" && operator != "/" {
+                break;
+            }
+            self.position += 1;
+
+            result = match (operator, result, self.parse_factor()?) {
+                ("*", Number(a), Number(b)) => Number(a * b),
+                ("*", Length(terms), Number(factor)) |
+                ("*", Number(factor), Length(terms)) => Length(scale_calc_terms(terms, factor)),
+                ("/", _, Number(divisor)) if divisor == 0.0 => return Err(CalcParseError::DivisionByZero(self.input)),
+                ("/", Number(a), Number(b)) => Number(a / b),
+                ("/", Length(terms), Number(divisor)) => Length(scale_calc_terms(terms, 1.0 / divisor)),
+                _ => return Err(CalcParseError::InvalidOperation(operator, self.input)),
+            };
+        }
+
+        Ok(result)
+    }
+
+    /// factor = "(" sum ")" | "calc(" sum ")" | number | length
+    fn parse_factor(&mut self) -> Result<CalcOperand, CalcParseError<'a>> {
+        let token = self.next().ok_or(CalcParseError::UnexpectedEnd(self.input))?;
+        match token {
+            "(" => self.parse_braces(),
+            "calc" => match self.next() {
+                Some("(") => self.parse_braces(),
+                Some(other) => Err(CalcParseError::UnexpectedToken(other)),
+                None => Err(CalcParseError::UnexpectedEnd(self.input)),
+            },
+            ")" | "*" | "/" | "+" | "-" => Err(CalcParseError::UnexpectedToken(token)),
+            _ => {
+                if let Ok(number) = token.parse::<f32>() {
+                    Ok(CalcOperand::Number(number))
+                } else {
+                    let length = parse_pixel_value(token).map_err(|_| CalcParseError::InvalidOperand(token))?;
+                    Ok(CalcOperand::Length(vec![(length.metric, length.number.get())]))
+                }
+            },
+        }
+    }
+
+    /// Parses the rest of an expression in braces, after the opening brace
+    fn parse_braces(&mut self) -> Result<CalcOperand, CalcParseError<'a>> {
+        let result = self.parse_sum()?;
+        match self.next() {
+            Some(")") => Ok(result),
+            Some(other) => Err(CalcParseError::UnexpectedToken(other)),
+            None => Err(CalcParseError::UnclosedBraces(self.input)),
+        }
+    }
+}
+
+fn scale_calc_terms(terms: Vec<(SizeMetric, f32)>, factor: f32) -> Vec<(SizeMetric, f32)> {
+    terms.into_iter().map(|(metric, number)| (metric, number * factor)).collect()
+}
+
+/// Same as `str::split_whitespace()`, but doesn't split inside of braces,
+/// i.e. `"calc(1em + 2px) 10px"` is split into `"calc(1em + 2px)"` and `"10px"`
+fn split_whitespace_outside_braces<'a>(input: &'a str) -> Vec<&'a str> {
+
+    let mut components = Vec::new();
+    let mut component_start = None;
+    let mut brace_depth = 0_usize;
+
+    for (idx, ch) in input.char_indices() {
+        if ch.is_whitespace() && brace_depth == 0 {
+            if let Some(start) = component_start.take() {
+                components.push(&input[start..idx]);
+            }
+            continue;
+        }
+
+        match ch {
+            '(' => brace_depth += 1,
+            ')' => brace_depth = brace_depth.saturating_sub(1),
+            _ => { },
+        }
+
+        if component_start.is_none() {
+            component_start = Some(idx);
+        }
+    }
+
+    if let Some(start) = component_start {
+        components.push(&input[start..]);
+    }
+
+    components
+}
+
+/// Splits a comma-separated list, but doesn't split inside of braces,
+/// i.e. `"width 1s cubic-bezier(0, 0, 1, 1), opacity 2s"` is split into two items
+pub(crate) fn split_commas_outside_braces<'a>(input: &'a str) -> Vec<&'a str> {
+
+    let mut components = Vec::new();
+    let mut component_start = 0;
+    let mut brace_depth = 0_usize;
+
+    for (idx, ch) in input.char_indices() {
+        match ch {
+            '(' => brace_depth += 1,
+            ')' => brace_depth = brace_depth.saturating_sub(1),
+            ',' if brace_depth == 0 => {
+                components.push(input[component_start..idx].trim());
+                component_start = idx + 1;
+            },
+            _ => { },
+        }
+    }
+
+    components.push(input[component_start..].trim());
+    components
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum PercentageParseError {
     ValueParseErr(ParseFloatError),
@@ -1012,7 +1434,7 @@ impl_from!(PixelParseError<'a>, LayoutPaddingParseError::PixelParseError);
 pub fn parse_layout_padding<'a>(input: &'a str)
 -> Result<LayoutPadding, LayoutPaddingParseError>
 {
-    let mut input_iter = input.split_whitespace();
+    let mut input_iter = split_whitespace_outside_braces(input).into_iter();
     let first = parse_pixel_value(input_iter.next().ok_or(LayoutPaddingParseError::TooFewValues)?)?;
     let second = parse_pixel_value(match input_iter.next() {
         Some(s) => s,
@@ -1182,13 +1604,198 @@ multi_type_parser!(parse_border_style, BorderStyle,
     ["inset", Inset],
     ["outset", Outset]);
 
+/// Parses a `border-style` attribute with one to four styles (top, right, bottom, left),
+/// such as `dashed solid`
+pub fn parse_style_border_style<'a>(input: &'a str)
+-> Result<StyleBorderStyle, CssBorderParseError<'a>>
+{
+    let styles = input.split_whitespace()
+        .map(|style| parse_border_style(style).map_err(|e| CssBorderParseError::InvalidBorderStyle(e)))
+        .collect::<Result<Vec<BorderStyle>, _>>()?;
+
+    let [top, right, bottom, left] = expand_side_values(&styles)
+        .ok_or(CssBorderParseError::InvalidBorderDeclaration(input))?;
+
+    Ok(StyleBorderStyle {
+        top: Some(top),
+        right: Some(right),
+        bottom: Some(bottom),
+        left: Some(left),
+    })
+}
+
+/// Expands one to four values into `[top, right, bottom, left]`, like in `padding: 5px 10px`
+fn expand_side_values<T: Copy>(values: &[T]) -> Option<[T;4]> {
+    match values {
+        [all] => Some([*all, *all, *all, *all]),
+        [vertical, horizontal] => Some([*vertical, *horizontal, *vertical, *horizontal]),
+        [top, horizontal, bottom] => Some([*top, *horizontal, *bottom, *horizontal]),
+        [top, right, bottom, left] => Some([*top, *right, *bottom, *left]),
+        _ => None,
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CssBorderImageParseError<'a> {
+    EmptyString,
+    /// The image source has to be `none`, `image("...")` or a quoted string
+    InvalidSource(&'a str),
+    ImageParseError(CssImageParseError<'a>),
+    /// More than four slice / width / outset values, more than two repeat keywords
+    /// or more than two `/` separators
+    TooManyValues(&'a str),
+    /// `border-image-slice` is missing, but a width or outset is given
+    MissingSlice(&'a str),
+    PixelParseError(PixelParseError<'a>),
+    InvalidRepeat(InvalidValueErr<'a>),
+}
+
+impl_debug_as_display!(CssBorderImageParseError<'a>);
+impl_display!{ CssBorderImageParseError<'a>, {
+    EmptyString => format!("Missing border-image value"),
+    InvalidSource(e) => format!("Invalid image source: \"{}\" - expected none, image(\"...\") or a quoted string", e),
+    ImageParseError(e) => format!("{}", e),
+    TooManyValues(e) => format!("Too many values: \"{}\"", e),
+    MissingSlice(e) => format!("Missing slice before \"/\": \"{}\"", e),
+    PixelParseError(e) => format!("{}", e),
+    InvalidRepeat(e) => format!("Invalid repeat value: \"{}\"", e.0),
+}}
+
+impl_from!(CssImageParseError<'a>, CssBorderImageParseError::ImageParseError);
+impl_from!(PixelParseError<'a>, CssBorderImageParseError::PixelParseError);
+
+/// Parses a `border-image` attribute (nine-patch border), such as
+/// `image("frame") 30 fill / 10px / 5px round stretch`. The image source has to come first,
+/// followed by the slices (numbers are pixels of the image), the optional width and outset
+/// and at most two repeat keywords (horizontal, vertical).
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_border_image;
+/// # use azul_css::{StyleBorderImage, BorderImageOffsets, BorderImageRepeat, CssImageId, PixelValue};
+/// assert_eq!(
+///     parse_style_border_image("image(\"frame\") 30 fill round"),
+///     Ok(StyleBorderImage {
+///         source: Some(CssImageId(String::from("frame"))),
+///         slice: BorderImageOffsets::all(PixelValue::px(30.0)),
+///         fill: true,
+///         repeat_horizontal: BorderImageRepeat::Round,
+///         repeat_vertical: BorderImageRepeat::Round,
+///         .. Default::default()
+///     })
+/// );
+/// ```
+pub fn parse_style_border_image<'a>(input: &'a str)
+-> Result<StyleBorderImage, CssBorderImageParseError<'a>>
+{
+    use self::CssBorderImageParseError::*;
+
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Err(EmptyString);
+    }
+
+    // Split the image source off, it may contain whitespace or slashes
+    let (source, rest) = if input.starts_with("none") {
+        (None, &input["none".len()..])
+    } else if input.starts_with("image(") {
+        let end = input.find(')').ok_or(InvalidSource(input))?;
+        (Some(parse_image(&input["image(".len()..end])?), &input[(end + 1)..])
+    } else if input.starts_with('"') || input.starts_with('\'') {
+        let quote = &input[..1];
+        let end = input[1..].find(quote).ok_or(InvalidSource(input))? + 2;
+        (Some(parse_image(&input[..end])?), &input[end..])
+    } else {
+        return Err(InvalidSource(input));
+    };
+
+    let mut border_image = StyleBorderImage { source, .. Default::default() };
+
+    // "30 / 10px" and "30/10px" are both valid, so split the slashes off before tokenizing
+    let mut sections = rest.split('/').map(|section| section.split_whitespace().collect()).collect::<Vec<Vec<&str>>>();
+    if sections.len() > 3 {
+        return Err(TooManyValues(input));
+    }
+
+    // The repeat keywords come at the end of the last section
+    let repeat_keywords = {
+        let last_section = sections.last_mut().unwrap();
+        let first_repeat = last_section.iter()
+            .position(|token| parse_border_image_repeat(token).is_ok())
+            .unwrap_or(last_section.len());
+        last_section.split_off(first_repeat)
+    };
+
+    let parse_offsets = |values: &[PixelValue]| -> Result<BorderImageOffsets, CssBorderImageParseError<'a>> {
+        let [top, right, bottom, left] = expand_side_values(values).ok_or(TooManyValues(input))?;
+        Ok(BorderImageOffsets { top, right, bottom, left })
+    };
+
+    // Slices are unitless numbers (pixels of the image) or percentages
+    let mut slices = Vec::new();
+    for token in &sections[0] {
+        if *token == "fill" {
+            border_image.fill = true;
+        } else if let Ok(number) = token.parse::<f32>() {
+            slices.push(PixelValue::px(number));
+        } else {
+            slices.push(parse_pixel_value(token)?);
+        }
+    }
+
+    if !slices.is_empty() {
+        border_image.slice = parse_offsets(&slices)?;
+    } else if sections.len() > 1 {
+        return Err(MissingSlice(input));
+    }
+
+    if let Some(width_tokens) = sections.get(1) {
+        let widths = width_tokens.iter().map(|w| parse_pixel_value(w)).collect::<Result<Vec<_>, _>>()?;
+        // "30 // 5px" sets the outset, but not the width
+        if !widths.is_empty() {
+            border_image.width = Some(parse_offsets(&widths)?);
+        }
+    }
+
+    if let Some(outset_tokens) = sections.get(2) {
+        let outsets = outset_tokens.iter().map(|o| parse_pixel_value(o)).collect::<Result<Vec<_>, _>>()?;
+        border_image.outset = parse_offsets(&outsets)?;
+    }
+
+    match repeat_keywords.as_slice() {
+        [] => { },
+        [both] => {
+            let repeat = parse_border_image_repeat(both).map_err(|e| InvalidRepeat(e))?;
+            border_image.repeat_horizontal = repeat;
+            border_image.repeat_vertical = repeat;
+        },
+        [horizontal, vertical] => {
+            border_image.repeat_horizontal = parse_border_image_repeat(horizontal).map_err(|e| InvalidRepeat(e))?;
+            border_image.repeat_vertical = parse_border_image_repeat(vertical).map_err(|e| InvalidRepeat(e))?;
+        },
+        _ => return Err(TooManyValues(input)),
+    }
+
+    Ok(border_image)
+}
+
+multi_type_parser!(parse_border_image_repeat, BorderImageRepeat,
+    ["stretch", Stretch],
+    ["repeat", Repeat],
+    ["round", Round],
+    ["space", Space]);
+
 parse_tblr!(box_shadow_parser, StyleBoxShadow, CssShadowParseError, parse_css_box_shadow);
 
 /// Parses a CSS box-shadow
 pub fn parse_css_box_shadow<'a>(input: &'a str)
 -> Result<Option<BoxShadowPreDisplayItem>, CssShadowParseError<'a>>
 {
-    let mut input_iter = input.split_whitespace();
+    let mut input_iter = split_whitespace_outside_braces(input).into_iter();
     let count = input_iter.clone().count();
 
     let mut box_shadow = BoxShadowPreDisplayItem {
@@ -1358,59 +1965,249 @@ pub fn parse_style_background<'a>(input: &'a str)
     }
 }
 
-/// Given a string, returns how many characters need to be skipped
-fn skip_next_braces(input: &str, target_char: char) -> Option<(usize, bool)> {
-
-    let mut depth = 0;
-    let mut last_character = 0;
-    let mut character_was_found = false;
-
-    if input.is_empty() {
-        return None;
-    }
+/// Parses a comma-separated list of background layers, i.e. `"image(\"a\"), red"`
+/// with `parse_layer` being called for `image("a")` and `red`
+fn parse_background_layers<'a, T, E, F>(input: &'a str, parse_layer: F)
+-> Result<Vec<T>, E> where F: Fn(&'a str) -> Result<T, E>
+{
+    split_commas_outside_braces(input).into_iter().map(parse_layer).collect()
+}
 
-    for (idx, ch) in input.char_indices() {
-        last_character = idx;
-        match ch {
-            '(' => { depth += 1; },
-            ')' => { depth -= 1; },
-            c => {
-                if c == target_char && depth == 0 {
-                    character_was_found = true;
-                    break;
-                }
-            },
-        }
+// parses one layer of a `background-image`, such as `"Cat 01"` or `none`
+fn parse_background_image<'a>(input: &'a str)
+-> Result<StyleBackground, CssImageParseError<'a>>
+{
+    match input {
+        "none" => Ok(StyleBackground::NoBackground),
+        other => Ok(StyleBackground::Image(parse_image(other)?)),
     }
+}
 
-    if last_character == 0 {
-        // No more split by `,`
-        None
-    } else {
-        Some((last_character, character_was_found))
-    }
+#[derive(Clone, PartialEq)]
+pub enum CssBackgroundPositionParseError<'a> {
+    EmptyString,
+    TooManyComponents(&'a str),
+    /// Keywords for the same axis, i.e. `left right`, or offsets in the wrong place, i.e. `center 10px top`
+    InvalidComponents(&'a str),
+    PixelParseError(PixelParseError<'a>),
 }
 
-// parses a single gradient such as "to right, 50px"
-pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
--> Result<StyleBackground, CssBackgroundParseError<'a>>
+impl_debug_as_display!(CssBackgroundPositionParseError<'a>);
+impl_display!{ CssBackgroundPositionParseError<'a>, {
+    EmptyString => format!("Missing background-position value"),
+    TooManyComponents(e) => format!("Too many components for background-position: \"{}\"", e),
+    InvalidComponents(e) => format!("Invalid background-position: \"{}\"", e),
+    PixelParseError(e) => format!("{}", e),
+}}
+
+impl_from!(PixelParseError<'a>, CssBackgroundPositionParseError::PixelParseError);
+
+/// Parses a `background-position` attribute, such as `center`, `right 20%`, `10px 50px`
+/// or `right 10px bottom 20px`. If only one value is given, the other axis defaults to `center`.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_background_position;
+/// # use azul_css::{StyleBackgroundPosition, PixelValue, SizeMetric};
+/// assert_eq!(
+///     parse_style_background_position("bottom left"),
+///     Ok(StyleBackgroundPosition { horizontal: PixelValue::percent(0.0), vertical: PixelValue::percent(100.0) })
+/// );
+/// assert_eq!(
+///     parse_style_background_position("right 10px top 5px"),
+///     Ok(StyleBackgroundPosition {
+///         horizontal: PixelValue::calc(&[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -10.0)]),
+///         vertical: PixelValue::px(5.0),
+///     })
+/// );
+/// ```
+pub fn parse_style_background_position<'a>(input: &'a str)
+-> Result<StyleBackgroundPosition, CssBackgroundPositionParseError<'a>>
 {
-    let input = input.trim();
+    use self::CssBackgroundPositionParseError::*;
 
-    match background_type {
-        BackgroundType::Image => { return Ok(StyleBackground::Image(parse_image(input)?)); }
-        BackgroundType::Color => { return Ok(StyleBackground::Color(parse_css_color(input)?)); }
-        _ => { },
+    #[derive(Copy, Clone, PartialEq)]
+    enum PositionKeyword {
+        Left,
+        Right,
+        Top,
+        Bottom,
+        Center,
     }
 
-    // Splitting the input by "," doesn't work since rgba() might contain commas
-    let mut comma_separated_items = Vec::<&str>::new();
-    let mut current_input = &input[..];
+    impl PositionKeyword {
 
-    'outer: loop {
-        let (skip_next_braces_result, character_was_found) =
-        match skip_next_braces(&current_input, ',') {
-            Some(s) => s,
+        fn is_horizontal(&self) -> bool {
+            *self == PositionKeyword::Left || *self == PositionKeyword::Right
+        }
+
+        fn is_vertical(&self) -> bool {
+            *self == PositionKeyword::Top || *self == PositionKeyword::Bottom
+        }
+
+        /// Offset of the keyword without an additional length, i.e. `right` = `100%`
+        fn to_percent(&self) -> PixelValue {
+            use PositionKeyword::*;
+            match self {
+                Left | Top => PixelValue::percent(0.0),
+                Center => PixelValue::percent(50.0),
+                Right | Bottom => PixelValue::percent(100.0),
+            }
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq)]
+    enum PositionComponent {
+        Keyword(PositionKeyword),
+        Offset(PixelValue),
+    }
+
+    impl PositionComponent {
+        fn to_offset(&self) -> PixelValue {
+            match self {
+                PositionComponent::Keyword(k) => k.to_percent(),
+                PositionComponent::Offset(o) => *o,
+            }
+        }
+    }
+
+    fn parse_component<'a>(input: &'a str) -> Result<PositionComponent, CssBackgroundPositionParseError<'a>> {
+        use PositionKeyword::*;
+        match input {
+            "left" => Ok(PositionComponent::Keyword(Left)),
+            "right" => Ok(PositionComponent::Keyword(Right)),
+            "top" => Ok(PositionComponent::Keyword(Top)),
+            "bottom" => Ok(PositionComponent::Keyword(Bottom)),
+            "center" => Ok(PositionComponent::Keyword(Center)),
+            other => Ok(PositionComponent::Offset(parse_pixel_value(other)?)),
+        }
+    }
+
+    // Offset of a keyword with an additional length, i.e. `right 10px` = `calc(100% - 10px)`
+    fn keyword_with_offset(keyword: PositionKeyword, offset: PixelValue) -> Option<PixelValue> {
+        use PositionKeyword::*;
+        match keyword {
+            Left | Top => Some(offset),
+            Right | Bottom => {
+                let terms = ::std::iter::once((SizeMetric::Percent, 100.0))
+                    .chain(offset.get_terms().into_iter().map(|(metric, number)| (metric, -number)))
+                    .collect::<Vec<_>>();
+                Some(PixelValue::calc(&terms))
+            },
+            Center => None,
+        }
+    }
+
+    let input = input.trim();
+    let components = split_whitespace_outside_braces(input);
+    let center = PixelValue::percent(50.0);
+
+    let (horizontal, vertical) = match components.as_slice() {
+        [] => return Err(EmptyString),
+        [single] => match parse_component(single)? {
+            PositionComponent::Keyword(k) if k.is_vertical() => (center, k.to_percent()),
+            other => (other.to_offset(), center),
+        },
+        [first, second] => {
+            let first = parse_component(first)?;
+            let second = parse_component(second)?;
+            let (horizontal, vertical) = match (first, second) {
+                // "top left" is the same as "left top", but "top 10px" is invalid
+                (PositionComponent::Keyword(v), PositionComponent::Keyword(h)) if v.is_vertical() || h.is_horizontal() => (second, first),
+                _ => (first, second),
+            };
+            match (horizontal, vertical) {
+                (PositionComponent::Keyword(h), _) if h.is_vertical() => return Err(InvalidComponents(input)),
+                (_, PositionComponent::Keyword(v)) if v.is_horizontal() => return Err(InvalidComponents(input)),
+                (h, v) => (h.to_offset(), v.to_offset()),
+            }
+        },
+        [_, _, _] | [_, _, _, _] => {
+            // Edge offsets, i.e. "right 10px bottom" or "right 10px bottom 20px"
+            let mut edges = Vec::<(PositionKeyword, Option<PixelValue>)>::new();
+            for component in components.iter() {
+                match (parse_component(component)?, edges.last_mut()) {
+                    (PositionComponent::Keyword(k), _) => edges.push((k, None)),
+                    (PositionComponent::Offset(o), Some((k, offset @ None))) if *k != PositionKeyword::Center => *offset = Some(o),
+                    _ => return Err(InvalidComponents(input)),
+                }
+            }
+            let (horizontal, vertical) = match edges.as_slice() {
+                [(first, _), (second, _)] if first.is_vertical() || second.is_horizontal() => (edges[1], edges[0]),
+                [first, second] => (*first, *second),
+                _ => return Err(InvalidComponents(input)),
+            };
+            if horizontal.0.is_vertical() || vertical.0.is_horizontal() {
+                return Err(InvalidComponents(input));
+            }
+            let resolve_edge = |(keyword, offset): (PositionKeyword, Option<PixelValue>)| match offset {
+                Some(offset) => keyword_with_offset(keyword, offset).ok_or(InvalidComponents(input)),
+                None => Ok(keyword.to_percent()),
+            };
+            (resolve_edge(horizontal)?, resolve_edge(vertical)?)
+        },
+        _ => return Err(TooManyComponents(input)),
+    };
+
+    Ok(StyleBackgroundPosition { horizontal, vertical })
+}
+
+/// Given a string, returns how many characters need to be skipped
+fn skip_next_braces(input: &str, target_char: char) -> Option<(usize, bool)> {
+
+    let mut depth = 0;
+    let mut last_character = 0;
+    let mut character_was_found = false;
+
+    if input.is_empty() {
+        return None;
+    }
+
+    for (idx, ch) in input.char_indices() {
+        last_character = idx;
+        match ch {
+            '(' => { depth += 1; },
+            ')' => { depth -= 1; },
+            c => {
+                if c == target_char && depth == 0 {
+                    character_was_found = true;
+                    break;
+                }
+            },
+        }
+    }
+
+    if last_character == 0 {
+        // No more split by `,`
+        None
+    } else {
+        Some((last_character, character_was_found))
+    }
+}
+
+// parses a single gradient such as "to right, 50px"
+pub fn parse_gradient<'a>(input: &'a str, background_type: BackgroundType)
+-> Result<StyleBackground, CssBackgroundParseError<'a>>
+{
+    let input = input.trim();
+
+    match background_type {
+        BackgroundType::Image => { return Ok(StyleBackground::Image(parse_image(input)?)); }
+        BackgroundType::Color => { return Ok(StyleBackground::Color(parse_css_color(input)?)); }
+        _ => { },
+    }
+
+    // Splitting the input by "," doesn't work since rgba() might contain commas
+    let mut comma_separated_items = Vec::<&str>::new();
+    let mut current_input = &input[..];
+
+    'outer: loop {
+        let (skip_next_braces_result, character_was_found) =
+        match skip_next_braces(&current_input, ',') {
+            Some(s) => s,
             None => break 'outer,
         };
         let new_push_item = if character_was_found {
@@ -1891,8 +2688,45 @@ pub struct RectLayout {
     pub align_content: Option<LayoutAlignContent>,
 }
 
-typed_pixel_value_parser!(parse_layout_width, LayoutWidth);
-typed_pixel_value_parser!(parse_layout_height, LayoutHeight);
+/// Parses a `width` attribute, i.e. `200px`, `25%` or one of the
+/// `min-content`, `max-content` and `fit-content` keywords
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_layout_width;
+/// # use azul_css::LayoutWidth;
+/// assert_eq!(parse_layout_width("5px"), Ok(LayoutWidth::px(5.0)));
+/// assert_eq!(parse_layout_width("min-content"), Ok(LayoutWidth::MinContent));
+/// ```
+pub fn parse_layout_width<'a>(input: &'a str) -> Result<LayoutWidth, PixelParseError<'a>> {
+    match input.trim() {
+        "min-content" => Ok(LayoutWidth::MinContent),
+        "max-content" => Ok(LayoutWidth::MaxContent),
+        "fit-content" => Ok(LayoutWidth::FitContent),
+        other => Ok(LayoutWidth::Exact(parse_pixel_value(other)?)),
+    }
+}
+
+/// Parses a `height` attribute, i.e. `200px`, `25%` or one of the
+/// `min-content`, `max-content` and `fit-content` keywords
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_layout_height;
+/// # use azul_css::LayoutHeight;
+/// assert_eq!(parse_layout_height("5px"), Ok(LayoutHeight::px(5.0)));
+/// assert_eq!(parse_layout_height("fit-content"), Ok(LayoutHeight::FitContent));
+/// ```
+pub fn parse_layout_height<'a>(input: &'a str) -> Result<LayoutHeight, PixelParseError<'a>> {
+    match input.trim() {
+        "min-content" => Ok(LayoutHeight::MinContent),
+        "max-content" => Ok(LayoutHeight::MaxContent),
+        "fit-content" => Ok(LayoutHeight::FitContent),
+        other => Ok(LayoutHeight::Exact(parse_pixel_value(other)?)),
+    }
+}
 
 typed_pixel_value_parser!(parse_layout_min_height, LayoutMinHeight);
 typed_pixel_value_parser!(parse_layout_min_width, LayoutMinWidth);
@@ -1936,6 +2770,141 @@ pub fn parse_layout_flex_shrink<'a>(input: &'a str) -> Result<LayoutFlexShrink,
     }
 }
 
+/// Parses a `flex-basis` attribute, i.e. `auto`, `200px` or `25%`
+pub fn parse_layout_flex_basis<'a>(input: &'a str) -> Result<LayoutFlexBasis, PixelParseError<'a>> {
+    match input.trim() {
+        "auto" | "content" => Ok(LayoutFlexBasis::Auto),
+        other => Ok(LayoutFlexBasis::Exact(parse_pixel_value(other)?)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FlexParseError<'a> {
+    EmptyString,
+    /// More than two flex factors or more than one flex basis, i.e. `1 1 1`
+    TooManyValues(&'a str),
+    PixelParseError(PixelParseError<'a>),
+}
+
+impl_display!{FlexParseError<'a>, {
+    EmptyString => format!("Missing flex-grow, flex-shrink or flex-basis"),
+    TooManyValues(e) => format!("Expected at most two numbers and a flex-basis, got: \"{}\"", e),
+    PixelParseError(e) => format!("{}", e),
+}}
+
+impl_from!(PixelParseError<'a>, FlexParseError::PixelParseError);
+
+/// Parses a `flex` attribute: `none` (`0 0 auto`), `auto` (`1 1 auto`) or up to two numbers
+/// (`flex-grow` and `flex-shrink`) and a `flex-basis`. An omitted `flex-shrink` defaults to `1`,
+/// an omitted `flex-basis` to `0px` - i.e. `flex: 2` is the same as `flex: 2 1 0px`
+/// and `flex: 200px` is the same as `flex: 1 1 200px`.
+pub fn parse_layout_flex<'a>(input: &'a str) -> Result<LayoutFlex, FlexParseError<'a>> {
+
+    let input = input.trim();
+
+    match input {
+        "" => return Err(FlexParseError::EmptyString),
+        "none" => return Ok(LayoutFlex {
+            grow: LayoutFlexGrow(FloatValue::new(0.0)),
+            shrink: LayoutFlexShrink(FloatValue::new(0.0)),
+            basis: LayoutFlexBasis::Auto,
+        }),
+        "auto" => return Ok(LayoutFlex {
+            grow: LayoutFlexGrow(FloatValue::new(1.0)),
+            shrink: LayoutFlexShrink(FloatValue::new(1.0)),
+            basis: LayoutFlexBasis::Auto,
+        }),
+        _ => { },
+    }
+
+    let mut factors = Vec::new();
+    let mut basis = None;
+
+    for value in split_whitespace_outside_braces(input) {
+        // A unitless number is a flex factor, unless there are already two factors,
+        // in which case only a unitless zero is allowed as the flex basis
+        match parse_float_value(value) {
+            Ok(factor) if factors.len() < 2 => factors.push(factor),
+            _ if basis.is_none() && value == "0" => basis = Some(LayoutFlexBasis::Exact(PixelValue::px(0.0))),
+            _ if basis.is_none() => basis = Some(parse_layout_flex_basis(value)?),
+            _ => return Err(FlexParseError::TooManyValues(input)),
+        }
+    }
+
+    Ok(LayoutFlex {
+        grow: LayoutFlexGrow(factors.get(0).cloned().unwrap_or(FloatValue::new(1.0))),
+        shrink: LayoutFlexShrink(factors.get(1).cloned().unwrap_or(FloatValue::new(1.0))),
+        basis: basis.unwrap_or(LayoutFlexBasis::Exact(PixelValue::px(0.0))),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderParseError<'a> {
+    ParseInt(ParseIntError, &'a str),
+}
+
+impl_display!{OrderParseError<'a>, {
+    ParseInt(e, orig_str) => format!("order: Could not parse integer value: \"{}\" - Error: \"{}\"", orig_str, e),
+}}
+
+/// Parses an `order` attribute, an integer such as `-1` or `2`
+pub fn parse_layout_order<'a>(input: &'a str) -> Result<LayoutOrder, OrderParseError<'a>> {
+    let input = input.trim();
+    match input.parse::<isize>() {
+        Ok(order) => Ok(LayoutOrder(order)),
+        Err(e) => Err(OrderParseError::ParseInt(e, input)),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GapParseError<'a> {
+    TooManyValues(&'a str),
+    PixelParseError(PixelParseError<'a>),
+}
+
+impl_display!{GapParseError<'a>, {
+    TooManyValues(e) => format!("Expected one or two values (row gap, column gap), got: \"{}\"", e),
+    PixelParseError(e) => format!("{}", e),
+}}
+
+impl_from!(PixelParseError<'a>, GapParseError::PixelParseError);
+
+/// Parses a `gap` attribute, either one value for both directions or the
+/// row gap followed by the column gap, i.e. `10px` or `10px 20px`
+pub fn parse_layout_gap<'a>(input: &'a str) -> Result<LayoutGap, GapParseError<'a>> {
+    let values = split_whitespace_outside_braces(input);
+    match values.as_slice() {
+        [row, column] => Ok(LayoutGap { row: Some(parse_pixel_value(row)?), column: Some(parse_pixel_value(column)?) }),
+        [_, _, ..] => Err(GapParseError::TooManyValues(input)),
+        _ => {
+            let gap = parse_pixel_value(input)?;
+            Ok(LayoutGap { row: Some(gap), column: Some(gap) })
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZIndexParseError<'a> {
+    ParseInt(ParseIntError, &'a str),
+}
+
+impl_display!{ZIndexParseError<'a>, {
+    ParseInt(e, orig_str) => format!("z-index: Could not parse integer value: \"{}\" - Error: \"{}\"", orig_str, e),
+}}
+
+/// Parses a `z-index` attribute, either `auto` or an integer such as `-1` or `10`
+pub fn parse_layout_z_index<'a>(input: &'a str) -> Result<LayoutZIndex, ZIndexParseError<'a>> {
+    let input = input.trim();
+    if input == "auto" {
+        return Ok(LayoutZIndex::Auto);
+    }
+
+    match input.parse::<isize>() {
+        Ok(z) => Ok(LayoutZIndex::Integer(z)),
+        Err(e) => Err(ZIndexParseError::ParseInt(e, input)),
+    }
+}
+
 pub fn parse_style_tab_width(input: &str)
 -> Result<StyleTabWidth, PercentageParseError>
 {
@@ -1948,120 +2917,1351 @@ pub fn parse_style_line_height(input: &str)
     parse_percentage_value(input).and_then(|e| Ok(StyleLineHeight(e)))
 }
 
-typed_pixel_value_parser!(parse_style_font_size, StyleFontSize);
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpacityParseError<'a> {
+    ParseFloat(ParseFloatError, &'a str),
+}
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-pub enum CssStyleFontFamilyParseError<'a> {
-    InvalidStyleFontFamily(&'a str),
-    UnclosedQuotes(&'a str),
+impl_display!{OpacityParseError<'a>, {
+    ParseFloat(e, orig_str) => format!("opacity: Could not parse floating-point value: \"{}\" - Error: \"{}\"", orig_str, e),
+}}
+
+/// Parses an `opacity` attribute, either as a number (`0.5`) or as a percentage (`50%`)
+pub fn parse_style_opacity<'a>(input: &'a str) -> Result<StyleOpacity, OpacityParseError<'a>> {
+    let input = input.trim();
+    let opacity = if input.ends_with('%') {
+        parse_float_value(&input[..(input.len() - 1)]).map(|p| p.get() / 100.0)
+    } else {
+        parse_float_value(input).map(|p| p.get())
+    };
+
+    match opacity {
+        Ok(o) => Ok(StyleOpacity(FloatValue::new(o))),
+        Err(e) => Err(OpacityParseError::ParseFloat(e, input)),
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CssAngleValueParseError<'a> {
+    EmptyString,
+    NoValueGiven(&'a str),
+    UnsupportedMetric(f32, String, &'a str),
+    ValueParseErr(ParseFloatError, String),
+}
+
+impl_debug_as_display!(CssAngleValueParseError<'a>);
+
+impl_display!{ CssAngleValueParseError<'a>, {
+    EmptyString => format!("Missing [deg / rad / grad / turn] value"),
+    NoValueGiven(input) => format!("Expected floating-point angle value, got: \"{}\"", input),
+    UnsupportedMetric(_, metric, input) => format!("Could not parse \"{}\": Metric \"{}\" is not (yet) implemented.", input, metric),
+    ValueParseErr(err, number_str) => format!("Could not parse \"{}\" as floating-point value: \"{}\"", number_str, err),
+}}
+
+/// Parses a single angle such as "45deg" (similar to `parse_pixel_value`)
+pub fn parse_angle_value<'a>(input: &'a str)
+-> Result<AngleValue, CssAngleValueParseError<'a>>
+{
+    let input = input.trim();
+
+    if input.is_empty() {
+        return Err(CssAngleValueParseError::EmptyString);
+    }
+
+    let is_part_of_number = |ch: &char| ch.is_numeric() || *ch == '.' || *ch == '-';
+
+    let number_str = input.chars().take_while(is_part_of_number).collect::<String>();
+    let unit_str = input.chars().filter(|ch| !is_part_of_number(ch)).collect::<String>();
+
+    if number_str.is_empty() {
+        return Err(CssAngleValueParseError::NoValueGiven(input));
+    }
+
+    let number = number_str.parse::<f32>().map_err(|e| CssAngleValueParseError::ValueParseErr(e, number_str))?;
+
+    let unit = match unit_str.as_str() {
+        "deg" => AngleMetric::Deg,
+        "rad" => AngleMetric::Rad,
+        "grad" => AngleMetric::Grad,
+        "turn" => AngleMetric::Turn,
+        // CSS allows unitless zero angles
+        "" if number == 0.0 => AngleMetric::Deg,
+        _ => return Err(CssAngleValueParseError::UnsupportedMetric(number, unit_str, input)),
+    };
+
+    Ok(AngleValue::from_metric(unit, number))
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CssStyleTransformParseError<'a> {
+    InvalidFunction(&'a str),
+    UnclosedBraces(&'a str),
+    WrongNumberOfArguments(&'a str),
+    FloatValueParseErr(ParseFloatError, &'a str),
+    PixelParseError(PixelParseError<'a>),
+    AngleValueParseError(CssAngleValueParseError<'a>),
+}
+
+impl_debug_as_display!(CssStyleTransformParseError<'a>);
+impl_display!{ CssStyleTransformParseError<'a>, {
+    InvalidFunction(f) => format!("Invalid transform function: \"{}\"", f),
+    UnclosedBraces(f) => format!("Unclosed parenthesis in transform: \"{}\"", f),
+    WrongNumberOfArguments(f) => format!("Wrong number of arguments for transform function: \"{}\"", f),
+    FloatValueParseErr(e, orig_str) => format!("Could not parse \"{}\" as floating-point value: \"{}\"", orig_str, e),
+    PixelParseError(e) => format!("{}", e),
+    AngleValueParseError(e) => format!("{}", e),
+}}
+
+impl_from!(PixelParseError<'a>, CssStyleTransformParseError::PixelParseError);
+impl_from!(CssAngleValueParseError<'a>, CssStyleTransformParseError::AngleValueParseError);
+
+/// Parses a `transform` attribute, such as `translate(10px, 20px) rotate(45deg)`.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_transform;
+/// # use azul_css::{StyleTransform, TransformFunction, PixelValue, AngleValue};
+/// assert_eq!(
+///     parse_style_transform("translateX(10px) rotate(45deg)"),
+///     Ok(StyleTransform(vec![
+///         TransformFunction::TranslateX(PixelValue::px(10.0)),
+///         TransformFunction::Rotate(AngleValue::deg(45.0)),
+///     ]))
+/// );
+/// assert_eq!(parse_style_transform("none"), Ok(StyleTransform(Vec::new())));
+/// ```
+pub fn parse_style_transform<'a>(input: &'a str)
+-> Result<StyleTransform, CssStyleTransformParseError<'a>>
+{
+    use self::CssStyleTransformParseError::*;
+
+    let input = input.trim();
+    if input == "none" {
+        return Ok(StyleTransform(Vec::new()));
+    }
+
+    let mut functions = Vec::new();
+    let mut remaining = input;
+
+    while !remaining.is_empty() {
+        let open_brace = remaining.find('(').ok_or(InvalidFunction(remaining))?;
+        let close_brace = remaining.find(')').ok_or(UnclosedBraces(remaining))?;
+        if close_brace < open_brace {
+            return Err(UnclosedBraces(remaining));
+        }
+
+        let function = &remaining[..=close_brace];
+        let function_name = remaining[..open_brace].trim();
+        let arguments = remaining[(open_brace + 1)..close_brace]
+            .split(',')
+            .map(|arg| arg.trim())
+            .collect::<Vec<&'a str>>();
+
+        functions.push(parse_transform_function(function, function_name, &arguments)?);
+        remaining = remaining[(close_brace + 1)..].trim_start();
+    }
+
+    if functions.is_empty() {
+        return Err(InvalidFunction(input));
+    }
+
+    Ok(StyleTransform(functions))
+}
+
+fn parse_transform_function<'a>(function: &'a str, function_name: &'a str, arguments: &[&'a str])
+-> Result<TransformFunction, CssStyleTransformParseError<'a>>
+{
+    use self::CssStyleTransformParseError::*;
+
+    let parse_number = |arg: &'a str| parse_float_value(arg).map_err(|e| FloatValueParseErr(e, arg));
+
+    match (function_name, arguments.len()) {
+        ("matrix", 6) => {
+            let mut matrix = [FloatValue::new(0.0); 6];
+            for (target, arg) in matrix.iter_mut().zip(arguments.iter()) {
+                *target = parse_number(*arg)?;
+            }
+            Ok(TransformFunction::Matrix(matrix))
+        },
+        ("translate", 1) => Ok(TransformFunction::Translate(parse_pixel_value(arguments[0])?, PixelValue::px(0.0))),
+        ("translate", 2) => Ok(TransformFunction::Translate(parse_pixel_value(arguments[0])?, parse_pixel_value(arguments[1])?)),
+        ("translateX", 1) => Ok(TransformFunction::TranslateX(parse_pixel_value(arguments[0])?)),
+        ("translateY", 1) => Ok(TransformFunction::TranslateY(parse_pixel_value(arguments[0])?)),
+        ("scale", 1) => {
+            let scale = parse_number(arguments[0])?;
+            Ok(TransformFunction::Scale(scale, scale))
+        },
+        ("scale", 2) => Ok(TransformFunction::Scale(parse_number(arguments[0])?, parse_number(arguments[1])?)),
+        ("scaleX", 1) => Ok(TransformFunction::ScaleX(parse_number(arguments[0])?)),
+        ("scaleY", 1) => Ok(TransformFunction::ScaleY(parse_number(arguments[0])?)),
+        ("rotate", 1) => Ok(TransformFunction::Rotate(parse_angle_value(arguments[0])?)),
+        ("skew", 1) => Ok(TransformFunction::Skew(parse_angle_value(arguments[0])?, AngleValue::deg(0.0))),
+        ("skew", 2) => Ok(TransformFunction::Skew(parse_angle_value(arguments[0])?, parse_angle_value(arguments[1])?)),
+        ("skewX", 1) => Ok(TransformFunction::SkewX(parse_angle_value(arguments[0])?)),
+        ("skewY", 1) => Ok(TransformFunction::SkewY(parse_angle_value(arguments[0])?)),
+        | ("matrix", _) | ("translate", _) | ("translateX", _) | ("translateY", _)
+        | ("scale", _) | ("scaleX", _) | ("scaleY", _) | ("rotate", _)
+        | ("skew", _) | ("skewX", _) | ("skewY", _) => Err(WrongNumberOfArguments(function)),
+        _ => Err(InvalidFunction(function)),
+    }
 }
 
-impl_display!{CssStyleFontFamilyParseError<'a>, {
-    InvalidStyleFontFamily(val) => format!("Invalid font-family: \"{}\"", val),
-    UnclosedQuotes(val) => format!("Unclosed quotes: \"{}\"", val),
-}}
+#[derive(Clone, PartialEq)]
+pub enum CssStyleTransformOriginParseError<'a> {
+    EmptyString,
+    TooManyValues(&'a str),
+    InvalidKeywordOrder(&'a str),
+    PixelParseError(PixelParseError<'a>),
+    PercentageParseError(PercentageParseError),
+}
+
+impl_debug_as_display!(CssStyleTransformOriginParseError<'a>);
+impl_display!{ CssStyleTransformOriginParseError<'a>, {
+    EmptyString => format!("Missing transform-origin value"),
+    TooManyValues(val) => format!("Too many values for transform-origin: \"{}\"", val),
+    InvalidKeywordOrder(val) => format!("Invalid transform-origin: \"{}\"", val),
+    PixelParseError(e) => format!("{}", e),
+    PercentageParseError(e) => format!("{}", e),
+}}
+
+impl_from!(PixelParseError<'a>, CssStyleTransformOriginParseError::PixelParseError);
+
+impl<'a> From<PercentageParseError> for CssStyleTransformOriginParseError<'a> {
+    fn from(e: PercentageParseError) -> Self {
+        CssStyleTransformOriginParseError::PercentageParseError(e)
+    }
+}
+
+/// Parses a `transform-origin` attribute, such as `left top`, `50% 100%` or `10px`.
+/// If only one value is given, the other axis defaults to `center`.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_transform_origin;
+/// # use azul_css::{StyleTransformOrigin, TransformOriginOffset, PixelValue, PercentageValue};
+/// assert_eq!(
+///     parse_style_transform_origin("bottom 10px"),
+///     parse_style_transform_origin("10px bottom")
+/// );
+/// assert_eq!(
+///     parse_style_transform_origin("10px"),
+///     Ok(StyleTransformOrigin {
+///         x: TransformOriginOffset::Pixels(PixelValue::px(10.0)),
+///         y: TransformOriginOffset::Percentage(PercentageValue::new(0.5)),
+///     })
+/// );
+/// ```
+pub fn parse_style_transform_origin<'a>(input: &'a str)
+-> Result<StyleTransformOrigin, CssStyleTransformOriginParseError<'a>>
+{
+    use self::CssStyleTransformOriginParseError::*;
+
+    #[derive(Copy, Clone, PartialEq)]
+    enum OriginComponent {
+        Horizontal(TransformOriginOffset),
+        Vertical(TransformOriginOffset),
+        Either(TransformOriginOffset),
+    }
+
+    impl OriginComponent {
+        fn offset(&self) -> TransformOriginOffset {
+            match self {
+                | OriginComponent::Horizontal(o)
+                | OriginComponent::Vertical(o)
+                | OriginComponent::Either(o) => *o,
+            }
+        }
+    }
+
+    fn parse_component<'a>(input: &'a str) -> Result<OriginComponent, CssStyleTransformOriginParseError<'a>> {
+        let percent = |p: f32| TransformOriginOffset::Percentage(PercentageValue::new(p));
+        match input {
+            "left" => Ok(OriginComponent::Horizontal(percent(0.0))),
+            "right" => Ok(OriginComponent::Horizontal(percent(1.0))),
+            "top" => Ok(OriginComponent::Vertical(percent(0.0))),
+            "bottom" => Ok(OriginComponent::Vertical(percent(1.0))),
+            "center" => Ok(OriginComponent::Either(percent(0.5))),
+            other if other.ends_with('%') => Ok(OriginComponent::Either(TransformOriginOffset::Percentage(parse_percentage_value(other)?))),
+            other => Ok(OriginComponent::Either(TransformOriginOffset::Pixels(parse_pixel_value(other)?))),
+        }
+    }
+
+    let input = input.trim();
+    let components = split_whitespace_outside_braces(input);
+    let center = TransformOriginOffset::Percentage(PercentageValue::new(0.5));
+
+    let (x, y) = match components.as_slice() {
+        [] => return Err(EmptyString),
+        [single] => match parse_component(single)? {
+            OriginComponent::Vertical(y) => (center, y),
+            other => (other.offset(), center),
+        },
+        [first, second] => {
+            let first = parse_component(first)?;
+            let second = parse_component(second)?;
+            match (first, second) {
+                // "top left" is the same as "left top"
+                (OriginComponent::Vertical(_), OriginComponent::Horizontal(_)) |
+                (OriginComponent::Vertical(_), OriginComponent::Either(_)) |
+                (OriginComponent::Either(_), OriginComponent::Horizontal(_)) => (second.offset(), first.offset()),
+                (OriginComponent::Horizontal(_), OriginComponent::Horizontal(_)) |
+                (OriginComponent::Vertical(_), OriginComponent::Vertical(_)) => return Err(InvalidKeywordOrder(input)),
+                _ => (first.offset(), second.offset()),
+            }
+        },
+        _ => return Err(TooManyValues(input)),
+    };
+
+    Ok(StyleTransformOrigin { x, y })
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CssAnimationParseError<'a> {
+    /// Empty value or empty item in a comma-separated list, i.e. `transition: width 1s, `
+    EmptyValue,
+    /// Time without `s` or `ms` unit, i.e. `500`
+    InvalidTime(&'a str),
+    /// Durations and delays can't be negative
+    NegativeTime(&'a str),
+    InvalidTimingFunction(&'a str),
+    /// `cubic-bezier()` needs four numbers, the x coordinates have to be between 0 and 1
+    InvalidCubicBezier(&'a str),
+    /// `steps()` needs a positive step count and an optional `start` or `end` position
+    InvalidSteps(&'a str),
+    /// The transitioned property is not a known CSS key
+    UnknownProperty(&'a str),
+    /// Value that can't be assigned to any part of the `transition` or `animation`
+    UnexpectedValue(&'a str),
+    /// The `animation` has no name of a `@keyframes` rule
+    MissingAnimationName(&'a str),
+}
+
+impl_debug_as_display!(CssAnimationParseError<'a>);
+impl_display!{ CssAnimationParseError<'a>, {
+    EmptyValue => format!("Missing transition or animation value"),
+    InvalidTime(t) => format!("Invalid time: \"{}\" - expected a value such as \"0.5s\" or \"500ms\"", t),
+    NegativeTime(t) => format!("Negative times are not supported: \"{}\"", t),
+    InvalidTimingFunction(f) => format!("Invalid timing function: \"{}\"", f),
+    InvalidCubicBezier(f) => format!("Invalid cubic-bezier() timing function: \"{}\"", f),
+    InvalidSteps(f) => format!("Invalid steps() timing function: \"{}\"", f),
+    UnknownProperty(p) => format!("Unknown property in transition: \"{}\"", p),
+    UnexpectedValue(v) => format!("Unexpected value: \"{}\"", v),
+    MissingAnimationName(a) => format!("Missing @keyframes name in animation: \"{}\"", a),
+}}
+
+/// Parses a time value such as `0.3s` or `300ms`
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css_parser;
+/// # use std::time::Duration;
+/// # use azul_css_parser::parse_time_value;
+/// assert_eq!(parse_time_value("0.3s"), Ok(Duration::from_millis(300)));
+/// assert_eq!(parse_time_value("300ms"), Ok(Duration::from_millis(300)));
+/// assert!(parse_time_value("300").is_err());
+/// ```
+pub fn parse_time_value<'a>(input: &'a str) -> Result<Duration, CssAnimationParseError<'a>> {
+
+    let input = input.trim();
+
+    let (number, multiplier) = if input.ends_with("ms") {
+        (&input[..input.len() - 2], 1.0)
+    } else if input.ends_with('s') {
+        (&input[..input.len() - 1], 1000.0)
+    } else {
+        return Err(CssAnimationParseError::InvalidTime(input));
+    };
+
+    let number = number.parse::<f32>().map_err(|_| CssAnimationParseError::InvalidTime(input))?;
+    if number < 0.0 {
+        return Err(CssAnimationParseError::NegativeTime(input));
+    }
+
+    let nanos = (number * multiplier * 1_000_000.0).round() as u64;
+    Ok(Duration::new(nanos / 1_000_000_000, (nanos % 1_000_000_000) as u32))
+}
+
+/// Parses the timing function of a `transition` or `animation`, such as
+/// `ease-in-out`, `cubic-bezier(0.1, 0.7, 1.0, 0.1)` or `steps(4, end)`
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_animation_timing_function;
+/// # use azul_css::{AnimationTimingFunction, StepPosition, FloatValue};
+/// assert_eq!(parse_animation_timing_function("ease-in"), Ok(AnimationTimingFunction::EaseIn));
+/// assert_eq!(parse_animation_timing_function("step-end"), Ok(AnimationTimingFunction::Steps(1, StepPosition::End)));
+/// assert_eq!(
+///     parse_animation_timing_function("cubic-bezier(0.5, 0, 1, 2)"),
+///     Ok(AnimationTimingFunction::CubicBezier([
+///         FloatValue::new(0.5), FloatValue::new(0.0), FloatValue::new(1.0), FloatValue::new(2.0)
+///     ]))
+/// );
+/// ```
+pub fn parse_animation_timing_function<'a>(input: &'a str) -> Result<AnimationTimingFunction, CssAnimationParseError<'a>> {
+
+    use self::CssAnimationParseError::*;
+
+    let input = input.trim();
+
+    match input {
+        "ease" => return Ok(AnimationTimingFunction::Ease),
+        "linear" => return Ok(AnimationTimingFunction::Linear),
+        "ease-in" => return Ok(AnimationTimingFunction::EaseIn),
+        "ease-out" => return Ok(AnimationTimingFunction::EaseOut),
+        "ease-in-out" => return Ok(AnimationTimingFunction::EaseInOut),
+        "step-start" => return Ok(AnimationTimingFunction::Steps(1, StepPosition::Start)),
+        "step-end" => return Ok(AnimationTimingFunction::Steps(1, StepPosition::End)),
+        _ => { },
+    }
+
+    let (function, arguments) = parse_parentheses(input, &["cubic-bezier", "steps"]).map_err(|_| InvalidTimingFunction(input))?;
+    let arguments = arguments.split(',').map(|arg| arg.trim()).collect::<Vec<&'a str>>();
+
+    match function {
+        "cubic-bezier" => {
+            if arguments.len() != 4 {
+                return Err(InvalidCubicBezier(input));
+            }
+            let mut points = [FloatValue::new(0.0); 4];
+            for (target, arg) in points.iter_mut().zip(arguments.iter()) {
+                *target = parse_float_value(arg).map_err(|_| InvalidCubicBezier(input))?;
+            }
+            // The x coordinates have to be in the 0..=1 range, otherwise the curve isn't a function of x
+            if [points[0], points[2]].iter().any(|x| x.get() < 0.0 || x.get() > 1.0) {
+                return Err(InvalidCubicBezier(input));
+            }
+            Ok(AnimationTimingFunction::CubicBezier(points))
+        },
+        "steps" => {
+            let count = match arguments.get(0).and_then(|count| count.parse::<usize>().ok()) {
+                Some(count) if count > 0 => count,
+                _ => return Err(InvalidSteps(input)),
+            };
+            let position = match arguments.get(1..) {
+                Some([]) => StepPosition::End,
+                Some(["end"]) | Some(["jump-end"]) => StepPosition::End,
+                Some(["start"]) | Some(["jump-start"]) => StepPosition::Start,
+                _ => return Err(InvalidSteps(input)),
+            };
+            Ok(AnimationTimingFunction::Steps(count, position))
+        },
+        _ => Err(InvalidTimingFunction(input)),
+    }
+}
+
+/// Parses a `transition` attribute, such as `opacity 0.3s ease-in, width 1s linear 500ms`.
+/// The first time is the duration, the second time the delay of the transition.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use std::time::Duration;
+/// # use azul_css_parser::parse_style_transition;
+/// # use azul_css::{StyleTransition, Transition, AnimationTimingFunction, CssPropertyType};
+/// assert_eq!(
+///     parse_style_transition("width 1s ease-in 500ms, all 2s"),
+///     Ok(StyleTransition(vec![
+///         Transition {
+///             property: Some(CssPropertyType::Width),
+///             duration: Duration::from_secs(1),
+///             timing_function: AnimationTimingFunction::EaseIn,
+///             delay: Duration::from_millis(500),
+///         },
+///         Transition {
+///             property: None,
+///             duration: Duration::from_secs(2),
+///             timing_function: AnimationTimingFunction::Ease,
+///             delay: Duration::from_secs(0),
+///         },
+///     ]))
+/// );
+/// assert_eq!(parse_style_transition("none"), Ok(StyleTransition(Vec::new())));
+/// ```
+pub fn parse_style_transition<'a>(input: &'a str) -> Result<StyleTransition, CssAnimationParseError<'a>> {
+
+    use self::CssAnimationParseError::*;
+
+    let input = input.trim();
+    if input == "none" {
+        return Ok(StyleTransition(Vec::new()));
+    }
+
+    let key_map = get_css_key_map();
+    let mut transitions = Vec::new();
+
+    for item in split_commas_outside_braces(input) {
+
+        let mut property = None;
+        let mut times = Vec::new();
+        let mut timing_function = None;
+
+        for component in split_whitespace_outside_braces(item) {
+            if component.starts_with(|c: char| c.is_numeric() || c == '.' || c == '-') {
+                times.push(parse_time_value(component)?);
+            } else if let Ok(f) = parse_animation_timing_function(component) {
+                if timing_function.replace(f).is_some() {
+                    return Err(UnexpectedValue(component));
+                }
+            } else if property.is_none() {
+                property = Some(match component {
+                    "all" => None,
+                    other => Some(CssPropertyType::from_str(other, &key_map).ok_or(UnknownProperty(other))?),
+                });
+            } else {
+                return Err(UnexpectedValue(component));
+            }
+        }
+
+        let (duration, delay) = match times.as_slice() {
+            [] => return Err(EmptyValue),
+            [duration] => (*duration, Duration::from_secs(0)),
+            [duration, delay] => (*duration, *delay),
+            _ => return Err(UnexpectedValue(item)),
+        };
+
+        transitions.push(Transition {
+            property: property.unwrap_or(None),
+            duration,
+            timing_function: timing_function.unwrap_or_default(),
+            delay,
+        });
+    }
+
+    Ok(StyleTransition(transitions))
+}
+
+/// Parses an `animation` attribute, such as `fade-in 1s ease-in-out 200ms infinite alternate both`.
+/// The first time is the duration, the second time the delay of the animation, the name
+/// refers to a `@keyframes` rule.
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use std::time::Duration;
+/// # use azul_css_parser::parse_style_animation;
+/// # use azul_css::{StyleAnimation, Animation, AnimationIterationCount, AnimationDirection};
+/// let mut expected = Animation::new("blink".into(), Duration::from_millis(500));
+/// expected.iteration_count = AnimationIterationCount::Infinite;
+/// expected.direction = AnimationDirection::Alternate;
+///
+/// assert_eq!(parse_style_animation("blink 500ms infinite alternate"), Ok(StyleAnimation(vec![expected])));
+/// assert_eq!(parse_style_animation("none"), Ok(StyleAnimation(Vec::new())));
+/// ```
+pub fn parse_style_animation<'a>(input: &'a str) -> Result<StyleAnimation, CssAnimationParseError<'a>> {
+
+    use self::CssAnimationParseError::*;
+
+    let input = input.trim();
+    if input == "none" {
+        return Ok(StyleAnimation(Vec::new()));
+    }
+
+    let mut animations = Vec::new();
+
+    for item in split_commas_outside_braces(input) {
+
+        let mut name = None;
+        let mut times = Vec::new();
+        let mut timing_function = None;
+        let mut iteration_count = None;
+        let mut direction = None;
+        let mut fill_mode = None;
+
+        for component in split_whitespace_outside_braces(item) {
+            let is_duplicate = match component {
+                "infinite" => iteration_count.replace(AnimationIterationCount::Infinite).is_some(),
+                "normal" => direction.replace(AnimationDirection::Normal).is_some(),
+                "reverse" => direction.replace(AnimationDirection::Reverse).is_some(),
+                "alternate" => direction.replace(AnimationDirection::Alternate).is_some(),
+                "alternate-reverse" => direction.replace(AnimationDirection::AlternateReverse).is_some(),
+                "none" => fill_mode.replace(AnimationFillMode::None).is_some(),
+                "forwards" => fill_mode.replace(AnimationFillMode::Forwards).is_some(),
+                "backwards" => fill_mode.replace(AnimationFillMode::Backwards).is_some(),
+                "both" => fill_mode.replace(AnimationFillMode::Both).is_some(),
+                other if other.ends_with('s') && other.starts_with(|c: char| c.is_numeric() || c == '.' || c == '-') => {
+                    times.push(parse_time_value(other)?);
+                    false
+                },
+                other if other.starts_with(|c: char| c.is_numeric() || c == '.') => {
+                    let count = parse_float_value(other).map_err(|_| UnexpectedValue(other))?;
+                    iteration_count.replace(AnimationIterationCount::Count(count)).is_some()
+                },
+                other => match parse_animation_timing_function(other) {
+                    Ok(f) => timing_function.replace(f).is_some(),
+                    Err(_) => name.replace(other).is_some(),
+                },
+            };
+
+            if is_duplicate {
+                return Err(UnexpectedValue(component));
+            }
+        }
+
+        let name = name.ok_or(MissingAnimationName(item))?;
+        let (duration, delay) = match times.as_slice() {
+            [] => (Duration::from_secs(0), Duration::from_secs(0)),
+            [duration] => (*duration, Duration::from_secs(0)),
+            [duration, delay] => (*duration, *delay),
+            _ => return Err(UnexpectedValue(item)),
+        };
+
+        animations.push(Animation {
+            name: name.trim_matches('"').trim_matches('\'').to_string(),
+            duration,
+            timing_function: timing_function.unwrap_or_default(),
+            delay,
+            iteration_count: iteration_count.unwrap_or_default(),
+            direction: direction.unwrap_or_default(),
+            fill_mode: fill_mode.unwrap_or_default(),
+        });
+    }
+
+    Ok(StyleAnimation(animations))
+}
+
+typed_pixel_value_parser!(parse_style_font_size, StyleFontSize);
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum CssStyleFontFamilyParseError<'a> {
+    InvalidStyleFontFamily(&'a str),
+    UnclosedQuotes(&'a str),
+}
+
+impl_display!{CssStyleFontFamilyParseError<'a>, {
+    InvalidStyleFontFamily(val) => format!("Invalid font-family: \"{}\"", val),
+    UnclosedQuotes(val) => format!("Unclosed quotes: \"{}\"", val),
+}}
+
+impl<'a> From<UnclosedQuotesError<'a>> for CssStyleFontFamilyParseError<'a> {
+    fn from(err: UnclosedQuotesError<'a>) -> Self {
+        CssStyleFontFamilyParseError::UnclosedQuotes(err.0)
+    }
+}
+
+/// Parses a `StyleFontFamily` declaration from a `&str`
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_font_family;
+/// # use azul_css::{StyleFontFamily, FontId};
+/// let input = "\"Helvetica\", 'Arial', Times New Roman";
+/// let fonts = vec![
+///     FontId("Helvetica".into()),
+///     FontId("Arial".into()),
+///     FontId("Times New Roman".into())
+/// ];
+///
+/// assert_eq!(parse_style_font_family(input), Ok(StyleFontFamily { fonts }));
+/// ```
+pub fn parse_style_font_family<'a>(input: &'a str) -> Result<StyleFontFamily, CssStyleFontFamilyParseError<'a>> {
+    let multiple_fonts = input.split(',');
+    let mut fonts = Vec::with_capacity(1);
+
+    for font in multiple_fonts {
+        let font = font.trim();
+        let font = font.trim_matches('\'');
+        let font = font.trim_matches('\"');
+        let font = font.trim();
+        fonts.push(FontId(font.into()));
+    }
+
+    Ok(StyleFontFamily {
+        fonts: fonts,
+    })
+}
+
+/// Parses a `font-weight` attribute, either `normal`, `bold` or a number from `1` to `1000`
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_font_weight;
+/// # use azul_css::StyleFontWeight;
+/// assert_eq!(parse_style_font_weight("bold"), Ok(StyleFontWeight::BOLD));
+/// assert_eq!(parse_style_font_weight("300"), Ok(StyleFontWeight(300)));
+/// assert!(parse_style_font_weight("1200").is_err());
+/// ```
+pub fn parse_style_font_weight<'a>(input: &'a str) -> Result<StyleFontWeight, InvalidValueErr<'a>> {
+    let input = input.trim();
+    match input {
+        "normal" => Ok(StyleFontWeight::NORMAL),
+        "bold" => Ok(StyleFontWeight::BOLD),
+        number => match number.parse::<u16>() {
+            Ok(weight) if weight >= 1 && weight <= 1000 => Ok(StyleFontWeight(weight)),
+            _ => Err(InvalidValueErr(input)),
+        },
+    }
+}
+
+multi_type_parser!(parse_style_font_style, StyleFontStyle,
+    ["normal", Normal],
+    ["italic", Italic],
+    ["oblique", Oblique]);
+
+multi_type_parser!(parse_style_font_stretch, StyleFontStretch,
+    ["ultra-condensed", UltraCondensed],
+    ["extra-condensed", ExtraCondensed],
+    ["condensed", Condensed],
+    ["semi-condensed", SemiCondensed],
+    ["normal", Normal],
+    ["semi-expanded", SemiExpanded],
+    ["expanded", Expanded],
+    ["extra-expanded", ExtraExpanded],
+    ["ultra-expanded", UltraExpanded]);
+
+#[derive(Clone, PartialEq)]
+pub enum CssTextDecorationParseError<'a> {
+    EmptyString,
+    /// Unknown `text-decoration-line` value or `none` combined with other lines
+    InvalidLine(&'a str),
+    /// The line, style, thickness or color is given more than once
+    DuplicateValue(&'a str),
+    ColorParseError(CssColorParseError<'a>),
+}
+
+impl_debug_as_display!(CssTextDecorationParseError<'a>);
+impl_display!{ CssTextDecorationParseError<'a>, {
+    EmptyString => format!("Missing text-decoration value"),
+    InvalidLine(e) => format!("Invalid line: \"{}\"", e),
+    DuplicateValue(e) => format!("Duplicate value: \"{}\"", e),
+    ColorParseError(e) => format!("Invalid color: {}", e),
+}}
+
+impl_from!(CssColorParseError<'a>, CssTextDecorationParseError::ColorParseError);
+
+/// Parses a `text-decoration` shorthand, i.e. `underline dotted red 2px`. The values can be
+/// given in any order, the style defaults to `solid` and the thickness to `auto`. If no color
+/// is given, the color is left unset (so that the line has the color of the text).
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_text_decoration;
+/// # use azul_css::{StyleTextDecoration, TextDecorationLine, TextDecorationStyle, TextDecorationThickness, ColorU};
+/// assert_eq!(
+///     parse_style_text_decoration("underline overline wavy red"),
+///     Ok(StyleTextDecoration {
+///         line: Some(TextDecorationLine { underline: true, overline: true, line_through: false }),
+///         color: Some(ColorU { r: 255, g: 0, b: 0, a: 255 }),
+///         style: Some(TextDecorationStyle::Wavy),
+///         thickness: Some(TextDecorationThickness::Auto),
+///     })
+/// );
+/// ```
+pub fn parse_style_text_decoration<'a>(input: &'a str)
+-> Result<StyleTextDecoration, CssTextDecorationParseError<'a>>
+{
+    use self::CssTextDecorationParseError::*;
+
+    let values = split_whitespace_outside_braces(input);
+    if values.is_empty() {
+        return Err(EmptyString);
+    }
+
+    let mut line_values = Vec::new();
+    let mut style = None;
+    let mut thickness = None;
+    let mut color = None;
+
+    for value in values {
+        match value {
+            "none" | "underline" | "overline" | "line-through" => {
+                line_values.push(value);
+                continue;
+            },
+            _ => { },
+        }
+
+        let is_duplicate = if let Ok(s) = parse_text_decoration_style(value) {
+            style.replace(s).is_some()
+        } else if let Ok(t) = parse_text_decoration_thickness(value) {
+            thickness.replace(t).is_some()
+        } else {
+            color.replace(parse_css_color(value)?).is_some()
+        };
+
+        if is_duplicate {
+            return Err(DuplicateValue(value));
+        }
+    }
+
+    let line = if line_values.is_empty() {
+        TextDecorationLine::NONE
+    } else {
+        parse_text_decoration_line_values(&line_values)?
+    };
+
+    Ok(StyleTextDecoration {
+        line: Some(line),
+        color,
+        style: Some(style.unwrap_or_default()),
+        thickness: Some(thickness.unwrap_or_default()),
+    })
+}
+
+/// Parses a `text-decoration-line` attribute, i.e. `underline line-through` or `none`
+pub fn parse_text_decoration_line<'a>(input: &'a str)
+-> Result<TextDecorationLine, CssTextDecorationParseError<'a>>
+{
+    let values = split_whitespace_outside_braces(input);
+    if values.is_empty() {
+        return Err(CssTextDecorationParseError::EmptyString);
+    }
+    parse_text_decoration_line_values(&values)
+}
+
+fn parse_text_decoration_line_values<'a>(values: &[&'a str])
+-> Result<TextDecorationLine, CssTextDecorationParseError<'a>>
+{
+    use std::mem;
+    use self::CssTextDecorationParseError::*;
+
+    let mut line = TextDecorationLine::NONE;
+
+    for value in values {
+        let is_duplicate = match *value {
+            "none" if values.len() == 1 => false,
+            "underline" => mem::replace(&mut line.underline, true),
+            "overline" => mem::replace(&mut line.overline, true),
+            "line-through" => mem::replace(&mut line.line_through, true),
+            _ => return Err(InvalidLine(value)),
+        };
+        if is_duplicate {
+            return Err(DuplicateValue(value));
+        }
+    }
+
+    Ok(line)
+}
+
+multi_type_parser!(parse_text_decoration_style, TextDecorationStyle,
+    ["solid", Solid],
+    ["double", Double],
+    ["dotted", Dotted],
+    ["dashed", Dashed],
+    ["wavy", Wavy]);
+
+/// Parses a `text-decoration-thickness` attribute, either `auto`, `from-font` or a length
+pub fn parse_text_decoration_thickness<'a>(input: &'a str)
+-> Result<TextDecorationThickness, PixelParseError<'a>>
+{
+    match input.trim() {
+        "auto" | "from-font" => Ok(TextDecorationThickness::Auto),
+        other => Ok(TextDecorationThickness::Length(parse_pixel_value(other)?)),
+    }
+}
+
+/// Parses a `text-shadow` attribute, a comma-separated list of shadows consisting of
+/// two offsets, an optional blur radius and an optional color
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_style_text_shadow;
+/// # use azul_css::{StyleTextShadow, TextShadow, PixelValue, ColorU};
+/// assert_eq!(
+///     parse_style_text_shadow("1px 2px 3px black, 0px 0px red"),
+///     Ok(StyleTextShadow(vec![
+///         TextShadow {
+///             offset: [PixelValue::px(1.0), PixelValue::px(2.0)],
+///             color: Some(ColorU { r: 0, g: 0, b: 0, a: 255 }),
+///             blur_radius: PixelValue::px(3.0),
+///         },
+///         TextShadow {
+///             offset: [PixelValue::px(0.0), PixelValue::px(0.0)],
+///             color: Some(ColorU { r: 255, g: 0, b: 0, a: 255 }),
+///             blur_radius: PixelValue::px(0.0),
+///         },
+///     ]))
+/// );
+/// assert_eq!(parse_style_text_shadow("none"), Ok(StyleTextShadow(Vec::new())));
+/// ```
+pub fn parse_style_text_shadow<'a>(input: &'a str)
+-> Result<StyleTextShadow, CssShadowParseError<'a>>
+{
+    let input = input.trim();
+
+    if input == "none" {
+        return Ok(StyleTextShadow(Vec::new()));
+    }
+
+    let shadows = split_commas_outside_braces(input).into_iter()
+        .map(parse_text_shadow)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(StyleTextShadow(shadows))
+}
+
+fn parse_text_shadow<'a>(input: &'a str)
+-> Result<TextShadow, CssShadowParseError<'a>>
+{
+    use self::CssShadowParseError::*;
+
+    let mut lengths = Vec::new();
+    let mut color = None;
+
+    for value in split_whitespace_outside_braces(input) {
+        match parse_pixel_value(value) {
+            Ok(length) => lengths.push(length),
+            Err(_) if color.is_none() => color = Some(parse_css_color(value)?),
+            Err(_) => return Err(TooManyComponents(input)),
+        }
+    }
+
+    let (offset, blur_radius) = match lengths.as_slice() {
+        [x, y] => ([*x, *y], PixelValue::px(0.0)),
+        [x, y, blur] => ([*x, *y], *blur),
+        [] | [_] => return Err(InvalidSingleStatement(input)),
+        _ => return Err(TooManyComponents(input)),
+    };
+
+    Ok(TextShadow { offset, color, blur_radius })
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+pub enum ParenthesisParseError<'a> {
+    UnclosedBraces,
+    NoOpeningBraceFound,
+    NoClosingBraceFound,
+    StopWordNotFound(&'a str),
+    EmptyInput,
+}
+
+impl_display!{ ParenthesisParseError<'a>, {
+    UnclosedBraces => format!("Unclosed parenthesis"),
+    NoOpeningBraceFound => format!("Expected value in parenthesis (missing \"(\")"),
+    NoClosingBraceFound => format!("Missing closing parenthesis (missing \")\")"),
+    StopWordNotFound(e) => format!("Stopword not found, found: \"{}\"", e),
+    EmptyInput => format!("Empty parenthesis"),
+}}
+
+/// Checks wheter a given input is enclosed in parentheses, prefixed
+/// by a certain number of stopwords.
+///
+/// On success, returns what the stopword was + the string inside the braces
+/// on failure returns None.
+///
+/// ```rust
+/// # use azul_css_parser::parse_parentheses;
+/// # use azul_css_parser::ParenthesisParseError::*;
+/// // Search for the nearest "abc()" brace
+/// assert_eq!(parse_parentheses("abc(def(g))", &["abc"]), Ok(("abc", "def(g)")));
+/// assert_eq!(parse_parentheses("abc(def(g))", &["def"]), Err(StopWordNotFound("abc")));
+/// assert_eq!(parse_parentheses("def(ghi(j))", &["def"]), Ok(("def", "ghi(j)")));
+/// assert_eq!(parse_parentheses("abc(def(g))", &["abc", "def"]), Ok(("abc", "def(g)")));
+/// ```
+pub fn parse_parentheses<'a>(
+    input: &'a str,
+    stopwords: &[&'static str])
+-> Result<(&'static str, &'a str), ParenthesisParseError<'a>>
+{
+    use self::ParenthesisParseError::*;
+
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(EmptyInput);
+    }
+
+    let first_open_brace = input.find('(').ok_or(NoOpeningBraceFound)?;
+    let found_stopword = &input[..first_open_brace];
+
+    // CSS does not allow for space between the ( and the stopword, so no .trim() here
+    let mut validated_stopword = None;
+    for stopword in stopwords {
+        if found_stopword == *stopword {
+            validated_stopword = Some(stopword);
+            break;
+        }
+    }
+
+    let validated_stopword = validated_stopword.ok_or(StopWordNotFound(found_stopword))?;
+    let last_closing_brace = input.rfind(')').ok_or(NoClosingBraceFound)?;
+
+    Ok((validated_stopword, &input[(first_open_brace + 1)..last_closing_brace]))
+}
+
+#[derive(Clone, PartialEq)]
+pub enum CssGridParseError<'a> {
+    EmptyString,
+    InvalidTrackSize(&'a str),
+    InvalidRepeat(&'a str),
+    InvalidLine(&'a str),
+    UnquotedAreaRow(&'a str),
+    InconsistentAreaRow(&'a str),
+    NonRectangularArea(&'a str),
+    TooManyValues(&'a str),
+    FloatValueParseErr(ParseFloatError, &'a str),
+    PixelParseError(PixelParseError<'a>),
+}
+
+impl_debug_as_display!(CssGridParseError<'a>);
+impl_display!{ CssGridParseError<'a>, {
+    EmptyString => format!("Missing grid value"),
+    InvalidTrackSize(e) => format!("Invalid track size: \"{}\", expected a length, \"auto\", \"fr\" or \"minmax()\"", e),
+    InvalidRepeat(e) => format!("Invalid repeat(): \"{}\", expected \"repeat(count, tracks)\"", e),
+    InvalidLine(e) => format!("Invalid grid line: \"{}\"", e),
+    UnquotedAreaRow(e) => format!("Expected quoted row of area names, got: \"{}\"", e),
+    InconsistentAreaRow(e) => format!("Row \"{}\" has a different number of columns than the previous rows", e),
+    NonRectangularArea(e) => format!("Area \"{}\" is not a rectangle", e),
+    TooManyValues(e) => format!("Too many values: \"{}\"", e),
+    FloatValueParseErr(e, orig_str) => format!("Could not parse \"{}\" as floating-point value: \"{}\"", orig_str, e),
+    PixelParseError(e) => format!("{}", e),
+}}
+
+impl_from!(PixelParseError<'a>, CssGridParseError::PixelParseError);
+
+/// Parses a `grid-template-columns` attribute, i.e. `200px repeat(2, minmax(100px, 1fr))`
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_layout_grid_template_columns;
+/// # use azul_css::{LayoutGridTemplateColumns, GridTrackSize, GridTrackBreadth, PixelValue, FloatValue};
+/// assert_eq!(
+///     parse_layout_grid_template_columns("200px repeat(2, 1fr)"),
+///     Ok(LayoutGridTemplateColumns(vec![
+///         GridTrackSize::Breadth(GridTrackBreadth::Length(PixelValue::px(200.0))),
+///         GridTrackSize::Breadth(GridTrackBreadth::Fraction(FloatValue::new(1.0))),
+///         GridTrackSize::Breadth(GridTrackBreadth::Fraction(FloatValue::new(1.0))),
+///     ]))
+/// );
+/// ```
+pub fn parse_layout_grid_template_columns<'a>(input: &'a str)
+-> Result<LayoutGridTemplateColumns, CssGridParseError<'a>>
+{
+    parse_grid_track_list(input).map(LayoutGridTemplateColumns)
+}
+
+/// Parses a `grid-template-rows` attribute, i.e. `auto 1fr auto`
+pub fn parse_layout_grid_template_rows<'a>(input: &'a str)
+-> Result<LayoutGridTemplateRows, CssGridParseError<'a>>
+{
+    parse_grid_track_list(input).map(LayoutGridTemplateRows)
+}
+
+/// Parses a list of track sizes, `repeat(count, tracks)` is expanded into `count` copies of the tracks
+fn parse_grid_track_list<'a>(input: &'a str)
+-> Result<Vec<GridTrackSize>, CssGridParseError<'a>>
+{
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(CssGridParseError::EmptyString);
+    } else if input == "none" {
+        return Ok(Vec::new());
+    }
+
+    let mut tracks = Vec::new();
+
+    for value in split_whitespace_outside_braces(input) {
+        if value.starts_with("repeat(") {
+            let (_, arguments) = parse_parentheses(value, &["repeat"]).map_err(|_| CssGridParseError::InvalidRepeat(value))?;
+            let arguments = split_commas_outside_braces(arguments);
+            if arguments.len() != 2 {
+                return Err(CssGridParseError::InvalidRepeat(value));
+            }
+            let count = arguments[0].parse::<usize>().ok().filter(|count| *count > 0).ok_or(CssGridParseError::InvalidRepeat(value))?;
+            let repeated_tracks = split_whitespace_outside_braces(arguments[1])
+                .into_iter()
+                .map(parse_grid_track_size)
+                .collect::<Result<Vec<_>, _>>()?;
+            if repeated_tracks.is_empty() {
+                return Err(CssGridParseError::InvalidRepeat(value));
+            }
+            for _ in 0..count {
+                tracks.extend(repeated_tracks.iter().cloned());
+            }
+        } else {
+            tracks.push(parse_grid_track_size(value)?);
+        }
+    }
+
+    Ok(tracks)
+}
+
+/// Parses a single track size, i.e. `200px`, `1fr`, `auto` or `minmax(100px, 1fr)`
+pub fn parse_grid_track_size<'a>(input: &'a str)
+-> Result<GridTrackSize, CssGridParseError<'a>>
+{
+    let input = input.trim();
+
+    if !input.starts_with("minmax(") {
+        return Ok(GridTrackSize::Breadth(parse_grid_track_breadth(input)?));
+    }
+
+    let (_, arguments) = parse_parentheses(input, &["minmax"]).map_err(|_| CssGridParseError::InvalidTrackSize(input))?;
+    let arguments = split_commas_outside_braces(arguments);
+    if arguments.len() != 2 {
+        return Err(CssGridParseError::InvalidTrackSize(input));
+    }
+
+    let min = parse_grid_track_breadth(arguments[0])?;
+    let max = parse_grid_track_breadth(arguments[1])?;
+
+    // The minimum of a track can't be flexible
+    if let GridTrackBreadth::Fraction(_) = min {
+        return Err(CssGridParseError::InvalidTrackSize(input));
+    }
+
+    Ok(GridTrackSize::MinMax(min, max))
+}
+
+fn parse_grid_track_breadth<'a>(input: &'a str)
+-> Result<GridTrackBreadth, CssGridParseError<'a>>
+{
+    let input = input.trim();
+
+    if input == "auto" {
+        Ok(GridTrackBreadth::Auto)
+    } else if input.ends_with("fr") {
+        let number = &input[..input.len() - 2];
+        let fraction = parse_float_value(number).map_err(|e| CssGridParseError::FloatValueParseErr(e, input))?;
+        if fraction.get() < 0.0 {
+            return Err(CssGridParseError::InvalidTrackSize(input));
+        }
+        Ok(GridTrackBreadth::Fraction(fraction))
+    } else {
+        Ok(GridTrackBreadth::Length(parse_pixel_value(input)?))
+    }
+}
+
+/// Parses a `grid-template-areas` attribute: one quoted string per row, with one area name
+/// per column. Cells that don't belong to any area are marked with a `.`
+///
+/// # Example
+///
+/// ```rust
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_layout_grid_template_areas;
+/// # use azul_css::GridArea;
+/// let areas = parse_layout_grid_template_areas("\"header header\" \"sidebar main\"").unwrap();
+/// assert_eq!(areas.get_area("header"), Some(GridArea { row_start: 0, row_end: 1, column_start: 0, column_end: 2 }));
+/// assert_eq!(areas.get_area("main"), Some(GridArea { row_start: 1, row_end: 2, column_start: 1, column_end: 2 }));
+/// assert!(parse_layout_grid_template_areas("\"a b\" \"b a\"").is_err());
+/// ```
+pub fn parse_layout_grid_template_areas<'a>(input: &'a str)
+-> Result<LayoutGridTemplateAreas, CssGridParseError<'a>>
+{
+    use self::CssGridParseError::*;
+
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(EmptyString);
+    } else if input == "none" {
+        return Ok(LayoutGridTemplateAreas::default());
+    }
+
+    let mut rows = Vec::<Vec<&'a str>>::new();
+    let mut remaining = input;
+
+    while !remaining.is_empty() {
+        let quote = match remaining.chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => quote,
+            _ => return Err(UnquotedAreaRow(remaining)),
+        };
+        let row_end = remaining[1..].find(quote).ok_or(UnquotedAreaRow(remaining))? + 1;
+        let row = &remaining[1..row_end];
+        let cells = row.split_whitespace().collect::<Vec<&'a str>>();
+
+        if cells.is_empty() || rows.first().map(|first_row| first_row.len() != cells.len()).unwrap_or(false) {
+            return Err(InconsistentAreaRow(row));
+        }
+
+        rows.push(cells);
+        remaining = remaining[(row_end + 1)..].trim_start();
+    }
+
+    let mut areas = Vec::<(String, GridArea)>::new();
+
+    for (row_idx, row) in rows.iter().enumerate() {
+        for (column_idx, name) in row.iter().enumerate() {
+            // A sequence of dots is an unnamed cell
+            if name.chars().all(|c| c == '.') {
+                continue;
+            }
+            match areas.iter_mut().find(|(area_name, _)| area_name == name) {
+                Some((_, area)) => {
+                    area.row_end = area.row_end.max(row_idx + 1);
+                    area.column_start = area.column_start.min(column_idx);
+                    area.column_end = area.column_end.max(column_idx + 1);
+                },
+                None => areas.push((name.to_string(), GridArea {
+                    row_start: row_idx,
+                    row_end: row_idx + 1,
+                    column_start: column_idx,
+                    column_end: column_idx + 1,
+                })),
+            }
+        }
+    }
+
+    // Every cell inside the bounds of an area has to belong to that area
+    for (name, area) in &areas {
+        for row in &rows[area.row_start..area.row_end] {
+            if row[area.column_start..area.column_end].iter().any(|cell| cell != name) {
+                let name = row.iter().find(|cell| *cell == name).cloned().unwrap_or(input);
+                return Err(NonRectangularArea(name));
+            }
+        }
+    }
+
+    Ok(LayoutGridTemplateAreas {
+        areas,
+        row_count: rows.len(),
+        column_count: rows.first().map(|row| row.len()).unwrap_or(0),
+    })
+}
 
-impl<'a> From<UnclosedQuotesError<'a>> for CssStyleFontFamilyParseError<'a> {
-    fn from(err: UnclosedQuotesError<'a>) -> Self {
-        CssStyleFontFamilyParseError::UnclosedQuotes(err.0)
+/// Parses a `grid-gap` attribute: `10px` sets the gap between the rows and the columns,
+/// `10px 20px` sets the gap between the rows to `10px` and between the columns to `20px`
+pub fn parse_layout_grid_gap<'a>(input: &'a str)
+-> Result<LayoutGridGap, CssGridParseError<'a>>
+{
+    let values = split_whitespace_outside_braces(input);
+    match values.as_slice() {
+        [] => Err(CssGridParseError::EmptyString),
+        [gap] => {
+            let gap = parse_pixel_value(gap)?;
+            Ok(LayoutGridGap { row: gap, column: gap })
+        },
+        [row, column] => Ok(LayoutGridGap { row: parse_pixel_value(row)?, column: parse_pixel_value(column)? }),
+        _ => Err(CssGridParseError::TooManyValues(input)),
     }
 }
 
-/// Parses a `StyleFontFamily` declaration from a `&str`
-///
-/// # Example
-///
-/// ```rust
-/// # extern crate azul_css;
-/// # extern crate azul_css_parser;
-/// # use azul_css_parser::parse_style_font_family;
-/// # use azul_css::{StyleFontFamily, FontId};
-/// let input = "\"Helvetica\", 'Arial', Times New Roman";
-/// let fonts = vec![
-///     FontId("Helvetica".into()),
-///     FontId("Arial".into()),
-///     FontId("Times New Roman".into())
-/// ];
-///
-/// assert_eq!(parse_style_font_family(input), Ok(StyleFontFamily { fonts }));
-/// ```
-pub fn parse_style_font_family<'a>(input: &'a str) -> Result<StyleFontFamily, CssStyleFontFamilyParseError<'a>> {
-    let multiple_fonts = input.split(',');
-    let mut fonts = Vec::with_capacity(1);
+/// Parses the start or end of a grid placement, i.e. `auto`, `2`, `-1`, `span 2` or `header`
+pub fn parse_grid_line<'a>(input: &'a str)
+-> Result<GridLine, CssGridParseError<'a>>
+{
+    let input = input.trim();
+    let values = input.split_whitespace().collect::<Vec<&'a str>>();
+
+    match values.as_slice() {
+        [] => Err(CssGridParseError::EmptyString),
+        ["auto"] => Ok(GridLine::Auto),
+        ["span"] => Err(CssGridParseError::InvalidLine(input)),
+        ["span", count] => count.parse::<usize>().ok()
+            .filter(|count| *count > 0)
+            .map(GridLine::Span)
+            .ok_or(CssGridParseError::InvalidLine(input)),
+        [value] => {
+            if let Ok(line) = value.parse::<isize>() {
+                if line == 0 {
+                    Err(CssGridParseError::InvalidLine(input))
+                } else {
+                    Ok(GridLine::Line(line))
+                }
+            } else if value.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') && !value.starts_with(|c: char| c.is_numeric()) {
+                Ok(GridLine::Area(value.to_string()))
+            } else {
+                Err(CssGridParseError::InvalidLine(input))
+            }
+        },
+        _ => Err(CssGridParseError::InvalidLine(input)),
+    }
+}
 
-    for font in multiple_fonts {
-        let font = font.trim();
-        let font = font.trim_matches('\'');
-        let font = font.trim_matches('\"');
-        let font = font.trim();
-        fonts.push(FontId(font.into()));
+/// If only the start of a placement is given, the end refers to the same area, otherwise it is `auto`
+fn get_default_grid_line_end(start: &GridLine) -> GridLine {
+    match start {
+        GridLine::Area(name) => GridLine::Area(name.clone()),
+        _ => GridLine::Auto,
     }
+}
 
-    Ok(StyleFontFamily {
-        fonts: fonts,
-    })
+/// Parses `start / end` or `start` into the start and end line of a grid placement
+fn parse_grid_placement<'a>(input: &'a str)
+-> Result<(GridLine, GridLine), CssGridParseError<'a>>
+{
+    let values = input.split('/').collect::<Vec<&'a str>>();
+    match values.as_slice() {
+        [start] => {
+            let start = parse_grid_line(start)?;
+            let end = get_default_grid_line_end(&start);
+            Ok((start, end))
+        },
+        [start, end] => Ok((parse_grid_line(start)?, parse_grid_line(end)?)),
+        _ => Err(CssGridParseError::TooManyValues(input)),
+    }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
-pub enum ParenthesisParseError<'a> {
-    UnclosedBraces,
-    NoOpeningBraceFound,
-    NoClosingBraceFound,
-    StopWordNotFound(&'a str),
-    EmptyInput,
+/// Parses a `grid-row` attribute, i.e. `1 / 3`, `2 / span 2` or `header`
+pub fn parse_layout_grid_row<'a>(input: &'a str)
+-> Result<LayoutGridRow, CssGridParseError<'a>>
+{
+    let (start, end) = parse_grid_placement(input)?;
+    Ok(LayoutGridRow { start, end })
 }
 
-impl_display!{ ParenthesisParseError<'a>, {
-    UnclosedBraces => format!("Unclosed parenthesis"),
-    NoOpeningBraceFound => format!("Expected value in parenthesis (missing \"(\")"),
-    NoClosingBraceFound => format!("Missing closing parenthesis (missing \")\")"),
-    StopWordNotFound(e) => format!("Stopword not found, found: \"{}\"", e),
-    EmptyInput => format!("Empty parenthesis"),
-}}
+/// Parses a `grid-column` attribute, i.e. `1 / -1`, `span 2` or `sidebar`
+pub fn parse_layout_grid_column<'a>(input: &'a str)
+-> Result<LayoutGridColumn, CssGridParseError<'a>>
+{
+    let (start, end) = parse_grid_placement(input)?;
+    Ok(LayoutGridColumn { start, end })
+}
 
-/// Checks wheter a given input is enclosed in parentheses, prefixed
-/// by a certain number of stopwords.
+/// Parses a `grid-area` attribute, either the name of an area or
+/// `row-start / column-start / row-end / column-end` (the ends can be omitted)
 ///
-/// On success, returns what the stopword was + the string inside the braces
-/// on failure returns None.
+/// # Example
 ///
 /// ```rust
-/// # use azul_css_parser::parse_parentheses;
-/// # use azul_css_parser::ParenthesisParseError::*;
-/// // Search for the nearest "abc()" brace
-/// assert_eq!(parse_parentheses("abc(def(g))", &["abc"]), Ok(("abc", "def(g)")));
-/// assert_eq!(parse_parentheses("abc(def(g))", &["def"]), Err(StopWordNotFound("abc")));
-/// assert_eq!(parse_parentheses("def(ghi(j))", &["def"]), Ok(("def", "ghi(j)")));
-/// assert_eq!(parse_parentheses("abc(def(g))", &["abc", "def"]), Ok(("abc", "def(g)")));
+/// # extern crate azul_css;
+/// # extern crate azul_css_parser;
+/// # use azul_css_parser::parse_layout_grid_area;
+/// # use azul_css::{LayoutGridArea, LayoutGridRow, LayoutGridColumn, GridLine};
+/// assert_eq!(
+///     parse_layout_grid_area("1 / 2 / span 2"),
+///     Ok(LayoutGridArea {
+///         row: LayoutGridRow { start: GridLine::Line(1), end: GridLine::Span(2) },
+///         column: LayoutGridColumn { start: GridLine::Line(2), end: GridLine::Auto },
+///     })
+/// );
 /// ```
-pub fn parse_parentheses<'a>(
-    input: &'a str,
-    stopwords: &[&'static str])
--> Result<(&'static str, &'a str), ParenthesisParseError<'a>>
+pub fn parse_layout_grid_area<'a>(input: &'a str)
+-> Result<LayoutGridArea, CssGridParseError<'a>>
 {
-    use self::ParenthesisParseError::*;
-
-    let input = input.trim();
-    if input.is_empty() {
-        return Err(EmptyInput);
-    }
-
-    let first_open_brace = input.find('(').ok_or(NoOpeningBraceFound)?;
-    let found_stopword = &input[..first_open_brace];
+    let lines = input.split('/').map(parse_grid_line).collect::<Result<Vec<GridLine>, _>>()?;
 
-    // CSS does not allow for space between the ( and the stopword, so no .trim() here
-    let mut validated_stopword = None;
-    for stopword in stopwords {
-        if found_stopword == *stopword {
-            validated_stopword = Some(stopword);
-            break;
-        }
+    if lines.len() > 4 {
+        return Err(CssGridParseError::TooManyValues(input));
     }
 
-    let validated_stopword = validated_stopword.ok_or(StopWordNotFound(found_stopword))?;
-    let last_closing_brace = input.rfind(')').ok_or(NoClosingBraceFound)?;
+    let row_start = lines[0].clone();
+    let column_start = lines.get(1).cloned().unwrap_or_else(|| get_default_grid_line_end(&row_start));
+    let row_end = lines.get(2).cloned().unwrap_or_else(|| get_default_grid_line_end(&row_start));
+    let column_end = lines.get(3).cloned().unwrap_or_else(|| get_default_grid_line_end(&column_start));
 
-    Ok((validated_stopword, &input[(first_open_brace + 1)..last_closing_brace]))
+    Ok(LayoutGridArea {
+        row: LayoutGridRow { start: row_start, end: row_end },
+        column: LayoutGridColumn { start: column_start, end: column_end },
+    })
 }
 
 multi_type_parser!(parse_style_cursor, StyleCursor,
@@ -2106,6 +4306,16 @@ multi_type_parser!(parse_style_background_repeat, StyleBackgroundRepeat,
                     ["repeat-x", RepeatX],
                     ["repeat-y", RepeatY]);
 
+multi_type_parser!(parse_style_background_origin, StyleBackgroundOrigin,
+                    ["border-box", BorderBox],
+                    ["padding-box", PaddingBox],
+                    ["content-box", ContentBox]);
+
+multi_type_parser!(parse_style_background_clip, StyleBackgroundClip,
+                    ["border-box", BorderBox],
+                    ["padding-box", PaddingBox],
+                    ["content-box", ContentBox]);
+
 multi_type_parser!(parse_layout_direction, LayoutDirection,
                     ["row", Row],
                     ["row-reverse", RowReverse],
@@ -2114,6 +4324,7 @@ multi_type_parser!(parse_layout_direction, LayoutDirection,
 
 multi_type_parser!(parse_layout_wrap, LayoutWrap,
                     ["wrap", Wrap],
+                    ["wrap-reverse", WrapReverse],
                     ["nowrap", NoWrap]);
 
 multi_type_parser!(parse_layout_justify_content, LayoutJustifyContent,
@@ -2129,6 +4340,13 @@ multi_type_parser!(parse_layout_align_items, LayoutAlignItems,
                     ["stretch", Stretch],
                     ["center", Center]);
 
+multi_type_parser!(parse_layout_align_self, LayoutAlignSelf,
+                    ["auto", Auto],
+                    ["flex-start", Start],
+                    ["flex-end", End],
+                    ["stretch", Stretch],
+                    ["center", Center]);
+
 multi_type_parser!(parse_layout_align_content, LayoutAlignContent,
                     ["flex-start", Start],
                     ["flex-end", End],
@@ -2144,7 +4362,24 @@ multi_type_parser!(parse_shape, Shape,
 multi_type_parser!(parse_layout_position, LayoutPosition,
                     ["static", Static],
                     ["absolute", Absolute],
-                    ["relative", Relative]);
+                    ["relative", Relative],
+                    ["fixed", Fixed],
+                    ["sticky", Sticky]);
+
+multi_type_parser!(parse_layout_float, LayoutFloat,
+                    ["left", Left],
+                    ["right", Right],
+                    ["none", None]);
+
+multi_type_parser!(parse_layout_display, LayoutDisplay,
+                    ["flex", Flex],
+                    ["grid", Grid],
+                    ["inline", Inline],
+                    ["none", None]);
+
+multi_type_parser!(parse_style_visibility, StyleVisibility,
+                    ["visible", Visible],
+                    ["hidden", Hidden]);
 
 multi_type_parser!(parse_layout_text_overflow, Overflow,
                     ["auto", Auto],
@@ -2265,6 +4500,17 @@ mod css_tests {
         })));
     }
 
+    #[test]
+    fn test_parse_box_shadow_11() {
+        assert_eq!(parse_css_box_shadow("calc(1em + 2px) 10px calc(2px * 2) rgba(0, 0, 0, 0.5) inset"), Ok(Some(BoxShadowPreDisplayItem {
+            offset: [PixelValue::calc(&[(SizeMetric::Em, 1.0), (SizeMetric::Px, 2.0)]), PixelValue::px(10.0)],
+            color: ColorU { r: 0, g: 0, b: 0, a: 128 },
+            blur_radius: PixelValue::px(4.0),
+            spread_radius: PixelValue::px(0.0),
+            clip_mode: BoxShadowClipMode::Inset,
+        })));
+    }
+
     #[test]
     fn test_parse_css_border_1() {
         assert_eq!(
@@ -2301,6 +4547,72 @@ mod css_tests {
         );
     }
 
+    #[test]
+    fn test_parse_style_border_style() {
+        assert_eq!(parse_style_border_style("dashed"), Ok(StyleBorderStyle::all(BorderStyle::Dashed)));
+        assert_eq!(parse_style_border_style("dotted double"), Ok(StyleBorderStyle {
+            top: Some(BorderStyle::Dotted),
+            right: Some(BorderStyle::Double),
+            bottom: Some(BorderStyle::Dotted),
+            left: Some(BorderStyle::Double),
+        }));
+        assert_eq!(parse_style_border_style("groove ridge inset outset"), Ok(StyleBorderStyle {
+            top: Some(BorderStyle::Groove),
+            right: Some(BorderStyle::Ridge),
+            bottom: Some(BorderStyle::Inset),
+            left: Some(BorderStyle::Outset),
+        }));
+        assert!(parse_style_border_style("solid solid solid solid solid").is_err());
+        assert!(parse_style_border_style("wavy").is_err());
+    }
+
+    #[test]
+    fn test_parse_border_side_styles_are_merged() {
+        let mut border_style = parse_key_value_pair(CssPropertyType::BorderTopStyle, "dashed").unwrap();
+        border_style.merge(&parse_key_value_pair(CssPropertyType::BorderLeftStyle, "dotted").unwrap());
+        assert_eq!(border_style, CssProperty::BorderStyle(StyleBorderStyle {
+            top: Some(BorderStyle::Dashed),
+            left: Some(BorderStyle::Dotted),
+            .. Default::default()
+        }));
+    }
+
+    #[test]
+    fn test_parse_style_border_image() {
+        assert_eq!(parse_style_border_image("none"), Ok(StyleBorderImage::default()));
+        assert_eq!(parse_style_border_image("\"frame\" 10% 20 / 5px/1px 2px repeat space"), Ok(StyleBorderImage {
+            source: Some(CssImageId(String::from("frame"))),
+            slice: BorderImageOffsets {
+                top: PixelValue::percent(10.0),
+                right: PixelValue::px(20.0),
+                bottom: PixelValue::percent(10.0),
+                left: PixelValue::px(20.0),
+            },
+            fill: false,
+            width: Some(BorderImageOffsets::all(PixelValue::px(5.0))),
+            outset: BorderImageOffsets {
+                top: PixelValue::px(1.0),
+                right: PixelValue::px(2.0),
+                bottom: PixelValue::px(1.0),
+                left: PixelValue::px(2.0),
+            },
+            repeat_horizontal: BorderImageRepeat::Repeat,
+            repeat_vertical: BorderImageRepeat::Space,
+        }));
+        assert_eq!(parse_style_border_image("image(\"my frame\") 5 fill // 3px"), Ok(StyleBorderImage {
+            source: Some(CssImageId(String::from("my frame"))),
+            slice: BorderImageOffsets::all(PixelValue::px(5.0)),
+            fill: true,
+            outset: BorderImageOffsets::all(PixelValue::px(3.0)),
+            .. Default::default()
+        }));
+        assert!(parse_style_border_image("").is_err());
+        assert!(parse_style_border_image("frame 10").is_err());
+        assert!(parse_style_border_image("\"frame\" / 5px").is_err());
+        assert!(parse_style_border_image("\"frame\" 1 2 3 4 5").is_err());
+        assert!(parse_style_border_image("\"frame\" 10 round round round").is_err());
+    }
+
     #[test]
     fn test_parse_linear_gradient_1() {
         assert_eq!(parse_style_background("linear-gradient(red, yellow)"),
@@ -2774,6 +5086,39 @@ mod css_tests {
         assert_eq!(parse_pixel_value("aslkfdjasdflk"), Err(PixelParseError::NoValueGiven("aslkfdjasdflk")));
     }
 
+    #[test]
+    fn test_parse_calc_expression() {
+        assert_eq!(parse_pixel_value("calc(100% - 240px)"), Ok(PixelValue::calc(&[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -240.0)])));
+        assert_eq!(parse_pixel_value("calc((100% - 20px) / 2)"), Ok(PixelValue::calc(&[(SizeMetric::Percent, 50.0), (SizeMetric::Px, -10.0)])));
+        assert_eq!(parse_pixel_value("calc(2 * calc(1em + 2px))"), Ok(PixelValue::calc(&[(SizeMetric::Em, 2.0), (SizeMetric::Px, 4.0)])));
+        assert_eq!(parse_pixel_value("calc(10px)"), Ok(PixelValue::px(10.0)));
+        assert_eq!(parse_calc_expression("calc(100%-240px)"), Err(CalcParseError::InvalidOperand("100%-240px")));
+        assert_eq!(parse_calc_expression("calc(10px * 2px)"), Err(CalcParseError::InvalidOperation("*", "calc(10px * 2px)")));
+        assert_eq!(parse_calc_expression("calc(10px / 0)"), Err(CalcParseError::DivisionByZero("calc(10px / 0)")));
+        assert_eq!(parse_calc_expression("calc(1 + 2)"), Err(CalcParseError::NotALength("calc(1 + 2)")));
+        assert_eq!(parse_calc_expression("calc(10px + (2px"), Err(CalcParseError::UnclosedBraces("calc(10px + (2px")));
+    }
+
+    #[test]
+    fn test_parse_layout_padding_calc() {
+        assert_eq!(parse_layout_padding("calc(1em + 2px) 10px"), Ok(LayoutPadding {
+            top: Some(PixelValue::calc(&[(SizeMetric::Em, 1.0), (SizeMetric::Px, 2.0)])),
+            bottom: Some(PixelValue::calc(&[(SizeMetric::Em, 1.0), (SizeMetric::Px, 2.0)])),
+            left: Some(PixelValue::px(10.0)),
+            right: Some(PixelValue::px(10.0)),
+        }));
+    }
+
+    #[test]
+    fn test_parse_pixel_value_5() {
+        assert_eq!(parse_pixel_value("25%"), Ok(PixelValue::percent(25.0)));
+        assert_eq!(parse_pixel_value("2rem"), Ok(PixelValue::rem(2.0)));
+        assert_eq!(parse_pixel_value("100vw"), Ok(PixelValue::vw(100.0)));
+        assert_eq!(parse_pixel_value("50vh"), Ok(PixelValue::vh(50.0)));
+        assert_eq!(parse_pixel_value("10vmin"), Ok(PixelValue::vmin(10.0)));
+        assert_eq!(parse_pixel_value("10vmax"), Ok(PixelValue::vmax(10.0)));
+    }
+
     #[test]
     fn test_parse_style_border_radius_1() {
         assert_eq!(parse_style_border_radius("15px"), Ok(StyleBorderRadius(
@@ -2837,6 +5182,54 @@ mod css_tests {
         )));
     }
 
+    #[test]
+    fn test_parse_multiple_backgrounds() {
+        assert_eq!(
+            parse_key_value_pair(CssPropertyType::Background, "image(\"Cat 01\"), linear-gradient(red, yellow), rgba(0, 0, 0, 0.5)"),
+            Ok(CssProperty::Background(StyleBackgrounds(vec![
+                StyleBackground::Image(CssImageId(String::from("Cat 01"))),
+                StyleBackground::LinearGradient(LinearGradient {
+                    direction: Direction::FromTo(DirectionCorner::Top, DirectionCorner::Bottom),
+                    extend_mode: ExtendMode::Clamp,
+                    stops: vec![
+                        GradientStopPre { offset: Some(PercentageValue::new(0.0)), color: ColorU { r: 255, g: 0, b: 0, a: 255 } },
+                        GradientStopPre { offset: Some(PercentageValue::new(100.0)), color: ColorU { r: 255, g: 255, b: 0, a: 255 } },
+                    ],
+                }),
+                StyleBackground::Color(ColorU { r: 0, g: 0, b: 0, a: 128 }),
+            ])))
+        );
+        assert_eq!(
+            parse_key_value_pair(CssPropertyType::BackgroundRepeat, "no-repeat, repeat-x"),
+            Ok(CssProperty::BackgroundRepeat(StyleBackgroundRepeats(vec![StyleBackgroundRepeat::NoRepeat, StyleBackgroundRepeat::RepeatX])))
+        );
+        assert_eq!(
+            parse_key_value_pair(CssPropertyType::BackgroundClip, "content-box"),
+            Ok(CssProperty::BackgroundClip(StyleBackgroundClips(vec![StyleBackgroundClip::ContentBox])))
+        );
+        assert!(parse_key_value_pair(CssPropertyType::Background, "red,").is_err());
+    }
+
+    #[test]
+    fn test_parse_style_background_position() {
+        assert_eq!(parse_style_background_position("center"), Ok(StyleBackgroundPosition {
+            horizontal: PixelValue::percent(50.0),
+            vertical: PixelValue::percent(50.0),
+        }));
+        assert_eq!(parse_style_background_position("top"), parse_style_background_position("center top"));
+        assert_eq!(parse_style_background_position("10px 20%"), Ok(StyleBackgroundPosition {
+            horizontal: PixelValue::px(10.0),
+            vertical: PixelValue::percent(20.0),
+        }));
+        assert_eq!(parse_style_background_position("bottom 20px right"), Ok(StyleBackgroundPosition {
+            horizontal: PixelValue::percent(100.0),
+            vertical: PixelValue::calc(&[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -20.0)]),
+        }));
+        assert!(parse_style_background_position("left right").is_err());
+        assert!(parse_style_background_position("top 10px").is_err());
+        assert!(parse_style_background_position("center 10px top").is_err());
+    }
+
     #[test]
     fn test_parse_padding_1() {
         assert_eq!(parse_layout_padding("10px"), Ok(LayoutPadding {
@@ -2876,4 +5269,276 @@ mod css_tests {
             left: Some(PixelValue::px(100.0)),
         }));
     }
+
+    #[test]
+    fn test_parse_style_transform_1() {
+        assert_eq!(parse_style_transform("scale(2) rotate(0.25turn)"), Ok(StyleTransform(vec![
+            TransformFunction::Scale(FloatValue::new(2.0), FloatValue::new(2.0)),
+            TransformFunction::Rotate(AngleValue::from_metric(AngleMetric::Turn, 0.25)),
+        ])));
+    }
+
+    #[test]
+    fn test_parse_style_transform_2() {
+        assert_eq!(parse_style_transform("matrix(1, 0, 0, 1, 10, -5)"), Ok(StyleTransform(vec![
+            TransformFunction::Matrix([
+                FloatValue::new(1.0), FloatValue::new(0.0), FloatValue::new(0.0),
+                FloatValue::new(1.0), FloatValue::new(10.0), FloatValue::new(-5.0),
+            ]),
+        ])));
+    }
+
+    #[test]
+    fn test_parse_style_transform_3() {
+        assert_eq!(
+            parse_style_transform("rotate(45deg, 10deg)"),
+            Err(CssStyleTransformParseError::WrongNumberOfArguments("rotate(45deg, 10deg)"))
+        );
+        assert_eq!(
+            parse_style_transform("rotate3d(1, 1, 1, 45deg)"),
+            Err(CssStyleTransformParseError::InvalidFunction("rotate3d(1, 1, 1, 45deg)"))
+        );
+    }
+
+    #[test]
+    fn test_parse_style_transform_origin_1() {
+        assert_eq!(parse_style_transform_origin("top left"), Ok(StyleTransformOrigin {
+            x: TransformOriginOffset::Percentage(PercentageValue::new(0.0)),
+            y: TransformOriginOffset::Percentage(PercentageValue::new(0.0)),
+        }));
+        assert_eq!(parse_style_transform_origin("center"), Ok(StyleTransformOrigin::default()));
+        assert!(parse_style_transform_origin("left right").is_err());
+        assert_eq!(parse_style_transform_origin("calc(50% - 10px) bottom"), Ok(StyleTransformOrigin {
+            x: TransformOriginOffset::Pixels(PixelValue::calc(&[(SizeMetric::Percent, 50.0), (SizeMetric::Px, -10.0)])),
+            y: TransformOriginOffset::Percentage(PercentageValue::new(1.0)),
+        }));
+    }
+
+    #[test]
+    fn test_parse_style_opacity_1() {
+        assert_eq!(parse_style_opacity("0.5"), Ok(StyleOpacity(FloatValue::new(0.5))));
+        assert_eq!(parse_style_opacity("25%"), Ok(StyleOpacity(FloatValue::new(0.25))));
+    }
+
+    #[test]
+    fn test_parse_layout_z_index() {
+        assert_eq!(parse_layout_z_index("auto"), Ok(LayoutZIndex::Auto));
+        assert_eq!(parse_layout_z_index("-5"), Ok(LayoutZIndex::Integer(-5)));
+        assert!(parse_layout_z_index("1.5").is_err());
+    }
+
+    #[test]
+    fn test_parse_layout_flex() {
+        let flex = |grow: f32, shrink: f32, basis| LayoutFlex {
+            grow: LayoutFlexGrow(FloatValue::new(grow)),
+            shrink: LayoutFlexShrink(FloatValue::new(shrink)),
+            basis,
+        };
+        assert_eq!(parse_layout_flex("none"), Ok(flex(0.0, 0.0, LayoutFlexBasis::Auto)));
+        assert_eq!(parse_layout_flex("2"), Ok(flex(2.0, 1.0, LayoutFlexBasis::Exact(PixelValue::px(0.0)))));
+        assert_eq!(parse_layout_flex("200px"), Ok(flex(1.0, 1.0, LayoutFlexBasis::Exact(PixelValue::px(200.0)))));
+        assert_eq!(parse_layout_flex("2 3 auto"), Ok(flex(2.0, 3.0, LayoutFlexBasis::Auto)));
+        assert_eq!(parse_layout_flex("1 1 0"), Ok(flex(1.0, 1.0, LayoutFlexBasis::Exact(PixelValue::px(0.0)))));
+        assert_eq!(parse_layout_flex("1 1 calc(50% - 10px)"), Ok(flex(1.0, 1.0, LayoutFlexBasis::Exact(parse_pixel_value("calc(50% - 10px)").unwrap()))));
+        assert_eq!(parse_layout_flex("1 auto auto"), Err(FlexParseError::TooManyValues("1 auto auto")));
+        assert_eq!(parse_layout_flex(""), Err(FlexParseError::EmptyString));
+    }
+
+    #[test]
+    fn test_parse_flex_item_properties() {
+        assert_eq!(parse_key_value_pair(CssPropertyType::FlexBasis, "25%"), Ok(CssProperty::FlexBasis(LayoutFlexBasis::Exact(PixelValue::percent(25.0)))));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Order, "-1"), Ok(CssProperty::Order(LayoutOrder(-1))));
+        assert_eq!(parse_key_value_pair(CssPropertyType::AlignSelf, "flex-end"), Ok(CssProperty::AlignSelf(LayoutAlignSelf::End)));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Gap, "10px 20px"), Ok(CssProperty::Gap(LayoutGap { row: Some(PixelValue::px(10.0)), column: Some(PixelValue::px(20.0)) })));
+        assert_eq!(parse_key_value_pair(CssPropertyType::ColumnGap, "5px"), Ok(CssProperty::Gap(LayoutGap { row: None, column: Some(PixelValue::px(5.0)) })));
+        assert!(parse_layout_gap("1px 2px 3px").is_err());
+    }
+
+    #[test]
+    fn test_parse_intrinsic_sizes() {
+        assert_eq!(parse_key_value_pair(CssPropertyType::Width, "min-content"), Ok(CssProperty::Width(LayoutWidth::MinContent)));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Height, "max-content"), Ok(CssProperty::Height(LayoutHeight::MaxContent)));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Width, " fit-content "), Ok(CssProperty::Width(LayoutWidth::FitContent)));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Width, "20%"), Ok(CssProperty::Width(LayoutWidth::percent(20.0))));
+        // The keywords are only valid for the `width` and `height`
+        assert!(parse_key_value_pair(CssPropertyType::MaxWidth, "fit-content").is_err());
+        assert!(parse_key_value_pair(CssPropertyType::Left, "min-content").is_err());
+    }
+
+    #[test]
+    fn test_parse_layout_position() {
+        assert_eq!(parse_layout_position("fixed"), Ok(LayoutPosition::Fixed));
+        assert_eq!(parse_layout_position("sticky"), Ok(LayoutPosition::Sticky));
+        assert!(parse_layout_position("-webkit-sticky").is_err());
+    }
+
+    #[test]
+    fn test_parse_layout_float() {
+        assert_eq!(parse_key_value_pair(CssPropertyType::Float, "left"), Ok(CssProperty::Float(LayoutFloat::Left)));
+        assert_eq!(parse_layout_float("right"), Ok(LayoutFloat::Right));
+        assert_eq!(parse_layout_float("none"), Ok(LayoutFloat::None));
+        assert!(parse_layout_float("inline-start").is_err());
+    }
+
+    #[test]
+    fn test_parse_display_and_visibility() {
+        assert_eq!(parse_key_value_pair(CssPropertyType::Display, "none"), Ok(CssProperty::Display(LayoutDisplay::None)));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Display, "flex"), Ok(CssProperty::Display(LayoutDisplay::Flex)));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Display, "inline"), Ok(CssProperty::Display(LayoutDisplay::Inline)));
+        assert_eq!(parse_key_value_pair(CssPropertyType::Visibility, "hidden"), Ok(CssProperty::Visibility(StyleVisibility::Hidden)));
+        assert!(parse_layout_display("block").is_err());
+    }
+
+    #[test]
+    fn test_parse_grid_template() {
+        let px = |v| GridTrackSize::Breadth(GridTrackBreadth::Length(PixelValue::px(v)));
+        let fr = |v| GridTrackSize::Breadth(GridTrackBreadth::Fraction(FloatValue::new(v)));
+
+        assert_eq!(
+            parse_layout_grid_template_columns("100px repeat(2, 1fr 20%) minmax(50px, auto)"),
+            Ok(LayoutGridTemplateColumns(vec![
+                px(100.0),
+                fr(1.0),
+                GridTrackSize::Breadth(GridTrackBreadth::Length(PixelValue::percent(20.0))),
+                fr(1.0),
+                GridTrackSize::Breadth(GridTrackBreadth::Length(PixelValue::percent(20.0))),
+                GridTrackSize::MinMax(GridTrackBreadth::Length(PixelValue::px(50.0)), GridTrackBreadth::Auto),
+            ]))
+        );
+        assert_eq!(
+            parse_layout_grid_template_columns("repeat(2, minmax(100px, 1fr))"),
+            Ok(LayoutGridTemplateColumns(vec![
+                GridTrackSize::MinMax(GridTrackBreadth::Length(PixelValue::px(100.0)), GridTrackBreadth::Fraction(FloatValue::new(1.0))),
+                GridTrackSize::MinMax(GridTrackBreadth::Length(PixelValue::px(100.0)), GridTrackBreadth::Fraction(FloatValue::new(1.0))),
+            ]))
+        );
+        assert_eq!(parse_layout_grid_template_rows("none"), Ok(LayoutGridTemplateRows(Vec::new())));
+        assert_eq!(parse_layout_grid_template_rows("auto 2fr"), Ok(LayoutGridTemplateRows(vec![GridTrackSize::default(), fr(2.0)])));
+        assert!(parse_layout_grid_template_columns("repeat(0, 1fr)").is_err());
+        assert!(parse_layout_grid_template_columns("minmax(1fr, 100px)").is_err());
+        assert!(parse_layout_grid_template_columns("-1fr").is_err());
+    }
+
+    #[test]
+    fn test_parse_grid_template_areas() {
+        let areas = parse_layout_grid_template_areas("\"header header header\" \"nav main .\"").unwrap();
+        assert_eq!((areas.row_count, areas.column_count), (2, 3));
+        assert_eq!(areas.get_area("header"), Some(GridArea { row_start: 0, row_end: 1, column_start: 0, column_end: 3 }));
+        assert_eq!(areas.get_area("nav"), Some(GridArea { row_start: 1, row_end: 2, column_start: 0, column_end: 1 }));
+        assert_eq!(areas.get_area("."), None);
+
+        assert!(parse_layout_grid_template_areas("\"a b\" \"a\"").is_err());
+        assert!(parse_layout_grid_template_areas("\"a b a\"").is_err());
+        assert!(parse_layout_grid_template_areas("a b").is_err());
+    }
+
+    #[test]
+    fn test_parse_grid_placement() {
+        assert_eq!(parse_layout_grid_gap("10px"), Ok(LayoutGridGap { row: PixelValue::px(10.0), column: PixelValue::px(10.0) }));
+        assert_eq!(parse_layout_grid_gap("10px 5%"), Ok(LayoutGridGap { row: PixelValue::px(10.0), column: PixelValue::percent(5.0) }));
+
+        assert_eq!(parse_layout_grid_row("1 / 3"), Ok(LayoutGridRow { start: GridLine::Line(1), end: GridLine::Line(3) }));
+        assert_eq!(parse_layout_grid_column("span 2"), Ok(LayoutGridColumn { start: GridLine::Span(2), end: GridLine::Auto }));
+        assert_eq!(parse_layout_grid_column("2 / -1"), Ok(LayoutGridColumn { start: GridLine::Line(2), end: GridLine::Line(-1) }));
+        assert_eq!(
+            parse_layout_grid_row("header"),
+            Ok(LayoutGridRow { start: GridLine::Area("header".to_string()), end: GridLine::Area("header".to_string()) })
+        );
+        assert!(parse_layout_grid_row("0").is_err());
+        assert!(parse_layout_grid_row("span").is_err());
+        assert!(parse_layout_grid_row("1 / 2 / 3").is_err());
+
+        let main = GridLine::Area("main".to_string());
+        assert_eq!(
+            parse_key_value_pair(CssPropertyType::GridArea, "main"),
+            Ok(CssProperty::GridArea(LayoutGridArea {
+                row: LayoutGridRow { start: main.clone(), end: main.clone() },
+                column: LayoutGridColumn { start: main.clone(), end: main },
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_style_transition() {
+        let transition = parse_style_transition("background-color 300ms steps(4, start), opacity 1s cubic-bezier(0.1, 0.7, 1.0, 0.1)").unwrap();
+        assert_eq!(transition.0.len(), 2);
+        assert_eq!(transition.0[0].property, Some(CssPropertyType::BackgroundColor));
+        assert_eq!(transition.0[0].timing_function, AnimationTimingFunction::Steps(4, StepPosition::Start));
+        assert_eq!(transition.0[1].duration, Duration::from_secs(1));
+        // background-color is parsed into a CssProperty::Background
+        assert!(transition.get_transition(CssPropertyType::Background).is_some());
+        assert!(transition.get_transition(CssPropertyType::Width).is_none());
+
+        assert_eq!(parse_style_transition("width"), Err(CssAnimationParseError::EmptyValue));
+        assert_eq!(parse_style_transition("wdth 1s"), Err(CssAnimationParseError::UnknownProperty("wdth")));
+        assert_eq!(parse_style_transition("width 1s 2s 3s"), Err(CssAnimationParseError::UnexpectedValue("width 1s 2s 3s")));
+        assert_eq!(parse_style_transition("width -1s"), Err(CssAnimationParseError::NegativeTime("-1s")));
+        assert!(parse_animation_timing_function("cubic-bezier(2, 0, 1, 1)").is_err());
+    }
+
+    #[test]
+    fn test_parse_style_animation() {
+        let animation = parse_style_animation("1s slide-in ease-out 2.5 reverse forwards 100ms, spin 2s linear infinite").unwrap();
+        assert_eq!(animation.0.len(), 2);
+        assert_eq!(animation.0[0], Animation {
+            name: "slide-in".into(),
+            duration: Duration::from_secs(1),
+            timing_function: AnimationTimingFunction::EaseOut,
+            delay: Duration::from_millis(100),
+            iteration_count: AnimationIterationCount::Count(FloatValue::new(2.5)),
+            direction: AnimationDirection::Reverse,
+            fill_mode: AnimationFillMode::Forwards,
+        });
+        assert_eq!(animation.0[1].name, "spin");
+        assert_eq!(animation.0[1].iteration_count, AnimationIterationCount::Infinite);
+
+        assert_eq!(parse_style_animation("1s infinite"), Err(CssAnimationParseError::MissingAnimationName("1s infinite")));
+        assert_eq!(parse_style_animation("spin 1s reverse normal"), Err(CssAnimationParseError::UnexpectedValue("normal")));
+    }
+
+    #[test]
+    fn test_parse_style_text_decoration() {
+        assert_eq!(parse_style_text_decoration("none"), Ok(StyleTextDecoration {
+            line: Some(TextDecorationLine::NONE),
+            color: None,
+            style: Some(TextDecorationStyle::Solid),
+            thickness: Some(TextDecorationThickness::Auto),
+        }));
+        assert_eq!(parse_style_text_decoration("2px line-through dashed"), Ok(StyleTextDecoration {
+            line: Some(TextDecorationLine { underline: false, overline: false, line_through: true }),
+            color: None,
+            style: Some(TextDecorationStyle::Dashed),
+            thickness: Some(TextDecorationThickness::Length(PixelValue::px(2.0))),
+        }));
+        assert_eq!(parse_style_text_decoration("underline underline"), Err(CssTextDecorationParseError::DuplicateValue("underline")));
+        assert_eq!(parse_style_text_decoration("none underline"), Err(CssTextDecorationParseError::InvalidLine("none")));
+        assert_eq!(parse_style_text_decoration("underline dotted wavy"), Err(CssTextDecorationParseError::DuplicateValue("wavy")));
+        assert!(parse_style_text_decoration("underline blu").is_err());
+    }
+
+    #[test]
+    fn test_parse_text_decoration_longhands_are_merged() {
+        let mut text_decoration = parse_key_value_pair(CssPropertyType::TextDecoration, "underline").unwrap();
+        text_decoration.merge(&parse_key_value_pair(CssPropertyType::TextDecorationColor, "rgba(0, 0, 255, 0.5)").unwrap());
+        text_decoration.merge(&parse_key_value_pair(CssPropertyType::TextDecorationStyle, "double").unwrap());
+        assert_eq!(text_decoration, CssProperty::TextDecoration(StyleTextDecoration {
+            line: Some(TextDecorationLine { underline: true, overline: false, line_through: false }),
+            color: Some(ColorU { r: 0, g: 0, b: 255, a: 128 }),
+            style: Some(TextDecorationStyle::Double),
+            thickness: Some(TextDecorationThickness::Auto),
+        }));
+    }
+
+    #[test]
+    fn test_parse_style_text_shadow() {
+        assert_eq!(parse_style_text_shadow("rgb(10, 20, 30) -1px 1.5px"), Ok(StyleTextShadow(vec![TextShadow {
+            offset: [PixelValue::px(-1.0), PixelValue::px(1.5)],
+            color: Some(ColorU { r: 10, g: 20, b: 30, a: 255 }),
+            blur_radius: PixelValue::px(0.0),
+        }])));
+        assert_eq!(parse_style_text_shadow("1px 1px 1em").map(|s| s.0[0].color), Ok(None));
+        assert_eq!(parse_style_text_shadow("1px"), Err(CssShadowParseError::InvalidSingleStatement("1px")));
+        assert_eq!(parse_style_text_shadow("1px 1px 1px 1px"), Err(CssShadowParseError::TooManyComponents("1px 1px 1px 1px")));
+        assert_eq!(parse_style_text_shadow("red 1px 1px blue"), Err(CssShadowParseError::TooManyComponents("red 1px 1px blue")));
+    }
 }
@@ -28,15 +28,10 @@ impl HotReloader {
 
 impl HotReloadHandler for HotReloader {
     fn reload_style(&mut self) -> Result<Css, String> {
-        use std::fs;
         use crate::css;
 
-        let file_name = self.file_path.file_name().map(|os_str| os_str.to_string_lossy()).unwrap_or_default();
-
-        let reloaded_css = fs::read_to_string(&self.file_path)
-            .map_err(|e| format!("Io error: Could not load \"{}\" when loading file: \"{}\"", file_name, e))?;
-
-        css::new_from_str(&reloaded_css).map_err(|e| format!("{}: {}", file_name, e))
+        // Imported files are reloaded as well, since they're loaded again on every reload
+        css::new_from_file(&self.file_path).map_err(|e| e.to_string())
     }
 
     fn get_reload_interval(&self) -> Duration {
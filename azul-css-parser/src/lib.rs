@@ -25,9 +25,13 @@ mod hot_reloader;
 
 pub use crate::css::{
     new_from_str,
+    new_from_file,
+    CssFileLoadError,
     parse_css_path,
     CssParseError,
     CssPathParseError,
+    CssMediaQueryParseError,
+    parse_media_query_list,
 };
 
 pub use crate::css_parser::*;
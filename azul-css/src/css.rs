@@ -1,6 +1,6 @@
 //! Types and methods used to describe the style of an application
-use crate::css_properties::{CssProperty, CssPropertyType};
-use std::fmt;
+use crate::css_properties::{CssProperty, CssPropertyType, AnimationTimingFunction, FloatValue, PixelValue, FontFaceProperties};
+use std::{fmt, path::PathBuf};
 
 /// Css stylesheet - contains a parsed CSS stylesheet in "rule blocks",
 /// i.e. blocks of key-value pairs associated with a selector path.
@@ -16,11 +16,113 @@ pub struct Css {
 pub struct Stylesheet {
     /// The style rules making up the document - for example, de-duplicated CSS rules
     pub rules: Vec<CssRuleBlock>,
+    /// The `@keyframes` rules of the document, referenced by the `animation` property
+    pub keyframes: Vec<CssKeyframes>,
+    /// The `@font-face` rules of the document, fonts that have to be loaded for the `font-family`
+    pub font_faces: Vec<CssFontFace>,
+    /// Whether the stylesheet is a default (native) style or a style of the application
+    pub origin: CssOrigin,
 }
 
 impl From<Vec<CssRuleBlock>> for Stylesheet {
     fn from(rules: Vec<CssRuleBlock>) -> Self {
-        Self { rules }
+        Self { rules, keyframes: Vec::new(), font_faces: Vec::new(), origin: CssOrigin::Author }
+    }
+}
+
+/// Origin of a stylesheet, determines which declarations win in the cascade.
+///
+/// Normal declarations of the application (`Author`) override the default
+/// styles (`UserAgent`), for `!important` declarations the order is inverted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CssOrigin {
+    /// Default styles, such as the native style from `azul-native-style`
+    UserAgent,
+    /// Styles of the application
+    Author,
+}
+
+impl Default for CssOrigin {
+    fn default() -> Self {
+        CssOrigin::Author
+    }
+}
+
+/// A `@font-face` rule, such as `@font-face { font-family: Roboto; src: url("Roboto.ttf"); }`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CssFontFace {
+    /// Name of the font, as used in the `font-family` property
+    pub font_family: String,
+    /// Where the font can be loaded from, in order of preference
+    pub sources: Vec<FontFaceSource>,
+    /// Weight, style and stretch of the face (`font-weight`, `font-style` and `font-stretch`)
+    pub properties: FontFaceProperties,
+}
+
+/// One source of the `src` of a `@font-face` rule
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FontFaceSource {
+    /// `url("fonts/Roboto.ttf")` - font file, relative to the CSS file it is declared
+    /// in (if the CSS was loaded from a file) or to the working directory
+    Url(PathBuf),
+    /// `local("Roboto")` - font that is installed on the system
+    Local(String),
+}
+
+/// A `@keyframes` rule, such as `@keyframes fade-in { from { opacity: 0; } to { opacity: 1; } }`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CssKeyframes {
+    /// Name of the animation, referenced by the `animation` property
+    pub name: String,
+    /// Keyframes, sorted by their offset
+    pub keyframes: Vec<CssKeyframe>,
+}
+
+/// One keyframe (i.e. `50% { opacity: 0.5; }`) of a `@keyframes` rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct CssKeyframe {
+    /// Offset of the keyframe in the animation, `0.0` = `from` / `0%`, `1.0` = `to` / `100%`
+    pub offset: FloatValue,
+    pub declarations: Vec<CssProperty>,
+}
+
+impl CssKeyframes {
+
+    /// Returns the animated properties at the given `progress` (`0.0` - `1.0`) of the animation,
+    /// easing between two keyframes with the `timing_function`.
+    ///
+    /// Every property is interpolated between the closest keyframes that declare it. Unlike
+    /// in CSS, properties that are missing in the first or last keyframe keep the value of the
+    /// closest keyframe instead of animating from / to the non-animated value of the node.
+    pub fn get_properties(&self, progress: f32, timing_function: AnimationTimingFunction) -> Vec<CssProperty> {
+
+        let mut property_types = Vec::<CssPropertyType>::new();
+        for declaration in self.keyframes.iter().flat_map(|keyframe| keyframe.declarations.iter()) {
+            if !property_types.contains(&declaration.get_type()) {
+                property_types.push(declaration.get_type());
+            }
+        }
+
+        fn find_declaration(keyframe: &CssKeyframe, property_type: CssPropertyType) -> Option<(f32, &CssProperty)> {
+            keyframe.declarations.iter().rev().find(|d| d.get_type() == property_type).map(|d| (keyframe.offset.get(), d))
+        }
+
+        property_types.into_iter().filter_map(|property_type| {
+            let before = self.keyframes.iter().rev().filter(|k| k.offset.get() <= progress).find_map(|k| find_declaration(k, property_type));
+            let after = self.keyframes.iter().filter(|k| k.offset.get() >= progress).find_map(|k| find_declaration(k, property_type));
+            match (before, after) {
+                (Some((start, from)), Some((end, to))) => {
+                    if end <= start {
+                        return Some(from.clone());
+                    }
+                    let t = timing_function.evaluate((progress - start) / (end - start));
+                    // Properties that can't be interpolated switch to the next value halfway
+                    Some(from.interpolate(to, t).unwrap_or_else(|| if t < 0.5 { from.clone() } else { to.clone() }))
+                },
+                (Some((_, value)), None) | (None, Some((_, value))) => Some(value.clone()),
+                (None, None) => None,
+            }
+        }).collect()
     }
 }
 
@@ -31,6 +133,12 @@ pub enum CssDeclaration {
     Static(CssProperty),
     /// Dynamic key-value pair with default value, such as `width: [[ my_id | 500px ]]`
     Dynamic(DynamicCssProperty),
+    /// Key-value pair that references a custom property, such as `color: var(--main-color)`.
+    /// Gets resolved to a `Static` declaration during the cascade.
+    Unresolved(UnresolvedCssProperty),
+    /// Key-value pair with a CSS-wide keyword as the value, such as `color: inherit`.
+    /// Gets resolved during the cascade.
+    Keyword(CssKeywordProperty),
 }
 
 impl CssDeclaration {
@@ -41,6 +149,8 @@ impl CssDeclaration {
         match self {
             Static(s) => s.get_type(),
             Dynamic(d) => d.property_type,
+            Unresolved(u) => u.property_type,
+            Keyword(k) => k.property_type.get_property_type(),
         }
     }
 
@@ -51,6 +161,8 @@ impl CssDeclaration {
         match self {
             Static(s) => s.get_type().is_inheritable(),
             Dynamic(d) => d.is_inheritable(),
+            Unresolved(u) => u.property_type.is_inheritable(),
+            Keyword(k) => k.property_type.is_inheritable(),
         }
     }
 
@@ -61,6 +173,8 @@ impl CssDeclaration {
         match self {
             Static(s) => s.get_type().can_trigger_relayout(),
             Dynamic(d) => d.can_trigger_relayout(),
+            Unresolved(u) => u.property_type.can_trigger_relayout(),
+            Keyword(k) => k.property_type.can_trigger_relayout(),
         }
     }
 }
@@ -125,6 +239,136 @@ impl DynamicCssProperty {
     }
 }
 
+/// A custom property, such as `--main-color: #ff0000`.
+///
+/// The value is stored as an unparsed string, since it only gets a meaning once it is
+/// substituted into a regular property via `var(--main-color)`. Custom properties are
+/// always inherited by the children of the node they are declared on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CustomCssProperty {
+    /// Name of the property, including the leading `--`, i.e. `"--main-color"`
+    pub name: String,
+    /// Unparsed value of the property, i.e. the `"#ff0000"` in `--main-color: #ff0000`
+    pub value: String,
+}
+
+/// A property whose value references one or more custom properties, such as
+/// `color: var(--main-color, black)`. Since the value of a custom property depends on
+/// where the node is in the DOM, these properties can only be parsed during the cascade.
+///
+/// Parsing the substituted value needs the `css_parser` feature of azul - without it,
+/// unresolved properties are removed from the node during the cascade.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct UnresolvedCssProperty {
+    /// Key for this property
+    pub property_type: CssPropertyType,
+    /// Unparsed value, i.e. the `"var(--main-color, black)"` in `color: var(--main-color, black)`
+    pub value: String,
+}
+
+/// A property whose value is a CSS-wide keyword, such as `color: inherit` or `width: initial`.
+///
+/// Keywords of a key that sets only one part of a property (i.e. `padding-left: inherit`)
+/// apply to the whole property (`padding`).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CssKeywordProperty {
+    /// Key for this property
+    pub property_type: CssPropertyType,
+    /// The `inherit` in `color: inherit`
+    pub keyword: CssWideKeyword,
+}
+
+/// Keywords that are valid values for every CSS property
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CssWideKeyword {
+    /// `inherit`: uses the value of the parent node (or the default value for the root node)
+    Inherit,
+    /// `initial`: uses the default value of the property, even if the property is inherited
+    Initial,
+    /// `unset`: `inherit` for inherited properties (such as `color`), `initial` for all others
+    Unset,
+}
+
+impl fmt::Display for CssWideKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CssWideKeyword::*;
+        match self {
+            Inherit => write!(f, "inherit"),
+            Initial => write!(f, "initial"),
+            Unset => write!(f, "unset"),
+        }
+    }
+}
+
+/// Substitutes all `var(--name)` and `var(--name, fallback)` functions in a CSS value.
+///
+/// `lookup` returns the (already substituted) value of a custom property or `None` if the
+/// property isn't defined, in which case the fallback is used. Returns `None` if a referenced
+/// property is undefined and has no fallback or if a `var()` function isn't closed.
+///
+/// ```rust
+/// # use azul_css::substitute_css_variables;
+/// let mut lookup = |name: &str| if name == "--width" { Some("500px".to_string()) } else { None };
+/// assert_eq!(substitute_css_variables("var(--width)", &mut lookup), Some("500px".to_string()));
+/// assert_eq!(substitute_css_variables("var(--height, 10px)", &mut lookup), Some("10px".to_string()));
+/// assert_eq!(substitute_css_variables("var(--height)", &mut lookup), None);
+/// ```
+pub fn substitute_css_variables<F>(value: &str, lookup: &mut F) -> Option<String>
+    where F: FnMut(&str) -> Option<String>
+{
+    const VAR_START: &str = "var(";
+
+    let mut substituted = String::with_capacity(value.len());
+    let mut remaining = value;
+
+    while let Some(var_start) = remaining.find(VAR_START) {
+
+        substituted.push_str(&remaining[..var_start]);
+        let var_inner_start = var_start + VAR_START.len();
+
+        // Find the closing brace of the var(), fallbacks can contain braces themselves,
+        // i.e. `var(--color, rgb(0, 0, 0))`
+        let mut depth = 1;
+        let mut var_inner_end = None;
+        let mut fallback_start = None;
+
+        for (idx, ch) in remaining[var_inner_start..].char_indices() {
+            match ch {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        var_inner_end = Some(var_inner_start + idx);
+                        break;
+                    }
+                },
+                ',' if depth == 1 && fallback_start.is_none() => {
+                    fallback_start = Some(var_inner_start + idx);
+                },
+                _ => { },
+            }
+        }
+
+        let var_inner_end = var_inner_end?;
+
+        let (name, fallback) = match fallback_start {
+            Some(comma) => (&remaining[var_inner_start..comma], Some(&remaining[(comma + 1)..var_inner_end])),
+            None => (&remaining[var_inner_start..var_inner_end], None),
+        };
+
+        let resolved = match lookup(name.trim()) {
+            Some(s) => s,
+            None => substitute_css_variables(fallback?.trim(), lookup)?,
+        };
+
+        substituted.push_str(&resolved);
+        remaining = &remaining[(var_inner_end + 1)..];
+    }
+
+    substituted.push_str(remaining);
+    Some(substituted)
+}
+
 /// One block of rules that applies a bunch of rules to a "path" in the style, i.e.
 /// `div#myid.myclass -> { ("justify-content", "center") }`
 #[derive(Debug, Clone, PartialEq)]
@@ -134,6 +378,142 @@ pub struct CssRuleBlock {
     /// `"justify-content: center"` =>
     /// `CssDeclaration::Static(CssProperty::JustifyContent(LayoutJustifyContent::Center))`
     pub declarations: Vec<CssDeclaration>,
+    /// Declarations marked with `!important`, i.e. `"color: red !important"`
+    pub important_declarations: Vec<CssDeclaration>,
+    /// `"--main-color: #ff0000"` =>
+    /// `CustomCssProperty { name: "--main-color", value: "#ff0000" }`
+    pub custom_properties: Vec<CustomCssProperty>,
+    /// The `@media` queries that the rule block is nested in - the rules only
+    /// apply if all of them match (empty for rules outside of `@media` blocks)
+    pub media_queries: Vec<MediaQueryList>,
+}
+
+impl CssRuleBlock {
+    /// Returns whether the rules of this block apply in the given media context
+    pub fn matches_media(&self, context: &MediaQueryContext) -> bool {
+        self.media_queries.iter().all(|query_list| query_list.matches(context))
+    }
+}
+
+/// Comma-separated list of media queries, i.e. `screen and (max-width: 600px), print` -
+/// matches if any of the queries match
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MediaQueryList(pub Vec<MediaQuery>);
+
+impl MediaQueryList {
+    pub fn matches(&self, context: &MediaQueryContext) -> bool {
+        self.0.iter().any(|query| query.matches(context))
+    }
+}
+
+/// One media query, i.e. `not screen and (orientation: portrait)`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MediaQuery {
+    /// Whether the query is prefixed with `not`, which negates the whole query
+    pub negated: bool,
+    pub media_type: MediaType,
+    /// Conditions joined by `and`, all of them have to match
+    pub features: Vec<MediaFeature>,
+}
+
+impl MediaQuery {
+    pub fn matches(&self, context: &MediaQueryContext) -> bool {
+        let media_type_matches = match self.media_type {
+            MediaType::All | MediaType::Screen => true,
+            MediaType::Print => false,
+        };
+        let matches = media_type_matches && self.features.iter().all(|feature| feature.matches(context));
+        matches != self.negated
+    }
+}
+
+/// Type of the output device - azul only renders to screens
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MediaType {
+    All,
+    Screen,
+    Print,
+}
+
+/// One condition of a media query, i.e. `(max-width: 600px)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MediaFeature {
+    MinWidth(PixelValue),
+    MaxWidth(PixelValue),
+    MinHeight(PixelValue),
+    MaxHeight(PixelValue),
+    Orientation(MediaOrientation),
+    /// Resolution in `dppx` (pixels per CSS pixel, i.e. the HiDPI factor)
+    Resolution(FloatValue),
+    MinResolution(FloatValue),
+    MaxResolution(FloatValue),
+    PrefersColorScheme(ColorScheme),
+}
+
+impl MediaFeature {
+    pub fn matches(&self, context: &MediaQueryContext) -> bool {
+        use self::MediaFeature::*;
+        match self {
+            MinWidth(w) => context.width >= w.to_pixels(),
+            MaxWidth(w) => context.width <= w.to_pixels(),
+            MinHeight(h) => context.height >= h.to_pixels(),
+            MaxHeight(h) => context.height <= h.to_pixels(),
+            Orientation(o) => *o == context.get_orientation(),
+            Resolution(r) => context.hidpi_factor == r.get(),
+            MinResolution(r) => context.hidpi_factor >= r.get(),
+            MaxResolution(r) => context.hidpi_factor <= r.get(),
+            PrefersColorScheme(c) => *c == context.color_scheme,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MediaOrientation {
+    Portrait,
+    Landscape,
+}
+
+/// Light or dark color scheme, used for the `prefers-color-scheme` media query
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl Default for ColorScheme {
+    fn default() -> Self {
+        ColorScheme::Light
+    }
+}
+
+/// State of the window that `@media` queries are evaluated against
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct MediaQueryContext {
+    /// Logical width of the window
+    pub width: f32,
+    /// Logical height of the window
+    pub height: f32,
+    /// HiDPI factor of the window, compared against the `resolution` in `dppx`
+    pub hidpi_factor: f32,
+    pub color_scheme: ColorScheme,
+}
+
+impl MediaQueryContext {
+    /// Like in CSS, square windows are in portrait mode
+    pub fn get_orientation(&self) -> MediaOrientation {
+        if self.height >= self.width { MediaOrientation::Portrait } else { MediaOrientation::Landscape }
+    }
+}
+
+impl Default for MediaQueryContext {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+            hidpi_factor: 1.0,
+            color_scheme: ColorScheme::default(),
+        }
+    }
 }
 
 pub type CssContentGroup<'a> = Vec<&'a CssPathSelector>;
@@ -235,10 +615,21 @@ pub enum CssPathSelector {
     Id(String),
     /// `:something`
     PseudoSelector(CssPathPseudoSelector),
+    /// `::something`, only valid as the last element of the path
+    /// (except for trailing `:horizontal` / `:vertical` selectors)
+    PseudoElement(CssPathPseudoElement),
+    /// `[something]`, `[something="value"]`, etc.
+    Attribute(CssAttributeSelector),
+    /// `:not(.something)` - the path only contains simple selectors, no combinators
+    Not(CssPath),
     /// Represents the `>` selector
     DirectChildren,
     /// Represents the ` ` selector
     Children,
+    /// Represents the `+` selector
+    AdjacentSibling,
+    /// Represents the `~` selector
+    GeneralSibling,
 }
 
 impl Default for CssPathSelector {
@@ -256,34 +647,191 @@ impl fmt::Display for CssPathSelector {
             Class(c) => write!(f, ".{}", c),
             Id(i) => write!(f, "#{}", i),
             PseudoSelector(p) => write!(f, ":{}", p),
+            PseudoElement(p) => write!(f, "::{}", p),
+            Attribute(a) => write!(f, "{}", a),
+            Not(p) => write!(f, ":not({})", p),
             DirectChildren => write!(f, ">"),
             Children => write!(f, " "),
+            AdjacentSibling => write!(f, "+"),
+            GeneralSibling => write!(f, "~"),
         }
     }
 }
 
+/// Attribute selector, i.e. `[type]` or `[type="text"]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CssAttributeSelector {
+    /// Name of the attribute, i.e. `type` in `[type="text"]`
+    pub name: String,
+    /// How the value of the attribute is compared
+    pub matcher: CssAttributeMatcher,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CssAttributeMatcher {
+    /// `[name]` - the attribute exists, regardless of its value
+    Exists,
+    /// `[name=value]` - the value is exactly `value`
+    Equals(String),
+    /// `[name~=value]` - the value is a whitespace-separated list containing `value`
+    Includes(String),
+    /// `[name|=value]` - the value is `value` or starts with `value-`
+    DashMatch(String),
+    /// `[name^=value]` - the value starts with `value`
+    Prefix(String),
+    /// `[name$=value]` - the value ends with `value`
+    Suffix(String),
+    /// `[name*=value]` - the value contains `value`
+    Substring(String),
+}
+
+impl CssAttributeSelector {
+
+    /// Returns whether the selector matches a node, given the value of the
+    /// attribute on the node (`None` if the node doesn't have the attribute)
+    pub fn matches(&self, attribute_value: Option<&str>) -> bool {
+        use self::CssAttributeMatcher::*;
+
+        let value = match attribute_value {
+            Some(v) => v,
+            None => return false,
+        };
+
+        match &self.matcher {
+            Exists => true,
+            Equals(v) => value == v,
+            Includes(v) => !v.is_empty() && !v.contains(char::is_whitespace) && value.split_whitespace().any(|word| word == v),
+            DashMatch(v) => value == v || (value.starts_with(v.as_str()) && value[v.len()..].starts_with('-')),
+            // An empty value never matches, same as in browsers
+            Prefix(v) => !v.is_empty() && value.starts_with(v.as_str()),
+            Suffix(v) => !v.is_empty() && value.ends_with(v.as_str()),
+            Substring(v) => !v.is_empty() && value.contains(v.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for CssAttributeSelector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CssAttributeMatcher::*;
+        let (operator, value) = match &self.matcher {
+            Exists => return write!(f, "[{}]", self.name),
+            Equals(v) => ("=", v),
+            Includes(v) => ("~=", v),
+            DashMatch(v) => ("|=", v),
+            Prefix(v) => ("^=", v),
+            Suffix(v) => ("$=", v),
+            Substring(v) => ("*=", v),
+        };
+        write!(f, "[{}{}\"{}\"]", self.name, operator, value)
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CssPathPseudoSelector {
     /// `:first`
     First,
     /// `:last`
     Last,
+    /// `:first-child`
+    FirstChild,
+    /// `:last-child`
+    LastChild,
+    /// `:only-child` - element is the only child of its parent
+    OnlyChild,
     /// `:nth-child`
     NthChild(CssNthChildSelector),
+    /// `:nth-last-child` - same as `:nth-child`, but counted from the last child
+    NthLastChild(CssNthChildSelector),
+    /// `:first-of-type`
+    FirstOfType,
+    /// `:last-of-type`
+    LastOfType,
+    /// `:only-of-type` - element is the only sibling of its node type
+    OnlyOfType,
+    /// `:nth-of-type` - same as `:nth-child`, but only counts siblings of the same node type
+    NthOfType(CssNthChildSelector),
+    /// `:nth-last-of-type`
+    NthLastOfType(CssNthChildSelector),
+    /// `:empty` - element has no children and no text
+    Empty,
+    /// `:root` - element is the root of the DOM
+    Root,
     /// `:hover` - mouse is over element
     Hover,
     /// `:active` - mouse is pressed and over element
     Active,
     /// `:focus` - element has received focus
     Focus,
+    /// `:horizontal` - restricts a scrollbar pseudo-element to the horizontal scrollbar
+    Horizontal,
+    /// `:vertical` - restricts a scrollbar pseudo-element to the vertical scrollbar
+    Vertical,
+}
+
+/// Pseudo-elements that style the scrollbars of a scrollable node
+/// (see `ScrollbarInfo` for which part of the scrollbar they style)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CssPathPseudoElement {
+    /// `::-webkit-scrollbar`
+    Scrollbar,
+    /// `::-webkit-scrollbar-track`
+    ScrollbarTrack,
+    /// `::-webkit-scrollbar-track-piece`
+    ScrollbarTrackPiece,
+    /// `::-webkit-scrollbar-thumb`
+    ScrollbarThumb,
+    /// `::-webkit-scrollbar-button`
+    ScrollbarButton,
+    /// `::-webkit-scrollbar-corner`
+    ScrollbarCorner,
+    /// `::-webkit-resizer`
+    Resizer,
+}
+
+impl fmt::Display for CssPathPseudoElement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CssPathPseudoElement::*;
+        match &self {
+            Scrollbar => write!(f, "-webkit-scrollbar"),
+            ScrollbarTrack => write!(f, "-webkit-scrollbar-track"),
+            ScrollbarTrackPiece => write!(f, "-webkit-scrollbar-track-piece"),
+            ScrollbarThumb => write!(f, "-webkit-scrollbar-thumb"),
+            ScrollbarButton => write!(f, "-webkit-scrollbar-button"),
+            ScrollbarCorner => write!(f, "-webkit-scrollbar-corner"),
+            Resizer => write!(f, "-webkit-resizer"),
+        }
+    }
 }
 
+/// Argument of `:nth-child()` and similar selectors
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CssNthChildSelector {
     Number(usize),
     Even,
     Odd,
-    Pattern { repeat: usize, offset: usize },
+    /// `An+B`, i.e. `2n+1`, `-n+3` or `3n-2`
+    Pattern { repeat: isize, offset: isize },
+}
+
+impl CssNthChildSelector {
+
+    /// Returns whether the selector matches the (1-indexed) position of a node,
+    /// i.e. whether there is a `n >= 0` so that `An+B` equals the `index`
+    pub fn matches(&self, index: usize) -> bool {
+        use self::CssNthChildSelector::*;
+        let (repeat, offset) = match *self {
+            Number(n) => return index == n,
+            Even => (2, 0),
+            Odd => (2, 1),
+            Pattern { repeat, offset } => (repeat, offset),
+        };
+        let distance = index as isize - offset;
+        if repeat == 0 {
+            distance == 0
+        } else {
+            distance % repeat == 0 && distance / repeat >= 0
+        }
+    }
 }
 
 impl fmt::Display for CssNthChildSelector {
@@ -293,7 +841,7 @@ impl fmt::Display for CssNthChildSelector {
             Number(u) => write!(f, "{}", u),
             Even => write!(f, "even"),
             Odd => write!(f, "odd"),
-            Pattern { repeat, offset } => write!(f, "{}n + {}", repeat, offset),
+            Pattern { repeat, offset } => write!(f, "{}n{:+}", repeat, offset),
         }
     }
 }
@@ -304,10 +852,23 @@ impl fmt::Display for CssPathPseudoSelector {
         match &self {
             First => write!(f, "first"),
             Last => write!(f, "last"),
+            FirstChild => write!(f, "first-child"),
+            LastChild => write!(f, "last-child"),
+            OnlyChild => write!(f, "only-child"),
             NthChild(u) => write!(f, "nth-child({})", u),
+            NthLastChild(u) => write!(f, "nth-last-child({})", u),
+            FirstOfType => write!(f, "first-of-type"),
+            LastOfType => write!(f, "last-of-type"),
+            OnlyOfType => write!(f, "only-of-type"),
+            NthOfType(u) => write!(f, "nth-of-type({})", u),
+            NthLastOfType(u) => write!(f, "nth-last-of-type({})", u),
+            Empty => write!(f, "empty"),
+            Root => write!(f, "root"),
             Hover => write!(f, "hover"),
             Active => write!(f, "active"),
             Focus => write!(f, "focus"),
+            Horizontal => write!(f, "horizontal"),
+            Vertical => write!(f, "vertical"),
         }
     }
 }
@@ -329,6 +890,14 @@ impl Css {
         self.stylesheets.push(styles);
     }
 
+    /// Sets the origin of all stylesheets, i.e. `CssOrigin::UserAgent` for a default style
+    /// that any stylesheet appended later should override
+    pub fn set_origin(&mut self, origin: CssOrigin) {
+        for stylesheet in &mut self.stylesheets {
+            stylesheet.origin = origin;
+        }
+    }
+
     pub fn sort_by_specificity(&mut self) {
         for stylesheet in &mut self.stylesheets {
             stylesheet.sort_by_specificity()
@@ -342,6 +911,12 @@ impl Css {
             css: self,
         }
     }
+
+    /// Returns the `@keyframes` rule with the given name - if the name is
+    /// defined more than once, the last definition wins
+    pub fn get_keyframes(&self, name: &str) -> Option<&CssKeyframes> {
+        self.stylesheets.iter().rev().flat_map(|stylesheet| stylesheet.keyframes.iter().rev()).find(|keyframes| keyframes.name == name)
+    }
 }
 
 pub struct RuleIterator<'a> {
@@ -378,27 +953,85 @@ impl Stylesheet {
 
     /// Sort the style rules by their weight, so that the rules are applied in the correct order.
     /// Should always be called when a new style is loaded from an external source.
+    ///
+    /// The sort is stable, so rules with the same specificity keep their source order.
     pub fn sort_by_specificity(&mut self) {
-        self.rules.sort_by(|a, b| get_specificity(&a.path).cmp(&get_specificity(&b.path)));
+        self.rules.sort_by_key(|rule| rule.path.get_specificity());
+    }
+}
+
+impl CssPath {
+    /// Returns the specificity of the path as `(ids, classes, types)`. Further information can be
+    /// found on [the w3 website](http://www.w3.org/TR/selectors/#specificity).
+    pub fn get_specificity(&self) -> (usize, usize, usize) {
+        count_selectors(&self.selectors)
     }
 }
 
-/// Returns specificity of the given css path. Further information can be found on
-/// [the w3 website](http://www.w3.org/TR/selectors/#specificity).
-fn get_specificity(path: &CssPath) -> (usize, usize, usize, usize) {
-    let id_count = path.selectors.iter().filter(|x|     if let CssPathSelector::Id(_) = x {     true } else { false }).count();
-    let class_count = path.selectors.iter().filter(|x|  if let CssPathSelector::Class(_) = x {  true } else { false }).count();
-    let div_count = path.selectors.iter().filter(|x|    if let CssPathSelector::Type(_) = x {   true } else { false }).count();
-    (id_count, class_count, div_count, path.selectors.len())
+/// Counts the ID, class (and attribute / pseudo-class) and type selectors - a `:not()`
+/// selector counts like the selectors in its argument
+fn count_selectors(selectors: &[CssPathSelector]) -> (usize, usize, usize) {
+    use self::CssPathSelector::*;
+    selectors.iter().fold((0, 0, 0), |(id_count, class_count, div_count), selector| match selector {
+        Id(_) => (id_count + 1, class_count, div_count),
+        Class(_) | Attribute(_) | PseudoSelector(_) => (id_count, class_count + 1, div_count),
+        Type(_) | PseudoElement(_) => (id_count, class_count, div_count + 1),
+        Not(path) => {
+            let (not_id_count, not_class_count, not_div_count) = count_selectors(&path.selectors);
+            (id_count + not_id_count, class_count + not_class_count, div_count + not_div_count)
+        },
+        _ => (id_count, class_count, div_count),
+    })
 }
 
 #[test]
 fn test_specificity() {
     use self::CssPathSelector::*;
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Id("hello".into())] }), (1, 0, 0, 1));
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Class("hello".into())] }), (0, 1, 0, 1));
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Type(NodeTypePath::Div)] }), (0, 0, 1, 1));
-    assert_eq!(get_specificity(&CssPath { selectors: vec![Id("hello".into()), Type(NodeTypePath::Div)] }), (1, 0, 1, 2));
+    assert_eq!(CssPath { selectors: vec![Id("hello".into())] }.get_specificity(), (1, 0, 0));
+    assert_eq!(CssPath { selectors: vec![Class("hello".into())] }.get_specificity(), (0, 1, 0));
+    assert_eq!(CssPath { selectors: vec![Type(NodeTypePath::Div)] }.get_specificity(), (0, 0, 1));
+    assert_eq!(CssPath { selectors: vec![Id("hello".into()), Type(NodeTypePath::Div)] }.get_specificity(), (1, 0, 1));
+    assert_eq!(CssPath { selectors: vec![
+        Attribute(CssAttributeSelector { name: "disabled".into(), matcher: CssAttributeMatcher::Exists }),
+        Not(CssPath { selectors: vec![Id("hello".into())] }),
+    ] }.get_specificity(), (1, 1, 0));
+}
+
+#[test]
+fn test_nth_child_selector_matches() {
+    use self::CssNthChildSelector::*;
+
+    let matching = |selector: CssNthChildSelector| (1..=10).filter(|i| selector.matches(*i)).collect::<Vec<_>>();
+
+    assert_eq!(matching(Number(3)), vec![3]);
+    assert_eq!(matching(Even), vec![2, 4, 6, 8, 10]);
+    assert_eq!(matching(Odd), vec![1, 3, 5, 7, 9]);
+    assert_eq!(matching(Pattern { repeat: 3, offset: 0 }), vec![3, 6, 9]);
+    assert_eq!(matching(Pattern { repeat: 2, offset: -1 }), vec![1, 3, 5, 7, 9]);
+    assert_eq!(matching(Pattern { repeat: -1, offset: 3 }), vec![1, 2, 3]);
+    assert_eq!(matching(Pattern { repeat: 3, offset: 8 }), vec![8]);
+    assert_eq!(matching(Pattern { repeat: 0, offset: -2 }), Vec::<usize>::new());
+    assert_eq!(format!("{}", Pattern { repeat: -1, offset: 3 }), "-1n+3");
+}
+
+#[test]
+fn test_attribute_selector_matches() {
+    use self::CssAttributeMatcher::*;
+
+    let selector = |matcher| CssAttributeSelector { name: "lang".into(), matcher };
+
+    assert!(selector(Exists).matches(Some("")));
+    assert!(!selector(Exists).matches(None));
+    assert!(selector(Equals("en".into())).matches(Some("en")));
+    assert!(!selector(Equals("en".into())).matches(Some("en-US")));
+    assert!(selector(Includes("b".into())).matches(Some("a b c")));
+    assert!(!selector(Includes("b".into())).matches(Some("abc")));
+    assert!(selector(DashMatch("en".into())).matches(Some("en-US")));
+    assert!(!selector(DashMatch("en".into())).matches(Some("english")));
+    assert!(selector(Prefix("en".into())).matches(Some("english")));
+    assert!(!selector(Prefix("".into())).matches(Some("english")));
+    assert!(selector(Suffix("US".into())).matches(Some("en-US")));
+    assert!(selector(Substring("n-U".into())).matches(Some("en-US")));
 }
 
 // Assert that order of the style items is correct (in order of CSS path specificity, lowest-to-highest)
@@ -410,12 +1043,15 @@ fn test_specificity_sort() {
     let mut input_style = Stylesheet {
         rules: vec![
             // Rules are sorted from lowest-specificity to highest specificity
-            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
         ],
+        keyframes: Vec::new(),
+        font_faces: Vec::new(),
+        origin: CssOrigin::Author,
     };
 
     input_style.sort_by_specificity();
@@ -423,13 +1059,36 @@ fn test_specificity_sort() {
     let expected_style = Stylesheet {
         rules: vec![
             // Rules are sorted from lowest-specificity to highest specificity
-            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new() },
-            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Global, Type(Div), Class("my_class".into()), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
+            CssRuleBlock { path: CssPath { selectors: vec![Type(Div), Class("my_class".into()), Class("specific".into()), Id("my_id".into())] }, declarations: Vec::new(), important_declarations: Vec::new(), custom_properties: Vec::new(), media_queries: Vec::new() },
         ],
+        keyframes: Vec::new(),
+        font_faces: Vec::new(),
+        origin: CssOrigin::Author,
     };
 
     assert_eq!(input_style, expected_style);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_substitute_css_variables() {
+    let mut lookup = |name: &str| match name {
+        "--main-color" => Some("#ff0000".to_string()),
+        "--spacing" => Some("5px".to_string()),
+        _ => None,
+    };
+
+    assert_eq!(substitute_css_variables("10px", &mut lookup), Some("10px".to_string()));
+    assert_eq!(substitute_css_variables("var(--main-color)", &mut lookup), Some("#ff0000".to_string()));
+    assert_eq!(substitute_css_variables("var( --spacing ) var(--spacing)", &mut lookup), Some("5px 5px".to_string()));
+    assert_eq!(substitute_css_variables("1px solid var(--main-color)", &mut lookup), Some("1px solid #ff0000".to_string()));
+    // Fallbacks can contain commas, braces and other var() functions
+    assert_eq!(substitute_css_variables("var(--unknown, rgb(0, 0, 0))", &mut lookup), Some("rgb(0, 0, 0)".to_string()));
+    assert_eq!(substitute_css_variables("var(--unknown, var(--spacing))", &mut lookup), Some("5px".to_string()));
+    assert_eq!(substitute_css_variables("var(--unknown)", &mut lookup), None);
+    assert_eq!(substitute_css_variables("var(--unknown, var(--other))", &mut lookup), None);
+    assert_eq!(substitute_css_variables("var(--main-color", &mut lookup), None);
+}
@@ -2,6 +2,11 @@
 
 use std::collections::BTreeMap;
 use std::fmt;
+use std::num::NonZeroU32;
+use std::ptr;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicPtr, Ordering as AtomicOrdering};
+use std::time::Duration;
 
 /// Currently hard-coded: Height of one em in pixels
 const EM_HEIGHT: f32 = 16.0;
@@ -64,6 +69,19 @@ pub struct LayoutSideOffsets {
 #[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
 pub struct ColorU { pub r: u8, pub g: u8, pub b: u8, pub a: u8 }
 
+impl ColorU {
+    /// Interpolates each channel linearly, `t = 0.0` returns `self`, `t = 1.0` returns `other`
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let interpolate_channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round().max(0.0).min(255.0) as u8;
+        ColorU {
+            r: interpolate_channel(self.r, other.r),
+            g: interpolate_channel(self.g, other.g),
+            b: interpolate_channel(self.b, other.b),
+            a: interpolate_channel(self.a, other.a),
+        }
+    }
+}
+
 /// f32-based color, range 0.0 to 1.0 (similar to webrenders ColorF)
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
 pub struct ColorF { pub r: f32, pub g: f32, pub b: f32, pub a: f32 }
@@ -171,12 +189,45 @@ pub enum BorderStyle {
     Outset,
 }
 
+/// Nine-patch border (`border-image`) with all sizes resolved to pixels, see
+/// `StyleBorderImage::get_webrender_border`. The image itself is not part of this struct,
+/// since it has to be looked up in the app resources first.
 #[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
 pub struct NinePatchBorder {
-    // not implemented or parse-able yet, so no fields!
+    /// Width of the source image in pixels
+    pub width: i32,
+    /// Height of the source image in pixels
+    pub height: i32,
+    /// Distance of the slice lines from the edges of the image, in image pixels
+    pub slice: LayoutSideOffsets,
+    /// Whether the middle part of the image is drawn
+    pub fill: bool,
+    pub repeat_horizontal: BorderImageRepeat,
+    pub repeat_vertical: BorderImageRepeat,
+    /// How far the border image extends beyond the border box
+    pub outset: LayoutSideOffsets,
+}
+
+/// How the edges of a `border-image` are scaled to fit the sides of the border
+#[derive(Debug, Copy, Clone, PartialEq, Ord, PartialOrd, Eq, Hash)]
+pub enum BorderImageRepeat {
+    /// The edges are stretched to fill the side
+    Stretch,
+    /// The edges are tiled, the outermost tiles may be cut off
+    Repeat,
+    /// The edges are tiled and scaled so that a whole number of tiles fits the side
+    Round,
+    /// The edges are tiled, the left over space is distributed between the tiles
+    Space,
 }
 
-/// Creates `pt`, `px` and `em` constructors for any struct that has a
+impl Default for BorderImageRepeat {
+    fn default() -> Self {
+        BorderImageRepeat::Stretch
+    }
+}
+
+/// Creates `pt`, `px`, `em`, `percent`, `rem`, `vw` and `vh` constructors for any struct that has a
 /// `PixelValue` as it's self.0 field.
 macro_rules! impl_pixel_value {($struct:ident) => (
     impl $struct {
@@ -194,6 +245,26 @@ macro_rules! impl_pixel_value {($struct:ident) => (
         pub fn pt(value: f32) -> Self {
             $struct(PixelValue::pt(value))
         }
+
+        #[inline]
+        pub fn percent(value: f32) -> Self {
+            $struct(PixelValue::percent(value))
+        }
+
+        #[inline]
+        pub fn rem(value: f32) -> Self {
+            $struct(PixelValue::rem(value))
+        }
+
+        #[inline]
+        pub fn vw(value: f32) -> Self {
+            $struct(PixelValue::vw(value))
+        }
+
+        #[inline]
+        pub fn vh(value: f32) -> Self {
+            $struct(PixelValue::vh(value))
+        }
     }
 
     impl ::std::fmt::Debug for $struct {
@@ -203,6 +274,68 @@ macro_rules! impl_pixel_value {($struct:ident) => (
     }
 )}
 
+/// Same as `impl_pixel_value!`, but for the `width` / `height`, which can
+/// also be one of the `min-content`, `max-content` or `fit-content` keywords
+macro_rules! impl_size_value {($enum:ident) => (
+    impl $enum {
+        #[inline]
+        pub fn px(value: f32) -> Self {
+            $enum::Exact(PixelValue::px(value))
+        }
+
+        #[inline]
+        pub fn em(value: f32) -> Self {
+            $enum::Exact(PixelValue::em(value))
+        }
+
+        #[inline]
+        pub fn pt(value: f32) -> Self {
+            $enum::Exact(PixelValue::pt(value))
+        }
+
+        #[inline]
+        pub fn percent(value: f32) -> Self {
+            $enum::Exact(PixelValue::percent(value))
+        }
+
+        #[inline]
+        pub fn rem(value: f32) -> Self {
+            $enum::Exact(PixelValue::rem(value))
+        }
+
+        #[inline]
+        pub fn vw(value: f32) -> Self {
+            $enum::Exact(PixelValue::vw(value))
+        }
+
+        #[inline]
+        pub fn vh(value: f32) -> Self {
+            $enum::Exact(PixelValue::vh(value))
+        }
+
+        /// Returns the length, `None` if the size is one of the keywords,
+        /// which can only be resolved by the layout solver
+        #[inline]
+        pub fn get_exact(&self) -> Option<PixelValue> {
+            match self {
+                $enum::Exact(value) => Some(*value),
+                _ => None,
+            }
+        }
+    }
+
+    impl ::std::fmt::Debug for $enum {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+            match self {
+                $enum::MinContent => write!(f, "{}(min-content)", stringify!($enum)),
+                $enum::MaxContent => write!(f, "{}(max-content)", stringify!($enum)),
+                $enum::FitContent => write!(f, "{}(fit-content)", stringify!($enum)),
+                $enum::Exact(value) => write!(f, "{}({:?})", stringify!($enum), value),
+            }
+        }
+    }
+)}
+
 macro_rules! impl_percentage_value{($struct:ident) => (
     impl ::std::fmt::Debug for $struct {
         fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
@@ -220,23 +353,44 @@ macro_rules! impl_float_value{($struct:ident) => (
 )}
 
 /// Map between CSS keys and a statically typed enum
-const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);56] = [
+const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);97] = [
     (CssPropertyType::Background,       "background"),
     (CssPropertyType::BackgroundSize,   "background-size"),
     (CssPropertyType::BackgroundRepeat, "background-repeat"),
     (CssPropertyType::BackgroundColor,  "background-color"),
     (CssPropertyType::BackgroundImage,  "background-image"),
+    (CssPropertyType::BackgroundPosition, "background-position"),
+    (CssPropertyType::BackgroundOrigin, "background-origin"),
+    (CssPropertyType::BackgroundClip,   "background-clip"),
 
     (CssPropertyType::BorderRadius,     "border-radius"),
     (CssPropertyType::TextColor,        "color"),
     (CssPropertyType::FontSize,         "font-size"),
     (CssPropertyType::FontFamily,       "font-family"),
+    (CssPropertyType::FontWeight,       "font-weight"),
+    (CssPropertyType::FontStyle,        "font-style"),
+    (CssPropertyType::FontStretch,      "font-stretch"),
     (CssPropertyType::TextAlign,        "text-align"),
+    (CssPropertyType::TextDecoration,   "text-decoration"),
+    (CssPropertyType::TextDecorationLine, "text-decoration-line"),
+    (CssPropertyType::TextDecorationColor, "text-decoration-color"),
+    (CssPropertyType::TextDecorationStyle, "text-decoration-style"),
+    (CssPropertyType::TextDecorationThickness, "text-decoration-thickness"),
+    (CssPropertyType::TextShadow,       "text-shadow"),
     (CssPropertyType::LetterSpacing,    "letter-spacing"),
     (CssPropertyType::LineHeight,       "line-height"),
     (CssPropertyType::WordSpacing,      "word-spacing"),
     (CssPropertyType::TabWidth,         "tab-width"),
     (CssPropertyType::Cursor,           "cursor"),
+    (CssPropertyType::Display,          "display"),
+    (CssPropertyType::GridTemplateColumns, "grid-template-columns"),
+    (CssPropertyType::GridTemplateRows, "grid-template-rows"),
+    (CssPropertyType::GridTemplateAreas, "grid-template-areas"),
+    (CssPropertyType::GridGap,          "grid-gap"),
+    (CssPropertyType::GridRow,          "grid-row"),
+    (CssPropertyType::GridColumn,       "grid-column"),
+    (CssPropertyType::GridArea,         "grid-area"),
+    (CssPropertyType::Visibility,       "visibility"),
     (CssPropertyType::Width,            "width"),
     (CssPropertyType::Height,           "height"),
     (CssPropertyType::MinWidth,         "min-width"),
@@ -244,17 +398,26 @@ const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);56] = [
     (CssPropertyType::MaxWidth,         "max-width"),
     (CssPropertyType::MaxHeight,        "max-height"),
     (CssPropertyType::Position,         "position"),
+    (CssPropertyType::Float,            "float"),
+    (CssPropertyType::ZIndex,           "z-index"),
     (CssPropertyType::Top,              "top"),
     (CssPropertyType::Right,            "right"),
     (CssPropertyType::Left,             "left"),
     (CssPropertyType::Bottom,           "bottom"),
     (CssPropertyType::FlexWrap,         "flex-wrap"),
     (CssPropertyType::FlexDirection,    "flex-direction"),
+    (CssPropertyType::Flex,             "flex"),
     (CssPropertyType::FlexGrow,         "flex-grow"),
     (CssPropertyType::FlexShrink,       "flex-shrink"),
+    (CssPropertyType::FlexBasis,        "flex-basis"),
+    (CssPropertyType::Order,            "order"),
     (CssPropertyType::JustifyContent,   "justify-content"),
     (CssPropertyType::AlignItems,       "align-items"),
+    (CssPropertyType::AlignSelf,        "align-self"),
     (CssPropertyType::AlignContent,     "align-content"),
+    (CssPropertyType::Gap,              "gap"),
+    (CssPropertyType::RowGap,           "row-gap"),
+    (CssPropertyType::ColumnGap,        "column-gap"),
     (CssPropertyType::Overflow,         "overflow"),
     (CssPropertyType::OverflowX,        "overflow-x"),
     (CssPropertyType::OverflowY,        "overflow-y"),
@@ -273,11 +436,22 @@ const CSS_PROPERTY_KEY_MAP: [(CssPropertyType, &'static str);56] = [
     (CssPropertyType::BorderLeft,       "border-left"),
     (CssPropertyType::BorderRight,      "border-right"),
     (CssPropertyType::BorderBottom,     "border-bottom"),
+    (CssPropertyType::BorderStyle,      "border-style"),
+    (CssPropertyType::BorderTopStyle,   "border-top-style"),
+    (CssPropertyType::BorderLeftStyle,  "border-left-style"),
+    (CssPropertyType::BorderRightStyle, "border-right-style"),
+    (CssPropertyType::BorderBottomStyle, "border-bottom-style"),
+    (CssPropertyType::BorderImage,      "border-image"),
     (CssPropertyType::BoxShadow,        "box-shadow"),
     (CssPropertyType::BoxShadowTop,     "box-shadow-top"),
     (CssPropertyType::BoxShadowLeft,    "box-shadow-left"),
     (CssPropertyType::BoxShadowRight,   "box-shadow-right"),
     (CssPropertyType::BoxShadowBottom,  "box-shadow-bottom"),
+    (CssPropertyType::Transform,        "transform"),
+    (CssPropertyType::TransformOrigin,  "transform-origin"),
+    (CssPropertyType::Opacity,          "opacity"),
+    (CssPropertyType::Transition,       "transition"),
+    (CssPropertyType::Animation,        "animation"),
 ];
 
 /// Returns a map useful for parsing the keys of CSS stylesheets
@@ -294,17 +468,38 @@ pub enum CssPropertyType {
     BackgroundSize,
     BackgroundRepeat,
     BackgroundImage,
+    BackgroundPosition,
+    BackgroundOrigin,
+    BackgroundClip,
 
     BorderRadius,
     TextColor,
     FontSize,
     FontFamily,
+    FontWeight,
+    FontStyle,
+    FontStretch,
     TextAlign,
+    TextDecoration,
+    TextDecorationLine,
+    TextDecorationColor,
+    TextDecorationStyle,
+    TextDecorationThickness,
+    TextShadow,
     LetterSpacing,
     WordSpacing,
     TabWidth,
     LineHeight,
     Cursor,
+    Display,
+    Visibility,
+    GridTemplateColumns,
+    GridTemplateRows,
+    GridTemplateAreas,
+    GridGap,
+    GridRow,
+    GridColumn,
+    GridArea,
     Width,
     Height,
     MinWidth,
@@ -312,17 +507,26 @@ pub enum CssPropertyType {
     MaxWidth,
     MaxHeight,
     Position,
+    Float,
+    ZIndex,
     Top,
     Right,
     Left,
     Bottom,
     FlexWrap,
     FlexDirection,
+    Flex,
     FlexGrow,
     FlexShrink,
+    FlexBasis,
+    Order,
     JustifyContent,
     AlignItems,
+    AlignSelf,
     AlignContent,
+    Gap,
+    RowGap,
+    ColumnGap,
 
     Overflow,
     OverflowX,
@@ -345,12 +549,25 @@ pub enum CssPropertyType {
     BorderLeft,
     BorderRight,
     BorderBottom,
+    BorderStyle,
+    BorderTopStyle,
+    BorderLeftStyle,
+    BorderRightStyle,
+    BorderBottomStyle,
+    BorderImage,
 
     BoxShadow,
     BoxShadowTop,
     BoxShadowLeft,
     BoxShadowRight,
     BoxShadowBottom,
+
+    Transform,
+    TransformOrigin,
+    Opacity,
+
+    Transition,
+    Animation,
 }
 
 impl CssPropertyType {
@@ -376,6 +593,24 @@ impl CssPropertyType {
         map.iter().find(|(_, v)| *v == self).and_then(|(k, _)| Some(k)).unwrap()
     }
 
+    /// Returns the type of the `CssProperty` that the value of this key gets parsed into,
+    /// i.e. `padding-top: 5px` is parsed into a `CssProperty::Padding`.
+    pub fn get_property_type(&self) -> Self {
+        use self::CssPropertyType::*;
+        match self {
+            BackgroundColor | BackgroundImage => Background,
+            OverflowX | OverflowY => Overflow,
+            PaddingTop | PaddingLeft | PaddingRight | PaddingBottom => Padding,
+            MarginTop | MarginLeft | MarginRight | MarginBottom => Margin,
+            BorderTop | BorderLeft | BorderRight | BorderBottom => Border,
+            BorderTopStyle | BorderLeftStyle | BorderRightStyle | BorderBottomStyle => CssPropertyType::BorderStyle,
+            TextDecorationLine | TextDecorationColor | TextDecorationStyle | TextDecorationThickness => TextDecoration,
+            BoxShadowTop | BoxShadowLeft | BoxShadowRight | BoxShadowBottom => BoxShadow,
+            RowGap | ColumnGap => Gap,
+            other => *other,
+        }
+    }
+
     /// Returns whether this property will be inherited during cascading
     pub fn is_inheritable(&self) -> bool {
         use self::CssPropertyType::*;
@@ -383,8 +618,13 @@ impl CssPropertyType {
             | TextColor
             | FontFamily
             | FontSize
+            | FontWeight
+            | FontStyle
+            | FontStretch
             | LineHeight
-            | TextAlign => true,
+            | TextShadow
+            | TextAlign
+            | Visibility => true,
             _ => false,
         }
     }
@@ -402,17 +642,34 @@ impl CssPropertyType {
 
         match self {
             | BorderRadius
+            | BorderImage
             | BackgroundColor
             | BackgroundSize
             | BackgroundRepeat
+            | BackgroundPosition
+            | BackgroundOrigin
+            | BackgroundClip
             | TextColor
             | Background
             | TextAlign
+            | TextDecoration
+            | TextDecorationLine
+            | TextDecorationColor
+            | TextDecorationStyle
+            | TextDecorationThickness
+            | TextShadow
             | BoxShadow
             | BoxShadowTop
             | BoxShadowLeft
             | BoxShadowBottom
             | BoxShadowRight
+            | Transform
+            | TransformOrigin
+            | Opacity
+            | Transition
+            | Animation
+            | ZIndex
+            | Visibility
             | Cursor => false,
             _ => true,
         }
@@ -430,20 +687,39 @@ impl fmt::Display for CssPropertyType {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum CssProperty {
     BorderRadius(StyleBorderRadius),
-    BackgroundSize(StyleBackgroundSize),
-    BackgroundRepeat(StyleBackgroundRepeat),
+    BackgroundSize(StyleBackgroundSizes),
+    BackgroundRepeat(StyleBackgroundRepeats),
+    BackgroundPosition(StyleBackgroundPositions),
+    BackgroundOrigin(StyleBackgroundOrigins),
+    BackgroundClip(StyleBackgroundClips),
     TextColor(StyleTextColor),
     Border(StyleBorder),
-    Background(StyleBackground),
+    BorderStyle(StyleBorderStyle),
+    BorderImage(StyleBorderImage),
+    Background(StyleBackgrounds),
     FontSize(StyleFontSize),
     FontFamily(StyleFontFamily),
+    FontWeight(StyleFontWeight),
+    FontStyle(StyleFontStyle),
+    FontStretch(StyleFontStretch),
     TextAlign(StyleTextAlignmentHorz),
+    TextDecoration(StyleTextDecoration),
+    TextShadow(StyleTextShadow),
     LetterSpacing(StyleLetterSpacing),
     BoxShadow(StyleBoxShadow),
     LineHeight(StyleLineHeight),
     WordSpacing(StyleWordSpacing),
     TabWidth(StyleTabWidth),
     Cursor(StyleCursor),
+    Display(LayoutDisplay),
+    Visibility(StyleVisibility),
+    GridTemplateColumns(LayoutGridTemplateColumns),
+    GridTemplateRows(LayoutGridTemplateRows),
+    GridTemplateAreas(LayoutGridTemplateAreas),
+    GridGap(LayoutGridGap),
+    GridRow(LayoutGridRow),
+    GridColumn(LayoutGridColumn),
+    GridArea(LayoutGridArea),
     Width(LayoutWidth),
     Height(LayoutHeight),
     MinWidth(LayoutMinWidth),
@@ -451,6 +727,8 @@ pub enum CssProperty {
     MaxWidth(LayoutMaxWidth),
     MaxHeight(LayoutMaxHeight),
     Position(LayoutPosition),
+    Float(LayoutFloat),
+    ZIndex(LayoutZIndex),
     Top(LayoutTop),
     Right(LayoutRight),
     Left(LayoutLeft),
@@ -459,12 +737,22 @@ pub enum CssProperty {
     Margin(LayoutMargin),
     FlexWrap(LayoutWrap),
     FlexDirection(LayoutDirection),
+    Flex(LayoutFlex),
     FlexGrow(LayoutFlexGrow),
     FlexShrink(LayoutFlexShrink),
+    FlexBasis(LayoutFlexBasis),
+    Order(LayoutOrder),
     JustifyContent(LayoutJustifyContent),
     AlignItems(LayoutAlignItems),
+    AlignSelf(LayoutAlignSelf),
     AlignContent(LayoutAlignContent),
+    Gap(LayoutGap),
     Overflow(LayoutOverflow),
+    Transform(StyleTransform),
+    TransformOrigin(StyleTransformOrigin),
+    Opacity(StyleOpacity),
+    Transition(StyleTransition),
+    Animation(StyleAnimation),
 }
 
 impl CssProperty {
@@ -475,18 +763,37 @@ impl CssProperty {
             CssProperty::BorderRadius(_) => CssPropertyType::BorderRadius,
             CssProperty::BackgroundSize(_) => CssPropertyType::BackgroundSize,
             CssProperty::BackgroundRepeat(_) => CssPropertyType::BackgroundRepeat,
+            CssProperty::BackgroundPosition(_) => CssPropertyType::BackgroundPosition,
+            CssProperty::BackgroundOrigin(_) => CssPropertyType::BackgroundOrigin,
+            CssProperty::BackgroundClip(_) => CssPropertyType::BackgroundClip,
             CssProperty::TextColor(_) => CssPropertyType::TextColor,
             CssProperty::Border(_) => CssPropertyType::Border,
+            CssProperty::BorderStyle(_) => CssPropertyType::BorderStyle,
+            CssProperty::BorderImage(_) => CssPropertyType::BorderImage,
             CssProperty::Background(_) => CssPropertyType::Background,
             CssProperty::FontSize(_) => CssPropertyType::FontSize,
+            CssProperty::FontWeight(_) => CssPropertyType::FontWeight,
+            CssProperty::FontStyle(_) => CssPropertyType::FontStyle,
+            CssProperty::FontStretch(_) => CssPropertyType::FontStretch,
             CssProperty::FontFamily(_) => CssPropertyType::FontFamily,
             CssProperty::TextAlign(_) => CssPropertyType::TextAlign,
+            CssProperty::TextDecoration(_) => CssPropertyType::TextDecoration,
+            CssProperty::TextShadow(_) => CssPropertyType::TextShadow,
             CssProperty::LetterSpacing(_) => CssPropertyType::LetterSpacing,
             CssProperty::WordSpacing(_) => CssPropertyType::WordSpacing,
             CssProperty::TabWidth(_) => CssPropertyType::TabWidth,
             CssProperty::BoxShadow(_) => CssPropertyType::BoxShadow,
             CssProperty::LineHeight(_) => CssPropertyType::LineHeight,
             CssProperty::Cursor(_) => CssPropertyType::Cursor,
+            CssProperty::Display(_) => CssPropertyType::Display,
+            CssProperty::Visibility(_) => CssPropertyType::Visibility,
+            CssProperty::GridTemplateColumns(_) => CssPropertyType::GridTemplateColumns,
+            CssProperty::GridTemplateRows(_) => CssPropertyType::GridTemplateRows,
+            CssProperty::GridTemplateAreas(_) => CssPropertyType::GridTemplateAreas,
+            CssProperty::GridGap(_) => CssPropertyType::GridGap,
+            CssProperty::GridRow(_) => CssPropertyType::GridRow,
+            CssProperty::GridColumn(_) => CssPropertyType::GridColumn,
+            CssProperty::GridArea(_) => CssPropertyType::GridArea,
             CssProperty::Width(_) => CssPropertyType::Width,
             CssProperty::Height(_) => CssPropertyType::Height,
             CssProperty::MinWidth(_) => CssPropertyType::MinWidth,
@@ -494,6 +801,8 @@ impl CssProperty {
             CssProperty::MaxWidth(_) => CssPropertyType::MaxWidth,
             CssProperty::MaxHeight(_) => CssPropertyType::MaxHeight,
             CssProperty::Position(_) => CssPropertyType::Position,
+            CssProperty::Float(_) => CssPropertyType::Float,
+            CssProperty::ZIndex(_) => CssPropertyType::ZIndex,
             CssProperty::Top(_) => CssPropertyType::Top,
             CssProperty::Right(_) => CssPropertyType::Right,
             CssProperty::Left(_) => CssPropertyType::Left,
@@ -502,31 +811,130 @@ impl CssProperty {
             CssProperty::Margin(_) => CssPropertyType::Margin,
             CssProperty::FlexWrap(_) => CssPropertyType::FlexWrap,
             CssProperty::FlexDirection(_) => CssPropertyType::FlexDirection,
+            CssProperty::Flex(_) => CssPropertyType::Flex,
             CssProperty::FlexGrow(_) => CssPropertyType::FlexGrow,
             CssProperty::FlexShrink(_) => CssPropertyType::FlexShrink,
+            CssProperty::FlexBasis(_) => CssPropertyType::FlexBasis,
+            CssProperty::Order(_) => CssPropertyType::Order,
             CssProperty::JustifyContent(_) => CssPropertyType::JustifyContent,
             CssProperty::AlignItems(_) => CssPropertyType::AlignItems,
+            CssProperty::AlignSelf(_) => CssPropertyType::AlignSelf,
             CssProperty::AlignContent(_) => CssPropertyType::AlignContent,
+            CssProperty::Gap(_) => CssPropertyType::Gap,
             CssProperty::Overflow(_) => CssPropertyType::Overflow,
+            CssProperty::Transform(_) => CssPropertyType::Transform,
+            CssProperty::TransformOrigin(_) => CssPropertyType::TransformOrigin,
+            CssProperty::Opacity(_) => CssPropertyType::Opacity,
+            CssProperty::Transition(_) => CssPropertyType::Transition,
+            CssProperty::Animation(_) => CssPropertyType::Animation,
+        }
+    }
+
+    /// Merges the sides of two `padding`, `margin`, `border`, `border-style`, `box-shadow`,
+    /// `overflow` or `gap` properties, i.e. `border-top` followed by `border-left` sets both sides.
+    /// The fields of `text-decoration` are merged the same way (`text-decoration-color` only
+    /// overrides the color). Any other property is simply replaced by `other`.
+    pub fn merge(&mut self, other: &Self) {
+        use self::CssProperty::*;
+        match (self, other) {
+            (Padding(a), Padding(b)) => a.merge_sides(b),
+            (Margin(a), Margin(b)) => a.merge_sides(b),
+            (Border(a), Border(b)) => a.merge_sides(b),
+            (BorderStyle(a), BorderStyle(b)) => a.merge_sides(b),
+            (BoxShadow(a), BoxShadow(b)) => a.merge_sides(b),
+            (Overflow(a), Overflow(b)) => a.merge_sides(b),
+            (Gap(a), Gap(b)) => a.merge_sides(b),
+            (TextDecoration(a), TextDecoration(b)) => a.merge_fields(b),
+            (this, other) => *this = other.clone(),
         }
     }
+
+    /// Interpolates between two values of the same property, `t = 0.0` returns `self`,
+    /// `t = 1.0` returns `other`. Only colors, lengths and the opacity can be interpolated.
+    ///
+    /// Returns `None` if the properties can't be interpolated (i.e. `position: absolute`
+    /// to `position: relative`) - animations switch between the two values halfway instead.
+    pub fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+
+        use self::CssProperty::*;
+
+        macro_rules! interpolate_sides {($a:expr, $b:expr, $struct:ident) => ({
+            let mut result = $struct::default();
+            result.top = interpolate_optional_pixel_value($a.top, $b.top, t)?;
+            result.bottom = interpolate_optional_pixel_value($a.bottom, $b.bottom, t)?;
+            result.left = interpolate_optional_pixel_value($a.left, $b.left, t)?;
+            result.right = interpolate_optional_pixel_value($a.right, $b.right, t)?;
+            result
+        })}
+
+        let interpolated = match (self, other) {
+            (TextColor(a), TextColor(b)) => TextColor(StyleTextColor(a.0.interpolate(&b.0, t))),
+            (Background(a), Background(b)) => Background(a.interpolate(b, t)?),
+            (BackgroundPosition(a), BackgroundPosition(b)) => BackgroundPosition(a.interpolate(b, t)?),
+            (FontSize(a), FontSize(b)) => FontSize(StyleFontSize(a.0.interpolate(&b.0, t))),
+            (FontWeight(a), FontWeight(b)) => FontWeight(a.interpolate(b, t)),
+            (LetterSpacing(a), LetterSpacing(b)) => LetterSpacing(StyleLetterSpacing(a.0.interpolate(&b.0, t))),
+            (WordSpacing(a), WordSpacing(b)) => WordSpacing(StyleWordSpacing(a.0.interpolate(&b.0, t))),
+            (LineHeight(a), LineHeight(b)) => LineHeight(StyleLineHeight(a.0.interpolate(&b.0, t))),
+            (TabWidth(a), TabWidth(b)) => TabWidth(StyleTabWidth(a.0.interpolate(&b.0, t))),
+            (Width(a), Width(b)) => Width(LayoutWidth::Exact(a.get_exact()?.interpolate(&b.get_exact()?, t))),
+            (Height(a), Height(b)) => Height(LayoutHeight::Exact(a.get_exact()?.interpolate(&b.get_exact()?, t))),
+            (MinWidth(a), MinWidth(b)) => MinWidth(LayoutMinWidth(a.0.interpolate(&b.0, t))),
+            (MinHeight(a), MinHeight(b)) => MinHeight(LayoutMinHeight(a.0.interpolate(&b.0, t))),
+            (MaxWidth(a), MaxWidth(b)) => MaxWidth(LayoutMaxWidth(a.0.interpolate(&b.0, t))),
+            (MaxHeight(a), MaxHeight(b)) => MaxHeight(LayoutMaxHeight(a.0.interpolate(&b.0, t))),
+            (Top(a), Top(b)) => Top(LayoutTop(a.0.interpolate(&b.0, t))),
+            (Right(a), Right(b)) => Right(LayoutRight(a.0.interpolate(&b.0, t))),
+            (Left(a), Left(b)) => Left(LayoutLeft(a.0.interpolate(&b.0, t))),
+            (Bottom(a), Bottom(b)) => Bottom(LayoutBottom(a.0.interpolate(&b.0, t))),
+            (Padding(a), Padding(b)) => Padding(interpolate_sides!(a, b, LayoutPadding)),
+            (Margin(a), Margin(b)) => Margin(interpolate_sides!(a, b, LayoutMargin)),
+            (Gap(a), Gap(b)) => Gap(LayoutGap {
+                row: interpolate_optional_pixel_value(a.row, b.row, t)?,
+                column: interpolate_optional_pixel_value(a.column, b.column, t)?,
+            }),
+            (FlexBasis(LayoutFlexBasis::Exact(a)), FlexBasis(LayoutFlexBasis::Exact(b))) => FlexBasis(LayoutFlexBasis::Exact(a.interpolate(b, t))),
+            (Opacity(a), Opacity(b)) => Opacity(StyleOpacity(a.0.interpolate(&b.0, t))),
+            _ => return None,
+        };
+
+        Some(interpolated)
+    }
+}
+
+/// Interpolates one side of a `padding` or `margin`, fails if only one of the two sides is set
+fn interpolate_optional_pixel_value(a: Option<PixelValue>, b: Option<PixelValue>, t: f32) -> Option<Option<PixelValue>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(Some(a.interpolate(&b, t))),
+        (None, None) => Some(None),
+        _ => None,
+    }
 }
 
 impl_from!(StyleBorderRadius, CssProperty::BorderRadius);
-impl_from!(StyleBackground, CssProperty::Background);
 impl_from!(StyleBoxShadow, CssProperty::BoxShadow);
 impl_from!(StyleBorder, CssProperty::Border);
+impl_from!(StyleBorderStyle, CssProperty::BorderStyle);
+impl_from!(StyleBorderImage, CssProperty::BorderImage);
 impl_from!(StyleFontSize, CssProperty::FontSize);
 impl_from!(StyleFontFamily, CssProperty::FontFamily);
+impl_from!(StyleFontWeight, CssProperty::FontWeight);
+impl_from!(StyleFontStyle, CssProperty::FontStyle);
+impl_from!(StyleFontStretch, CssProperty::FontStretch);
 impl_from!(StyleTextAlignmentHorz, CssProperty::TextAlign);
+impl_from!(StyleTextDecoration, CssProperty::TextDecoration);
+impl_from!(StyleTextShadow, CssProperty::TextShadow);
 impl_from!(StyleLineHeight, CssProperty::LineHeight);
 impl_from!(StyleTabWidth, CssProperty::TabWidth);
 impl_from!(StyleWordSpacing, CssProperty::WordSpacing);
 impl_from!(StyleLetterSpacing, CssProperty::LetterSpacing);
-impl_from!(StyleBackgroundSize, CssProperty::BackgroundSize);
-impl_from!(StyleBackgroundRepeat, CssProperty::BackgroundRepeat);
 impl_from!(StyleTextColor, CssProperty::TextColor);
 impl_from!(StyleCursor, CssProperty::Cursor);
+impl_from!(StyleTransform, CssProperty::Transform);
+impl_from!(StyleTransformOrigin, CssProperty::TransformOrigin);
+impl_from!(StyleOpacity, CssProperty::Opacity);
+impl_from!(StyleTransition, CssProperty::Transition);
+impl_from!(StyleAnimation, CssProperty::Animation);
 
 impl_from!(LayoutOverflow, CssProperty::Overflow);
 impl_from!(LayoutWidth, CssProperty::Width);
@@ -537,6 +945,17 @@ impl_from!(LayoutMaxWidth, CssProperty::MaxWidth);
 impl_from!(LayoutMaxHeight, CssProperty::MaxHeight);
 
 impl_from!(LayoutPosition, CssProperty::Position);
+impl_from!(LayoutFloat, CssProperty::Float);
+impl_from!(LayoutDisplay, CssProperty::Display);
+impl_from!(StyleVisibility, CssProperty::Visibility);
+impl_from!(LayoutGridTemplateColumns, CssProperty::GridTemplateColumns);
+impl_from!(LayoutGridTemplateRows, CssProperty::GridTemplateRows);
+impl_from!(LayoutGridTemplateAreas, CssProperty::GridTemplateAreas);
+impl_from!(LayoutGridGap, CssProperty::GridGap);
+impl_from!(LayoutGridRow, CssProperty::GridRow);
+impl_from!(LayoutGridColumn, CssProperty::GridColumn);
+impl_from!(LayoutGridArea, CssProperty::GridArea);
+impl_from!(LayoutZIndex, CssProperty::ZIndex);
 impl_from!(LayoutTop, CssProperty::Top);
 impl_from!(LayoutBottom, CssProperty::Bottom);
 impl_from!(LayoutRight, CssProperty::Right);
@@ -547,11 +966,16 @@ impl_from!(LayoutMargin, CssProperty::Margin);
 
 impl_from!(LayoutWrap, CssProperty::FlexWrap);
 impl_from!(LayoutDirection, CssProperty::FlexDirection);
+impl_from!(LayoutFlex, CssProperty::Flex);
 impl_from!(LayoutFlexGrow, CssProperty::FlexGrow);
 impl_from!(LayoutFlexShrink, CssProperty::FlexShrink);
+impl_from!(LayoutFlexBasis, CssProperty::FlexBasis);
+impl_from!(LayoutOrder, CssProperty::Order);
 impl_from!(LayoutJustifyContent, CssProperty::JustifyContent);
 impl_from!(LayoutAlignItems, CssProperty::AlignItems);
+impl_from!(LayoutAlignSelf, CssProperty::AlignSelf);
 impl_from!(LayoutAlignContent, CssProperty::AlignContent);
+impl_from!(LayoutGap, CssProperty::Gap);
 
 /// Multiplier for floating point accuracy. Elements such as px or %
 /// are only accurate until a certain number of decimal points, therefore
@@ -566,12 +990,101 @@ const FP_PRECISION_MULTIPLIER_CONST: isize = FP_PRECISION_MULTIPLIER as isize;
 pub struct PixelValue {
     pub metric: SizeMetric,
     pub number: FloatValue,
+    /// Set if the value is a `calc()` expression, in which case `metric` and `number`
+    /// are unused. The expression itself is stored out of line, see `CalcId`.
+    pub calc: Option<CalcId>,
+}
+
+/// ID of an interned `calc()` expression. Expressions are stored in a global table (instead
+/// of inline in the `PixelValue`), so that plain lengths stay small and `Copy`. Equal expressions
+/// always get the same ID, so two `PixelValue`s can still be compared without looking them up.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalcId {
+    id: NonZeroU32,
+}
+
+/// Lengths can only be added to each other or multiplied / divided by plain numbers,
+/// so every `calc()` expression can be simplified to a sum of lengths with different metrics:
+/// `calc((100% - 20px) / 2)` is stored as `50% - 10px`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CalcExpression {
+    /// Sum of the terms, each term has a different metric
+    Sum(Vec<(SizeMetric, FloatValue)>),
+    /// Result of `PixelValue::interpolate()` between values with different metrics. Storing
+    /// the (quantized) interpolation factor instead of the interpolated terms means that
+    /// animations only ever create a limited number of expressions.
+    Mix { from: PixelValue, to: PixelValue, t: FloatValue },
+}
+
+/// Interned `calc()` expressions, the `CalcId` is the index + 1. Expressions are never
+/// removed, since it isn't known whether any `PixelValue` still refers to them.
+static CALC_EXPRESSIONS: AtomicPtr<Mutex<Vec<CalcExpression>>> = AtomicPtr::new(ptr::null_mut());
+
+fn get_calc_expressions() -> &'static Mutex<Vec<CalcExpression>> {
+    let mut expressions = CALC_EXPRESSIONS.load(AtomicOrdering::Acquire);
+    if expressions.is_null() {
+        let new_expressions = Box::into_raw(Box::new(Mutex::new(Vec::new())));
+        expressions = match CALC_EXPRESSIONS.compare_exchange(ptr::null_mut(), new_expressions, AtomicOrdering::AcqRel, AtomicOrdering::Acquire) {
+            Ok(_) => new_expressions,
+            Err(existing) => {
+                // Another thread was faster, the table is never deallocated after this point
+                unsafe { drop(Box::from_raw(new_expressions)); }
+                existing
+            }
+        };
+    }
+    unsafe { &*expressions }
+}
+
+impl CalcId {
+
+    fn new(expression: CalcExpression) -> Self {
+        let mut expressions = get_calc_expressions().lock().unwrap();
+        let index = match expressions.iter().position(|e| *e == expression) {
+            Some(index) => index,
+            None => {
+                expressions.push(expression);
+                expressions.len() - 1
+            }
+        };
+        Self { id: NonZeroU32::new(index as u32 + 1).unwrap() }
+    }
+
+    fn get_expression(&self) -> CalcExpression {
+        get_calc_expressions().lock().unwrap()[self.id.get() as usize - 1].clone()
+    }
+}
+
+/// Adds up terms with the same metric, so that each metric occurs at most once
+fn sum_calc_terms<I: IntoIterator<Item = (SizeMetric, f32)>>(terms: I) -> Vec<(SizeMetric, f32)> {
+    let mut summed_terms = Vec::<(SizeMetric, f32)>::new();
+    for (metric, number) in terms {
+        match summed_terms.iter_mut().find(|(m, _)| *m == metric) {
+            Some(term) => term.1 += number,
+            None => summed_terms.push((metric, number)),
+        }
+    }
+    summed_terms
 }
 
 // Manual Debug implementation, because the auto-generated one is nearly unreadable
 impl fmt::Debug for PixelValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}{:?}", self.number, self.metric)
+        if self.calc.is_none() {
+            return write!(f, "{:?}{:?}", self.number, self.metric);
+        }
+
+        write!(f, "calc(")?;
+        for (idx, (metric, number)) in self.get_terms().into_iter().enumerate() {
+            if idx == 0 {
+                write!(f, "{}{:?}", number, metric)?;
+            } else if number < 0.0 {
+                write!(f, " - {}{:?}", -number, metric)?;
+            } else {
+                write!(f, " + {}{:?}", number, metric)?;
+            }
+        }
+        write!(f, ")")
     }
 }
 
@@ -581,7 +1094,13 @@ impl fmt::Debug for SizeMetric {
         match self {
             Px => write!(f, "px"),
             Pt => write!(f, "pt"),
-            Em => write!(f, "pt"),
+            Em => write!(f, "em"),
+            Percent => write!(f, "%"),
+            Rem => write!(f, "rem"),
+            Vw => write!(f, "vw"),
+            Vh => write!(f, "vh"),
+            Vmin => write!(f, "vmin"),
+            Vmax => write!(f, "vmax"),
         }
     }
 }
@@ -620,6 +1139,7 @@ impl PixelValue {
         Self {
             metric: metric,
             number: FloatValue::const_new(value),
+            calc: None,
         }
     }
 
@@ -638,21 +1158,180 @@ impl PixelValue {
         Self::from_metric(SizeMetric::Pt, value)
     }
 
+    /// Percentage of the containing block, i.e. `PixelValue::percent(50.0)` = `50%`
+    #[inline]
+    pub fn percent(value: f32) -> Self {
+        Self::from_metric(SizeMetric::Percent, value)
+    }
+
+    #[inline]
+    pub fn rem(value: f32) -> Self {
+        Self::from_metric(SizeMetric::Rem, value)
+    }
+
+    #[inline]
+    pub fn vw(value: f32) -> Self {
+        Self::from_metric(SizeMetric::Vw, value)
+    }
+
+    #[inline]
+    pub fn vh(value: f32) -> Self {
+        Self::from_metric(SizeMetric::Vh, value)
+    }
+
+    #[inline]
+    pub fn vmin(value: f32) -> Self {
+        Self::from_metric(SizeMetric::Vmin, value)
+    }
+
+    #[inline]
+    pub fn vmax(value: f32) -> Self {
+        Self::from_metric(SizeMetric::Vmax, value)
+    }
+
     #[inline]
     pub fn from_metric(metric: SizeMetric, value: f32) -> Self {
         Self {
             metric: metric,
             number: FloatValue::new(value),
+            calc: None,
+        }
+    }
+
+    /// Creates the value of a `calc()` expression from the sum of the `terms`, i.e.
+    /// `calc(100% - 240px)` = `PixelValue::calc(&[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -240.0)])`.
+    ///
+    /// Terms with the same metric are added together, if only one metric is left,
+    /// the result is a plain value (`calc(1px + 2px)` = `3px`).
+    pub fn calc(terms: &[(SizeMetric, f32)]) -> Self {
+        let summed_terms = sum_calc_terms(terms.iter().cloned());
+        match summed_terms.as_slice() {
+            [] => Self::px(0.0),
+            [(metric, number)] => Self::from_metric(*metric, *number),
+            _ => {
+                let terms = summed_terms.iter().map(|(metric, number)| (*metric, FloatValue::new(*number))).collect();
+                Self::from_calc_expression(CalcExpression::Sum(terms))
+            },
+        }
+    }
+
+    fn from_calc_expression(expression: CalcExpression) -> Self {
+        Self {
+            metric: SizeMetric::Px,
+            number: FloatValue::const_new(0),
+            calc: Some(CalcId::new(expression)),
+        }
+    }
+
+    /// Returns the terms of the value, each with a different metric, i.e.
+    /// `[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -240.0)]` for `calc(100% - 240px)`.
+    /// Plain values only have one term.
+    pub fn get_terms(&self) -> Vec<(SizeMetric, f32)> {
+        let calc = match self.calc {
+            Some(calc) => calc,
+            None => return vec![(self.metric, self.number.get())],
+        };
+
+        match calc.get_expression() {
+            CalcExpression::Sum(terms) => terms.into_iter().map(|(metric, number)| (metric, number.get())).collect(),
+            CalcExpression::Mix { from, to, t } => {
+                let t = t.get();
+                sum_calc_terms(
+                    from.get_terms().into_iter().map(|(metric, number)| (metric, number * (1.0 - t)))
+                    .chain(to.get_terms().into_iter().map(|(metric, number)| (metric, number * t)))
+                )
+            },
         }
     }
 
     /// Returns the value of the SizeMetric in pixels
+    ///
+    /// Relative metrics need to know what they are relative to, so they are resolved
+    /// against `PixelResolveContext::default()`: `%` and the viewport metrics are
+    /// always 0, `rem` is relative to the default font size. Use `to_pixels_relative()`
+    /// to resolve them properly.
     #[inline]
     pub fn to_pixels(&self) -> f32 {
-        match self.metric {
-            SizeMetric::Px => { self.number.get() },
-            SizeMetric::Pt => { (self.number.get()) * PT_TO_PX },
-            SizeMetric::Em => { (self.number.get()) * EM_HEIGHT },
+        self.to_pixels_relative(0.0, &PixelResolveContext::default())
+    }
+
+    /// Returns the value of the SizeMetric in pixels, `percent_base` is the length
+    /// (usually the width or height of the containing block) that `100%` refers to.
+    ///
+    /// `calc()` expressions are evaluated by resolving and adding up all of their terms.
+    pub fn to_pixels_relative(&self, percent_base: f32, context: &PixelResolveContext) -> f32 {
+        if self.calc.is_none() {
+            return metric_to_pixels(self.metric, self.number.get(), percent_base, context);
+        }
+
+        self.get_terms().into_iter()
+            .map(|(metric, number)| metric_to_pixels(metric, number, percent_base, context))
+            .sum()
+    }
+
+    /// Same as `to_pixels_relative()`, but returns a new `px` value
+    #[inline]
+    pub fn resolve(&self, percent_base: f32, context: &PixelResolveContext) -> Self {
+        Self::px(self.to_pixels_relative(percent_base, context))
+    }
+
+    /// Returns whether the value can only be resolved if the size of the containing block is known
+    #[inline]
+    pub fn is_percentage(&self) -> bool {
+        match self.calc {
+            Some(_) => self.get_terms().iter().any(|(metric, _)| *metric == SizeMetric::Percent),
+            None => self.metric == SizeMetric::Percent,
+        }
+    }
+
+    /// Interpolates linearly, `t = 0.0` returns `self`, `t = 1.0` returns `other`.
+    ///
+    /// Values with different metrics are interpolated as a `calc()` expression, i.e. halfway
+    /// between `100px` and `50%` is `calc(50px + 25%)`.
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        if t <= 0.0 {
+            return *self;
+        } else if t >= 1.0 {
+            return *other;
+        }
+
+        if self.calc.is_none() && other.calc.is_none() && self.metric == other.metric {
+            return Self { number: self.number.interpolate(&other.number, t), .. *self };
+        }
+
+        Self::from_calc_expression(CalcExpression::Mix { from: *self, to: *other, t: FloatValue::new(t) })
+    }
+}
+
+fn metric_to_pixels(metric: SizeMetric, number: f32, percent_base: f32, context: &PixelResolveContext) -> f32 {
+    let viewport = context.viewport_size;
+    match metric {
+        SizeMetric::Px => { number },
+        SizeMetric::Pt => { number * PT_TO_PX },
+        SizeMetric::Em => { number * EM_HEIGHT },
+        SizeMetric::Percent => { number / 100.0 * percent_base },
+        SizeMetric::Rem => { number * context.root_font_size },
+        SizeMetric::Vw => { number / 100.0 * viewport.width },
+        SizeMetric::Vh => { number / 100.0 * viewport.height },
+        SizeMetric::Vmin => { number / 100.0 * viewport.width.min(viewport.height) },
+        SizeMetric::Vmax => { number / 100.0 * viewport.width.max(viewport.height) },
+    }
+}
+
+/// Lengths that the relative metrics (`rem`, `vw`, `vh`, `vmin`, `vmax`) are resolved against
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+pub struct PixelResolveContext {
+    /// Font size of the root node in pixels, `1rem` = `root_font_size`
+    pub root_font_size: f32,
+    /// Logical size of the window, `100vw` = `viewport_size.width`
+    pub viewport_size: LayoutSize,
+}
+
+impl Default for PixelResolveContext {
+    fn default() -> Self {
+        Self {
+            root_font_size: EM_HEIGHT,
+            viewport_size: LayoutSize::zero(),
         }
     }
 }
@@ -685,6 +1364,11 @@ impl PercentageValue {
     pub fn get(&self) -> f32 {
         self.number.get()
     }
+
+    /// Interpolates linearly, `t = 0.0` returns `self`, `t = 1.0` returns `other`
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self { number: self.number.interpolate(&other.number, t) }
+    }
 }
 
 /// Wrapper around an f32 value that is internally casted to an isize,
@@ -709,6 +1393,11 @@ impl FloatValue {
     pub fn get(&self) -> f32 {
         self.number as f32 / FP_PRECISION_MULTIPLIER
     }
+
+    /// Interpolates linearly, `t = 0.0` returns `self`, `t = 1.0` returns `other`
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        Self::new(self.get() + (other.get() - self.get()) * t)
+    }
 }
 
 impl From<f32> for FloatValue {
@@ -723,6 +1412,18 @@ pub enum SizeMetric {
     Px,
     Pt,
     Em,
+    /// Percentage of the width or height of the containing block
+    Percent,
+    /// Multiple of the font size of the root node
+    Rem,
+    /// Percentage of the viewport width
+    Vw,
+    /// Percentage of the viewport height
+    Vh,
+    /// Percentage of the smaller viewport dimension
+    Vmin,
+    /// Percentage of the larger viewport dimension
+    Vmax,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -756,6 +1457,152 @@ impl Default for StyleBackgroundRepeat {
     }
 }
 
+/// Represents a `background-position` attribute, the offset of a background
+/// image from the top left corner of the background positioning area.
+///
+/// Keywords are stored as percentages (`right` = `100%`, `center` = `50%`), offsets from the
+/// right / bottom edge as `calc()` expressions, i.e. `right 10px` = `calc(100% - 10px)`.
+/// Percentages refer to the size of the positioning area minus the size of the image,
+/// so `100% 100%` aligns the bottom right corner of the image with the one of the area.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBackgroundPosition {
+    pub horizontal: PixelValue,
+    pub vertical: PixelValue,
+}
+
+impl Default for StyleBackgroundPosition {
+    fn default() -> Self {
+        StyleBackgroundPosition {
+            horizontal: PixelValue::const_from_metric(SizeMetric::Percent, 0),
+            vertical: PixelValue::const_from_metric(SizeMetric::Percent, 0),
+        }
+    }
+}
+
+/// Represents a `background-origin` attribute, the box that background images are positioned in
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleBackgroundOrigin {
+    BorderBox,
+    PaddingBox,
+    ContentBox,
+}
+
+impl Default for StyleBackgroundOrigin {
+    fn default() -> Self {
+        StyleBackgroundOrigin::PaddingBox
+    }
+}
+
+/// Represents a `background-clip` attribute, the box that a background layer is clipped to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleBackgroundClip {
+    BorderBox,
+    PaddingBox,
+    ContentBox,
+}
+
+impl Default for StyleBackgroundClip {
+    fn default() -> Self {
+        StyleBackgroundClip::BorderBox
+    }
+}
+
+// Comma-separated list of background values, one for each background layer
+macro_rules! impl_background_layer_list {($list:ident, $layer:ident, $property:ident) => (
+    impl $list {
+        /// Returns the value for the background layer at `layer_idx`. If the list
+        /// has less values than there are layers, the values are repeated.
+        pub fn get(&self, layer_idx: usize) -> Option<&$layer> {
+            if self.0.is_empty() {
+                None
+            } else {
+                Some(&self.0[layer_idx % self.0.len()])
+            }
+        }
+    }
+
+    impl From<$layer> for $list {
+        fn from(layer: $layer) -> Self {
+            $list(vec![layer])
+        }
+    }
+
+    impl From<$layer> for CssProperty {
+        fn from(layer: $layer) -> Self {
+            CssProperty::$property($list::from(layer))
+        }
+    }
+
+    impl_from!($list, CssProperty::$property);
+)}
+
+/// Represents a `background` or `background-image` attribute: the background layers of a node,
+/// where the first layer is drawn on top, i.e. `background: image("logo"), linear-gradient(red, blue)`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBackgrounds(pub Vec<StyleBackground>);
+
+/// Represents a `background-size` attribute (one value per background layer)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBackgroundSizes(pub Vec<StyleBackgroundSize>);
+
+/// Represents a `background-repeat` attribute (one value per background layer)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBackgroundRepeats(pub Vec<StyleBackgroundRepeat>);
+
+/// Represents a `background-position` attribute (one value per background layer)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBackgroundPositions(pub Vec<StyleBackgroundPosition>);
+
+/// Represents a `background-origin` attribute (one value per background layer)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBackgroundOrigins(pub Vec<StyleBackgroundOrigin>);
+
+/// Represents a `background-clip` attribute (one value per background layer)
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBackgroundClips(pub Vec<StyleBackgroundClip>);
+
+impl_background_layer_list!(StyleBackgrounds, StyleBackground, Background);
+impl_background_layer_list!(StyleBackgroundSizes, StyleBackgroundSize, BackgroundSize);
+impl_background_layer_list!(StyleBackgroundRepeats, StyleBackgroundRepeat, BackgroundRepeat);
+impl_background_layer_list!(StyleBackgroundPositions, StyleBackgroundPosition, BackgroundPosition);
+impl_background_layer_list!(StyleBackgroundOrigins, StyleBackgroundOrigin, BackgroundOrigin);
+impl_background_layer_list!(StyleBackgroundClips, StyleBackgroundClip, BackgroundClip);
+
+impl StyleBackgrounds {
+
+    /// Returns the IDs of all images used in the background layers
+    pub fn get_css_image_ids<'a>(&'a self) -> impl Iterator<Item = &'a CssImageId> + 'a {
+        self.0.iter().filter_map(|layer| layer.get_css_image_id())
+    }
+
+    /// Interpolates the background colors layer by layer, fails if the two backgrounds
+    /// have a different number of layers or if any of the layers isn't a plain color
+    pub fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        if self.0.len() != other.0.len() {
+            return None;
+        }
+        self.0.iter().zip(other.0.iter()).map(|layers| match layers {
+            (StyleBackground::Color(a), StyleBackground::Color(b)) => Some(StyleBackground::Color(a.interpolate(b, t))),
+            _ => None,
+        }).collect::<Option<Vec<_>>>().map(StyleBackgrounds)
+    }
+}
+
+impl StyleBackgroundPositions {
+
+    /// Interpolates the background positions layer by layer, fails if
+    /// the two lists have a different number of positions
+    pub fn interpolate(&self, other: &Self, t: f32) -> Option<Self> {
+        if self.0.len() != other.0.len() {
+            return None;
+        }
+        Some(StyleBackgroundPositions(self.0.iter().zip(other.0.iter()).map(|(a, b)| StyleBackgroundPosition {
+            horizontal: a.horizontal.interpolate(&b.horizontal, t),
+            vertical: a.vertical.interpolate(&b.vertical, t),
+        }).collect()))
+    }
+}
+
 /// Represents a `color` attribute
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StyleTextColor(pub ColorU);
@@ -774,14 +1621,19 @@ macro_rules! merge_struct {($struct_name:ident) => (
 impl $struct_name {
     pub fn merge(a: &mut Option<$struct_name>, b: &$struct_name) {
        if let Some(ref mut existing) = a {
-           if b.top.is_some() { existing.top = b.top; }
-           if b.bottom.is_some() { existing.bottom = b.bottom; }
-           if b.left.is_some() { existing.left = b.left; }
-           if b.right.is_some() { existing.right = b.right; }
+           existing.merge_sides(b);
        } else {
            *a = Some(*b);
        }
     }
+
+    /// Overwrites the sides (top, left, right, bottom) that are set in `other`
+    pub fn merge_sides(&mut self, other: &$struct_name) {
+        if other.top.is_some() { self.top = other.top; }
+        if other.bottom.is_some() { self.bottom = other.bottom; }
+        if other.left.is_some() { self.left = other.left; }
+        if other.right.is_some() { self.right = other.right; }
+    }
 })}
 
 macro_rules! struct_all {($struct_name:ident, $field_type:ty) => (
@@ -811,6 +1663,31 @@ pub struct LayoutMargin {
     pub right: Option<PixelValue>,
 }
 
+/// Wrapper for the `row-gap` + `column-gap` + `gap` property: `row` is the vertical,
+/// `column` the horizontal space between two items or lines of a flex container
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGap {
+    pub row: Option<PixelValue>,
+    pub column: Option<PixelValue>,
+}
+
+impl LayoutGap {
+
+    pub fn merge(a: &mut Option<Self>, b: &Self) {
+        if let Some(ref mut existing) = a {
+            existing.merge_sides(b);
+        } else {
+            *a = Some(*b);
+        }
+    }
+
+    /// Overwrites the directions (row, column) that are set in `other`
+    pub fn merge_sides(&mut self, other: &Self) {
+        if other.row.is_some() { self.row = other.row; }
+        if other.column.is_some() { self.column = other.column; }
+    }
+}
+
 /// Wrapper for the `overflow-{x,y}` + `overflow` property
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LayoutOverflow {
@@ -838,6 +1715,12 @@ impl LayoutOverflow {
         }
     }
 
+    /// Overwrites the directions (horizontal, vertical) that are set in `other`
+    pub fn merge_sides(&mut self, other: &Self) {
+        if other.horizontal.is_some() { self.horizontal = other.horizontal; }
+        if other.vertical.is_some() { self.vertical = other.vertical; }
+    }
+
     pub fn needs_horizontal_scrollbar(&self, currently_overflowing_horz: bool) -> bool {
         self.horizontal.unwrap_or_default().needs_scrollbar(currently_overflowing_horz)
     }
@@ -868,19 +1751,45 @@ struct_all!(StyleBorder, StyleBorderSide);
 
 impl StyleBorder {
 
+    /// Overrides the styles of the sides with the ones set in the `border-style`. Sides that
+    /// only have a style, but no `border`, are drawn with the default width (`3px`) and color.
+    pub fn with_styles(&self, styles: &StyleBorderStyle) -> Self {
+
+        fn with_style(side: Option<StyleBorderSide>, style: Option<BorderStyle>) -> Option<StyleBorderSide> {
+            match (side, style) {
+                (Some(side), Some(style)) => Some(StyleBorderSide { border_style: style, .. side }),
+                (None, Some(style)) => Some(StyleBorderSide {
+                    border_width: DEFAULT_BORDER_WIDTH,
+                    border_style: style,
+                    border_color: DEFAULT_BORDER_COLOR,
+                }),
+                (side, None) => side,
+            }
+        }
+
+        StyleBorder {
+            top: with_style(self.top, styles.top),
+            left: with_style(self.left, styles.left),
+            bottom: with_style(self.bottom, styles.bottom),
+            right: with_style(self.right, styles.right),
+        }
+    }
+
     /// Returns the merged offsets and details for the top, left,
     /// right and bottom styles - necessary, so we can combine `border-top`,
     /// `border-left`, etc. into one border
-    pub fn get_webrender_border(&self, border_radius: Option<StyleBorderRadius>) -> Option<(LayoutSideOffsets, BorderDetails)> {
+    ///
+    /// The `resolve_context` is used to resolve the relative units (`rem`, `vw`, ...) of the border widths.
+    pub fn get_webrender_border(&self, border_radius: Option<StyleBorderRadius>, resolve_context: &PixelResolveContext) -> Option<(LayoutSideOffsets, BorderDetails)> {
         match (self.top, self.left, self.bottom, self.right) {
             (None, None, None, None) => None,
             (top, left, bottom, right) => {
 
                 // Widths
-                let border_width_top = top.and_then(|top|  Some(top.border_width.to_pixels())).unwrap_or(0.0);
-                let border_width_bottom = bottom.and_then(|bottom|  Some(bottom.border_width.to_pixels())).unwrap_or(0.0);
-                let border_width_left = left.and_then(|left|  Some(left.border_width.to_pixels())).unwrap_or(0.0);
-                let border_width_right = right.and_then(|right|  Some(right.border_width.to_pixels())).unwrap_or(0.0);
+                let border_width_top = top.and_then(|top|  Some(top.border_width.to_pixels_relative(0.0, resolve_context))).unwrap_or(0.0);
+                let border_width_bottom = bottom.and_then(|bottom|  Some(bottom.border_width.to_pixels_relative(0.0, resolve_context))).unwrap_or(0.0);
+                let border_width_left = left.and_then(|left|  Some(left.border_width.to_pixels_relative(0.0, resolve_context))).unwrap_or(0.0);
+                let border_width_right = right.and_then(|right|  Some(right.border_width.to_pixels_relative(0.0, resolve_context))).unwrap_or(0.0);
 
                 // Color
                 let border_color_top = top.and_then(|top| Some(top.border_color.into())).unwrap_or(DEFAULT_BORDER_COLOR);
@@ -916,19 +1825,141 @@ impl StyleBorder {
 
 const DEFAULT_BORDER_STYLE: BorderStyle = BorderStyle::Solid;
 const DEFAULT_BORDER_COLOR: ColorU = ColorU { r: 0, g: 0, b: 0, a: 255 };
+const DEFAULT_BORDER_WIDTH: PixelValue = PixelValue::const_px(3);
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct StyleBorderSide {
-    pub border_width: PixelValue,
-    pub border_style: BorderStyle,
-    pub border_color: ColorU,
+/// Represents a `border-style` attribute, overrides the style of the `border` on each side
+/// that is set (`border-top-style`, etc. only set one side)
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBorderStyle {
+    pub top: Option<BorderStyle>,
+    pub left: Option<BorderStyle>,
+    pub bottom: Option<BorderStyle>,
+    pub right: Option<BorderStyle>,
 }
 
-/// Represents a `box-shadow` attribute.
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct StyleBoxShadow {
-    pub top: Option<Option<BoxShadowPreDisplayItem>>,
-    pub left: Option<Option<BoxShadowPreDisplayItem>>,
+merge_struct!(StyleBorderStyle);
+struct_all!(StyleBorderStyle, BorderStyle);
+
+/// Represents a `border-image` attribute: the `source` image is sliced into nine parts by
+/// the `slice` offsets, the corners are drawn into the corners of the border, the edges are
+/// stretched or repeated along the sides (nine-patch border).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBorderImage {
+    /// `None` for `border-image: none`
+    pub source: Option<CssImageId>,
+    /// Distance of the slice lines from the edges of the image - numbers are stored as `px`
+    /// (image pixels), percentages refer to the width / height of the image
+    pub slice: BorderImageOffsets,
+    /// Whether the middle part of the image is drawn as well
+    pub fill: bool,
+    /// Width of the border image, defaults to the width of the `border` (`None`)
+    pub width: Option<BorderImageOffsets>,
+    /// How far the border image extends beyond the border box
+    pub outset: BorderImageOffsets,
+    pub repeat_horizontal: BorderImageRepeat,
+    pub repeat_vertical: BorderImageRepeat,
+}
+
+impl Default for StyleBorderImage {
+    fn default() -> Self {
+        StyleBorderImage {
+            source: None,
+            slice: BorderImageOffsets::all(PixelValue::const_from_metric(SizeMetric::Percent, 100)),
+            fill: false,
+            width: None,
+            outset: BorderImageOffsets::all(PixelValue::const_px(0)),
+            repeat_horizontal: BorderImageRepeat::Stretch,
+            repeat_vertical: BorderImageRepeat::Stretch,
+        }
+    }
+}
+
+impl StyleBorderImage {
+
+    /// Returns the border widths and the nine-patch border for a source image of the size
+    /// `image_size` (in pixels). If no `width` is set, the widths of the `border` are used.
+    pub fn get_webrender_border(&self, border: &StyleBorder, image_size: (i32, i32), resolve_context: &PixelResolveContext) -> (LayoutSideOffsets, BorderDetails) {
+
+        let (image_width, image_height) = image_size;
+
+        // Slices can't be larger than the image itself
+        let slice = |value: PixelValue, image_length: i32| {
+            FloatValue::new(value.to_pixels_relative(image_length as f32, resolve_context).max(0.0).min(image_length as f32).round())
+        };
+
+        let width = |value: PixelValue| FloatValue::new(value.to_pixels_relative(0.0, resolve_context));
+
+        let border_width = |side: Option<StyleBorderSide>| {
+            side.map(|side| width(side.border_width)).unwrap_or(FloatValue::new(0.0))
+        };
+
+        let widths = match self.width {
+            Some(w) => LayoutSideOffsets {
+                top: width(w.top),
+                right: width(w.right),
+                bottom: width(w.bottom),
+                left: width(w.left),
+            },
+            None => LayoutSideOffsets {
+                top: border_width(border.top),
+                right: border_width(border.right),
+                bottom: border_width(border.bottom),
+                left: border_width(border.left),
+            },
+        };
+
+        let nine_patch = NinePatchBorder {
+            width: image_width,
+            height: image_height,
+            slice: LayoutSideOffsets {
+                top: slice(self.slice.top, image_height),
+                right: slice(self.slice.right, image_width),
+                bottom: slice(self.slice.bottom, image_height),
+                left: slice(self.slice.left, image_width),
+            },
+            fill: self.fill,
+            repeat_horizontal: self.repeat_horizontal,
+            repeat_vertical: self.repeat_vertical,
+            outset: LayoutSideOffsets {
+                top: FloatValue::new(self.outset.top.to_pixels()),
+                right: FloatValue::new(self.outset.right.to_pixels()),
+                bottom: FloatValue::new(self.outset.bottom.to_pixels()),
+                left: FloatValue::new(self.outset.left.to_pixels()),
+            },
+        };
+
+        (widths, BorderDetails::NinePatch(nine_patch))
+    }
+}
+
+/// Offsets of the four sides of a `border-image`, i.e. the `border-image-slice`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BorderImageOffsets {
+    pub top: PixelValue,
+    pub right: PixelValue,
+    pub bottom: PixelValue,
+    pub left: PixelValue,
+}
+
+impl BorderImageOffsets {
+    /// Sets all of the sides to the same `value`
+    pub const fn all(value: PixelValue) -> Self {
+        Self { top: value, right: value, bottom: value, left: value }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBorderSide {
+    pub border_width: PixelValue,
+    pub border_style: BorderStyle,
+    pub border_color: ColorU,
+}
+
+/// Represents a `box-shadow` attribute.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleBoxShadow {
+    pub top: Option<Option<BoxShadowPreDisplayItem>>,
+    pub left: Option<Option<BoxShadowPreDisplayItem>>,
     pub bottom: Option<Option<BoxShadowPreDisplayItem>>,
     pub right: Option<Option<BoxShadowPreDisplayItem>>,
 }
@@ -1220,7 +2251,17 @@ pub struct GradientStopPre {
 
 /// Represents a `width` attribute
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct LayoutWidth(pub PixelValue);
+pub enum LayoutWidth {
+    /// `min-content`: the smallest width of the content, i.e. the width of the longest word of a text
+    MinContent,
+    /// `max-content`: the width of the content without any line breaks
+    MaxContent,
+    /// `fit-content`: the available width, but at least the
+    /// `min-content` and at most the `max-content` width
+    FitContent,
+    /// Length or percentage, i.e. `width: 50px`
+    Exact(PixelValue),
+}
 /// Represents a `min-width` attribute
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LayoutMinWidth(pub PixelValue);
@@ -1229,7 +2270,18 @@ pub struct LayoutMinWidth(pub PixelValue);
 pub struct LayoutMaxWidth(pub PixelValue);
 /// Represents a `height` attribute
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct LayoutHeight(pub PixelValue);
+pub enum LayoutHeight {
+    /// `min-content`: the smallest height of the content, i.e. the height of a text
+    /// that is laid out with its min-content width
+    MinContent,
+    /// `max-content`: the height of the content, i.e. the height of a laid out text
+    MaxContent,
+    /// `fit-content`: the available height, but at least the
+    /// `min-content` and at most the `max-content` height
+    FitContent,
+    /// Length or percentage, i.e. `height: 50px`
+    Exact(PixelValue),
+}
 /// Represents a `min-height` attribute
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct LayoutMinHeight(pub PixelValue);
@@ -1260,6 +2312,36 @@ pub struct LayoutFlexShrink(pub FloatValue);
 impl_float_value!(LayoutFlexGrow);
 impl_float_value!(LayoutFlexShrink);
 
+/// Represents a `flex-basis` attribute - default: `Auto`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutFlexBasis {
+    /// The `width` or `height` of the item is its initial size along the main axis
+    Auto,
+    /// Initial size of the item along the main axis, before it is grown or shrunk -
+    /// percentages refer to the inner size of the flex container
+    Exact(PixelValue),
+}
+
+impl Default for LayoutFlexBasis {
+    fn default() -> Self {
+        LayoutFlexBasis::Auto
+    }
+}
+
+/// Represents a `flex` attribute, the shorthand for `flex-grow`, `flex-shrink`
+/// and `flex-basis`, i.e. `flex: 1` is the same as `flex: 1 1 0px`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutFlex {
+    pub grow: LayoutFlexGrow,
+    pub shrink: LayoutFlexShrink,
+    pub basis: LayoutFlexBasis,
+}
+
+/// Represents an `order` attribute - the items of a flex container are laid out in
+/// ascending order, items with the same order in DOM order (default: `0`)
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutOrder(pub isize);
+
 /// Represents a `flex-direction` attribute - default: `Column`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LayoutDirection {
@@ -1306,6 +2388,485 @@ pub struct StyleWordSpacing(pub PixelValue);
 impl_percentage_value!(StyleTabWidth);
 impl_percentage_value!(StyleLineHeight);
 
+/// Represents an `opacity` attribute, `0.0` = fully transparent, `1.0` = fully opaque
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleOpacity(pub FloatValue);
+
+impl_float_value!(StyleOpacity);
+
+impl StyleOpacity {
+    /// Returns the opacity, clamped to the range `0.0..=1.0`
+    pub fn get(&self) -> f32 {
+        self.0.get().max(0.0).min(1.0)
+    }
+}
+
+/// Represents a `transition` attribute, i.e. `transition: opacity 0.3s ease-in, width 1s`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTransition(pub Vec<Transition>);
+
+impl StyleTransition {
+    /// Returns the transition that should be used when the value of the given property
+    /// changes. Like in CSS, later transitions take precedence over earlier ones, so
+    /// `transition: all 1s, width 2s` transitions the width over two seconds.
+    pub fn get_transition(&self, property_type: CssPropertyType) -> Option<&Transition> {
+        self.0.iter().rev().find(|transition| transition.applies_to(property_type))
+    }
+}
+
+/// One transition of a `transition` attribute, i.e. `width 1s ease-in 500ms`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Transition {
+    /// Property that gets transitioned, `None` if the transition applies to `all` properties
+    pub property: Option<CssPropertyType>,
+    pub duration: Duration,
+    pub timing_function: AnimationTimingFunction,
+    pub delay: Duration,
+}
+
+impl Transition {
+
+    /// Returns whether the transition applies to properties of the given type
+    pub fn applies_to(&self, property_type: CssPropertyType) -> bool {
+        match self.property {
+            None => true,
+            Some(p) => p.get_property_type() == property_type.get_property_type(),
+        }
+    }
+
+    /// Returns the eased progress (`0.0` - `1.0`) of the transition,
+    /// `elapsed` is the time since the transitioned property changed
+    pub fn get_progress(&self, elapsed: Duration) -> f32 {
+        if elapsed < self.delay {
+            return 0.0;
+        }
+        let duration = duration_to_secs(self.duration);
+        if duration <= 0.0 {
+            return 1.0;
+        }
+        let t = (duration_to_secs(elapsed - self.delay) / duration).min(1.0);
+        self.timing_function.evaluate(t)
+    }
+
+    /// Returns whether the transition has finished after `elapsed` time
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        elapsed >= self.delay + self.duration
+    }
+}
+
+/// Represents an `animation` attribute, i.e. `animation: fade-in 1s ease-in-out infinite alternate`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleAnimation(pub Vec<Animation>);
+
+/// One animation of an `animation` attribute
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Animation {
+    /// Name of the `@keyframes` rule to play
+    pub name: String,
+    pub duration: Duration,
+    /// Easing between two keyframes (not over the whole animation, like in CSS)
+    pub timing_function: AnimationTimingFunction,
+    pub delay: Duration,
+    pub iteration_count: AnimationIterationCount,
+    pub direction: AnimationDirection,
+    pub fill_mode: AnimationFillMode,
+}
+
+impl Animation {
+
+    /// Creates an animation that plays the `@keyframes` with the given name once, without a delay
+    pub fn new(name: String, duration: Duration) -> Self {
+        Self {
+            name,
+            duration,
+            timing_function: AnimationTimingFunction::default(),
+            delay: Duration::from_secs(0),
+            iteration_count: AnimationIterationCount::default(),
+            direction: AnimationDirection::default(),
+            fill_mode: AnimationFillMode::default(),
+        }
+    }
+
+    /// Returns the progress (`0.0` - `1.0`) within the current iteration, after the `direction`
+    /// has been applied, `elapsed` is the time since the animation was started. Returns `None`
+    /// if the animation has no effect at that time (during the delay or after it has finished,
+    /// depending on the `fill_mode`).
+    pub fn get_progress(&self, elapsed: Duration) -> Option<f32> {
+
+        use self::AnimationFillMode::*;
+
+        if elapsed < self.delay {
+            return match self.fill_mode {
+                Backwards | Both => Some(self.apply_direction(0.0, 0)),
+                None | Forwards => Option::None,
+            };
+        }
+
+        let iterations = self.iteration_count.get();
+        let duration = duration_to_secs(self.duration);
+        let current_iteration = if duration <= 0.0 { iterations } else { duration_to_secs(elapsed - self.delay) / duration };
+
+        if current_iteration < iterations {
+            return Some(self.apply_direction(current_iteration.fract(), current_iteration as usize));
+        }
+
+        match self.fill_mode {
+            Forwards | Both => {},
+            None | Backwards => return Option::None,
+        }
+
+        // Hold the last frame, i.e. `iteration_count: 1.5` ends in the middle of the second iteration
+        if iterations.is_infinite() || iterations <= 0.0 {
+            Some(self.apply_direction(if iterations <= 0.0 { 0.0 } else { 1.0 }, 0))
+        } else if iterations.fract() == 0.0 {
+            Some(self.apply_direction(1.0, iterations as usize - 1))
+        } else {
+            Some(self.apply_direction(iterations.fract(), iterations as usize))
+        }
+    }
+
+    /// Returns whether the animation has finished playing after `elapsed` time
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        let iterations = self.iteration_count.get();
+        if elapsed < self.delay {
+            false
+        } else if self.duration == Duration::from_secs(0) {
+            true
+        } else {
+            duration_to_secs(elapsed - self.delay) / duration_to_secs(self.duration) >= iterations
+        }
+    }
+
+    fn apply_direction(&self, progress: f32, iteration: usize) -> f32 {
+        use self::AnimationDirection::*;
+        let is_reversed = match self.direction {
+            Normal => false,
+            Reverse => true,
+            Alternate => iteration % 2 == 1,
+            AlternateReverse => iteration % 2 == 0,
+        };
+        if is_reversed { 1.0 - progress } else { progress }
+    }
+}
+
+/// Represents an `animation-iteration-count`, defaults to `1`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnimationIterationCount {
+    Count(FloatValue),
+    Infinite,
+}
+
+impl Default for AnimationIterationCount {
+    fn default() -> Self {
+        AnimationIterationCount::Count(FloatValue::const_new(1))
+    }
+}
+
+impl AnimationIterationCount {
+    /// Returns the number of iterations, `f32::INFINITY` for `infinite`
+    pub fn get(&self) -> f32 {
+        match self {
+            AnimationIterationCount::Count(c) => c.get().max(0.0),
+            AnimationIterationCount::Infinite => ::std::f32::INFINITY,
+        }
+    }
+}
+
+/// Represents an `animation-direction`, defaults to `normal`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnimationDirection {
+    Normal,
+    Reverse,
+    Alternate,
+    AlternateReverse,
+}
+
+impl Default for AnimationDirection {
+    fn default() -> Self {
+        AnimationDirection::Normal
+    }
+}
+
+/// Represents an `animation-fill-mode`, defaults to `none`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnimationFillMode {
+    /// The animation has no effect before it starts and after it has finished
+    None,
+    /// The last frame is held after the animation has finished
+    Forwards,
+    /// The first frame is applied during the `animation-delay`
+    Backwards,
+    Both,
+}
+
+impl Default for AnimationFillMode {
+    fn default() -> Self {
+        AnimationFillMode::None
+    }
+}
+
+/// Easing function of a transition or animation, defaults to `ease`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnimationTimingFunction {
+    Ease,
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// `cubic-bezier(x1, y1, x2, y2)`, `x1` and `x2` are in the range `0.0..=1.0`
+    CubicBezier([FloatValue;4]),
+    /// `steps(count, position)`, `step-start` = `steps(1, start)`, `step-end` = `steps(1, end)`
+    Steps(usize, StepPosition),
+}
+
+impl Default for AnimationTimingFunction {
+    fn default() -> Self {
+        AnimationTimingFunction::Ease
+    }
+}
+
+impl AnimationTimingFunction {
+
+    /// Maps the linear progress `t` (`0.0` - `1.0`) of an animation to the eased progress
+    pub fn evaluate(&self, t: f32) -> f32 {
+        use self::AnimationTimingFunction::*;
+        let t = t.max(0.0).min(1.0);
+        match self {
+            Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            Linear => t,
+            EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            CubicBezier([x1, y1, x2, y2]) => cubic_bezier(x1.get(), y1.get(), x2.get(), y2.get(), t),
+            Steps(count, position) => {
+                let count = (*count).max(1) as f32;
+                let step = match position {
+                    StepPosition::Start => (t * count).ceil(),
+                    StepPosition::End => (t * count).floor(),
+                };
+                (step / count).min(1.0)
+            },
+        }
+    }
+}
+
+/// Whether a `steps()` timing function jumps at the start or at the end of each step
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StepPosition {
+    Start,
+    End,
+}
+
+/// Evaluates the y coordinate of a cubic bezier curve from `(0, 0)` to `(1, 1)` at the x coordinate `t`
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, t: f32) -> f32 {
+
+    fn sample(p1: f32, p2: f32, s: f32) -> f32 {
+        let inv = 1.0 - s;
+        3.0 * inv * inv * s * p1 + 3.0 * inv * s * s * p2 + s * s * s
+    }
+
+    // x(s) is monotonic if x1 and x2 are in the range 0..=1, so bisection always converges
+    let (mut lower, mut upper) = (0.0, 1.0);
+    let mut s = t;
+    for _ in 0..32 {
+        let x = sample(x1, x2, s);
+        if (x - t).abs() < 0.00001 {
+            break;
+        }
+        if x < t { lower = s; } else { upper = s; }
+        s = (lower + upper) / 2.0;
+    }
+
+    sample(y1, y2, s)
+}
+
+fn duration_to_secs(duration: Duration) -> f32 {
+    duration.as_secs() as f32 + duration.subsec_nanos() as f32 / 1_000_000_000.0
+}
+
+/// Represents a `transform` attribute, i.e. `transform: translateX(10px) rotate(45deg)`.
+/// The functions are stored in the order of the source string.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTransform(pub Vec<TransformFunction>);
+
+impl StyleTransform {
+
+    /// Returns the 2D transformation matrix `[a, b, c, d, tx, ty]` (same layout as
+    /// `matrix(a, b, c, d, tx, ty)`), rotating / scaling around the `origin` point.
+    /// Like in CSS, the rightmost function is applied first.
+    pub fn to_matrix(&self, origin: LayoutPoint) -> [f32;6] {
+        let translate_to_origin = [1.0, 0.0, 0.0, 1.0, origin.x, origin.y];
+        let translate_from_origin = [1.0, 0.0, 0.0, 1.0, -origin.x, -origin.y];
+        let matrix = self.0.iter().fold(translate_to_origin, |matrix, function| multiply_matrix_2d(matrix, function.to_matrix()));
+        multiply_matrix_2d(matrix, translate_from_origin)
+    }
+}
+
+/// Multiplies two 2D matrices in the form of `[a, b, c, d, tx, ty]`, so that `b` gets applied first
+fn multiply_matrix_2d(a: [f32;6], b: [f32;6]) -> [f32;6] {
+    [
+        a[0] * b[0] + a[2] * b[1],
+        a[1] * b[0] + a[3] * b[1],
+        a[0] * b[2] + a[2] * b[3],
+        a[1] * b[2] + a[3] * b[3],
+        a[0] * b[4] + a[2] * b[5] + a[4],
+        a[1] * b[4] + a[3] * b[5] + a[5],
+    ]
+}
+
+/// One function of a `transform` attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TransformFunction {
+    /// `matrix(a, b, c, d, tx, ty)`
+    Matrix([FloatValue;6]),
+    /// `translate(x, y)`
+    Translate(PixelValue, PixelValue),
+    /// `translateX(x)`
+    TranslateX(PixelValue),
+    /// `translateY(y)`
+    TranslateY(PixelValue),
+    /// `scale(x, y)` - `scale(x)` is parsed as `scale(x, x)`
+    Scale(FloatValue, FloatValue),
+    /// `scaleX(x)`
+    ScaleX(FloatValue),
+    /// `scaleY(y)`
+    ScaleY(FloatValue),
+    /// `rotate(angle)`, positive angles rotate clockwise
+    Rotate(AngleValue),
+    /// `skew(x_angle, y_angle)`
+    Skew(AngleValue, AngleValue),
+    /// `skewX(angle)`
+    SkewX(AngleValue),
+    /// `skewY(angle)`
+    SkewY(AngleValue),
+}
+
+impl TransformFunction {
+
+    /// Returns the 2D transformation matrix `[a, b, c, d, tx, ty]` of this function
+    pub fn to_matrix(&self) -> [f32;6] {
+        use self::TransformFunction::*;
+        match self {
+            Matrix(m) => [m[0].get(), m[1].get(), m[2].get(), m[3].get(), m[4].get(), m[5].get()],
+            Translate(x, y) => [1.0, 0.0, 0.0, 1.0, x.to_pixels(), y.to_pixels()],
+            TranslateX(x) => [1.0, 0.0, 0.0, 1.0, x.to_pixels(), 0.0],
+            TranslateY(y) => [1.0, 0.0, 0.0, 1.0, 0.0, y.to_pixels()],
+            Scale(x, y) => [x.get(), 0.0, 0.0, y.get(), 0.0, 0.0],
+            ScaleX(x) => [x.get(), 0.0, 0.0, 1.0, 0.0, 0.0],
+            ScaleY(y) => [1.0, 0.0, 0.0, y.get(), 0.0, 0.0],
+            Rotate(angle) => {
+                let (sin, cos) = angle.to_degrees().to_radians().sin_cos();
+                [cos, sin, -sin, cos, 0.0, 0.0]
+            },
+            Skew(x, y) => [1.0, y.to_degrees().to_radians().tan(), x.to_degrees().to_radians().tan(), 1.0, 0.0, 0.0],
+            SkewX(x) => [1.0, 0.0, x.to_degrees().to_radians().tan(), 1.0, 0.0, 0.0],
+            SkewY(y) => [1.0, y.to_degrees().to_radians().tan(), 0.0, 1.0, 0.0, 0.0],
+        }
+    }
+}
+
+/// Represents a `transform-origin` attribute, relative to the top left corner
+/// of the node. Defaults to `50% 50%` (the center of the node).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTransformOrigin {
+    pub x: TransformOriginOffset,
+    pub y: TransformOriginOffset,
+}
+
+impl Default for StyleTransformOrigin {
+    fn default() -> Self {
+        StyleTransformOrigin {
+            x: TransformOriginOffset::Percentage(PercentageValue::new(0.5)),
+            y: TransformOriginOffset::Percentage(PercentageValue::new(0.5)),
+        }
+    }
+}
+
+/// Offset of the `transform-origin` on one axis, either a fixed
+/// pixel value or a fraction of the width / height of the node
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TransformOriginOffset {
+    Pixels(PixelValue),
+    /// Fraction of the width / height, i.e. `50%` is stored as `0.5`
+    Percentage(PercentageValue),
+}
+
+impl TransformOriginOffset {
+    /// Resolves the offset, given the width (or height) of the node
+    pub fn to_pixels(&self, full_length: f32) -> f32 {
+        match self {
+            TransformOriginOffset::Pixels(p) => p.to_pixels(),
+            TransformOriginOffset::Percentage(p) => p.get() * full_length,
+        }
+    }
+}
+
+/// FloatValue, but associated with an angle metric (deg, rad, etc.)
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AngleValue {
+    pub metric: AngleMetric,
+    pub number: FloatValue,
+}
+
+impl fmt::Debug for AngleValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}{:?}", self.number, self.metric)
+    }
+}
+
+/// Enum representing the metric associated with an angle (deg, rad, etc.)
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AngleMetric {
+    Deg,
+    Rad,
+    Grad,
+    Turn,
+}
+
+impl fmt::Debug for AngleMetric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::AngleMetric::*;
+        match self {
+            Deg => write!(f, "deg"),
+            Rad => write!(f, "rad"),
+            Grad => write!(f, "grad"),
+            Turn => write!(f, "turn"),
+        }
+    }
+}
+
+impl AngleValue {
+
+    #[inline]
+    pub fn deg(value: f32) -> Self {
+        Self::from_metric(AngleMetric::Deg, value)
+    }
+
+    #[inline]
+    pub fn rad(value: f32) -> Self {
+        Self::from_metric(AngleMetric::Rad, value)
+    }
+
+    #[inline]
+    pub fn from_metric(metric: AngleMetric, value: f32) -> Self {
+        Self {
+            metric: metric,
+            number: FloatValue::new(value),
+        }
+    }
+
+    /// Returns the value of the angle in degrees
+    #[inline]
+    pub fn to_degrees(&self) -> f32 {
+        match self.metric {
+            AngleMetric::Deg => self.number.get(),
+            AngleMetric::Rad => self.number.get().to_degrees(),
+            AngleMetric::Grad => self.number.get() * 360.0 / 400.0,
+            AngleMetric::Turn => self.number.get() * 360.0,
+        }
+    }
+}
+
 /// Same as the `LayoutDirection`, but without the `-reverse` properties, used in the layout solver,
 /// makes decisions based on horizontal / vertical direction easier to write.
 /// Use `LayoutDirection::get_axis()` to get the axis for a given `LayoutDirection`.
@@ -1321,8 +2882,15 @@ pub enum LayoutAxis {
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LayoutPosition {
     Static,
+    /// Laid out like `static`, then offset by `top`, `left`, `right` and `bottom`
     Relative,
+    /// Taken out of the flow, positioned relative to the nearest positioned ancestor
     Absolute,
+    /// Taken out of the flow, positioned relative to the window, doesn't scroll
+    Fixed,
+    /// Laid out like `static`, but sticks to the edges of the nearest scroll frame
+    /// given by `top`, `left`, `right` and `bottom` while its parent is visible
+    Sticky,
 }
 
 impl Default for LayoutPosition {
@@ -1331,16 +2899,102 @@ impl Default for LayoutPosition {
     }
 }
 
-/// Represents a `flex-wrap` attribute - default: `Wrap`
+impl LayoutPosition {
+    /// Returns whether the node is taken out of the regular flow (`absolute` or `fixed`),
+    /// so that it doesn't take up any space in its parent
+    pub fn is_out_of_flow(&self) -> bool {
+        match self {
+            LayoutPosition::Absolute | LayoutPosition::Fixed => true,
+            _ => false,
+        }
+    }
+}
+
+/// Represents a `display` attribute - default: `Flex`
+///
+/// Every node is a flex container, unless it is a `grid` container. `display: none` removes
+/// the node and its children from the layout, they are neither drawn nor hit-tested.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutDisplay {
+    Flex,
+    Grid,
+    /// The node flows together with its siblings in the lines of the text of its parent,
+    /// i.e. a text span with a different font or an image inside of a paragraph
+    Inline,
+    None,
+}
+
+impl Default for LayoutDisplay {
+    fn default() -> Self {
+        LayoutDisplay::Flex
+    }
+}
+
+/// Represents a `float` attribute - default: `None`
+///
+/// Only has an effect on the children of a node that contains inline children
+/// (`display: inline`): the node is moved to the left or right edge of its parent
+/// and the text of the parent flows around it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutFloat {
+    Left,
+    Right,
+    None,
+}
+
+impl Default for LayoutFloat {
+    fn default() -> Self {
+        LayoutFloat::None
+    }
+}
+
+/// Represents a `visibility` attribute - default: `Visible`
+///
+/// Hidden nodes still take up space in the layout, but are neither drawn nor
+/// hit-tested. Children can override the (inherited) visibility of the parent.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleVisibility {
+    Visible,
+    Hidden,
+}
+
+impl Default for StyleVisibility {
+    fn default() -> Self {
+        StyleVisibility::Visible
+    }
+}
+
+/// Represents a `z-index` attribute (default: `Auto`). Only has an effect
+/// on positioned (`position: relative` or `position: absolute`) nodes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutZIndex {
+    /// The node is painted in the stacking context of its parent
+    Auto,
+    /// The node creates a new stacking context, which is painted in
+    /// ascending order of the z-index relative to its siblings
+    Integer(isize),
+}
+
+impl Default for LayoutZIndex {
+    fn default() -> Self {
+        LayoutZIndex::Auto
+    }
+}
+
+/// Represents a `flex-wrap` attribute - default: `NoWrap`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LayoutWrap {
+    /// Children are broken into multiple lines, new lines are added at the cross-end
     Wrap,
+    /// Same as `Wrap`, but new lines are added at the cross-start
+    WrapReverse,
+    /// Default value. All children are laid out on a single line
     NoWrap,
 }
 
 impl Default for LayoutWrap {
     fn default() -> Self {
-        LayoutWrap::Wrap
+        LayoutWrap::NoWrap
     }
 }
 
@@ -1384,6 +3038,36 @@ impl Default for LayoutAlignItems {
     }
 }
 
+/// Represents a `align-self` attribute, overrides the `align-items` of the parent for a single item
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutAlignSelf {
+    /// Uses the `align-items` of the parent
+    Auto,
+    Stretch,
+    Center,
+    Start,
+    End,
+}
+
+impl Default for LayoutAlignSelf {
+    fn default() -> Self {
+        LayoutAlignSelf::Auto
+    }
+}
+
+impl LayoutAlignSelf {
+    /// Returns the alignment of the item, given the `align-items` of its parent
+    pub fn resolve(&self, align_items: LayoutAlignItems) -> LayoutAlignItems {
+        match self {
+            LayoutAlignSelf::Auto => align_items,
+            LayoutAlignSelf::Stretch => LayoutAlignItems::Stretch,
+            LayoutAlignSelf::Center => LayoutAlignItems::Center,
+            LayoutAlignSelf::Start => LayoutAlignItems::Start,
+            LayoutAlignSelf::End => LayoutAlignItems::End,
+        }
+    }
+}
+
 /// Represents a `align-content` attribute
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LayoutAlignContent {
@@ -1401,6 +3085,138 @@ pub enum LayoutAlignContent {
     SpaceAround,
 }
 
+impl Default for LayoutAlignContent {
+    fn default() -> Self {
+        LayoutAlignContent::Stretch
+    }
+}
+
+/// Minimum or maximum size of a grid track (a row or a column)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GridTrackBreadth {
+    /// Fixed size, i.e. `200px` - percentages refer to the size of the grid container
+    Length(PixelValue),
+    /// Share of the leftover space of the grid container, i.e. `1fr`
+    Fraction(FloatValue),
+    /// Sized to the largest item in the track, stretched if there are no `fr` tracks
+    Auto,
+}
+
+/// Size of a single grid track, either `200px`, `1fr`, `auto` or `minmax(100px, 1fr)`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GridTrackSize {
+    Breadth(GridTrackBreadth),
+    MinMax(GridTrackBreadth, GridTrackBreadth),
+}
+
+impl GridTrackSize {
+
+    /// Returns the minimum sizing function of the track - `1fr` is a shorthand
+    /// for `minmax(auto, 1fr)`, so the minimum of a flexible track is `auto`
+    pub fn get_min(&self) -> GridTrackBreadth {
+        match self {
+            GridTrackSize::Breadth(GridTrackBreadth::Fraction(_)) => GridTrackBreadth::Auto,
+            GridTrackSize::Breadth(breadth) => *breadth,
+            GridTrackSize::MinMax(min, _) => *min,
+        }
+    }
+
+    /// Returns the maximum sizing function of the track
+    pub fn get_max(&self) -> GridTrackBreadth {
+        match self {
+            GridTrackSize::Breadth(breadth) => *breadth,
+            GridTrackSize::MinMax(_, max) => *max,
+        }
+    }
+}
+
+impl Default for GridTrackSize {
+    fn default() -> Self {
+        GridTrackSize::Breadth(GridTrackBreadth::Auto)
+    }
+}
+
+/// Represents a `grid-template-columns` attribute, i.e. `200px repeat(2, 1fr)`
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridTemplateColumns(pub Vec<GridTrackSize>);
+
+/// Represents a `grid-template-rows` attribute, i.e. `auto 1fr auto`
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridTemplateRows(pub Vec<GridTrackSize>);
+
+/// Rectangle of grid cells, the `start` is inclusive, the `end` exclusive (0-based track indices)
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GridArea {
+    pub row_start: usize,
+    pub row_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+}
+
+/// Represents a `grid-template-areas` attribute, i.e. `"header header" "sidebar main"`
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridTemplateAreas {
+    /// Named areas, in the order of their first appearance
+    pub areas: Vec<(String, GridArea)>,
+    pub row_count: usize,
+    pub column_count: usize,
+}
+
+impl LayoutGridTemplateAreas {
+    /// Returns the cells that the area with the given name covers
+    pub fn get_area(&self, name: &str) -> Option<GridArea> {
+        self.areas.iter().find(|(area_name, _)| area_name == name).map(|(_, area)| *area)
+    }
+}
+
+/// Represents a `grid-gap` attribute, i.e. `10px` or `10px 20px` (row gap, column gap)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridGap {
+    pub row: PixelValue,
+    pub column: PixelValue,
+}
+
+/// Start or end of the placement of a grid item along one axis
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GridLine {
+    /// Placed automatically
+    Auto,
+    /// 1-based line number, negative numbers count from the last line of the explicit grid
+    Line(isize),
+    /// Spans this many tracks, i.e. `span 2`
+    Span(usize),
+    /// Start or end line of an area of the `grid-template-areas`
+    Area(String),
+}
+
+impl Default for GridLine {
+    fn default() -> Self {
+        GridLine::Auto
+    }
+}
+
+/// Represents a `grid-row` attribute, i.e. `1 / 3` or `2 / span 2`
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridRow {
+    pub start: GridLine,
+    pub end: GridLine,
+}
+
+/// Represents a `grid-column` attribute, i.e. `1 / -1` or `span 2`
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridColumn {
+    pub start: GridLine,
+    pub end: GridLine,
+}
+
+/// Represents a `grid-area` attribute, either the name of an area (`grid-area: header`) or
+/// `row-start / column-start / row-end / column-end`. Sets both the `grid-row` and the `grid-column`
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayoutGridArea {
+    pub row: LayoutGridRow,
+    pub column: LayoutGridColumn,
+}
+
 /// Represents a `overflow-x` or `overflow-y` property, see
 /// [`TextOverflowBehaviour`](./struct.TextOverflowBehaviour.html) - default: `Auto`
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -1477,25 +3293,45 @@ impl Default for StyleTextAlignmentVert {
 #[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RectStyle {
     /// Background size of this rectangle
-    pub background_size: Option<StyleBackgroundSize>,
+    pub background_size: Option<StyleBackgroundSizes>,
     /// Background repetition
-    pub background_repeat: Option<StyleBackgroundRepeat>,
+    pub background_repeat: Option<StyleBackgroundRepeats>,
+    /// `background-position` property
+    pub background_position: Option<StyleBackgroundPositions>,
+    /// `background-origin` property
+    pub background_origin: Option<StyleBackgroundOrigins>,
+    /// `background-clip` property
+    pub background_clip: Option<StyleBackgroundClips>,
     /// Shadow color
     pub box_shadow: Option<StyleBoxShadow>,
-    /// Gradient (location) + stops
-    pub background: Option<StyleBackground>,
+    /// Background layers (colors, gradients and images), the first layer is drawn on top
+    pub background: Option<StyleBackgrounds>,
     /// Border
     pub border: Option<StyleBorder>,
+    /// `border-style` property, overrides the styles of the `border`
+    pub border_style: Option<StyleBorderStyle>,
+    /// `border-image` property (nine-patch border)
+    pub border_image: Option<StyleBorderImage>,
     /// Border radius
     pub border_radius: Option<StyleBorderRadius>,
     /// Font size
     pub font_size: Option<StyleFontSize>,
     /// Font name / family
     pub font_family: Option<StyleFontFamily>,
+    /// `font-weight` property
+    pub font_weight: Option<StyleFontWeight>,
+    /// `font-style` property
+    pub font_style: Option<StyleFontStyle>,
+    /// `font-stretch` property
+    pub font_stretch: Option<StyleFontStretch>,
     /// Text color
     pub font_color: Option<StyleTextColor>,
     /// Text alignment
     pub text_align: Option<StyleTextAlignmentHorz,>,
+    /// `text-decoration` property (underline, overline, line-through)
+    pub text_decoration: Option<StyleTextDecoration>,
+    /// `text-shadow` property
+    pub text_shadow: Option<StyleTextShadow>,
     /// `line-height` property
     pub line_height: Option<StyleLineHeight>,
     /// `letter-spacing` property
@@ -1504,6 +3340,18 @@ pub struct RectStyle {
     pub word_spacing: Option<StyleWordSpacing>,
     /// `tab-width` property
     pub tab_width: Option<StyleTabWidth>,
+    /// `transform` property
+    pub transform: Option<StyleTransform>,
+    /// `transform-origin` property
+    pub transform_origin: Option<StyleTransformOrigin>,
+    /// `opacity` property
+    pub opacity: Option<StyleOpacity>,
+    /// `visibility` property
+    pub visibility: Option<StyleVisibility>,
+    /// Horizontal scrollbar, styled via the `::-webkit-scrollbar` pseudo-elements
+    pub horizontal_scrollbar: Option<Box<ScrollbarInfo>>,
+    /// Vertical scrollbar, styled via the `::-webkit-scrollbar` pseudo-elements
+    pub vertical_scrollbar: Option<Box<ScrollbarInfo>>,
 }
 
 impl_pixel_value!(StyleLetterSpacing);
@@ -1511,15 +3359,40 @@ impl_pixel_value!(StyleWordSpacing);
 
 impl RectStyle {
 
+    /// Returns whether the node is hidden via `visibility: hidden`
+    pub fn is_visibility_hidden(&self) -> bool {
+        self.visibility.unwrap_or_default() == StyleVisibility::Hidden
+    }
+
     pub fn get_horizontal_scrollbar_style(&self) -> ScrollbarInfo {
-        ScrollbarInfo::default()
+        self.get_scrollbar_style(ScrollbarOrientation::Horizontal)
     }
 
     pub fn get_vertical_scrollbar_style(&self) -> ScrollbarInfo {
-        ScrollbarInfo::default()
+        self.get_scrollbar_style(ScrollbarOrientation::Vertical)
+    }
+
+    /// Returns the styled scrollbar or the default scrollbar style, if the
+    /// scrollbar isn't styled by any `::-webkit-scrollbar` pseudo-element
+    pub fn get_scrollbar_style(&self, orientation: ScrollbarOrientation) -> ScrollbarInfo {
+        let scrollbar = match orientation {
+            ScrollbarOrientation::Horizontal => &self.horizontal_scrollbar,
+            ScrollbarOrientation::Vertical => &self.vertical_scrollbar,
+        };
+        match scrollbar {
+            Some(s) => (**s).clone(),
+            None => ScrollbarInfo::new(orientation),
+        }
     }
 }
 
+/// Direction of a scrollbar (`:horizontal` / `:vertical`)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScrollbarOrientation {
+    Horizontal,
+    Vertical,
+}
+
 /// Holds info necessary for layouting / styling scrollbars (-webkit-scrollbar)
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ScrollbarInfo {
@@ -1542,10 +3415,29 @@ pub struct ScrollbarInfo {
     pub resizer: RectStyle,
 }
 
+impl ScrollbarInfo {
+
+    /// Returns the default scrollbar style for the given orientation - the default
+    /// padding only narrows the thumb, but doesn't shorten the track
+    pub fn new(orientation: ScrollbarOrientation) -> Self {
+        match orientation {
+            ScrollbarOrientation::Vertical => ScrollbarInfo::default(),
+            ScrollbarOrientation::Horizontal => ScrollbarInfo {
+                padding: LayoutPadding {
+                    top: Some(PixelValue::px(2.0)),
+                    bottom: Some(PixelValue::px(2.0)),
+                    .. Default::default()
+                },
+                .. ScrollbarInfo::default()
+            },
+        }
+    }
+}
+
 impl Default for ScrollbarInfo {
     fn default() -> Self {
         ScrollbarInfo {
-            width: LayoutWidth(PixelValue::px(17.0)),
+            width: LayoutWidth::px(17.0),
             padding: LayoutPadding {
                 left: Some(PixelValue::px(2.0)),
                 right: Some(PixelValue::px(2.0)),
@@ -1554,19 +3446,19 @@ impl Default for ScrollbarInfo {
             track: RectStyle {
                 background: Some(StyleBackground::Color(ColorU {
                     r: 241, g: 241, b: 241, a: 255
-                })),
+                }).into()),
                 .. Default::default()
             },
             thumb: RectStyle {
                 background: Some(StyleBackground::Color(ColorU {
                     r: 193, g: 193, b: 193, a: 255
-                })),
+                }).into()),
                 .. Default::default()
             },
             button: RectStyle {
                 background: Some(StyleBackground::Color(ColorU {
                     r: 163, g: 163, b: 163, a: 255
-                })),
+                }).into()),
                 .. Default::default()
             },
             corner: RectStyle::default(),
@@ -1576,7 +3468,7 @@ impl Default for ScrollbarInfo {
 }
 
 /// Options of a cascaded (styled) DOM node that are relevant for constructing the layout of a div
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct RectLayout {
 
     pub width: Option<LayoutWidth>,
@@ -1586,7 +3478,10 @@ pub struct RectLayout {
     pub max_width: Option<LayoutMaxWidth>,
     pub max_height: Option<LayoutMaxHeight>,
 
+    pub display: Option<LayoutDisplay>,
     pub position: Option<LayoutPosition>,
+    pub float: Option<LayoutFloat>,
+    pub z_index: Option<LayoutZIndex>,
     pub top: Option<LayoutTop>,
     pub bottom: Option<LayoutBottom>,
     pub right: Option<LayoutRight>,
@@ -1600,13 +3495,39 @@ pub struct RectLayout {
     pub wrap: Option<LayoutWrap>,
     pub flex_grow: Option<LayoutFlexGrow>,
     pub flex_shrink: Option<LayoutFlexShrink>,
+    pub flex_basis: Option<LayoutFlexBasis>,
+    pub order: Option<LayoutOrder>,
     pub justify_content: Option<LayoutJustifyContent>,
     pub align_items: Option<LayoutAlignItems>,
+    pub align_self: Option<LayoutAlignSelf>,
     pub align_content: Option<LayoutAlignContent>,
+    pub gap: Option<LayoutGap>,
+
+    pub grid_template_columns: Option<LayoutGridTemplateColumns>,
+    pub grid_template_rows: Option<LayoutGridTemplateRows>,
+    pub grid_template_areas: Option<LayoutGridTemplateAreas>,
+    pub grid_gap: Option<LayoutGridGap>,
+    pub grid_row: Option<LayoutGridRow>,
+    pub grid_column: Option<LayoutGridColumn>,
 }
 
 impl RectLayout {
 
+    /// Returns whether the node is removed from the layout via `display: none`
+    pub fn is_display_none(&self) -> bool {
+        self.display.unwrap_or_default() == LayoutDisplay::None
+    }
+
+    /// Returns whether the children of the node are laid out in a grid (`display: grid`)
+    pub fn is_grid_container(&self) -> bool {
+        self.display.unwrap_or_default() == LayoutDisplay::Grid
+    }
+
+    /// Returns whether the node is laid out as a part of the text of its parent (`display: inline`)
+    pub fn is_inline(&self) -> bool {
+        self.display.unwrap_or_default() == LayoutDisplay::Inline
+    }
+
     pub fn get_horizontal_padding(&self) -> f32 {
         let padding = self.padding.unwrap_or_default();
         padding.left.map(|l| l.to_pixels()).unwrap_or(0.0)
@@ -1640,8 +3561,8 @@ impl RectLayout {
     }
 }
 
-impl_pixel_value!(LayoutWidth);
-impl_pixel_value!(LayoutHeight);
+impl_size_value!(LayoutWidth);
+impl_size_value!(LayoutHeight);
 impl_pixel_value!(LayoutMinHeight);
 impl_pixel_value!(LayoutMinWidth);
 impl_pixel_value!(LayoutMaxWidth);
@@ -1678,3 +3599,423 @@ impl FontId {
         &self.0
     }
 }
+
+/// Represents a `font-weight` attribute, from `1` (thinnest) to `1000` (boldest)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleFontWeight(pub u16);
+
+impl Default for StyleFontWeight {
+    fn default() -> Self {
+        StyleFontWeight::NORMAL
+    }
+}
+
+impl StyleFontWeight {
+
+    pub const NORMAL: StyleFontWeight = StyleFontWeight(400);
+    pub const BOLD: StyleFontWeight = StyleFontWeight(700);
+
+    /// Whether a font of this weight counts as bold, i.e. for selecting a system font
+    pub fn is_bold(&self) -> bool {
+        self.0 >= 600
+    }
+
+    pub fn interpolate(&self, other: &Self, t: f32) -> Self {
+        let weight = self.0 as f32 + (other.0 as f32 - self.0 as f32) * t;
+        StyleFontWeight(weight.round().max(1.0).min(1000.0) as u16)
+    }
+}
+
+/// Represents a `font-style` attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleFontStyle {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl Default for StyleFontStyle {
+    fn default() -> Self {
+        StyleFontStyle::Normal
+    }
+}
+
+/// Represents a `font-stretch` attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleFontStretch {
+    UltraCondensed,
+    ExtraCondensed,
+    Condensed,
+    SemiCondensed,
+    Normal,
+    SemiExpanded,
+    Expanded,
+    ExtraExpanded,
+    UltraExpanded,
+}
+
+impl Default for StyleFontStretch {
+    fn default() -> Self {
+        StyleFontStretch::Normal
+    }
+}
+
+impl StyleFontStretch {
+
+    /// Width of the font in percent of the normal width, i.e. `75` for `condensed`
+    pub fn get_percentage(&self) -> u16 {
+        use self::StyleFontStretch::*;
+        match self {
+            UltraCondensed => 50,
+            ExtraCondensed => 62,
+            Condensed => 75,
+            SemiCondensed => 87,
+            Normal => 100,
+            SemiExpanded => 112,
+            Expanded => 125,
+            ExtraExpanded => 150,
+            UltraExpanded => 200,
+        }
+    }
+
+    /// Returns the suffix that system fonts of this width usually have in their
+    /// family name, i.e. `"Condensed"` for `"Roboto Condensed"`
+    pub fn get_family_suffix(&self) -> Option<&'static str> {
+        use self::StyleFontStretch::*;
+        match self {
+            UltraCondensed => Some("Ultra Condensed"),
+            ExtraCondensed => Some("Extra Condensed"),
+            Condensed => Some("Condensed"),
+            SemiCondensed => Some("Semi Condensed"),
+            Normal => None,
+            SemiExpanded => Some("Semi Expanded"),
+            Expanded => Some("Expanded"),
+            ExtraExpanded => Some("Extra Expanded"),
+            UltraExpanded => Some("Ultra Expanded"),
+        }
+    }
+}
+
+/// Represents a `text-decoration` attribute - `text-decoration-line`, `-color`, `-style`
+/// and `-thickness` only set one of the fields. `None` fields use the default value
+/// (no line, the text color, a solid line and the thickness from the font metrics).
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTextDecoration {
+    pub line: Option<TextDecorationLine>,
+    pub color: Option<ColorU>,
+    pub style: Option<TextDecorationStyle>,
+    pub thickness: Option<TextDecorationThickness>,
+}
+
+impl StyleTextDecoration {
+
+    pub fn merge(a: &mut Option<StyleTextDecoration>, b: &StyleTextDecoration) {
+        if let Some(ref mut existing) = a {
+            existing.merge_fields(b);
+        } else {
+            *a = Some(*b);
+        }
+    }
+
+    /// Overwrites the fields (line, color, style, thickness) that are set in `other`
+    pub fn merge_fields(&mut self, other: &StyleTextDecoration) {
+        if other.line.is_some() { self.line = other.line; }
+        if other.color.is_some() { self.color = other.color; }
+        if other.style.is_some() { self.style = other.style; }
+        if other.thickness.is_some() { self.thickness = other.thickness; }
+    }
+}
+
+/// Represents a `text-decoration-line` attribute, multiple lines can be combined
+/// (`text-decoration-line: underline overline`)
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextDecorationLine {
+    pub underline: bool,
+    pub overline: bool,
+    pub line_through: bool,
+}
+
+impl TextDecorationLine {
+
+    /// `text-decoration-line: none`
+    pub const NONE: TextDecorationLine = TextDecorationLine { underline: false, overline: false, line_through: false };
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+/// Represents a `text-decoration-style` attribute
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TextDecorationStyle {
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    Wavy,
+}
+
+impl Default for TextDecorationStyle {
+    fn default() -> Self {
+        TextDecorationStyle::Solid
+    }
+}
+
+/// Represents a `text-decoration-thickness` attribute, percentages refer to the font size
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TextDecorationThickness {
+    /// `auto` or `from-font`: the underline / strikeout thickness of the font
+    Auto,
+    Length(PixelValue),
+}
+
+impl Default for TextDecorationThickness {
+    fn default() -> Self {
+        TextDecorationThickness::Auto
+    }
+}
+
+/// Represents a `text-shadow` attribute, the first shadow is drawn on top
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StyleTextShadow(pub Vec<TextShadow>);
+
+/// One shadow of a `text-shadow`, i.e. `1px 1px 2px black`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextShadow {
+    pub offset: [PixelValue;2],
+    /// `None` if the color was omitted, the shadow then has the color of the text
+    pub color: Option<ColorU>,
+    pub blur_radius: PixelValue,
+}
+
+/// Weight, style and stretch of a font face - one font family can consist of multiple faces,
+/// i.e. "Roboto" (normal), "Roboto" (bold) and "Roboto" (italic)
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FontFaceProperties {
+    pub weight: StyleFontWeight,
+    pub style: StyleFontStyle,
+    pub stretch: StyleFontStretch,
+}
+
+impl FontFaceProperties {
+
+    /// Returns how well a font face with these properties matches the `requested` properties,
+    /// the face with the lowest distance should be used. Follows the CSS font matching
+    /// algorithm: the stretch is more important than the style, which is more important than
+    /// the weight. Bold and condensed requests prefer bolder / narrower faces and vice versa.
+    pub fn get_match_distance(&self, requested: &FontFaceProperties) -> (u16, u16, u16) {
+
+        use self::StyleFontStyle::*;
+
+        // Penalty for faces that are on the "wrong side" of the requested value
+        const WRONG_DIRECTION: u16 = 1000;
+
+        let requested_stretch = requested.stretch.get_percentage();
+        let stretch = self.stretch.get_percentage();
+        let stretch_distance = match (requested_stretch <= 100, stretch <= requested_stretch) {
+            (true, true) => requested_stretch - stretch,
+            (true, false) => WRONG_DIRECTION + stretch - requested_stretch,
+            (false, false) => stretch - requested_stretch,
+            (false, true) => WRONG_DIRECTION + requested_stretch - stretch,
+        };
+
+        let style_distance = match (requested.style, self.style) {
+            (a, b) if a == b => 0,
+            (Italic, Oblique) | (Oblique, Italic) | (Normal, Oblique) => 1,
+            _ => 2,
+        };
+
+        let (requested_weight, weight) = (requested.weight.0, self.weight.0);
+        let weight_distance = if requested_weight > 500 {
+            if weight >= requested_weight { weight - requested_weight } else { WRONG_DIRECTION + requested_weight - weight }
+        } else if requested_weight < 400 {
+            if weight <= requested_weight { requested_weight - weight } else { WRONG_DIRECTION + weight - requested_weight }
+        } else if weight >= requested_weight && weight <= 500 {
+            weight - requested_weight
+        } else if weight < requested_weight {
+            WRONG_DIRECTION + requested_weight - weight
+        } else {
+            2 * WRONG_DIRECTION + weight - requested_weight
+        };
+
+        (stretch_distance, style_distance, weight_distance)
+    }
+}
+
+#[test]
+fn test_transform_to_matrix() {
+
+    fn assert_matrix_eq(a: [f32;6], b: [f32;6]) {
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 0.001, "{:?} != {:?}", a, b);
+        }
+    }
+
+    let rotate = StyleTransform(vec![TransformFunction::Rotate(AngleValue::deg(90.0))]);
+    // rotating (100, 0) around (0, 0) by 90 degrees ends up at (0, 100)
+    assert_matrix_eq(rotate.to_matrix(LayoutPoint { x: 0.0, y: 0.0 }), [0.0, 1.0, -1.0, 0.0, 0.0, 0.0]);
+    // rotating around (50, 50) keeps (50, 50) in place
+    assert_matrix_eq(rotate.to_matrix(LayoutPoint { x: 50.0, y: 50.0 }), [0.0, 1.0, -1.0, 0.0, 100.0, 0.0]);
+
+    // the rightmost function is applied first: scale, then translate
+    let translate_scale = StyleTransform(vec![
+        TransformFunction::TranslateX(PixelValue::px(10.0)),
+        TransformFunction::Scale(FloatValue::new(2.0), FloatValue::new(2.0)),
+    ]);
+    assert_matrix_eq(translate_scale.to_matrix(LayoutPoint { x: 0.0, y: 0.0 }), [2.0, 0.0, 0.0, 2.0, 10.0, 0.0]);
+}
+
+#[test]
+fn test_pixel_value_to_pixels_relative() {
+    let context = PixelResolveContext {
+        root_font_size: 20.0,
+        viewport_size: LayoutSize::new(800.0, 600.0),
+    };
+    assert_eq!(PixelValue::px(10.0).to_pixels_relative(400.0, &context), 10.0);
+    assert_eq!(PixelValue::percent(25.0).to_pixels_relative(400.0, &context), 100.0);
+    assert_eq!(PixelValue::rem(2.0).to_pixels_relative(400.0, &context), 40.0);
+    assert_eq!(PixelValue::vw(50.0).to_pixels_relative(400.0, &context), 400.0);
+    assert_eq!(PixelValue::vh(50.0).to_pixels_relative(400.0, &context), 300.0);
+    assert_eq!(PixelValue::vmin(10.0).to_pixels_relative(400.0, &context), 60.0);
+    assert_eq!(PixelValue::vmax(10.0).to_pixels_relative(400.0, &context), 80.0);
+    // Without a context, percentages can't be resolved
+    assert_eq!(PixelValue::percent(25.0).to_pixels(), 0.0);
+}
+
+#[test]
+fn test_pixel_value_calc() {
+    let context = PixelResolveContext {
+        root_font_size: 20.0,
+        viewport_size: LayoutSize::new(800.0, 600.0),
+    };
+
+    // calc(100% - 240px + 1rem - 10px)
+    let calc = PixelValue::calc(&[
+        (SizeMetric::Percent, 100.0),
+        (SizeMetric::Px, -240.0),
+        (SizeMetric::Rem, 1.0),
+        (SizeMetric::Px, -10.0),
+    ]);
+
+    assert_eq!(calc.to_pixels_relative(400.0, &context), 170.0);
+    assert!(calc.is_percentage());
+    assert_eq!(format!("{:?}", calc), "calc(100% - 250px + 1rem)");
+    assert_eq!(PixelValue::calc(&[(SizeMetric::Px, 10.0), (SizeMetric::Px, 5.0)]), PixelValue::px(15.0));
+
+    // Expressions are stored out of line, equal expressions get the same ID
+    assert_eq!(::std::mem::size_of::<PixelValue>(), 16);
+    assert_eq!(calc, PixelValue::calc(&[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -250.0), (SizeMetric::Rem, 1.0)]));
+    assert!(calc != PixelValue::calc(&[(SizeMetric::Percent, 100.0), (SizeMetric::Px, -250.0)]));
+}
+
+#[test]
+fn test_css_property_interpolate() {
+    let red = CssProperty::TextColor(StyleTextColor(ColorU { r: 255, g: 0, b: 0, a: 255 }));
+    let blue = CssProperty::TextColor(StyleTextColor(ColorU { r: 0, g: 0, b: 255, a: 255 }));
+    assert_eq!(red.interpolate(&blue, 0.5), Some(CssProperty::TextColor(StyleTextColor(ColorU { r: 128, g: 0, b: 128, a: 255 }))));
+
+    let from = CssProperty::Width(LayoutWidth::px(100.0));
+    let to = CssProperty::Width(LayoutWidth::px(200.0));
+    assert_eq!(from.interpolate(&to, 0.25), Some(CssProperty::Width(LayoutWidth::px(125.0))));
+
+    // Different metrics are interpolated via calc()
+    let to = CssProperty::Width(LayoutWidth::percent(50.0));
+    let halfway = match from.interpolate(&to, 0.5) {
+        Some(CssProperty::Width(LayoutWidth::Exact(value))) => value,
+        other => panic!("expected an exact width, got {:?}", other),
+    };
+    assert_eq!(halfway.get_terms(), vec![(SizeMetric::Px, 50.0), (SizeMetric::Percent, 25.0)]);
+    assert_eq!(halfway.to_pixels_relative(400.0, &PixelResolveContext::default()), 150.0);
+    assert_eq!(from.interpolate(&to, 0.5), Some(CssProperty::Width(LayoutWidth::Exact(halfway))));
+
+    let from = CssProperty::Opacity(StyleOpacity(FloatValue::new(0.0)));
+    let to = CssProperty::Opacity(StyleOpacity(FloatValue::new(1.0)));
+    assert_eq!(from.interpolate(&to, 0.5), Some(CssProperty::Opacity(StyleOpacity(FloatValue::new(0.5)))));
+
+    // Discrete properties and mismatched types can't be interpolated
+    assert_eq!(CssProperty::Position(LayoutPosition::Static).interpolate(&CssProperty::Position(LayoutPosition::Absolute), 0.5), None);
+    assert_eq!(red.interpolate(&to, 0.5), None);
+    assert_eq!(CssProperty::Width(LayoutWidth::MaxContent).interpolate(&to, 0.5), None);
+}
+
+#[test]
+fn test_animation_timing() {
+    assert_eq!(AnimationTimingFunction::Linear.evaluate(0.3), 0.3);
+    assert_eq!(AnimationTimingFunction::EaseInOut.evaluate(0.0), 0.0);
+    assert_eq!(AnimationTimingFunction::EaseInOut.evaluate(1.0), 1.0);
+    assert!((AnimationTimingFunction::EaseInOut.evaluate(0.5) - 0.5).abs() < 0.001);
+    assert!(AnimationTimingFunction::EaseIn.evaluate(0.25) < 0.25);
+    assert_eq!(AnimationTimingFunction::Steps(4, StepPosition::End).evaluate(0.3), 0.25);
+    assert_eq!(AnimationTimingFunction::Steps(4, StepPosition::Start).evaluate(0.3), 0.5);
+
+    let mut animation = Animation::new("fade".into(), Duration::from_secs(2));
+    animation.delay = Duration::from_secs(1);
+    animation.iteration_count = AnimationIterationCount::Count(FloatValue::new(2.0));
+    animation.direction = AnimationDirection::Alternate;
+
+    assert_eq!(animation.get_progress(Duration::from_millis(500)), None);
+    assert_eq!(animation.get_progress(Duration::from_millis(1500)), Some(0.25));
+    assert_eq!(animation.get_progress(Duration::from_millis(3500)), Some(0.75));
+    assert_eq!(animation.get_progress(Duration::from_secs(6)), None);
+    assert!(animation.is_finished(Duration::from_secs(5)));
+
+    animation.fill_mode = AnimationFillMode::Both;
+    assert_eq!(animation.get_progress(Duration::from_millis(500)), Some(0.0));
+    assert_eq!(animation.get_progress(Duration::from_secs(6)), Some(0.0));
+}
+
+#[test]
+fn test_border_image_get_webrender_border() {
+    let border = StyleBorder::all(StyleBorderSide {
+        border_width: PixelValue::px(4.0),
+        border_style: BorderStyle::Solid,
+        border_color: ColorU { r: 0, g: 0, b: 0, a: 255 },
+    });
+
+    let border_image = StyleBorderImage {
+        source: Some(CssImageId(String::from("frame"))),
+        slice: BorderImageOffsets { left: PixelValue::px(200.0), .. BorderImageOffsets::all(PixelValue::percent(25.0)) },
+        .. Default::default()
+    };
+
+    let (widths, details) = border_image.get_webrender_border(&border, (40, 80), &PixelResolveContext::default());
+    assert_eq!(widths.top, FloatValue::new(4.0));
+
+    match details {
+        BorderDetails::NinePatch(nine_patch) => {
+            assert_eq!((nine_patch.width, nine_patch.height), (40, 80));
+            assert_eq!(nine_patch.slice.top, FloatValue::new(20.0));
+            assert_eq!(nine_patch.slice.right, FloatValue::new(10.0));
+            // Slices are clamped to the size of the image
+            assert_eq!(nine_patch.slice.left, FloatValue::new(40.0));
+        },
+        BorderDetails::Normal(_) => panic!("expected a nine-patch border"),
+    }
+
+    let styled = border.with_styles(&StyleBorderStyle { top: Some(BorderStyle::Dashed), .. Default::default() });
+    assert_eq!(styled.top.unwrap().border_style, BorderStyle::Dashed);
+    assert_eq!(styled.left.unwrap().border_style, BorderStyle::Solid);
+}
+
+#[test]
+fn test_font_face_match_distance() {
+
+    let face = |weight, style| FontFaceProperties { weight: StyleFontWeight(weight), style, stretch: StyleFontStretch::Normal };
+    let faces = [
+        face(300, StyleFontStyle::Normal),
+        face(400, StyleFontStyle::Normal),
+        face(700, StyleFontStyle::Normal),
+        face(400, StyleFontStyle::Italic),
+    ];
+
+    let best_match = |requested: FontFaceProperties| *faces.iter().min_by_key(|f| f.get_match_distance(&requested)).unwrap();
+
+    assert_eq!(best_match(face(400, StyleFontStyle::Normal)), faces[1]);
+    assert_eq!(best_match(face(600, StyleFontStyle::Normal)), faces[2]);
+    assert_eq!(best_match(face(900, StyleFontStyle::Normal)), faces[2]);
+    assert_eq!(best_match(face(200, StyleFontStyle::Normal)), faces[0]);
+    // 500 prefers 400 (lighter) over 700 (bolder)
+    assert_eq!(best_match(face(500, StyleFontStyle::Normal)), faces[1]);
+    // The style is more important than the weight
+    assert_eq!(best_match(face(700, StyleFontStyle::Oblique)), faces[3]);
+}
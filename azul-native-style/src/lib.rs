@@ -1,7 +1,7 @@
 //! Provides azul-compatible approximations of OS-native styles.
 
 extern crate azul_css;
-use azul_css::Css;
+use azul_css::{Css, CssOrigin};
 
 extern crate azul_css_parser;
 
@@ -26,7 +26,10 @@ pub const NATIVE_CSS: &str = concat!(
     include_str!("styles/shared/table.css"),
 );
 
-/// Returns the native style for the OS
+/// Returns the native style for the OS - the stylesheets are marked as `CssOrigin::UserAgent`,
+/// so that any appended style overrides them, regardless of the selector specificity
 pub fn native() -> Css {
-    azul_css_parser::new_from_str(NATIVE_CSS).unwrap()
+    let mut css = azul_css_parser::new_from_str(NATIVE_CSS).unwrap();
+    css.set_origin(CssOrigin::UserAgent);
+    css
 }
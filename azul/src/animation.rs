@@ -0,0 +1,170 @@
+use std::{
+    collections::BTreeMap,
+    time::Instant,
+};
+use azul_css::{Css, CssProperty, CssPropertyType, Transition, Animation};
+use {
+    FastHashMap,
+    id_tree::NodeId,
+};
+
+/// Stores the running CSS transitions and animations of all nodes in a window
+///
+/// Transitions are started when the styled value of a property changes between two frames
+/// (for example because of a `:hover` selector or a dynamic CSS override), animations are
+/// started when a node is first rendered with an `animation` property.
+#[derive(Debug, Default)]
+pub(crate) struct AnimationStates {
+    nodes: FastHashMap<NodeId, NodeAnimationState>,
+}
+
+#[derive(Debug, Default)]
+struct NodeAnimationState {
+    /// The styled (non-animated) properties of the node in the last frame
+    last_properties: BTreeMap<CssPropertyType, CssProperty>,
+    /// Currently running transitions, keyed by the transitioned property
+    transitions: FastHashMap<CssPropertyType, RunningTransition>,
+    animations: Vec<RunningAnimation>,
+    /// Was the node rendered in this frame?
+    used_this_frame: bool,
+}
+
+#[derive(Debug, Clone)]
+struct RunningTransition {
+    from: CssProperty,
+    to: CssProperty,
+    transition: Transition,
+    start: Instant,
+}
+
+impl RunningTransition {
+
+    fn get_value(&self, now: Instant) -> CssProperty {
+        let progress = self.transition.get_progress(now.duration_since(self.start));
+        self.from.interpolate(&self.to, progress).unwrap_or_else(|| self.to.clone())
+    }
+
+    fn is_finished(&self, now: Instant) -> bool {
+        self.transition.is_finished(now.duration_since(self.start))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RunningAnimation {
+    animation: Animation,
+    start: Instant,
+}
+
+impl AnimationStates {
+
+    /// Updates the transitions and animations of a node, given the styled `properties`
+    /// of the node in the current frame. Returns the animated properties, which have to
+    /// be applied on top of the styled properties (transitions override animations).
+    pub(crate) fn update_node(
+        &mut self,
+        node_id: NodeId,
+        properties: BTreeMap<CssPropertyType, CssProperty>,
+        css: &Css,
+        now: Instant,
+    ) -> Vec<CssProperty> {
+
+        let node_state = self.nodes.entry(node_id).or_insert_with(|| NodeAnimationState::default());
+        node_state.used_this_frame = true;
+
+        // Start new transitions for all properties that changed since the last frame
+        let style_transition = match properties.get(&CssPropertyType::Transition) {
+            Some(CssProperty::Transition(t)) => Some(t.clone()),
+            _ => None,
+        };
+
+        if let Some(style_transition) = &style_transition {
+            for (property_type, new_value) in &properties {
+                let old_value = match node_state.last_properties.get(property_type) {
+                    Some(old) if old != new_value => old,
+                    _ => continue,
+                };
+                let transition = match style_transition.get_transition(*property_type) {
+                    Some(t) => *t,
+                    None => continue,
+                };
+                // If the property is already transitioning, start from the currently displayed value
+                let from = match node_state.transitions.get(property_type) {
+                    Some(running) => running.get_value(now),
+                    None => old_value.clone(),
+                };
+                // Properties that can't be interpolated (i.e. `display`) switch instantly
+                if from.interpolate(new_value, 0.5).is_none() {
+                    continue;
+                }
+                node_state.transitions.insert(*property_type, RunningTransition {
+                    from,
+                    to: new_value.clone(),
+                    transition,
+                    start: now,
+                });
+            }
+        }
+
+        // Drop transitions that are finished or whose target value isn't the styled value anymore
+        node_state.transitions.retain(|property_type, running| {
+            !running.is_finished(now) && properties.get(property_type) == Some(&running.to)
+        });
+
+        // Keep the start time of animations that were already running in the last frame
+        let animations = match properties.get(&CssPropertyType::Animation) {
+            Some(CssProperty::Animation(a)) => a.0.clone(),
+            _ => Vec::new(),
+        };
+
+        let mut running_animations = Vec::with_capacity(animations.len());
+        for animation in animations {
+            let start = node_state.animations.iter()
+                .find(|running| running.animation == animation)
+                .map(|running| running.start)
+                .unwrap_or(now);
+            running_animations.push(RunningAnimation { animation, start });
+        }
+        node_state.animations = running_animations;
+
+        let mut animated_properties = Vec::new();
+
+        for running in &node_state.animations {
+            let keyframes = match css.get_keyframes(&running.animation.name) {
+                Some(k) => k,
+                None => continue,
+            };
+            if let Some(progress) = running.animation.get_progress(now.duration_since(running.start)) {
+                animated_properties.extend(keyframes.get_properties(progress, running.animation.timing_function));
+            }
+        }
+
+        animated_properties.extend(node_state.transitions.values().map(|running| running.get_value(now)));
+
+        node_state.last_properties = properties;
+
+        animated_properties
+    }
+
+    /// Returns whether any transition or animation is still running, i.e. whether
+    /// the window has to be redrawn in the next frame
+    pub(crate) fn is_animating(&self, now: Instant) -> bool {
+        self.nodes.values().any(|node_state| {
+            node_state.transitions.values().any(|running| !running.is_finished(now)) ||
+            node_state.animations.iter().any(|running| !running.animation.is_finished(now.duration_since(running.start)))
+        })
+    }
+
+    /// Removes the animation states of all nodes, i.e. because the structure of the DOM
+    /// changed, so that the node IDs of the states refer to different nodes now
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    /// Removes all animation states of nodes that weren't rendered in the last frame
+    pub(crate) fn remove_unused_animation_states(&mut self) {
+        self.nodes.retain(|_, node_state| node_state.used_this_frame);
+        for node_state in self.nodes.values_mut() {
+            node_state.used_this_frame = false;
+        }
+    }
+}
@@ -19,7 +19,7 @@ use webrender::{
     PipelineInfo, Renderer,
     api::{
         HitTestResult, HitTestFlags, DevicePixel,
-        WorldPoint, LayoutSize, LayoutPoint,
+        WorldPoint, LayoutSize, LayoutPoint, ExternalScrollId,
         Epoch, Transaction, ImageData, ImageDescriptor,
     },
 };
@@ -27,17 +27,18 @@ use webrender::{
 use app_resources::ImageSource;
 #[cfg(feature = "logging")]
 use log::LevelFilter;
-use azul_css::{Css, ColorU};
+use azul_css::{Css, ColorU, FontFaceProperties, CssPathPseudoElement, ScrollbarOrientation};
 use {
     FastHashMap,
     error::ClipboardError,
     window::{
-        Window, FakeWindow, ScrollStates,
+        Window, FakeWindow, ScrollStates, ScrollbarDrag,
         WindowCreateError, WindowCreateOptions, RendererType,
     },
     window_state::{WindowSize, DebugState},
     app_resources::TextId,
     dom::{Dom, ScrollTagId},
+    diff::diff_dom_tree,
     app_resources::{
         ImageId, FontSource, FontId, ImageReloadError,
         FontReloadError, CssImageId,
@@ -333,6 +334,7 @@ impl<T> App<T> {
             read_only_window: window.display.clone(),
         };
 
+        self.app_state.resources.add_css_font_faces(&window.css);
         self.app_state.windows.insert(window_id, fake_window);
         self.windows.insert(window_id, window);
     }
@@ -417,6 +419,7 @@ impl<T> App<T> {
             });
 
             let mut single_window_results = Vec::with_capacity(self.windows.len());
+            let mut scrolled_windows = Vec::new();
 
             for (current_window_id, mut window) in self.windows.iter_mut() {
 
@@ -438,6 +441,10 @@ impl<T> App<T> {
                     frame_was_resize = true;
                 }
 
+                if single_window_result.should_scroll_render {
+                    scrolled_windows.push(*current_window_id);
+                }
+
                 if single_window_result.window_should_close {
                     closed_windows.push(*current_window_id);
 
@@ -453,6 +460,8 @@ impl<T> App<T> {
             #[cfg(debug_assertions)] {
                 hot_reload_css(
                     &mut self.windows,
+                    &mut ui_description_cache,
+                    &mut self.app_state.resources,
                     &mut last_style_reload,
                     &mut should_print_css_error,
                     &mut awakened_tasks,
@@ -474,8 +483,15 @@ impl<T> App<T> {
             let should_redraw_tasks = self.app_state.clean_up_finished_tasks();
             let should_redraw_timers_or_tasks = [should_redraw_timers, should_redraw_tasks].into_iter().any(|e| *e == Redraw);
 
+            // Running CSS transitions and animations need a new display list every frame
+            let now = Instant::now();
+            let animating_windows = self.windows.iter()
+                .filter(|(_, window)| window.animation_states.is_animating(now))
+                .map(|(window_id, _)| *window_id)
+                .collect::<Vec<GliumWindowId>>();
+
             // If there is a relayout necessary, re-layout *all* windows!
-            if should_relayout_all_windows || should_redraw_timers_or_tasks{
+            if should_relayout_all_windows || should_redraw_timers_or_tasks {
                 for (current_window_id, mut window) in self.windows.iter_mut() {
                     relayout_single_window(
                         self.layout_callback,
@@ -488,11 +504,27 @@ impl<T> App<T> {
                         &mut awakened_tasks,
                     )?;
                 }
+            } else {
+                // Only the animated values or the scroll positions changed, the DOM and the styles of the last frame can be re-used
+                let redrawn_windows = self.windows.iter_mut().filter(|(id, _)| animating_windows.contains(id) || scrolled_windows.contains(id));
+                for (current_window_id, mut window) in redrawn_windows {
+                    redraw_single_window(
+                        &current_window_id,
+                        &mut window,
+                        &mut self.app_state,
+                        &ui_state_cache,
+                        &ui_description_cache,
+                    )?;
+                }
             }
 
             // If there is a re-render necessary, re-render *all* windows
-            if should_rerender_all_windows || should_redraw_timers_or_tasks {
-                for window in self.windows.values_mut() {
+            let should_rerender = should_rerender_all_windows || should_redraw_timers_or_tasks;
+            if should_rerender || !animating_windows.is_empty() {
+                for (current_window_id, window) in self.windows.iter_mut() {
+                    if !should_rerender && !animating_windows.contains(current_window_id) {
+                        continue;
+                    }
                     // TODO: For some reason this function has to be called twice in order
                     // to actually update the screen. For some reason the first swap_buffers() has
                     // no effect (winit bug?)
@@ -731,7 +763,10 @@ fn hit_test_single_window<T>(
         None => false,
     };
 
-    ret.should_scroll_render = should_scroll_render;
+    // Drag the scrollbar thumbs / scroll by clicking on the scrollbar tracks
+    let should_scrollbar_render = update_scrollbar_state(window, &events, ret.hit_test_results.as_ref());
+
+    ret.should_scroll_render = should_scroll_render || should_scrollbar_render;
 
     if frame_event_info.is_resize_event {
         // This is a hack because during a resize event, winit eats the "awakened"
@@ -782,21 +817,34 @@ fn relayout_single_window<T>(
     use self::RuntimeError::*;
 
     // Call the Layout::layout() fn, get the DOM
-    *ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)? =
-        UiState::from_app_state(app_state, window_id, layout_callback)?;
+    let new_ui_state = UiState::from_app_state(app_state, window_id, layout_callback)?;
+    let ui_state = ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)?;
+
+    // Compare the new DOM with the DOM of the last frame, so that only the changed nodes get restyled
+    let mut dom_diff = diff_dom_tree(&ui_state.dom, &new_ui_state.dom);
+    dom_diff.relayout_resized_images(&ui_state.dom, &new_ui_state.dom, &app_state.resources);
+    *ui_state = new_ui_state;
+
+    // The running transitions and animations are stored by node ID
+    if dom_diff.structure_changed {
+        window.animation_states.clear();
+    }
 
     // Style the DOM (is_mouse_down is necessary for styling :hover, :active + :focus nodes)
     let is_mouse_down = window.state.internal.mouse_state.mouse_down();
 
-    *ui_description_cache.get_mut(window_id).ok_or(WindowIndexError)? =
-        UiDescription::match_css_to_dom(
-            ui_state_cache.get_mut(window_id).ok_or(WindowIndexError)?,
-            &window.css,
-            &mut window.state.internal.focused_node,
-            &mut window.state.internal.pending_focus_target,
-            &window.state.internal.hovered_nodes,
-            is_mouse_down,
-        );
+    let ui_description = UiDescription::restyle(
+        ui_state,
+        ui_description_cache.get(window_id).ok_or(WindowIndexError)?,
+        &mut dom_diff,
+        &window.css,
+        &mut window.state.internal.focused_node,
+        &mut window.state.internal.pending_focus_target,
+        &window.state.internal.hovered_nodes,
+        is_mouse_down,
+        &window.state.get_media_query_context(),
+    );
+    *ui_description_cache.get_mut(window_id).ok_or(WindowIndexError)? = ui_description;
 
     let mut fake_window = app_state.windows.get_mut(window_id).ok_or(WindowIndexError)?;
     update_display_list(
@@ -806,6 +854,7 @@ fn relayout_single_window<T>(
         &mut *window,
         &mut fake_window,
         &mut app_state.resources,
+        dom_diff.needs_relayout(),
     );
     *awakened_tasks.get_mut(window_id).ok_or(WindowIndexError)? = false;
 
@@ -819,6 +868,35 @@ fn relayout_single_window<T>(
     Ok(())
 }
 
+/// Redraws a window with running CSS transitions or animations or a window that was scrolled
+/// (the scrollbar thumbs are positioned when building the display list): The DOM and the styles
+/// of the last frame are re-used, only the animated values are interpolated again. The layout is
+/// only re-calculated if one of the animated properties affects the layout.
+#[cfg(not(test))]
+fn redraw_single_window<T>(
+    window_id: &GliumWindowId,
+    window: &mut Window<T>,
+    app_state: &mut AppState<T>,
+    ui_state_cache: &BTreeMap<GliumWindowId, UiState<T>>,
+    ui_description_cache: &BTreeMap<GliumWindowId, UiDescription<T>>,
+) -> Result<(), RuntimeError<T>> {
+
+    use self::RuntimeError::*;
+
+    let mut fake_window = app_state.windows.get_mut(window_id).ok_or(WindowIndexError)?;
+    update_display_list(
+        &mut app_state.data,
+        ui_description_cache.get(window_id).ok_or(WindowIndexError)?,
+        ui_state_cache.get(window_id).ok_or(WindowIndexError)?,
+        &mut *window,
+        &mut fake_window,
+        &mut app_state.resources,
+        false,
+    );
+
+    Ok(())
+}
+
 #[cfg(not(test))]
 fn rerender_single_window<T>(
     config: &AppConfig,
@@ -832,6 +910,8 @@ fn rerender_single_window<T>(
 #[cfg(debug_assertions)]
 fn hot_reload_css<T>(
     windows: &mut BTreeMap<GliumWindowId, Window<T>>,
+    ui_description_cache: &mut BTreeMap<GliumWindowId, UiDescription<T>>,
+    app_resources: &mut AppResources,
     last_style_reload: &mut Instant,
     should_print_error: &mut bool,
     awakened_tasks: &mut BTreeMap<GliumWindowId, bool>,
@@ -855,7 +935,12 @@ fn hot_reload_css<T>(
         match hot_reloader.reload_style() {
             Ok(mut new_css) => {
                 new_css.sort_by_specificity();
+                app_resources.add_css_font_faces(&new_css);
                 window.css = new_css;
+                // The styles of the last frame can't be re-used with the new CSS
+                if let Some(ui_description) = ui_description_cache.get_mut(window_id) {
+                    ui_description.style_cache = None;
+                }
                 if !(*should_print_error) {
                     println!("--- OK: CSS parsed without errors, continuing hot-reload.");
                 }
@@ -1041,6 +1126,10 @@ fn call_callbacks<T>(
 }
 
 /// Build the display list and send it to webrender
+///
+/// If `needs_relayout` is false, only paint properties changed since the last frame, so the
+/// layout of the last frame is re-used (if the window size, the HiDPI factor and the loaded
+/// fonts and images didn't change, see `CachedLayoutKey`)
 #[cfg(not(test))]
 fn update_display_list<T>(
     app_data: &mut Arc<Mutex<T>>,
@@ -1049,20 +1138,31 @@ fn update_display_list<T>(
     window: &mut Window<T>,
     fake_window: &mut FakeWindow<T>,
     app_resources: &mut AppResources,
+    needs_relayout: bool,
 ) {
     use display_list::DisplayList;
 
-    let display_list = DisplayList::new_from_ui_description(ui_description, ui_state);
+    let mut display_list = DisplayList::new_from_ui_description(ui_description, ui_state);
+    let animations_affect_layout = display_list.apply_animations(&mut window.animation_states, &window.css, Instant::now());
+
+    let cached_layout = window.internal.cached_layout.take()
+        .filter(|_| !needs_relayout && !animations_affect_layout);
 
-    // NOTE: layout_result contains all words, text information, etc.
+    // NOTE: the layout result contains all words, text information, etc.
     // - very important for selection!
-    let (builder, scrolled_nodes, _layout_result) = display_list.into_display_list_builder(
+    let (builder, scrolled_nodes, cached_layout) = display_list.into_display_list_builder(
         app_data,
         window,
         fake_window,
         app_resources,
+        cached_layout,
     );
 
+    // Layouts with animated layout properties change every frame, so they can't be re-used
+    if !animations_affect_layout {
+        window.internal.cached_layout = Some(cached_layout);
+    }
+
     // NOTE: Display list has to be rebuilt every frame, otherwise, the epochs get out of sync
     let display_list_builder = builder.finalize().2;
     window.internal.last_scrolled_nodes = scrolled_nodes;
@@ -1146,6 +1246,136 @@ fn update_scroll_state<T>(
     should_scroll_render
 }
 
+/// Handles the mouse events on the scrollbars of the last frame: Pressing the left mouse button
+/// on a thumb starts dragging the thumb, pressing it on a track scrolls the node by one page
+/// towards the cursor. Returns whether a node was scrolled.
+#[must_use]
+fn update_scrollbar_state<T>(
+    window: &mut Window<T>,
+    events: &[WindowEvent],
+    hit_test_results: Option<&HitTestResult>,
+) -> bool {
+
+    use glium::glutin::{ElementState, MouseButton};
+
+    let mut should_scroll_render = false;
+    let mut cursor_moved = false;
+
+    for event in events {
+        match event {
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                if let Some(hit_test_results) = hit_test_results {
+                    if start_scrollbar_interaction(window, hit_test_results) {
+                        should_scroll_render = true;
+                    }
+                }
+            },
+            WindowEvent::MouseInput { state: ElementState::Released, button: MouseButton::Left, .. } => {
+                window.scrollbar_drag = None;
+            },
+            WindowEvent::CursorMoved { .. } => {
+                cursor_moved = true;
+            },
+            _ => { },
+        }
+    }
+
+    // The button may have been released outside of the window
+    if !window.state.internal.mouse_state.left_down {
+        window.scrollbar_drag = None;
+    }
+
+    let drag = match window.scrollbar_drag {
+        Some(drag) if cursor_moved => drag,
+        _ => return should_scroll_render,
+    };
+
+    let cursor_pos = match window.state.internal.mouse_state.cursor_pos {
+        Some(pos) => pos,
+        None => return should_scroll_render,
+    };
+
+    // The DOM may have changed since the drag was started
+    let scrollbar = window.internal.last_scrolled_nodes.overflowing_nodes.get(&drag.node_id)
+        .filter(|scroll_node| scroll_node.parent_external_scroll_id == drag.scroll_id)
+        .and_then(|scroll_node| scroll_node.get_scrollbar(drag.orientation));
+
+    let scrollbar = match scrollbar {
+        Some(s) => s,
+        None => {
+            window.scrollbar_drag = None;
+            return should_scroll_render;
+        },
+    };
+
+    let cursor_on_track = scrollbar.get_axis_position(cursor_pos.x as f32, cursor_pos.y as f32);
+    let new_scroll_offset = scrollbar.get_drag_scroll_offset(drag.start_cursor_pos, drag.start_scroll_offset, cursor_on_track);
+    scroll_along_scrollbar(&mut window.scroll_states, drag.scroll_id, drag.orientation, new_scroll_offset);
+
+    true
+}
+
+/// Starts dragging the scrollbar thumb or scrolls the node by one page if the mouse
+/// was pressed on a scrollbar track. Returns whether the node was scrolled.
+fn start_scrollbar_interaction<T>(window: &mut Window<T>, hit_test_results: &HitTestResult) -> bool {
+
+    let cursor_pos = match window.state.internal.mouse_state.cursor_pos {
+        Some(pos) => pos,
+        None => return false,
+    };
+
+    let scrolled_nodes = &window.internal.last_scrolled_nodes;
+
+    // The hit-test results are ordered back-to-front, the thumb is drawn on top of the track
+    let (node_id, scroll_node, scrollbar, scrollbar_part, hit_item) = match hit_test_results.items.iter().rev().filter_map(|item| {
+        let (node_id, scroll_node, scrollbar, scrollbar_part) = scrolled_nodes.get_scrollbar_by_tag(item.tag.0)?;
+        Some((node_id, scroll_node, scrollbar, scrollbar_part, item))
+    }).next() {
+        Some(s) => s,
+        None => return false,
+    };
+
+    let scroll_id = scroll_node.parent_external_scroll_id;
+    let (scroll_x, scroll_y) = window.scroll_states.get_scroll_amount(&scroll_id).unwrap_or((0.0, 0.0));
+    let scroll_offset = scrollbar.get_axis_position(scroll_x, scroll_y);
+
+    match scrollbar_part {
+        CssPathPseudoElement::ScrollbarThumb => {
+            window.scrollbar_drag = Some(ScrollbarDrag {
+                node_id,
+                scroll_id,
+                orientation: scrollbar.orientation,
+                start_cursor_pos: scrollbar.get_axis_position(cursor_pos.x as f32, cursor_pos.y as f32),
+                start_scroll_offset: scroll_offset,
+            });
+            false
+        },
+        _ => {
+            // Position of the cursor on the track, independent of any scrolled parent node
+            let cursor_on_track = scrollbar.get_axis_position(
+                scrollbar.bounds.origin.x + hit_item.point_relative_to_item.x,
+                scrollbar.bounds.origin.y + hit_item.point_relative_to_item.y,
+            );
+            let new_scroll_offset = match scrollbar.get_track_click_scroll_offset(scroll_offset, cursor_on_track) {
+                Some(s) => s,
+                None => return false,
+            };
+            let orientation = scrollbar.orientation;
+            scroll_along_scrollbar(&mut window.scroll_states, scroll_id, orientation, new_scroll_offset);
+            true
+        },
+    }
+}
+
+/// Scrolls the node to the new offset in the direction of the scrollbar, keeping the other direction
+fn scroll_along_scrollbar(scroll_states: &mut ScrollStates, scroll_id: ExternalScrollId, orientation: ScrollbarOrientation, new_scroll_offset: f32) {
+    let (scroll_x, scroll_y) = scroll_states.get_scroll_amount(&scroll_id).unwrap_or((0.0, 0.0));
+    match orientation {
+        ScrollbarOrientation::Horizontal => scroll_states.scroll_node_to(&scroll_id, new_scroll_offset, scroll_y),
+        ScrollbarOrientation::Vertical => scroll_states.scroll_node_to(&scroll_id, scroll_x, new_scroll_offset),
+    }
+}
+
 fn clean_up_unused_opengl_textures(pipeline_info: PipelineInfo) {
 
     use compositor::ACTIVE_GL_TEXTURES;
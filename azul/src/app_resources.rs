@@ -5,11 +5,12 @@ use std::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 use webrender::api::{
-    FontKey, FontInstanceKey, ImageKey, AddImage,
+    FontKey, FontInstanceKey, ImageKey, AddImage, LayoutSize,
     ResourceUpdate, AddFont, AddFontInstance, RenderApi,
 };
 use app_units::Au;
 use clipboard2::{Clipboard, ClipboardError, SystemClipboard};
+use azul_css::{Css, FontFaceSource, FontFaceProperties, StyleFontStyle};
 use {
     FastHashMap, FastHashSet,
     window::{FakeDisplay, WindowCreateError},
@@ -39,8 +40,9 @@ pub struct AppResources {
     fake_render_api: FakeRenderApi,
     /// The CssImageId is the string used in the CSS, i.e. "my_image" -> ImageId(4)
     css_ids_to_image_ids: FastHashMap<CssImageId, ImageId>,
-    /// Same as CssImageId -> ImageId, but for fonts, i.e. "Roboto" -> FontId(9)
-    css_ids_to_font_ids: FastHashMap<CssFontId, FontId>,
+    /// Same as CssImageId -> ImageId, but for fonts - one family can have multiple faces,
+    /// i.e. "Roboto" -> (bold, italic) -> FontId(9)
+    css_ids_to_font_ids: FastHashMap<CssFontId, FastHashMap<FontFaceProperties, FontId>>,
     /// Stores where the images were loaded from
     image_sources: FastHashMap<ImageId, ImageSource>,
     /// Stores where the fonts were loaded from
@@ -61,6 +63,13 @@ pub struct AppResources {
     /// the string of the file path where the font was loaded from, so no huge memory pressure).
     /// The reason for this agressive strategy is that the
     last_frame_font_keys: FastHashMap<ImmediateFontId, FastHashSet<Au>>,
+    /// System fonts that couldn't be found, so that the next font of the `font-family`
+    /// is used instead and the system isn't searched for the font again on every frame
+    missing_fonts: FastHashSet<ImmediateFontId>,
+    /// Incremented every time that fonts or images are added to or deleted from the RenderApi
+    /// or that system fonts turn out to be missing - the layout depends on the loaded fonts
+    /// and images, so a layout that was done for an older generation can't be re-used
+    resource_generation: usize,
     /// Stores long texts across frames
     text_cache: TextCache,
     /// Keyboard clipboard storage and retrieval functionality
@@ -248,7 +257,7 @@ impl FontSource {
                 .map_err(|e| FontReloadError::Io(e, file_path.clone()))
                 .map(|f| (f, 0))
             },
-            System(id) => load_system_font(id).ok_or(FontReloadError::FontNotFound(id.clone())),
+            System(id) => load_system_font(id, FontFaceProperties::default()).ok_or(FontReloadError::FontNotFound(id.clone())),
         }
     }
 }
@@ -385,6 +394,8 @@ impl AppResources {
             currently_registered_images: FastHashMap::default(),
             last_frame_font_keys: FastHashMap::default(),
             last_frame_image_keys: FastHashSet::default(),
+            missing_fonts: FastHashSet::default(),
+            resource_generation: 0,
             text_cache: TextCache::default(),
             clipboard: SystemClipboard::new().unwrap(),
         })
@@ -466,26 +477,98 @@ impl AppResources {
 
     // -- FontId cache
 
+    /// Registers the normal face (`font-weight: normal`, `font-style: normal`) of the `css_id`
+    /// font family, see `add_css_font_face`
     pub fn add_css_font_id<S: Into<String>>(&mut self, css_id: S) -> FontId {
-        *self.css_ids_to_font_ids.entry(css_id.into()).or_insert_with(|| FontId::new())
+        self.add_css_font_face(css_id, FontFaceProperties::default())
+    }
+
+    /// Registers one face of the `css_id` font family, i.e. the bold or italic variant, so that
+    /// nodes with `font-family: css_id` can select it via `font-weight` and `font-style`
+    pub fn add_css_font_face<S: Into<String>>(&mut self, css_id: S, properties: FontFaceProperties) -> FontId {
+        *self.css_ids_to_font_ids.entry(css_id.into()).or_insert_with(|| FastHashMap::default())
+            .entry(properties).or_insert_with(|| FontId::new())
     }
 
     pub fn has_css_font_id(&self, css_id: &str) -> bool {
         self.get_css_font_id(css_id).is_some()
     }
 
+    /// Returns the face of the `css_id` font family that matches the normal face best
     pub fn get_css_font_id(&self, css_id: &str) -> Option<&FontId> {
-        self.css_ids_to_font_ids.get(css_id)
+        self.get_css_font_face(css_id, FontFaceProperties::default())
     }
 
+    /// Returns the registered face of the `css_id` font family that matches the `properties`
+    /// best (i.e. the normal face if no bold face is registered), `None` if the family has no faces
+    pub fn get_css_font_face(&self, css_id: &str, properties: FontFaceProperties) -> Option<&FontId> {
+        self.css_ids_to_font_ids.get(css_id)?
+            .iter()
+            .min_by_key(|(face_properties, _)| face_properties.get_match_distance(&properties))
+            .map(|(_, font_id)| font_id)
+    }
+
+    /// Removes the normal face of the `css_id` font family, see `delete_css_font_face`
     pub fn delete_css_font_id(&mut self, css_id: &str) -> Option<FontId> {
-        self.css_ids_to_font_ids.remove(css_id)
+        self.delete_css_font_face(css_id, FontFaceProperties::default())
+    }
+
+    pub fn delete_css_font_face(&mut self, css_id: &str, properties: FontFaceProperties) -> Option<FontId> {
+        let faces = self.css_ids_to_font_ids.get_mut(css_id)?;
+        let font_id = faces.remove(&properties);
+        if faces.is_empty() {
+            self.css_ids_to_font_ids.remove(css_id);
+        }
+        font_id
     }
 
     pub fn add_font(&mut self, font_id: FontId, font_source: FontSource) {
         self.font_sources.insert(font_id, font_source);
     }
 
+    /// Registers the fonts of all `@font-face` rules of the `css` under their `font-family`,
+    /// weight, style and stretch.
+    ///
+    /// The first `url()` that points to an existing file is used, otherwise the first `local()`
+    /// font. Fonts declared in CSS replace fonts with the same CSS ID that were added via
+    /// `add_css_font_id`. If the source of a font changes (i.e. because the CSS was hot-reloaded),
+    /// the font gets a new `FontId`, so that the font is loaded again.
+    pub(crate) fn add_css_font_faces(&mut self, css: &Css) {
+        for font_face in css.stylesheets.iter().flat_map(|stylesheet| stylesheet.font_faces.iter()) {
+
+            let font_source = font_face.sources.iter().filter_map(|source| match source {
+                FontFaceSource::Url(path) if path.exists() => Some(FontSource::File(path.clone())),
+                _ => None,
+            }).chain(font_face.sources.iter().filter_map(|source| match source {
+                FontFaceSource::Local(name) => Some(FontSource::System(name.clone())),
+                _ => None,
+            })).next();
+
+            let font_source = match font_source {
+                Some(s) => s,
+                None => {
+                    #[cfg(feature = "logging")] {
+                        warn!("No source of @font-face \"{}\" could be found: {:?}", font_face.font_family, font_face.sources);
+                    }
+                    continue;
+                },
+            };
+
+            let is_already_registered = self.css_ids_to_font_ids.get(&font_face.font_family)
+                .and_then(|faces| faces.get(&font_face.properties))
+                .and_then(|font_id| self.font_sources.get(font_id)) == Some(&font_source);
+
+            if is_already_registered {
+                continue;
+            }
+
+            let font_id = FontId::new();
+            self.css_ids_to_font_ids.entry(font_face.font_family.clone()).or_insert_with(|| FastHashMap::default())
+                .insert(font_face.properties, font_id);
+            self.add_font(font_id, font_source);
+        }
+    }
+
     /// Given a `FontId`, returns the bytes for that font or `None`, if the `FontId` is invalid.
     pub fn get_font_bytes(&self, font_id: &FontId) -> Option<Result<(Vec<u8>, i32), FontReloadError>> {
         let font_source = self.font_sources.get(font_id)?;
@@ -539,16 +622,46 @@ impl AppResources {
         self.currently_registered_fonts.get(font_id)
     }
 
+    /// Returns the registered face of the `css_id` font family that matches the `properties`
+    /// best or, if no font with this family is registered, the system font of that family
+    pub(crate) fn get_immediate_font_id(&self, css_id: &str, properties: FontFaceProperties) -> ImmediateFontId {
+        match self.get_css_font_face(css_id, properties) {
+            Some(font_id) => ImmediateFontId::Resolved(*font_id),
+            None => ImmediateFontId::Unresolved(css_id.to_string(), properties),
+        }
+    }
+
+    /// Returns whether the font is a system font that doesn't exist on this system
+    pub(crate) fn is_font_missing(&self, font_id: &ImmediateFontId) -> bool {
+        self.missing_fonts.contains(font_id)
+    }
+
+    /// Returns the current generation of the loaded fonts and images, see `resource_generation`
+    pub(crate) fn get_resource_generation(&self) -> usize {
+        self.resource_generation
+    }
+
     /// Scans the DisplayList for new images and fonts. After this call, the RenderApi is
     /// guaranteed to know about all FontKeys and FontInstanceKey
-    pub(crate) fn add_fonts_and_images<T>(&mut self, display_list: &DisplayList<T>) {
-        let font_keys = scan_ui_description_for_font_keys(&self, display_list);
+    ///
+    /// The `viewport_size` is necessary to resolve font sizes given in `vw` / `vh`.
+    pub(crate) fn add_fonts_and_images<T>(&mut self, display_list: &DisplayList<T>, viewport_size: LayoutSize) {
+        let mut font_keys = scan_ui_description_for_font_keys(&self, display_list, viewport_size);
+        let (mut add_font_resource_updates, missing_fonts) = build_add_font_resource_updates(self, &font_keys);
+
+        // If system fonts are missing, the next fonts of the font-family lists have to be loaded instead
+        if !missing_fonts.is_empty() {
+            self.missing_fonts.extend(missing_fonts);
+            self.resource_generation += 1;
+            font_keys = scan_ui_description_for_font_keys(&self, display_list, viewport_size);
+            add_font_resource_updates = build_add_font_resource_updates(self, &font_keys).0;
+        }
+
         let image_keys = scan_ui_description_for_image_keys(&self, display_list);
 
         self.last_frame_font_keys.extend(font_keys.clone().into_iter());
         self.last_frame_image_keys.extend(image_keys.clone().into_iter());
 
-        let add_font_resource_updates = build_add_font_resource_updates(self, &font_keys);
         let add_image_resource_updates = build_add_image_resource_updates(self, &image_keys);
 
         add_resources(self, add_font_resource_updates, add_image_resource_updates);
@@ -573,19 +686,23 @@ impl AppResources {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) enum ImmediateFontId {
     Resolved(FontId),
-    Unresolved(CssFontId),
+    /// System font, the properties select the bold / italic / condensed face of the family
+    Unresolved(CssFontId, FontFaceProperties),
 }
 
-/// Scans the display list for all font IDs + their font size
+/// Scans the display list for all font IDs + their font size. All fonts of the
+/// `font-family` are loaded, since characters can fall back to the next font
 fn scan_ui_description_for_font_keys<'a, T>(
     app_resources: &AppResources,
-    display_list: &DisplayList<'a, T>
+    display_list: &DisplayList<'a, T>,
+    viewport_size: LayoutSize,
 ) -> FastHashMap<ImmediateFontId, FastHashSet<Au>> {
 
     use dom::NodeType::*;
     use ui_solver;
 
     let mut font_keys = FastHashMap::default();
+    let resolve_context = ui_solver::get_resolve_context(&display_list.rectangles, viewport_size);
 
     for node_id in display_list.rectangles.linear_iter() {
 
@@ -594,16 +711,13 @@ fn scan_ui_description_for_font_keys<'a, T>(
 
         match node_data.node_type {
             Text(_) | Label(_) => {
-                let css_font_id = ui_solver::get_font_id(&display_rect.style);
-                let font_id = match app_resources.css_ids_to_font_ids.get(css_font_id) {
-                    Some(s) => ImmediateFontId::Resolved(*s),
-                    None => ImmediateFontId::Unresolved(css_font_id.to_string()),
-                };
-                let font_size = ui_solver::get_font_size(&display_rect.style);
-                font_keys
-                    .entry(font_id)
-                    .or_insert_with(|| FastHashSet::default())
-                    .insert(ui_solver::font_size_to_au(font_size));
+                let font_size = ui_solver::get_font_size_px(&display_rect.style, &resolve_context);
+                for font_id in ui_solver::get_font_ids(&display_rect.style, app_resources) {
+                    font_keys
+                        .entry(font_id)
+                        .or_insert_with(|| FastHashSet::default())
+                        .insert(ui_solver::px_to_au(font_size));
+                }
             },
             _ => { }
         }
@@ -623,14 +737,15 @@ fn scan_ui_description_for_image_keys<'a, T>(
     display_list.rectangles
     .iter()
     .zip(display_list.ui_descr.ui_descr_arena.node_data.iter())
-    .filter_map(|(display_rect, node_data)| {
+    .flat_map(|(display_rect, node_data)| {
         match node_data.node_type {
-            Image(id) => Some(id),
+            Image(id) => vec![id],
             _ => {
-                let background = display_rect.style.background.as_ref()?;
-                let css_image_id = background.get_css_image_id()?;
-                let image_id = app_resources.get_css_image_id(&css_image_id.0)?;
-                Some(*image_id)
+                display_rect.style.background.iter()
+                .flat_map(|background| background.get_css_image_ids())
+                .chain(display_rect.style.border_image.iter().filter_map(|border_image| border_image.source.as_ref()))
+                .filter_map(|css_image_id| app_resources.get_css_image_id(&css_image_id.0).cloned())
+                .collect()
             }
         }
     }).collect()
@@ -692,7 +807,7 @@ impl DeleteImageMsg {
 
 /// Given the fonts of the current frame, returns `AddFont` and `AddFontInstance`s of
 /// which fonts / instances are currently not in the `current_registered_fonts` and
-/// need to be added.
+/// need to be added. Also returns the system fonts that couldn't be found.
 ///
 /// Deleting fonts can only be done after the entire frame has finished drawing,
 /// otherwise (if removing fonts would happen after every DOM) we'd constantly
@@ -701,11 +816,12 @@ impl DeleteImageMsg {
 fn build_add_font_resource_updates(
     app_resources: &AppResources,
     fonts_in_dom: &FastHashMap<ImmediateFontId, FastHashSet<Au>>,
-) -> Vec<(ImmediateFontId, AddFontMsg)> {
+) -> (Vec<(ImmediateFontId, AddFontMsg)>, Vec<ImmediateFontId>) {
 
     use webrender::api::{FontInstancePlatformOptions, FontInstanceOptions, FontRenderMode, FontInstanceFlags};
 
     let mut resource_updates = Vec::new();
+    let mut missing_fonts = Vec::new();
 
     for (im_font_id, font_sizes) in fonts_in_dom {
 
@@ -772,22 +888,27 @@ fn build_add_font_resource_updates(
                 use self::ImmediateFontId::*;
 
                 // If there is no font key, that means there's also no font instances
-                let font_source = match im_font_id {
+                let font_bytes = match im_font_id {
                     Resolved(font_id) => {
                         match app_resources.font_sources.get(font_id) {
-                            Some(s) => s.clone(),
+                            Some(s) => s.get_bytes(),
                             None => continue,
                         }
                     },
-                    Unresolved(css_font_id) => FontSource::System(css_font_id.clone()),
+                    Unresolved(css_font_id, properties) => {
+                        load_system_font(css_font_id, *properties).ok_or(FontReloadError::FontNotFound(css_font_id.clone()))
+                    },
                 };
 
-                let (font_bytes, font_index) = match font_source.get_bytes() {
+                let (font_bytes, font_index) = match font_bytes {
                     Ok(o) => o,
                     Err(e) => {
                         #[cfg(feature = "logging")] {
                             warn!("Could not load font with ID: {:?} - error: {}", im_font_id, e);
                         }
+                        if let FontReloadError::FontNotFound(_) = e {
+                            missing_fonts.push(im_font_id.clone());
+                        }
                         continue;
                     }
                 };
@@ -805,7 +926,7 @@ fn build_add_font_resource_updates(
         }
     }
 
-    resource_updates
+    (resource_updates, missing_fonts)
 }
 
 /// Given the images of the current frame, returns `AddImage`s of
@@ -860,6 +981,7 @@ fn add_resources(
         app_resources.get_render_api().update_resources(merged_resource_updates);
         // Assure that the AddFont / AddImage updates get processed immediately
         app_resources.get_render_api().flush_scene_builder();
+        app_resources.resource_generation += 1;
     }
 
     for (image_id, add_image_msg) in add_image_resources.iter() {
@@ -919,6 +1041,7 @@ fn delete_resources(
 
     if !merged_resource_updates.is_empty() {
         app_resources.get_render_api().update_resources(merged_resource_updates);
+        app_resources.resource_generation += 1;
     }
 
     for (removed_id, _removed_info) in delete_image_resources {
@@ -944,39 +1067,59 @@ fn decode_image_data(image_data: Vec<u8>) -> Result<(ImageData, ImageDescriptor)
 }
 
 /// Returns the font + the index of the font (in case the font is a collection)
-fn load_system_font(id: &str) -> Option<(Vec<u8>, i32)> {
+fn load_system_font(id: &str, properties: FontFaceProperties) -> Option<(Vec<u8>, i32)> {
     use font_loader::system_fonts::{self, FontPropertyBuilder};
 
-    let font_builder = match id {
+    let family = match id {
         "monospace" => {
             #[cfg(target_os = "linux")] {
-                let native_monospace_font = linux_get_native_font(LinuxNativeFontType::Monospace);
-                FontPropertyBuilder::new().family(&native_monospace_font)
+                Some(linux_get_native_font(LinuxNativeFontType::Monospace))
             }
             #[cfg(not(target_os = "linux"))] {
-                FontPropertyBuilder::new().monospace()
+                None
             }
         },
-        "fantasy" => FontPropertyBuilder::new().oblique(),
+        "fantasy" => None,
         "sans-serif" => {
             #[cfg(target_os = "mac_os")] {
-                FontPropertyBuilder::new().family("Helvetica")
+                Some(String::from("Helvetica"))
             }
             #[cfg(target_os = "linux")] {
-                let native_sans_serif_font = linux_get_native_font(LinuxNativeFontType::SansSerif);
-                FontPropertyBuilder::new().family(&native_sans_serif_font)
+                Some(linux_get_native_font(LinuxNativeFontType::SansSerif))
             }
             #[cfg(all(not(target_os = "linux"), not(target_os = "mac_os")))] {
-                FontPropertyBuilder::new().family("Segoe UI")
+                Some(String::from("Segoe UI"))
             }
         },
-        "serif" => {
-            FontPropertyBuilder::new().family("Times New Roman")
-        },
-        other => FontPropertyBuilder::new().family(other)
+        "serif" => Some(String::from("Times New Roman")),
+        other => Some(other.to_string()),
+    };
+
+    let font_builder = |family: Option<&str>| {
+        let mut font_builder = match (id, family) {
+            ("monospace", None) => FontPropertyBuilder::new().monospace(),
+            ("fantasy", _) => FontPropertyBuilder::new().oblique(),
+            (_, Some(family)) => FontPropertyBuilder::new().family(family),
+            (_, None) => FontPropertyBuilder::new(),
+        };
+        if properties.weight.is_bold() {
+            font_builder = font_builder.bold();
+        }
+        match properties.style {
+            StyleFontStyle::Normal => { },
+            StyleFontStyle::Italic => { font_builder = font_builder.italic(); },
+            StyleFontStyle::Oblique => { font_builder = font_builder.oblique(); },
+        }
+        font_builder.build()
+    };
+
+    // Condensed / expanded faces are usually separate families, i.e. "Roboto Condensed"
+    let stretched_font = match (&family, properties.stretch.get_family_suffix()) {
+        (Some(family), Some(suffix)) => system_fonts::get(&font_builder(Some(&format!("{} {}", family, suffix)))),
+        _ => None,
     };
 
-    system_fonts::get(&font_builder.build())
+    stretched_font.or_else(|| system_fonts::get(&font_builder(family.as_ref().map(|f| f.as_str()))))
 }
 
 /// Return the native fonts
@@ -1180,6 +1323,7 @@ fn test_font_gc() {
     use ui_description::UiDescription;
     use ui_state::UiState;
     use ui_solver::px_to_au;
+    use azul_css::MediaQueryContext;
     use {FastHashMap, FastHashSet};
     use std::hash::Hash;
 
@@ -1190,6 +1334,7 @@ fn test_font_gc() {
     let mut pending_focus_target = None;
     let is_mouse_down = false;
     let hovered_nodes = BTreeMap::new();
+    let viewport_size = LayoutSize::new(800.0, 600.0);
     let css = css::from_str(r#"
         #one { font-family: Helvetica; }
         #two { font-family: Arial; }
@@ -1201,14 +1346,14 @@ fn test_font_gc() {
         <p id="two">Hello</p>
         <p id="three">Hello</p>
     "#).into_ui_state();
-    let ui_description_frame_1 = UiDescription::match_css_to_dom(&mut ui_state_frame_1, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
+    let ui_description_frame_1 = UiDescription::match_css_to_dom(&mut ui_state_frame_1, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down, &MediaQueryContext::default());
     let display_list_frame_1 = DisplayList::new_from_ui_description(&ui_description_frame_1, &ui_state_frame_1);
 
 
     let mut ui_state_frame_2: UiState<Mock> = Dom::mock_from_xml(r#"
         <p>Hello</p>
     "#).into_ui_state();
-    let ui_description_frame_2 = UiDescription::match_css_to_dom(&mut ui_state_frame_2, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
+    let ui_description_frame_2 = UiDescription::match_css_to_dom(&mut ui_state_frame_2, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down, &MediaQueryContext::default());
     let display_list_frame_2 = DisplayList::new_from_ui_description(&ui_description_frame_2, &ui_state_frame_2);
 
 
@@ -1217,7 +1362,7 @@ fn test_font_gc() {
         <p id="two">Hello</p>
         <p id="three">Hello</p>
     "#).into_ui_state();
-    let ui_description_frame_3 = UiDescription::match_css_to_dom(&mut ui_state_frame_3, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down);
+    let ui_description_frame_3 = UiDescription::match_css_to_dom(&mut ui_state_frame_3, &css, &mut focused_node, &mut pending_focus_target, &hovered_nodes, is_mouse_down, &MediaQueryContext::default());
     let display_list_frame_3 = DisplayList::new_from_ui_description(&ui_description_frame_3, &ui_state_frame_3);
 
 
@@ -1243,23 +1388,23 @@ fn test_font_gc() {
     assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_2), FastHashSet::default());
     assert_eq!(scan_ui_description_for_image_keys(&app_resources, &display_list_frame_3), FastHashSet::default());
 
-    assert_eq!(scan_ui_description_for_font_keys(&app_resources, &display_list_frame_1), build_map(vec![
-        (ImmediateFontId::Unresolved("Arial".to_string()), build_set(vec![px_to_au(10.0)])),
-        (ImmediateFontId::Unresolved("Helvetica".to_string()), build_set(vec![px_to_au(10.0)])),
-        (ImmediateFontId::Unresolved("Times New Roman".to_string()), build_set(vec![px_to_au(10.0)])),
+    assert_eq!(scan_ui_description_for_font_keys(&app_resources, &display_list_frame_1, viewport_size), build_map(vec![
+        (ImmediateFontId::Unresolved("Arial".to_string(), FontFaceProperties::default()), build_set(vec![px_to_au(10.0)])),
+        (ImmediateFontId::Unresolved("Helvetica".to_string(), FontFaceProperties::default()), build_set(vec![px_to_au(10.0)])),
+        (ImmediateFontId::Unresolved("Times New Roman".to_string(), FontFaceProperties::default()), build_set(vec![px_to_au(10.0)])),
     ]));
-    assert_eq!(scan_ui_description_for_font_keys(&app_resources, &display_list_frame_2), build_map(vec![
-        (ImmediateFontId::Unresolved("sans-serif".to_string()), build_set(vec![px_to_au(10.0)])),
+    assert_eq!(scan_ui_description_for_font_keys(&app_resources, &display_list_frame_2, viewport_size), build_map(vec![
+        (ImmediateFontId::Unresolved("sans-serif".to_string(), FontFaceProperties::default()), build_set(vec![px_to_au(10.0)])),
     ]));
-    assert_eq!(scan_ui_description_for_font_keys(&app_resources, &display_list_frame_3), build_map(vec![
-        (ImmediateFontId::Unresolved("Arial".to_string()), build_set(vec![px_to_au(10.0)])),
-        (ImmediateFontId::Unresolved("Helvetica".to_string()), build_set(vec![px_to_au(10.0)])),
-        (ImmediateFontId::Unresolved("Times New Roman".to_string()), build_set(vec![px_to_au(10.0)])),
+    assert_eq!(scan_ui_description_for_font_keys(&app_resources, &display_list_frame_3, viewport_size), build_map(vec![
+        (ImmediateFontId::Unresolved("Arial".to_string(), FontFaceProperties::default()), build_set(vec![px_to_au(10.0)])),
+        (ImmediateFontId::Unresolved("Helvetica".to_string(), FontFaceProperties::default()), build_set(vec![px_to_au(10.0)])),
+        (ImmediateFontId::Unresolved("Times New Roman".to_string(), FontFaceProperties::default()), build_set(vec![px_to_au(10.0)])),
     ]));
 
 
 
-    app_resources.add_fonts_and_images(&display_list_frame_1);
+    app_resources.add_fonts_and_images(&display_list_frame_1, viewport_size);
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
     assert_eq!(app_resources.last_frame_font_keys.len(), 3);
 
@@ -1268,25 +1413,28 @@ fn test_font_gc() {
     assert_eq!(app_resources.currently_registered_fonts.len(), 3); // fails
 
     // Assert that fonts don't get double-inserted, still the same font sources as previously
-    app_resources.add_fonts_and_images(&display_list_frame_3);
+    app_resources.add_fonts_and_images(&display_list_frame_3, viewport_size);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
 
     // Assert that no new fonts get added on subsequent frames
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
-    app_resources.add_fonts_and_images(&display_list_frame_3);
+    let resource_generation = app_resources.get_resource_generation();
+    app_resources.add_fonts_and_images(&display_list_frame_3, viewport_size);
+    app_resources.add_fonts_and_images(&display_list_frame_3, viewport_size);
+    app_resources.add_fonts_and_images(&display_list_frame_3, viewport_size);
+    app_resources.add_fonts_and_images(&display_list_frame_3, viewport_size);
+    app_resources.add_fonts_and_images(&display_list_frame_3, viewport_size);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
+    assert_eq!(app_resources.get_resource_generation(), resource_generation);
 
     // If the DOM changes, the fonts should get deleted, the only font still present is "sans-serif"
-    app_resources.add_fonts_and_images(&display_list_frame_2);
+    app_resources.add_fonts_and_images(&display_list_frame_2, viewport_size);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 1);
+    assert!(app_resources.get_resource_generation() > resource_generation);
 
-    app_resources.add_fonts_and_images(&display_list_frame_1);
+    app_resources.add_fonts_and_images(&display_list_frame_1, viewport_size);
     app_resources.garbage_collect_fonts_and_images();
     assert_eq!(app_resources.currently_registered_fonts.len(), 3);
 }
\ No newline at end of file
@@ -8,10 +8,19 @@
 //! | `background-color`                                 |              |             |            |                  |
 //! | `background-size`                                  |              |             |            |                  |
 //! | `background-repeat`                                |              |             |            |                  |
+//! | `background-image`                                 |              |             |            |                  |
+//! | `background-position`                              |              |             |            |                  |
+//! | `background-origin`                                |              |             |            |                  |
+//! | `background-clip`                                  |              |             |            |                  |
 //! | `color`                                            |              |             |            |                  |
 //! | `font-size`                                        |              |             |            |                  |
 //! | `font-family`                                      |              |             |            |                  |
+//! | `font-weight`                                      |              |             |            |                  |
+//! | `font-style`                                       |              |             |            |                  |
+//! | `font-stretch`                                     |              |             |            |                  |
 //! | `text-align`                                       |              |             |            |                  |
+//! | `text-decoration`, `-line`, `-color`, `-style`, ...|              |             |            |                  |
+//! | `text-shadow`                                      |              |             |            |                  |
 //! | `letter-spacing`                                   |              |             |            |                  |
 //! | `line-height`                                      |              |             |            |                  |
 //! | `word-spacing`                                     |              |             |            |                  |
@@ -19,20 +28,154 @@
 //! | `cursor`                                           |              |             |            |                  |
 //! | `width`, `min-width`, `max-width`                  |              |             |            |                  |
 //! | `height`, `min-height`, `max-height`               |              |             |            |                  |
+//! | `display`                                          |              |             |            |                  |
+//! | `visibility`                                       |              |             |            |                  |
 //! | `position`                                         |              |             |            |                  |
+//! | `float`                                            |              |             |            |                  |
+//! | `z-index`                                          |              |             |            |                  |
 //! | `top`, `right`, `left`, `bottom`                   |              |             |            |                  |
 //! | `flex-wrap`                                        |              |             |            |                  |
 //! | `flex-direction`                                   |              |             |            |                  |
-//! | `flex-grow`                                        |              |             |            |                  |
-//! | `flex-shrink`                                      |              |             |            |                  |
+//! | `flex`, `flex-grow`, `flex-shrink`, `flex-basis`   |              |             |            |                  |
+//! | `order`                                            |              |             |            |                  |
 //! | `justify-content`                                  |              |             |            |                  |
-//! | `align-items`                                      |              |             |            |                  |
+//! | `align-items`, `align-self`                        |              |             |            |                  |
 //! | `align-content`                                    |              |             |            |                  |
+//! | `gap`, `row-gap`, `column-gap`                     |              |             |            |                  |
+//! | `grid-template-columns`, `grid-template-rows`      |              |             |            |                  |
+//! | `grid-template-areas`                              |              |             |            |                  |
+//! | `grid-gap`                                         |              |             |            |                  |
+//! | `grid-row`, `grid-column`, `grid-area`             |              |             |            |                  |
 //! | `overflow`, `overflow-x`, `overflow-y`             |              |             |            |                  |
 //! | `padding`, `-top`, `-left`, `-right`, `-bottom`    |              |             |            |                  |
 //! | `margin`,  `-top`, `-left`, `-right`, `-bottom`    |              |             |            |                  |
 //! | `border`,  `-top`, `-left`, `-right`, `-bottom`    |              |             |            |                  |
+//! | `border-style`, `-top-style`, `-left-style`, ...   |              |             |            |                  |
+//! | `border-image`                                     |              |             |            |                  |
 //! | `box-shadow`, `-top`, `-left`, `-right`, `-bottom` |              |             |            |                  |
+//! | `transform`                                        |              |             |            |                  |
+//! | `transform-origin`                                 |              |             |            |                  |
+//! | `opacity`                                          |              |             |            |                  |
+//! | `transition`                                       |              |             |            |                  |
+//! | `animation`                                        |              |             |            |                  |
+//!
+//! Custom properties (`--main-color: red;`) can be declared on any rule and are inherited
+//! by all children. They can be referenced in the value of any property via
+//! `var(--main-color)` or `var(--main-color, black)` (with a fallback value).
+//!
+//! Lengths can be given in `px`, `pt`, `em`, `%`, `rem`, `vw`, `vh`, `vmin` and `vmax`.
+//! Percentages refer to the width (`width`, `left`, `right`, `margin`, `padding`)
+//! or the height (`height`, `top`, `bottom`) of the containing block, `rem` refers to the
+//! `font-size` of the root node and the viewport units refer to the logical size of the window.
+//! Lengths can also be computed with `calc()`, i.e. `width: calc(100% - 240px)`.
+//!
+//! Backgrounds can have multiple comma-separated layers, the first layer is drawn on top,
+//! i.e. `background: image("logo"), linear-gradient(red, blue);`. The values of
+//! `background-size`, `-repeat`, `-position`, `-origin` and `-clip` are assigned to the
+//! layers in the same order and are repeated if there are more layers than values.
+//!
+//! `border-image: image("frame") 30 fill / 10px round` draws a nine-patch border: the image is
+//! sliced 30 pixels from each edge, the corners are drawn into the corners of the border and
+//! the edges are stretched or repeated along the sides. The border widths are taken from the
+//! `border` unless a width is given after the first `/`.
+//!
+//! `text-decoration: underline wavy red` draws lines under, over (`overline`) or through
+//! (`line-through`) each line of a text. The position and the default thickness of the lines
+//! are taken from the metrics of the font. `text-shadow: 1px 1px 2px black` draws blurred
+//! copies of the text and its decorations behind the text.
+//!
+//! Changes of numeric properties and colors can be animated with `transition`
+//! (i.e. `transition: opacity 300ms ease-in`) and `animation`, which plays the
+//! keyframes of a `@keyframes` rule with the same name.
+//!
+//! `@media` rules are evaluated against the state of each window and re-evaluated when
+//! the window is resized. Supported media features are `min-width`, `max-width`,
+//! `min-height`, `max-height`, `orientation`, `resolution` (the HiDPI factor, in `dppx`,
+//! `dpi` or `dpcm`) and `prefers-color-scheme` (set via `WindowState::color_scheme`).
+//!
+//! Fonts declared with `@font-face { font-family: Roboto; src: url("Roboto.ttf"); }` are
+//! registered automatically and can be used in `font-family`. Stylesheets can be split
+//! across files with `@import "other.css";` - imports are resolved relative to the importing
+//! file when the CSS is loaded with `css::from_file` or hot-reloaded.
+//!
+//! `font-family` is a fallback chain: `font-family: "Fira Sans", Arial, sans-serif;` uses the
+//! first font that exists and draws the characters it doesn't contain with the next fonts.
+//! `font-weight`, `font-style` and `font-stretch` select the closest matching face of the
+//! family - either a system font or an `@font-face` rule with the same descriptors, i.e.
+//! `@font-face { font-family: Roboto; font-weight: bold; src: url("Roboto-Bold.ttf"); }`.
+//!
+//! Nodes with `overflow: auto` or `overflow: scroll` get scrollbars when their content
+//! overflows. The scrollbars can be styled with the `::-webkit-scrollbar`, `-track`,
+//! `-track-piece`, `-thumb` and `-corner` pseudo-elements, optionally restricted to one
+//! direction with `:horizontal` / `:vertical`, i.e.
+//! `.list::-webkit-scrollbar-thumb:vertical { background: gray; border-radius: 4px; }`.
+//! `width` / `height` on `::-webkit-scrollbar` set the thickness of the vertical / horizontal
+//! scrollbar. The thumb can be dragged, clicking on the track scrolls by one page.
+//!
+//! Besides type, class and ID selectors, rules can select nodes by their attributes
+//! (`[disabled]`, `[lang|=en]`, `[href^="http"]`, etc. - see `Dom::with_attribute`), by their
+//! preceding siblings (`.a + .b`, `.a ~ .b`) and exclude nodes with `:not(.selected)`.
+//! The structural pseudo-classes `:first-child`, `:last-child`, `:only-child`, `:nth-child()`,
+//! `:nth-last-child()`, the `-of-type` variants, `:empty` and `:root` are supported,
+//! `:nth-child()` takes `odd`, `even` or an `An+B` pattern such as `2n+1` or `-n+3`.
+//!
+//! When several rules set the same property, the declaration is chosen by the cascade: the
+//! styles of the application override the native style (see `override_native`), then the
+//! more specific selector wins and on equal specificity the rule that comes last. Declarations
+//! marked with `!important` override all normal declarations, overridden dynamic properties
+//! (`[[ my_id | 200px ]]`, see `Dom::with_css_override`) override only normal declarations.
+//! For `!important` declarations the order of the native and the application style is inverted.
+//!
+//! Every property accepts the keywords `inherit` (use the value of the parent node), `initial`
+//! (use the default value, i.e. to reset a property of the native style) and `unset` (`inherit`
+//! for inherited properties such as `color` or `font-size`, `initial` for all others).
+//!
+//! Every node is a flex container (`display: flex`) unless it is a grid container
+//! (`display: grid`). Nodes with `display: none` are removed
+//! from the layout together with their children, they don't take up any space. Nodes with
+//! `visibility: hidden` keep their space, but are neither drawn nor hit-tested - since
+//! `visibility` is inherited, children can be made visible again with `visibility: visible`.
+//!
+//! The items of a flex container are laid out in their `order` (DOM order for equal values) and
+//! start growing from their `flex-basis` - `flex: 1` is the same as `flex: 1 1 0px`, so all items
+//! with `flex: 1` end up with the same size. `align-self` overrides the `align-items` of the
+//! container for a single item. `gap: 10px` (or `row-gap` / `column-gap`) sets the space between
+//! two adjacent items and between two lines of a multi-line container, without needing a margin
+//! on every item except the last.
+//!
+//! `position: relative` moves a node by its `top` / `left` (or `bottom` / `right`) offsets
+//! after the layout, without affecting its siblings. `position: absolute` nodes are taken out
+//! of the flow and positioned relative to the nearest positioned (non-`static`) ancestor,
+//! `position: fixed` nodes relative to the window - they don't scroll with their ancestors.
+//! If both `left` and `right` (or `top` and `bottom`) are set, the node is stretched between
+//! them unless it has a `width` (or `height`). `position: sticky` nodes are laid out like
+//! `relative` nodes, but stick to the `top` / `right` / `bottom` / `left` edge of the nearest
+//! scrolling ancestor while it is scrolled, i.e. for frozen table headers - they never leave
+//! their parent though. `justify-content` distributes the leftover space along the main axis.
+//!
+//! Besides `px`, `em`, `pt` and `%`, the `width` and `height` properties accept the keywords
+//! `min-content` (the size of the longest word, i.e. the narrowest the node can get without
+//! overflowing), `max-content` (the size of the text without any line breaks) and `fit-content`
+//! (`max-content`, but never larger than the available space and never smaller than
+//! `min-content`). For containers, the sizes of the children are added up along the main axis
+//! and the largest child is used along the cross axis.
+//!
+//! The children of a grid container are laid out in the cells of a grid. The tracks are defined
+//! with `grid-template-columns` / `grid-template-rows` as `px` / `%` lengths, `auto` (as large as
+//! the content), `fr` (share of the leftover space), `minmax(min, max)` and `repeat(count, tracks)`,
+//! i.e. `grid-template-columns: 200px repeat(2, minmax(100px, 1fr));`. `grid-gap: 10px 20px` sets
+//! the space between the rows and the columns. Items are placed with `grid-row` / `grid-column`
+//! (`1 / 3`, `2 / span 2`, `-1`) or into a named area of the `grid-template-areas`
+//! (`grid-area: header`), all other items fill the next free cell row by row.
+//!
+//! A node with `display: inline` children is a paragraph: its texts, `display: inline` nodes
+//! and other children are laid out in lines like the words of a text, so texts with different
+//! fonts and sizes flow into each other and share a common baseline. `display: inline` nodes
+//! with children (spans) only group their children (i.e. for a background), all other children
+//! (such as images) take up one box in a line. Children with `float: left` / `float: right` are
+//! placed at the left / right edge of the top of the paragraph and the lines flow around them.
+//! The texts of a paragraph always flow in its lines, the `padding` and `margin` of spans are
+//! ignored and the paragraph is the containing block of its `position: absolute` children.
 
 #[cfg(debug_assertions)]
 use std::time::Duration;
@@ -54,7 +197,7 @@ pub mod native_style {
 }
 
 #[cfg(feature = "css_parser")]
-use azul_css_parser::{self, CssParseError};
+use azul_css_parser::{self, CssParseError, CssFileLoadError};
 
 /// Returns a style with the native appearance for the operating system. Convenience wrapper
 /// for functionality from the the `azul-native-style` crate.
@@ -69,6 +212,13 @@ pub fn from_str(input: &str) -> Result<Css, CssParseError> {
     azul_css_parser::new_from_str(input)
 }
 
+/// Loads and parses a CSS file, including the files imported via `@import`.
+/// Convenience wrapper for `azul-css-parser::new_from_file`.
+#[cfg(feature = "css_parser")]
+pub fn from_file<P: AsRef<::std::path::Path>>(file_path: P) -> Result<Css, CssFileLoadError> {
+    azul_css_parser::new_from_file(file_path)
+}
+
 /// Appends a custom stylesheet to `css::native()`.
 #[cfg(all(feature = "css_parser", feature = "native_style"))]
 pub fn override_native(input: &str) -> Result<Css, CssParseError> {
@@ -154,6 +304,21 @@ pub(crate) mod webrender_translate {
         )
     }
 
+    use webrender::api::LineStyle as WrLineStyle;
+    use azul_css::TextDecorationStyle as CssTextDecorationStyle;
+
+    /// Double lines are drawn as two solid lines
+    #[inline(always)]
+    pub fn wr_translate_text_decoration_style(input: CssTextDecorationStyle) -> WrLineStyle {
+        match input {
+            CssTextDecorationStyle::Solid => WrLineStyle::Solid,
+            CssTextDecorationStyle::Double => WrLineStyle::Solid,
+            CssTextDecorationStyle::Dotted => WrLineStyle::Dotted,
+            CssTextDecorationStyle::Dashed => WrLineStyle::Dashed,
+            CssTextDecorationStyle::Wavy => WrLineStyle::Wavy,
+        }
+    }
+
     use webrender::api::ColorU as WrColorU;
     use azul_css::ColorU as CssColorU;
 
@@ -231,22 +396,82 @@ pub(crate) mod webrender_translate {
         }
     }
 
+    use webrender::api::LayoutSize as WrLayoutSize;
+
+    // NOTE: Reverse direction: Translate from webrender::LayoutSize to css::LayoutSize
+    #[inline(always)]
+    pub fn wr_translate_layout_size(input: WrLayoutSize) -> CssLayoutSize {
+        CssLayoutSize { width: input.width, height: input.height }
+    }
+
+    use webrender::api::LayoutTransform as WrLayoutTransform;
+
+    /// Translates a 2D matrix in the form of `[a, b, c, d, tx, ty]` (see `StyleTransform::to_matrix`)
+    #[inline(always)]
+    pub fn wr_translate_transform_matrix(input: [f32;6]) -> WrLayoutTransform {
+        WrLayoutTransform::row_major_2d(input[0], input[1], input[2], input[3], input[4], input[5])
+    }
+
+    use azul_css::BorderImageRepeat as CssBorderImageRepeat;
+    use webrender::api::RepeatMode as WrRepeatMode;
+
+    #[inline(always)]
+    pub fn wr_translate_border_image_repeat(input: CssBorderImageRepeat) -> WrRepeatMode {
+        match input {
+            CssBorderImageRepeat::Stretch => WrRepeatMode::Stretch,
+            CssBorderImageRepeat::Repeat => WrRepeatMode::Repeat,
+            CssBorderImageRepeat::Round => WrRepeatMode::Round,
+            CssBorderImageRepeat::Space => WrRepeatMode::Space,
+        }
+    }
+
+    use azul_css::NinePatchBorder as CssNinePatchBorder;
+    use webrender::api::{
+        NinePatchBorder as WrNinePatchBorder, NinePatchBorderSource as WrNinePatchBorderSource,
+        ImageKey as WrImageKey,
+    };
+    use euclid::SideOffsets2D;
+
+    #[inline(always)]
+    pub fn wr_translate_nine_patch_border(input: CssNinePatchBorder, image_key: WrImageKey) -> WrNinePatchBorder {
+        WrNinePatchBorder {
+            source: WrNinePatchBorderSource::Image(image_key),
+            width: input.width,
+            height: input.height,
+            slice: SideOffsets2D::new(
+                input.slice.top.get() as i32,
+                input.slice.right.get() as i32,
+                input.slice.bottom.get() as i32,
+                input.slice.left.get() as i32,
+            ),
+            fill: input.fill,
+            repeat_horizontal: wr_translate_border_image_repeat(input.repeat_horizontal),
+            repeat_vertical: wr_translate_border_image_repeat(input.repeat_vertical),
+            outset: SideOffsets2D::new(
+                input.outset.top.get(),
+                input.outset.right.get(),
+                input.outset.bottom.get(),
+                input.outset.left.get(),
+            ),
+        }
+    }
+
     use azul_css::BorderDetails as CssBorderDetails;
     use webrender::api::BorderDetails as WrBorderDetails;
 
-    // NOTE: Reverse direction: Translate from webrender::LayoutRect to css::LayoutRect
+    /// Translates the border details - a nine-patch border needs the key of its (loaded) image,
+    /// if the `image_key` is `None`, an invisible border is drawn instead.
     #[inline(always)]
-    pub fn wr_translate_border_details(input: CssBorderDetails) -> WrBorderDetails {
+    pub fn wr_translate_border_details(input: CssBorderDetails, image_key: Option<WrImageKey>) -> WrBorderDetails {
         let zero_border_side = WrBorderSide {
             color: WrColorU { r: 0, g: 0, b: 0, a: 0 }.into(),
             style: WrBorderStyle::None
         };
 
-        match input {
-            CssBorderDetails::Normal(normal) => WrBorderDetails::Normal(wr_translate_normal_border(normal)),
-            // TODO: Do 9patch border properly - currently this can't be reached since there
-            // is no parsing for 9patch border yet!
-            CssBorderDetails::NinePatch(_) => WrBorderDetails::Normal(WrNormalBorder {
+        match (input, image_key) {
+            (CssBorderDetails::Normal(normal), _) => WrBorderDetails::Normal(wr_translate_normal_border(normal)),
+            (CssBorderDetails::NinePatch(nine_patch), Some(image_key)) => WrBorderDetails::NinePatch(wr_translate_nine_patch_border(nine_patch, image_key)),
+            (CssBorderDetails::NinePatch(_), None) => WrBorderDetails::Normal(WrNormalBorder {
                 left: zero_border_side,
                 right: zero_border_side,
                 bottom: zero_border_side,
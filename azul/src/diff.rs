@@ -1,11 +1,12 @@
-#![allow(unused_variables)]
 #![allow(dead_code)]
 
-use std::{collections::BTreeMap, marker::PhantomData};
+use std::marker::PhantomData;
 use {
     id_tree::{NodeId, NodeHierarchy},
-    dom::{Dom, NodeData},
+    dom::{Dom, NodeData, NodeType, DomString},
+    app_resources::AppResources,
 };
+use azul_css::CssProperty;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) struct DomRange<F: FrameMarker> {
@@ -30,6 +31,12 @@ pub(crate) struct DomNode<F: FrameMarker> {
     pub(crate) marker: PhantomData<F>,
 }
 
+impl<F: FrameMarker> DomNode<F> {
+    pub(crate) fn new(id: NodeId) -> Self {
+        Self { id, marker: PhantomData }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) enum DomChange {
     Added(DomRange<NewState>),
@@ -38,23 +45,47 @@ pub(crate) enum DomChange {
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DomDiff {
+    /// Did the structure (the node hierarchy) of the DOM change? If so, the node
+    /// IDs of the old DOM don't refer to the same nodes in the new DOM anymore
+    pub(crate) structure_changed: bool,
     /// What the actual changes nodes (not trees / subtrees) were in this diff, in order of appearance
     pub(crate) changed_nodes: Vec<DomChange>,
     /// Which items simply need updating in terms of image source?
     pub(crate) only_replace_images: Vec<NodeId>,
     /// Which nodes / subtrees need re-styling?
     pub(crate) need_restyling: Vec<DomRange<NewState>>,
-    /// Which nodes need a re-layout?
-    /// For example A would be:
+    /// Which nodes need a re-layout? Nodes that only changed their
+    /// paint properties (colors, shadows, etc.) don't show up here.
     pub(crate) need_relayout: Vec<DomRange<NewState>>,
 }
 
-type TreeDepth = usize;
-type ParentNodeId = NodeId;
-type LeafNodeId = NodeId;
-
 impl<F: FrameMarker + PartialEq> DomRange<F> {
 
+    pub(crate) fn new(start: NodeId, end: NodeId) -> Self {
+        Self { start: DomNode::new(start), end: DomNode::new(end) }
+    }
+
+    /// Range that only contains the node itself
+    pub(crate) fn single_node(node_id: NodeId) -> Self {
+        Self::new(node_id, node_id)
+    }
+
+    /// Range of the node and all of its children
+    pub(crate) fn subtree(node_id: NodeId, node_hierarchy: &NodeHierarchy) -> Self {
+        Self::new(node_id, get_last_descendant(node_id, node_hierarchy))
+    }
+
+    /// Range of the node, its children and all following siblings (and their children),
+    /// i.e. all nodes that a changed selector (class, id, etc.) of `node_id` can affect,
+    /// since a selector can match descendants (`.a .b`) and following siblings (`.a ~ .b`)
+    pub(crate) fn subtree_and_following_siblings(node_id: NodeId, node_hierarchy: &NodeHierarchy) -> Self {
+        let end = match node_hierarchy[node_id].parent {
+            Some(parent_id) => get_last_descendant(parent_id, node_hierarchy),
+            None => get_last_descendant(node_id, node_hierarchy),
+        };
+        Self::new(node_id, end)
+    }
+
     /// Is `other` a subtree of `self`? - Assumes that the DOM was
     /// constructed in a linear order, i.e. the child being within
     /// the parents start / end bounds
@@ -70,78 +101,331 @@ impl<F: FrameMarker + PartialEq> DomRange<F> {
     }
 }
 
-// In order to test two DOM nodes for "equality", you'd need to
-// test if the node type, the classes and the ids are the same.
-// The rest of the attributes can be ignored, since they are not
-// used by the CSS engine.
-//
-// Right now the CSS doesn't support adjacent modifiers ("+" selectors),
-// which will make this algorithm a bit more complex (but should be
-// solvable with adjacency lists).
-//
-// for each leaf node (sorted by depth):
-//     - if the node has only changed its position:
-//         - node doesn't need restyle (but may need re-layout when "+" selectors are implemented)
-//     - else insert it it
-//     - add the end added / removed nodes
+impl DomDiff {
+
+    /// Diff that restyles and relayouts the entire new DOM, for example if the
+    /// structure of the DOM changed, so that the old styles can't be re-used
+    pub(crate) fn full(old: &NodeHierarchy, new: &NodeHierarchy) -> Self {
+        let mut diff = Self { structure_changed: true, .. Self::default() };
+        if old.len() > 0 {
+            diff.changed_nodes.push(DomChange::Removed(DomRange::subtree(NodeId::new(0), old)));
+        }
+        if new.len() > 0 {
+            diff.changed_nodes.push(DomChange::Added(DomRange::subtree(NodeId::new(0), new)));
+        }
+        diff.invalidate_all(new);
+        diff
+    }
+
+    /// Marks all nodes of the (new) DOM as needing a restyle and a relayout
+    pub(crate) fn invalidate_all(&mut self, node_hierarchy: &NodeHierarchy) {
+        self.need_restyling.clear();
+        self.need_relayout.clear();
+        if node_hierarchy.len() > 0 {
+            let full_range = DomRange::subtree(NodeId::new(0), node_hierarchy);
+            self.need_restyling.push(full_range);
+            self.need_relayout.push(full_range);
+        }
+    }
+
+    /// Returns whether the layout of the last frame can't be re-used for the new DOM
+    pub(crate) fn needs_relayout(&self) -> bool {
+        !self.need_relayout.is_empty()
+    }
+
+    /// Swapping an image for an image with a different size changes the layout,
+    /// so these nodes of the `only_replace_images` need a relayout
+    pub(crate) fn relayout_resized_images<T>(&mut self, old: &Dom<T>, new: &Dom<T>, app_resources: &AppResources) {
+        let get_image_size = |dom: &Dom<T>, node_id: NodeId| match &dom.arena.node_data[node_id].node_type {
+            NodeType::Image(image_id) => app_resources.get_image_info(image_id).map(|info| info.get_dimensions()),
+            _ => None,
+        };
+
+        let resized_images = self.only_replace_images.iter()
+            .filter(|node_id| get_image_size(old, **node_id) != get_image_size(new, **node_id))
+            .map(|node_id| DomRange::single_node(*node_id))
+            .collect::<Vec<_>>();
+
+        if !resized_images.is_empty() {
+            self.need_relayout.extend(resized_images);
+            merge_ranges(&mut self.need_relayout);
+        }
+    }
+
+    /// Returns for each node of the new DOM whether it is contained in any of the `need_restyling` ranges
+    pub(crate) fn get_restyle_mask(&self, node_count: usize) -> Vec<bool> {
+        let mut mask = vec![false; node_count];
+        for range in &self.need_restyling {
+            for is_restyled in mask.iter_mut().take(range.end.id.index() + 1).skip(range.start.id.index()) {
+                *is_restyled = true;
+            }
+        }
+        mask
+    }
+}
+
+// In order to test two DOM nodes for "equality", you'd need to test if the
+// node type, the classes, the ids and the attributes are the same (since these
+// are used by the CSS engine). Callbacks, tab indices, etc. can be ignored,
+// since they neither affect the style nor the layout.
 //
-// for each parent node (sorted by depth, bubble up):
-//     - if the parent has changed:
+// If the structure of the two DOMs (the node hierarchy) is different, the node
+// IDs of the old DOM don't correspond to the node IDs of the new DOM anymore,
+// so the entire DOM is restyled and relayouted. This is usually not the case for
+// the most common updates (i.e. changing the text of a label on every keystroke).
 //
-//     - ask if that child has affected that parents layout
-//          - if yes, set the parent to be restyled
-//     - ask if that child affects its siblings layout
-//          - if yes, add the siblings to the changeset
+// Otherwise, for each node:
+//     - if the selector-relevant data (type, classes, ids, attributes) changed:
+//         - restyle the node, its children and its following siblings (since the
+//           node can be matched by sibling selectors, such as `.a + .b`)
+//     - if the dynamic CSS overrides changed:
+//         - restyle the node and its children (the overridden properties are inherited)
+//         - relayout if any overridden property can trigger a relayout
+//     - if only the content changed (i.e. the text of a label):
+//         - relayout, but don't restyle
+//     - if only the image source changed:
+//         - insert the node into `only_replace_images`
 //
+// Whether the restyled nodes need a relayout is determined later on, after the
+// restyle, by comparing the old and the new style of the node.
 
-const NODE_CHANGED_NOTHING: u8  = 0x01;
-const NODE_CHANGED_TYPE: u8     = 0x02;
-const NODE_CHANGED_CLASSES: u8  = 0x04;
-const NODE_CHANGED_IDS: u8      = 0x08;
+const NODE_CHANGED_NOTHING: u8          = 0x00;
+const NODE_CHANGED_TYPE: u8             = 0x01;
+const NODE_CHANGED_CLASSES: u8          = 0x02;
+const NODE_CHANGED_IDS: u8              = 0x04;
+const NODE_CHANGED_ATTRIBUTES: u8       = 0x08;
+const NODE_CHANGED_CONTENT: u8          = 0x10;
+const NODE_CHANGED_IMAGE: u8            = 0x20;
+const NODE_CHANGED_CSS_OVERRIDES: u8    = 0x40;
 
-fn node_needs_restyle<T>(old: &NodeData<T>, new: &NodeData<T>) -> u8 {
+const NODE_NEEDS_SELECTOR_RESTYLE: u8 = NODE_CHANGED_TYPE | NODE_CHANGED_CLASSES | NODE_CHANGED_IDS | NODE_CHANGED_ATTRIBUTES;
+
+fn get_node_changes<T>(old: &NodeData<T>, new: &NodeData<T>) -> u8 {
     let mut result = NODE_CHANGED_NOTHING;
 
     if old.node_type != new.node_type {
-        result &= NODE_CHANGED_TYPE;
+        // An empty label is matched by `:empty`, so it has to be restyled if it gains text
+        if old.node_type.get_path() != new.node_type.get_path() || is_empty_label(old) != is_empty_label(new) {
+            result |= NODE_CHANGED_TYPE;
+        } else {
+            match (&old.node_type, &new.node_type) {
+                (NodeType::Image(_), NodeType::Image(_)) => { result |= NODE_CHANGED_IMAGE; },
+                _ => { result |= NODE_CHANGED_CONTENT; },
+            }
+        }
     }
 
     if old.classes != new.classes {
-        result &= NODE_CHANGED_CLASSES;
+        result |= NODE_CHANGED_CLASSES;
     }
 
     if old.ids != new.ids {
-        result &= NODE_CHANGED_CLASSES;
+        result |= NODE_CHANGED_IDS;
+    }
+
+    if old.attributes != new.attributes {
+        result |= NODE_CHANGED_ATTRIBUTES;
+    }
+
+    if old.dynamic_css_overrides != new.dynamic_css_overrides {
+        result |= NODE_CHANGED_CSS_OVERRIDES;
     }
 
     result
 }
 
-fn get_leaf_nodes_by_depth<T: FrameMarker>(hierarchy: &NodeHierarchy)
--> BTreeMap<TreeDepth, BTreeMap<ParentNodeId, Vec<DomNode<T>>>>
-{
-    let mut map = BTreeMap::new();
-    let parent_nodes = hierarchy.get_parents_sorted_by_depth();
-
-    for (parent_depth, parent_id) in parent_nodes {
-        for child_id in parent_id.children(hierarchy).filter(|child| hierarchy[*child].first_child.is_some()) {
-            map.entry(parent_depth + 1).or_insert_with(|| BTreeMap::new())
-               .entry(parent_id).or_insert_with(|| Vec::new())
-               .push(DomNode { id: child_id, marker: PhantomData });
+fn is_empty_label<T>(node: &NodeData<T>) -> bool {
+    match &node.node_type {
+        NodeType::Label(text) => text.as_str().is_empty(),
+        _ => false,
+    }
+}
+
+/// Returns whether any of the added, removed or changed overrides can trigger a relayout
+fn css_overrides_affect_layout(old: &[(DomString, CssProperty)], new: &[(DomString, CssProperty)]) -> bool {
+    old.iter().filter(|o| !new.contains(o))
+        .chain(new.iter().filter(|n| !old.contains(n)))
+        .any(|(_, property)| property.get_type().can_trigger_relayout())
+}
+
+/// Returns the last node of the sub-tree of `node_id` (in DOM order)
+fn get_last_descendant(node_id: NodeId, node_hierarchy: &NodeHierarchy) -> NodeId {
+    let mut current = node_id;
+    while let Some(last_child) = node_hierarchy[current].last_child {
+        current = last_child;
+    }
+    current
+}
+
+/// Sorts the ranges and merges overlapping and adjacent ranges
+fn merge_ranges(ranges: &mut Vec<DomRange<NewState>>) {
+    ranges.sort();
+    let mut merged: Vec<DomRange<NewState>> = Vec::with_capacity(ranges.len());
+    for range in ranges.drain(..) {
+        match merged.last_mut() {
+            Some(last) if range.start.id.index() <= last.end.id.index() + 1 => {
+                if range.end.id.index() > last.end.id.index() {
+                    last.end = range.end;
+                }
+            },
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+/// Compares the old and the new DOM and returns which nodes need to be restyled or relayouted
+pub(crate) fn diff_dom_tree<T>(old: &Dom<T>, new: &Dom<T>) -> DomDiff {
+
+    let old_hierarchy = &old.arena.node_layout;
+    let new_hierarchy = &new.arena.node_layout;
+
+    if old_hierarchy != new_hierarchy {
+        return DomDiff::full(old_hierarchy, new_hierarchy);
+    }
+
+    let mut diff = DomDiff::default();
+
+    for node_id in new_hierarchy.linear_iter() {
+
+        let old_node = &old.arena.node_data[node_id];
+        let new_node = &new.arena.node_data[node_id];
+
+        let changes = get_node_changes(old_node, new_node);
+        if changes == NODE_CHANGED_NOTHING {
+            continue;
+        }
+
+        diff.changed_nodes.push(DomChange::Removed(DomRange::single_node(node_id)));
+        diff.changed_nodes.push(DomChange::Added(DomRange::single_node(node_id)));
+
+        if changes & NODE_NEEDS_SELECTOR_RESTYLE != 0 {
+            diff.need_restyling.push(DomRange::subtree_and_following_siblings(node_id, new_hierarchy));
+        } else if changes & NODE_CHANGED_CSS_OVERRIDES != 0 {
+            diff.need_restyling.push(DomRange::subtree(node_id, new_hierarchy));
+        }
+
+        if changes & (NODE_CHANGED_TYPE | NODE_CHANGED_CONTENT) != 0 ||
+           (changes & NODE_CHANGED_CSS_OVERRIDES != 0 &&
+            css_overrides_affect_layout(&old_node.dynamic_css_overrides, &new_node.dynamic_css_overrides)) {
+            diff.need_relayout.push(DomRange::single_node(node_id));
+        }
+
+        if changes & NODE_CHANGED_IMAGE != 0 {
+            diff.only_replace_images.push(node_id);
         }
     }
 
-    map
+    merge_ranges(&mut diff.need_restyling);
+    merge_ranges(&mut diff.need_relayout);
+
+    diff
 }
 
-pub(crate) fn diff_dom_tree<T>(old: &Dom<T>, new:Dom<T>) -> DomDiff {
+#[cfg(test)]
+mod diff_tests {
+
+    use super::*;
+    use azul_css::{CssProperty, StyleTextColor, LayoutWidth, ColorU};
+    use prelude::*;
+
+    struct Mock;
 
-    // TODO!
+    //  0: [div]
+    //   |-- 1: [div .a]
+    //   |    |-- 2: [p "hello"]
+    //   |-- 3: [div .b]
+    //   |-- 4: [div .c]
+    fn mock_dom(text: &str, class: &str) -> Dom<Mock> {
+        Dom::div()
+            .with_child(Dom::div().with_class(class.to_string()).with_child(Dom::label(text.to_string())))
+            .with_child(Dom::div().with_class("b"))
+            .with_child(Dom::div().with_class("c"))
+    }
 
-    // let old_leaf_nodes = get_leaf_nodes_by_depth(&old.arena.node_layout, OldState { });
-    // let new_leaf_nodes = get_leaf_nodes_by_depth(&new.arena.node_layout, NewState { });
+    fn range(start: usize, end: usize) -> DomRange<NewState> {
+        DomRange::new(NodeId::new(start), NodeId::new(end))
+    }
 
-    // depth -> parents (in order) -> [leaf children]
+    #[test]
+    fn test_diff_dom_tree_unchanged() {
+        let diff = diff_dom_tree(&mock_dom("hello", "a"), &mock_dom("hello", "a"));
+        assert_eq!(diff, DomDiff::default());
+        assert!(!diff.needs_relayout());
+    }
+
+    #[test]
+    fn test_diff_dom_tree_changed_text_only_needs_relayout() {
+        let diff = diff_dom_tree(&mock_dom("hello", "a"), &mock_dom("hello world", "a"));
+        assert_eq!(diff.need_restyling, Vec::new());
+        assert_eq!(diff.need_relayout, vec![range(2, 2)]);
+        assert_eq!(diff.changed_nodes.len(), 2);
+
+        // A label that gets a text is not `:empty` anymore
+        let diff = diff_dom_tree(&mock_dom("", "a"), &mock_dom("hello", "a"));
+        assert_eq!(diff.need_restyling, vec![range(2, 2)]);
+    }
+
+    #[test]
+    fn test_diff_dom_tree_changed_class_restyles_following_siblings() {
+        let diff = diff_dom_tree(&mock_dom("hello", "a"), &mock_dom("hello", "x"));
+        assert_eq!(diff.need_restyling, vec![range(1, 4)]);
+        // Whether the new class changed the layout is only known after the restyle
+        assert_eq!(diff.need_relayout, Vec::new());
+    }
 
-    DomDiff::default()
-}
\ No newline at end of file
+    #[test]
+    fn test_diff_dom_tree_changed_css_overrides() {
+        const RED: ColorU = ColorU { r: 255, g: 0, b: 0, a: 255 };
+        const BLUE: ColorU = ColorU { r: 0, g: 0, b: 255, a: 255 };
+        let with_override = |property: CssProperty| Dom::<Mock>::div()
+            .with_child(Dom::div().with_css_override("my_property", property).with_child(Dom::div()))
+            .with_child(Dom::div());
+
+        let color = diff_dom_tree(
+            &with_override(CssProperty::TextColor(StyleTextColor(BLUE))),
+            &with_override(CssProperty::TextColor(StyleTextColor(RED))),
+        );
+        assert_eq!(color.need_restyling, vec![range(1, 2)]);
+        assert!(!color.needs_relayout());
+
+        let width = diff_dom_tree(
+            &with_override(CssProperty::Width(LayoutWidth::px(100.0))),
+            &with_override(CssProperty::Width(LayoutWidth::px(200.0))),
+        );
+        assert_eq!(width.need_restyling, vec![range(1, 2)]);
+        assert_eq!(width.need_relayout, vec![range(1, 1)]);
+    }
+
+    #[test]
+    fn test_diff_dom_tree_changed_structure() {
+        let old = mock_dom("hello", "a");
+        let new = mock_dom("hello", "a").with_child(Dom::div());
+        let diff = diff_dom_tree(&old, &new);
+        assert!(diff.structure_changed);
+        assert_eq!(diff.changed_nodes, vec![
+            DomChange::Removed(DomRange::new(NodeId::new(0), NodeId::new(4))),
+            DomChange::Added(DomRange::new(NodeId::new(0), NodeId::new(5))),
+        ]);
+        assert_eq!(diff.need_restyling, vec![range(0, 5)]);
+        assert_eq!(diff.need_relayout, vec![range(0, 5)]);
+    }
+
+    #[test]
+    fn test_diff_dom_tree_replaced_image() {
+        let image = |image_id| Dom::<Mock>::div().with_child(Dom::image(image_id));
+        let diff = diff_dom_tree(&image(ImageId::new()), &image(ImageId::new()));
+        assert_eq!(diff.only_replace_images, vec![NodeId::new(1)]);
+        assert_eq!(diff.need_restyling, Vec::new());
+        assert_eq!(diff.need_relayout, Vec::new());
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        let mut ranges = vec![range(5, 6), range(1, 2), range(2, 3), range(8, 8), range(4, 4)];
+        merge_ranges(&mut ranges);
+        assert_eq!(ranges, vec![range(1, 6), range(8, 8)]);
+        assert_eq!(DomDiff { need_restyling: ranges, .. DomDiff::default() }.get_restyle_mask(10),
+            vec![false, true, true, true, true, true, true, false, true, false]);
+    }
+}
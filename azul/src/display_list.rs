@@ -4,8 +4,10 @@ use std::{
     fmt,
     sync::{Arc, Mutex},
     collections::BTreeMap,
+    time::Instant,
 };
 use euclid::{TypedRect, TypedSize2D};
+use glium::glutin::dpi::LogicalSize;
 use webrender::api::{
     LayoutPixel, DisplayListBuilder, PrimitiveInfo, GradientStop,
     ColorF, PipelineId, Epoch, ImageData, ImageDescriptor,
@@ -14,13 +16,19 @@ use webrender::api::{
     ComplexClipRegion, LayoutPrimitiveInfo, ExternalImageId,
     ExternalImageData, ImageFormat, ExternalImageType, TextureTarget,
     ImageRendering, AlphaType, FontInstanceFlags, FontRenderMode,
+    PropertyBinding, TransformStyle, MixBlendMode, FilterOp, GlyphRasterSpace,
+    ClipId, ScrollSensitivity,
 };
 use azul_css::{
-    Css, LayoutPosition,CssProperty, LayoutOverflow,
-    StyleBorderRadius, LayoutMargin, LayoutPadding, BoxShadowClipMode,
+    Css, LayoutPosition, LayoutZIndex, CssProperty, CssPropertyType, LayoutOverflow,
+    StyleBorderRadius, LayoutMargin, LayoutPadding, LayoutGap, BoxShadowClipMode,
     StyleTextColor, StyleBackground, StyleBoxShadow,
-    StyleBackgroundSize, StyleBackgroundRepeat, StyleBorder, BoxShadowPreDisplayItem,
+    StyleBackgroundSize, StyleBackgroundRepeat, StyleBorder, StyleBorderStyle, BoxShadowPreDisplayItem,
+    StyleBackgroundPosition, StyleBackgroundOrigin, StyleBackgroundClip, PixelResolveContext, PixelValue,
+    StyleTextDecoration, TextDecorationStyle, TextDecorationThickness,
     RectStyle, RectLayout, ColorU as StyleColorU, DynamicCssPropertyDefault,
+    LayoutPoint as StyleLayoutPoint, CssDeclaration, LayoutWidth,
+    ScrollbarInfo, ScrollbarOrientation, CssPathPseudoElement,
 };
 use {
     FastHashMap,
@@ -30,13 +38,15 @@ use {
     ui_description::{UiDescription, StyledNode},
     id_tree::{NodeDataContainer, NodeId, NodeHierarchy},
     dom::{
-        NodeData, ScrollTagId, DomHash, DomString, new_scroll_tag_id,
+        NodeData, ScrollTagId, DomHash, DomString, TagId, new_scroll_tag_id, new_tag_id,
         NodeType::{self, Div, Text, Image, GlTexture, IFrame, Label},
     },
     ui_solver::{do_the_layout, LayoutResult, PositionedRectangle},
+    text_layout::FontMetrics,
     app_resources::ImageId,
     compositor::new_opengl_texture_id,
     window::{Window, FakeWindow, ScrollStates},
+    animation::AnimationStates,
     callbacks::LayoutInfo,
     window_state::WindowSize,
 };
@@ -102,16 +112,69 @@ impl<'a, T: 'a> DisplayList<'a, T> {
         }
     }
 
+    /// Starts the CSS transitions and animations of all nodes and overrides
+    /// the styled properties of animated nodes with their current values
+    ///
+    /// The animated values are inherited by the descendants that inherit the
+    /// styled value of the property (see `StyledNode::inherited_properties`).
+    ///
+    /// Returns whether any of the animated properties can trigger a relayout
+    pub(crate) fn apply_animations(&mut self, animation_states: &mut AnimationStates, css: &Css, now: Instant) -> bool {
+
+        let mut affects_layout = false;
+
+        let css_overrides = &self.ui_descr.dynamic_css_overrides;
+        let node_hierarchy = &self.ui_descr.ui_descr_arena.node_layout;
+
+        // Animated values of the nodes - the nodes are in depth-first order,
+        // so the parent of a node is always animated before the node itself
+        let mut animated_nodes = BTreeMap::<NodeId, BTreeMap<CssPropertyType, CssProperty>>::new();
+
+        for (node_id, rect) in self.rectangles.iter_mut().enumerate() {
+            let node_id = NodeId::new(node_id);
+            let properties = rect.styled_node.css_constraints.iter().filter_map(|(property_type, declaration)| {
+                get_css_property_value(declaration, node_id, css_overrides).map(|property| (*property_type, property.clone()))
+            }).collect();
+
+            let mut animated_properties = node_hierarchy[node_id].parent
+                .and_then(|parent_id| animated_nodes.get(&parent_id))
+                .map(|parent_properties| parent_properties.iter()
+                    .filter(|(property_type, _)| rect.styled_node.inherited_properties.contains(property_type))
+                    .map(|(property_type, property)| (*property_type, property.clone()))
+                    .collect())
+                .unwrap_or_else(BTreeMap::new);
+
+            for animated_property in animation_states.update_node(node_id, properties, css, now) {
+                animated_properties.insert(animated_property.get_type(), animated_property);
+            }
+
+            for animated_property in animated_properties.values() {
+                affects_layout |= animated_property.get_type().can_trigger_relayout();
+                apply_style_property(rect, animated_property);
+            }
+
+            if !animated_properties.is_empty() {
+                animated_nodes.insert(node_id, animated_properties);
+            }
+        }
+
+        animation_states.remove_unused_animation_states();
+        affects_layout
+    }
+
     /// Inserts and solves the top-level DOM (i.e. the DOM with the ID 0)
+    ///
+    /// If a `cached_layout` is given, the DOM is not layouted again, the layout has to be
+    /// done for the same DOM and styles. The `cached_layout` is only re-used if its key
+    /// matches the current window size, HiDPI factor and loaded fonts and images.
     pub(crate) fn into_display_list_builder(
         &self,
         app_data_access: &mut Arc<Mutex<T>>,
         window: &mut Window<T>,
         fake_window: &mut FakeWindow<T>,
-        app_resources: &mut AppResources
-    ) -> (DisplayListBuilder, ScrolledNodes, LayoutResult) {
-
-        use glium::glutin::dpi::LogicalSize;
+        app_resources: &mut AppResources,
+        cached_layout: Option<CachedLayout>,
+    ) -> (DisplayListBuilder, ScrolledNodes, CachedLayout) {
 
         let mut resource_updates = Vec::<ResourceUpdate>::new();
 
@@ -133,24 +196,32 @@ impl<'a, T: 'a> DisplayList<'a, T> {
         //      - Insert the new font keys and image keys into the render API
         //      - Scan all IFrameCallbacks, generate the DomID for each callback
         //      - Repeat while number_of_iframe_callbacks != 0
-        app_resources.add_fonts_and_images(&self);
-
         let window_size = window.state.size.get_reverse_logical_size();
-        let layout_result = do_the_layout(
-            node_hierarchy,
-            node_data,
-            &self.rectangles,
-            &*app_resources,
-            LayoutSize::new(window_size.width as f32, window_size.height as f32),
-            LayoutPoint::new(0.0, 0.0),
-        );
+        let window_size = LayoutSize::new(window_size.width as f32, window_size.height as f32);
+
+        app_resources.add_fonts_and_images(&self, window_size);
+
+        let layout_key = CachedLayoutKey::new(&window.state.size, app_resources.get_resource_generation());
+
+        let layout_result = match cached_layout {
+            Some(cached) if cached.key == layout_key => cached.layout_result,
+            _ => do_the_layout(
+                node_hierarchy,
+                node_data,
+                &self.rectangles,
+                &*app_resources,
+                window_size,
+                LayoutPoint::new(0.0, 0.0),
+                window_size,
+            ),
+        };
 
         // TODO: After the layout has been done, call all IFrameCallbacks and get and insert
         // their font keys / image keys
 
         let mut scrollable_nodes = get_nodes_that_need_scroll_clip(
             node_hierarchy, &self.rectangles, node_data, &layout_result.rects,
-            &layout_result.node_depths, window.internal.pipeline_id
+            &layout_result.node_depths, &layout_result.resolve_context, window.internal.pipeline_id
         );
 
         // Make sure unused scroll states are garbage collected.
@@ -159,7 +230,7 @@ impl<'a, T: 'a> DisplayList<'a, T> {
         let LogicalSize { width, height } = window.state.size.dimensions;
         let mut builder = DisplayListBuilder::with_capacity(window.internal.pipeline_id, TypedSize2D::new(width as f32, height as f32), self.rectangles.len());
 
-        let rects_in_rendering_order = determine_rendering_order(node_hierarchy, &self.rectangles, &layout_result.rects);
+        let rects_in_rendering_order = determine_rendering_order(node_hierarchy, &self.rectangles);
 
         push_rectangles_into_displaylist(
             window.internal.epoch,
@@ -185,12 +256,12 @@ impl<'a, T: 'a> DisplayList<'a, T> {
             },
         );
 
-        (builder, scrollable_nodes, layout_result)
+        (builder, scrollable_nodes, CachedLayout { key: layout_key, layout_result })
     }
 }
 
 /// In order to render rectangles in the correct order, we have to group them together:
-/// As long as there are no position:absolute items, items are inserted in a parents-then-child order
+/// As long as there are no positioned items, items are inserted in a parents-then-child order
 ///
 /// ```no_run,ignore
 /// a
@@ -221,14 +292,18 @@ impl<'a, T: 'a> DisplayList<'a, T> {
 ///
 /// will be rendered as: `a,b,e,f,g,c,d`, so that the `c,d` sub-DOM is on top of the rest
 /// of the content. To support this, the content needs to be grouped: Whenever there is a
-/// `position:absolute` encountered, the children are grouped into a new `ContentGroup`:
+/// `position:absolute` node or a node that creates a new stacking context (see
+/// `node_creates_stacking_context`) encountered, the children are grouped into a new `ContentGroup`:
 ///
 /// ```no_run,ignore
-/// Group 1: [a, b, c, e, f, g]
+/// Group 1: [a, b, e, f, g]
 /// Group 2: [c, d]
 /// ```
-/// Then the groups are simply rendered in-order: if there are multiple position:absolute
-/// groups, this has the side effect of later groups drawing on top of earlier groups.
+///
+/// The groups are then sorted by their `z-index` within the stacking context of their parent:
+/// Groups with a negative `z-index` are drawn below the parent group, groups with a `z-index`
+/// of `auto` or `0` and above are drawn on top of it. Groups with the same `z-index` are drawn
+/// in DOM order, so that later groups are drawn on top of earlier groups.
 #[derive(Debug, Clone, PartialEq)]
 struct ContentGroup {
     /// The parent of the current node group, i.e. either the root node (0)
@@ -259,32 +334,122 @@ struct ContentGroupOrder {
 fn determine_rendering_order<'a>(
     node_hierarchy: &NodeHierarchy,
     rectangles: &NodeDataContainer<DisplayRectangle<'a>>,
-    layouted_rects: &NodeDataContainer<PositionedRectangle>,
 ) -> ContentGroupOrder
 {
-    let mut content_groups = Vec::new();
-
-    determine_rendering_order_inner(
+    let (root_layer, _) = build_stacking_layer(
         node_hierarchy,
         rectangles,
-        layouted_rects,
         0, // depth of this node
         NodeId::new(0),
-        &mut content_groups
+        true, // the root node always creates a stacking context
     );
 
+    let mut content_groups = Vec::new();
+    flatten_stacking_layer(root_layer, &mut content_groups);
+
     ContentGroupOrder { groups: content_groups }
 }
 
-fn determine_rendering_order_inner<'a>(
+/// A `ContentGroup`, together with the groups that are painted in its stacking context
+#[derive(Debug, Clone, PartialEq)]
+struct StackingLayer {
+    /// `z-index` of the group root, `auto` is treated as `0`
+    z_index: isize,
+    group: ContentGroup,
+    /// Layers that are sorted relative to this layer. Empty if the group root doesn't
+    /// create a stacking context, the layers are sorted in the parents stacking context then.
+    children: Vec<StackingLayer>,
+}
+
+/// Returns whether the node creates a new stacking context: Positioned nodes with a
+/// `z-index` other than `auto`, `position: fixed` and `position: sticky` nodes and
+/// nodes with a `transform` or an `opacity` below 1.0
+fn node_creates_stacking_context<'a>(rect: &DisplayRectangle<'a>) -> bool {
+    let position = rect.layout.position.unwrap_or_default();
+    let is_positioned = position != LayoutPosition::Static;
+    let is_fixed_or_sticky = position == LayoutPosition::Fixed || position == LayoutPosition::Sticky;
+    let has_z_index = rect.layout.z_index.unwrap_or_default() != LayoutZIndex::Auto;
+    let has_transform = rect.style.transform.as_ref().map(|t| !t.0.is_empty()).unwrap_or(false);
+    let has_opacity = rect.style.opacity.map(|o| o.get() < 1.0).unwrap_or(false);
+    (is_positioned && has_z_index) || is_fixed_or_sticky || has_transform || has_opacity
+}
+
+/// Returns the `z-index` of the node, `z-index` only has an effect on positioned nodes
+fn get_z_index<'a>(rect: &DisplayRectangle<'a>) -> isize {
+    if rect.layout.position.unwrap_or_default() == LayoutPosition::Static {
+        return 0;
+    }
+
+    match rect.layout.z_index.unwrap_or_default() {
+        LayoutZIndex::Auto => 0,
+        LayoutZIndex::Integer(z) => z,
+    }
+}
+
+/// Builds the `StackingLayer` for the sub-DOM of `root_id`. If the root doesn't create its
+/// own stacking context, the layers of its positioned children are returned separately,
+/// so that they can be sorted in the stacking context of the parent.
+fn build_stacking_layer<'a>(
+    node_hierarchy: &NodeHierarchy,
+    rectangles: &NodeDataContainer<DisplayRectangle<'a>>,
+    root_depth: usize,
+    root_id: NodeId,
+    creates_stacking_context: bool,
+) -> (StackingLayer, Vec<StackingLayer>)
+{
+    let (group, separate_node_ids) = collect_content_group(node_hierarchy, rectangles, root_depth, root_id);
+
+    let mut child_layers = Vec::new();
+    for (depth, node_id) in separate_node_ids {
+        let (layer, parent_layers) = build_stacking_layer(
+            node_hierarchy,
+            rectangles,
+            depth,
+            node_id,
+            node_creates_stacking_context(&rectangles[node_id]),
+        );
+        child_layers.push(layer);
+        child_layers.extend(parent_layers);
+    }
+
+    let z_index = get_z_index(&rectangles[root_id]);
+
+    if creates_stacking_context {
+        (StackingLayer { z_index, group, children: child_layers }, Vec::new())
+    } else {
+        (StackingLayer { z_index, group, children: Vec::new() }, child_layers)
+    }
+}
+
+/// Pushes the groups of the layer in painting order (back to front)
+fn flatten_stacking_layer(layer: StackingLayer, content_groups: &mut Vec<ContentGroup>) {
+
+    let StackingLayer { group, mut children, .. } = layer;
+
+    // sort_by_key is stable, so layers with the same z-index are still drawn in DOM order
+    children.sort_by_key(|child| child.z_index);
+    let (below, above): (Vec<StackingLayer>, Vec<StackingLayer>) = children.into_iter().partition(|child| child.z_index < 0);
+
+    for child in below {
+        flatten_stacking_layer(child, content_groups);
+    }
+
+    content_groups.push(group);
+
+    for child in above {
+        flatten_stacking_layer(child, content_groups);
+    }
+}
+
+/// Collects all nodes of the sub-DOM of `root_id` that can be drawn in one group.
+/// Returns the group and the `(depth, NodeId)` of all nodes that need to be drawn in a
+/// separate group (in DOM order), their sub-DOMs are skipped.
+fn collect_content_group<'a>(
     node_hierarchy: &NodeHierarchy,
     rectangles: &NodeDataContainer<DisplayRectangle<'a>>,
-    layouted_rects: &NodeDataContainer<PositionedRectangle>,
-    // recursive parameters
     root_depth: usize,
     root_id: NodeId,
-    content_groups: &mut Vec<ContentGroup>,
-)
+) -> (ContentGroup, Vec<(usize, NodeId)>)
 {
     use id_tree::NodeEdge;
 
@@ -298,7 +463,7 @@ fn determine_rendering_order_inner<'a>(
         node_ids: Vec::new(),
     };
 
-    let mut absolute_node_ids = Vec::new();
+    let mut separate_node_ids = Vec::new();
     let mut depth = root_depth + 1;
 
     // Same as the traverse function, but allows us to skip items, returns the next element
@@ -333,11 +498,15 @@ fn determine_rendering_order_inner<'a>(
                 NodeEdge::Start(node_id) => {
                     let rect_node = &rectangles[node_id];
                     let position = rect_node.layout.position.unwrap_or_default();
-                    if position == LayoutPosition::Absolute {
+                    if rect_node.layout.is_display_none() {
+                        // `display: none` - neither the node nor its sub-tree is drawn
+                        current_node_edge = NodeEdge::End(node_id);
+                        should_continue_loop = false;
+                    } else if position.is_out_of_flow() || node_creates_stacking_context(rect_node) {
                         // For now, ignore the node and put it aside for later
-                        absolute_node_ids.push((depth, node_id));
+                        separate_node_ids.push((depth, node_id));
                         // Skip this sub-tree and go straight to the next sibling
-                        // Since the tree is positioned absolute, we'll worry about it later
+                        // Since the tree is drawn in a separate group, we'll worry about it later
                         current_node_edge = NodeEdge::End(node_id);
                         should_continue_loop = false;
                     } else {
@@ -349,9 +518,10 @@ fn determine_rendering_order_inner<'a>(
                             clip_children: node_is_overflow_hidden,
                             scrolls_children: node_needs_to_scroll_children,
                         });
+                        // Skipped sub-trees never reach their `NodeEdge::End`,
+                        // so only descend into nodes that are drawn in this group
+                        depth += 1;
                     }
-
-                    depth += 1;
                 },
                 NodeEdge::End(node_id) => {
                     depth -= 1;
@@ -364,12 +534,40 @@ fn determine_rendering_order_inner<'a>(
         }
     }
 
-    content_groups.push(root_group);
+    (root_group, separate_node_ids)
+}
+
+
+/// The layout of the last frame, see `WindowInternal::cached_layout`
+#[derive(Debug, Clone)]
+pub(crate) struct CachedLayout {
+    /// Window size and resources that the layout was done for
+    pub(crate) key: CachedLayoutKey,
+    pub(crate) layout_result: LayoutResult,
+}
+
+/// Everything besides the DOM and the styles that a layout depends on -
+/// a `CachedLayout` can only be re-used if its key matches the current key
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct CachedLayoutKey {
+    /// Logical size of the window
+    pub(crate) window_size: LogicalSize,
+    /// HiDPI factors of the window, these change if the window is moved to another monitor
+    pub(crate) hidpi_factor: f64,
+    pub(crate) winit_hidpi_factor: f64,
+    /// See `AppResources::get_resource_generation()`, fonts and images that
+    /// finished loading change the size of the texts and images
+    pub(crate) resource_generation: usize,
+}
 
-    // Note: Currently reversed order, so that earlier absolute
-    // items are drawn on top of later absolute items
-    for (absolute_depth, absolute_node_id) in absolute_node_ids.into_iter().rev() {
-        determine_rendering_order_inner(node_hierarchy, rectangles, layouted_rects, absolute_depth, absolute_node_id, content_groups);
+impl CachedLayoutKey {
+    pub(crate) fn new(window_size: &WindowSize, resource_generation: usize) -> Self {
+        Self {
+            window_size: window_size.get_reverse_logical_size(),
+            hidpi_factor: window_size.hidpi_factor,
+            winit_hidpi_factor: window_size.winit_hidpi_factor,
+            resource_generation,
+        }
     }
 }
 
@@ -379,6 +577,25 @@ pub(crate)  struct ScrolledNodes {
     pub(crate) tags_to_node_ids: BTreeMap<ScrollTagId, NodeId>,
 }
 
+impl ScrolledNodes {
+
+    /// Returns the scrollbar whose thumb or track has the given hit-testing tag, together
+    /// with the overflowing node and the part of the scrollbar (thumb or track) that was hit
+    pub(crate) fn get_scrollbar_by_tag(&self, tag: TagId) -> Option<(NodeId, &OverflowingScrollNode, &Scrollbar, CssPathPseudoElement)> {
+        self.overflowing_nodes.iter().filter_map(|(node_id, scroll_node)| {
+            scroll_node.horizontal_scrollbar.iter().chain(scroll_node.vertical_scrollbar.iter()).filter_map(|scrollbar| {
+                if scrollbar.thumb_tag == tag {
+                    Some((*node_id, scroll_node, scrollbar, CssPathPseudoElement::ScrollbarThumb))
+                } else if scrollbar.track_tag == tag {
+                    Some((*node_id, scroll_node, scrollbar, CssPathPseudoElement::ScrollbarTrack))
+                } else {
+                    None
+                }
+            }).next()
+        }).next()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct OverflowingScrollNode {
     pub(crate) parent_rect: PositionedRectangle,
@@ -386,6 +603,201 @@ pub(crate) struct OverflowingScrollNode {
     pub(crate) parent_external_scroll_id: ExternalScrollId,
     pub(crate) parent_dom_hash: DomHash,
     pub(crate) scroll_tag_id: ScrollTagId,
+    /// Area that the children are visible in: the padding box of the node minus the scrollbars
+    pub(crate) viewport: LayoutRect,
+    /// Horizontal scrollbar, `None` if the node can't be scrolled horizontally
+    pub(crate) horizontal_scrollbar: Option<Scrollbar>,
+    /// Vertical scrollbar, `None` if the node can't be scrolled vertically
+    pub(crate) vertical_scrollbar: Option<Scrollbar>,
+}
+
+impl OverflowingScrollNode {
+
+    /// Returns how far the content can be scrolled in the horizontal and vertical direction
+    pub(crate) fn get_max_scroll(&self) -> LayoutSize {
+        LayoutSize::new(
+            self.horizontal_scrollbar.as_ref().map(|s| s.max_scroll).unwrap_or(0.0),
+            self.vertical_scrollbar.as_ref().map(|s| s.max_scroll).unwrap_or(0.0),
+        )
+    }
+
+    pub(crate) fn get_scrollbar(&self, orientation: ScrollbarOrientation) -> Option<&Scrollbar> {
+        match orientation {
+            ScrollbarOrientation::Horizontal => self.horizontal_scrollbar.as_ref(),
+            ScrollbarOrientation::Vertical => self.vertical_scrollbar.as_ref(),
+        }
+    }
+
+    /// Returns the corner between the two scrollbars, if the node has both scrollbars
+    fn get_scrollbar_corner(&self) -> Option<LayoutRect> {
+        let horizontal = self.horizontal_scrollbar.as_ref()?;
+        let vertical = self.vertical_scrollbar.as_ref()?;
+        Some(LayoutRect::new(
+            LayoutPoint::new(vertical.bounds.origin.x, horizontal.bounds.origin.y),
+            LayoutSize::new(vertical.bounds.size.width, horizontal.bounds.size.height),
+        ))
+    }
+}
+
+/// Geometry of a scrollbar of an `OverflowingScrollNode`, the position of the thumb
+/// depends on the current scroll offset of the node (see `ScrollStates`)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Scrollbar {
+    pub(crate) orientation: ScrollbarOrientation,
+    /// Style of the track, thumb and corner (`::-webkit-scrollbar-*`)
+    pub(crate) style: ScrollbarInfo,
+    /// Bounds of the whole scrollbar, the track background is drawn in this area
+    pub(crate) bounds: LayoutRect,
+    /// Area that the thumb moves in: the scrollbar bounds minus the scrollbar padding
+    pub(crate) track: LayoutRect,
+    /// Length of the thumb along the track
+    pub(crate) thumb_length: f32,
+    /// Length of the viewport along the scroll direction, a click on the track scrolls by one page
+    pub(crate) page_length: f32,
+    /// How many pixels the content overflows the viewport
+    pub(crate) max_scroll: f32,
+    /// Hit-testing tag of the track
+    pub(crate) track_tag: TagId,
+    /// Hit-testing tag of the thumb
+    pub(crate) thumb_tag: TagId,
+}
+
+impl Scrollbar {
+
+    /// Smallest length of the thumb, so that it can still be grabbed with the mouse
+    const MIN_THUMB_LENGTH: f32 = 20.0;
+
+    /// Creates the scrollbar for a node. The scrollbar is placed at the bottom (horizontal) or at the
+    /// right (vertical) edge of the `padding_box`, the `viewport` is the padding box minus the scrollbars.
+    fn new(
+        orientation: ScrollbarOrientation,
+        style: ScrollbarInfo,
+        padding_box: LayoutRect,
+        viewport: LayoutRect,
+        content_length: f32,
+        resolve_context: &PixelResolveContext,
+    ) -> Self {
+
+        let bounds = match orientation {
+            ScrollbarOrientation::Horizontal => LayoutRect::new(
+                LayoutPoint::new(padding_box.origin.x, viewport.max_y()),
+                LayoutSize::new(viewport.size.width, padding_box.max_y() - viewport.max_y()),
+            ),
+            ScrollbarOrientation::Vertical => LayoutRect::new(
+                LayoutPoint::new(viewport.max_x(), padding_box.origin.y),
+                LayoutSize::new(padding_box.max_x() - viewport.max_x(), viewport.size.height),
+            ),
+        };
+
+        // Percentages of the scrollbar padding refer to the width of the scrollbar
+        let resolve = |padding: Option<PixelValue>| padding.map(|p| p.resolve(bounds.size.width, resolve_context));
+        let track = subtract_padding(&bounds, &LayoutPadding {
+            top: resolve(style.padding.top),
+            bottom: resolve(style.padding.bottom),
+            left: resolve(style.padding.left),
+            right: resolve(style.padding.right),
+        });
+
+        let (page_length, track_length) = match orientation {
+            ScrollbarOrientation::Horizontal => (viewport.size.width, track.size.width),
+            ScrollbarOrientation::Vertical => (viewport.size.height, track.size.height),
+        };
+
+        let max_scroll = (content_length - page_length).max(0.0);
+        let thumb_length = if content_length > 0.0 {
+            (track_length * (page_length / content_length).min(1.0)).max(Self::MIN_THUMB_LENGTH).min(track_length)
+        } else {
+            track_length
+        };
+
+        Self {
+            orientation,
+            style,
+            bounds,
+            track,
+            thumb_length: thumb_length.max(0.0),
+            page_length,
+            max_scroll,
+            track_tag: new_tag_id(),
+            thumb_tag: new_tag_id(),
+        }
+    }
+
+    /// Returns the start and the length of the track along the scroll direction
+    fn get_track_extent(&self) -> (f32, f32) {
+        match self.orientation {
+            ScrollbarOrientation::Horizontal => (self.track.origin.x, self.track.size.width),
+            ScrollbarOrientation::Vertical => (self.track.origin.y, self.track.size.height),
+        }
+    }
+
+    /// Returns the coordinate of the point along the scroll direction
+    pub(crate) fn get_axis_position(&self, x: f32, y: f32) -> f32 {
+        match self.orientation {
+            ScrollbarOrientation::Horizontal => x,
+            ScrollbarOrientation::Vertical => y,
+        }
+    }
+
+    /// Returns the distance between the start of the track and the start of the thumb
+    pub(crate) fn get_thumb_offset(&self, scroll_offset: f32) -> f32 {
+        let (_, track_length) = self.get_track_extent();
+        if self.max_scroll <= 0.0 {
+            return 0.0;
+        }
+        (track_length - self.thumb_length).max(0.0) * (scroll_offset / self.max_scroll).max(0.0).min(1.0)
+    }
+
+    /// Returns the start and the end of the thumb along the scroll direction
+    pub(crate) fn get_thumb_extent(&self, scroll_offset: f32) -> (f32, f32) {
+        let (track_start, _) = self.get_track_extent();
+        let thumb_start = track_start + self.get_thumb_offset(scroll_offset);
+        (thumb_start, thumb_start + self.thumb_length)
+    }
+
+    pub(crate) fn get_thumb_rect(&self, scroll_offset: f32) -> LayoutRect {
+        let (thumb_start, _) = self.get_thumb_extent(scroll_offset);
+        match self.orientation {
+            ScrollbarOrientation::Horizontal => LayoutRect::new(
+                LayoutPoint::new(thumb_start, self.track.origin.y),
+                LayoutSize::new(self.thumb_length, self.track.size.height),
+            ),
+            ScrollbarOrientation::Vertical => LayoutRect::new(
+                LayoutPoint::new(self.track.origin.x, thumb_start),
+                LayoutSize::new(self.track.size.width, self.thumb_length),
+            ),
+        }
+    }
+
+    /// Converts a distance that the thumb was dragged by into a distance that the content has to be scrolled by
+    pub(crate) fn thumb_distance_to_scroll_distance(&self, thumb_distance: f32) -> f32 {
+        let (_, track_length) = self.get_track_extent();
+        let free_track_length = track_length - self.thumb_length;
+        if free_track_length <= 0.0 {
+            0.0
+        } else {
+            thumb_distance * self.max_scroll / free_track_length
+        }
+    }
+
+    /// Returns the scroll offset while the thumb is dragged: `start_cursor_pos` and `start_scroll_offset`
+    /// are the cursor position and the scroll offset (along the scroll direction) when the thumb was grabbed
+    pub(crate) fn get_drag_scroll_offset(&self, start_cursor_pos: f32, start_scroll_offset: f32, cursor_pos: f32) -> f32 {
+        start_scroll_offset + self.thumb_distance_to_scroll_distance(cursor_pos - start_cursor_pos)
+    }
+
+    /// Returns the scroll offset after a click on the track: the content is scrolled by one page
+    /// towards the cursor. Returns `None` if the cursor is on the thumb.
+    pub(crate) fn get_track_click_scroll_offset(&self, scroll_offset: f32, cursor_on_track: f32) -> Option<f32> {
+        let (thumb_start, thumb_end) = self.get_thumb_extent(scroll_offset);
+        if cursor_on_track < thumb_start {
+            Some(scroll_offset - self.page_length)
+        } else if cursor_on_track > thumb_end {
+            Some(scroll_offset + self.page_length)
+        } else {
+            None
+        }
+    }
 }
 
 /// Returns all node IDs where the children overflow the parent, together with the
@@ -405,6 +817,7 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
     dom_rects: &NodeDataContainer<NodeData<T>>,
     layouted_rects: &NodeDataContainer<PositionedRectangle>,
     parents: &[(usize, NodeId)],
+    resolve_context: &PixelResolveContext,
     pipeline_id: PipelineId,
 ) -> ScrolledNodes {
 
@@ -416,7 +829,7 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
         let mut children_sum_rect = None;
 
         for child in parent.children(&node_hierarchy) {
-            let old = children_sum_rect.unwrap_or(LayoutRect::zero());
+            let old = children_sum_rect.unwrap_or(layouted_rects[child].bounds);
             children_sum_rect = Some(old.union(&layouted_rects[child].bounds));
         }
 
@@ -427,10 +840,51 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
 
         let parent_rect = layouted_rects.get(*parent).unwrap();
 
-        if children_sum_rect.contains_rect(&parent_rect.bounds) {
+        if parent_rect.bounds.contains_rect(&children_sum_rect) {
             continue;
         }
 
+        let rect = &display_list_rects[*parent];
+        let overflow = rect.layout.overflow.unwrap_or_default();
+        let padding_box = subtract_border(&parent_rect.bounds, &rect.style.border, resolve_context);
+
+        // Size of the content, measured from the top left corner of the padding box
+        let content_width = children_sum_rect.max_x() - padding_box.origin.x;
+        let content_height = children_sum_rect.max_y() - padding_box.origin.y;
+
+        let horizontal_style = if overflow.needs_horizontal_scrollbar(content_width > padding_box.size.width) {
+            Some(rect.style.get_horizontal_scrollbar_style())
+        } else {
+            None
+        };
+
+        let vertical_style = if overflow.needs_vertical_scrollbar(content_height > padding_box.size.height) {
+            Some(rect.style.get_vertical_scrollbar_style())
+        } else {
+            None
+        };
+
+        if horizontal_style.is_none() && vertical_style.is_none() {
+            continue;
+        }
+
+        let mut viewport = padding_box;
+        if let Some(horizontal_style) = &horizontal_style {
+            let thickness = horizontal_style.width.get_exact().map(|w| w.to_pixels_relative(padding_box.size.height, resolve_context)).unwrap_or(0.0);
+            viewport.size.height = (viewport.size.height - thickness).max(0.0);
+        }
+        if let Some(vertical_style) = &vertical_style {
+            let thickness = vertical_style.width.get_exact().map(|w| w.to_pixels_relative(padding_box.size.width, resolve_context)).unwrap_or(0.0);
+            viewport.size.width = (viewport.size.width - thickness).max(0.0);
+        }
+
+        let horizontal_scrollbar = horizontal_style.map(|style| {
+            Scrollbar::new(ScrollbarOrientation::Horizontal, style, padding_box, viewport, content_width, resolve_context)
+        });
+        let vertical_scrollbar = vertical_style.map(|style| {
+            Scrollbar::new(ScrollbarOrientation::Vertical, style, padding_box, viewport, content_height, resolve_context)
+        });
+
         let parent_dom_hash = dom_rects[*parent].calculate_node_data_hash();
 
         // Create an external scroll id. This id is required to preserve its
@@ -438,7 +892,7 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
         let parent_external_scroll_id  = ExternalScrollId(parent_dom_hash.0, pipeline_id);
 
         // Create a unique scroll tag for hit-testing
-        let scroll_tag_id = match display_list_rects.get(*parent).and_then(|node| node.tag) {
+        let scroll_tag_id = match rect.tag {
             Some(existing_tag) => ScrollTagId(existing_tag),
             None => new_scroll_tag_id(),
         };
@@ -450,6 +904,9 @@ fn get_nodes_that_need_scroll_clip<'a, T: 'a>(
             parent_external_scroll_id,
             parent_dom_hash,
             scroll_tag_id,
+            viewport,
+            horizontal_scrollbar,
+            vertical_scrollbar,
         });
     }
 
@@ -492,6 +949,142 @@ fn test_overflow_parsing() {
     assert_eq!(node_needs_to_clip_children(&layout3), true);
 }
 
+#[test]
+fn test_determine_rendering_order_z_index() {
+
+    use dom::Dom;
+
+    struct Mock;
+
+    //  0
+    //  |- 1: position: absolute; z-index: 2
+    //  |- 2: position: relative; z-index: -1
+    //  |- 3: position: absolute
+    //  |   |- 4: position: relative; z-index: 1
+    //  |- 5
+    let dom: Dom<Mock> = Dom::div()
+        .with_child(Dom::div())
+        .with_child(Dom::div())
+        .with_child(Dom::div().with_child(Dom::div()))
+        .with_child(Dom::div());
+
+    let styled_node = StyledNode::default();
+    let mut rectangles = dom.arena.node_data.transform(|_, _| DisplayRectangle::new(None, &styled_node));
+
+    rectangles[NodeId::new(1)].layout.position = Some(LayoutPosition::Absolute);
+    rectangles[NodeId::new(1)].layout.z_index = Some(LayoutZIndex::Integer(2));
+    rectangles[NodeId::new(2)].layout.position = Some(LayoutPosition::Relative);
+    rectangles[NodeId::new(2)].layout.z_index = Some(LayoutZIndex::Integer(-1));
+    rectangles[NodeId::new(3)].layout.position = Some(LayoutPosition::Absolute);
+    rectangles[NodeId::new(4)].layout.position = Some(LayoutPosition::Relative);
+    rectangles[NodeId::new(4)].layout.z_index = Some(LayoutZIndex::Integer(1));
+
+    let order = determine_rendering_order(&dom.arena.node_layout, &rectangles);
+    let group_roots = order.groups.iter().map(|group| group.root.node_id.index()).collect::<Vec<usize>>();
+
+    // 3 doesn't create a stacking context, so 4 is sorted in the stacking context of 0
+    assert_eq!(group_roots, vec![2, 0, 3, 4, 1]);
+    assert_eq!(order.groups[1].node_ids.iter().map(|n| n.node_id.index()).collect::<Vec<usize>>(), vec![5]);
+}
+
+/// Vertical scrollbar of a 100x100px node with 400px of content: the thumb is 25px long and
+/// can be moved by 75px along the track, the content can be scrolled by 300px
+#[test]
+fn test_cached_layout_key() {
+    let window_size = WindowSize::default();
+    let key = CachedLayoutKey::new(&window_size, 0);
+    assert_eq!(CachedLayoutKey::new(&window_size, 0), key);
+
+    // Same logical size, but the window was moved to a HiDPI monitor
+    let hidpi_window_size = WindowSize { hidpi_factor: 2.0, winit_hidpi_factor: 2.0, .. window_size };
+    assert_eq!(hidpi_window_size.get_reverse_logical_size(), window_size.get_reverse_logical_size());
+    assert_ne!(CachedLayoutKey::new(&hidpi_window_size, 0), key);
+
+    // Fonts or images finished loading
+    assert_ne!(CachedLayoutKey::new(&window_size, 1), key);
+}
+
+#[cfg(test)]
+fn get_test_scroll_node() -> OverflowingScrollNode {
+    let padding_box = LayoutRect::new(LayoutPoint::new(0.0, 0.0), LayoutSize::new(100.0, 100.0));
+    let viewport = LayoutRect::new(LayoutPoint::new(0.0, 0.0), LayoutSize::new(83.0, 100.0));
+    OverflowingScrollNode {
+        parent_rect: PositionedRectangle { bounds: padding_box, content_width: None, content_height: None },
+        child_rect: LayoutRect::new(LayoutPoint::new(0.0, 0.0), LayoutSize::new(83.0, 400.0)),
+        parent_external_scroll_id: ExternalScrollId(0, PipelineId(0, 0)),
+        parent_dom_hash: DomHash(0),
+        scroll_tag_id: new_scroll_tag_id(),
+        viewport,
+        horizontal_scrollbar: None,
+        vertical_scrollbar: Some(Scrollbar::new(ScrollbarOrientation::Vertical, ScrollbarInfo::default(), padding_box, viewport, 400.0, &PixelResolveContext::default())),
+    }
+}
+
+#[test]
+fn test_scrollbar_hit_test() {
+
+    let scroll_node = get_test_scroll_node();
+    let scrollbar = scroll_node.vertical_scrollbar.clone().unwrap();
+
+    let mut scrolled_nodes = ScrolledNodes::default();
+    scrolled_nodes.overflowing_nodes.insert(NodeId::new(1), scroll_node);
+
+    let hit_test = |tag| scrolled_nodes.get_scrollbar_by_tag(tag)
+        .map(|(node_id, _, scrollbar, scrollbar_part)| (node_id, scrollbar.orientation, scrollbar_part));
+
+    assert_eq!(hit_test(scrollbar.thumb_tag), Some((NodeId::new(1), ScrollbarOrientation::Vertical, CssPathPseudoElement::ScrollbarThumb)));
+    assert_eq!(hit_test(scrollbar.track_tag), Some((NodeId::new(1), ScrollbarOrientation::Vertical, CssPathPseudoElement::ScrollbarTrack)));
+    assert_eq!(hit_test(new_tag_id()), None);
+}
+
+#[test]
+fn test_scrollbar_thumb_drag() {
+
+    let scrollbar = get_test_scroll_node().vertical_scrollbar.unwrap();
+
+    assert_eq!(scrollbar.bounds, LayoutRect::new(LayoutPoint::new(83.0, 0.0), LayoutSize::new(17.0, 100.0)));
+    assert_eq!(scrollbar.thumb_length, 25.0);
+    assert_eq!(scrollbar.max_scroll, 300.0);
+    assert_eq!(scrollbar.get_thumb_extent(0.0), (0.0, 25.0));
+    assert_eq!(scrollbar.get_thumb_extent(150.0), (37.5, 62.5));
+    assert_eq!(scrollbar.get_thumb_extent(300.0), (75.0, 100.0));
+
+    // Dragging the thumb by 15px scrolls the content by 60px, relative to where the thumb was grabbed
+    assert_eq!(scrollbar.get_drag_scroll_offset(10.0, 0.0, 25.0), 60.0);
+    assert_eq!(scrollbar.get_drag_scroll_offset(50.0, 150.0, 35.0), 90.0);
+
+    // Dragging the thumb past the end of the track is clamped by the scroll state
+    let scroll_node = get_test_scroll_node();
+    let scroll_id = scroll_node.parent_external_scroll_id;
+    let mut scroll_states = ScrollStates::new();
+    scroll_states.ensure_initialized_scroll_state(scroll_id, 0.0, scrollbar.max_scroll);
+    scroll_states.scroll_node_to(&scroll_id, 0.0, scrollbar.get_drag_scroll_offset(10.0, 0.0, 200.0));
+    assert_eq!(scroll_states.get_scroll_amount(&scroll_id), Some((0.0, 300.0)));
+}
+
+#[test]
+fn test_scrollbar_track_click() {
+
+    let scrollbar = get_test_scroll_node().vertical_scrollbar.unwrap();
+
+    // Clicking below the thumb scrolls down by one page, clicking above it scrolls up by one page
+    assert_eq!(scrollbar.get_track_click_scroll_offset(0.0, 50.0), Some(100.0));
+    assert_eq!(scrollbar.get_track_click_scroll_offset(300.0, 10.0), Some(200.0));
+
+    // Clicks on the thumb don't scroll
+    assert_eq!(scrollbar.get_track_click_scroll_offset(0.0, 10.0), None);
+    assert_eq!(scrollbar.get_track_click_scroll_offset(150.0, 50.0), None);
+
+    // Scrolling above the start of the content is clamped by the scroll state
+    let scroll_node = get_test_scroll_node();
+    let scroll_id = scroll_node.parent_external_scroll_id;
+    let mut scroll_states = ScrollStates::new();
+    scroll_states.ensure_initialized_scroll_state(scroll_id, 0.0, scrollbar.max_scroll);
+    scroll_states.scroll_node_to(&scroll_id, 0.0, 50.0);
+    scroll_states.scroll_node_to(&scroll_id, 0.0, scrollbar.get_track_click_scroll_offset(50.0, 0.0).unwrap());
+    assert_eq!(scroll_states.get_scroll_amount(&scroll_id), Some((0.0, 0.0)));
+}
+
 fn push_rectangles_into_displaylist<'a, 'b, 'c, 'd, 'e, 'f, T>(
     epoch: Epoch,
     window_size: WindowSize,
@@ -503,25 +1096,71 @@ fn push_rectangles_into_displaylist<'a, 'b, 'c, 'd, 'e, 'f, T>(
 {
     let mut clip_stack = Vec::new();
 
+    // Scroll frames that were already defined, so that the groups of position:absolute
+    // nodes can be pushed into the scroll frames of their ancestors
+    let mut scroll_frames = BTreeMap::new();
+    // Same for the sticky frames of position:sticky nodes
+    let mut sticky_frames = BTreeMap::new();
+
     for content_group in content_grouped_rectangles.groups {
-        let rectangle = DisplayListRectParams {
-            epoch,
-            rect_idx: content_group.root.node_id,
-            html_node: &referenced_content.node_data[content_group.root.node_id].node_type,
-            window_size,
-        };
 
-        // Push the root of the node
-        push_rectangles_into_displaylist_inner(
-            content_group.root,
-            scrollable_nodes,
-            &rectangle,
-            referenced_content,
-            referenced_mutable_content,
-            &mut clip_stack
-        );
+        let mut open_nodes = Vec::new();
+
+        // position:absolute groups are drawn after the rest of the DOM, but they
+        // still have to be transformed / faded / scrolled by the ancestors of the group root
+        let mut ancestors = Vec::new();
+        let mut current_parent = referenced_content.node_hierarchy[content_group.root.node_id].parent;
+        while let Some(parent) = current_parent {
+            ancestors.push(parent);
+            current_parent = referenced_content.node_hierarchy[parent].parent;
+        }
+
+        // position:fixed nodes don't scroll with their ancestors, so the scroll
+        // frames above the outermost position:fixed node aren't pushed again
+        let ancestors = ancestors.into_iter().rev().collect::<Vec<NodeId>>();
+        let first_fixed_node = ancestors.iter().cloned().chain(::std::iter::once(content_group.root.node_id)).position(|node_id| {
+            referenced_content.display_rectangle_arena[node_id].layout.position.unwrap_or_default() == LayoutPosition::Fixed
+        });
+
+        for (ancestor_idx, node_id) in ancestors.into_iter().enumerate() {
+            let stacking_context = push_stacking_context(node_id, referenced_content, referenced_mutable_content.builder);
+            let sticky_frame = sticky_frames.get(&node_id).cloned();
+            if let Some(sticky_frame) = sticky_frame {
+                referenced_mutable_content.builder.push_clip_id(sticky_frame);
+            }
+            let scrolls_fixed_node = first_fixed_node.map(|fixed_idx| ancestor_idx < fixed_idx).unwrap_or(false);
+            let scroll_frame = scroll_frames.get(&node_id).cloned().filter(|_| !scrolls_fixed_node);
+            if let Some(scroll_frame) = scroll_frame {
+                referenced_mutable_content.builder.push_clip_id(scroll_frame);
+            }
+            open_nodes.push(OpenNode {
+                node_id,
+                stacking_context,
+                has_sticky_frame: sticky_frame.is_some(),
+                has_scroll_frame: scroll_frame.is_some(),
+                // The scrollbars are drawn by the group of the ancestor
+                has_scrollbars: false,
+            });
+        }
+
+        for item in ::std::iter::once(content_group.root).chain(content_group.node_ids.into_iter()) {
+
+            // Close all nodes whose children have been drawn completely
+            while let Some(last) = open_nodes.last().cloned() {
+                if is_ancestor_of(last.node_id, item.node_id, referenced_content.node_hierarchy) {
+                    break;
+                }
+                close_node(last, scrollable_nodes, scroll_states, referenced_content, referenced_mutable_content);
+                open_nodes.pop();
+            }
 
-        for item in content_group.node_ids {
+            let stacking_context = push_stacking_context(item.node_id, referenced_content, referenced_mutable_content.builder);
+
+            // The node and its children are pushed into the sticky frame of the node
+            let sticky_frame = push_sticky_frame(item.node_id, referenced_content, referenced_mutable_content.builder);
+            if let Some(sticky_frame) = sticky_frame {
+                sticky_frames.insert(item.node_id, sticky_frame);
+            }
 
             let rectangle = DisplayListRectParams {
                 epoch,
@@ -538,7 +1177,338 @@ fn push_rectangles_into_displaylist<'a, 'b, 'c, 'd, 'e, 'f, T>(
                 referenced_mutable_content,
                 &mut clip_stack
             );
+
+            // The children of an overflowing node are pushed into its scroll frame
+            let scroll_frame = push_scroll_frame(item.node_id, scrollable_nodes, scroll_states, referenced_mutable_content.builder);
+            if let Some(scroll_frame) = scroll_frame {
+                scroll_frames.insert(item.node_id, scroll_frame);
+            }
+
+            if stacking_context.is_some() || sticky_frame.is_some() || scroll_frame.is_some() {
+                open_nodes.push(OpenNode {
+                    node_id: item.node_id,
+                    stacking_context,
+                    has_sticky_frame: sticky_frame.is_some(),
+                    has_scroll_frame: scroll_frame.is_some(),
+                    has_scrollbars: scroll_frame.is_some() && !referenced_content.display_rectangle_arena[item.node_id].style.is_visibility_hidden(),
+                });
+            }
+        }
+
+        for open_node in open_nodes.into_iter().rev() {
+            close_node(open_node, scrollable_nodes, scroll_states, referenced_content, referenced_mutable_content);
+        }
+    }
+}
+
+/// Node whose children are currently being pushed into the display list
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct OpenNode {
+    node_id: NodeId,
+    /// Stacking context that has to be popped after the last child of the node
+    stacking_context: Option<PushedStackingContext>,
+    /// Whether the node and its children are pushed into the sticky frame of the node
+    has_sticky_frame: bool,
+    /// Whether the children are pushed into the scroll frame of the node
+    has_scroll_frame: bool,
+    /// Whether the scrollbars of the node have to be drawn on top of the children
+    has_scrollbars: bool,
+}
+
+/// Pops the scroll frame, draws the scrollbars, pops the sticky frame and
+/// pops the stacking context of a node after its last child has been pushed
+fn close_node<'a,'b,'c,'d,'e,'f, T>(
+    open_node: OpenNode,
+    scrollable_nodes: &ScrolledNodes,
+    scroll_states: &mut ScrollStates,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    referenced_mutable_content: &mut DisplayListParametersMut<'f, T>,
+) {
+    if open_node.has_scroll_frame {
+        referenced_mutable_content.builder.pop_clip_id();
+    }
+
+    if open_node.has_scrollbars {
+        if let Some(scroll_node) = scrollable_nodes.overflowing_nodes.get(&open_node.node_id) {
+            let scroll_offset = scroll_states.get_scroll_amount(&scroll_node.parent_external_scroll_id).unwrap_or((0.0, 0.0));
+            push_scrollbars(
+                scroll_node,
+                scroll_offset,
+                referenced_mutable_content.builder,
+                &referenced_content.layout_result.resolve_context,
+                referenced_mutable_content.app_resources,
+            );
+        }
+    }
+
+    if open_node.has_sticky_frame {
+        referenced_mutable_content.builder.pop_clip_id();
+    }
+
+    if let Some(stacking_context) = open_node.stacking_context {
+        pop_stacking_context(stacking_context, referenced_mutable_content.builder);
+    }
+}
+
+/// If the node is `position: sticky`, defines the sticky frame that the node and its children
+/// are pushed into. WebRender keeps the node at the `top` / `right` / `bottom` / `left` distance
+/// from the edges of the nearest scroll frame while scrolling, but never moves it out of the
+/// content box of its parent.
+fn push_sticky_frame<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    builder: &mut DisplayListBuilder,
+) -> Option<ClipId>
+{
+    use euclid::SideOffsets2D;
+    use webrender::api::{StickyOffsetBounds, LayoutVector2D};
+
+    // Insets and paddings with relative units (`%`, `em`, `vw`, `calc()`) are already resolved to pixels
+    let layout = &referenced_content.layout_result.rect_layouts[node_id];
+    if layout.position.unwrap_or_default() != LayoutPosition::Sticky {
+        return None;
+    }
+
+    let bounds = referenced_content.layout_result.rects[node_id].bounds;
+
+    let parent_bounds = match referenced_content.node_hierarchy[node_id].parent {
+        Some(parent_id) => {
+            let parent_bounds = referenced_content.layout_result.rects[parent_id].bounds;
+            let parent_padding = referenced_content.layout_result.rect_layouts[parent_id].padding.unwrap_or_default();
+            let padding_top = parent_padding.top.map(|p| p.to_pixels()).unwrap_or(0.0);
+            let padding_right = parent_padding.right.map(|p| p.to_pixels()).unwrap_or(0.0);
+            let padding_bottom = parent_padding.bottom.map(|p| p.to_pixels()).unwrap_or(0.0);
+            let padding_left = parent_padding.left.map(|p| p.to_pixels()).unwrap_or(0.0);
+            LayoutRect::new(
+                LayoutPoint::new(parent_bounds.origin.x + padding_left, parent_bounds.origin.y + padding_top),
+                LayoutSize::new(
+                    parent_bounds.size.width - padding_left - padding_right,
+                    parent_bounds.size.height - padding_top - padding_bottom,
+                ),
+            )
+        },
+        None => bounds,
+    };
+
+    let margins = SideOffsets2D::new(
+        layout.top.map(|t| t.0.to_pixels()),
+        layout.right.map(|r| r.0.to_pixels()),
+        layout.bottom.map(|b| b.0.to_pixels()),
+        layout.left.map(|l| l.0.to_pixels()),
+    );
+
+    // How far the node can be moved before it would leave the content box of its parent
+    let vertical_offset_bounds = StickyOffsetBounds::new(
+        (parent_bounds.min_y() - bounds.min_y()).min(0.0),
+        (parent_bounds.max_y() - bounds.max_y()).max(0.0),
+    );
+    let horizontal_offset_bounds = StickyOffsetBounds::new(
+        (parent_bounds.min_x() - bounds.min_x()).min(0.0),
+        (parent_bounds.max_x() - bounds.max_x()).max(0.0),
+    );
+
+    let sticky_frame = builder.define_sticky_frame(
+        bounds,
+        margins,
+        vertical_offset_bounds,
+        horizontal_offset_bounds,
+        LayoutVector2D::zero(),
+    );
+
+    builder.push_clip_id(sticky_frame);
+
+    Some(sticky_frame)
+}
+
+/// If the node overflows, defines the scroll frame that the children of the node
+/// are pushed into, so that WebRender can scroll them (see `ScrollStates`)
+fn push_scroll_frame(
+    node_id: NodeId,
+    scrollable_nodes: &ScrolledNodes,
+    scroll_states: &mut ScrollStates,
+    builder: &mut DisplayListBuilder,
+) -> Option<ClipId> {
+
+    let scroll_node = scrollable_nodes.overflowing_nodes.get(&node_id)?;
+    let max_scroll = scroll_node.get_max_scroll();
+
+    scroll_states.ensure_initialized_scroll_state(scroll_node.parent_external_scroll_id, max_scroll.width, max_scroll.height);
+
+    let content_rect = LayoutRect::new(
+        scroll_node.viewport.origin,
+        LayoutSize::new(
+            scroll_node.viewport.size.width + max_scroll.width,
+            scroll_node.viewport.size.height + max_scroll.height,
+        ),
+    );
+
+    let scroll_frame = builder.define_scroll_frame(
+        Some(scroll_node.parent_external_scroll_id),
+        content_rect,
+        scroll_node.viewport,
+        Vec::<ComplexClipRegion>::new(),
+        None,
+        ScrollSensitivity::ScriptAndInputEvents,
+    );
+
+    builder.push_clip_id(scroll_frame);
+
+    Some(scroll_frame)
+}
+
+/// Pushes the scrollbars of an overflowing node, the thumbs are positioned according
+/// to the current `scroll_offset` of the node. The buttons and the resizer aren't drawn.
+fn push_scrollbars(
+    scroll_node: &OverflowingScrollNode,
+    scroll_offset: (f32, f32),
+    builder: &mut DisplayListBuilder,
+    resolve_context: &PixelResolveContext,
+    app_resources: &AppResources,
+) {
+    let (scroll_x, scroll_y) = scroll_offset;
+
+    let scrollbars = [
+        (&scroll_node.horizontal_scrollbar, scroll_x),
+        (&scroll_node.vertical_scrollbar, scroll_y),
+    ];
+
+    for (scrollbar, scroll) in scrollbars.iter() {
+        let scrollbar = match scrollbar {
+            Some(s) => s,
+            None => continue,
+        };
+        push_scrollbar_part(builder, &scrollbar.style.track, scrollbar.bounds, Some(scrollbar.track_tag), resolve_context, app_resources);
+        push_scrollbar_part(builder, &scrollbar.style.thumb, scrollbar.get_thumb_rect(*scroll), Some(scrollbar.thumb_tag), resolve_context, app_resources);
+    }
+
+    if let (Some(corner), Some(scrollbar)) = (scroll_node.get_scrollbar_corner(), &scroll_node.vertical_scrollbar) {
+        push_scrollbar_part(builder, &scrollbar.style.corner, corner, None, resolve_context, app_resources);
+    }
+}
+
+/// Pushes a single part of a scrollbar (track, thumb or corner) with its background, border and border-radius
+fn push_scrollbar_part(
+    builder: &mut DisplayListBuilder,
+    style: &RectStyle,
+    bounds: LayoutRect,
+    tag: Option<TagId>,
+    resolve_context: &PixelResolveContext,
+    app_resources: &AppResources,
+) {
+    let info = LayoutPrimitiveInfo {
+        rect: bounds,
+        clip_rect: bounds,
+        is_backface_visible: false,
+        tag: tag.map(|tag| (tag, 0)),
+    };
+
+    let clip_region_id = get_clip_region(bounds, style).map(|clip|
+        builder.define_clip(bounds, vec![clip], None)
+    );
+
+    if let Some(id) = clip_region_id {
+        builder.push_clip_id(id);
+    }
+
+    // The track and the thumb have to be hit-testable, even if they have no background
+    if info.tag.is_some() {
+        const TRANSPARENT_BG: StyleColorU = StyleColorU { r: 0, g: 0, b: 0, a: 0 };
+        push_rect(&info, builder, &TRANSPARENT_BG);
+    }
+
+    push_background(&LayoutPrimitiveInfo { tag: None, .. info }, builder, style, &RectLayout::default(), resolve_context, app_resources);
+    push_border(&info, builder, style, resolve_context, app_resources);
+
+    if clip_region_id.is_some() {
+        builder.pop_clip_id();
+    }
+}
+
+/// Stacking context that was pushed for a node with a `transform` or an `opacity`,
+/// it has to be popped again after the last child of the node has been pushed
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct PushedStackingContext {
+    node_id: NodeId,
+    /// Whether a reference frame (for the `transform`) was pushed before the stacking context
+    has_reference_frame: bool,
+}
+
+/// Returns whether `ancestor` is a (direct or indirect) parent of `node_id`
+fn is_ancestor_of(ancestor: NodeId, node_id: NodeId, node_hierarchy: &NodeHierarchy) -> bool {
+    let mut current_parent = node_hierarchy[node_id].parent;
+    while let Some(parent) = current_parent {
+        if parent == ancestor {
+            return true;
         }
+        current_parent = node_hierarchy[parent].parent;
+    }
+    false
+}
+
+/// If the node has a `transform` or an `opacity` below 1.0, pushes a reference frame
+/// (with the transformation matrix) and / or a stacking context (with the opacity filter).
+///
+/// Since all content of the node and its children is pushed into the reference frame,
+/// WebRender hit-tests the items using the transformed geometry.
+fn push_stacking_context<'a,'b,'c,'d,'e, T>(
+    node_id: NodeId,
+    referenced_content: &DisplayListParametersRef<'a,'b,'c,'d,'e, T>,
+    builder: &mut DisplayListBuilder,
+) -> Option<PushedStackingContext>
+{
+    use css::webrender_translate::wr_translate_transform_matrix;
+
+    let style = &referenced_content.display_rectangle_arena[node_id].style;
+
+    let transform = style.transform.as_ref().filter(|t| !t.0.is_empty());
+    let opacity = style.opacity.map(|o| o.get()).filter(|o| *o < 1.0);
+
+    if transform.is_none() && opacity.is_none() {
+        return None;
+    }
+
+    // The frame is positioned at (0, 0), so that the children can keep their
+    // absolute positions - the transform-origin is baked into the matrix
+    let info = LayoutPrimitiveInfo::new(LayoutRect::zero());
+
+    let has_reference_frame = match transform {
+        Some(transform) => {
+            let bounds = referenced_content.layout_result.rects[node_id].bounds;
+            let transform_origin = style.transform_origin.unwrap_or_default();
+            let origin = StyleLayoutPoint {
+                x: bounds.origin.x + transform_origin.x.to_pixels(bounds.size.width),
+                y: bounds.origin.y + transform_origin.y.to_pixels(bounds.size.height),
+            };
+            let matrix = wr_translate_transform_matrix(transform.to_matrix(origin));
+            let reference_frame_id = builder.push_reference_frame(&info, Some(PropertyBinding::Value(matrix)), None);
+            builder.push_clip_id(reference_frame_id);
+            true
+        },
+        None => false,
+    };
+
+    let filters = match opacity {
+        Some(opacity) => vec![FilterOp::Opacity(PropertyBinding::Value(opacity), opacity)],
+        None => Vec::new(),
+    };
+
+    builder.push_stacking_context(
+        &info,
+        None,
+        TransformStyle::Flat,
+        MixBlendMode::Normal,
+        filters,
+        GlyphRasterSpace::Screen,
+    );
+
+    Some(PushedStackingContext { node_id, has_reference_frame })
+}
+
+fn pop_stacking_context(stacking_context: PushedStackingContext, builder: &mut DisplayListBuilder) {
+    builder.pop_stacking_context();
+    if stacking_context.has_reference_frame {
+        builder.pop_clip_id();
+        builder.pop_reference_frame();
     }
 }
 
@@ -564,7 +1534,7 @@ fn push_rectangles_into_displaylist_inner<'a,'b,'c,'d,'e,'f, T>(
         if let Some(last_child) = referenced_content.node_hierarchy[rectangle.rect_idx].last_child {
             let styled_node = &referenced_content.display_rectangle_arena[rectangle.rect_idx];
             let solved_rect = &referenced_content.layout_result.rects[rectangle.rect_idx];
-            let clip = get_clip_region(solved_rect.bounds, &styled_node)
+            let clip = get_clip_region(solved_rect.bounds, &styled_node.style)
                 .unwrap_or(ComplexClipRegion::new(solved_rect.bounds, BorderRadius::zero(), ClipMode::Clip));
             let clip_id = referenced_mutable_content.builder.define_clip(solved_rect.bounds, vec![clip], /* image_mask: */ None);
             referenced_mutable_content.builder.push_clip_id(clip_id);
@@ -588,9 +1558,9 @@ pub(crate) struct DisplayListRectParams<'a, T: 'a> {
     window_size: WindowSize,
 }
 
-fn get_clip_region<'a>(bounds: LayoutRect, rect: &DisplayRectangle<'a>) -> Option<ComplexClipRegion> {
+fn get_clip_region(bounds: LayoutRect, style: &RectStyle) -> Option<ComplexClipRegion> {
     use css::webrender_translate::wr_translate_border_radius;
-    rect.style.border_radius.and_then(|border_radius| {
+    style.border_radius.and_then(|border_radius| {
         Some(ComplexClipRegion {
             rect: bounds,
             radii: wr_translate_border_radius(border_radius.0).into(),
@@ -618,6 +1588,13 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
     } = rectangle;
 
     let rect = &display_rectangle_arena[*rect_idx];
+
+    // `visibility: hidden` - the node still takes up space in the layout, but is neither
+    // drawn nor hit-tested. Children are handled separately, since they can be `visible` again
+    if rect.style.is_visibility_hidden() {
+        return;
+    }
+
     let bounds = layout_result.rects[*rect_idx].bounds;
 
     let info = LayoutPrimitiveInfo {
@@ -631,7 +1608,7 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
         }),
     };
 
-    let clip_region_id = get_clip_region(bounds, &rect).map(|clip|
+    let clip_region_id = get_clip_region(bounds, &rect.style).map(|clip|
         referenced_mutable_content.builder.define_clip(bounds, vec![clip], None)
     );
 
@@ -641,6 +1618,7 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
         &rect.style,
         &bounds,
         BoxShadowClipMode::Outset,
+        &layout_result.resolve_context,
     );
 
     if let Some(id) = clip_region_id {
@@ -648,18 +1626,9 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
     }
 
     // If the rect is hit-testing relevant, we need to push a rect anyway.
-    // Otherwise the hit-testing gets confused
-    if let Some(bg) = &rect.style.background {
-        push_background(
-            &info,
-            &bounds,
-            referenced_mutable_content.builder,
-            bg,
-            &rect.style.background_size,
-            &rect.style.background_repeat,
-            &referenced_mutable_content.app_resources,
-        );
-    } else if info.tag.is_some() {
+    // Otherwise the hit-testing gets confused (background layers can be
+    // clipped to the content box or can't be hit-tested at all, i.e. `none`)
+    if info.tag.is_some() {
         const TRANSPARENT_BG: StyleColorU = StyleColorU { r: 0, g: 0, b: 0, a: 0 };
         push_rect(
             &info,
@@ -668,14 +1637,22 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
         );
     }
 
-    if let Some(ref border) = rect.style.border {
-        push_border(
-            &info,
-            referenced_mutable_content.builder,
-            &border,
-            &rect.style.border_radius,
-        );
-    }
+    push_background(
+        &LayoutPrimitiveInfo { tag: None, .. info },
+        referenced_mutable_content.builder,
+        &rect.style,
+        &layout_result.rect_layouts[*rect_idx],
+        &layout_result.resolve_context,
+        &referenced_mutable_content.app_resources,
+    );
+
+    push_border(
+        &info,
+        referenced_mutable_content.builder,
+        &rect.style,
+        &layout_result.resolve_context,
+        &referenced_mutable_content.app_resources,
+    );
 
     match html_node {
         Div => { },
@@ -690,7 +1667,7 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
                 layout_result,
                 rect_idx,
                 &rect.style,
-                &rect.layout,
+                &layout_result.rect_layouts[*rect_idx],
             )
         },
         Image(image_id) => push_image(
@@ -709,7 +1686,8 @@ fn displaylist_handle_rect<'a,'b,'c,'d,'e,'f,'g, T>(
         referenced_mutable_content.builder,
         &rect.style,
         &bounds,
-        BoxShadowClipMode::Inset
+        BoxShadowClipMode::Inset,
+        &layout_result.resolve_context,
     );
 
     if clip_region_id.is_some() {
@@ -828,6 +1806,9 @@ fn push_iframe<'a,'b,'c,'d,'e,'f, T>(
     let mut focus_target = None;
     let hovered_nodes = BTreeMap::new();
 
+    // Like `vw` and `vh`, @media queries inside of an IFrame refer to the window
+    let media_context = referenced_mutable_content.fake_window.state.get_media_query_context();
+
     let mut ui_state = new_dom.into_ui_state();
     let ui_description = UiDescription::<T>::match_css_to_dom(
         &mut ui_state,
@@ -835,11 +1816,17 @@ fn push_iframe<'a,'b,'c,'d,'e,'f, T>(
         &mut focused_node,
         &mut focus_target,
         &hovered_nodes,
-        is_mouse_down
+        is_mouse_down,
+        &media_context,
     );
 
     let display_list = DisplayList::new_from_ui_description(&ui_description, &ui_state);
-    referenced_mutable_content.app_resources.add_fonts_and_images(&display_list);
+
+    // `vw` and `vh` inside of an IFrame still refer to the size of the window
+    let window_size = rectangle.window_size.get_reverse_logical_size();
+    let window_size = LayoutSize::new(window_size.width as f32, window_size.height as f32);
+
+    referenced_mutable_content.app_resources.add_fonts_and_images(&display_list, window_size);
 
     let arena = &ui_description.ui_descr_arena;
     let node_hierarchy = &arena.node_layout;
@@ -858,16 +1845,15 @@ fn push_iframe<'a,'b,'c,'d,'e,'f, T>(
         &*referenced_mutable_content.app_resources,
         rect_size,
         rect_origin,
+        window_size,
     );
 
     let mut scrollable_nodes = get_nodes_that_need_scroll_clip(
         node_hierarchy, &display_list.rectangles, node_data, &layout_result.rects,
-        &layout_result.node_depths, referenced_content.pipeline_id
+        &layout_result.node_depths, &layout_result.resolve_context, referenced_content.pipeline_id
     );
 
-    let rects_in_rendering_order = determine_rendering_order(
-        node_hierarchy, &display_list.rectangles, &layout_result.rects
-    );
+    let rects_in_rendering_order = determine_rendering_order(node_hierarchy, &display_list.rectangles);
 
     let referenced_content = DisplayListParametersRef {
         // Important: Need to update the ui description, otherwise this function would be endlessly recursive
@@ -948,25 +1934,35 @@ fn push_text(
     rect_style: &RectStyle,
     rect_layout: &RectLayout,
 ) {
-    use text_layout::get_layouted_glyphs;
+    use webrender::api::{Shadow, LayoutVector2D};
+    use text_layout::{get_layouted_glyphs, get_line_extents};
     use css::webrender_translate::wr_translate_color_u;
     use ui_solver::determine_text_alignment;
+    use azul_css::{StyleTextAlignmentHorz, StyleTextAlignmentVert};
 
-    let (scaled_words, _font_instance_key) = match layout_result.scaled_words.get(node_id) {
+    let (scaled_words, _font_instance_keys) = match layout_result.scaled_words.get(node_id) {
         Some(s) => s,
         None => return,
     };
 
-    let (word_positions, font_instance_key) = match layout_result.positioned_word_cache.get(node_id) {
+    let (word_positions, font_instance_keys) = match layout_result.positioned_word_cache.get(node_id) {
         Some(s) => s,
         None => return,
     };
 
-    let (horz_alignment, vert_alignment) = determine_text_alignment(rect_style, rect_layout);
+    // Texts of a paragraph are already aligned, relative to the content box of the paragraph root
+    let inline_text_origin = layout_result.inline_texts.get(node_id).cloned();
+
+    let (horz_alignment, vert_alignment) = match inline_text_origin {
+        Some(_) => (StyleTextAlignmentHorz::Left, StyleTextAlignmentVert::Top),
+        None => determine_text_alignment(rect_style, rect_layout),
+    };
 
     let rect_padding_top = rect_layout.padding.unwrap_or_default().top.map(|top| top.to_pixels()).unwrap_or(0.0);
     let rect_padding_left = rect_layout.padding.unwrap_or_default().left.map(|left| left.to_pixels()).unwrap_or(0.0);
-    let rect_offset = LayoutPoint::new(info.rect.origin.x + rect_padding_left, info.rect.origin.y + rect_padding_top);
+    let rect_offset = inline_text_origin.unwrap_or_else(|| {
+        LayoutPoint::new(info.rect.origin.x + rect_padding_left, info.rect.origin.y + rect_padding_top)
+    });
     let bounding_size_height_px = info.rect.size.height - rect_layout.get_vertical_padding();
 
     let layouted_glyphs = get_layouted_glyphs(
@@ -978,8 +1974,8 @@ fn push_text(
         bounding_size_height_px
     );
 
-    let font_color = rect_style.font_color.unwrap_or(DEFAULT_FONT_COLOR).0;
-    let font_color = wr_translate_color_u(font_color);
+    let css_font_color = rect_style.font_color.unwrap_or(DEFAULT_FONT_COLOR).0;
+    let font_color = wr_translate_color_u(css_font_color);
 
     // WARNING: Do not enable FontInstanceFlags::FONT_SMOOTHING or FontInstanceFlags::FORCE_AUTOHINT -
     // they seem to interfere with the text layout thereby messing with the actual text layout.
@@ -998,8 +1994,10 @@ fn push_text(
         .map(|padding| subtract_padding(&current_bounds, padding))
         .unwrap_or(current_bounds);
 
-    // Adjust the bounds by the padding, depending on the overflow:visible parameter
+    // Adjust the bounds by the padding, depending on the overflow:visible parameter -
+    // texts of a paragraph are only clipped by the paragraph root
     let mut text_bounds = match (overflow_horizontal_visible, overflow_vertical_visible) {
+        _ if inline_text_origin.is_some() => None,
         (true, true) => None,
         (false, false) => Some(original_text_bounds),
         (true, false) => {
@@ -1023,22 +2021,139 @@ fn push_text(
         builder.push_clip_id(clip_id);
     }
 
-    builder.push_text(
-        &info,
-        &layouted_glyphs.glyphs,
-        *font_instance_key,
-        font_color.into(),
-        Some(GlyphOptions {
-            render_mode: FontRenderMode::Subpixel,
-            flags: flags,
-        })
-    );
+    // All items until pop_all_shadows() are drawn with every shadow, the first shadow is on top
+    let text_shadows = rect_style.text_shadow.as_ref().map(|s| s.0.as_slice()).unwrap_or(&[]);
+    for text_shadow in text_shadows.iter().rev() {
+        builder.push_shadow(&info, Shadow {
+            offset: LayoutVector2D::new(
+                text_shadow.offset[0].to_pixels_relative(0.0, &layout_result.resolve_context),
+                text_shadow.offset[1].to_pixels_relative(0.0, &layout_result.resolve_context),
+            ),
+            color: wr_translate_color_u(text_shadow.color.unwrap_or(css_font_color)).into(),
+            blur_radius: text_shadow.blur_radius.to_pixels_relative(0.0, &layout_result.resolve_context),
+        });
+    }
+
+    // Glyphs from fallback fonts have to be pushed with the font instance of their font
+    for (font_idx, font_instance_key) in font_instance_keys.iter().enumerate() {
+
+        let glyphs = layouted_glyphs.glyphs.iter()
+            .zip(layouted_glyphs.glyph_fonts.iter())
+            .filter(|(_, glyph_font)| **glyph_font == font_idx)
+            .map(|(glyph, _)| *glyph)
+            .collect::<Vec<_>>();
+
+        if glyphs.is_empty() {
+            continue;
+        }
+
+        builder.push_text(
+            &info,
+            &glyphs,
+            *font_instance_key,
+            font_color.into(),
+            Some(GlyphOptions {
+                render_mode: FontRenderMode::Subpixel,
+                flags: flags,
+            })
+        );
+    }
+
+    if let Some(text_decoration) = &rect_style.text_decoration {
+        let line_extents = get_line_extents(&layouted_glyphs, scaled_words, word_positions);
+        push_text_decoration(
+            info,
+            builder,
+            text_decoration,
+            &line_extents,
+            &scaled_words.font_metrics,
+            scaled_words.font_size_px,
+            css_font_color,
+            &layout_result.resolve_context,
+        );
+    }
+
+    if !text_shadows.is_empty() {
+        builder.pop_all_shadows();
+    }
 
     if text_bounds.is_some() {
         builder.pop_clip_id();
     }
 }
 
+/// Draws the underlines, overlines and strikethroughs of every line of a text,
+/// `line_extents` are the start of the baseline and the width of each line
+fn push_text_decoration(
+    info: &PrimitiveInfo<LayoutPixel>,
+    builder: &mut DisplayListBuilder,
+    text_decoration: &StyleTextDecoration,
+    line_extents: &[(LayoutPoint, f32)],
+    font_metrics: &FontMetrics,
+    font_size_px: f32,
+    font_color: StyleColorU,
+    resolve_context: &PixelResolveContext,
+) {
+    use webrender::api::LineOrientation;
+    use css::webrender_translate::{wr_translate_color_u, wr_translate_text_decoration_style};
+
+    let line = text_decoration.line.unwrap_or_default();
+    if line.is_none() {
+        return;
+    }
+
+    let color: ColorF = wr_translate_color_u(text_decoration.color.unwrap_or(font_color)).into();
+    let style = text_decoration.style.unwrap_or_default();
+
+    // A thickness set in the CSS overrides the thickness of all lines, percentages refer to the font size
+    let css_thickness = match text_decoration.thickness.unwrap_or_default() {
+        TextDecorationThickness::Auto => None,
+        TextDecorationThickness::Length(thickness) => Some(thickness.to_pixels_relative(font_size_px, resolve_context)),
+    };
+
+    let decorations = [
+        (line.underline, font_metrics.underline_position, font_metrics.underline_thickness),
+        (line.overline, font_metrics.ascender, font_metrics.underline_thickness),
+        (line.line_through, font_metrics.strikeout_position, font_metrics.strikeout_thickness),
+    ];
+
+    for (is_visible, position, font_thickness) in decorations.iter() {
+
+        if !is_visible {
+            continue;
+        }
+
+        let thickness = css_thickness.unwrap_or(*font_thickness).max(1.0);
+
+        // Double lines are two solid lines with a gap of one line thickness,
+        // wavy lines need some vertical space for the wave
+        let line_offsets = match style {
+            TextDecorationStyle::Double => vec![0.0, 2.0 * thickness],
+            _ => vec![0.0],
+        };
+        let line_height = match style {
+            TextDecorationStyle::Wavy => 3.0 * thickness,
+            _ => thickness,
+        };
+
+        for (baseline_start, line_width) in line_extents {
+            for line_offset in &line_offsets {
+                let line_rect = LayoutRect::new(
+                    LayoutPoint::new(baseline_start.x, baseline_start.y + position + line_offset),
+                    LayoutSize::new(*line_width, line_height),
+                );
+                builder.push_line(
+                    &LayoutPrimitiveInfo { rect: line_rect, .. *info },
+                    thickness,
+                    LineOrientation::Horizontal,
+                    &color,
+                    wr_translate_text_decoration_style(style),
+                );
+            }
+        }
+    }
+}
+
 enum ShouldPushShadow {
     OneShadow,
     TwoShadows,
@@ -1055,7 +2170,8 @@ fn push_box_shadow(
     builder: &mut DisplayListBuilder,
     style: &RectStyle,
     bounds: &LayoutRect,
-    shadow_type: BoxShadowClipMode)
+    shadow_type: BoxShadowClipMode,
+    resolve_context: &PixelResolveContext)
 {
     use self::ShouldPushShadow::*;
 
@@ -1088,7 +2204,7 @@ fn push_box_shadow(
 
             push_single_box_shadow_edge(
                 builder, current_shadow, bounds, border_radius, shadow_type,
-                top, bottom, left, right
+                top, bottom, left, right, resolve_context
             );
         },
         // Two shadows in opposite directions:
@@ -1102,22 +2218,22 @@ fn push_box_shadow(
                 (Some(Some(t)), None, Some(Some(b)), right) => {
                     push_single_box_shadow_edge(
                         builder, t, bounds, border_radius, shadow_type,
-                        top, &None, &None, &None
+                        top, &None, &None, &None, resolve_context
                     );
                     push_single_box_shadow_edge(
                         builder, b, bounds, border_radius, shadow_type,
-                        &None, bottom, &None, &None
+                        &None, bottom, &None, &None, resolve_context
                     );
                 },
                 // left + right box-shadow pair
                 (None, Some(Some(l)), None, Some(Some(r))) => {
                     push_single_box_shadow_edge(
                         builder, l, bounds, border_radius, shadow_type,
-                        &None, &None, left, &None
+                        &None, &None, left, &None, resolve_context
                     );
                     push_single_box_shadow_edge(
                         builder, r, bounds, border_radius, shadow_type,
-                        &None, &None, &None, right
+                        &None, &None, &None, right, resolve_context
                     );
                 }
                 _ => return, // reachable, but invalid
@@ -1129,7 +2245,7 @@ fn push_box_shadow(
             let top_shadow = top.unwrap();
             let clip_rect = top_shadow
                 .as_ref()
-                .map(|top_shadow| get_clip_rect(top_shadow, bounds))
+                .map(|top_shadow| get_clip_rect(top_shadow, bounds, resolve_context))
                 .unwrap_or(*bounds);
 
             push_box_shadow_inner(
@@ -1138,7 +2254,8 @@ fn push_box_shadow(
                 border_radius,
                 bounds,
                 clip_rect,
-                shadow_type
+                shadow_type,
+                resolve_context,
             );
         }
     }
@@ -1150,7 +2267,8 @@ fn push_box_shadow_inner(
     border_radius: StyleBorderRadius,
     bounds: &LayoutRect,
     clip_rect: LayoutRect,
-    shadow_type: BoxShadowClipMode)
+    shadow_type: BoxShadowClipMode,
+    resolve_context: &PixelResolveContext)
 {
     use webrender::api::LayoutVector2D;
     use css::webrender_translate::{
@@ -1193,16 +2311,16 @@ fn push_box_shadow_inner(
     builder.push_box_shadow(
         &info,
         *bounds,
-        LayoutVector2D::new(pre_shadow.offset[0].to_pixels(), pre_shadow.offset[1].to_pixels()),
+        LayoutVector2D::new(pre_shadow.offset[0].to_pixels_relative(0.0, resolve_context), pre_shadow.offset[1].to_pixels_relative(0.0, resolve_context)),
         apply_gamma(wr_translate_color_u(pre_shadow.color).into()),
-        pre_shadow.blur_radius.to_pixels(),
-        pre_shadow.spread_radius.to_pixels(),
+        pre_shadow.blur_radius.to_pixels_relative(0.0, resolve_context),
+        pre_shadow.spread_radius.to_pixels_relative(0.0, resolve_context),
         wr_translate_border_radius(border_radius.0).into(),
         wr_translate_box_shadow_clip_mode(pre_shadow.clip_mode)
     );
 }
 
-fn get_clip_rect(pre_shadow: &BoxShadowPreDisplayItem, bounds: &LayoutRect) -> LayoutRect {
+fn get_clip_rect(pre_shadow: &BoxShadowPreDisplayItem, bounds: &LayoutRect, resolve_context: &PixelResolveContext) -> LayoutRect {
     if pre_shadow.clip_mode == BoxShadowClipMode::Inset {
         // inset shadows do not work like outset shadows
         // for inset shadows, you have to push a clip ID first, so that they are
@@ -1212,9 +2330,9 @@ fn get_clip_rect(pre_shadow: &BoxShadowPreDisplayItem, bounds: &LayoutRect) -> L
         // calculate the maximum extent of the outset shadow
         let mut clip_rect = *bounds;
 
-        let origin_displace = (pre_shadow.spread_radius.to_pixels() + pre_shadow.blur_radius.to_pixels()) * 2.0;
-        clip_rect.origin.x = clip_rect.origin.x - pre_shadow.offset[0].to_pixels() - origin_displace;
-        clip_rect.origin.y = clip_rect.origin.y - pre_shadow.offset[1].to_pixels() - origin_displace;
+        let origin_displace = (pre_shadow.spread_radius.to_pixels_relative(0.0, resolve_context) + pre_shadow.blur_radius.to_pixels_relative(0.0, resolve_context)) * 2.0;
+        clip_rect.origin.x = clip_rect.origin.x - pre_shadow.offset[0].to_pixels_relative(0.0, resolve_context) - origin_displace;
+        clip_rect.origin.y = clip_rect.origin.y - pre_shadow.offset[1].to_pixels_relative(0.0, resolve_context) - origin_displace;
 
         clip_rect.size.height = clip_rect.size.height + (origin_displace * 2.0);
         clip_rect.size.width = clip_rect.size.width + (origin_displace * 2.0);
@@ -1233,9 +2351,10 @@ fn push_single_box_shadow_edge(
         bottom: &Option<Option<BoxShadowPreDisplayItem>>,
         left: &Option<Option<BoxShadowPreDisplayItem>>,
         right: &Option<Option<BoxShadowPreDisplayItem>>,
+        resolve_context: &PixelResolveContext,
 ) {
     let is_inset_shadow = current_shadow.clip_mode == BoxShadowClipMode::Inset;
-    let origin_displace = (current_shadow.spread_radius.to_pixels() + current_shadow.blur_radius.to_pixels()) * 2.0;
+    let origin_displace = (current_shadow.spread_radius.to_pixels_relative(0.0, resolve_context) + current_shadow.blur_radius.to_pixels_relative(0.0, resolve_context)) * 2.0;
 
     let mut shadow_bounds = *bounds;
     let mut clip_rect = *bounds;
@@ -1292,18 +2411,77 @@ fn push_single_box_shadow_edge(
         border_radius,
         &shadow_bounds,
         clip_rect,
-        shadow_type
+        shadow_type,
+        resolve_context,
     );
 }
 
 #[inline]
 fn push_background(
     info: &PrimitiveInfo<LayoutPixel>,
-    bounds: &TypedRect<f32, LayoutPixel>,
+    builder: &mut DisplayListBuilder,
+    rect_style: &RectStyle,
+    rect_layout: &RectLayout,
+    resolve_context: &PixelResolveContext,
+    app_resources: &AppResources)
+{
+    let backgrounds = match &rect_style.background {
+        Some(b) => b,
+        None => return,
+    };
+
+    let border_box = info.rect;
+    let padding_box = subtract_border(&border_box, &rect_style.border, resolve_context);
+    let content_box = subtract_padding(&padding_box, &rect_layout.padding.unwrap_or_default());
+
+    // The first layer is drawn on top, so the layers are pushed in reverse order
+    for (layer_idx, background) in backgrounds.0.iter().enumerate().rev() {
+
+        let background_clip = rect_style.background_clip.as_ref().and_then(|c| c.get(layer_idx).cloned());
+        let background_origin = rect_style.background_origin.as_ref().and_then(|o| o.get(layer_idx).cloned());
+
+        let clip_rect = match background_clip.unwrap_or_default() {
+            StyleBackgroundClip::BorderBox => border_box,
+            StyleBackgroundClip::PaddingBox => padding_box,
+            StyleBackgroundClip::ContentBox => content_box,
+        };
+
+        let positioning_area = match background_origin.unwrap_or_default() {
+            StyleBackgroundOrigin::BorderBox => border_box,
+            StyleBackgroundOrigin::PaddingBox => padding_box,
+            StyleBackgroundOrigin::ContentBox => content_box,
+        };
+
+        let layer_info = LayoutPrimitiveInfo {
+            rect: positioning_area,
+            clip_rect,
+            .. *info
+        };
+
+        push_background_layer(
+            &layer_info,
+            builder,
+            background,
+            rect_style.background_size.as_ref().and_then(|s| s.get(layer_idx).cloned()),
+            rect_style.background_repeat.as_ref().and_then(|r| r.get(layer_idx).cloned()).unwrap_or_default(),
+            rect_style.background_position.as_ref().and_then(|p| p.get(layer_idx).cloned()).unwrap_or_default(),
+            resolve_context,
+            app_resources,
+        );
+    }
+}
+
+/// Pushes a single background layer, the `info.rect` is the background positioning area
+/// (see `background-origin`), the `info.clip_rect` is the area that the layer is painted in
+/// (see `background-clip`).
+fn push_background_layer(
+    info: &LayoutPrimitiveInfo,
     builder: &mut DisplayListBuilder,
     background: &StyleBackground,
-    background_size: &Option<StyleBackgroundSize>,
-    background_repeat: &Option<StyleBackgroundRepeat>,
+    background_size: Option<StyleBackgroundSize>,
+    background_repeat: StyleBackgroundRepeat,
+    background_position: StyleBackgroundPosition,
+    resolve_context: &PixelResolveContext,
     app_resources: &AppResources)
 {
     use azul_css::{Shape, StyleBackground::*};
@@ -1312,6 +2490,8 @@ fn push_background(
         wr_translate_layout_rect,
     };
 
+    let positioning_area = info.rect;
+
     match background {
         RadialGradient(gradient) => {
             let stops: Vec<GradientStop> = gradient.stops.iter().map(|gradient_pre|
@@ -1320,19 +2500,23 @@ fn push_background(
                     color: wr_translate_color_u(gradient_pre.color).into(),
                 }).collect();
 
-            let center = bounds.center();
+            // Gradients have no intrinsic size, so they always fill the positioning area
+            // Note: the center is relative to the tile, not to the positioning area
+            let tile_size = positioning_area.size;
+            let center = LayoutPoint::new(tile_size.width / 2.0, tile_size.height / 2.0);
 
             // Note: division by 2.0 because it's the radius, not the diameter
             let radius = match gradient.shape {
-                Shape::Ellipse => TypedSize2D::new(bounds.size.width / 2.0, bounds.size.height / 2.0),
+                Shape::Ellipse => TypedSize2D::new(tile_size.width / 2.0, tile_size.height / 2.0),
                 Shape::Circle => {
-                    let largest_bound_size = bounds.size.width.max(bounds.size.height);
+                    let largest_bound_size = tile_size.width.max(tile_size.height);
                     TypedSize2D::new(largest_bound_size / 2.0, largest_bound_size / 2.0)
                 },
             };
 
+            let tile_info = get_background_repeat_info(info, background_repeat, positioning_area.origin, tile_size);
             let gradient = builder.create_radial_gradient(center, radius, stops, wr_translate_extend_mode(gradient.extend_mode));
-            builder.push_radial_gradient(&info, gradient, bounds.size, LayoutSize::zero());
+            builder.push_radial_gradient(&tile_info, gradient, tile_size, LayoutSize::zero());
         },
         LinearGradient(gradient) => {
 
@@ -1342,7 +2526,9 @@ fn push_background(
                     color: wr_translate_color_u(gradient_pre.color).into(),
                 }).collect();
 
-            let (begin_pt, end_pt) = gradient.direction.to_points(&wr_translate_layout_rect(*bounds));
+            let tile_size = positioning_area.size;
+            let (begin_pt, end_pt) = gradient.direction.to_points(&wr_translate_layout_rect(positioning_area));
+            let tile_info = get_background_repeat_info(info, background_repeat, positioning_area.origin, tile_size);
             let gradient = builder.create_gradient(
                 wr_translate_layout_point(begin_pt),
                 wr_translate_layout_point(end_pt),
@@ -1350,64 +2536,81 @@ fn push_background(
                 wr_translate_extend_mode(gradient.extend_mode),
             );
 
-            builder.push_gradient(&info, gradient, bounds.size, LayoutSize::zero());
+            builder.push_gradient(&tile_info, gradient, tile_size, LayoutSize::zero());
         },
         Image(style_image_id) => {
-            // TODO: background-origin, background-position, background-repeat
             if let Some(image_id) = app_resources.get_css_image_id(&style_image_id.0) {
 
-                let bounds = info.rect;
                 let image_dimensions = app_resources.get_image_info(image_id)
                     .map(|info| (info.descriptor.size.width, info.descriptor.size.height))
-                    .unwrap_or((bounds.size.width as i32, bounds.size.height as i32)); // better than crashing...
+                    .unwrap_or((positioning_area.size.width as i32, positioning_area.size.height as i32)); // better than crashing...
 
                 let size = match background_size {
-                    Some(bg_size) => calculate_background_size(bg_size, &info, &image_dimensions),
+                    Some(bg_size) => calculate_background_size(&bg_size, &positioning_area.size, &image_dimensions),
                     None => TypedSize2D::new(image_dimensions.0 as f32, image_dimensions.1 as f32),
                 };
 
-                let background_repeat = background_repeat.unwrap_or_default();
-                let background_repeat_info = get_background_repeat_info(&info, background_repeat, size);
+                // Percentages refer to the space that is left over in the positioning area,
+                // so that `100%` aligns the right / bottom edges of the image and the area
+                let position = LayoutPoint::new(
+                    positioning_area.origin.x + background_position.horizontal.to_pixels_relative(positioning_area.size.width - size.width, resolve_context),
+                    positioning_area.origin.y + background_position.vertical.to_pixels_relative(positioning_area.size.height - size.height, resolve_context),
+                );
+
+                let background_repeat_info = get_background_repeat_info(info, background_repeat, position, size);
 
                 push_image(&background_repeat_info, builder, app_resources, image_id, size);
             }
         },
         Color(c) => {
-            push_rect(&info, builder, c);
+            // Colors aren't positioned, they fill the whole clip rect
+            let color_info = LayoutPrimitiveInfo {
+                rect: info.clip_rect,
+                .. *info
+            };
+            push_rect(&color_info, builder, c);
         },
         NoBackground => { },
     }
 }
 
+/// Returns the area that a background image with the size `background_size` is tiled in,
+/// starting at the `position`. Repeated tiles extend to the edges of the `info.clip_rect`
+/// in both directions.
 fn get_background_repeat_info(
     info: &LayoutPrimitiveInfo,
     background_repeat: StyleBackgroundRepeat,
+    position: LayoutPoint,
     background_size: TypedSize2D<f32, LayoutPixel>,
 ) -> LayoutPrimitiveInfo {
+
     use azul_css::StyleBackgroundRepeat::*;
-    match background_repeat {
-        NoRepeat => LayoutPrimitiveInfo::with_clip_rect(
-            info.rect,
-            TypedRect::new(
-                info.rect.origin,
-                TypedSize2D::new(background_size.width, background_size.height),
-            ),
-        ),
-        Repeat => *info,
-        RepeatX => LayoutPrimitiveInfo::with_clip_rect(
-            info.rect,
-            TypedRect::new(
-                info.rect.origin,
-                TypedSize2D::new(info.rect.size.width, background_size.height),
-            ),
-        ),
-        RepeatY => LayoutPrimitiveInfo::with_clip_rect(
-            info.rect,
-            TypedRect::new(
-                info.rect.origin,
-                TypedSize2D::new(background_size.width, info.rect.size.height),
-            ),
-        ),
+
+    let (repeat_x, repeat_y) = match background_repeat {
+        NoRepeat => (false, false),
+        Repeat => (true, true),
+        RepeatX => (true, false),
+        RepeatY => (false, true),
+    };
+
+    // Returns the start and length of the tiled area on one axis - the first tile
+    // starts before the clip rect, so that one of the tiles starts exactly at the position
+    let tile_axis = |repeat: bool, position: f32, tile_size: f32, clip_start: f32, clip_end: f32| -> (f32, f32) {
+        if repeat && tile_size > 0.0 {
+            let start = position - ((position - clip_start) / tile_size).ceil() * tile_size;
+            (start, (clip_end - start).max(0.0))
+        } else {
+            (position, tile_size)
+        }
+    };
+
+    let clip_rect = info.clip_rect;
+    let (x, width) = tile_axis(repeat_x, position.x, background_size.width, clip_rect.min_x(), clip_rect.max_x());
+    let (y, height) = tile_axis(repeat_y, position.y, background_size.height, clip_rect.min_y(), clip_rect.max_y());
+
+    LayoutPrimitiveInfo {
+        rect: TypedRect::new(LayoutPoint::new(x, y), LayoutSize::new(width, height)),
+        .. *info
     }
 }
 
@@ -1418,13 +2621,13 @@ struct Ratio {
 
 fn calculate_background_size(
     bg_size: &StyleBackgroundSize,
-    info: &PrimitiveInfo<LayoutPixel>,
+    positioning_area: &TypedSize2D<f32, LayoutPixel>,
     image_dimensions: &(i32, i32)
 ) -> TypedSize2D<f32, LayoutPixel> {
 
     let original_ratios = Ratio {
-        width: info.rect.size.width / image_dimensions.0 as f32,
-        height: info.rect.size.height / image_dimensions.1 as f32,
+        width: positioning_area.width / image_dimensions.0 as f32,
+        height: positioning_area.height / image_dimensions.1 as f32,
     };
 
     let ratio = match bg_size {
@@ -1460,18 +2663,40 @@ fn push_image(
 fn push_border(
     info: &PrimitiveInfo<LayoutPixel>,
     builder: &mut DisplayListBuilder,
-    border: &StyleBorder,
-    border_radius: &Option<StyleBorderRadius>)
+    rect_style: &RectStyle,
+    resolve_context: &PixelResolveContext,
+    app_resources: &AppResources)
 {
     use css::webrender_translate::{
         wr_translate_layout_side_offsets, wr_translate_border_details
     };
 
-    if let Some((border_widths, border_details)) = border.get_webrender_border(*border_radius) {
+    let border = match (rect_style.border, rect_style.border_style) {
+        (Some(border), Some(border_style)) => border.with_styles(&border_style),
+        (Some(border), None) => border,
+        (None, Some(border_style)) => StyleBorder::default().with_styles(&border_style),
+        (None, None) => StyleBorder::default(),
+    };
+
+    // The border-image replaces the normal border, but only once its image is loaded
+    let border_image = rect_style.border_image.as_ref().and_then(|border_image| {
+        let css_image_id = border_image.source.as_ref()?;
+        let image_id = app_resources.get_css_image_id(&css_image_id.0)?;
+        let image_info = app_resources.get_image_info(image_id)?;
+        let image_size = (image_info.descriptor.size.width as i32, image_info.descriptor.size.height as i32);
+        Some((border_image.get_webrender_border(&border, image_size, resolve_context), image_info.key))
+    });
+
+    if let Some(((border_widths, border_details), image_key)) = border_image {
         builder.push_border(
             info,
             wr_translate_layout_side_offsets(border_widths),
-            wr_translate_border_details(border_details));
+            wr_translate_border_details(border_details, Some(image_key)));
+    } else if let Some((border_widths, border_details)) = border.get_webrender_border(rect_style.border_radius, resolve_context) {
+        builder.push_border(
+            info,
+            wr_translate_layout_side_offsets(border_widths),
+            wr_translate_border_details(border_details, None));
     }
 }
 
@@ -1496,40 +2721,129 @@ fn subtract_padding(bounds: &TypedRect<f32, LayoutPixel>, padding: &LayoutPaddin
     new_bounds
 }
 
+/// Subtracts the border widths from the bounds, returning the padding box
+fn subtract_border(bounds: &TypedRect<f32, LayoutPixel>, border: &Option<StyleBorder>, resolve_context: &PixelResolveContext)
+-> TypedRect<f32, LayoutPixel>
+{
+    let border = border.unwrap_or_default();
+    subtract_padding(bounds, &LayoutPadding {
+        top: border.top.map(|top| top.border_width.resolve(0.0, resolve_context)),
+        bottom: border.bottom.map(|bottom| bottom.border_width.resolve(0.0, resolve_context)),
+        left: border.left.map(|left| left.border_width.resolve(0.0, resolve_context)),
+        right: border.right.map(|right| right.border_width.resolve(0.0, resolve_context)),
+    })
+}
+
 /// Populate the style properties of the `DisplayRectangle`, apply static / dynamic properties
 fn populate_css_properties(
     rect: &mut DisplayRectangle,
     node_id: NodeId,
     css_overrides: &BTreeMap<NodeId, FastHashMap<DomString, CssProperty>>
 ) {
-    use azul_css::CssDeclaration::*;
-
     for constraint in rect.styled_node.css_constraints.values() {
-        match &constraint {
-            Static(static_property) => apply_style_property(rect, static_property),
-            Dynamic(dynamic_property) => {
-                let is_dynamic_prop = css_overrides.get(&node_id).and_then(|overrides| {
-                    overrides.get(&DomString::Heap(dynamic_property.dynamic_id.clone()))
-                });
+        if let Some(property) = get_css_property_value(constraint, node_id, css_overrides) {
+            apply_style_property(rect, property);
+        }
+    }
 
-                if let Some(overridden_property) = is_dynamic_prop {
-                    // Only apply the dynamic style property default, if it isn't set to auto
-                    if property_type_matches(overridden_property, &dynamic_property.default) {
-                        apply_style_property(rect, overridden_property);
-                    } else {
-                        #[cfg(feature = "logging")] {
-                            error!(
-                                "Dynamic style property on rect {:?} don't have the same discriminant type,\r\n
-                                cannot override {:?} with {:?} - enum discriminant mismatch",
-                                rect, dynamic_property.default, overridden_property
-                            )
-                        }
-                    }
-                } else if let DynamicCssPropertyDefault::Exact(default) = &dynamic_property.default {
-                    apply_style_property(rect, default);
+    populate_scrollbar_styles(rect, node_id, css_overrides);
+}
+
+/// Builds the `ScrollbarInfo` of the horizontal and vertical scrollbar from the properties
+/// of the `::-webkit-scrollbar` pseudo-elements. The `::-webkit-scrollbar` properties are applied
+/// first, so that `::-webkit-scrollbar-track` and `::-webkit-scrollbar-track-piece` override them.
+fn populate_scrollbar_styles(
+    rect: &mut DisplayRectangle,
+    node_id: NodeId,
+    css_overrides: &BTreeMap<NodeId, FastHashMap<DomString, CssProperty>>
+) {
+    let styled_node = rect.styled_node;
+
+    for ((orientation, pseudo_element), constraints) in &styled_node.scrollbar_constraints {
+
+        let scrollbar = match orientation {
+            ScrollbarOrientation::Horizontal => &mut rect.style.horizontal_scrollbar,
+            ScrollbarOrientation::Vertical => &mut rect.style.vertical_scrollbar,
+        }.get_or_insert_with(|| Box::new(ScrollbarInfo::new(*orientation)));
+
+        // Layout properties of the pseudo-element, only the size and padding
+        // of the `::-webkit-scrollbar` are used, everything else is ignored
+        let mut layout = RectLayout::default();
+
+        {
+            let part_style = match pseudo_element {
+                CssPathPseudoElement::Scrollbar |
+                CssPathPseudoElement::ScrollbarTrack |
+                CssPathPseudoElement::ScrollbarTrackPiece => &mut scrollbar.track,
+                CssPathPseudoElement::ScrollbarThumb => &mut scrollbar.thumb,
+                CssPathPseudoElement::ScrollbarButton => &mut scrollbar.button,
+                CssPathPseudoElement::ScrollbarCorner => &mut scrollbar.corner,
+                CssPathPseudoElement::Resizer => &mut scrollbar.resizer,
+            };
+
+            for constraint in constraints.values() {
+                if let Some(property) = get_css_property_value(constraint, node_id, css_overrides) {
+                    apply_css_property(part_style, &mut layout, property);
                 }
             }
         }
+
+        if *pseudo_element == CssPathPseudoElement::Scrollbar {
+            // `width` sets the width of the vertical scrollbar, `height` the height of the horizontal one -
+            // the `min-content`, `max-content` and `fit-content` keywords are ignored, a scrollbar has no content
+            let thickness = match orientation {
+                ScrollbarOrientation::Horizontal => layout.height.and_then(|height| height.get_exact()),
+                ScrollbarOrientation::Vertical => layout.width.and_then(|width| width.get_exact()),
+            };
+            if let Some(thickness) = thickness {
+                scrollbar.width = LayoutWidth::Exact(thickness);
+            }
+            if let Some(padding) = layout.padding {
+                scrollbar.padding = padding;
+            }
+        }
+    }
+}
+
+/// Returns the value of a static or dynamic CSS declaration, `None` if the declaration has no
+/// value (i.e. if it is a non-overridden dynamic property that defaults to `auto`)
+fn get_css_property_value<'b>(
+    declaration: &'b CssDeclaration,
+    node_id: NodeId,
+    css_overrides: &'b BTreeMap<NodeId, FastHashMap<DomString, CssProperty>>
+) -> Option<&'b CssProperty> {
+    use azul_css::CssDeclaration::*;
+
+    match declaration {
+        Static(static_property) => Some(static_property),
+        Dynamic(dynamic_property) => {
+            let is_dynamic_prop = css_overrides.get(&node_id).and_then(|overrides| {
+                overrides.get(&DomString::Heap(dynamic_property.dynamic_id.clone()))
+            });
+
+            if let Some(overridden_property) = is_dynamic_prop {
+                // Only apply the dynamic style property default, if it isn't set to auto
+                if property_type_matches(overridden_property, &dynamic_property.default) {
+                    Some(overridden_property)
+                } else {
+                    #[cfg(feature = "logging")] {
+                        error!(
+                            "Dynamic style property on node {:?} don't have the same discriminant type,\r\n
+                            cannot override {:?} with {:?} - enum discriminant mismatch",
+                            node_id, dynamic_property.default, overridden_property
+                        )
+                    }
+                    None
+                }
+            } else if let DynamicCssPropertyDefault::Exact(default) = &dynamic_property.default {
+                Some(default)
+            } else {
+                None
+            }
+        },
+        // var() references and keywords are resolved during the cascade, anything
+        // left over here couldn't be resolved and is ignored
+        Unresolved(_) | Keyword(_) => None,
     }
 }
 
@@ -1544,49 +2858,95 @@ fn property_type_matches(a: &CssProperty, b: &DynamicCssPropertyDefault) -> bool
 }
 
 fn apply_style_property(rect: &mut DisplayRectangle, property: &CssProperty) {
+    apply_css_property(&mut rect.style, &mut rect.layout, property);
+}
+
+/// Applies a single CSS property to the style or the layout properties of a node
+fn apply_css_property(style: &mut RectStyle, layout: &mut RectLayout, property: &CssProperty) {
 
     use azul_css::CssProperty::*;
 
     match property {
-        BorderRadius(b)     => { rect.style.border_radius = Some(*b);                   },
-        BackgroundSize(s)   => { rect.style.background_size = Some(*s);                 },
-        BackgroundRepeat(r) => { rect.style.background_repeat = Some(*r);               },
-        TextColor(t)        => { rect.style.font_color = Some(*t);                      },
-        Border(b)           => { StyleBorder::merge(&mut rect.style.border, &b);        },
-        Background(b)       => { rect.style.background = Some(b.clone());               },
-        FontSize(f)         => { rect.style.font_size = Some(*f);                       },
-        FontFamily(f)       => { rect.style.font_family = Some(f.clone());              },
-        LetterSpacing(l)    => { rect.style.letter_spacing = Some(*l);                  },
-        TextAlign(ta)       => { rect.style.text_align = Some(*ta);                     },
-        BoxShadow(b)        => { StyleBoxShadow::merge(&mut rect.style.box_shadow, b);  },
-        LineHeight(lh)      => { rect.style.line_height = Some(*lh);                    },
-
-        Width(w)            => { rect.layout.width = Some(*w);                          },
-        Height(h)           => { rect.layout.height = Some(*h);                         },
-        MinWidth(mw)        => { rect.layout.min_width = Some(*mw);                     },
-        MinHeight(mh)       => { rect.layout.min_height = Some(*mh);                    },
-        MaxWidth(mw)        => { rect.layout.max_width = Some(*mw);                     },
-        MaxHeight(mh)       => { rect.layout.max_height = Some(*mh);                    },
-
-        Position(p)         => { rect.layout.position = Some(*p);                       },
-        Top(t)              => { rect.layout.top = Some(*t);                            },
-        Bottom(b)           => { rect.layout.bottom = Some(*b);                         },
-        Right(r)            => { rect.layout.right = Some(*r);                          },
-        Left(l)             => { rect.layout.left = Some(*l);                           },
-
-        Padding(p)          => { LayoutPadding::merge(&mut rect.layout.padding, &p);    },
-        Margin(m)           => { LayoutMargin::merge(&mut rect.layout.margin, &m);      },
-        Overflow(o)         => { LayoutOverflow::merge(&mut rect.layout.overflow, &o);  },
-        WordSpacing(ws)     => { rect.style.word_spacing = Some(*ws);                   },
-        TabWidth(tw)        => { rect.style.tab_width = Some(*tw);                      },
-
-        FlexGrow(g)         => { rect.layout.flex_grow = Some(*g)                       },
-        FlexShrink(s)       => { rect.layout.flex_shrink = Some(*s)                     },
-        FlexWrap(w)         => { rect.layout.wrap = Some(*w);                           },
-        FlexDirection(d)    => { rect.layout.direction = Some(*d);                      },
-        JustifyContent(j)   => { rect.layout.justify_content = Some(*j);                },
-        AlignItems(a)       => { rect.layout.align_items = Some(*a);                    },
-        AlignContent(a)     => { rect.layout.align_content = Some(*a);                  },
+        BorderRadius(b)     => { style.border_radius = Some(*b);                        },
+        BackgroundSize(s)   => { style.background_size = Some(s.clone());               },
+        BackgroundRepeat(r) => { style.background_repeat = Some(r.clone());             },
+        BackgroundPosition(p) => { style.background_position = Some(p.clone());         },
+        BackgroundOrigin(o) => { style.background_origin = Some(o.clone());             },
+        BackgroundClip(c)   => { style.background_clip = Some(c.clone());               },
+        TextColor(t)        => { style.font_color = Some(*t);                           },
+        Border(b)           => { StyleBorder::merge(&mut style.border, &b);             },
+        BorderStyle(b)      => { StyleBorderStyle::merge(&mut style.border_style, &b); },
+        BorderImage(b)      => { style.border_image = Some(b.clone());                  },
+        Background(b)       => { style.background = Some(b.clone());                    },
+        FontSize(f)         => { style.font_size = Some(*f);                            },
+        FontFamily(f)       => { style.font_family = Some(f.clone());                   },
+        FontWeight(w)       => { style.font_weight = Some(*w);                          },
+        FontStyle(f)        => { style.font_style = Some(*f);                           },
+        FontStretch(f)      => { style.font_stretch = Some(*f);                         },
+        LetterSpacing(l)    => { style.letter_spacing = Some(*l);                       },
+        TextAlign(ta)       => { style.text_align = Some(*ta);                          },
+        TextDecoration(t)   => { StyleTextDecoration::merge(&mut style.text_decoration, t); },
+        TextShadow(t)       => { style.text_shadow = Some(t.clone());                   },
+        BoxShadow(b)        => { StyleBoxShadow::merge(&mut style.box_shadow, b);       },
+        LineHeight(lh)      => { style.line_height = Some(*lh);                         },
+
+        Width(w)            => { layout.width = Some(*w);                               },
+        Height(h)           => { layout.height = Some(*h);                              },
+        MinWidth(mw)        => { layout.min_width = Some(*mw);                          },
+        MinHeight(mh)       => { layout.min_height = Some(*mh);                         },
+        MaxWidth(mw)        => { layout.max_width = Some(*mw);                          },
+        MaxHeight(mh)       => { layout.max_height = Some(*mh);                         },
+
+        Display(d)          => { layout.display = Some(*d);                             },
+        Position(p)         => { layout.position = Some(*p);                            },
+        Float(f)            => { layout.float = Some(*f);                               },
+        ZIndex(z)           => { layout.z_index = Some(*z);                             },
+        Top(t)              => { layout.top = Some(*t);                                 },
+        Bottom(b)           => { layout.bottom = Some(*b);                              },
+        Right(r)            => { layout.right = Some(*r);                               },
+        Left(l)             => { layout.left = Some(*l);                                },
+
+        Padding(p)          => { LayoutPadding::merge(&mut layout.padding, &p);         },
+        Margin(m)           => { LayoutMargin::merge(&mut layout.margin, &m);           },
+        Overflow(o)         => { LayoutOverflow::merge(&mut layout.overflow, &o);       },
+        WordSpacing(ws)     => { style.word_spacing = Some(*ws);                        },
+        TabWidth(tw)        => { style.tab_width = Some(*tw);                           },
+
+        Flex(f)             => {
+            layout.flex_grow = Some(f.grow);
+            layout.flex_shrink = Some(f.shrink);
+            layout.flex_basis = Some(f.basis);
+        },
+        FlexGrow(g)         => { layout.flex_grow = Some(*g)                            },
+        FlexShrink(s)       => { layout.flex_shrink = Some(*s)                          },
+        FlexBasis(b)        => { layout.flex_basis = Some(*b);                          },
+        Order(o)            => { layout.order = Some(*o);                               },
+        FlexWrap(w)         => { layout.wrap = Some(*w);                                },
+        FlexDirection(d)    => { layout.direction = Some(*d);                           },
+        JustifyContent(j)   => { layout.justify_content = Some(*j);                     },
+        AlignItems(a)       => { layout.align_items = Some(*a);                         },
+        AlignSelf(a)        => { layout.align_self = Some(*a);                          },
+        AlignContent(a)     => { layout.align_content = Some(*a);                       },
+        Gap(g)              => { LayoutGap::merge(&mut layout.gap, &g);                 },
+
+        GridTemplateColumns(c) => { layout.grid_template_columns = Some(c.clone());     },
+        GridTemplateRows(r)    => { layout.grid_template_rows = Some(r.clone());        },
+        GridTemplateAreas(a)   => { layout.grid_template_areas = Some(a.clone());       },
+        GridGap(g)             => { layout.grid_gap = Some(*g);                         },
+        GridRow(r)             => { layout.grid_row = Some(r.clone());                  },
+        GridColumn(c)          => { layout.grid_column = Some(c.clone());               },
+        GridArea(a)            => {
+            layout.grid_row = Some(a.row.clone());
+            layout.grid_column = Some(a.column.clone());
+        },
+
         Cursor(_)           => { /* cursor neither affects layout nor styling */        },
+
+        Transform(t)        => { style.transform = Some(t.clone());                     },
+        TransformOrigin(o)  => { style.transform_origin = Some(*o);                     },
+        Opacity(o)          => { style.opacity = Some(*o);                              },
+        Visibility(v)       => { style.visibility = Some(*v);                           },
+        Transition(_)       => { /* handled by the AnimationStates of the window */      },
+        Animation(_)        => { /* handled by the AnimationStates of the window */      },
     }
 }
@@ -504,6 +504,8 @@ pub struct NodeData<T> {
     pub ids: Vec<DomString>,
     /// `.myclass .otherclass`
     pub classes: Vec<DomString>,
+    /// Arbitrary `key="value"` attributes, selectable via `[key]` or `[key="value"]` in CSS
+    pub attributes: Vec<(DomString, DomString)>,
     /// `On::MouseUp` -> `Callback(my_button_click_handler)`
     pub callbacks: Vec<(EventFilter, Callback<T>)>,
     /// Usually not set by the user directly - `FakeWindow::add_default_callback`
@@ -590,6 +592,7 @@ impl<T> PartialEq for NodeData<T> {
         self.node_type == other.node_type &&
         self.ids == other.ids &&
         self.classes == other.classes &&
+        self.attributes == other.attributes &&
         self.callbacks == other.callbacks &&
         self.default_callback_ids == other.default_callback_ids &&
         self.dynamic_css_overrides == other.dynamic_css_overrides &&
@@ -615,6 +618,9 @@ impl<T> Hash for NodeData<T> {
         for class in &self.classes {
             class.hash(state);
         }
+        for attribute in &self.attributes {
+            attribute.hash(state);
+        }
         for callback in &self.callbacks {
             callback.hash(state);
         }
@@ -635,6 +641,7 @@ impl<T> Clone for NodeData<T> {
             node_type: self.node_type.clone(),
             ids: self.ids.clone(),
             classes: self.classes.clone(),
+            attributes: self.attributes.clone(),
             callbacks: self.callbacks.clone(),
             default_callback_ids: self.default_callback_ids.clone(),
             dynamic_css_overrides: self.dynamic_css_overrides.clone(),
@@ -662,6 +669,10 @@ impl<T> fmt::Display for NodeData<T> {
             format!(" class=\"{}\"", self.classes.iter().map(|s| s.as_str().to_string()).collect::<Vec<String>>().join(" "))
         };
 
+        let attribute_string = self.attributes.iter()
+            .map(|(key, value)| format!(" {}=\"{}\"", key, value))
+            .collect::<String>();
+
         let draggable = if self.is_draggable {
             format!(" draggable=\"true\"")
         } else {
@@ -693,12 +704,12 @@ impl<T> fmt::Display for NodeData<T> {
         };
 
         if let Some(content) = text_content {
-            write!(f, "<{}{}{}{}{}{}{}{}>{}</{}>",
-                html_type, id_string, class_string, tabindex, draggable, callbacks, default_callbacks, css_overrides, content, html_type
+            write!(f, "<{}{}{}{}{}{}{}{}{}>{}</{}>",
+                html_type, id_string, class_string, attribute_string, tabindex, draggable, callbacks, default_callbacks, css_overrides, content, html_type
             )
         } else {
-            write!(f, "<{}{}{}{}{}{}{}{}/>",
-                html_type, id_string, class_string, tabindex, draggable, callbacks, default_callbacks, css_overrides,
+            write!(f, "<{}{}{}{}{}{}{}{}{}/>",
+                html_type, id_string, class_string, attribute_string, tabindex, draggable, callbacks, default_callbacks, css_overrides,
             )
         }
     }
@@ -711,6 +722,7 @@ impl<T> fmt::Debug for NodeData<T> {
                 \tnode_type: {:?}, \
                 \tids: {:?}, \
                 \tclasses: {:?}, \
+                \tattributes: {:?}, \
                 \tcallbacks: {:?}, \
                 \tdefault_callback_ids: {:?}, \
                 \tdynamic_css_overrides: {:?}, \
@@ -720,6 +732,7 @@ impl<T> fmt::Debug for NodeData<T> {
             self.node_type,
             self.ids,
             self.classes,
+            self.attributes,
             self.callbacks,
             self.default_callback_ids,
             self.dynamic_css_overrides,
@@ -740,6 +753,7 @@ impl<T> NodeData<T> {
             node_type,
             ids: Vec::new(),
             classes: Vec::new(),
+            attributes: Vec::new(),
             callbacks: Vec::new(),
             default_callback_ids: Vec::new(),
             dynamic_css_overrides: Vec::new(),
@@ -764,6 +778,11 @@ impl<T> NodeData<T> {
         self.classes.iter().any(|self_class| self_class.equals_str(class))
     }
 
+    /// Returns the value of the attribute with the given key (the first one, if the key is set multiple times)
+    pub fn get_attribute(&self, key: &str) -> Option<&str> {
+        self.attributes.iter().find(|(self_key, _)| self_key.equals_str(key)).map(|(_, value)| value.as_str())
+    }
+
     pub(crate) fn calculate_node_data_hash(&self) -> DomHash {
 
         // Pick hash algorithm based on features
@@ -1180,6 +1199,13 @@ impl<T> Dom<T> {
         self
     }
 
+    /// Same as `add_attribute`, but easier to use for method chaining in a builder-style pattern
+    #[inline]
+    pub fn with_attribute<K: Into<DomString>, V: Into<DomString>>(mut self, key: K, value: V) -> Self {
+        self.add_attribute(key, value);
+        self
+    }
+
     /// Same as `event`, but easier to use for method chaining in a builder-style pattern
     #[inline]
     pub fn with_callback<O: Into<EventFilter>>(mut self, on: O, callback: Callback<T>) -> Self {
@@ -1221,6 +1247,17 @@ impl<T> Dom<T> {
         self.arena.node_data[self.head].classes.push(class.into());
     }
 
+    /// Sets an attribute, overwriting the previous value if the attribute is already set
+    #[inline]
+    pub fn add_attribute<K: Into<DomString>, V: Into<DomString>>(&mut self, key: K, value: V) {
+        let (key, value) = (key.into(), value.into());
+        let attributes = &mut self.arena.node_data[self.head].attributes;
+        match attributes.iter_mut().find(|(self_key, _)| *self_key == key) {
+            Some(attribute) => attribute.1 = value,
+            None => attributes.push((key, value)),
+        }
+    }
+
     #[inline]
     pub fn add_callback<O: Into<EventFilter>>(&mut self, on: O, callback: Callback<T>) {
         self.arena.node_data[self.head].callbacks.push((on.into(), callback));
@@ -60,8 +60,8 @@
 //!
 //! There are a few limitations that should be noted:
 //!
-//! - There are no scrollbars yet. Creating scrollable frames can be done by
-//!   [creating an `IFrameCallback`].
+//! - Scrollbars don't have arrow buttons yet. Custom scrolling behaviour can be
+//!   implemented by [creating an `IFrameCallback`].
 //! - Similarly, there is no clipping of overflowing content yet - clipping only
 //!   works for `IFrameCallback`s.
 //! - There is no support for CSS animations of any kind yet
@@ -231,6 +231,8 @@ mod ui_solver;
 mod style;
 /// DOM diffing
 mod diff;
+/// CSS transitions and `@keyframes` animations
+mod animation;
 /// Checks that two-way bound values are on the stack
 mod stack_checked_pointer;
 /// Window state handling and diffing
@@ -246,6 +246,13 @@ impl<T> $struct_name<T> {
         self.$struct_field.add_css_font_id(css_id)
     }
 
+    /// See [`AppResources::add_css_font_face`]
+    ///
+    /// [`AppResources::add_css_font_face`]: ../app_resources/struct.AppResources.html#method.add_css_font_face
+    pub fn add_css_font_face<S: Into<String>>(&mut self, css_id: S, properties: FontFaceProperties) -> FontId {
+        self.$struct_field.add_css_font_face(css_id, properties)
+    }
+
     /// See [`AppResources::has_css_font_id`]
     ///
     /// [`AppResources::has_css_font_id`]: ../app_resources/struct.AppResources.html#method.has_css_font_id
@@ -260,12 +267,26 @@ impl<T> $struct_name<T> {
         self.$struct_field.get_css_font_id(css_id)
     }
 
+    /// See [`AppResources::get_css_font_face`]
+    ///
+    /// [`AppResources::get_css_font_face`]: ../app_resources/struct.AppResources.html#method.get_css_font_face
+    pub fn get_css_font_face(&self, css_id: &str, properties: FontFaceProperties) -> Option<&FontId> {
+        self.$struct_field.get_css_font_face(css_id, properties)
+    }
+
     /// See [`AppResources::delete_css_font_id`]
     ///
     /// [`AppResources::delete_css_font_id`]: ../app_resources/struct.AppResources.html#method.delete_css_font_id
     pub fn delete_css_font_id(&mut self, css_id: &str) -> Option<FontId> {
         self.$struct_field.delete_css_font_id(css_id)
     }
+
+    /// See [`AppResources::delete_css_font_face`]
+    ///
+    /// [`AppResources::delete_css_font_face`]: ../app_resources/struct.AppResources.html#method.delete_css_font_face
+    pub fn delete_css_font_face(&mut self, css_id: &str, properties: FontFaceProperties) -> Option<FontId> {
+        self.$struct_field.delete_css_font_face(css_id, properties)
+    }
 }
 
 )}
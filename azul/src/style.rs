@@ -1,14 +1,17 @@
 //! DOM-tree to CSS style tree stying
 
-use std::{fmt, collections::BTreeMap};
+use std::{fmt, collections::{BTreeMap, BTreeSet}};
 use azul_css::{
-    Css, CssContentGroup, CssPath,
-    CssPathSelector, CssPathPseudoSelector, CssNthChildSelector::*,
+    Css, CssContentGroup, CssPath, CssRuleBlock, CssDeclaration, CssProperty, CssPropertyType,
+    CssPathSelector, CssPathPseudoSelector, NodeTypePath, substitute_css_variables,
+    MediaQueryContext, CssPathPseudoElement, ScrollbarOrientation, CssOrigin,
 };
 use webrender::api::HitTestItem;
 use {
+    FastHashMap,
     ui_description::{UiDescription, StyledNode},
-    dom::NodeData,
+    diff::{DomDiff, DomRange},
+    dom::{NodeData, NodeType, DomString},
     ui_state::UiState,
     id_tree::{NodeId, NodeHierarchy, NodeDataContainer},
     callbacks::FocusTarget,
@@ -17,8 +20,18 @@ use {
 /// Has all the necessary information about the style CSS path
 pub(crate) struct HtmlCascadeInfo<'a, T: 'a> {
     pub node_data: &'a NodeData<T>,
+    /// Position of the node among its siblings, 1-indexed (for `:nth-child`)
     pub index_in_parent: usize,
+    /// Position of the node among its siblings, counted from the last sibling, 1-indexed
+    pub index_from_end: usize,
+    /// Position of the node among the siblings of the same node type, 1-indexed (for `:nth-of-type`)
+    pub index_of_type: usize,
+    /// Position of the node among the siblings of the same node type, counted from the last sibling
+    pub index_of_type_from_end: usize,
     pub is_last_child: bool,
+    pub is_root: bool,
+    /// Whether the node has no children and no text content (for `:empty`)
+    pub is_empty: bool,
     pub is_hovered_over: bool,
     pub is_focused: bool,
     pub is_active: bool,
@@ -29,14 +42,24 @@ impl<'a, T: 'a> fmt::Debug for HtmlCascadeInfo<'a, T> {
         write!(f, "HtmlCascadeInfo {{ \
             node_data: {:?}, \
             index_in_parent: {}, \
+            index_from_end: {}, \
+            index_of_type: {}, \
+            index_of_type_from_end: {}, \
             is_last_child: {:?}, \
+            is_root: {:?}, \
+            is_empty: {:?}, \
             is_hovered_over: {:?}, \
             is_focused: {:?}, \
             is_active: {:?}, \
          }}",
             self.node_data,
             self.index_in_parent,
+            self.index_from_end,
+            self.index_of_type,
+            self.index_of_type_from_end,
             self.is_last_child,
+            self.is_root,
+            self.is_empty,
             self.is_hovered_over,
             self.is_focused,
             self.is_active,
@@ -52,48 +75,63 @@ pub(crate) fn matches_html_element<'a, T>(
     html_node_tree: &NodeDataContainer<HtmlCascadeInfo<'a, T>>)
 -> bool
 {
-    use self::CssGroupSplitReason::*;
-
     if css_path.selectors.is_empty() {
         return false;
     }
 
-    let mut current_node = Some(node_id);
-    let mut direct_parent_has_to_match = false;
-    let mut last_selector_matched = true;
+    let content_groups = CssGroupIterator::new(&css_path.selectors).collect::<Vec<_>>();
+    content_groups_match(&content_groups, node_id, node_hierarchy, html_node_tree)
+}
 
-    for (content_group, reason) in CssGroupIterator::new(&css_path.selectors) {
-        let cur_node_id = match current_node {
-            Some(c) => c,
-            None => {
-                // The node has no parent, but the CSS path
-                // still has an extra limitation - only valid if the
-                // next content group is a "*" element
-                return *content_group == [&CssPathSelector::Global];
-            },
-        };
-        let current_selector_matches = selector_group_matches(&content_group, &html_node_tree[cur_node_id]);
+/// Matches the content groups (ordered from right to left) against the node and, depending on
+/// the combinator between the groups, against its parents or preceding siblings.
+///
+/// For ` ` and `~`, all ancestors / preceding siblings are tried until the rest of the path matches.
+fn content_groups_match<'a, T>(
+    content_groups: &[(CssContentGroup, CssGroupSplitReason)],
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    html_node_tree: &NodeDataContainer<HtmlCascadeInfo<'a, T>>)
+-> bool
+{
+    use self::CssGroupSplitReason::*;
 
-        if direct_parent_has_to_match && !current_selector_matches {
-            // If the element was a ">" element and the current,
-            // direct parent does not match, return false
-            return false; // not executed (maybe this is the bug)
-        }
+    let (content_group, reason) = match content_groups.first() {
+        Some(s) => s,
+        None => return true,
+    };
 
-        // If the current selector matches, but the previous one didn't,
-        // that means that the CSS path chain is broken and therefore doesn't match the element
-        if current_selector_matches && !last_selector_matched {
-            return false;
-        }
+    if !selector_group_matches(content_group, &html_node_tree[node_id]) {
+        return false;
+    }
 
-        // Important: Set if the current selector has matched the element
-        last_selector_matched = current_selector_matches;
-        // Select if the next content group has to exactly match or if it can potentially be skipped
-        direct_parent_has_to_match = reason == DirectChildren;
-        current_node = node_hierarchy[cur_node_id].parent;
+    let remaining_groups = &content_groups[1..];
+    if remaining_groups.is_empty() {
+        return true;
     }
 
-    last_selector_matched
+    let rest_matches = |node_id| content_groups_match(remaining_groups, node_id, node_hierarchy, html_node_tree);
+
+    match reason {
+        Children | DirectChildren => {
+            let parent = match node_hierarchy[node_id].parent {
+                Some(p) => p,
+                None => {
+                    // The node has no parent, but the CSS path
+                    // still has an extra limitation - only valid if the
+                    // next content group is a "*" element
+                    return remaining_groups.len() == 1 && remaining_groups[0].0 == [&CssPathSelector::Global];
+                },
+            };
+            if *reason == DirectChildren {
+                rest_matches(parent)
+            } else {
+                parent.ancestors(node_hierarchy).any(rest_matches)
+            }
+        },
+        AdjacentSibling => node_hierarchy[node_id].previous_sibling.map(rest_matches).unwrap_or(false),
+        GeneralSibling => node_id.preceding_siblings(node_hierarchy).skip(1).any(rest_matches),
+    }
 }
 
 struct CssGroupIterator<'a> {
@@ -106,6 +144,8 @@ struct CssGroupIterator<'a> {
 enum CssGroupSplitReason {
     Children,
     DirectChildren,
+    AdjacentSibling,
+    GeneralSibling,
 }
 
 impl<'a> CssGroupIterator<'a> {
@@ -143,6 +183,14 @@ impl<'a> Iterator for CssGroupIterator<'a> {
                     self.last_reason = CssGroupSplitReason::DirectChildren;
                     break;
                 },
+                AdjacentSibling => {
+                    self.last_reason = CssGroupSplitReason::AdjacentSibling;
+                    break;
+                },
+                GeneralSibling => {
+                    self.last_reason = CssGroupSplitReason::GeneralSibling;
+                    break;
+                },
                 other => current_path.push(other),
             }
             new_idx -= 1;
@@ -162,7 +210,7 @@ impl<'a> Iterator for CssGroupIterator<'a> {
                 Some((current_path, self.last_reason))
             }
         } else {
-            // skip the "Children | DirectChildren" element itself
+            // skip the combinator ("Children | DirectChildren | ...") element itself
             self.current_idx = new_idx - 1;
             Some((current_path, self.last_reason))
         }
@@ -178,44 +226,56 @@ fn construct_html_cascade_tree<'a, T>(
     is_mouse_down: bool
 ) -> NodeDataContainer<HtmlCascadeInfo<'a, T>> {
 
-    let mut nodes = (0..node_hierarchy.len()).map(|_| HtmlCascadeInfo {
-        node_data: &input[NodeId::new(0)],
-        index_in_parent: 0,
-        is_last_child: false,
-        is_hovered_over: false,
-        is_active: false,
-        is_focused: false,
+    let mut nodes = (0..node_hierarchy.len()).map(|node_index| {
+        let node_id = NodeId::new(node_index);
+        let is_hovered_over = hovered_items.contains_key(&node_id);
+        let has_text = match &input[node_id].node_type {
+            NodeType::Label(text) => !text.as_str().is_empty(),
+            NodeType::Text(_) => true,
+            _ => false,
+        };
+        HtmlCascadeInfo {
+            node_data: &input[node_id],
+            // Root nodes are the first and last (and only) child, siblings are set below
+            index_in_parent: 1,
+            index_from_end: 1,
+            index_of_type: 1,
+            index_of_type_from_end: 1,
+            is_last_child: true,
+            is_root: node_hierarchy[node_id].parent.is_none(),
+            is_empty: node_hierarchy[node_id].first_child.is_none() && !has_text,
+            is_hovered_over,
+            is_active: is_hovered_over && is_mouse_down,
+            is_focused: focused_item == Some(node_id),
+        }
     }).collect::<Vec<_>>();
 
     for (_depth, parent_id) in node_depths_sorted {
 
+        let children = parent_id.children(node_hierarchy).collect::<Vec<_>>();
+        let child_count = children.len();
+
         // Note: :nth-child() starts at 1 instead of 0
-        let index_in_parent = parent_id.preceding_siblings(node_hierarchy).count();
-
-        let is_parent_hovered_over = hovered_items.contains_key(parent_id);
-        let parent_html_matcher = HtmlCascadeInfo {
-            node_data: &input[*parent_id],
-            index_in_parent: index_in_parent, // necessary for nth-child
-            is_last_child: node_hierarchy[*parent_id].next_sibling.is_none(), // Necessary for :last selectors
-            is_hovered_over: is_parent_hovered_over,
-            is_active: is_parent_hovered_over && is_mouse_down,
-            is_focused: focused_item == Some(*parent_id),
-        };
+        for (child_idx, child_id) in children.iter().enumerate() {
+            let child = &mut nodes[child_id.index()];
+            child.index_in_parent = child_idx + 1;
+            child.index_from_end = child_count - child_idx;
+            child.is_last_child = child_idx + 1 == child_count;
+        }
 
-        nodes[parent_id.index()] = parent_html_matcher;
-
-        for (child_idx, child_id) in parent_id.children(node_hierarchy).enumerate() {
-            let is_child_hovered_over = hovered_items.contains_key(&child_id);
-            let child_html_matcher = HtmlCascadeInfo {
-                node_data: &input[child_id],
-                index_in_parent: child_idx + 1, // necessary for nth-child
-                is_last_child: node_hierarchy[child_id].next_sibling.is_none(),
-                is_hovered_over: is_child_hovered_over,
-                is_active: is_child_hovered_over && is_mouse_down,
-                is_focused: focused_item == Some(child_id),
-            };
+        // Count the siblings of the same type forwards and backwards (for :nth-of-type)
+        let mut type_counts = BTreeMap::<NodeTypePath, usize>::new();
+        for child_id in &children {
+            let count = type_counts.entry(input[*child_id].node_type.get_path()).or_insert(0);
+            *count += 1;
+            nodes[child_id.index()].index_of_type = *count;
+        }
 
-            nodes[child_id.index()] = child_html_matcher;
+        let mut type_counts = BTreeMap::<NodeTypePath, usize>::new();
+        for child_id in children.iter().rev() {
+            let count = type_counts.entry(input[*child_id].node_type.get_path()).or_insert(0);
+            *count += 1;
+            nodes[child_id.index()].index_of_type_from_end = *count;
         }
     }
 
@@ -242,7 +302,7 @@ pub enum ActiveHover {
 
 /// Returns all CSS paths that have a `:hover` or `:active` in their path
 /// (since they need to have tags for hit-testing)
-fn collect_hover_groups(css: &Css) -> BTreeMap<CssPath, HoverGroup> {
+fn collect_hover_groups(css: &Css, media_context: &MediaQueryContext) -> BTreeMap<CssPath, HoverGroup> {
     use azul_css::{CssPathSelector::*, CssPathPseudoSelector::*};
 
     let hover_rule = PseudoSelector(Hover);
@@ -250,9 +310,9 @@ fn collect_hover_groups(css: &Css) -> BTreeMap<CssPath, HoverGroup> {
 
     // Filter out all :hover and :active rules, since we need to create tags
     // for them after the main CSS styling has been done
-    css.rules().filter_map(|rule_block| {
+    css.rules().filter(|rule_block| rule_block.matches_media(media_context)).filter_map(|rule_block| {
         let pos = rule_block.path.selectors.iter().position(|x| *x == hover_rule || *x == active_rule)?;
-        if rule_block.declarations.is_empty() {
+        if rule_block.declarations.is_empty() && rule_block.important_declarations.is_empty() {
             return None;
         }
 
@@ -264,7 +324,9 @@ fn collect_hover_groups(css: &Css) -> BTreeMap<CssPath, HoverGroup> {
 
         let css_path = CssPath { selectors: rule_block.path.selectors.iter().cloned().take(pos).collect() };
         let hover_group = HoverGroup {
-            affects_layout: rule_block.declarations.iter().any(|hover_rule| hover_rule.can_trigger_relayout()),
+            affects_layout: rule_block.declarations.iter()
+                .chain(rule_block.important_declarations.iter())
+                .any(|hover_rule| hover_rule.can_trigger_relayout()),
             active_or_hover,
         };
         Some((css_path, hover_group))
@@ -293,7 +355,7 @@ fn match_hover_selectors<'a, T>(
     btree_map
 }
 
-/// Matches a single group of items, panics on Children or DirectChildren selectors
+/// Matches a single group of items, panics on combinators (Children, DirectChildren, etc.)
 ///
 /// The intent is to "split" the CSS path into groups by selectors, then store and cache
 /// whether the direct or any parent has matched the path correctly
@@ -326,14 +388,41 @@ fn selector_group_matches<'a, T>(selectors: &[&CssPathSelector], html_node: &Htm
                 // Notice: index_in_parent is 1-indexed
                 if !html_node.is_last_child { return false; }
             },
+            PseudoSelector(CssPathPseudoSelector::FirstChild) => {
+                if html_node.index_in_parent != 1 { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::LastChild) => {
+                if !html_node.is_last_child { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::OnlyChild) => {
+                if html_node.index_in_parent != 1 || !html_node.is_last_child { return false; }
+            },
             PseudoSelector(CssPathPseudoSelector::NthChild(x)) => {
-                match *x {
-                    Number(value) => if html_node.index_in_parent != value { return false; },
-                    Even => if html_node.index_in_parent % 2 == 0 { return false; },
-                    Odd => if html_node.index_in_parent % 2 == 1 { return false; },
-                    Pattern { repeat, offset } => if html_node.index_in_parent >= offset &&
-                        ((html_node.index_in_parent - offset) % repeat != 0) { return false; },
-                }
+                if !x.matches(html_node.index_in_parent) { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::NthLastChild(x)) => {
+                if !x.matches(html_node.index_from_end) { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::FirstOfType) => {
+                if html_node.index_of_type != 1 { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::LastOfType) => {
+                if html_node.index_of_type_from_end != 1 { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::OnlyOfType) => {
+                if html_node.index_of_type != 1 || html_node.index_of_type_from_end != 1 { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::NthOfType(x)) => {
+                if !x.matches(html_node.index_of_type) { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::NthLastOfType(x)) => {
+                if !x.matches(html_node.index_of_type_from_end) { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::Empty) => {
+                if !html_node.is_empty { return false; }
+            },
+            PseudoSelector(CssPathPseudoSelector::Root) => {
+                if !html_node.is_root { return false; }
             },
             PseudoSelector(CssPathPseudoSelector::Hover) => {
                 if !html_node.is_hovered_over { return false; }
@@ -344,8 +433,26 @@ fn selector_group_matches<'a, T>(selectors: &[&CssPathSelector], html_node: &Htm
             PseudoSelector(CssPathPseudoSelector::Focus) => {
                 if !html_node.is_focused { return false; }
             },
-            DirectChildren | Children => {
-                panic!("Unreachable: DirectChildren or Children in CSS path!");
+            Attribute(a) => {
+                if !a.matches(html_node.node_data.get_attribute(&a.name)) {
+                    return false;
+                }
+            },
+            Not(path) => {
+                let not_group = path.selectors.iter().collect::<Vec<_>>();
+                if selector_group_matches(&not_group, html_node) {
+                    return false;
+                }
+            },
+            // Pseudo-elements are styled separately (see `match_scrollbar_selectors`),
+            // they never match the node itself
+            PseudoElement(_) |
+            PseudoSelector(CssPathPseudoSelector::Horizontal) |
+            PseudoSelector(CssPathPseudoSelector::Vertical) => {
+                return false;
+            },
+            DirectChildren | Children | AdjacentSibling | GeneralSibling => {
+                panic!("Unreachable: combinator in CSS content group!");
             },
         }
     }
@@ -353,6 +460,330 @@ fn selector_group_matches<'a, T>(selectors: &[&CssPathSelector], html_node: &Htm
     true
 }
 
+/// Splits a path that ends with a scrollbar pseudo-element (`.list::-webkit-scrollbar-thumb:vertical`)
+/// into the path of the node that owns the scrollbar, the pseudo-element and the scrollbar
+/// orientations that the rule applies to.
+///
+/// Returns `None` if the path doesn't contain a pseudo-element or if the pseudo-element is
+/// followed by anything other than a `:horizontal` or `:vertical` selector.
+fn split_scrollbar_path(path: &CssPath) -> Option<(CssPath, CssPathPseudoElement, Vec<ScrollbarOrientation>)> {
+    use azul_css::{CssPathSelector::*, CssPathPseudoSelector::*};
+
+    let pos = path.selectors.iter().position(|selector| match selector {
+        PseudoElement(_) => true,
+        _ => false,
+    })?;
+
+    let pseudo_element = match path.selectors[pos] {
+        PseudoElement(p) => p,
+        _ => return None,
+    };
+
+    let mut orientations = vec![ScrollbarOrientation::Horizontal, ScrollbarOrientation::Vertical];
+
+    for selector in &path.selectors[(pos + 1)..] {
+        let orientation = match selector {
+            PseudoSelector(Horizontal) => ScrollbarOrientation::Horizontal,
+            PseudoSelector(Vertical) => ScrollbarOrientation::Vertical,
+            _ => return None,
+        };
+        orientations.retain(|o| *o == orientation);
+    }
+
+    let node_path = CssPath { selectors: path.selectors[..pos].to_vec() };
+    Some((node_path, pseudo_element, orientations))
+}
+
+/// Collects the declarations of all `::-webkit-scrollbar` rules that match the node,
+/// grouped by scrollbar orientation and pseudo-element. A pseudo-element without a
+/// preceding selector (`::-webkit-scrollbar`) applies to all nodes.
+fn match_scrollbar_selectors<'a, 'b, T>(
+    scrollbar_rules: &[(CssOrigin, &'b CssRuleBlock, CssPath, CssPathPseudoElement, Vec<ScrollbarOrientation>)],
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    html_node_tree: &NodeDataContainer<HtmlCascadeInfo<'a, T>>,
+    inline_overrides: Option<&FastHashMap<DomString, CssProperty>>,
+) -> BTreeMap<(ScrollbarOrientation, CssPathPseudoElement), BTreeMap<CssPropertyType, CssDeclaration>>
+{
+    let mut matched_rules = BTreeMap::<_, Vec<(CssOrigin, &'b CssRuleBlock)>>::new();
+
+    for (origin, rule, node_path, pseudo_element, orientations) in scrollbar_rules {
+        if !node_path.selectors.is_empty() && !matches_html_element(node_path, node_id, node_hierarchy, html_node_tree) {
+            continue;
+        }
+        for orientation in orientations {
+            matched_rules.entry((*orientation, *pseudo_element)).or_insert_with(|| Vec::new()).push((*origin, *rule));
+        }
+    }
+
+    matched_rules.into_iter().map(|(key, mut rules)| {
+        sort_by_cascade_order(&mut rules);
+        (key, cascade_declarations(&rules, inline_overrides))
+    }).collect()
+}
+
+/// Resolves the custom properties (`--my-var: value`) of every node and substitutes
+/// them into all `CssDeclaration::Unresolved` declarations of that node.
+///
+/// Custom properties are always inherited, so the nodes have to be visited in depth order.
+/// Declarations that can't be resolved (undefined variable without fallback, cyclic
+/// variables or a value that doesn't parse after substitution) are removed from the node.
+///
+/// Only the nodes marked in `needs_restyle` are resolved, all other nodes keep the
+/// `computed_properties` of the last frame.
+fn resolve_css_variables(
+    matched_rules: &NodeDataContainer<Vec<(CssOrigin, &CssRuleBlock)>>,
+    non_leaf_nodes: &[(usize, NodeId)],
+    node_hierarchy: &NodeHierarchy,
+    needs_restyle: &[bool],
+    computed_properties: &mut NodeDataContainer<BTreeMap<String, String>>,
+    styled_nodes: &mut NodeDataContainer<StyledNode>,
+) {
+    // Custom properties declared directly on each node, later rules override earlier ones
+    let declared_properties = matched_rules.transform(|rules, _| {
+        let mut declared = BTreeMap::new();
+        for (_, rule) in rules {
+            for custom_property in &rule.custom_properties {
+                declared.insert(custom_property.name.clone(), custom_property.value.clone());
+            }
+        }
+        declared
+    });
+
+    if node_hierarchy.len() == 0 {
+        return;
+    }
+
+    // The root node always comes first, then all children of each parent in depth order
+    let root_node = NodeId::new(0);
+    let nodes_in_depth_order = ::std::iter::once(root_node)
+        .chain(non_leaf_nodes.iter().flat_map(|(_, parent_id)| parent_id.children(node_hierarchy)))
+        .collect::<Vec<NodeId>>();
+
+    for node_id in nodes_in_depth_order.into_iter().filter(|node_id| needs_restyle[node_id.index()]) {
+
+        let inherited = match node_hierarchy[node_id].parent {
+            Some(parent_id) => computed_properties[parent_id].clone(),
+            None => BTreeMap::new(),
+        };
+
+        let declared = &declared_properties[node_id];
+        let mut computed = inherited.clone();
+
+        for name in declared.keys() {
+            match resolve_custom_property(name, declared, &inherited, &mut Vec::new()) {
+                Some(value) => { computed.insert(name.clone(), value); },
+                None => { computed.remove(name); },
+            }
+        }
+
+        let css_constraints = &mut styled_nodes[node_id].css_constraints;
+
+        let unresolved_properties = css_constraints.iter().filter_map(|(property_type, declaration)| match declaration {
+            CssDeclaration::Unresolved(u) => Some((*property_type, u.value.clone())),
+            _ => None,
+        }).collect::<Vec<(CssPropertyType, String)>>();
+
+        for (property_type, value) in unresolved_properties {
+            let parsed = substitute_css_variables(&value, &mut |name| computed.get(name).cloned())
+                .and_then(|substituted| parse_css_declaration(property_type, &substituted));
+            match parsed {
+                Some(declaration) => { css_constraints.insert(property_type, declaration); },
+                None => { css_constraints.remove(&property_type); },
+            }
+        }
+
+        computed_properties[node_id] = computed;
+    }
+}
+
+/// Resolves the value of a single custom property declared on a node, following
+/// references to other custom properties. `resolving` is used to detect cycles,
+/// such as `--a: var(--b); --b: var(--a);`, which make both properties invalid.
+fn resolve_custom_property(
+    name: &str,
+    declared: &BTreeMap<String, String>,
+    inherited: &BTreeMap<String, String>,
+    resolving: &mut Vec<String>,
+) -> Option<String> {
+    let raw_value = match declared.get(name) {
+        Some(s) => s,
+        None => return inherited.get(name).cloned(),
+    };
+
+    if resolving.iter().any(|n| n == name) {
+        return None;
+    }
+
+    resolving.push(name.to_string());
+    let resolved = substitute_css_variables(raw_value, &mut |n| resolve_custom_property(n, declared, inherited, resolving));
+    resolving.pop();
+    resolved
+}
+
+#[cfg(feature = "css_parser")]
+fn parse_css_declaration(property_type: CssPropertyType, value: &str) -> Option<CssDeclaration> {
+    ::azul_css_parser::parse_css_declaration(property_type, value).ok()
+}
+
+/// Without the `css_parser` feature, the value of a `var()` can't be parsed after the
+/// substitution, so declarations that use custom properties are dropped with an error.
+#[cfg(not(feature = "css_parser"))]
+fn parse_css_declaration(_property_type: CssPropertyType, _value: &str) -> Option<CssDeclaration> {
+    #[cfg(feature = "logging")] {
+        error!(
+            "Cannot resolve \"{}: {}\": custom properties (var()) require the \"css_parser\" feature",
+            _property_type, _value
+        );
+    }
+    None
+}
+
+/// Resolves the CSS-wide keywords (`inherit`, `initial`, `unset`) of a node: `inherit` copies the
+/// declaration of the parent, `initial` removes the declaration, so that the default value is used.
+///
+/// Returns the properties that were reset with `initial`, these must not be inherited from the parent.
+fn resolve_css_keywords(
+    css_constraints: &mut BTreeMap<CssPropertyType, CssDeclaration>,
+    parent_constraints: Option<&BTreeMap<CssPropertyType, CssDeclaration>>,
+) -> Vec<CssPropertyType> {
+    use azul_css::CssWideKeyword::*;
+
+    let keywords = css_constraints.iter().filter_map(|(property_type, declaration)| match declaration {
+        CssDeclaration::Keyword(k) => Some((*property_type, *k)),
+        _ => None,
+    }).collect::<Vec<_>>();
+
+    let mut reset_properties = Vec::new();
+
+    for (property_type, keyword_property) in keywords {
+        let inherits = match keyword_property.keyword {
+            Inherit => true,
+            Initial => false,
+            Unset => keyword_property.property_type.is_inheritable(),
+        };
+
+        match parent_constraints.and_then(|parent| parent.get(&property_type)) {
+            Some(parent_declaration) if inherits => {
+                css_constraints.insert(property_type, parent_declaration.clone());
+            },
+            _ => {
+                css_constraints.remove(&property_type);
+                if !inherits {
+                    reset_properties.push(property_type);
+                }
+            },
+        }
+    }
+
+    reset_properties
+}
+
+/// Inserts a declaration into the declarations of a node, overriding the previous declaration
+/// of the same type. Static side properties (`border-top`, `padding-left`, etc.) are merged
+/// with the previous value instead, so that `border-top` followed by `border-left` sets both sides.
+fn insert_css_declaration(css_constraints: &mut BTreeMap<CssPropertyType, CssDeclaration>, declaration: &CssDeclaration) {
+    match (css_constraints.get_mut(&declaration.get_type()), declaration) {
+        (Some(CssDeclaration::Static(existing)), CssDeclaration::Static(new)) => existing.merge(new),
+        _ => { css_constraints.insert(declaration.get_type(), declaration.clone()); },
+    }
+}
+
+/// Level of a declaration in the cascade, declarations of a higher level override declarations
+/// of a lower level, regardless of their specificity. Within the same level, the more specific
+/// declaration wins, within the same specificity the declaration that comes last in the source.
+///
+/// See the [W3C cascade order](https://www.w3.org/TR/css-cascade-3/#cascade-origin) for more information.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum CascadeLevel {
+    /// Normal declarations of the native / default style
+    UserAgentNormal,
+    /// Normal declarations of the application style
+    AuthorNormal,
+    /// Dynamic declarations (`[[ id | default ]]`) overridden on the node via `with_css_override`
+    Inline,
+    /// `!important` declarations of the application style
+    AuthorImportant,
+    /// `!important` declarations of the native / default style
+    UserAgentImportant,
+}
+
+impl CascadeLevel {
+    fn new(origin: CssOrigin, is_important: bool) -> Self {
+        match (origin, is_important) {
+            (CssOrigin::UserAgent, false) => CascadeLevel::UserAgentNormal,
+            (CssOrigin::Author, false) => CascadeLevel::AuthorNormal,
+            (CssOrigin::Author, true) => CascadeLevel::AuthorImportant,
+            (CssOrigin::UserAgent, true) => CascadeLevel::UserAgentImportant,
+        }
+    }
+}
+
+/// Sorts the matched rules of a node by origin and specificity. The sort is stable,
+/// so that rules of the same origin and specificity stay in source order.
+fn sort_by_cascade_order(matched_rules: &mut Vec<(CssOrigin, &CssRuleBlock)>) {
+    matched_rules.sort_by_key(|(origin, rule)| (*origin, rule.path.get_specificity()));
+}
+
+/// Returns whether the declaration is a dynamic declaration that is overridden on the node itself
+fn is_inline_override(declaration: &CssDeclaration, inline_overrides: Option<&FastHashMap<DomString, CssProperty>>) -> bool {
+    match (declaration, inline_overrides) {
+        (CssDeclaration::Dynamic(dynamic), Some(overrides)) => overrides.contains_key(&DomString::Heap(dynamic.dynamic_id.clone())),
+        _ => false,
+    }
+}
+
+/// Cascades the declarations of the matched rules (sorted via `sort_by_cascade_order`)
+/// into the final declarations of a node, see `CascadeLevel` for the order.
+fn cascade_declarations(
+    matched_rules: &[(CssOrigin, &CssRuleBlock)],
+    inline_overrides: Option<&FastHashMap<DomString, CssProperty>>,
+) -> BTreeMap<CssPropertyType, CssDeclaration> {
+
+    let mut declarations = matched_rules.iter().flat_map(|(origin, rule)| {
+        let normal = rule.declarations.iter().map(move |declaration| {
+            if is_inline_override(declaration, inline_overrides) {
+                (CascadeLevel::Inline, declaration)
+            } else {
+                (CascadeLevel::new(*origin, false), declaration)
+            }
+        });
+        let important = rule.important_declarations.iter().map(move |declaration| (CascadeLevel::new(*origin, true), declaration));
+        normal.chain(important)
+    }).collect::<Vec<_>>();
+
+    // Stable sort, the rules are already sorted by specificity and source order
+    declarations.sort_by_key(|(level, _)| *level);
+
+    let mut css_constraints = BTreeMap::new();
+    for (_, declaration) in declarations {
+        insert_css_declaration(&mut css_constraints, declaration);
+    }
+    css_constraints
+}
+
+/// The `:hover`, `:focus` and `:active` state of a node, these states can change
+/// without the DOM changing, so they have to be compared between two frames
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PseudoClasses {
+    pub(crate) is_hovered_over: bool,
+    pub(crate) is_focused: bool,
+    pub(crate) is_active: bool,
+}
+
+/// Intermediate results of the styling step, so that the next
+/// frame only needs to restyle the nodes that actually changed
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StyleCache {
+    /// The `:hover`, `:focus` and `:active` state of each node
+    pub(crate) pseudo_classes: NodeDataContainer<PseudoClasses>,
+    /// The computed custom properties (`--my-var`) of each node, including the inherited ones
+    pub(crate) custom_properties: NodeDataContainer<BTreeMap<String, String>>,
+    /// The state of the window that the `@media` rules were evaluated against
+    pub(crate) media_context: MediaQueryContext,
+}
+
+/// Styles the entire DOM
 pub(crate) fn match_dom_selectors<T>(
     ui_state: &UiState<T>,
     css: &Css,
@@ -360,15 +791,48 @@ pub(crate) fn match_dom_selectors<T>(
     pending_focus_target: &mut Option<FocusTarget>,
     hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
     is_mouse_down: bool,
+    media_context: &MediaQueryContext,
 ) -> UiDescription<T> {
+    restyle_dom_selectors(
+        ui_state,
+        None,
+        &mut DomDiff::default(),
+        css,
+        focused_node,
+        pending_focus_target,
+        hovered_nodes,
+        is_mouse_down,
+        media_context,
+    )
+}
 
-    use azul_css::CssDeclaration;
+/// Styles the DOM, but only restyles the nodes that are in the `need_restyling` ranges of
+/// the `dom_diff` or whose `:hover`, `:focus` or `:active` state changed, all other nodes
+/// re-use the style of the `previous` frame.
+///
+/// The `previous` frame has to be styled with the same `css`. If there is no previous frame
+/// (or it can't be re-used, because the media context changed), the entire DOM is restyled.
+///
+/// Afterwards, all restyled nodes whose layout-relevant properties changed are added to
+/// the `need_relayout` ranges of the `dom_diff`.
+pub(crate) fn restyle_dom_selectors<T>(
+    ui_state: &UiState<T>,
+    previous: Option<&UiDescription<T>>,
+    dom_diff: &mut DomDiff,
+    css: &Css,
+    focused_node: &mut Option<NodeId>,
+    pending_focus_target: &mut Option<FocusTarget>,
+    hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
+    is_mouse_down: bool,
+    media_context: &MediaQueryContext,
+) -> UiDescription<T> {
 
-    let non_leaf_nodes = ui_state.dom.arena.node_layout.get_parents_sorted_by_depth();
+    let node_hierarchy = &ui_state.dom.arena.node_layout;
+    let non_leaf_nodes = node_hierarchy.get_parents_sorted_by_depth();
 
     let mut html_tree = construct_html_cascade_tree(
         &ui_state.dom.arena.node_data,
-        &ui_state.dom.arena.node_layout,
+        node_hierarchy,
         &non_leaf_nodes,
         *focused_node,
         hovered_nodes,
@@ -380,34 +844,143 @@ pub(crate) fn match_dom_selectors<T>(
     update_focus_from_callbacks(
         pending_focus_target,
         focused_node,
-        &ui_state.dom.arena.node_layout,
+        node_hierarchy,
         &mut html_tree,
     );
 
+    let pseudo_classes = html_tree.transform(|html_node, _| PseudoClasses {
+        is_hovered_over: html_node.is_hovered_over,
+        is_focused: html_node.is_focused,
+        is_active: html_node.is_active,
+    });
+
+    let previous = previous.and_then(|previous| {
+        let style_cache = previous.style_cache.as_ref()?;
+        if style_cache.media_context != *media_context || previous.styled_nodes.len() != node_hierarchy.len() {
+            None
+        } else {
+            Some((previous, style_cache))
+        }
+    });
+
+    match previous {
+        Some((_, style_cache)) => {
+            // Nodes that changed their :hover, :focus or :active state can affect
+            // their children (`.a:hover .b`) and following siblings (`.a:hover + .b`)
+            for node_id in node_hierarchy.linear_iter() {
+                if pseudo_classes[node_id] != style_cache.pseudo_classes[node_id] {
+                    dom_diff.need_restyling.push(DomRange::subtree_and_following_siblings(node_id, node_hierarchy));
+                }
+            }
+        },
+        None => dom_diff.invalidate_all(node_hierarchy),
+    }
+
+    let needs_restyle = dom_diff.get_restyle_mask(node_hierarchy.len());
+
     // First, apply all rules normally (no inheritance) of CSS values
     // This is an O(n^2) operation, but it can be parallelized in the future
-    let mut styled_nodes = ui_state.dom.arena.node_data.transform(|_, node_id| StyledNode {
-        css_constraints: css
-            .rules()
-            .filter(|rule| matches_html_element(&rule.path, node_id, &ui_state.dom.arena.node_layout, &html_tree))
-            .flat_map(|matched_rule| matched_rule.declarations.iter().map(|declaration| (declaration.get_type(), declaration.clone())))
-            .collect(),
+    let rules_with_origin = || css.stylesheets.iter()
+        .flat_map(|stylesheet| stylesheet.rules.iter().map(move |rule| (stylesheet.origin, rule)))
+        .filter(move |(_, rule)| rule.matches_media(media_context));
+
+    let matched_rules = ui_state.dom.arena.node_data.transform(|_, node_id| {
+        if !needs_restyle[node_id.index()] {
+            return Vec::new();
+        }
+        let mut rules = rules_with_origin()
+            .filter(|(_, rule)| matches_html_element(&rule.path, node_id, node_hierarchy, &html_tree))
+            .collect::<Vec<(CssOrigin, &CssRuleBlock)>>();
+        sort_by_cascade_order(&mut rules);
+        rules
+    });
+
+    let scrollbar_rules = rules_with_origin()
+        .filter_map(|(origin, rule)| {
+            let (node_path, pseudo_element, orientations) = split_scrollbar_path(&rule.path)?;
+            Some((origin, rule, node_path, pseudo_element, orientations))
+        })
+        .collect::<Vec<_>>();
+
+    let mut styled_nodes = matched_rules.transform(|rules, node_id| {
+        if let Some((previous, _)) = previous {
+            if !needs_restyle[node_id.index()] {
+                return previous.styled_nodes[node_id].clone();
+            }
+        }
+        let inline_overrides = ui_state.dynamic_css_overrides.get(&node_id);
+        let css_constraints = cascade_declarations(rules, inline_overrides);
+        let scrollbar_constraints = match_scrollbar_selectors(
+            &scrollbar_rules,
+            node_id,
+            node_hierarchy,
+            &html_tree,
+            inline_overrides,
+        );
+        StyledNode { css_constraints, scrollbar_constraints, inherited_properties: BTreeSet::new() }
     });
 
+    let mut custom_properties = match previous {
+        Some((_, style_cache)) => style_cache.custom_properties.clone(),
+        None => matched_rules.transform(|_, _| BTreeMap::new()),
+    };
+
+    // Substitute the var() references, this has to happen before the inheritance step,
+    // since children inherit the computed (substituted) value, not the var() reference
+    resolve_css_variables(&matched_rules, &non_leaf_nodes, node_hierarchy, &needs_restyle, &mut custom_properties, &mut styled_nodes);
+
+    // The root node has no parent, so `inherit` behaves like `initial`
+    if node_hierarchy.len() > 0 && needs_restyle[0] {
+        resolve_css_keywords(&mut styled_nodes[NodeId::new(0)].css_constraints, None);
+    }
+
     // Then, inherit all values of the parent to the children, but only if the property is
     // inheritable and isn't yet set. NOTE: This step can't be parallelized!
     for (_depth, parent_id) in non_leaf_nodes {
 
-        let inherited_rules: Vec<CssDeclaration> = styled_nodes[parent_id].css_constraints.values().filter(|prop| prop.is_inheritable()).cloned().collect();
-        if inherited_rules.is_empty() {
+        let restyled_children = parent_id.children(node_hierarchy)
+            .filter(|child_id| needs_restyle[child_id.index()])
+            .collect::<Vec<NodeId>>();
+
+        if restyled_children.is_empty() {
             continue;
         }
 
-        for child_id in parent_id.children(&ui_state.dom.arena.node_layout) {
+        let parent_constraints = styled_nodes[parent_id].css_constraints.clone();
+        let inherited_rules: Vec<&CssDeclaration> = parent_constraints.values().filter(|prop| prop.is_inheritable()).collect();
+
+        for child_id in restyled_children {
+            let StyledNode { css_constraints: child_constraints, inherited_properties, .. } = &mut styled_nodes[child_id];
+
+            let keyword_properties = child_constraints.iter()
+                .filter(|(_, declaration)| match declaration { CssDeclaration::Keyword(_) => true, _ => false })
+                .map(|(property_type, _)| *property_type)
+                .collect::<Vec<CssPropertyType>>();
+
+            // Resolve `inherit` / `initial` / `unset` before inheriting, the parent is already resolved
+            let reset_properties = resolve_css_keywords(child_constraints, Some(&parent_constraints));
+
+            // Keywords that weren't removed copied the declaration of the parent
+            inherited_properties.extend(keyword_properties.into_iter().filter(|property_type| child_constraints.contains_key(property_type)));
+
             for inherited_rule in &inherited_rules {
                 // Only override the rule if the child already has an inherited rule, don't override it
                 let inherited_rule_type = inherited_rule.get_type();
-                styled_nodes[child_id].css_constraints.entry(inherited_rule_type).or_insert_with(|| inherited_rule.clone());
+                if reset_properties.contains(&inherited_rule_type) || child_constraints.contains_key(&inherited_rule_type) {
+                    continue;
+                }
+                child_constraints.insert(inherited_rule_type, (*inherited_rule).clone());
+                inherited_properties.insert(inherited_rule_type);
+            }
+        }
+    }
+
+    // Only properties such as `width` or `font-size` need a relayout, if only the
+    // `color` of a node changed, the layout of the last frame can be re-used
+    if let Some((previous, _)) = previous {
+        for node_id in node_hierarchy.linear_iter().filter(|node_id| needs_restyle[node_id.index()]) {
+            if layout_properties_changed(&previous.styled_nodes[node_id], &styled_nodes[node_id]) {
+                dom_diff.need_relayout.push(DomRange::single_node(node_id));
             }
         }
     }
@@ -415,8 +988,8 @@ pub(crate) fn match_dom_selectors<T>(
     // In order to hit-test :hover and :active nodes, need to select them
     // first (to insert their TagId later)
     let selected_hover_nodes = match_hover_selectors(
-        collect_hover_groups(css),
-        &ui_state.dom.arena.node_layout,
+        collect_hover_groups(css, media_context),
+        node_hierarchy,
         &html_tree,
     );
 
@@ -437,9 +1010,22 @@ pub(crate) fn match_dom_selectors<T>(
         ui_descr_root: ui_state.dom.root,
         styled_nodes,
         selected_hover_nodes,
+        style_cache: Some(StyleCache {
+            pseudo_classes,
+            custom_properties,
+            media_context: *media_context,
+        }),
     }
 }
 
+/// Returns whether any property that can trigger a relayout was added, removed or changed
+fn layout_properties_changed(old: &StyledNode, new: &StyledNode) -> bool {
+    let changed = |a: &BTreeMap<CssPropertyType, CssDeclaration>, b: &BTreeMap<CssPropertyType, CssDeclaration>| {
+        a.iter().any(|(property_type, declaration)| property_type.can_trigger_relayout() && b.get(property_type) != Some(declaration))
+    };
+    changed(&old.css_constraints, &new.css_constraints) || changed(&new.css_constraints, &old.css_constraints)
+}
+
 /// Update the WindowStates focus node in case the previous
 /// frames callbacks set the focus to a specific node
 ///
@@ -567,6 +1153,111 @@ fn test_case_issue_93() {
     assert_eq!(matches_html_element(&tab_active_close, NodeId::new(4), &node_hierarchy, &html_node_tree), true);
 }
 
+#[test]
+fn test_sibling_and_attribute_selectors() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div().with_class("a"))
+        .with_child(Dom::div().with_class("b").with_attribute("disabled", "true"))
+        .with_child(Dom::div().with_class("b").with_attribute("lang", "en-US"));
+
+    //  0: [div]
+    //   |-- 1: [div .a]
+    //   |-- 2: [div .b disabled="true"]
+    //   |-- 3: [div .b lang="en-US"]
+
+    let node_hierarchy = &dom.arena.node_layout;
+    let nodes_sorted = node_hierarchy.get_parents_sorted_by_depth();
+    let html_node_tree = construct_html_cascade_tree(
+        &dom.arena.node_data,
+        &node_hierarchy,
+        &nodes_sorted,
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+
+    let matches = |selectors: Vec<CssPathSelector>, node_id: usize| {
+        matches_html_element(&CssPath { selectors }, NodeId::new(node_id), &node_hierarchy, &html_node_tree)
+    };
+
+    let disabled = || Attribute(CssAttributeSelector { name: "disabled".into(), matcher: CssAttributeMatcher::Exists });
+
+    // ".a + .b"
+    assert!(matches(vec![Class("a".into()), AdjacentSibling, Class("b".into())], 2));
+    assert!(!matches(vec![Class("a".into()), AdjacentSibling, Class("b".into())], 3));
+    // ".a ~ .b"
+    assert!(matches(vec![Class("a".into()), GeneralSibling, Class("b".into())], 3));
+    assert!(!matches(vec![Class("b".into()), GeneralSibling, Class("a".into())], 1));
+    // ".b[disabled]", ".b:not([disabled])"
+    assert!(matches(vec![Class("b".into()), disabled()], 2));
+    assert!(!matches(vec![Class("b".into()), disabled()], 3));
+    assert!(matches(vec![Class("b".into()), Not(CssPath { selectors: vec![disabled()] })], 3));
+    // "[lang|=en]"
+    assert!(matches(vec![Attribute(CssAttributeSelector { name: "lang".into(), matcher: CssAttributeMatcher::DashMatch("en".into()) })], 3));
+}
+
+#[test]
+fn test_structural_pseudo_selectors() {
+
+    use azul_css::CssPathSelector::*;
+    use azul_css::CssPathPseudoSelector::*;
+    use azul_css::*;
+    use prelude::*;
+
+    struct DataModel;
+
+    let dom: Dom<DataModel> = Dom::div()
+        .with_child(Dom::div())
+        .with_child(Dom::label("x"))
+        .with_child(Dom::div())
+        .with_child(Dom::label(""))
+        .with_child(Dom::div().with_child(Dom::div()));
+
+    //  0: [div]
+    //   |-- 1: [div]
+    //   |-- 2: [p "x"]
+    //   |-- 3: [div]
+    //   |-- 4: [p ""]
+    //   |-- 5: [div]
+    //   |    |-- 6: [div]
+
+    let node_hierarchy = &dom.arena.node_layout;
+    let nodes_sorted = node_hierarchy.get_parents_sorted_by_depth();
+    let html_node_tree = construct_html_cascade_tree(
+        &dom.arena.node_data,
+        &node_hierarchy,
+        &nodes_sorted,
+        None,
+        &BTreeMap::new(),
+        false,
+    );
+
+    let matching_nodes = |selectors: Vec<CssPathSelector>| {
+        let path = CssPath { selectors };
+        (0..dom.arena.len()).filter(|i| matches_html_element(&path, NodeId::new(*i), &node_hierarchy, &html_node_tree)).collect::<Vec<_>>()
+    };
+
+    // The root node counts as the first and only child (same as in browsers)
+    assert_eq!(matching_nodes(vec![PseudoSelector(Root)]), vec![0]);
+    assert_eq!(matching_nodes(vec![PseudoSelector(FirstChild)]), vec![0, 1, 6]);
+    assert_eq!(matching_nodes(vec![PseudoSelector(LastChild)]), vec![0, 5, 6]);
+    assert_eq!(matching_nodes(vec![PseudoSelector(OnlyChild)]), vec![0, 6]);
+    assert_eq!(matching_nodes(vec![PseudoSelector(NthChild(CssNthChildSelector::Pattern { repeat: -1, offset: 2 }))]), vec![0, 1, 2, 6]);
+    assert_eq!(matching_nodes(vec![PseudoSelector(NthLastChild(CssNthChildSelector::Odd))]), vec![0, 1, 3, 5, 6]);
+    assert_eq!(matching_nodes(vec![Type(NodeTypePath::Div), PseudoSelector(NthOfType(CssNthChildSelector::Number(2)))]), vec![3]);
+    assert_eq!(matching_nodes(vec![Type(NodeTypePath::P), PseudoSelector(FirstOfType)]), vec![2]);
+    assert_eq!(matching_nodes(vec![Type(NodeTypePath::P), PseudoSelector(LastOfType)]), vec![4]);
+    assert_eq!(matching_nodes(vec![PseudoSelector(OnlyOfType)]), vec![0, 6]);
+    assert_eq!(matching_nodes(vec![PseudoSelector(Empty)]), vec![1, 3, 4, 6]);
+}
+
 #[test]
 fn test_css_group_iterator() {
 
@@ -615,4 +1306,285 @@ fn test_css_group_iterator() {
     ], CssGroupSplitReason::Children)));
 
     assert_eq!(it.next(), None);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_resolve_custom_property() {
+
+    let mut inherited = BTreeMap::new();
+    inherited.insert("--main-color".to_string(), "#FF0000".to_string());
+
+    let mut declared = BTreeMap::new();
+    declared.insert("--border".to_string(), "1px solid var(--main-color)".to_string());
+    declared.insert("--a".to_string(), "var(--b)".to_string());
+    declared.insert("--b".to_string(), "var(--a)".to_string());
+    declared.insert("--c".to_string(), "var(--a, 5px)".to_string());
+
+    let resolve = |name: &str| resolve_custom_property(name, &declared, &inherited, &mut Vec::new());
+
+    assert_eq!(resolve("--border"), Some("1px solid #FF0000".to_string()));
+    assert_eq!(resolve("--main-color"), Some("#FF0000".to_string()));
+    assert_eq!(resolve("--undefined"), None);
+
+    // Cyclic properties are invalid, but a fallback can still be used
+    assert_eq!(resolve("--a"), None);
+    assert_eq!(resolve("--b"), None);
+    assert_eq!(resolve("--c"), Some("5px".to_string()));
+}
+
+#[test]
+fn test_insert_css_declaration_merges_sides() {
+    use azul_css::{StyleBorder, StyleBorderSide, BorderStyle, PixelValue, ColorU};
+
+    let side = |border_style| StyleBorderSide {
+        border_width: PixelValue::px(1.0),
+        border_style,
+        border_color: ColorU { r: 0, g: 0, b: 0, a: 255 },
+    };
+
+    let mut css_constraints = BTreeMap::new();
+    insert_css_declaration(&mut css_constraints, &CssDeclaration::Static(StyleBorder { top: Some(side(BorderStyle::Dashed)), .. Default::default() }.into()));
+    insert_css_declaration(&mut css_constraints, &CssDeclaration::Static(StyleBorder { left: Some(side(BorderStyle::Dotted)), .. Default::default() }.into()));
+
+    assert_eq!(css_constraints.get(&CssPropertyType::Border), Some(&CssDeclaration::Static(StyleBorder {
+        top: Some(side(BorderStyle::Dashed)),
+        left: Some(side(BorderStyle::Dotted)),
+        .. Default::default()
+    }.into())));
+}
+
+#[test]
+fn test_resolve_css_keywords() {
+    use azul_css::{CssKeywordProperty, CssWideKeyword, StyleTextColor, ColorU, LayoutWidth, PixelValue};
+
+    let keyword = |property_type, keyword| CssDeclaration::Keyword(CssKeywordProperty { property_type, keyword });
+    let red = CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(ColorU { r: 255, g: 0, b: 0, a: 255 })));
+    let width = CssDeclaration::Static(CssProperty::Width(LayoutWidth::Exact(PixelValue::px(10.0))));
+
+    let mut parent_constraints = BTreeMap::new();
+    parent_constraints.insert(CssPropertyType::TextColor, red.clone());
+    parent_constraints.insert(CssPropertyType::Width, width.clone());
+
+    let mut css_constraints = BTreeMap::new();
+    for (property_type, k) in vec![
+        (CssPropertyType::TextColor, CssWideKeyword::Initial),
+        (CssPropertyType::Width, CssWideKeyword::Inherit),
+        (CssPropertyType::FontSize, CssWideKeyword::Unset),
+        (CssPropertyType::Height, CssWideKeyword::Unset),
+    ] {
+        css_constraints.insert(property_type, keyword(property_type, k));
+    }
+
+    let mut root_constraints = css_constraints.clone();
+
+    // `initial` and `unset` on a non-inherited property block the inheritance of the parent value
+    assert_eq!(resolve_css_keywords(&mut css_constraints, Some(&parent_constraints)), vec![CssPropertyType::TextColor, CssPropertyType::Height]);
+    assert_eq!(css_constraints.into_iter().collect::<Vec<_>>(), vec![(CssPropertyType::Width, width)]);
+
+    // Without a parent, every keyword removes the declaration
+    resolve_css_keywords(&mut root_constraints, None);
+    assert!(root_constraints.is_empty());
+}
+
+#[test]
+fn test_cascade_order() {
+    use azul_css::{CssPathSelector::*, StyleTextColor, ColorU, DynamicCssProperty, DynamicCssPropertyDefault};
+    use azul_css::CssOrigin::*;
+
+    const RED: ColorU = ColorU { r: 255, g: 0, b: 0, a: 255 };
+    const BLUE: ColorU = ColorU { r: 0, g: 0, b: 255, a: 255 };
+
+    let color = |c| CssDeclaration::Static(CssProperty::TextColor(StyleTextColor(c)));
+    let dynamic = |id: &str| CssDeclaration::Dynamic(DynamicCssProperty {
+        property_type: CssPropertyType::TextColor,
+        dynamic_id: id.into(),
+        default: DynamicCssPropertyDefault::Exact(CssProperty::TextColor(StyleTextColor(RED))),
+    });
+
+    // (origin, selector, declaration, is_important)
+    let rule = |origin, selector: CssPathSelector, declaration: CssDeclaration, is_important: bool| {
+        let (declarations, important_declarations) = if is_important {
+            (Vec::new(), vec![declaration])
+        } else {
+            (vec![declaration], Vec::new())
+        };
+        (origin, CssRuleBlock {
+            path: CssPath { selectors: vec![selector] },
+            declarations,
+            important_declarations,
+            custom_properties: Vec::new(),
+            media_queries: Vec::new(),
+        })
+    };
+
+    let mut inline_overrides = FastHashMap::default();
+    inline_overrides.insert(DomString::Static("overridden"), CssProperty::TextColor(StyleTextColor(BLUE)));
+
+    let div = || Type(NodeTypePath::Div);
+    let class = |c: &str| Class(c.into());
+    let id = |i: &str| Id(i.into());
+
+    let cases = vec![
+        ("author overrides user agent", vec![
+            rule(UserAgent, class("a"), color(RED), false),
+            rule(Author, div(), color(BLUE), false),
+        ], color(BLUE)),
+        ("higher specificity wins", vec![
+            rule(Author, class("a"), color(RED), false),
+            rule(Author, div(), color(BLUE), false),
+        ], color(RED)),
+        ("source order breaks ties", vec![
+            rule(Author, class("a"), color(RED), false),
+            rule(Author, class("b"), color(BLUE), false),
+        ], color(BLUE)),
+        ("important beats specificity", vec![
+            rule(Author, div(), color(BLUE), true),
+            rule(Author, id("a"), color(RED), false),
+        ], color(BLUE)),
+        ("important source order", vec![
+            rule(Author, class("a"), color(RED), true),
+            rule(Author, class("b"), color(BLUE), true),
+        ], color(BLUE)),
+        ("user agent important beats author important", vec![
+            rule(UserAgent, div(), color(RED), true),
+            rule(Author, id("a"), color(BLUE), true),
+        ], color(RED)),
+        ("user agent important beats author normal", vec![
+            rule(UserAgent, div(), color(RED), true),
+            rule(Author, id("a"), color(BLUE), false),
+        ], color(RED)),
+        ("inline override beats specificity", vec![
+            rule(Author, div(), dynamic("overridden"), false),
+            rule(Author, id("a"), color(RED), false),
+        ], dynamic("overridden")),
+        ("dynamic property without override is a normal declaration", vec![
+            rule(Author, div(), dynamic("not_overridden"), false),
+            rule(Author, id("a"), color(RED), false),
+        ], color(RED)),
+        ("important beats inline override", vec![
+            rule(Author, div(), color(RED), true),
+            rule(Author, id("a"), dynamic("overridden"), false),
+        ], color(RED)),
+    ];
+
+    for (name, rules, expected) in cases {
+        let mut matched_rules = rules.iter().map(|(origin, rule)| (*origin, rule)).collect::<Vec<_>>();
+        sort_by_cascade_order(&mut matched_rules);
+        let css_constraints = cascade_declarations(&matched_rules, Some(&inline_overrides));
+        assert_eq!(css_constraints.get(&CssPropertyType::TextColor), Some(&expected), "{}", name);
+    }
+}
+
+#[test]
+fn test_split_scrollbar_path() {
+    use azul_css::{CssPathSelector::*, CssPathPseudoSelector::*};
+
+    let path = CssPath { selectors: vec![
+        Class("list".into()),
+        PseudoElement(CssPathPseudoElement::ScrollbarThumb),
+        PseudoSelector(Vertical),
+    ]};
+
+    assert_eq!(split_scrollbar_path(&path), Some((
+        CssPath { selectors: vec![Class("list".into())] },
+        CssPathPseudoElement::ScrollbarThumb,
+        vec![ScrollbarOrientation::Vertical],
+    )));
+
+    // Without an orientation, the rule applies to both scrollbars
+    let path = CssPath { selectors: vec![PseudoElement(CssPathPseudoElement::Scrollbar)] };
+    assert_eq!(split_scrollbar_path(&path), Some((
+        CssPath { selectors: Vec::new() },
+        CssPathPseudoElement::Scrollbar,
+        vec![ScrollbarOrientation::Horizontal, ScrollbarOrientation::Vertical],
+    )));
+
+    // Normal paths and unsupported pseudo-classes on the pseudo-element are ignored
+    assert_eq!(split_scrollbar_path(&CssPath { selectors: vec![Class("list".into())] }), None);
+    let path = CssPath { selectors: vec![PseudoElement(CssPathPseudoElement::ScrollbarThumb), PseudoSelector(Hover)] };
+    assert_eq!(split_scrollbar_path(&path), None);
+}
+
+#[test]
+fn test_restyle_only_changed_nodes() {
+    use azul_css::{CssPathSelector::*, Stylesheet, StyleTextColor, ColorU, LayoutWidth};
+    use diff::diff_dom_tree;
+    use prelude::*;
+
+    struct DataModel;
+
+    const RED: ColorU = ColorU { r: 255, g: 0, b: 0, a: 255 };
+    const BLUE: ColorU = ColorU { r: 0, g: 0, b: 255, a: 255 };
+
+    let rule = |selectors: Vec<CssPathSelector>, property: CssProperty| CssRuleBlock {
+        path: CssPath { selectors },
+        declarations: vec![CssDeclaration::Static(property)],
+        important_declarations: Vec::new(),
+        custom_properties: Vec::new(),
+        media_queries: Vec::new(),
+    };
+
+    let css = Css { stylesheets: vec![Stylesheet::from(vec![
+        rule(vec![Class("a".into())], CssProperty::TextColor(StyleTextColor(RED))),
+        rule(vec![Class("blue".into())], CssProperty::TextColor(StyleTextColor(BLUE))),
+        rule(vec![Class("wide".into())], CssProperty::Width(LayoutWidth::px(100.0))),
+        rule(vec![Class("a".into()), AdjacentSibling, Class("b".into())], CssProperty::Width(LayoutWidth::px(50.0))),
+    ])] };
+
+    //  0: [div]
+    //   |-- 1: [div]
+    //   |    |-- 2: [div .{class}]
+    //   |    |    |-- 3: [p "hello"]
+    //   |    |-- 4: [div .b]
+    //   |-- 5: [div]
+    let dom = |class: &str| -> Dom<DataModel> {
+        Dom::div()
+            .with_child(Dom::div()
+                .with_child(Dom::div().with_class(class.to_string()).with_child(Dom::label("hello")))
+                .with_child(Dom::div().with_class("b")))
+            .with_child(Dom::div())
+    };
+
+    let style = |ui_state: &UiState<DataModel>, previous: Option<&UiDescription<DataModel>>, dom_diff: &mut DomDiff| {
+        restyle_dom_selectors(ui_state, previous, dom_diff, &css, &mut None, &mut None, &BTreeMap::new(), false, &MediaQueryContext::default())
+    };
+
+    let first_ui_state = dom("a").into_ui_state();
+    let first_frame = style(&first_ui_state, None, &mut DomDiff::default());
+
+    // The label inherits the color of node 2, which sets the color itself
+    assert!(first_frame.styled_nodes[NodeId::new(3)].inherited_properties.contains(&CssPropertyType::TextColor));
+    assert!(!first_frame.styled_nodes[NodeId::new(2)].inherited_properties.contains(&CssPropertyType::TextColor));
+
+    // (new class of node 2, whether the layout changed)
+    let cases = vec![
+        ("a", false),
+        ("blue", true), // `.a + .b` doesn't match node 4 anymore
+        ("a blue", false),
+        ("a wide", true),
+    ];
+
+    for (class, layout_changed) in cases {
+        let mut ui_state = dom("a").into_ui_state();
+        let classes = class.split_whitespace().map(|c| DomString::Heap(c.to_string())).collect();
+        ui_state.dom.arena.node_data[NodeId::new(2)].classes = classes;
+
+        let mut dom_diff = diff_dom_tree(&first_ui_state.dom, &ui_state.dom);
+        let restyled = style(&ui_state, Some(&first_frame), &mut dom_diff);
+        let fully_styled = style(&ui_state, None, &mut DomDiff::default());
+
+        assert_eq!(restyled.styled_nodes, fully_styled.styled_nodes, "{}", class);
+        assert_eq!(dom_diff.needs_relayout(), layout_changed, "{}", class);
+        // Only node 2, its children and its following siblings are restyled
+        let changed = class != "a";
+        assert_eq!(dom_diff.get_restyle_mask(6), vec![false, false, changed, changed, changed, false], "{}", class);
+    }
+
+    // Without a style cache, the entire DOM is restyled and relayouted
+    let mut uncached_frame = first_frame.clone();
+    uncached_frame.style_cache = None;
+    let mut dom_diff = DomDiff::default();
+    style(&first_ui_state, Some(&uncached_frame), &mut dom_diff);
+    assert_eq!(dom_diff.get_restyle_mask(6), vec![true; 6]);
+    assert!(dom_diff.needs_relayout());
+}
@@ -7,6 +7,7 @@ pub use webrender::api::{
     GlyphInstance, LayoutSize, LayoutRect, LayoutPoint,
 };
 pub use harfbuzz_sys::{hb_glyph_info_t as GlyphInfo, hb_glyph_position_t as GlyphPosition};
+pub use text_shaping::FontMetrics;
 
 pub type WordIndex = usize;
 pub type GlyphIndex = usize;
@@ -81,6 +82,8 @@ pub struct ScaledWords {
     pub space_advance_px: f32,
     /// Glyph index of the space character
     pub space_codepoint: u32,
+    /// Underline / strikethrough metrics of the primary font
+    pub font_metrics: FontMetrics,
 }
 
 /// Word that is scaled (to a font / font instance), but not yet positioned
@@ -93,6 +96,9 @@ pub struct ScaledWord {
     pub glyph_positions: Vec<GlyphPosition>,
     /// The sum of the width of all the characters in this word
     pub word_width: f32,
+    /// Which font each glyph was shaped with: `0` is the primary font, `1..` are the
+    /// fallback fonts, for characters that the primary font doesn't contain
+    pub glyph_fonts: Vec<usize>,
 }
 
 /// Stores the positions of the vertically laid out texts
@@ -158,8 +164,6 @@ pub struct TextLayoutOptions {
     pub leading: Option<f32>,
     /// This is more important for inline text layout where items can punch "holes"
     /// into the text flow, for example an image that floats to the right.
-    ///
-    /// TODO: Currently unused!
     pub holes: Vec<LayoutRect>,
 }
 
@@ -181,6 +185,8 @@ pub struct LeftAlignedGlyphs<'a> {
 #[derive(Debug, Clone, PartialEq)]
 pub struct LayoutedGlyphs {
     pub glyphs: Vec<GlyphInstance>,
+    /// Font index of each glyph, see `ScaledWord::glyph_fonts`
+    pub glyph_fonts: Vec<usize>,
 }
 
 /// Whether the text overflows the parent rectangle, and if yes, by how many pixels,
@@ -193,6 +199,32 @@ pub enum TextOverflow {
     InBounds(f32),
 }
 
+/// Item of a paragraph that is laid out by `position_inline_items`
+#[derive(Debug, Clone)]
+pub enum InlineItem<'a> {
+    /// Text with its own font, font size and spacing - the `max_horizontal_width`,
+    /// `leading` and `holes` of the `text_layout_options` are determined by the paragraph
+    Text {
+        words: &'a Words,
+        scaled_words: &'a ScaledWords,
+        text_layout_options: TextLayoutOptions,
+    },
+    /// Box that can't be broken into lines, such as an image (size including the margin)
+    Block(LayoutSize),
+}
+
+/// Positions of the items of a paragraph, relative to the top left corner of the paragraph
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineLayout {
+    /// Word positions of the texts (`None` for blocks). The words are already aligned, so the
+    /// glyphs have to be laid out with `StyleTextAlignmentHorz::Left` and `StyleTextAlignmentVert::Top`
+    pub word_positions: Vec<Option<WordPositions>>,
+    /// Bounds of each item, for texts the union of the lines that the text spans
+    pub item_bounds: Vec<LayoutRect>,
+    /// Width of the longest line and height of all lines
+    pub content_size: LayoutSize,
+}
+
 /// Iterator over glyphs that returns information about the cluster that this glyph belongs to.
 /// Returned by the `ScaledWord::cluster_iter()` function.
 ///
@@ -376,12 +408,14 @@ pub fn split_text_into_words(text: &str) -> Words {
 }
 
 /// Takes a text broken into semantic items and a font instance and
-/// scales the font accordingly.
+/// scales the font accordingly. Characters that the font doesn't contain
+/// are shaped with the first of the `fallback_fonts` that contains them.
 pub fn words_to_scaled_words(
     words: &Words,
     font_bytes: &[u8],
     font_index: u32,
     font_size_px: f32,
+    fallback_fonts: &[(&[u8], u32)],
 ) -> ScaledWords {
 
     use text_shaping::{self, HbBuffer, HbFont, HbScaledFont};
@@ -398,30 +432,79 @@ pub fn words_to_scaled_words(
     let hb_buffer_entire_paragraph = HbBuffer::from_str(&words.internal_str);
     let hb_shaped_entire_paragraph = text_shaping::shape_word_hb(&hb_buffer_entire_paragraph, &hb_scaled_font);
 
+    let mut paragraph_glyph_infos = hb_shaped_entire_paragraph.glyph_infos.to_vec();
+    let mut paragraph_glyph_positions = hb_shaped_entire_paragraph.glyph_positions.to_vec();
+    let mut paragraph_glyph_fonts = vec![0; paragraph_glyph_infos.len()];
+
+    // Glyph 0 is the "missing glyph" - reshape these characters with the fallback fonts
+    if !fallback_fonts.is_empty() && paragraph_glyph_infos.iter().any(|info| info.codepoint == 0) {
+
+        let fallback_hb_fonts = fallback_fonts.iter()
+            .map(|(bytes, index)| HbFont::from_bytes(bytes, *index))
+            .collect::<Vec<_>>();
+        let fallback_hb_scaled_fonts = fallback_hb_fonts.iter()
+            .map(|hb_font| HbScaledFont::from_font(hb_font, font_size_px))
+            .collect::<Vec<_>>();
+
+        for i in 0..paragraph_glyph_infos.len() {
+
+            if paragraph_glyph_infos[i].codepoint != 0 {
+                continue;
+            }
+
+            let cluster = paragraph_glyph_infos[i].cluster as usize;
+            let character = match words.internal_str.get(cluster..).and_then(|s| s.chars().next()) {
+                Some(c) => c.to_string(),
+                None => continue,
+            };
+
+            let hb_char_buffer = HbBuffer::from_str(&character);
+
+            for (fallback_idx, fallback_scaled_font) in fallback_hb_scaled_fonts.iter().enumerate() {
+                let hb_shaped_char = text_shaping::shape_word_hb(&hb_char_buffer, fallback_scaled_font);
+                let (glyph_info, glyph_position) = match (hb_shaped_char.glyph_infos.get(0), hb_shaped_char.glyph_positions.get(0)) {
+                    (Some(info), Some(position)) if info.codepoint != 0 => (*info, *position),
+                    _ => continue,
+                };
+                paragraph_glyph_infos[i].codepoint = glyph_info.codepoint;
+                paragraph_glyph_positions[i] = glyph_position;
+                paragraph_glyph_fonts[i] = fallback_idx + 1;
+                break;
+            }
+        }
+    }
+
     let mut shaped_word_positions = Vec::new();
     let mut shaped_word_infos = Vec::new();
+    let mut shaped_word_fonts = Vec::new();
     let mut current_word_positions = Vec::new();
     let mut current_word_infos = Vec::new();
+    let mut current_word_fonts = Vec::new();
 
-    for i in 0..hb_shaped_entire_paragraph.glyph_positions.len() {
-        let glyph_info = hb_shaped_entire_paragraph.glyph_infos[i];
-        let glyph_position = hb_shaped_entire_paragraph.glyph_positions[i];
+    for i in 0..paragraph_glyph_positions.len() {
+        let glyph_info = paragraph_glyph_infos[i];
+        let glyph_position = paragraph_glyph_positions[i];
+        let glyph_font = paragraph_glyph_fonts[i];
 
-        let is_space = glyph_info.codepoint == space_codepoint;
+        let is_space = glyph_font == 0 && glyph_info.codepoint == space_codepoint;
         if is_space {
             shaped_word_positions.push(current_word_positions.clone());
             shaped_word_infos.push(current_word_infos.clone());
+            shaped_word_fonts.push(current_word_fonts.clone());
             current_word_positions.clear();
             current_word_infos.clear();
+            current_word_fonts.clear();
         } else {
             current_word_positions.push(glyph_position);
             current_word_infos.push(glyph_info);
+            current_word_fonts.push(glyph_font);
         }
     }
 
     if !current_word_positions.is_empty() {
         shaped_word_positions.push(current_word_positions);
         shaped_word_infos.push(current_word_infos);
+        shaped_word_fonts.push(current_word_fonts);
     }
 
     let mut longest_word_width = 0.0_f32;
@@ -433,6 +516,7 @@ pub fn words_to_scaled_words(
 
             let hb_glyph_positions = shaped_word_positions.get(word_idx)?;
             let hb_glyph_infos = shaped_word_infos.get(word_idx)?;
+            let glyph_fonts = shaped_word_fonts.get(word_idx)?.clone();
 
             let hb_word_width = text_shaping::get_word_visual_width_hb(&hb_glyph_positions);
             let hb_glyph_positions = text_shaping::get_glyph_positions_hb(&hb_glyph_positions);
@@ -444,6 +528,7 @@ pub fn words_to_scaled_words(
                 glyph_infos: hb_glyph_infos,
                 glyph_positions: hb_glyph_positions,
                 word_width: hb_word_width,
+                glyph_fonts,
             })
         }).collect();
 
@@ -453,6 +538,7 @@ pub fn words_to_scaled_words(
         space_advance_px,
         space_codepoint,
         font_size_px,
+        font_metrics: text_shaping::get_font_metrics(font_bytes, font_index, font_size_px),
     }
 }
 
@@ -631,6 +717,255 @@ pub fn position_words(
     }
 }
 
+/// Returns the min-content width (the width of the longest word) and the max-content width
+/// (the width of the longest line if the text is only broken at return characters) of a text,
+/// without laying out the text. The holes and the `max_horizontal_width` of the
+/// `text_layout_options` are ignored.
+pub fn get_intrinsic_widths(
+    words: &Words,
+    scaled_words: &ScaledWords,
+    text_layout_options: &TextLayoutOptions,
+) -> (f32, f32) {
+
+    use self::WordType::*;
+
+    let space_advance = scaled_words.space_advance_px;
+    let word_spacing_px = space_advance * text_layout_options.word_spacing.unwrap_or(DEFAULT_WORD_SPACING);
+    let tab_width_px = space_advance * text_layout_options.tab_width.unwrap_or(DEFAULT_TAB_WIDTH);
+
+    let mut min_content_width = 0.0_f32;
+    let mut max_content_width = 0.0_f32;
+    let mut line_width = text_layout_options.leading.unwrap_or(0.0);
+    let mut word_idx = 0;
+
+    for (item_idx, word) in words.items.iter().enumerate() {
+        // Same as in `position_words`, any trailing Return, Space or Tab characters are ignored
+        let is_last_item = item_idx + 1 == words.items.len();
+        match word.word_type {
+            Word => {
+                let scaled_word = match scaled_words.items.get(word_idx) {
+                    Some(s) => s,
+                    None => continue,
+                };
+                let reserved_letter_spacing_px = match text_layout_options.letter_spacing {
+                    None => 0.0,
+                    Some(spacing_multiplier) => spacing_multiplier * scaled_word.number_of_clusters().saturating_sub(1) as f32,
+                };
+                let word_advance_x = scaled_word.word_width + reserved_letter_spacing_px;
+                min_content_width = min_content_width.max(word_advance_x);
+                line_width += word_advance_x;
+                word_idx += 1;
+            },
+            _ if is_last_item => { },
+            Return => {
+                max_content_width = max_content_width.max(line_width);
+                line_width = 0.0;
+            },
+            Space => line_width += word_spacing_px,
+            Tab => line_width += word_spacing_px + tab_width_px,
+        }
+    }
+
+    (min_content_width, max_content_width.max(line_width))
+}
+
+/// Lays out the items of a paragraph (texts with different fonts and sizes and boxes such as images)
+/// in one flow of lines: each text continues where the last item left off (the `trailing` of a text
+/// is the `leading` of the next item) and flows around the `holes`. The items of a line are aligned
+/// at a common baseline, the height of a line is the height of its largest item.
+pub fn position_inline_items(
+    items: &[InlineItem],
+    max_horizontal_width: Option<f32>,
+    holes: &[LayoutRect],
+    alignment_horz: StyleTextAlignmentHorz,
+) -> InlineLayout {
+
+    use azul_css::StyleTextAlignmentHorz::*;
+
+    /// Raises the ascent (distance from the top of the line to the baseline) and the height of
+    /// a line - lines that are skipped (i.e. because of a hole) get the metrics of the item
+    fn set_line_metrics(line_metrics: &mut Vec<(f32, f32)>, line_number: usize, (ascent, height): (f32, f32)) {
+        if line_metrics.len() <= line_number {
+            line_metrics.resize(line_number + 1, (ascent, height));
+        }
+        let metrics = &mut line_metrics[line_number];
+        metrics.0 = metrics.0.max(ascent);
+        metrics.1 = metrics.1.max(height);
+    }
+
+    // Line, horizontal position, width, ascent and height of every word of a text / of every block
+    let mut item_extents = Vec::<Vec<(usize, f32, f32, f32, f32)>>::with_capacity(items.len());
+    let mut word_positions = Vec::with_capacity(items.len());
+    let mut line_metrics = vec![(0.0, 0.0)];
+
+    let mut line_number = 0;
+    let mut line_caret_x = 0.0;
+
+    // Font size and line height of the last text, necessary for
+    // checking whether a block intersects with the holes
+    let mut hole_metrics = (0.0, 0.0);
+
+    for item in items {
+        match item {
+            InlineItem::Text { words, scaled_words, text_layout_options } => {
+
+                let font_size_px = scaled_words.font_size_px;
+                let space_advance = scaled_words.space_advance_px;
+                let line_height_px = space_advance * text_layout_options.line_height.unwrap_or(DEFAULT_LINE_HEIGHT);
+                let word_spacing_px = space_advance * text_layout_options.word_spacing.unwrap_or(DEFAULT_WORD_SPACING);
+                let tab_width_px = space_advance * text_layout_options.tab_width.unwrap_or(DEFAULT_TAB_WIDTH);
+                let line_pitch = font_size_px + line_height_px;
+                hole_metrics = (font_size_px, line_height_px);
+
+                // The text starts on line 0, so the holes have to be moved up by the previous lines
+                let line_offset_y = line_pitch * line_number as f32;
+                let text_holes = holes.iter().map(|hole| {
+                    LayoutRect::new(LayoutPoint::new(hole.origin.x, hole.origin.y - line_offset_y), hole.size)
+                }).collect::<Vec<_>>();
+
+                // The leading is added onto the holes at the start of the line
+                let line_start_x = match caret_intersects_with_holes(0.0, 0, font_size_px, line_height_px, &text_holes, max_horizontal_width) {
+                    LineCaretIntersection::AdvanceCaretTo(x) => x,
+                    _ => 0.0,
+                };
+
+                let text_layout_options = TextLayoutOptions {
+                    max_horizontal_width,
+                    leading: Some((line_caret_x - line_start_x).max(0.0)),
+                    holes: text_holes,
+                    .. text_layout_options.clone()
+                };
+
+                let positions = position_words(words, scaled_words, &text_layout_options, font_size_px);
+
+                let letter_spacing_px = text_layout_options.letter_spacing.unwrap_or(0.0);
+                let extents = positions.word_positions.iter().zip(scaled_words.items.iter()).map(|(position, scaled_word)| {
+                    let word_line = if line_pitch > 0.0 { ((position.y - font_size_px) / line_pitch).round().max(0.0) as usize } else { 0 };
+                    let word_width = scaled_word.word_width + letter_spacing_px * scaled_word.number_of_clusters().saturating_sub(1) as f32;
+                    (line_number + word_line, position.x, word_width, font_size_px, line_pitch)
+                }).collect();
+
+                let last_line_number = line_number + positions.number_of_lines.saturating_sub(1);
+                for line in line_number..=last_line_number {
+                    set_line_metrics(&mut line_metrics, line, (font_size_px, line_pitch));
+                }
+
+                line_number = last_line_number;
+                line_caret_x = positions.trailing;
+
+                // `position_words` ignores the white space at the end of the text,
+                // but the next item still has to be separated from this text
+                match words.items.last().map(|word| word.word_type) {
+                    Some(WordType::Space) => line_caret_x += word_spacing_px,
+                    Some(WordType::Tab) => line_caret_x += word_spacing_px + tab_width_px,
+                    Some(WordType::Return) => {
+                        line_number += 1;
+                        line_caret_x = 0.0;
+                        set_line_metrics(&mut line_metrics, line_number, (font_size_px, line_pitch));
+                    },
+                    _ => { },
+                }
+
+                item_extents.push(extents);
+                word_positions.push(Some(positions));
+            },
+            InlineItem::Block(size) => {
+
+                let (font_size_px, line_height_px) = if hole_metrics.0 + hole_metrics.1 > 0.0 {
+                    hole_metrics
+                } else {
+                    (size.height, 0.0)
+                };
+
+                let mut block_x = line_caret_x;
+                let mut block_line_number = line_number;
+
+                // Blocks that don't fit into the rest of the line are moved onto the next line
+                let block_end = caret_intersects_with_holes(block_x + size.width, block_line_number, font_size_px, line_height_px, holes, max_horizontal_width);
+                if let LineCaretIntersection::PushCaretOntoNextLine(_, _) = block_end {
+                    if block_x > 0.0 {
+                        advance_caret(&mut block_x, &mut block_line_number, block_end);
+                    }
+                }
+
+                let block_start = caret_intersects_with_holes(block_x, block_line_number, font_size_px, line_height_px, holes, max_horizontal_width);
+                advance_caret(&mut block_x, &mut block_line_number, block_start);
+
+                for line in line_number..block_line_number {
+                    set_line_metrics(&mut line_metrics, line, (font_size_px, font_size_px + line_height_px));
+                }
+                set_line_metrics(&mut line_metrics, block_line_number, (size.height, size.height));
+
+                line_number = block_line_number;
+                line_caret_x = block_x + size.width;
+
+                item_extents.push(vec![(line_number, block_x, size.width, size.height, size.height)]);
+                word_positions.push(None);
+            },
+        }
+    }
+
+    let mut line_tops = Vec::with_capacity(line_metrics.len());
+    let mut line_top = 0.0;
+    for (_, line_height) in &line_metrics {
+        line_tops.push(line_top);
+        line_top += line_height;
+    }
+
+    let line_baselines = line_tops.iter().zip(line_metrics.iter()).map(|(top, (ascent, _))| top + ascent).collect::<Vec<f32>>();
+
+    // Align the lines horizontally
+    let mut line_widths = vec![0.0_f32; line_metrics.len()];
+    for (line, x, width, _, _) in item_extents.iter().flat_map(|extents| extents.iter()) {
+        line_widths[*line] = line_widths[*line].max(x + width);
+    }
+
+    let content_width = line_widths.iter().cloned().fold(0.0, f32::max);
+    let available_width = max_horizontal_width.unwrap_or(content_width);
+
+    let multiply_factor = match alignment_horz {
+        Left => 0.0,
+        Center => 0.5,
+        Right => 1.0,
+    };
+
+    // A line ends at the first hole to the right of its content that overlaps the line vertically
+    let line_offsets = line_widths.iter().enumerate().map(|(line, line_width)| {
+        let line_top = line_tops[line];
+        let line_bottom = line_top + line_metrics[line].1;
+        let line_end = holes.iter()
+            .filter(|hole| hole.origin.y < line_bottom && hole.origin.y + hole.size.height > line_top)
+            .map(|hole| hole.origin.x)
+            .filter(|hole_x| *hole_x >= *line_width)
+            .fold(available_width, f32::min);
+        (line_end - line_width).max(0.0) * multiply_factor
+    }).collect::<Vec<f32>>();
+
+    let item_bounds = item_extents.iter().map(|extents| {
+        extents.iter().fold(LayoutRect::zero(), |bounds, (line, x, width, ascent, height)| {
+            bounds.union(&LayoutRect::new(
+                LayoutPoint::new(x + line_offsets[*line], line_baselines[*line] - ascent),
+                LayoutSize::new(*width, *height),
+            ))
+        })
+    }).collect();
+
+    let word_positions = word_positions.into_iter().zip(item_extents.iter()).map(|(positions, extents)| {
+        positions.map(|mut positions| {
+            for (position, (line, x, _, _, _)) in positions.word_positions.iter_mut().zip(extents.iter()) {
+                *position = LayoutPoint::new(x + line_offsets[*line], line_baselines[*line]);
+            }
+            positions
+        })
+    }).collect();
+
+    InlineLayout {
+        word_positions,
+        item_bounds,
+        content_size: LayoutSize::new(content_width, line_baselines[line_number]),
+    }
+}
+
 pub fn get_layouted_glyphs_unpositioned(
     word_positions: &WordPositions,
     scaled_words: &ScaledWords,
@@ -639,6 +974,7 @@ pub fn get_layouted_glyphs_unpositioned(
     use text_shaping;
 
     let mut glyphs = Vec::with_capacity(scaled_words.items.len());
+    let mut glyph_fonts = Vec::with_capacity(scaled_words.items.len());
 
     let letter_spacing_px = word_positions.text_layout_options.letter_spacing.unwrap_or(0.0);
 
@@ -654,10 +990,11 @@ pub fn get_layouted_glyphs_unpositioned(
                 glyph.point.x += letter_spacing_px * cluster_info.cluster_idx as f32;
                 glyph
             })
-        )
+        );
+        glyph_fonts.extend(scaled_word.glyph_fonts.iter().cloned());
     }
 
-    LayoutedGlyphs { glyphs }
+    LayoutedGlyphs { glyphs, glyph_fonts }
 }
 
 pub fn get_layouted_glyphs_with_horizonal_alignment(
@@ -696,6 +1033,46 @@ pub fn get_layouted_glyphs(
     glyphs
 }
 
+/// Returns the start of the baseline and the width of each line of the layouted glyphs,
+/// necessary for drawing text decorations. The spaces between the words of a line are
+/// part of the line, the spaces at the end of the line are not.
+pub fn get_line_extents(
+    layouted_glyphs: &LayoutedGlyphs,
+    scaled_words: &ScaledWords,
+    word_positions: &WordPositions,
+) -> Vec<(LayoutPoint, f32)> {
+
+    let letter_spacing_px = word_positions.text_layout_options.letter_spacing.unwrap_or(0.0);
+
+    let mut lines: Vec<(LayoutPoint, f32)> = Vec::new();
+    let mut glyph_idx = 0;
+
+    for scaled_word in &scaled_words.items {
+
+        if scaled_word.glyph_infos.is_empty() {
+            continue;
+        }
+
+        let word_start = match layouted_glyphs.glyphs.get(glyph_idx) {
+            Some(glyph) => glyph.point,
+            None => break,
+        };
+
+        glyph_idx += scaled_word.glyph_infos.len();
+
+        let word_end_x = word_start.x + scaled_word.word_width + letter_spacing_px * scaled_word.number_of_clusters() as f32;
+
+        match lines.last_mut() {
+            Some((line_start, line_width)) if (line_start.y - word_start.y).abs() < 0.5 => {
+                *line_width = word_end_x - line_start.x;
+            },
+            _ => lines.push((word_start, word_end_x - word_start.x)),
+        }
+    }
+
+    lines
+}
+
 /// Given a width, returns the vertical height and width of the text
 pub fn get_positioned_word_bounding_box(word_positions: &WordPositions) -> LayoutSize {
     word_positions.content_size
@@ -770,7 +1147,7 @@ enum LineCaretIntersection {
 /// - `line_caret_x`: The current horizontal caret position
 /// - `line_number`: The current line number
 /// - `holes`: Whether the text should respect any rectangular regions
-///    where the text can't flow (preparation for inline / float layout).
+///    where the text can't flow (i.e. floating images).
 /// - `max_width`: Does the text have a restriction on how wide it can be (in pixels)
 fn caret_intersects_with_holes(
     line_caret_x: f32,
@@ -781,69 +1158,51 @@ fn caret_intersects_with_holes(
     max_width: Option<f32>,
 ) -> LineCaretIntersection {
 
-    let mut new_line_caret_x = None;
+    let mut new_line_caret_x = line_caret_x;
     let mut line_advance = 0;
 
     // If the caret is outside of the max_width, move it to the start of a new line
     if let Some(max_width) = max_width {
         if line_caret_x > max_width {
-            new_line_caret_x = Some(0.0);
+            new_line_caret_x = 0.0;
             line_advance += 1;
         }
     }
 
-    for hole in holes {
-
-        let mut should_move_caret = false;
-        let mut current_line_advance = 0;
-        let mut new_line_number = line_number + current_line_advance;
-        let mut current_caret = LayoutPoint::new(
-            new_line_caret_x.unwrap_or(line_caret_x),
-            get_line_y_position(new_line_number, font_size_px, line_height_px)
+    // Move the caret to the right of the holes until it doesn't intersect any hole -
+    // holes that reach to the max_width push the caret onto the next line
+    loop {
+        let current_caret = LayoutPoint::new(
+            new_line_caret_x,
+            get_line_y_position(line_number + line_advance, font_size_px, line_height_px)
         );
 
-        // NOTE: holes need to be sorted by Y origin (from smallest to largest Y),
-        // and be sorted from left to right
-        while hole.contains(&current_caret) {
-            should_move_caret = true;
-            if let Some(max_width) = max_width {
-                if hole.origin.x + hole.size.width >= max_width {
-                    // Need to break the line here
-                    current_line_advance += 1;
-                    new_line_number = line_number + current_line_advance;
-                    current_caret = LayoutPoint::new(
-                        new_line_caret_x.unwrap_or(line_caret_x),
-                        get_line_y_position(new_line_number, font_size_px, line_height_px)
-                    );
-                } else {
-                    new_line_number = line_number + current_line_advance;
-                    current_caret = LayoutPoint::new(
-                        hole.origin.x + hole.size.width,
-                        get_line_y_position(new_line_number, font_size_px, line_height_px)
-                    );
-                }
-            } else {
-                // No max width, so no need to break the line, move the caret to the right side of the hole
-                new_line_number = line_number + current_line_advance;
-                current_caret = LayoutPoint::new(
-                    hole.origin.x + hole.size.width,
-                    get_line_y_position(new_line_number, font_size_px, line_height_px)
-                );
-            }
-        }
+        let hole = match holes.iter().find(|hole| hole.contains(&current_caret)) {
+            Some(s) => s,
+            None => break,
+        };
 
-        if should_move_caret {
-            new_line_caret_x = Some(current_caret.x);
-            line_advance += current_line_advance;
+        let hole_end_x = hole.origin.x + hole.size.width;
+
+        match max_width {
+            Some(max_width) if hole_end_x >= max_width => {
+                // Lines without a height would never leave the hole
+                if font_size_px + line_height_px <= 0.0 {
+                    break;
+                }
+                // Need to break the line here
+                new_line_caret_x = 0.0;
+                line_advance += 1;
+            },
+            // No need to break the line, move the caret to the right side of the hole
+            _ => new_line_caret_x = hole_end_x,
         }
     }
 
-    if let Some(new_line_caret_x) = new_line_caret_x {
-        if line_advance == 0 {
-            LineCaretIntersection::AdvanceCaretTo(new_line_caret_x)
-        } else {
-            LineCaretIntersection::PushCaretOntoNextLine(line_advance, new_line_caret_x)
-        }
+    if line_advance != 0 {
+        LineCaretIntersection::PushCaretOntoNextLine(line_advance, new_line_caret_x)
+    } else if new_line_caret_x != line_caret_x {
+        LineCaretIntersection::AdvanceCaretTo(new_line_caret_x)
     } else {
         LineCaretIntersection::NoIntersection
     }
@@ -1208,3 +1567,122 @@ fn test_caret_intersects_with_holes_4() {
 
     assert_eq!(result, LineCaretIntersection::NoIntersection);
 }
+
+#[test]
+fn test_get_intrinsic_widths() {
+
+    let scaled_word = |word_width| ScaledWord {
+        glyph_infos: Vec::new(),
+        glyph_positions: Vec::new(),
+        word_width,
+        glyph_fonts: Vec::new(),
+    };
+
+    // "ab" = 20px, "cde" = 30px, "f" = 10px, a space is 5px wide
+    let words = split_text_into_words("ab cde\nf ");
+    let scaled_words = ScaledWords {
+        font_size_px: 10.0,
+        items: vec![scaled_word(20.0), scaled_word(30.0), scaled_word(10.0)],
+        longest_word_width: 30.0,
+        space_advance_px: 5.0,
+        space_codepoint: 0,
+        font_metrics: FontMetrics::from_font_size(10.0),
+    };
+
+    // The trailing space is ignored
+    assert_eq!(get_intrinsic_widths(&words, &scaled_words, &TextLayoutOptions::default()), (30.0, 55.0));
+}
+
+#[test]
+fn test_position_inline_items() {
+
+    let scaled_word = |word_width| ScaledWord {
+        glyph_infos: Vec::new(),
+        glyph_positions: Vec::new(),
+        word_width,
+        glyph_fonts: Vec::new(),
+    };
+
+    let scaled_words = |font_size_px: f32, items| ScaledWords {
+        font_size_px,
+        items,
+        longest_word_width: 0.0,
+        space_advance_px: font_size_px / 2.0,
+        space_codepoint: 0,
+        font_metrics: FontMetrics::from_font_size(font_size_px),
+    };
+
+    // "ab cd " with a font size of 10px (line height: 15px),
+    // followed by a 30x30 image and "efg" with a font size of 20px (line height: 30px)
+    let small_words = split_text_into_words("ab cd ");
+    let small_scaled_words = scaled_words(10.0, vec![scaled_word(20.0), scaled_word(20.0)]);
+    let large_words = split_text_into_words("efg");
+    let large_scaled_words = scaled_words(20.0, vec![scaled_word(40.0)]);
+
+    let items = [
+        InlineItem::Text { words: &small_words, scaled_words: &small_scaled_words, text_layout_options: TextLayoutOptions::default() },
+        InlineItem::Block(LayoutSize::new(30.0, 30.0)),
+        InlineItem::Text { words: &large_words, scaled_words: &large_scaled_words, text_layout_options: TextLayoutOptions::default() },
+    ];
+
+    // The image continues after the trailing space, "efg" doesn't fit into the first line anymore.
+    // The first line is as high as the image, the baseline of the second line is 20px below it.
+    let inline_layout = position_inline_items(&items, Some(100.0), &[], StyleTextAlignmentHorz::Left);
+    assert_eq!(inline_layout.word_positions[0].as_ref().unwrap().word_positions, vec![LayoutPoint::new(0.0, 30.0), LayoutPoint::new(25.0, 30.0)]);
+    assert_eq!(inline_layout.word_positions[1], None);
+    assert_eq!(inline_layout.word_positions[2].as_ref().unwrap().word_positions, vec![LayoutPoint::new(0.0, 50.0)]);
+    assert_eq!(inline_layout.item_bounds[1], LayoutRect::new(LayoutPoint::new(50.0, 0.0), LayoutSize::new(30.0, 30.0)));
+    assert_eq!(inline_layout.content_size, LayoutSize::new(80.0, 50.0));
+
+    // Every line is centered on its own
+    let inline_layout = position_inline_items(&items, Some(100.0), &[], StyleTextAlignmentHorz::Center);
+    assert_eq!(inline_layout.item_bounds[1].origin, LayoutPoint::new(60.0, 0.0));
+    assert_eq!(inline_layout.word_positions[2].as_ref().unwrap().word_positions, vec![LayoutPoint::new(30.0, 50.0)]);
+}
+
+#[test]
+fn test_position_inline_items_with_holes() {
+
+    let scaled_word = |word_width| ScaledWord {
+        glyph_infos: Vec::new(),
+        glyph_positions: Vec::new(),
+        word_width,
+        glyph_fonts: Vec::new(),
+    };
+
+    // "ab cd ef" with a font size of 10px (line height: 15px)
+    let words = split_text_into_words("ab cd ef");
+    let scaled_words = ScaledWords {
+        font_size_px: 10.0,
+        items: vec![scaled_word(20.0), scaled_word(20.0), scaled_word(20.0)],
+        longest_word_width: 20.0,
+        space_advance_px: 5.0,
+        space_codepoint: 0,
+        font_metrics: FontMetrics::from_font_size(10.0),
+    };
+
+    let items = [
+        InlineItem::Text { words: &words, scaled_words: &scaled_words, text_layout_options: TextLayoutOptions::default() },
+        InlineItem::Block(LayoutSize::new(10.0, 10.0)),
+    ];
+
+    // A 30x20 image floating to the left, the first line (baseline at 10px) starts to
+    // the right of it, "ef" doesn't fit into the first line and the second line
+    // (baseline at 25px) is below the image
+    let holes = [LayoutRect::new(LayoutPoint::new(0.0, 0.0), LayoutSize::new(30.0, 20.0))];
+    let inline_layout = position_inline_items(&items, Some(80.0), &holes, StyleTextAlignmentHorz::Left);
+    assert_eq!(
+        inline_layout.word_positions[0].as_ref().unwrap().word_positions,
+        vec![LayoutPoint::new(30.0, 10.0), LayoutPoint::new(55.0, 10.0), LayoutPoint::new(0.0, 25.0)]
+    );
+    assert_eq!(inline_layout.item_bounds[1].origin, LayoutPoint::new(20.0, 15.0));
+
+    // The same image floating to the right: both lines end at the left edge of the image
+    let holes = [LayoutRect::new(LayoutPoint::new(50.0, 0.0), LayoutSize::new(30.0, 20.0))];
+    let inline_layout = position_inline_items(&items, Some(80.0), &holes, StyleTextAlignmentHorz::Right);
+    assert_eq!(
+        inline_layout.word_positions[0].as_ref().unwrap().word_positions,
+        vec![LayoutPoint::new(5.0, 10.0), LayoutPoint::new(30.0, 10.0), LayoutPoint::new(20.0, 25.0)]
+    );
+    assert_eq!(inline_layout.item_bounds[1].origin, LayoutPoint::new(40.0, 15.0));
+}
@@ -270,3 +270,153 @@ pub(crate) fn get_glyph_instances_hb(
         }
     }).collect()
 }
+
+/// Metrics for drawing text decorations (underlines, overlines, strikethroughs), in pixels
+/// for the font size that the font was scaled to. All positions are relative to the baseline,
+/// positive values point downwards.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FontMetrics {
+    /// Top of the glyphs, the position of overlines
+    pub ascender: f32,
+    /// Top edge of the underline
+    pub underline_position: f32,
+    pub underline_thickness: f32,
+    /// Top edge of the strikethrough
+    pub strikeout_position: f32,
+    pub strikeout_thickness: f32,
+}
+
+impl FontMetrics {
+    /// Approximates the metrics for fonts that don't contain the metric tables
+    pub fn from_font_size(font_size_px: f32) -> Self {
+        let thickness = font_size_px / 14.0;
+        Self {
+            ascender: -0.8 * font_size_px,
+            underline_position: 0.1 * font_size_px,
+            underline_thickness: thickness,
+            strikeout_position: -0.3 * font_size_px,
+            strikeout_thickness: thickness,
+        }
+    }
+}
+
+/// Reads the decoration metrics from the `head`, `hhea`, `post` and `OS/2` tables of the font,
+/// metrics that are missing in the font are approximated from the font size
+pub(crate) fn get_font_metrics(font_bytes: &[u8], font_index: u32, font_size_px: f32) -> FontMetrics {
+
+    let mut metrics = FontMetrics::from_font_size(font_size_px);
+
+    let units_per_em = get_font_table(font_bytes, font_index, b"head")
+        .and_then(|head| read_u16(head, 18))
+        .filter(|units_per_em| *units_per_em != 0);
+
+    let scale = match units_per_em {
+        Some(units_per_em) => font_size_px / units_per_em as f32,
+        None => return metrics,
+    };
+
+    // Font units point upwards, pixels point downwards
+    let to_px = |font_units: u16| -(font_units as i16 as f32) * scale;
+
+    if let Some(ascender) = get_font_table(font_bytes, font_index, b"hhea").and_then(|hhea| read_u16(hhea, 4)) {
+        metrics.ascender = to_px(ascender);
+    }
+
+    if let Some(post) = get_font_table(font_bytes, font_index, b"post") {
+        if let (Some(position), Some(thickness)) = (read_u16(post, 8), read_u16(post, 10)) {
+            metrics.underline_position = to_px(position);
+            metrics.underline_thickness = -to_px(thickness);
+        }
+    }
+
+    if let Some(os2) = get_font_table(font_bytes, font_index, b"OS/2") {
+        if let (Some(thickness), Some(position)) = (read_u16(os2, 26), read_u16(os2, 28)) {
+            metrics.strikeout_position = to_px(position);
+            metrics.strikeout_thickness = -to_px(thickness);
+        }
+    }
+
+    metrics
+}
+
+/// Returns the bytes of an OpenType table, i.e. `b"head"`. For font collections (`.ttc`),
+/// the `font_index` selects the font in the collection.
+fn get_font_table<'a>(font_bytes: &'a [u8], font_index: u32, tag: &[u8;4]) -> Option<&'a [u8]> {
+
+    let font_offset = if font_bytes.get(0..4)? == b"ttcf" {
+        read_u32(font_bytes, 12 + 4 * font_index as usize)? as usize
+    } else {
+        0
+    };
+
+    let num_tables = read_u16(font_bytes, font_offset + 4)? as usize;
+
+    let table_record = (0..num_tables)
+        .map(|table_idx| font_offset + 12 + 16 * table_idx)
+        .find(|record| font_bytes.get(*record..*record + 4) == Some(&tag[..]))?;
+
+    let table_offset = read_u32(font_bytes, table_record + 8)? as usize;
+    let table_length = read_u32(font_bytes, table_record + 12)? as usize;
+
+    font_bytes.get(table_offset..table_offset.checked_add(table_length)?)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(((b[0] as u16) << 8) | b[1] as u16)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(((b[0] as u32) << 24) | ((b[1] as u32) << 16) | ((b[2] as u32) << 8) | b[3] as u32)
+}
+
+#[test]
+fn test_get_font_metrics() {
+
+    fn push_u16(bytes: &mut Vec<u8>, value: u16) {
+        bytes.push((value >> 8) as u8);
+        bytes.push(value as u8);
+    }
+
+    fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+        push_u16(bytes, (value >> 16) as u16);
+        push_u16(bytes, value as u16);
+    }
+
+    // Font with a "head" table (1000 units per em) and a "post" table (underline
+    // 100 units below the baseline, 50 units thick), but without "hhea" and "OS/2"
+    let mut font = Vec::new();
+    push_u32(&mut font, 0x00010000);
+    push_u16(&mut font, 2);
+    font.extend_from_slice(&[0; 6]);
+
+    let head_offset = 12 + 2 * 16;
+    let post_offset = head_offset + 20;
+
+    font.extend_from_slice(b"head");
+    push_u32(&mut font, 0);
+    push_u32(&mut font, head_offset);
+    push_u32(&mut font, 20);
+    font.extend_from_slice(b"post");
+    push_u32(&mut font, 0);
+    push_u32(&mut font, post_offset);
+    push_u32(&mut font, 12);
+
+    font.extend_from_slice(&[0; 18]);
+    push_u16(&mut font, 1000);
+
+    font.extend_from_slice(&[0; 8]);
+    push_u16(&mut font, -100_i16 as u16);
+    push_u16(&mut font, 50);
+
+    let metrics = get_font_metrics(&font, 0, 20.0);
+    let approximated = FontMetrics::from_font_size(20.0);
+
+    assert_eq!(metrics.underline_position, 2.0);
+    assert_eq!(metrics.underline_thickness, 1.0);
+    assert_eq!(metrics.ascender, approximated.ascender);
+    assert_eq!(metrics.strikeout_position, approximated.strikeout_position);
+
+    assert_eq!(get_font_metrics(&font[..30], 0, 20.0), approximated);
+}
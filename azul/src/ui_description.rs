@@ -1,15 +1,19 @@
 use std::{
     fmt,
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
+};
+use azul_css::{
+    Css, CssDeclaration, CssProperty, CssPropertyType, MediaQueryContext,
+    CssPathPseudoElement, ScrollbarOrientation,
 };
-use azul_css::{ Css, CssDeclaration, CssProperty, CssPropertyType };
 use webrender::api::HitTestItem;
 use {
     FastHashMap,
     id_tree::{Arena, NodeId, NodeDataContainer},
     dom::{Dom, NodeData, DomString},
     ui_state::UiState,
-    style::HoverGroup,
+    style::{HoverGroup, StyleCache},
+    diff::DomDiff,
     callbacks::FocusTarget,
 };
 
@@ -25,6 +29,9 @@ pub struct UiDescription<T> {
     /// that have a non-:hover path, for example if we have `#thing:hover`, then all nodes selected by `#thing`
     /// need to get a TagId, otherwise, they can't be hit-tested.
     pub(crate) selected_hover_nodes: BTreeMap<NodeId, HoverGroup>,
+    /// Necessary to only restyle the changed nodes in the next frame. If this is `None`,
+    /// (i.e. because the CSS was hot-reloaded), the next frame restyles the entire DOM.
+    pub(crate) style_cache: Option<StyleCache>,
 }
 
 impl<T> fmt::Debug for UiDescription<T> {
@@ -35,12 +42,14 @@ impl<T> fmt::Debug for UiDescription<T> {
             styled_nodes: {:?},
             dynamic_css_overrides: {:?},
             selected_hover_nodes: {:?},
+            style_cache: {:?},
         }}",
             self.ui_descr_arena,
             self.ui_descr_root,
             self.styled_nodes,
             self.dynamic_css_overrides,
             self.selected_hover_nodes,
+            self.style_cache,
         )
     }
 }
@@ -53,6 +62,7 @@ impl<T> Clone for UiDescription<T> {
             styled_nodes: self.styled_nodes.clone(),
             dynamic_css_overrides: self.dynamic_css_overrides.clone(),
             selected_hover_nodes: self.selected_hover_nodes.clone(),
+            style_cache: self.style_cache.clone(),
         }
     }
 }
@@ -65,14 +75,19 @@ impl<T> Default for UiDescription<T> {
         let is_mouse_down = false;
         let mut focused_node = None;
         let mut focus_target = None;
-        Self::match_css_to_dom(
+        let mut ui_description = Self::match_css_to_dom(
             &mut default_dom.into_ui_state(),
             &Css::default(),
             &mut focused_node,
             &mut focus_target,
             &hovered_nodes,
             is_mouse_down,
-        )
+            &MediaQueryContext::default(),
+        );
+        // The default UiDescription isn't styled with the CSS of the window,
+        // so it can't be used for restyling only the changed nodes
+        ui_description.style_cache = None;
+        ui_description
     }
 }
 
@@ -80,6 +95,8 @@ impl<T> UiDescription<T> {
     /// Applies the styles to the nodes calculated from the `layout_screen`
     /// function and calculates the final display list that is submitted to the
     /// renderer.
+    ///
+    /// `@media` rules are only applied if they match the `media_context`.
     pub fn match_css_to_dom(
         ui_state: &mut UiState<T>,
         style: &Css,
@@ -87,6 +104,7 @@ impl<T> UiDescription<T> {
         pending_focus_target: &mut Option<FocusTarget>,
         hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
         is_mouse_down: bool,
+        media_context: &MediaQueryContext,
     ) -> Self
     {
         let ui_description = ::style::match_dom_selectors(
@@ -95,17 +113,56 @@ impl<T> UiDescription<T> {
             focused_node,
             pending_focus_target,
             hovered_nodes,
-            is_mouse_down
+            is_mouse_down,
+            media_context,
         );
 
         // Important: Create all the tags for the :hover and :active selectors
         ui_state.create_tags_for_hover_nodes(&ui_description.selected_hover_nodes);
         ui_description
     }
+
+    /// Same as `match_css_to_dom`, but only restyles the nodes of the `dom_diff`, all other
+    /// nodes re-use the styles of the `previous` frame (which has to be styled with the same `style`).
+    ///
+    /// Adds the nodes whose layout changed during the restyle to the `dom_diff`.
+    pub(crate) fn restyle(
+        ui_state: &mut UiState<T>,
+        previous: &UiDescription<T>,
+        dom_diff: &mut DomDiff,
+        style: &Css,
+        focused_node: &mut Option<NodeId>,
+        pending_focus_target: &mut Option<FocusTarget>,
+        hovered_nodes: &BTreeMap<NodeId, HitTestItem>,
+        is_mouse_down: bool,
+        media_context: &MediaQueryContext,
+    ) -> Self
+    {
+        let ui_description = ::style::restyle_dom_selectors(
+            ui_state,
+            Some(previous),
+            dom_diff,
+            &style,
+            focused_node,
+            pending_focus_target,
+            hovered_nodes,
+            is_mouse_down,
+            media_context,
+        );
+
+        ui_state.create_tags_for_hover_nodes(&ui_description.selected_hover_nodes);
+        ui_description
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Hash, PartialOrd, Eq, Ord)]
 pub(crate) struct StyledNode {
     /// The CSS constraints, after the cascading step
     pub(crate) css_constraints: BTreeMap<CssPropertyType, CssDeclaration>,
+    /// The CSS constraints of the `::-webkit-scrollbar` pseudo-elements of this node,
+    /// for each orientation and pseudo-element (not inherited)
+    pub(crate) scrollbar_constraints: BTreeMap<(ScrollbarOrientation, CssPathPseudoElement), BTreeMap<CssPropertyType, CssDeclaration>>,
+    /// The properties of the `css_constraints` that are inherited from the parent node, i.e. that
+    /// the node doesn't set itself (or sets to `inherit`) - these follow the animations of the parent
+    pub(crate) inherited_properties: BTreeSet<CssPropertyType>,
 }
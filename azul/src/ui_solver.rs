@@ -1,16 +1,21 @@
-use std::{f32, collections::BTreeMap};
+use std::{f32, collections::{BTreeMap, BTreeSet}};
 use azul_css::{
-    LayoutPosition, LayoutMargin, LayoutPadding,
-    RectLayout, StyleFontSize, RectStyle,
-    StyleTextAlignmentHorz, StyleTextAlignmentVert, PixelValue,
+    LayoutPosition, LayoutMargin, LayoutPadding, LayoutWrap, LayoutAxis, LayoutAlignContent,
+    LayoutWidth, LayoutHeight, LayoutMinWidth, LayoutMinHeight, LayoutMaxWidth, LayoutMaxHeight,
+    LayoutTop, LayoutBottom, LayoutLeft, LayoutRight,
+    RectLayout, StyleFontSize, RectStyle, PixelResolveContext,
+    StyleTextAlignmentHorz, StyleTextAlignmentVert, PixelValue, FontFaceProperties,
+    GridArea, GridLine, GridTrackSize, GridTrackBreadth, LayoutGridTemplateColumns, SizeMetric,
+    LayoutGridTemplateRows, LayoutGridGap, LayoutFlexBasis, LayoutGap, LayoutAlignItems,
+    LayoutJustifyContent, LayoutFloat,
 };
 use app_units::Au;
 use {
-    id_tree::{NodeId, NodeDataContainer, NodeHierarchy},
+    id_tree::{Node, NodeId, NodeDataContainer, NodeHierarchy},
     display_list::DisplayRectangle,
     dom::{NodeData, NodeType},
-    app_resources::AppResources,
-    text_layout::{Words, ScaledWords, TextLayoutOptions, WordPositions},
+    app_resources::{AppResources, ImmediateFontId},
+    text_layout::{Words, ScaledWords, TextLayoutOptions, WordPositions, InlineItem},
 };
 use webrender::api::{LayoutRect, LayoutPoint, LayoutSize, FontInstanceKey};
 
@@ -64,19 +69,67 @@ impl WhConstraint {
     }
 }
 
+/// Sizes of the content of a node along one axis
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub(crate) struct IntrinsicSizes {
+    /// Smallest size that the content can take without overflowing,
+    /// i.e. the width of the longest word of a text
+    pub min_content: f32,
+    /// Size of the content without any constraints, i.e. the width of a text without line breaks
+    pub max_content: f32,
+    /// Size that the node prefers if it has no `width` / `height`,
+    /// i.e. the size of an image or of a text that is laid out in the node
+    pub preferred: f32,
+}
+
+impl IntrinsicSizes {
+    /// Sizes of content that can't be broken up, such as an image
+    pub fn fixed(size: f32) -> Self {
+        Self { min_content: size, max_content: size, preferred: size }
+    }
+}
+
+/// Returns whether the `width` (or `height`) of the node is one of
+/// the `min-content`, `max-content` or `fit-content` keywords
+fn has_intrinsic_size(layout: &RectLayout, axis: LayoutAxis) -> bool {
+    match axis {
+        LayoutAxis::Horizontal => layout.width.map(|w| w.get_exact().is_none()).unwrap_or(false),
+        LayoutAxis::Vertical => layout.height.map(|h| h.get_exact().is_none()).unwrap_or(false),
+    }
+}
+
 macro_rules! determine_preferred {
-    ($fn_name:ident, $width:ident, $min_width:ident, $max_width:ident) => (
+    ($fn_name:ident, $width:ident, $width_type:ident, $min_width:ident, $max_width:ident) => (
 
-    /// - `preferred_inner_width` denotes the preferred width of the width or height got from the
+    /// - `intrinsic_sizes` denote the sizes of the width or height got from the
     /// from the rectangles content.
     ///
-    /// For example, if you have an image, the `preferred_inner_width` is the images width,
-    /// if the node type is an text, the `preferred_inner_width` is the text height.
-    fn $fn_name(layout: &RectLayout, preferred_inner_width: Option<f32>) -> WhConstraint {
-
-        let width = layout.$width.map(|w| w.0.to_pixels());
-        let min_width = layout.$min_width.map(|w| w.0.to_pixels());
-        let max_width = layout.$max_width.map(|w| w.0.to_pixels());
+    /// For example, if you have an image, the preferred inner width is the images width,
+    /// if the node type is an text, the preferred inner width is the text height. The
+    /// `min-content`, `max-content` and `fit-content` keywords are resolved against the
+    /// min-content and max-content sizes.
+    fn $fn_name(layout: &RectLayout, intrinsic_sizes: Option<IntrinsicSizes>) -> WhConstraint {
+
+        let content_sizes = intrinsic_sizes.unwrap_or_default();
+        let mut preferred_inner_width = intrinsic_sizes.map(|sizes| sizes.preferred);
+
+        let mut min_width = layout.$min_width.map(|w| w.0.to_pixels());
+        let mut max_width = layout.$max_width.map(|w| w.0.to_pixels());
+
+        let width = match layout.$width {
+            Some($width_type::Exact(w)) => Some(w.to_pixels()),
+            Some($width_type::MinContent) => Some(content_sizes.min_content),
+            Some($width_type::MaxContent) => Some(content_sizes.max_content),
+            // `fit-content` takes the available space, but at least the
+            // min-content size and at most the max-content size
+            Some($width_type::FitContent) => {
+                min_width = Some(min_width.unwrap_or(0.0).max(content_sizes.min_content));
+                max_width = Some(max_width.map(|max| max.min(content_sizes.max_content)).unwrap_or(content_sizes.max_content));
+                preferred_inner_width = None;
+                None
+            },
+            None => None,
+        };
 
         // TODO: correct for width / height less than 0 - "negative" width is impossible!
 
@@ -174,13 +227,13 @@ macro_rules! determine_preferred {
 // or `None` if the height can't be determined from the node alone.
 //
 // fn determine_preferred_width(layout: &RectLayout) -> Option<f32>
-determine_preferred!(determine_preferred_width, width, min_width, max_width);
+determine_preferred!(determine_preferred_width, width, LayoutWidth, min_width, max_width);
 
 // Returns the preferred height, given [height, min_height, max_height] inside a RectLayout
 // or `None` if the height can't be determined from the node alone.
 //
 // fn determine_preferred_height(layout: &RectLayout) -> Option<f32>
-determine_preferred!(determine_preferred_height, height, min_height, max_height);
+determine_preferred!(determine_preferred_height, height, LayoutHeight, min_height, max_height);
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 struct WidthCalculatedRect {
@@ -189,6 +242,8 @@ struct WidthCalculatedRect {
     pub padding: LayoutPadding,
     pub flex_grow_px: f32,
     pub min_inner_size_px: f32,
+    /// Sizes of the content, needed to determine the constraint again once percentages are resolved
+    pub intrinsic_sizes: Option<IntrinsicSizes>,
 }
 
 impl WidthCalculatedRect {
@@ -223,6 +278,8 @@ struct HeightCalculatedRect {
     pub padding: LayoutPadding,
     pub flex_grow_px: f32,
     pub min_inner_size_px: f32,
+    /// Sizes of the content, needed to determine the constraint again once percentages are resolved
+    pub intrinsic_sizes: Option<IntrinsicSizes>,
 }
 
 impl HeightCalculatedRect {
@@ -250,6 +307,976 @@ impl HeightCalculatedRect {
     }
 }
 
+/// Lines of the multi-line flex containers (`flex-wrap: wrap | wrap-reverse`), indexed by the
+/// container. Every line holds the in-flow children of the container in DOM order.
+/// Single-line containers (`flex-wrap: nowrap`) don't have an entry.
+pub(crate) type FlexLines = BTreeMap<NodeId, Vec<Vec<NodeId>>>;
+
+/// Returns whether the children of this node are laid out in multiple flex lines
+fn is_multi_line(layout: &RectLayout) -> bool {
+    layout.wrap.unwrap_or_default() != LayoutWrap::NoWrap
+}
+
+/// Returns the sum of the margins of the node along the given axis
+fn get_margin_along_axis(layout: &RectLayout, axis: LayoutAxis) -> f32 {
+    match axis {
+        LayoutAxis::Horizontal => layout.get_horizontal_margin(),
+        LayoutAxis::Vertical => layout.get_vertical_margin(),
+    }
+}
+
+/// Returns the `left` and `right` (or `top` and `bottom`) offsets of the node in pixels
+fn get_insets_along_axis(layout: &RectLayout, axis: LayoutAxis) -> (Option<f32>, Option<f32>) {
+    match axis {
+        LayoutAxis::Horizontal => (layout.left.map(|l| l.0.to_pixels()), layout.right.map(|r| r.0.to_pixels())),
+        LayoutAxis::Vertical => (layout.top.map(|t| t.0.to_pixels()), layout.bottom.map(|b| b.0.to_pixels())),
+    }
+}
+
+/// Returns the root node and all positioned (non-`static`) ancestors of the `node_id`, including
+/// the node itself, ordered from the root to the nearest positioned node
+fn get_positioned_node_stack(
+    node_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    arena_data: &NodeDataContainer<RectLayout>)
+-> Vec<NodeId>
+{
+    let mut positioned_node_stack = Vec::new();
+    let mut current_node = Some(node_id);
+
+    while let Some(current_node_id) = current_node {
+        if arena_data[current_node_id].position.unwrap_or_default() != LayoutPosition::Static {
+            positioned_node_stack.push(current_node_id);
+        }
+        current_node = node_hierarchy[current_node_id].parent;
+    }
+
+    positioned_node_stack.push(NodeId::new(0));
+    positioned_node_stack.reverse();
+    positioned_node_stack
+}
+
+/// Returns the node that an out-of-flow node is positioned relative to: the window (the root node)
+/// for `position: fixed`, the nearest positioned ancestor (the last node of the
+/// `positioned_node_stack`) for `position: absolute`
+fn get_positioned_ancestor(layout: &RectLayout, positioned_node_stack: &[NodeId]) -> NodeId {
+    let root_id = NodeId::new(0);
+    if layout.position.unwrap_or_default() == LayoutPosition::Fixed {
+        root_id
+    } else {
+        positioned_node_stack.last().cloned().unwrap_or(root_id)
+    }
+}
+
+/// Returns the offset of a `position: relative` node from the position that it was laid out at:
+/// `left` (or `top`) moves the node towards the end, `right` (or `bottom`) towards the start.
+/// If both are set, `left` (or `top`) wins.
+fn get_relative_offset(layout: &RectLayout, axis: LayoutAxis) -> f32 {
+    if layout.position.unwrap_or_default() != LayoutPosition::Relative {
+        return 0.0;
+    }
+
+    match get_insets_along_axis(layout, axis) {
+        (Some(start), _) => start,
+        (None, Some(end)) => -end,
+        (None, None) => 0.0,
+    }
+}
+
+/// Distributes the leftover space of a flex line along the main axis, according to the
+/// `justify-content` property. Returns the offset of the first item from the main-start
+/// edge of the line and the spacing that has to be added between two items.
+fn justify_flex_items(
+    justify_content: LayoutJustifyContent,
+    leftover_space: f32,
+    item_count: usize)
+-> (f32, f32)
+{
+    use azul_css::LayoutJustifyContent::*;
+
+    if item_count == 0 {
+        return (0.0, 0.0);
+    }
+
+    match justify_content {
+        Start => (0.0, 0.0),
+        End => (leftover_space, 0.0),
+        Center => (leftover_space / 2.0, 0.0),
+        SpaceBetween => {
+            if leftover_space > 0.0 && item_count > 1 {
+                (0.0, leftover_space / (item_count - 1) as f32)
+            } else {
+                (0.0, 0.0)
+            }
+        },
+        SpaceAround => {
+            if leftover_space > 0.0 {
+                let space_per_item = leftover_space / item_count as f32;
+                (space_per_item / 2.0, space_per_item)
+            } else {
+                (leftover_space / 2.0, 0.0)
+            }
+        },
+    }
+}
+
+/// Returns the `gap` between two items (or two lines) of a container along the given axis:
+/// the `column-gap` along the horizontal axis and the `row-gap` along the vertical axis.
+///
+/// Percentages refer to `percent_base`, the inner size of the container along the axis,
+/// a percentage gap is ignored while that size isn't known yet.
+fn get_gap_along_axis(layout: &RectLayout, axis: LayoutAxis, percent_base: Option<f32>) -> f32 {
+    let gap = match axis {
+        LayoutAxis::Horizontal => layout.gap.and_then(|gap| gap.column),
+        LayoutAxis::Vertical => layout.gap.and_then(|gap| gap.row),
+    };
+
+    match (gap, percent_base) {
+        (Some(gap), Some(percent_base)) => gap.to_pixels_relative(percent_base, &PixelResolveContext::default()),
+        (Some(gap), None) if !gap.is_percentage() => gap.to_pixels(),
+        _ => 0.0,
+    }
+}
+
+/// Returns the `flex-basis` of an item along the main axis of its parent, limited by the
+/// `min-width` and `max-width` (or `min-height` and `max-height`) of the item.
+///
+/// Returns `None` on `flex-basis: auto`, the item then starts at its preferred size instead.
+/// Percentages refer to `percent_base`, the inner size of the container along its main axis,
+/// they behave like `auto` while that size isn't known yet.
+fn get_flex_basis(layout: &RectLayout, axis: LayoutAxis, percent_base: Option<f32>) -> Option<f32> {
+    let basis = match layout.flex_basis.unwrap_or_default() {
+        LayoutFlexBasis::Auto => return None,
+        LayoutFlexBasis::Exact(basis) => basis,
+    };
+
+    let basis = match percent_base {
+        Some(percent_base) => basis.to_pixels_relative(percent_base, &PixelResolveContext::default()),
+        None if basis.is_percentage() => return None,
+        None => basis.to_pixels(),
+    };
+
+    let (min, max) = get_min_max_along_axis(layout, axis);
+
+    // Same as for the `width`: if `min-width > max-width`, the `min-width` wins
+    let basis = max.map(|max| basis.min(max)).unwrap_or(basis);
+    Some(min.map(|min| basis.max(min)).unwrap_or(basis))
+}
+
+/// Returns the `min-width` and `max-width` (or `min-height` and `max-height`) of the node in pixels
+fn get_min_max_along_axis(layout: &RectLayout, axis: LayoutAxis) -> (Option<f32>, Option<f32>) {
+    match axis {
+        LayoutAxis::Horizontal => (layout.min_width.map(|w| w.0.to_pixels()), layout.max_width.map(|w| w.0.to_pixels())),
+        LayoutAxis::Vertical => (layout.min_height.map(|h| h.0.to_pixels()), layout.max_height.map(|h| h.0.to_pixels())),
+    }
+}
+
+/// Returns the alignment of an item along the cross axis of its parent -
+/// `align-self: auto` takes the `align-items` of the parent
+fn get_align_self(item: &RectLayout, parent: &RectLayout) -> LayoutAlignItems {
+    item.align_self.unwrap_or_default().resolve(parent.align_items.unwrap_or_default())
+}
+
+/// Returns the children of `parent_id`, sorted by their `order` -
+/// children with the same `order` stay in DOM order
+fn get_children_in_order(
+    parent_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    arena_data: &NodeDataContainer<RectLayout>)
+-> Vec<NodeId>
+{
+    let mut children = parent_id.children(node_hierarchy).collect::<Vec<NodeId>>();
+    // `sort_by_key` is stable, so the DOM order is kept for equal keys
+    children.sort_by_key(|child_id| arena_data[*child_id].order.unwrap_or_default());
+    children
+}
+
+/// Breaks the in-flow children of `parent_id` into flex lines: a new line is started as soon
+/// as the next item doesn't fit into the `available_space` of the current line anymore.
+/// Every line contains at least one item, so an item that is larger than the `available_space`
+/// overflows on its own line. The items are taken in their `order`, two adjacent items
+/// of a line are separated by the `gap` of the container.
+///
+/// `get_item_size` has to return the size of the item along the main axis, including its margin.
+fn break_into_flex_lines<F: Fn(NodeId) -> f32>(
+    parent_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    arena_data: &NodeDataContainer<RectLayout>,
+    available_space: f32,
+    gap: f32,
+    get_item_size: F)
+-> Vec<Vec<NodeId>>
+{
+    let mut lines = Vec::new();
+    let mut current_line = Vec::new();
+    let mut current_line_size = 0.0;
+
+    for child_id in get_children_in_order(parent_id, node_hierarchy, arena_data) {
+
+        // `position: absolute` and `fixed` items are taken out of the flow, so they can't be part of a line
+        if arena_data[child_id].position.unwrap_or_default().is_out_of_flow() {
+            continue;
+        }
+
+        let item_size = get_item_size(child_id);
+
+        if !current_line.is_empty() && current_line_size + gap + item_size > available_space {
+            lines.push(current_line);
+            current_line = Vec::new();
+            current_line_size = 0.0;
+        }
+
+        if !current_line.is_empty() {
+            current_line_size += gap;
+        }
+
+        current_line.push(child_id);
+        current_line_size += item_size;
+    }
+
+    if !current_line.is_empty() {
+        lines.push(current_line);
+    }
+
+    lines
+}
+
+/// Returns the `position: absolute` and `position: fixed` children of a node, which aren't part of any flex line
+fn get_absolute_children(
+    parent_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    arena_data: &NodeDataContainer<RectLayout>)
+-> Vec<NodeId>
+{
+    parent_id
+        .children(node_hierarchy)
+        .filter(|child_id| arena_data[*child_id].position.unwrap_or_default().is_out_of_flow())
+        .collect()
+}
+
+/// Distributes the leftover space of a multi-line flex container across its lines, according
+/// to the `align-content` property. `line_sizes` are the sizes of the lines along the cross axis,
+/// on `align-content: stretch`, the lines are grown to fill the container.
+///
+/// Returns the offset of the first line from the cross-start edge of the container
+/// and the spacing that has to be added between two lines.
+fn align_flex_lines(
+    align_content: LayoutAlignContent,
+    line_sizes: &mut [f32],
+    available_space: f32)
+-> (f32, f32)
+{
+    use azul_css::LayoutAlignContent::*;
+
+    let line_count = line_sizes.len();
+    let leftover_space = available_space - line_sizes.iter().sum::<f32>();
+
+    if line_count == 0 {
+        return (0.0, 0.0);
+    }
+
+    match align_content {
+        Start => (0.0, 0.0),
+        End => (leftover_space, 0.0),
+        Center => (leftover_space / 2.0, 0.0),
+        Stretch => {
+            // If the lines overflow the container, they can't be stretched
+            if leftover_space > 0.0 {
+                let space_per_line = leftover_space / line_count as f32;
+                for line_size in line_sizes.iter_mut() {
+                    *line_size += space_per_line;
+                }
+            }
+            (0.0, 0.0)
+        },
+        SpaceBetween => {
+            if leftover_space > 0.0 && line_count > 1 {
+                (0.0, leftover_space / (line_count - 1) as f32)
+            } else {
+                (0.0, 0.0)
+            }
+        },
+        SpaceAround => {
+            if leftover_space > 0.0 {
+                let space_per_line = leftover_space / line_count as f32;
+                (space_per_line / 2.0, space_per_line)
+            } else {
+                (0.0, 0.0)
+            }
+        },
+    }
+}
+
+/// Placement of the in-flow children of a grid container (`display: grid`) into the cells of the grid
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GridPlacement {
+    /// Number of columns, including the implicit columns that were added for the items
+    pub column_count: usize,
+    /// Number of rows, including the implicit rows that were added for the items
+    pub row_count: usize,
+    /// Cells covered by every item, in DOM order
+    pub items: Vec<(NodeId, GridArea)>,
+}
+
+impl GridPlacement {
+    fn get_track_count(&self, axis: LayoutAxis) -> usize {
+        match axis {
+            LayoutAxis::Horizontal => self.column_count,
+            LayoutAxis::Vertical => self.row_count,
+        }
+    }
+}
+
+/// Solved sizes of the tracks of a grid container along one axis
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GridTracks {
+    pub sizes: Vec<f32>,
+    /// Space between two tracks (`grid-gap`)
+    pub gap: f32,
+}
+
+impl GridTracks {
+
+    /// Returns the offset of the start of a track from the content box of the grid container
+    pub fn get_offset(&self, track: usize) -> f32 {
+        self.sizes[..track].iter().sum::<f32>() + self.gap * track as f32
+    }
+
+    /// Returns the size of the tracks from `start` to `end` (exclusive), including the gaps between them
+    pub fn get_size(&self, start: usize, end: usize) -> f32 {
+        self.sizes[start..end].iter().sum::<f32>() + self.gap * end.saturating_sub(start + 1) as f32
+    }
+
+    /// Returns the size of all tracks, including the gaps between them
+    pub fn get_total_size(&self) -> f32 {
+        self.get_size(0, self.sizes.len())
+    }
+}
+
+/// Grid containers, indexed by the container: the placement of the items
+/// and the solved tracks along the axis that the grids were solved for
+pub(crate) type Grids = BTreeMap<NodeId, (GridPlacement, GridTracks)>;
+
+/// Returns the first and last (exclusive) track of an area along the given axis
+fn get_area_span(area: &GridArea, axis: LayoutAxis) -> (usize, usize) {
+    match axis {
+        LayoutAxis::Horizontal => (area.column_start, area.column_end),
+        LayoutAxis::Vertical => (area.row_start, area.row_end),
+    }
+}
+
+/// Resolves the start and end line of a grid item along one axis into the index of the first
+/// track (`None` if the item has to be placed automatically) and the number of spanned tracks.
+///
+/// `get_area_lines` returns the start and end line of a named area along the axis.
+fn resolve_grid_span<F: Fn(&str) -> Option<(usize, usize)>>(
+    start: &GridLine,
+    end: &GridLine,
+    explicit_track_count: usize,
+    get_area_lines: F)
+-> (Option<usize>, usize)
+{
+    // Converts a line into a 0-based line index, 0 is the start of the first track
+    let resolve_line = |line: &GridLine, is_start: bool| -> Option<usize> {
+        match line {
+            GridLine::Line(line) if *line > 0 => Some((*line - 1) as usize),
+            // -1 is the last line of the explicit grid
+            GridLine::Line(line) => Some((explicit_track_count as isize + 1 + *line).max(0) as usize),
+            GridLine::Area(name) => get_area_lines(name).map(|(start, end)| if is_start { start } else { end }),
+            GridLine::Auto | GridLine::Span(_) => None,
+        }
+    };
+
+    let get_span = |line: &GridLine| match line {
+        GridLine::Span(span) => (*span).max(1),
+        _ => 1,
+    };
+
+    match (resolve_line(start, true), resolve_line(end, false)) {
+        (Some(start_line), Some(end_line)) => {
+            if end_line > start_line {
+                (Some(start_line), end_line - start_line)
+            } else if end_line < start_line {
+                // `grid-column: 3 / 1` is the same as `grid-column: 1 / 3`
+                (Some(end_line), start_line - end_line)
+            } else {
+                (Some(start_line), 1)
+            }
+        },
+        (Some(start_line), None) => (Some(start_line), get_span(end)),
+        (None, Some(end_line)) => {
+            let span = get_span(start);
+            (Some(end_line.saturating_sub(span)), span.min(end_line).max(1))
+        },
+        (None, None) => match start {
+            GridLine::Span(_) => (None, get_span(start)),
+            _ => (None, get_span(end)),
+        },
+    }
+}
+
+/// Returns whether all cells of the given rectangle are still free
+fn is_grid_area_free(occupied: &BTreeSet<(usize, usize)>, row: usize, column: usize, row_span: usize, column_span: usize) -> bool {
+    (row..row + row_span).all(|r| (column..column + column_span).all(|c| !occupied.contains(&(r, c))))
+}
+
+/// Places the in-flow children of a grid container into the cells of the grid. Items with a
+/// definite row and column are placed first, then the items with a definite row, the remaining
+/// items fill the next free cells row by row. If the items don't fit into the explicit grid
+/// (`grid-template-rows` / `grid-template-columns`), implicit `auto` tracks are added.
+fn place_grid_items(
+    parent_id: NodeId,
+    node_hierarchy: &NodeHierarchy,
+    arena_data: &NodeDataContainer<RectLayout>)
+-> GridPlacement
+{
+    let container = &arena_data[parent_id];
+    let template_areas = container.grid_template_areas.as_ref();
+
+    let explicit_column_count = container.grid_template_columns.as_ref().map(|c| c.0.len()).unwrap_or(0)
+        .max(template_areas.map(|a| a.column_count).unwrap_or(0));
+    let explicit_row_count = container.grid_template_rows.as_ref().map(|r| r.0.len()).unwrap_or(0)
+        .max(template_areas.map(|a| a.row_count).unwrap_or(0));
+
+    // Items are auto-placed in their `order`
+    let children = get_children_in_order(parent_id, node_hierarchy, arena_data)
+        .into_iter()
+        .filter(|child_id| !arena_data[*child_id].position.unwrap_or_default().is_out_of_flow())
+        .collect::<Vec<NodeId>>();
+
+    // (row start, row span) and (column start, column span) of every item
+    let spans = children.iter().map(|child_id| {
+        let child = &arena_data[*child_id];
+        let row = child.grid_row.clone().unwrap_or_default();
+        let column = child.grid_column.clone().unwrap_or_default();
+        let get_area = |name: &str| template_areas.and_then(|areas| areas.get_area(name));
+        (
+            resolve_grid_span(&row.start, &row.end, explicit_row_count, |name| get_area(name).map(|a| (a.row_start, a.row_end))),
+            resolve_grid_span(&column.start, &column.end, explicit_column_count, |name| get_area(name).map(|a| (a.column_start, a.column_end))),
+        )
+    }).collect::<Vec<_>>();
+
+    // Auto-placed items wrap into the next row at the end of the explicit columns, unless
+    // an item was explicitly placed further right or spans more columns
+    let column_count = spans.iter().fold(explicit_column_count.max(1), |count, (_, (column_start, column_span))| {
+        count.max(column_start.unwrap_or(0) + column_span)
+    });
+
+    let mut occupied = BTreeSet::new();
+    let mut areas = vec![None; children.len()];
+
+    fn place(occupied: &mut BTreeSet<(usize, usize)>, area: &mut Option<GridArea>, row: usize, column: usize, row_span: usize, column_span: usize) {
+        for r in row..row + row_span {
+            for c in column..column + column_span {
+                occupied.insert((r, c));
+            }
+        }
+        *area = Some(GridArea {
+            row_start: row,
+            row_end: row + row_span,
+            column_start: column,
+            column_end: column + column_span,
+        });
+    }
+
+    // 1. Items with a definite position (they may overlap each other)
+    for (item_idx, ((row, row_span), (column, column_span))) in spans.iter().enumerate() {
+        if let (Some(row), Some(column)) = (row, column) {
+            place(&mut occupied, &mut areas[item_idx], *row, *column, *row_span, *column_span);
+        }
+    }
+
+    // 2. Items that are locked to a row, placed into the first free column of that row
+    for (item_idx, ((row, row_span), (column, column_span))) in spans.iter().enumerate() {
+        if let (Some(row), None) = (row, column) {
+            let column = (0..column_count.saturating_sub(*column_span) + 1)
+                .find(|column| is_grid_area_free(&occupied, *row, *column, *row_span, *column_span))
+                .unwrap_or(column_count);
+            place(&mut occupied, &mut areas[item_idx], *row, column, *row_span, *column_span);
+        }
+    }
+
+    // 3. All other items, placed in DOM order starting at the position of the last auto-placed item
+    let mut cursor_row = 0;
+    let mut cursor_column = 0;
+
+    for (item_idx, ((row, row_span), (column, column_span))) in spans.iter().enumerate() {
+        if row.is_some() {
+            continue;
+        }
+
+        match column {
+            Some(column) => {
+                // Locked to a column, the cursor only moves forward
+                if *column < cursor_column {
+                    cursor_row += 1;
+                }
+                while !is_grid_area_free(&occupied, cursor_row, *column, *row_span, *column_span) {
+                    cursor_row += 1;
+                }
+                cursor_column = *column;
+            },
+            None => {
+                loop {
+                    if cursor_column + column_span > column_count && cursor_column != 0 {
+                        cursor_row += 1;
+                        cursor_column = 0;
+                    } else if is_grid_area_free(&occupied, cursor_row, cursor_column, *row_span, *column_span) {
+                        break;
+                    } else {
+                        cursor_column += 1;
+                    }
+                }
+            },
+        }
+
+        place(&mut occupied, &mut areas[item_idx], cursor_row, cursor_column, *row_span, *column_span);
+        cursor_column += column_span;
+    }
+
+    let items = children.into_iter().zip(areas.into_iter()).filter_map(|(child_id, area)| area.map(|area| (child_id, area))).collect::<Vec<_>>();
+
+    GridPlacement {
+        column_count: items.iter().fold(column_count, |count, (_, area)| count.max(area.column_end)),
+        row_count: items.iter().fold(explicit_row_count, |count, (_, area)| count.max(area.row_end)),
+        items,
+    }
+}
+
+/// Grows the `tracks` by the same amount until the `free_space` is used up or all tracks have
+/// reached their limit. Returns the leftover free space.
+fn grow_grid_tracks_equally(sizes: &mut [f32], mut tracks: Vec<usize>, limits: &[Option<f32>], mut free_space: f32) -> f32 {
+    while free_space > 0.0 && !tracks.is_empty() {
+        let space_per_track = free_space / tracks.len() as f32;
+        let mut tracks_at_limit = Vec::new();
+
+        for track in &tracks {
+            let limit = limits[*track].unwrap_or(f32::MAX);
+            let growth = space_per_track.min(limit - sizes[*track]);
+            sizes[*track] += growth;
+            free_space -= growth;
+            if sizes[*track] >= limit {
+                tracks_at_limit.push(*track);
+            }
+        }
+
+        if tracks_at_limit.is_empty() {
+            break;
+        }
+
+        tracks.retain(|track| !tracks_at_limit.contains(track));
+    }
+
+    free_space
+}
+
+/// Solves the sizes of the tracks of a grid container along the given axis.
+///
+/// `available_space` is the inner size of the grid container or `None` if it isn't known yet
+/// (while bubbling the content sizes up to the parents) - in that case, the tracks are only
+/// as large as their content. `get_item_size` has to return the size of an item along the axis,
+/// including its margin.
+fn solve_grid_tracks<F: Fn(NodeId) -> f32>(
+    container: &RectLayout,
+    placement: &GridPlacement,
+    axis: LayoutAxis,
+    available_space: Option<f32>,
+    get_item_size: F)
+-> GridTracks
+{
+    // `grid-gap` takes precedence over the `gap` of the container
+    let (definitions, gap) = match axis {
+        LayoutAxis::Horizontal => (container.grid_template_columns.as_ref().map(|c| &c.0[..]), container.grid_gap.map(|gap| gap.column).or(container.gap.and_then(|gap| gap.column))),
+        LayoutAxis::Vertical => (container.grid_template_rows.as_ref().map(|r| &r.0[..]), container.grid_gap.map(|gap| gap.row).or(container.gap.and_then(|gap| gap.row))),
+    };
+
+    // Implicit tracks are `auto`-sized
+    let track_count = placement.get_track_count(axis);
+    let definitions = definitions.unwrap_or(&[]);
+    let tracks = (0..track_count).map(|track| definitions.get(track).cloned().unwrap_or_default()).collect::<Vec<GridTrackSize>>();
+
+    // Percentages refer to the size of the grid container, they behave like `auto` while it isn't known
+    let resolve_breadth = |breadth: GridTrackBreadth| match breadth {
+        GridTrackBreadth::Length(length) if length.is_percentage() && available_space.is_none() => GridTrackBreadth::Auto,
+        other => other,
+    };
+    let to_pixels = |length: PixelValue| length.to_pixels_relative(available_space.unwrap_or(0.0), &PixelResolveContext::default());
+
+    let gap = gap.map(to_pixels).unwrap_or(0.0);
+    let min_breadths = tracks.iter().map(|track| resolve_breadth(track.get_min())).collect::<Vec<_>>();
+    let max_breadths = tracks.iter().map(|track| resolve_breadth(track.get_max())).collect::<Vec<_>>();
+
+    // 1. Start with the fixed minimum sizes, the tracks with an `auto` minimum grow to fit their items.
+    //    Items that span multiple tracks are handled last, they distribute the size that is still
+    //    missing equally across the `auto` tracks that they span
+    let mut sizes = min_breadths.iter().map(|min| match min {
+        GridTrackBreadth::Length(length) => to_pixels(*length),
+        _ => 0.0,
+    }).collect::<Vec<f32>>();
+
+    let mut items = placement.items.iter().map(|(child_id, area)| (get_area_span(area, axis), get_item_size(*child_id))).collect::<Vec<_>>();
+    items.sort_by_key(|((start, end), _)| end - start);
+
+    for ((start, end), item_size) in items {
+        let tracks_size = sizes[start..end].iter().sum::<f32>() + gap * end.saturating_sub(start + 1) as f32;
+        let missing_space = item_size - tracks_size;
+        if missing_space <= 0.0 {
+            continue;
+        }
+        let auto_tracks = (start..end).filter(|track| min_breadths[*track] == GridTrackBreadth::Auto).collect::<Vec<usize>>();
+        for track in &auto_tracks {
+            sizes[*track] += missing_space / auto_tracks.len() as f32;
+        }
+    }
+
+    let available_space = match available_space {
+        Some(s) => s,
+        None => return GridTracks { sizes, gap },
+    };
+
+    let mut free_space = available_space - (sizes.iter().sum::<f32>() + gap * track_count.saturating_sub(1) as f32);
+
+    // 2. Grow the tracks with a fixed maximum (i.e. `minmax(100px, 200px)`) up to that maximum
+    let limits = max_breadths.iter().zip(sizes.iter()).map(|(max, size)| match max {
+        GridTrackBreadth::Length(length) => Some(to_pixels(*length).max(*size)),
+        _ => None,
+    }).collect::<Vec<Option<f32>>>();
+
+    let tracks_with_limit = (0..track_count).filter(|track| limits[*track].is_some()).collect();
+    free_space = grow_grid_tracks_equally(&mut sizes, tracks_with_limit, &limits, free_space);
+
+    if free_space <= 0.0 {
+        return GridTracks { sizes, gap };
+    }
+
+    let flexible_tracks = (0..track_count).filter_map(|track| match max_breadths[track] {
+        GridTrackBreadth::Fraction(fr) => Some((track, fr.get())),
+        _ => None,
+    }).collect::<Vec<(usize, f32)>>();
+
+    if flexible_tracks.is_empty() {
+        // 3a. Without `fr` tracks, the `auto` tracks are stretched to fill the grid container
+        let auto_tracks = (0..track_count).filter(|track| max_breadths[*track] == GridTrackBreadth::Auto).collect::<Vec<usize>>();
+        for track in &auto_tracks {
+            sizes[*track] += free_space / auto_tracks.len() as f32;
+        }
+    } else {
+        // 3b. Distribute the free space across the `fr` tracks in proportion to their flex factor.
+        //     Tracks whose content is larger than their share keep their size and are treated as
+        //     inflexible, the space for the remaining tracks has to be recalculated then
+        let mut inflexible_tracks = Vec::new();
+        loop {
+            let remaining_tracks = flexible_tracks.iter().filter(|(track, _)| !inflexible_tracks.contains(track)).collect::<Vec<_>>();
+            let space = free_space + remaining_tracks.iter().map(|(track, _)| sizes[*track]).sum::<f32>();
+            // A flex factor sum below 1 only takes up a part of the free space
+            let flex_factor_sum = remaining_tracks.iter().map(|(_, fr)| *fr).sum::<f32>().max(1.0);
+            let space_per_fr = space / flex_factor_sum;
+
+            let too_large = remaining_tracks.iter().filter(|(track, fr)| sizes[*track] > space_per_fr * fr).map(|(track, _)| *track).collect::<Vec<usize>>();
+            if too_large.is_empty() {
+                for (track, fr) in remaining_tracks {
+                    sizes[*track] = space_per_fr * fr;
+                }
+                break;
+            }
+
+            inflexible_tracks.extend(too_large);
+        }
+    }
+
+    GridTracks { sizes, gap }
+}
+
+/// Returns the intrinsic sizes of the nodes along the `axis`, for all nodes that use the
+/// `min-content`, `max-content` or `fit-content` keywords: Nodes without children are sized by
+/// their content, all other nodes by the intrinsic sizes of their in-flow children. Along the main
+/// axis, the sizes of the children are added up (the min-content size of a multi-line container
+/// is the size of its largest child, since the other children can wrap), along the cross axis the
+/// largest child determines the size. All other nodes keep their `content_sizes`.
+fn get_intrinsic_sizes(
+    node_hierarchy: &NodeHierarchy,
+    arena_data: &NodeDataContainer<RectLayout>,
+    content_sizes: &NodeDataContainer<Option<IntrinsicSizes>>,
+    non_leaf_nodes: &[(usize, NodeId)],
+    axis: LayoutAxis,
+    determine_preferred_fn: fn(&RectLayout, Option<IntrinsicSizes>) -> WhConstraint,
+) -> NodeDataContainer<Option<IntrinsicSizes>>
+{
+    if !arena_data.internal.iter().any(|layout| has_intrinsic_size(layout, axis)) {
+        return content_sizes.clone();
+    }
+
+    let mut intrinsic_sizes = content_sizes.clone();
+
+    // Go from the inside out, since the children have to be sized before their parents
+    for (_node_depth, parent_id) in non_leaf_nodes.iter().rev() {
+
+        let parent_node = &arena_data[*parent_id];
+
+        let children_sizes = parent_id.children(node_hierarchy)
+            .filter(|child_id| !arena_data[*child_id].position.unwrap_or_default().is_out_of_flow())
+            .map(|child_id| {
+                let child_node = &arena_data[child_id];
+                let child_sizes = intrinsic_sizes[child_id].unwrap_or_default();
+                // The `width`, `min-width` and `max-width` of the child constrain its content
+                let child_constraint = determine_preferred_fn(child_node, Some(IntrinsicSizes { preferred: child_sizes.min_content, .. child_sizes }));
+                let (min_content, max_content) = match child_constraint {
+                    WhConstraint::EqualTo(size) => (size, size),
+                    WhConstraint::Between(min, max) => (
+                        child_sizes.min_content.max(min).min(max),
+                        child_sizes.max_content.max(min).min(max),
+                    ),
+                    WhConstraint::Unconstrained => (child_sizes.min_content, child_sizes.max_content),
+                };
+                let child_padding = match axis {
+                    LayoutAxis::Horizontal => child_node.get_horizontal_padding(),
+                    LayoutAxis::Vertical => child_node.get_vertical_padding(),
+                };
+                let child_outer_size = child_padding + get_margin_along_axis(child_node, axis);
+                (min_content + child_outer_size, max_content + child_outer_size)
+            }).collect::<Vec<(f32, f32)>>();
+
+        let largest_min_content = children_sizes.iter().map(|(min, _)| *min).fold(0.0, f32::max);
+        let largest_max_content = children_sizes.iter().map(|(_, max)| *max).fold(0.0, f32::max);
+
+        let (min_content, max_content) = if parent_node.direction.unwrap_or_default().get_axis() == axis {
+            let gap = get_gap_along_axis(parent_node, axis, None) * children_sizes.len().saturating_sub(1) as f32;
+            let min_content_sum = children_sizes.iter().map(|(min, _)| *min).sum::<f32>() + gap;
+            let max_content_sum = children_sizes.iter().map(|(_, max)| *max).sum::<f32>() + gap;
+            if is_multi_line(parent_node) {
+                (largest_min_content, max_content_sum)
+            } else {
+                (min_content_sum, max_content_sum)
+            }
+        } else {
+            (largest_min_content, largest_max_content)
+        };
+
+        intrinsic_sizes[*parent_id] = Some(IntrinsicSizes { min_content, max_content, preferred: max_content });
+    }
+
+    content_sizes.transform(|content_size, node_id| {
+        if has_intrinsic_size(&arena_data[node_id], axis) {
+            intrinsic_sizes[node_id]
+        } else {
+            *content_size
+        }
+    })
+}
+
+/// Item of a paragraph, see `InlineFormattingContext`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum InlineContent {
+    /// Text that flows in the lines of the paragraph
+    Text(NodeId),
+    /// Node that takes up one rectangle in a line, such as an image
+    Box(NodeId),
+}
+
+/// Paragraph, i.e. a node that has `display: inline` children: the texts and boxes of the paragraph
+/// are laid out in lines by the text layout instead of the flex solver. The texts of the paragraph
+/// always flow in the lines, even if they aren't `display: inline` themselves.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct InlineFormattingContext {
+    /// Texts and boxes of the paragraph in DOM order, including the content of the spans
+    pub items: Vec<InlineContent>,
+    /// `display: inline` nodes with children - they don't take up any space on
+    /// their own, their bounds enclose the bounds of their children
+    pub spans: Vec<NodeId>,
+    /// Children of the paragraph (or of its spans) with `float: left` or `float: right`
+    pub floats: Vec<NodeId>,
+    /// `position: absolute` and `position: fixed` children of the paragraph (or of its spans)
+    pub out_of_flow: Vec<NodeId>,
+}
+
+impl InlineFormattingContext {
+    /// Returns the boxes of the paragraph in DOM order
+    pub fn boxes<'a>(&'a self) -> impl Iterator<Item=NodeId> + 'a {
+        self.items.iter().filter_map(|item| match item {
+            InlineContent::Box(node_id) => Some(*node_id),
+            InlineContent::Text(_) => None,
+        })
+    }
+}
+
+/// Returns whether the node flows in the lines of its parent (`display: inline`
+/// nodes that are neither floating nor `position: absolute` / `position: fixed`)
+fn is_inline_node(layout: &RectLayout) -> bool {
+    layout.is_inline()
+    && layout.float.unwrap_or_default() == LayoutFloat::None
+    && !layout.position.unwrap_or_default().is_out_of_flow()
+}
+
+/// Returns all paragraphs of the DOM, indexed by the paragraph root. `is_text_node` has to
+/// return whether a node is a text (a `NodeType::Label` or `NodeType::Text`).
+fn get_inline_formatting_contexts<F: Fn(NodeId) -> bool>(
+    node_hierarchy: &NodeHierarchy,
+    rect_layouts: &NodeDataContainer<RectLayout>,
+    is_text_node: F,
+) -> BTreeMap<NodeId, InlineFormattingContext>
+{
+    fn collect_inline_content<F: Fn(NodeId) -> bool>(
+        parent_id: NodeId,
+        node_hierarchy: &NodeHierarchy,
+        rect_layouts: &NodeDataContainer<RectLayout>,
+        is_text_node: &F,
+        context: &mut InlineFormattingContext,
+    ) {
+        for child_id in parent_id.children(node_hierarchy) {
+            let child_layout = &rect_layouts[child_id];
+            if child_layout.position.unwrap_or_default().is_out_of_flow() {
+                context.out_of_flow.push(child_id);
+            } else if child_layout.float.unwrap_or_default() != LayoutFloat::None {
+                context.floats.push(child_id);
+            } else if is_text_node(child_id) {
+                context.items.push(InlineContent::Text(child_id));
+            } else if child_layout.is_inline() && node_hierarchy[child_id].first_child.is_some() {
+                context.spans.push(child_id);
+                collect_inline_content(child_id, node_hierarchy, rect_layouts, is_text_node, context);
+            } else {
+                context.items.push(InlineContent::Box(child_id));
+            }
+        }
+    }
+
+    // Nodes that were removed from the hierarchy (i.e. because of `display: none`) aren't visited
+    NodeId::new(0).descendants(node_hierarchy)
+        .filter(|node_id| {
+            !is_inline_node(&rect_layouts[*node_id])
+            && node_id.children(node_hierarchy).any(|child_id| is_inline_node(&rect_layouts[child_id]))
+        })
+        .map(|root_id| {
+            let mut context = InlineFormattingContext::default();
+            collect_inline_content(root_id, node_hierarchy, rect_layouts, &is_text_node, &mut context);
+            (root_id, context)
+        })
+        .collect()
+}
+
+/// Returns the hierarchy that the flex solver lays out: the texts and spans of the paragraphs
+/// are unlinked, the boxes, floats and out-of-flow nodes become the only children of the paragraph
+/// root - their position is determined by the text layout afterwards.
+fn get_inline_layout_hierarchy(
+    node_hierarchy: &NodeHierarchy,
+    inline_contexts: &BTreeMap<NodeId, InlineFormattingContext>,
+) -> NodeHierarchy
+{
+    let mut flex_hierarchy = node_hierarchy.clone();
+
+    for (root_id, context) in inline_contexts {
+
+        let texts = context.items.iter().filter_map(|item| match item {
+            InlineContent::Text(node_id) => Some(*node_id),
+            InlineContent::Box(_) => None,
+        });
+
+        for node_id in texts.chain(context.spans.iter().cloned()) {
+            flex_hierarchy[node_id] = Node::default();
+        }
+
+        let mut children = context.boxes()
+            .chain(context.floats.iter().cloned())
+            .chain(context.out_of_flow.iter().cloned())
+            .collect::<Vec<NodeId>>();
+        children.sort();
+
+        flex_hierarchy[*root_id].first_child = children.first().cloned();
+        flex_hierarchy[*root_id].last_child = children.last().cloned();
+
+        for (child_idx, child_id) in children.iter().enumerate() {
+            let child = &mut flex_hierarchy[*child_id];
+            child.parent = Some(*root_id);
+            child.previous_sibling = child_idx.checked_sub(1).map(|previous_idx| children[previous_idx]);
+            child.next_sibling = children.get(child_idx + 1).cloned();
+        }
+    }
+
+    flex_hierarchy
+}
+
+/// Returns the layout that the flex solver uses for a box or float of a paragraph: the box is taken
+/// out of the flow and loses its insets and margin (the margin is part of the space that the box takes
+/// up in its line). Boxes without a `width` or `height` are sized to their content.
+fn get_inline_box_layout(layout: &RectLayout) -> RectLayout {
+    RectLayout {
+        width: Some(layout.width.unwrap_or(LayoutWidth::FitContent)),
+        height: Some(layout.height.unwrap_or(LayoutHeight::FitContent)),
+        position: Some(LayoutPosition::Absolute),
+        top: None,
+        bottom: None,
+        left: None,
+        right: None,
+        margin: None,
+        .. layout.clone()
+    }
+}
+
+/// Returns the layouts for the `get_inline_layout_hierarchy`: the boxes and floats are laid out with
+/// the `get_inline_box_layout`, the paragraph root is their containing block (`position: relative`).
+fn get_inline_flex_layouts(
+    rect_layouts: &NodeDataContainer<RectLayout>,
+    inline_contexts: &BTreeMap<NodeId, InlineFormattingContext>,
+) -> NodeDataContainer<RectLayout>
+{
+    let mut flex_layouts = rect_layouts.clone();
+
+    for (root_id, context) in inline_contexts {
+        if flex_layouts[*root_id].position.unwrap_or_default() == LayoutPosition::Static {
+            flex_layouts[*root_id].position = Some(LayoutPosition::Relative);
+        }
+        for box_id in context.boxes().chain(context.floats.iter().cloned()) {
+            let box_layout = get_inline_box_layout(&flex_layouts[box_id]);
+            flex_layouts[box_id] = box_layout;
+        }
+    }
+
+    flex_layouts
+}
+
+/// Places the floats of a paragraph at the top of the paragraph: `float: left` boxes are stacked
+/// from the left edge to the right, `float: right` boxes from the right edge to the left. A float
+/// that doesn't fit into the current row anymore starts a new row below the current row.
+///
+/// `floats` are the sizes of the floats including their margin, the returned rectangles are
+/// the holes that the lines of the paragraph have to flow around.
+fn place_floats(floats: &[(LayoutFloat, LayoutSize)], max_width: f32) -> Vec<LayoutRect> {
+
+    let mut holes = Vec::with_capacity(floats.len());
+
+    let mut row_top = 0.0;
+    let mut row_height = 0.0_f32;
+    let mut left_x = 0.0;
+    let mut right_x = max_width;
+
+    for (float, size) in floats {
+
+        // Every row holds at least one float
+        let row_is_empty = left_x == 0.0 && right_x == max_width;
+        if !row_is_empty && left_x + size.width > right_x {
+            row_top += row_height;
+            row_height = 0.0;
+            left_x = 0.0;
+            right_x = max_width;
+        }
+
+        let x = match float {
+            LayoutFloat::Right => {
+                right_x -= size.width;
+                right_x
+            },
+            _ => {
+                left_x += size.width;
+                left_x - size.width
+            },
+        };
+
+        row_height = row_height.max(size.height);
+        holes.push(LayoutRect::new(LayoutPoint::new(x, row_top), *size));
+    }
+
+    holes
+}
+
 // `typed_arena!(WidthCalculatedRect, preferred_width, determine_preferred_width, get_horizontal_padding, get_flex_basis_horizontal)`
 macro_rules! typed_arena {(
     $struct_name:ident,
@@ -271,16 +1298,17 @@ impl NodeDataContainer<$struct_name> {
     ///
     /// NOTE: Later on, this could maybe be a NodeDataContainer<&'a RectLayout>.
     #[must_use]
-    fn from_rect_layout_arena(node_data: &NodeDataContainer<RectLayout>, widths: &NodeDataContainer<Option<f32>>) -> Self {
+    fn from_rect_layout_arena(node_data: &NodeDataContainer<RectLayout>, widths: &NodeDataContainer<Option<IntrinsicSizes>>) -> Self {
         let new_nodes = node_data.internal.iter().enumerate().map(|(node_id, node_data)|{
             let id = NodeId::new(node_id);
             $struct_name {
-                // TODO: get the initial width of the rect content
+                // The initial width of the rect content, see `get_intrinsic_sizes`
                 $preferred_field: $determine_preferred_fn(&node_data, widths[id]),
                 margin: node_data.margin.unwrap_or_default(),
                 padding: node_data.padding.unwrap_or_default(),
                 flex_grow_px: 0.0,
                 min_inner_size_px: 0.0,
+                intrinsic_sizes: widths[id],
             }
         }).collect();
         NodeDataContainer { internal: new_nodes }
@@ -299,54 +1327,120 @@ impl NodeDataContainer<$struct_name> {
         //
         // Set the preferred_width of the parent nodes
         for (_node_depth, non_leaf_id) in non_leaf_nodes.iter().rev() {
+            self[*non_leaf_id].min_inner_size_px = self.get_min_inner_size(*non_leaf_id, node_hierarchy, arena_data);
+        }
+
+        // Now, the width of all elements should be filled,
+        // but they aren't flex-growed or flex-shrinked yet
+    }
+
+    /// Returns the inner size that a parent node needs to contain its children (if
+    /// that doesn't violate the constraints of the parent)
+    fn get_min_inner_size(
+        &self,
+        non_leaf_id: NodeId,
+        node_hierarchy: &NodeHierarchy,
+        arena_data: &NodeDataContainer<RectLayout>)
+    -> f32
+    {
+        use self::WhConstraint::*;
+
+        let parent_node = &arena_data[non_leaf_id];
+
+        // Sum of the direct children's flex-basis = the parents preferred width
+        //
+        // A multi-line container only has to be as large as its largest item along the
+        // main axis, since the remaining items can wrap into the next line. A grid container
+        // has to be as large as its tracks if they are sized to their content.
+        let children_flex_basis = if parent_node.is_grid_container() {
+            let placement = place_grid_items(non_leaf_id, node_hierarchy, arena_data);
+            solve_grid_tracks(parent_node, &placement, LayoutAxis::$main_axis, None, |child_id| self[child_id].$get_flex_basis()).get_total_size()
+        } else if is_multi_line(parent_node) && parent_node.direction.unwrap_or_default().get_axis() == LayoutAxis::$main_axis {
+            self.max_children_flex_basis(non_leaf_id, node_hierarchy, arena_data)
+        } else {
+            self.sum_children_flex_basis(non_leaf_id, node_hierarchy, arena_data)
+        };
 
-            use self::WhConstraint::*;
+        // Calculate the new flex-basis width
+        let parent_width_metrics = self[non_leaf_id];
 
-            // Sum of the direct children's flex-basis = the parents preferred width
-            let children_flex_basis = self.sum_children_flex_basis(*non_leaf_id, node_hierarchy, arena_data);
+        // For calculating the inner width, subtract the parents padding
+        let parent_padding = self[non_leaf_id].$get_padding_fn();
 
-            // Calculate the new flex-basis width
-            let parent_width_metrics = self[*non_leaf_id];
+        // If the children are larger than the parents preferred max-width or smaller
+        // than the parents min-width, adjust
+        match parent_width_metrics.$preferred_field {
+            Between(min, max) => {
+                if children_flex_basis > (max - parent_padding)  {
+                    max
+                } else if children_flex_basis < (min + parent_padding) {
+                    min
+                } else {
+                    children_flex_basis
+                }
+            },
+            EqualTo(exact) => exact - parent_padding,
+            Unconstrained => children_flex_basis,
+        }
+    }
 
-            // For calculating the inner width, subtract the parents padding
-            let parent_padding = self[*non_leaf_id].$get_padding_fn();
+    /// Resolves the percentages of the node along the current axis against the `percent_base`, the
+    /// final inner size of its containing block, and determines the constraints of the node again.
+    ///
+    /// While the sizes are bubbled up, the size of the containing block isn't known yet, so the
+    /// percentages are ignored until the parent of the node is solved (see `apply_flex_grow`).
+    fn resolve_percentages(
+        &mut self,
+        node_id: NodeId,
+        percent_base: f32,
+        node_hierarchy: &NodeHierarchy,
+        unresolved_layouts: &NodeDataContainer<RectLayout>,
+        arena_data: &mut NodeDataContainer<RectLayout>)
+    {
+        if !has_percentages_along_axis(&unresolved_layouts[node_id], LayoutAxis::$main_axis) {
+            return;
+        }
 
-            // If the children are larger than the parents preferred max-width or smaller
-            // than the parents min-width, adjust
-            let child_width = match parent_width_metrics.$preferred_field {
-                Between(min, max) => {
-                    if children_flex_basis > (max - parent_padding)  {
-                        max
-                    } else if children_flex_basis < (min + parent_padding) {
-                        min
-                    } else {
-                        children_flex_basis
-                    }
-                },
-                EqualTo(exact) => exact - parent_padding,
-                Unconstrained => children_flex_basis,
-            };
+        let layout = resolve_percentages_along_axis(&unresolved_layouts[node_id], LayoutAxis::$main_axis, Some(percent_base));
 
-            self[*non_leaf_id].min_inner_size_px = child_width;
+        {
+            let node = &mut self[node_id];
+            node.$preferred_field = $determine_preferred_fn(&layout, node.intrinsic_sizes);
+            node.margin = layout.margin.unwrap_or_default();
+            node.padding = layout.padding.unwrap_or_default();
         }
 
-        // Now, the width of all elements should be filled,
-        // but they aren't flex-growed or flex-shrinked yet
+        arena_data[node_id] = layout;
+
+        if node_hierarchy[node_id].first_child.is_some() {
+            self[node_id].min_inner_size_px = self.get_min_inner_size(node_id, node_hierarchy, arena_data);
+        }
     }
 
     /// Go from the root down and flex_grow the children if needed - respects the `width`, `min_width` and `max_width` properties
     /// The layout step doesn't account for the min_width and max_width constraints, so we have to adjust them manually
+    ///
+    /// `cross_axis_flex_lines` are the flex lines of the containers whose main axis has already been solved
+    /// (i.e. the lines of `flex-direction: row` containers when solving the heights). Returns the flex lines of the
+    /// multi-line containers whose main axis is the current axis and the grid tracks along the current axis.
+    ///
+    /// The percentages of the `unresolved_layouts` along the current axis are resolved on the way down, as soon
+    /// as the size of the containing block is final - the resolved layouts are written into the `arena_data`.
     fn apply_flex_grow(
         &mut self,
         node_hierarchy: &NodeHierarchy,
-        arena_data: &NodeDataContainer<RectLayout>,
+        unresolved_layouts: &NodeDataContainer<RectLayout>,
+        arena_data: &mut NodeDataContainer<RectLayout>,
         parent_ids_sorted_by_depth: &[(usize, NodeId)],
-        root_width: f32
-    ) {
-        use azul_css::LayoutAlignItems;
+        root_width: f32,
+        cross_axis_flex_lines: &FlexLines,
+    ) -> (FlexLines, Grids) {
 
         debug_assert!(self[NodeId::new(0)].flex_grow_px == 0.0);
 
+        // The containing block of the root node is the window
+        self.resolve_percentages(NodeId::new(0), root_width, node_hierarchy, unresolved_layouts, arena_data);
+
         // Set the window width on the root node (since there is only one root node, we can
         // calculate the `flex_grow_px` directly)
         //
@@ -363,20 +1457,37 @@ impl NodeDataContainer<$struct_name> {
 
         self[NodeId::new(0)].flex_grow_px = root_preferred_width - top_level_flex_basis;
 
-        // Keep track of the nearest relative or absolute positioned element
-        let mut positioned_node_stack = vec![NodeId::new(0)];
+        let mut flex_lines = FlexLines::new();
+        let mut grids = Grids::new();
 
         for (_node_depth, parent_id) in parent_ids_sorted_by_depth {
 
-            use azul_css::{LayoutAxis, LayoutPosition};
+            // Keep track of the nearest positioned ancestor of the children
+            let positioned_node_stack = get_positioned_node_stack(*parent_id, node_hierarchy, arena_data);
 
-            let parent_node = &arena_data[*parent_id];
-            let parent_is_positioned = parent_node.position.unwrap_or_default() != LayoutPosition::Static;
+            // Inner size of the parent along the axis that is currently being solved
+            let parent_inner_size = {
+                let parent_width_metrics = &self[*parent_id];
+                parent_width_metrics.min_inner_size_px + parent_width_metrics.flex_grow_px - parent_width_metrics.$get_padding_fn()
+            };
 
-            if parent_is_positioned {
-                positioned_node_stack.push(*parent_id);
+            // The parent (and all positioned ancestors) are solved, so the percentages of the children can be resolved
+            for child_id in parent_id.children(node_hierarchy) {
+                let percent_base = if arena_data[child_id].position.unwrap_or_default().is_out_of_flow() {
+                    let positioned_ancestor = &self[get_positioned_ancestor(&arena_data[child_id], &positioned_node_stack)];
+                    positioned_ancestor.min_inner_size_px + positioned_ancestor.flex_grow_px - positioned_ancestor.$get_padding_fn()
+                } else {
+                    parent_inner_size
+                };
+                self.resolve_percentages(child_id, percent_base, node_hierarchy, unresolved_layouts, arena_data);
             }
 
+            let arena_data = &*arena_data;
+            let parent_node = &arena_data[*parent_id];
+
+            // Space between two items (along the main axis) or two lines (along the cross axis)
+            let gap = get_gap_along_axis(parent_node, LayoutAxis::$main_axis, Some(parent_inner_size));
+
             // How much width is there to distribute along the main and cross axis?
             let (width_main_axis, width_cross_axis) = {
                 let parent_width_metrics = &self[*parent_id];
@@ -391,10 +1502,11 @@ impl NodeDataContainer<$struct_name> {
                     parent_width_metrics.min_inner_size_px + parent_width_metrics.flex_grow_px - parent_node.get_vertical_padding()
                 };
 
+                let in_flow_children = parent_id.children(node_hierarchy).filter(|child_id| !arena_data[*child_id].position.unwrap_or_default().is_out_of_flow()).count();
                 let width_main_axis = match LayoutAxis::$main_axis {
                     LayoutAxis::Horizontal => width_horizontal_axis,
                     LayoutAxis::Vertical => width_vertical_axis,
-                };
+                } - gap * in_flow_children.saturating_sub(1) as f32;
 
                 let width_cross_axis = match LayoutAxis::$main_axis {
                     LayoutAxis::Horizontal => width_vertical_axis,
@@ -404,48 +1516,171 @@ impl NodeDataContainer<$struct_name> {
                 (width_main_axis, width_cross_axis)
             };
 
-            // Only stretch the items, if they have a align-items: stretch!
-            if parent_node.align_items.unwrap_or_default() == LayoutAlignItems::Stretch {
-                if parent_node.direction.unwrap_or_default().get_axis() == LayoutAxis::$main_axis {
-                    Self::distribute_space_along_main_axis(parent_id, width_main_axis, node_hierarchy, arena_data, self, &positioned_node_stack);
+            // Grid containers size their items to the grid areas along both axes,
+            // independent of the `flex-direction` and `align-items`
+            if parent_node.is_grid_container() {
+                let placement = place_grid_items(*parent_id, node_hierarchy, arena_data);
+                let tracks = solve_grid_tracks(parent_node, &placement, LayoutAxis::$main_axis, Some(parent_inner_size), |child_id| self[child_id].$get_flex_basis());
+                Self::distribute_space_in_grid(&placement, &tracks, arena_data, self);
+                let absolute_children = get_absolute_children(*parent_id, node_hierarchy, arena_data);
+                Self::distribute_space_along_cross_axis(&absolute_children, 0.0, arena_data, self, &positioned_node_stack);
+                grids.insert(*parent_id, (placement, tracks));
+                continue;
+            }
+
+            let parent_is_along_main_axis = parent_node.direction.unwrap_or_default().get_axis() == LayoutAxis::$main_axis;
+
+            // Multi-line containers break their children into lines along the main axis
+            if parent_is_along_main_axis && is_multi_line(parent_node) {
+                let lines = break_into_flex_lines(*parent_id, node_hierarchy, arena_data, parent_inner_size, gap, |child_id| {
+                    self.get_item_flex_basis(child_id, arena_data, Some(parent_inner_size))
+                });
+                flex_lines.insert(*parent_id, lines);
+            }
+
+            if parent_is_along_main_axis {
+                if let Some(lines) = flex_lines.get(parent_id) {
+                    // Every line distributes its space separately
+                    for line in lines {
+                        let line_margin: f32 = line.iter().map(|child_id| get_margin_along_axis(&arena_data[*child_id], LayoutAxis::$main_axis)).sum();
+                        let line_gap = gap * line.len().saturating_sub(1) as f32;
+                        Self::distribute_space_along_main_axis(line, parent_inner_size - line_margin - line_gap, parent_inner_size, arena_data, self, &positioned_node_stack);
+                    }
+                    let absolute_children = get_absolute_children(*parent_id, node_hierarchy, arena_data);
+                    Self::distribute_space_along_main_axis(&absolute_children, 0.0, parent_inner_size, arena_data, self, &positioned_node_stack);
                 } else {
-                    Self::distribute_space_along_cross_axis(parent_id, width_cross_axis, node_hierarchy, arena_data, self, &positioned_node_stack);
+                    let children = parent_id.children(node_hierarchy).collect::<Vec<NodeId>>();
+                    Self::distribute_space_along_main_axis(&children, width_main_axis, parent_inner_size, arena_data, self, &positioned_node_stack);
+                }
+            } else if let Some(lines) = cross_axis_flex_lines.get(parent_id) {
+                // Items are stretched to the size of their line instead of the size of the container
+                let mut line_sizes = lines.iter().map(|line| {
+                    line.iter().map(|child_id| self[*child_id].$get_flex_basis()).fold(0.0, f32::max)
+                }).collect::<Vec<f32>>();
+                let line_gap = gap * lines.len().saturating_sub(1) as f32;
+                align_flex_lines(parent_node.align_content.unwrap_or_default(), &mut line_sizes, parent_inner_size - line_gap);
+                for (line, line_size) in lines.iter().zip(line_sizes.into_iter()) {
+                    Self::align_items_along_cross_axis(parent_node, line, line_size, arena_data, self, &positioned_node_stack);
                 }
+                let absolute_children = get_absolute_children(*parent_id, node_hierarchy, arena_data);
+                Self::distribute_space_along_cross_axis(&absolute_children, 0.0, arena_data, self, &positioned_node_stack);
+            } else if is_multi_line(parent_node) {
+                // The lines of this container are only known after its main axis has been solved,
+                // so the items can't be stretched to their line yet - size them to their content instead
+                let children = parent_id.children(node_hierarchy).collect::<Vec<NodeId>>();
+                Self::size_items_to_content(&children, self);
+            } else {
+                let children = parent_id.children(node_hierarchy).collect::<Vec<NodeId>>();
+                Self::align_items_along_cross_axis(parent_node, &children, width_cross_axis, arena_data, self, &positioned_node_stack);
             }
+        }
 
-            if parent_is_positioned {
-                positioned_node_stack.pop();
-            }
+        (flex_lines, grids)
+    }
+
+    /// Sizes the items of a grid container to their grid area along the current axis.
+    /// Items with a fixed size keep that size, items with a `max-width` / `max-height`
+    /// don't grow beyond that, items with a `min-width` / `min-height` can overflow the area.
+    fn distribute_space_in_grid(
+        placement: &GridPlacement,
+        tracks: &GridTracks,
+        arena_data: &NodeDataContainer<RectLayout>,
+        width_calculated_arena: &mut NodeDataContainer<$struct_name>)
+    {
+        for (child_id, area) in &placement.items {
+            let (start, end) = get_area_span(area, LayoutAxis::$main_axis);
+            let area_size = tracks.get_size(start, end) - get_margin_along_axis(&arena_data[*child_id], LayoutAxis::$main_axis);
+            let child = &mut width_calculated_arena[*child_id];
+            let item_size = match child.$preferred_field {
+                WhConstraint::EqualTo(exact) => exact,
+                constraint => {
+                    let max_size = constraint.max_available_space().map(|max| max.min(area_size)).unwrap_or(area_size);
+                    max_size.max(constraint.min_needed_space().unwrap_or(0.0))
+                },
+            };
+            // so that node.min_inner_size_px + node.flex_grow_px = item_size
+            child.flex_grow_px = item_size - child.min_inner_size_px;
+        }
+    }
+
+    /// Returns the size of an item along the main axis of its parent, including its margin and padding.
+    /// Starts from the `flex-basis` of the item instead of its preferred size, if the basis is definite.
+    fn get_item_flex_basis(
+        &self,
+        child_id: NodeId,
+        display_arena: &NodeDataContainer<RectLayout>,
+        percent_base: Option<f32>)
+    -> f32
+    {
+        let item = &self[child_id];
+        match get_flex_basis(&display_arena[child_id], LayoutAxis::$main_axis, percent_base) {
+            Some(basis) => item.$get_flex_basis() - item.$preferred_field.min_needed_space().unwrap_or(0.0) + basis,
+            None => item.$get_flex_basis(),
         }
     }
 
-    /// Returns the sum of the flex-basis of the current nodes' children
+    /// Returns the sum of the flex-basis of the current nodes' children - if the children are
+    /// laid out along the current axis, this includes the `gap` between them
     fn sum_children_flex_basis(
         &self,
         node_id: NodeId,
         node_hierarchy: &NodeHierarchy,
         display_arena: &NodeDataContainer<RectLayout>)
     -> f32
+    {
+        let parent_node = &display_arena[node_id];
+        let is_along_main_axis = parent_node.direction.unwrap_or_default().get_axis() == LayoutAxis::$main_axis;
+
+        let children = node_id
+            .children(node_hierarchy)
+            .filter(|child_node_id| !display_arena[*child_node_id].position.unwrap_or_default().is_out_of_flow())
+            .collect::<Vec<NodeId>>();
+
+        if !is_along_main_axis {
+            return children.iter().map(|child_node_id| self[*child_node_id].$get_flex_basis()).sum();
+        }
+
+        let gap = get_gap_along_axis(parent_node, LayoutAxis::$main_axis, None);
+        children.iter().map(|child_node_id| self.get_item_flex_basis(*child_node_id, display_arena, None)).sum::<f32>()
+        + gap * children.len().saturating_sub(1) as f32
+    }
+
+    /// Returns the largest flex-basis of the current nodes' children
+    fn max_children_flex_basis(
+        &self,
+        node_id: NodeId,
+        node_hierarchy: &NodeHierarchy,
+        display_arena: &NodeDataContainer<RectLayout>)
+    -> f32
     {
         node_id
             .children(node_hierarchy)
-            .filter(|child_node_id| display_arena[*child_node_id].position != Some(LayoutPosition::Absolute))
-            .map(|child_node_id| self[child_node_id].$get_flex_basis())
-            .sum()
+            .filter(|child_node_id| !display_arena[*child_node_id].position.unwrap_or_default().is_out_of_flow())
+            .map(|child_node_id| self.get_item_flex_basis(child_node_id, display_arena, None))
+            .fold(0.0, f32::max)
     }
 
     /// Does the actual width layout, respects the `width`, `min_width` and `max_width`
-    /// properties as well as the `flex_grow` factor. `flex_shrink` currently does nothing.
+    /// properties as well as the `flex_grow` factor and the `flex-basis`. `flex_shrink` currently does nothing.
+    ///
+    /// `children` are the children of a single flex line (or all children of a single-line container),
+    /// `percent_base` is the inner size of the parent, which percentages of the `flex-basis` refer to.
     fn distribute_space_along_main_axis(
-        node_id: &NodeId,
+        children: &[NodeId],
         width_to_distribute: f32,
-        node_hierarchy: &NodeHierarchy,
+        percent_base: f32,
         arena_data: &NodeDataContainer<RectLayout>,
         width_calculated_arena: &mut NodeDataContainer<$struct_name>,
         positioned_node_stack: &[NodeId]
     ) {
         let mut parent_node_inner_width = width_to_distribute;
 
+        // Items with a definite `flex-basis` start at that basis instead of their `width`
+        let flex_bases = children
+            .iter()
+            .filter_map(|id| get_flex_basis(&arena_data[*id], LayoutAxis::$main_axis, Some(percent_base)).map(|basis| (*id, basis)))
+            .collect::<BTreeMap<NodeId, f32>>();
+
         // 1. Set all child elements that have an exact width to that width, record their violations
         //    and add their violation to the leftover horizontal space.
         // let mut horizontal_space_from_fixed_width_items = 0.0;
@@ -453,8 +1688,10 @@ impl NodeDataContainer<$struct_name> {
 
         {
             // Vec<(NodeId, PreferredWidth)>
-            let exact_width_childs = node_id
-                    .children(node_hierarchy)
+            let exact_width_childs = children
+                    .iter()
+                    .cloned()
+                    .filter(|id| !flex_bases.contains_key(id))
                     .filter_map(|id| if let WhConstraint::EqualTo(exact) = width_calculated_arena[id].$preferred_field {
                         Some((id, exact))
                     } else {
@@ -464,9 +1701,9 @@ impl NodeDataContainer<$struct_name> {
 
             for (exact_width_child_id, exact_width) in exact_width_childs {
 
-                // If this child node is `position: absolute`, it doesn't take any space away from
+                // If this child node is `position: absolute` or `fixed`, it doesn't take any space away from
                 // its siblings, since it is taken out of the regular content flow
-                if arena_data[exact_width_child_id].position.unwrap_or_default() != LayoutPosition::Absolute {
+                if !arena_data[exact_width_child_id].position.unwrap_or_default().is_out_of_flow() {
                     horizontal_space_taken_up_by_fixed_width_items += exact_width;
                 }
 
@@ -482,75 +1719,46 @@ impl NodeDataContainer<$struct_name> {
         // Now we can be sure that if we write #x { width: 500px; } that it will actually be 500px large
         // and not be influenced by flex in any way.
 
-        // 2. Set all items to their minimum width. Record how much space is gained by doing so.
+        // 2. Set all items to their minimum width (or their flex-basis). Record how much space is gained by doing so.
         let mut horizontal_space_taken_up_by_variable_items = 0.0;
 
-        use FastHashSet;
+        // The size that every variable item starts to grow from and the size that it can't grow beyond
+        let mut variable_width_childs = BTreeMap::<NodeId, (f32, Option<f32>)>::new();
 
-        let mut variable_width_childs = node_id
-            .children(node_hierarchy)
-            .filter(|id| !width_calculated_arena[*id].$preferred_field.is_fixed_constraint())
-            .collect::<FastHashSet<NodeId>>();
+        for variable_child_id in children.iter().cloned() {
 
-        let mut absolute_variable_width_nodes = Vec::new();
+            let preferred_width = width_calculated_arena[variable_child_id].$preferred_field;
 
-        for variable_child_id in &variable_width_childs {
+            if preferred_width.is_fixed_constraint() && !flex_bases.contains_key(&variable_child_id) {
+                continue;
+            }
+
+            if !arena_data[variable_child_id].position.unwrap_or_default().is_out_of_flow() {
 
-            if arena_data[*variable_child_id].position.unwrap_or_default() != LayoutPosition::Absolute {
+                // The `width` doesn't limit an item with a `flex-basis`, only its `min-width` and `max-width` do
+                let (start_width, max_width) = match flex_bases.get(&variable_child_id) {
+                    Some(basis) => (*basis, get_min_max_along_axis(&arena_data[variable_child_id], LayoutAxis::$main_axis).1),
+                    None => (preferred_width.min_needed_space().unwrap_or(0.0), preferred_width.max_available_space()),
+                };
 
-                let min_width = width_calculated_arena[*variable_child_id].$preferred_field.min_needed_space().unwrap_or(0.0);
+                horizontal_space_taken_up_by_variable_items += start_width;
 
-                horizontal_space_taken_up_by_variable_items += min_width;
+                // so that node.min_inner_size_px + node.flex_grow_px = start_width
+                width_calculated_arena[variable_child_id].flex_grow_px =
+                    start_width - width_calculated_arena[variable_child_id].min_inner_size_px;
 
-                // so that node.min_inner_size_px + node.flex_grow_px = min_width
-                width_calculated_arena[*variable_child_id].flex_grow_px =
-                    min_width - width_calculated_arena[*variable_child_id].min_inner_size_px;
+                variable_width_childs.insert(variable_child_id, (start_width, max_width));
 
             } else {
 
                 // `position: absolute` items don't take space away from their siblings, rather
-                // they take the minimum needed space by their content
-
-                let root_id = NodeId::new(0);
-                let nearest_relative_parent_node = positioned_node_stack.get(positioned_node_stack.len() - 1).unwrap_or(&root_id);
-                let relative_parent_width = {
-                    let relative_parent_node = &width_calculated_arena[*nearest_relative_parent_node];
-                    relative_parent_node.flex_grow_px + relative_parent_node.min_inner_size_px
-                };
-
-                // By default, absolute positioned elements take the width of their content
-                // let min_inner_width = width_calculated_arena[*variable_child_id].$preferred_field.min_needed_space().unwrap_or(0.0);
-
-                // The absolute positioned node might have a max-width constraint, which has a
-                // higher precedence than `top, bottom, left, right`.
-                let max_space_current_node = match width_calculated_arena[*variable_child_id].$preferred_field {
-                    WhConstraint::EqualTo(e) => e,
-                    WhConstraint::Between(min, max) => {
-                        if relative_parent_width > min {
-                            if relative_parent_width < max {
-                                relative_parent_width
-                            } else {
-                                max
-                            }
-                        } else {
-                            min
-                        }
-                    },
-                    WhConstraint::Unconstrained => relative_parent_width,
-                };
+                // they take the space of their positioned ancestor
+                let max_space_current_node = Self::get_out_of_flow_item_size(variable_child_id, arena_data, width_calculated_arena, positioned_node_stack);
 
                 // so that node.min_inner_size_px + node.flex_grow_px = max_space_current_node
-                width_calculated_arena[*variable_child_id].flex_grow_px =
-                    max_space_current_node - width_calculated_arena[*variable_child_id].min_inner_size_px;
-
-                absolute_variable_width_nodes.push(*variable_child_id);
+                width_calculated_arena[variable_child_id].flex_grow_px =
+                    max_space_current_node - width_calculated_arena[variable_child_id].min_inner_size_px;
             }
-
-        }
-
-        // Absolute positioned nodes aren't in the space-to-distribute set
-        for absolute_node in absolute_variable_width_nodes {
-            variable_width_childs.remove(&absolute_node);
         }
 
         // This satisfies the `width` and `min_width` constraints. However, we still need to worry about
@@ -574,16 +1782,16 @@ impl NodeDataContainer<$struct_name> {
             //
             // NOTE: variable_width_childs can change its length, have to recalculate every loop!
             let children_combined_flex_grow: f32 = variable_width_childs
-                .iter()
-                .map(|child_id|
-                        // Prevent flex-grow and flex-shrink to be less than 1
-                        arena_data[*child_id].flex_grow
-                            .map(|grow| grow.0.get().max(1.0))
-                            .unwrap_or(DEFAULT_FLEX_GROW_FACTOR))
+                .keys()
+                .map(|child_id| arena_data[*child_id].flex_grow.map(|grow| grow.0.get()).unwrap_or(DEFAULT_FLEX_GROW_FACTOR))
+                // Items with "flex-grow: 0" don't grow, so they don't get a share of the space
+                .filter(|flex_grow| *flex_grow as usize != 0)
+                // Prevent flex-grow and flex-shrink to be less than 1
+                .map(|flex_grow| flex_grow.max(1.0))
                 .sum();
 
             // Grow all variable children by the same amount.
-            for variable_child_id in &variable_width_childs {
+            for (variable_child_id, (start_width, max_width)) in &variable_width_childs {
 
                 let flex_grow = arena_data[*variable_child_id].flex_grow
                     .and_then(|grow| Some(grow.0.get()))
@@ -596,26 +1804,17 @@ impl NodeDataContainer<$struct_name> {
 
                 let added_space_for_one_child = total_horizontal_space_available * (flex_grow / children_combined_flex_grow);
 
-                let current_width_of_child = width_calculated_arena[*variable_child_id].min_inner_size_px +
-                                             width_calculated_arena[*variable_child_id].flex_grow_px;
-
-                if let Some(max_width) = width_calculated_arena[*variable_child_id].$preferred_field.max_available_space() {
-                    if (current_width_of_child + added_space_for_one_child) > max_width {
-                        // so that node.min_inner_size_px + node.flex_grow_px = max_width
-                        width_calculated_arena[*variable_child_id].flex_grow_px =
-                            max_width - width_calculated_arena[*variable_child_id].min_inner_size_px;
+                let new_width_of_child = match max_width {
+                    Some(max_width) if (start_width + added_space_for_one_child) > *max_width => {
+                        max_width_violations.push((*variable_child_id, max_width - start_width));
+                        *max_width
+                    },
+                    _ => start_width + added_space_for_one_child,
+                };
 
-                        max_width_violations.push(*variable_child_id);
-                    } else {
-                        // so that node.min_inner_size_px + node.flex_grow_px = added_space_for_one_child
-                        width_calculated_arena[*variable_child_id].flex_grow_px =
-                            added_space_for_one_child - width_calculated_arena[*variable_child_id].min_inner_size_px;
-                    }
-                } else {
-                    // so that node.min_inner_size_px + node.flex_grow_px = added_space_for_one_child
-                    width_calculated_arena[*variable_child_id].flex_grow_px =
-                        added_space_for_one_child - width_calculated_arena[*variable_child_id].min_inner_size_px;
-                }
+                // so that node.min_inner_size_px + node.flex_grow_px = new_width_of_child
+                width_calculated_arena[*variable_child_id].flex_grow_px =
+                    new_width_of_child - width_calculated_arena[*variable_child_id].min_inner_size_px;
             }
 
             // If we haven't violated any max_width constraints, then we have
@@ -627,12 +1826,10 @@ impl NodeDataContainer<$struct_name> {
                 // so we remove them from the solution and consider them "solved".
                 // Their amount of violation then gets distributed across the remaining
                 // items in the next iteration.
-                for solved_node_id in max_width_violations.drain(..) {
+                for (solved_node_id, space_added) in max_width_violations.drain(..) {
 
                     // Since the node now gets removed, it doesn't contribute to the pool anymore
-                    total_horizontal_space_available -=
-                        width_calculated_arena[solved_node_id].min_inner_size_px +
-                        width_calculated_arena[solved_node_id].flex_grow_px;
+                    total_horizontal_space_available -= space_added;
 
                     variable_width_childs.remove(&solved_node_id);
                 }
@@ -640,30 +1837,97 @@ impl NodeDataContainer<$struct_name> {
         }
     }
 
-    fn distribute_space_along_cross_axis(
-        node_id: &NodeId,
+    /// Sizes the `children` along the cross axis of their parent: items with `align-self: stretch`
+    /// (or `align-items: stretch` on the parent) fill the `width_to_distribute`, which is the size
+    /// of their line or container. All other items are only as large as their content.
+    fn align_items_along_cross_axis(
+        parent_node: &RectLayout,
+        children: &[NodeId],
         width_to_distribute: f32,
-        node_hierarchy: &NodeHierarchy,
         arena_data: &NodeDataContainer<RectLayout>,
         width_calculated_arena: &mut NodeDataContainer<$struct_name>,
         positioned_node_stack: &[NodeId])
     {
-        let parent_node_inner_width = width_to_distribute;
+        let (stretched_children, aligned_children): (Vec<NodeId>, Vec<NodeId>) = children.iter().cloned().partition(|child_id| {
+            arena_data[*child_id].position.unwrap_or_default().is_out_of_flow() ||
+            get_align_self(&arena_data[*child_id], parent_node) == LayoutAlignItems::Stretch
+        });
+
+        Self::distribute_space_along_cross_axis(&stretched_children, width_to_distribute, arena_data, width_calculated_arena, positioned_node_stack);
+        Self::size_items_to_content(&aligned_children, width_calculated_arena);
+    }
+
+    /// Sizes the `children` to their content (or their fixed size)
+    fn size_items_to_content(
+        children: &[NodeId],
+        width_calculated_arena: &mut NodeDataContainer<$struct_name>)
+    {
+        for child_id in children {
+            let child = &mut width_calculated_arena[*child_id];
+            let content_size = child.$preferred_field.min_needed_space().unwrap_or(0.0).max(child.min_inner_size_px);
+            // so that node.min_inner_size_px + node.flex_grow_px = content_size
+            child.flex_grow_px = content_size - child.min_inner_size_px;
+        }
+    }
+
+    /// Returns the size of a `position: absolute` or `position: fixed` item: it takes the inner size
+    /// of its positioned ancestor (see `get_positioned_ancestor`), minus the `left` and `right`
+    /// (or `top` and `bottom`) offsets if both are set. The `width` / `height` and its `min-` and
+    /// `max-` constraints have a higher precedence than the offsets.
+    fn get_out_of_flow_item_size(
+        child_id: NodeId,
+        arena_data: &NodeDataContainer<RectLayout>,
+        width_calculated_arena: &NodeDataContainer<$struct_name>,
+        positioned_node_stack: &[NodeId])
+    -> f32
+    {
+        let child_node = &arena_data[child_id];
 
-        let last_relative_node_width = {
-            let zero_node = NodeId::new(0);
-            let last_relative_node_id = positioned_node_stack.get(positioned_node_stack.len() - 1).unwrap_or(&zero_node);
-            let last_relative_node = &width_calculated_arena[*last_relative_node_id];
-            last_relative_node.min_inner_size_px + last_relative_node.flex_grow_px - last_relative_node.$get_padding_fn()
+        let positioned_ancestor_width = {
+            let positioned_ancestor = &width_calculated_arena[get_positioned_ancestor(child_node, positioned_node_stack)];
+            positioned_ancestor.min_inner_size_px + positioned_ancestor.flex_grow_px - positioned_ancestor.$get_padding_fn()
         };
 
-        for child_id in node_id.children(node_hierarchy) {
+        let available_width = match get_insets_along_axis(child_node, LayoutAxis::$main_axis) {
+            (Some(start), Some(end)) => positioned_ancestor_width - start - end - get_margin_along_axis(child_node, LayoutAxis::$main_axis),
+            _ => positioned_ancestor_width,
+        };
 
-            let parent_node_inner_width = if arena_data[child_id].position.unwrap_or_default() != LayoutPosition::Absolute {
-                parent_node_inner_width
-            } else {
-                last_relative_node_width
-            };
+        match width_calculated_arena[child_id].$preferred_field {
+            WhConstraint::EqualTo(e) => e,
+            WhConstraint::Between(min, max) => {
+                if available_width > min {
+                    if available_width < max {
+                        available_width
+                    } else {
+                        max
+                    }
+                } else {
+                    min
+                }
+            },
+            WhConstraint::Unconstrained => available_width,
+        }
+    }
+
+    fn distribute_space_along_cross_axis(
+        children: &[NodeId],
+        width_to_distribute: f32,
+        arena_data: &NodeDataContainer<RectLayout>,
+        width_calculated_arena: &mut NodeDataContainer<$struct_name>,
+        positioned_node_stack: &[NodeId])
+    {
+        let parent_node_inner_width = width_to_distribute;
+
+        for child_id in children.iter().cloned() {
+
+            if arena_data[child_id].position.unwrap_or_default().is_out_of_flow() {
+                let preferred_width = Self::get_out_of_flow_item_size(child_id, arena_data, width_calculated_arena, positioned_node_stack);
+                // so that node.min_inner_size_px + node.flex_grow_px = preferred_width
+                width_calculated_arena[child_id].flex_grow_px =
+                    preferred_width - width_calculated_arena[child_id].min_inner_size_px;
+                continue;
+            }
 
             let preferred_width = {
                 let min_width = width_calculated_arena[child_id].$preferred_field.min_needed_space().unwrap_or(0.0);
@@ -741,42 +2005,60 @@ pub(crate) struct SolvedWidthLayout {
     pub solved_widths: NodeDataContainer<WidthSolvedResult>,
     pub layout_only_arena: NodeDataContainer<RectLayout>,
     pub non_leaf_nodes_sorted_by_depth: Vec<(usize, NodeId)>,
+    /// Lines of the multi-line containers with a horizontal main axis
+    pub flex_lines: FlexLines,
+    /// Placement and column sizes of the grid containers
+    pub grids: Grids,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct SolvedHeightLayout {
     pub solved_heights: NodeDataContainer<HeightSolvedResult>,
+    pub layout_only_arena: NodeDataContainer<RectLayout>,
+    /// Lines of the multi-line containers with a vertical main axis
+    pub flex_lines: FlexLines,
+    /// Placement and row sizes of the grid containers
+    pub grids: Grids,
 }
 
 /// Returns the solved widths of the items in a BTree form
-pub(crate) fn solve_flex_layout_width<'a>(
+///
+/// The horizontal percentages of the `rect_layouts` are resolved while solving the widths,
+/// the resolved layouts are returned in the `layout_only_arena`.
+pub(crate) fn solve_flex_layout_width(
     node_hierarchy: &NodeHierarchy,
-    display_rectangles: &NodeDataContainer<DisplayRectangle<'a>>,
-    preferred_widths: &NodeDataContainer<Option<f32>>,
+    rect_layouts: &NodeDataContainer<RectLayout>,
+    preferred_widths: &NodeDataContainer<Option<IntrinsicSizes>>,
     window_width: f32
 ) -> SolvedWidthLayout {
-    let layout_only_arena = display_rectangles.transform(|node, _| node.layout);
-    let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&layout_only_arena, preferred_widths);
+    let mut layout_only_arena = rect_layouts.transform(|layout, _| resolve_percentages_along_axis(layout, LayoutAxis::Horizontal, None));
     let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+    let preferred_widths = get_intrinsic_sizes(node_hierarchy, &layout_only_arena, preferred_widths, &non_leaf_nodes_sorted_by_depth, LayoutAxis::Horizontal, determine_preferred_width);
+    let mut width_calculated_arena = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&layout_only_arena, &preferred_widths);
     width_calculated_arena.bubble_preferred_widths_to_parents(node_hierarchy, &layout_only_arena, &non_leaf_nodes_sorted_by_depth);
-    width_calculated_arena.apply_flex_grow(node_hierarchy, &layout_only_arena, &non_leaf_nodes_sorted_by_depth, window_width);
+    // The lines of `flex-direction: column` containers are only known after the heights are solved
+    let (flex_lines, grids) = width_calculated_arena.apply_flex_grow(node_hierarchy, rect_layouts, &mut layout_only_arena, &non_leaf_nodes_sorted_by_depth, window_width, &FlexLines::new());
     let solved_widths = width_calculated_arena.transform(|node, _| node.solved_result());
-    SolvedWidthLayout { solved_widths , layout_only_arena, non_leaf_nodes_sorted_by_depth }
+    SolvedWidthLayout { solved_widths , layout_only_arena, non_leaf_nodes_sorted_by_depth, flex_lines, grids }
 }
 
 /// Returns the solved height of the items in a BTree form
+///
+/// The vertical percentages of the layouts are resolved while solving the heights,
+/// the resolved layouts are returned in the `layout_only_arena`.
 pub(crate) fn solve_flex_layout_height(
     node_hierarchy: &NodeHierarchy,
     solved_widths: &SolvedWidthLayout,
-    preferred_heights: &NodeDataContainer<Option<f32>>,
+    preferred_heights: &NodeDataContainer<Option<IntrinsicSizes>>,
     window_height: f32
 ) -> SolvedHeightLayout {
-    let SolvedWidthLayout { layout_only_arena, .. } = solved_widths;
-    let mut height_calculated_arena = NodeDataContainer::<HeightCalculatedRect>::from_rect_layout_arena(&layout_only_arena, preferred_heights);
+    let mut layout_only_arena = solved_widths.layout_only_arena.transform(|layout, _| resolve_percentages_along_axis(layout, LayoutAxis::Vertical, None));
+    let preferred_heights = get_intrinsic_sizes(node_hierarchy, &layout_only_arena, preferred_heights, &solved_widths.non_leaf_nodes_sorted_by_depth, LayoutAxis::Vertical, determine_preferred_height);
+    let mut height_calculated_arena = NodeDataContainer::<HeightCalculatedRect>::from_rect_layout_arena(&layout_only_arena, &preferred_heights);
     height_calculated_arena.bubble_preferred_heights_to_parents(node_hierarchy, &layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth);
-    height_calculated_arena.apply_flex_grow(node_hierarchy, &layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, window_height);
+    let (flex_lines, grids) = height_calculated_arena.apply_flex_grow(node_hierarchy, &solved_widths.layout_only_arena, &mut layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, window_height, &solved_widths.flex_lines);
     let solved_heights = height_calculated_arena.transform(|node, _| node.solved_result());
-    SolvedHeightLayout { solved_heights }
+    SolvedHeightLayout { solved_heights, layout_only_arena, flex_lines, grids }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
@@ -797,11 +2079,15 @@ macro_rules! get_position {
 ) => (
 
 /// Traverses along the DOM and solve for the X or Y position
+///
+/// `cross_axis_flex_lines` are the flex lines of the multi-line containers
+/// whose cross axis is the axis that is being solved.
 fn $fn_name(
     node_hierarchy: &NodeHierarchy,
     node_data: &NodeDataContainer<RectLayout>,
     non_leaf_nodes: &[(usize, NodeId)],
-    solved_widths: &$width_layout)
+    solved_widths: &$width_layout,
+    cross_axis_flex_lines: &FlexLines)
 -> NodeDataContainer<$height_solved_position>
 {
     fn determine_child_x_absolute(
@@ -821,10 +2107,9 @@ fn $fn_name(
         let child_margin_left = child_margin.$left.map(|x| x.to_pixels()).unwrap_or(0.0);
         let child_margin_right = child_margin.$right.map(|x| x.to_pixels()).unwrap_or(0.0);
 
-        let zero_node = NodeId::new(0);
-        let last_relative_node_id = positioned_node_stack.get(positioned_node_stack.len() - 1).unwrap_or(&zero_node);
+        let last_relative_node_id = &get_positioned_ancestor(child_node, positioned_node_stack);
 
-        let last_relative_node = arena_data[*last_relative_node_id];
+        let last_relative_node = &arena_data[*last_relative_node_id];
         let last_relative_padding = last_relative_node.padding.unwrap_or_default();
         let last_relative_padding_left = last_relative_padding.$left.map(|x| x.to_pixels()).unwrap_or(0.0);
         let last_relative_padding_right = last_relative_padding.$right.map(|x| x.to_pixels()).unwrap_or(0.0);
@@ -835,40 +2120,35 @@ fn $fn_name(
             last_relative_node.$min_width + last_relative_node.space_added - (last_relative_padding_left + last_relative_padding_right)
         };
 
-        let child_left = &arena_data[child_id].$left.map(|s| s.0.to_pixels());
-        let child_right = &arena_data[child_id].$right.map(|s| s.0.to_pixels());
-
-        if let Some(child_right) = child_right {
-            // align right / bottom of last relative parent
-            arena_solved_data[child_id].0 =
-                last_relative_node_x
-                + last_relative_node_inner_width
-                - child_width_with_padding
-                - child_margin_right
-                - child_right;
-        } else {
-            // align left / top of last relative parent
-            arena_solved_data[child_id].0 =
-                last_relative_node_x
-                + child_margin_left
-                + child_left.unwrap_or(0.0);
+        match get_insets_along_axis(child_node, LayoutAxis::$axis) {
+            (None, Some(child_right)) => {
+                // align right / bottom of last relative parent
+                arena_solved_data[child_id].0 =
+                    last_relative_node_x
+                    + last_relative_node_inner_width
+                    - child_width_with_padding
+                    - child_margin_right
+                    - child_right;
+            },
+            (child_left, _) => {
+                // align left / top of last relative parent, `left` wins if both are set
+                arena_solved_data[child_id].0 =
+                    last_relative_node_x
+                    + child_margin_left
+                    + child_left.unwrap_or(0.0);
+            },
         }
     }
 
     fn determine_child_x_along_main_axis(
-        parent_id: NodeId,
-        main_axis_alignment: LayoutJustifyContent,
         arena_data: &NodeDataContainer<RectLayout>,
         arena_solved_data: &mut NodeDataContainer<$height_solved_position>,
         solved_widths: &$width_layout,
         child_id: NodeId,
         parent_x_position: f32,
-        parent_inner_width: f32,
         sum_x_of_children_so_far: &mut f32,
         positioned_node_stack: &[NodeId],
     ) {
-        use azul_css::LayoutJustifyContent::*;
-
         let child_width_with_padding = {
             let child_node = &solved_widths.$solved_widths_field[child_id];
             child_node.$min_width + child_node.space_added
@@ -880,7 +2160,7 @@ fn $fn_name(
         let child_margin_left = child_margin.$left.map(|x| x.to_pixels()).unwrap_or(0.0);
         let child_margin_right = child_margin.$right.map(|x| x.to_pixels()).unwrap_or(0.0);
 
-        if child_node.position.unwrap_or_default() == LayoutPosition::Absolute {
+        if child_node.position.unwrap_or_default().is_out_of_flow() {
             determine_child_x_absolute(
                 child_id,
                 positioned_node_stack,
@@ -889,42 +2169,31 @@ fn $fn_name(
                 solved_widths
             );
         } else {
-            // X position of the top left corner
-            // WARNING: End has to be added after all children!
-            let x_of_top_left_corner = match main_axis_alignment {
-                Start | End => {
-                    parent_x_position + *sum_x_of_children_so_far + child_margin_left
-                },
-                Center => {
-                    parent_x_position
-                    + ((parent_inner_width / 2.0)
-                    - ((*sum_x_of_children_so_far + child_margin_right + child_width_with_padding) / 2.0))
-                },
-                SpaceBetween => {
-                    parent_x_position // TODO!
-                },
-                SpaceAround => {
-                    parent_x_position // TODO!
-                },
-            };
-
-            arena_solved_data[child_id].0 = x_of_top_left_corner;
+            // X position of the top left corner, the `justify-content` offset
+            // is already included in the `sum_x_of_children_so_far`
+            arena_solved_data[child_id].0 = parent_x_position + *sum_x_of_children_so_far + child_margin_left;
             *sum_x_of_children_so_far += child_margin_right + child_width_with_padding + child_margin_left;
         }
     }
 
+    /// `line_width` is the size of the line (or the inner size of the parent) along the cross axis,
+    /// which the item is aligned in according to its `cross_axis_alignment`
     fn determine_child_x_along_cross_axis(
         arena_data: &NodeDataContainer<RectLayout>,
         solved_widths: &$width_layout,
         child_id: NodeId,
         positioned_node_stack: &[NodeId],
         arena_solved_data: &mut NodeDataContainer<$height_solved_position>,
-        parent_x_position: f32)
+        parent_x_position: f32,
+        line_width: f32,
+        cross_axis_alignment: LayoutAlignItems)
     {
         let child_node = &arena_data[child_id];
-        let child_margin_left = child_node.margin.unwrap_or_default().$left.map(|x| x.to_pixels()).unwrap_or(0.0);
+        let child_margin = child_node.margin.unwrap_or_default();
+        let child_margin_left = child_margin.$left.map(|x| x.to_pixels()).unwrap_or(0.0);
+        let child_margin_right = child_margin.$right.map(|x| x.to_pixels()).unwrap_or(0.0);
 
-        if child_node.position.unwrap_or_default() == LayoutPosition::Absolute {
+        if child_node.position.unwrap_or_default().is_out_of_flow() {
             determine_child_x_absolute(
                 child_id,
                 positioned_node_stack,
@@ -933,7 +2202,18 @@ fn $fn_name(
                 solved_widths
             );
         } else {
-            arena_solved_data[child_id].0 = parent_x_position + child_margin_left;
+            let child_width_with_margin = {
+                let child_node = &solved_widths.$solved_widths_field[child_id];
+                child_node.$min_width + child_node.space_added + child_margin_left + child_margin_right
+            };
+
+            let alignment_offset = match cross_axis_alignment {
+                LayoutAlignItems::Center => (line_width - child_width_with_margin) / 2.0,
+                LayoutAlignItems::End => line_width - child_width_with_margin,
+                LayoutAlignItems::Start | LayoutAlignItems::Stretch => 0.0,
+            };
+
+            arena_solved_data[child_id].0 = parent_x_position + alignment_offset + child_margin_left;
         }
     }
 
@@ -941,12 +2221,9 @@ fn $fn_name(
 
     let mut arena_solved_data = NodeDataContainer::new(vec![$height_solved_position(0.0); node_data.len()]);
 
-    // Stack of the positioned nodes (nearest relative or absolute positioned node)
-    let mut positioned_node_stack = vec![NodeId::new(0)];
-
     for (_node_depth, parent_id) in non_leaf_nodes {
 
-        let parent_node = node_data[*parent_id];
+        let parent_node = &node_data[*parent_id];
 
         let parent_padding = parent_node.padding.unwrap_or_default();
         let parent_padding_left = parent_padding.$left.map(|x| x.to_pixels()).unwrap_or(0.0);
@@ -955,67 +2232,154 @@ fn $fn_name(
         let parent_x_position = arena_solved_data[*parent_id].0 + parent_padding_left;
         let parent_direction = parent_node.direction.unwrap_or_default();
 
-        // Push nearest relative or absolute positioned element
-        let parent_is_positioned = parent_node.position.unwrap_or_default() != LayoutPosition::Static;
-        if parent_is_positioned {
-            positioned_node_stack.push(*parent_id);
-        }
+        // Stack of the positioned nodes (nearest relative, absolute, fixed or sticky positioned node)
+        let positioned_node_stack = get_positioned_node_stack(*parent_id, node_hierarchy, node_data);
 
         let parent_inner_width = {
             let parent_node = &solved_widths.$solved_widths_field[*parent_id];
             parent_node.$min_width + parent_node.space_added - (parent_padding_left + parent_padding_right)
         };
 
-        if parent_direction.get_axis() == LayoutAxis::$axis {
+        if let Some((placement, tracks)) = solved_widths.grids.get(parent_id) {
+            // Grid items are placed at the start of their grid area
+            for (child_id, area) in &placement.items {
+                let (start, _) = get_area_span(area, LayoutAxis::$axis);
+                let child_margin_left = node_data[*child_id].margin.unwrap_or_default().$left.map(|x| x.to_pixels()).unwrap_or(0.0);
+                arena_solved_data[*child_id].0 = parent_x_position + tracks.get_offset(start) + child_margin_left;
+            }
+
+            for child_id in get_absolute_children(*parent_id, node_hierarchy, node_data) {
+                determine_child_x_absolute(
+                    child_id,
+                    &positioned_node_stack,
+                    node_data,
+                    &mut arena_solved_data,
+                    solved_widths,
+                );
+            }
+
+        } else if parent_direction.get_axis() == LayoutAxis::$axis {
             // Along main axis: Take X of parent
             let main_axis_alignment = node_data[*parent_id].justify_content.unwrap_or_default();
-            let mut sum_x_of_children_so_far = 0.0;
+            let gap = get_gap_along_axis(parent_node, LayoutAxis::$axis, Some(parent_inner_width));
+
+            // Multi-line containers align every line separately, the `position: absolute`
+            // children aren't part of any line, so they are positioned on their own
+            let lines = match solved_widths.flex_lines.get(parent_id) {
+                Some(lines) => {
+                    let mut lines = lines.clone();
+                    lines.push(get_absolute_children(*parent_id, node_hierarchy, node_data));
+                    lines
+                },
+                None => vec![get_children_in_order(*parent_id, node_hierarchy, node_data)],
+            };
+
+            // In a reversed container, the items start at the main-end edge
+            let main_axis_alignment = match (parent_direction.is_reverse(), main_axis_alignment) {
+                (true, LayoutJustifyContent::Start) => LayoutJustifyContent::End,
+                (true, LayoutJustifyContent::End) => LayoutJustifyContent::Start,
+                (_, other) => other,
+            };
+
+            for line in &lines {
+
+                let line_in_direction = if parent_direction.is_reverse() {
+                    line.iter().rev().cloned().collect::<Vec<NodeId>>()
+                } else {
+                    line.clone()
+                };
+
+                // Distribute the space that is left over after all in-flow items
+                // (and the gaps between them) have been placed
+                let in_flow_children = line.iter().filter(|child_id| !node_data[**child_id].position.unwrap_or_default().is_out_of_flow()).count();
+                let in_flow_children_width: f32 = line.iter().filter(|child_id| {
+                    !node_data[**child_id].position.unwrap_or_default().is_out_of_flow()
+                }).map(|child_id| {
+                    let child_node = &solved_widths.$solved_widths_field[*child_id];
+                    child_node.$min_width + child_node.space_added + get_margin_along_axis(&node_data[*child_id], LayoutAxis::$axis)
+                }).sum();
+                let leftover_space = parent_inner_width - in_flow_children_width - gap * in_flow_children.saturating_sub(1) as f32;
+                let (first_item_offset, item_spacing) = justify_flex_items(main_axis_alignment, leftover_space, in_flow_children);
+
+                let mut sum_x_of_children_so_far = first_item_offset;
+                let mut is_first_in_flow_child = true;
+
+                for child_id in line_in_direction {
+
+                    // Two adjacent in-flow items are separated by the gap
+                    if !node_data[child_id].position.unwrap_or_default().is_out_of_flow() {
+                        if !is_first_in_flow_child {
+                            sum_x_of_children_so_far += gap + item_spacing;
+                        }
+                        is_first_in_flow_child = false;
+                    }
 
-            if parent_direction.is_reverse() {
-                for child_id in parent_id.reverse_children(node_hierarchy) {
                     determine_child_x_along_main_axis(
-                        *parent_id,
-                        main_axis_alignment,
                         &node_data,
                         &mut arena_solved_data,
                         solved_widths,
                         child_id,
                         parent_x_position,
-                        parent_inner_width,
                         &mut sum_x_of_children_so_far,
                         &positioned_node_stack,
                     );
                 }
+            }
+
+        } else if let Some(lines) = cross_axis_flex_lines.get(parent_id) {
+            // Along cross axis of a multi-line container: Every line is as large as its
+            // largest item, the lines are then aligned according to `align-content`
+            let mut line_sizes = lines.iter().map(|line| {
+                line.iter().map(|child_id| {
+                    let child_node = &solved_widths.$solved_widths_field[*child_id];
+                    let child_margin = node_data[*child_id].margin.unwrap_or_default();
+                    child_node.$min_width + child_node.space_added
+                    + child_margin.$left.map(|x| x.to_pixels()).unwrap_or(0.0)
+                    + child_margin.$right.map(|x| x.to_pixels()).unwrap_or(0.0)
+                }).fold(0.0, f32::max)
+            }).collect::<Vec<f32>>();
+
+            let gap = get_gap_along_axis(parent_node, LayoutAxis::$axis, Some(parent_inner_width));
+
+            let (first_line_offset, line_spacing) = align_flex_lines(
+                parent_node.align_content.unwrap_or_default(),
+                &mut line_sizes,
+                parent_inner_width - gap * lines.len().saturating_sub(1) as f32,
+            );
+
+            // `flex-wrap: wrap-reverse` stacks the lines starting at the cross-end
+            let line_order = if parent_node.wrap.unwrap_or_default() == LayoutWrap::WrapReverse {
+                (0..lines.len()).rev().collect::<Vec<usize>>()
             } else {
-                for child_id in parent_id.children(node_hierarchy) {
-                    determine_child_x_along_main_axis(
-                        *parent_id,
-                        main_axis_alignment,
-                        &node_data,
-                        &mut arena_solved_data,
+                (0..lines.len()).collect::<Vec<usize>>()
+            };
+
+            let mut line_x_position = parent_x_position + first_line_offset;
+
+            for line_idx in line_order {
+                for child_id in &lines[line_idx] {
+                    determine_child_x_along_cross_axis(
+                        node_data,
                         solved_widths,
-                        child_id,
-                        parent_x_position,
-                        parent_inner_width,
-                        &mut sum_x_of_children_so_far,
+                        *child_id,
                         &positioned_node_stack,
+                        &mut arena_solved_data,
+                        line_x_position,
+                        line_sizes[line_idx],
+                        get_align_self(&node_data[*child_id], parent_node),
                     );
                 }
+                line_x_position += line_sizes[line_idx] + line_spacing + gap;
             }
 
-            // If the direction is `flex-end`, we can't add the X position during the iteration,
-            // so we have to "add" the diff to the parent_inner_width at the end
-            let should_align_towards_end =
-                (!parent_direction.is_reverse() && main_axis_alignment == LayoutJustifyContent::End) ||
-                (parent_direction.is_reverse() && main_axis_alignment == LayoutJustifyContent::Start);
-
-            if should_align_towards_end {
-                let diff = parent_inner_width - sum_x_of_children_so_far;
-                for child_id in parent_id.children(node_hierarchy).filter(|ch| {
-                    node_data[*ch].position.unwrap_or_default() != LayoutPosition::Absolute
-                }) {
-                    arena_solved_data[child_id].0 += diff;
-                }
+            for child_id in get_absolute_children(*parent_id, node_hierarchy, node_data) {
+                determine_child_x_absolute(
+                    child_id,
+                    &positioned_node_stack,
+                    node_data,
+                    &mut arena_solved_data,
+                    solved_widths,
+                );
             }
 
         } else {
@@ -1030,6 +2394,8 @@ fn $fn_name(
                         &positioned_node_stack,
                         &mut arena_solved_data,
                         parent_x_position,
+                        parent_inner_width,
+                        get_align_self(&node_data[child_id], parent_node),
                     );
                 }
             } else {
@@ -1041,15 +2407,19 @@ fn $fn_name(
                         &positioned_node_stack,
                         &mut arena_solved_data,
                         parent_x_position,
+                        parent_inner_width,
+                        get_align_self(&node_data[child_id], parent_node),
                     );
                 }
             }
         }
 
-        if parent_is_positioned {
-            positioned_node_stack.pop();
+        // `position: relative` children are shifted by their offsets after the layout,
+        // without affecting their siblings. Their descendants are positioned afterwards,
+        // so they move along with them.
+        for child_id in parent_id.children(node_hierarchy) {
+            arena_solved_data[child_id].0 += get_relative_offset(&node_data[child_id], LayoutAxis::$axis);
         }
-
     }
 
     arena_solved_data
@@ -1059,12 +2429,13 @@ fn $fn_name(
 
 fn get_x_positions(
     solved_widths: &SolvedWidthLayout,
+    solved_heights: &SolvedHeightLayout,
     node_hierarchy: &NodeHierarchy,
     origin: LayoutPoint,
 ) -> NodeDataContainer<HorizontalSolvedPosition>
 {
     get_position!(get_pos_x, SolvedWidthLayout, HorizontalSolvedPosition, solved_widths, min_width, left, right, Horizontal);
-    let mut arena = get_pos_x(node_hierarchy, &solved_widths.layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, solved_widths);
+    let mut arena = get_pos_x(node_hierarchy, &solved_heights.layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, solved_widths, &solved_heights.flex_lines);
 
     // Add the origin on top of the position
     let x = origin.x as f32;
@@ -1084,7 +2455,7 @@ fn get_y_positions(
 ) -> NodeDataContainer<VerticalSolvedPosition>
 {
     get_position!(get_pos_y, SolvedHeightLayout, VerticalSolvedPosition, solved_heights, min_height, top, bottom, Vertical);
-    let mut arena = get_pos_y(node_hierarchy, &solved_widths.layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, solved_heights);
+    let mut arena = get_pos_y(node_hierarchy, &solved_heights.layout_only_arena, &solved_widths.non_leaf_nodes_sorted_by_depth, solved_heights, &solved_widths.flex_lines);
 
     // Add the origin on top of the position
     let y = origin.y as f32;
@@ -1097,17 +2468,23 @@ fn get_y_positions(
 }
 
 /// Returns the preferred width, for example for an image, that would be the
-/// original width (an image always wants to take up the original space)
+/// original width (an image always wants to take up the original space).
+/// A text can be broken into lines, so its min-content and max-content widths differ.
 fn get_content_width<T>(
         node_id: &NodeId,
         node_type: &NodeType<T>,
         app_resources: &AppResources,
-        positioned_words: &BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
-) -> Option<f32> {
+        positioned_words: &BTreeMap<NodeId, (WordPositions, Vec<FontInstanceKey>)>,
+        intrinsic_text_widths: &BTreeMap<NodeId, (f32, f32)>,
+) -> Option<IntrinsicSizes> {
     use dom::NodeType::*;
     match node_type {
-        Image(image_id) => app_resources.get_image_info(image_id).map(|info| info.descriptor.size.width as f32),
-        Label(_) | Text(_) => positioned_words.get(node_id).map(|pos| pos.0.content_size.width),
+        Image(image_id) => app_resources.get_image_info(image_id).map(|info| IntrinsicSizes::fixed(info.descriptor.size.width as f32)),
+        Label(_) | Text(_) => {
+            let preferred = positioned_words.get(node_id)?.0.content_size.width;
+            let (min_content, max_content) = intrinsic_text_widths.get(node_id).cloned().unwrap_or((preferred, preferred));
+            Some(IntrinsicSizes { min_content, max_content, preferred })
+        },
         _ => None,
     }
 }
@@ -1116,7 +2493,7 @@ fn get_content_height<T>(
     node_id: &NodeId,
     node_type: &NodeType<T>,
     app_resources: &AppResources,
-    positioned_words: &BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
+    positioned_words: &BTreeMap<NodeId, (WordPositions, Vec<FontInstanceKey>)>,
     div_width: f32,
 ) -> Option<PreferredHeight> {
     use dom::NodeType::*;
@@ -1159,23 +2536,279 @@ impl PreferredHeight {
     }
 }
 
-pub(crate) fn font_size_to_au(font_size: StyleFontSize) -> Au {
-    px_to_au(font_size.0.to_pixels())
-}
-
 pub(crate) fn px_to_au(px: f32) -> Au {
     use app_units::{AU_PER_PX, MIN_AU, MAX_AU};
     let target_app_units = Au((px * AU_PER_PX as f32) as i32);
     target_app_units.min(MAX_AU).max(MIN_AU)
 }
 
-pub(crate) fn get_font_id(rect_style: &RectStyle) -> &str {
-    let font_id = rect_style.font_family.as_ref().and_then(|family| family.fonts.get(0));
-    font_id.map(|f| f.get_str()).unwrap_or(DEFAULT_FONT_ID)
+/// Returns the fonts of the `font-family` of the node, in the order of preference, with the
+/// system fonts that don't exist on this system skipped. Falls back to the default
+/// font if none of the fonts are available.
+pub(crate) fn get_font_ids(rect_style: &RectStyle, app_resources: &AppResources) -> Vec<ImmediateFontId> {
+    let properties = get_font_face_properties(rect_style);
+    let mut font_ids: Vec<ImmediateFontId> = rect_style.font_family.as_ref()
+        .map(|family| family.fonts.iter()
+            .map(|font| app_resources.get_immediate_font_id(font.get_str(), properties))
+            .filter(|font_id| !app_resources.is_font_missing(font_id))
+            .collect())
+        .unwrap_or_default();
+
+    if font_ids.is_empty() {
+        font_ids.push(app_resources.get_immediate_font_id(DEFAULT_FONT_ID, properties));
+    }
+
+    font_ids
+}
+
+/// Returns the weight, style and stretch that the font face of the node has to match
+pub(crate) fn get_font_face_properties(rect_style: &RectStyle) -> FontFaceProperties {
+    FontFaceProperties {
+        weight: rect_style.font_weight.unwrap_or_default(),
+        style: rect_style.font_style.unwrap_or_default(),
+        stretch: rect_style.font_stretch.unwrap_or_default(),
+    }
+}
+
+pub(crate) fn get_font_size(rect_style: &RectStyle) -> StyleFontSize {
+    rect_style.font_size.unwrap_or(DEFAULT_FONT_SIZE)
+}
+
+/// Returns the font size of the node in pixels. `%` font sizes refer to the default font size.
+pub(crate) fn get_font_size_px(rect_style: &RectStyle, resolve_context: &PixelResolveContext) -> f32 {
+    get_font_size(rect_style).0.to_pixels_relative(DEFAULT_FONT_SIZE.0.to_pixels(), resolve_context)
+}
+
+/// Returns the lengths that the relative units are resolved against: `rem` refers to
+/// the font size of the root node, `vw`, `vh`, `vmin` and `vmax` refer to the `viewport_size`
+pub(crate) fn get_resolve_context<'a>(
+    display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
+    viewport_size: LayoutSize,
+) -> PixelResolveContext {
+
+    use css::webrender_translate::wr_translate_layout_size;
+
+    let mut resolve_context = PixelResolveContext {
+        root_font_size: DEFAULT_FONT_SIZE.0.to_pixels(),
+        viewport_size: wr_translate_layout_size(viewport_size),
+    };
+
+    // `rem` on the root node itself refers to the default font size
+    if let Some(root) = display_rects.get(NodeId::new(0)) {
+        resolve_context.root_font_size = get_font_size_px(&root.style, &resolve_context);
+    }
+
+    resolve_context
+}
+
+/// Resolves the relative units (`rem`, `vw`, `vh`, `vmin`, `vmax`) of the layout to pixels.
+///
+/// Percentages can only be resolved once the size of the containing block is known, so they are
+/// kept and resolved by the solver (see `resolve_percentages_along_axis`).
+fn resolve_rect_layout(layout: &RectLayout, resolve_context: &PixelResolveContext) -> RectLayout {
+
+    let resolve = |value: PixelValue| resolve_length(value, resolve_context);
+
+    RectLayout {
+        // The `min-content`, `max-content` and `fit-content` keywords are resolved by the solver
+        width: layout.width.map(|w| match w {
+            LayoutWidth::Exact(w) => LayoutWidth::Exact(resolve(w)),
+            keyword => keyword,
+        }),
+        height: layout.height.map(|h| match h {
+            LayoutHeight::Exact(h) => LayoutHeight::Exact(resolve(h)),
+            keyword => keyword,
+        }),
+        min_width: layout.min_width.map(|w| LayoutMinWidth(resolve(w.0))),
+        min_height: layout.min_height.map(|h| LayoutMinHeight(resolve(h.0))),
+        max_width: layout.max_width.map(|w| LayoutMaxWidth(resolve(w.0))),
+        max_height: layout.max_height.map(|h| LayoutMaxHeight(resolve(h.0))),
+        top: layout.top.map(|t| LayoutTop(resolve(t.0))),
+        bottom: layout.bottom.map(|b| LayoutBottom(resolve(b.0))),
+        left: layout.left.map(|l| LayoutLeft(resolve(l.0))),
+        right: layout.right.map(|r| LayoutRight(resolve(r.0))),
+        padding: layout.padding.map(|padding| LayoutPadding {
+            top: padding.top.map(resolve),
+            bottom: padding.bottom.map(resolve),
+            left: padding.left.map(resolve),
+            right: padding.right.map(resolve),
+        }),
+        margin: layout.margin.map(|margin| LayoutMargin {
+            top: margin.top.map(resolve),
+            bottom: margin.bottom.map(resolve),
+            left: margin.left.map(resolve),
+            right: margin.right.map(resolve),
+        }),
+        grid_template_columns: layout.grid_template_columns.as_ref().map(|columns| LayoutGridTemplateColumns(resolve_grid_tracks(&columns.0, resolve_context))),
+        grid_template_rows: layout.grid_template_rows.as_ref().map(|rows| LayoutGridTemplateRows(resolve_grid_tracks(&rows.0, resolve_context))),
+        grid_gap: layout.grid_gap.map(|gap| LayoutGridGap {
+            row: resolve(gap.row),
+            column: resolve(gap.column),
+        }),
+        gap: layout.gap.map(|gap| LayoutGap {
+            row: gap.row.map(resolve),
+            column: gap.column.map(resolve),
+        }),
+        flex_basis: layout.flex_basis.map(|basis| match basis {
+            LayoutFlexBasis::Exact(basis) => LayoutFlexBasis::Exact(resolve(basis)),
+            LayoutFlexBasis::Auto => LayoutFlexBasis::Auto,
+        }),
+        .. layout.clone()
+    }
+}
+
+/// Resolves all units of the length except for percentages to `px` - `calc(50% + 1rem)` is
+/// resolved to `calc(50% + 16px)`. Percentages are resolved by the solver: the `width`,
+/// `height`, offsets, `margin` and `padding` refer to the containing block, the track sizes
+/// and gaps of a grid container to the grid container itself, the `gap` of a flex container
+/// and the `flex-basis` of its items to the flex container.
+fn resolve_length(value: PixelValue, resolve_context: &PixelResolveContext) -> PixelValue {
+    if !value.is_percentage() {
+        return value.resolve(0.0, resolve_context);
+    }
+
+    if value.calc.is_none() {
+        return value;
+    }
+
+    let percent: f32 = value.get_terms().into_iter()
+        .filter(|(metric, _)| *metric == SizeMetric::Percent)
+        .map(|(_, number)| number)
+        .sum();
+
+    // Resolving with a `percent_base` of 0 leaves only the non-percentage terms
+    PixelValue::calc(&[(SizeMetric::Percent, percent), (SizeMetric::Px, value.to_pixels_relative(0.0, resolve_context))])
+}
+
+fn resolve_grid_tracks(tracks: &[GridTrackSize], resolve_context: &PixelResolveContext) -> Vec<GridTrackSize> {
+    let resolve_breadth = |breadth: GridTrackBreadth| match breadth {
+        GridTrackBreadth::Length(length) => GridTrackBreadth::Length(resolve_length(length, resolve_context)),
+        other => other,
+    };
+    tracks.iter().map(|track| match *track {
+        GridTrackSize::Breadth(breadth) => GridTrackSize::Breadth(resolve_breadth(breadth)),
+        GridTrackSize::MinMax(min, max) => GridTrackSize::MinMax(resolve_breadth(min), resolve_breadth(max)),
+    }).collect()
+}
+
+/// Resolves the percentages of the layout that refer to the size of the containing block along
+/// the `axis` against the `percent_base`: the `width`, `left`, `right`, `margin` and `padding`
+/// refer to the width of the containing block, the `height`, `top` and `bottom` to its height.
+///
+/// If the size of the containing block isn't known yet (`None`), percentages are treated as if they weren't set.
+fn resolve_percentages_along_axis(layout: &RectLayout, axis: LayoutAxis, percent_base: Option<f32>) -> RectLayout {
+
+    // The other units have already been resolved by `resolve_rect_layout`
+    let resolve = |value: PixelValue| -> Option<PixelValue> {
+        match percent_base {
+            Some(percent_base) => Some(value.resolve(percent_base, &PixelResolveContext::default())),
+            None if value.is_percentage() => None,
+            None => Some(value),
+        }
+    };
+
+    match axis {
+        LayoutAxis::Horizontal => RectLayout {
+            width: layout.width.and_then(|w| match w {
+                LayoutWidth::Exact(w) => resolve(w).map(LayoutWidth::Exact),
+                keyword => Some(keyword),
+            }),
+            min_width: layout.min_width.and_then(|w| resolve(w.0)).map(LayoutMinWidth),
+            max_width: layout.max_width.and_then(|w| resolve(w.0)).map(LayoutMaxWidth),
+            left: layout.left.and_then(|l| resolve(l.0)).map(LayoutLeft),
+            right: layout.right.and_then(|r| resolve(r.0)).map(LayoutRight),
+            padding: layout.padding.map(|padding| LayoutPadding {
+                top: padding.top.and_then(resolve),
+                bottom: padding.bottom.and_then(resolve),
+                left: padding.left.and_then(resolve),
+                right: padding.right.and_then(resolve),
+            }),
+            margin: layout.margin.map(|margin| LayoutMargin {
+                top: margin.top.and_then(resolve),
+                bottom: margin.bottom.and_then(resolve),
+                left: margin.left.and_then(resolve),
+                right: margin.right.and_then(resolve),
+            }),
+            .. layout.clone()
+        },
+        LayoutAxis::Vertical => RectLayout {
+            height: layout.height.and_then(|h| match h {
+                LayoutHeight::Exact(h) => resolve(h).map(LayoutHeight::Exact),
+                keyword => Some(keyword),
+            }),
+            min_height: layout.min_height.and_then(|h| resolve(h.0)).map(LayoutMinHeight),
+            max_height: layout.max_height.and_then(|h| resolve(h.0)).map(LayoutMaxHeight),
+            top: layout.top.and_then(|t| resolve(t.0)).map(LayoutTop),
+            bottom: layout.bottom.and_then(|b| resolve(b.0)).map(LayoutBottom),
+            .. layout.clone()
+        },
+    }
+}
+
+/// Returns whether any of the lengths that `resolve_percentages_along_axis` resolves is a percentage
+fn has_percentages_along_axis(layout: &RectLayout, axis: LayoutAxis) -> bool {
+    let padding = layout.padding.unwrap_or_default();
+    let margin = layout.margin.unwrap_or_default();
+
+    let lengths = match axis {
+        LayoutAxis::Horizontal => [
+            layout.width.and_then(|w| match w { LayoutWidth::Exact(w) => Some(w), _ => None }),
+            layout.min_width.map(|w| w.0),
+            layout.max_width.map(|w| w.0),
+            layout.left.map(|l| l.0),
+            layout.right.map(|r| r.0),
+            padding.top, padding.bottom, padding.left, padding.right,
+            margin.top, margin.bottom, margin.left, margin.right,
+        ],
+        LayoutAxis::Vertical => [
+            layout.height.and_then(|h| match h { LayoutHeight::Exact(h) => Some(h), _ => None }),
+            layout.min_height.map(|h| h.0),
+            layout.max_height.map(|h| h.0),
+            layout.top.map(|t| t.0),
+            layout.bottom.map(|b| b.0),
+            None, None, None, None, None, None, None, None,
+        ],
+    };
+
+    lengths.iter().any(|length| length.map(|l| l.is_percentage()).unwrap_or(false))
 }
 
-pub(crate) fn get_font_size(rect_style: &RectStyle) -> StyleFontSize {
-    rect_style.font_size.unwrap_or(DEFAULT_FONT_SIZE)
+/// Resolves the percentages of the `rect_layouts` along the `axis` against the solved size of their containing block -
+/// the content box of the parent or (for `position: absolute` nodes) of the nearest positioned ancestor, the root
+/// node for `position: fixed` nodes. The containing block of the root node has the size of the `root_size`.
+///
+/// The solver resolves the percentages of the layouts it solves (the `solved_layouts`) on its own, this is for the
+/// layouts of the boxes and floats in paragraphs, which the solver only sees as out-of-flow boxes.
+fn resolve_solved_percentages(
+    node_hierarchy: &NodeHierarchy,
+    rect_layouts: &NodeDataContainer<RectLayout>,
+    solved_layouts: &NodeDataContainer<RectLayout>,
+    solved_sizes: &NodeDataContainer<f32>,
+    axis: LayoutAxis,
+    root_size: f32,
+) -> NodeDataContainer<RectLayout>
+{
+    rect_layouts.transform(|layout, node_id| {
+
+        // Same as in the solver, `position: absolute` nodes without a positioned ancestor
+        // and `position: fixed` nodes are positioned relative to the root node
+        let containing_block_id = match node_hierarchy[node_id].parent {
+            Some(parent_id) if solved_layouts[node_id].position.unwrap_or_default().is_out_of_flow() => {
+                Some(get_positioned_ancestor(&solved_layouts[node_id], &get_positioned_node_stack(parent_id, node_hierarchy, solved_layouts)))
+            },
+            parent => parent,
+        };
+
+        let percent_base = match containing_block_id {
+            Some(id) => solved_sizes[id] - match axis {
+                LayoutAxis::Horizontal => solved_layouts[id].get_horizontal_padding(),
+                LayoutAxis::Vertical => solved_layouts[id].get_vertical_padding(),
+            },
+            None => root_size,
+        };
+
+        resolve_percentages_along_axis(layout, axis, Some(percent_base))
+    })
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -1190,22 +2823,63 @@ pub struct PositionedRectangle {
 #[derive(Debug, Clone)]
 pub struct LayoutResult {
     pub rects: NodeDataContainer<PositionedRectangle>,
+    /// Layout of the nodes, with all relative units (`%`, `rem`, `vw`, ...) resolved to pixels
+    pub rect_layouts: NodeDataContainer<RectLayout>,
+    /// Lengths that the relative units (`rem`, `vw`, ...) of the styling properties are resolved against
+    pub resolve_context: PixelResolveContext,
     pub word_cache: BTreeMap<NodeId, Words>,
-    pub scaled_words: BTreeMap<NodeId, (ScaledWords, FontInstanceKey)>,
-    pub positioned_word_cache: BTreeMap<NodeId, (WordPositions, FontInstanceKey)>,
+    pub scaled_words: BTreeMap<NodeId, (ScaledWords, Vec<FontInstanceKey>)>,
+    pub positioned_word_cache: BTreeMap<NodeId, (WordPositions, Vec<FontInstanceKey>)>,
+    /// Texts that are laid out as a part of a paragraph, with the origin that their word positions
+    /// are relative to (the content box of the paragraph root) - the words are already aligned
+    pub inline_texts: BTreeMap<NodeId, LayoutPoint>,
     pub node_depths: Vec<(usize, NodeId)>,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-struct InlineText {
-    /// Horizontal padding of the text in pixels
-    horizontal_padding: f32,
-    /// Horizontal margin of the text in pixels
-    horizontal_margin: f32,
+/// Returns a copy of the `node_hierarchy` where all nodes with `display: none` are unlinked
+/// from their parents and siblings, so that the layout solver skips their sub-trees entirely
+fn remove_display_none_nodes(node_hierarchy: &NodeHierarchy, rect_layouts: &NodeDataContainer<RectLayout>) -> NodeHierarchy {
+    let mut pruned_hierarchy = node_hierarchy.clone();
+
+    for node_id in node_hierarchy.linear_iter().filter(|node_id| rect_layouts[*node_id].is_display_none()) {
+        let Node { parent, previous_sibling, next_sibling, .. } = pruned_hierarchy[node_id];
+
+        match previous_sibling {
+            Some(previous_sibling) => pruned_hierarchy[previous_sibling].next_sibling = next_sibling,
+            None => if let Some(parent) = parent { pruned_hierarchy[parent].first_child = next_sibling; },
+        }
+
+        match next_sibling {
+            Some(next_sibling) => pruned_hierarchy[next_sibling].previous_sibling = previous_sibling,
+            None => if let Some(parent) = parent { pruned_hierarchy[parent].last_child = previous_sibling; },
+        }
+
+        let node = &mut pruned_hierarchy[node_id];
+        node.parent = None;
+        node.previous_sibling = None;
+        node.next_sibling = None;
+    }
+
+    pruned_hierarchy
+}
+
+/// Returns whether the node or one of its ancestors has `display: none`
+fn is_in_display_none_subtree(node_id: NodeId, node_hierarchy: &NodeHierarchy, rect_layouts: &NodeDataContainer<RectLayout>) -> bool {
+    let mut current_node = Some(node_id);
+    while let Some(node_id) = current_node {
+        if rect_layouts[node_id].is_display_none() {
+            return true;
+        }
+        current_node = node_hierarchy[node_id].parent;
+    }
+    false
 }
 
 /// At this point in time, all font keys, image keys, etc. have
 /// to be already submitted in the RenderApi!
+///
+/// The `viewport_size` is the logical size of the window, which the `vw`, `vh`,
+/// `vmin` and `vmax` units refer to.
 pub(crate) fn do_the_layout<'a,'b, T>(
     node_hierarchy: &NodeHierarchy,
     node_data: &NodeDataContainer<NodeData<T>>,
@@ -1213,72 +2887,154 @@ pub(crate) fn do_the_layout<'a,'b, T>(
     app_resources: &'b AppResources,
     rect_size: LayoutSize,
     rect_offset: LayoutPoint,
+    viewport_size: LayoutSize,
 ) -> LayoutResult {
 
+    let resolve_context = get_resolve_context(display_rects, viewport_size);
+
+    // Nodes with `display: none` don't take up any space, so they are removed from the
+    // hierarchy before solving the layout - their bounds are set to zero afterwards
+    let display_layouts = display_rects.transform(|rect, _| rect.layout.clone());
+    let full_node_hierarchy = node_hierarchy;
+    let node_hierarchy = &remove_display_none_nodes(full_node_hierarchy, &display_layouts);
+
+    // The texts and boxes of paragraphs are laid out in lines by the text layout, the flex
+    // solver only sees the boxes and floats as out-of-flow children of the paragraph root
+    let inline_contexts = get_inline_formatting_contexts(node_hierarchy, &display_layouts, |node_id| {
+        match node_data[node_id].node_type {
+            NodeType::Label(_) | NodeType::Text(_) => true,
+            _ => false,
+        }
+    });
+    let flex_hierarchy = &get_inline_layout_hierarchy(node_hierarchy, &inline_contexts);
+
+    // Percentages are kept until the size of the containing block is known, the solver
+    // resolves them while solving the widths and heights
+    let unresolved_layouts = display_rects.transform(|rect, _| resolve_rect_layout(&rect.layout, &resolve_context));
+    let flex_layouts = get_inline_flex_layouts(&unresolved_layouts, &inline_contexts);
+
     // Determine what the width for each div would be if the content size didn't matter
     let widths_content_ignored = solve_flex_layout_width(
-        node_hierarchy,
-        &display_rects,
+        flex_hierarchy,
+        &flex_layouts,
         &node_data.transform(|_, _| None),
         rect_size.width as f32,
     );
 
+    let rect_layouts = resolve_solved_percentages(
+        flex_hierarchy,
+        &unresolved_layouts,
+        &widths_content_ignored.layout_only_arena,
+        &widths_content_ignored.solved_widths.transform(|width, _| width.total()),
+        LayoutAxis::Horizontal,
+        rect_size.width as f32,
+    );
+
     // Determine what the "maximum width" for each div is, except for divs where overflow:visible is set
     // I.e. for a div width 800px, with 4 text child nodes, each text node gets a width of 200px
-    let max_widths = node_hierarchy
+    let max_widths = flex_hierarchy
         .linear_iter()
-        .filter(|node_id| !display_rects[*node_id].layout.is_horizontal_overflow_visible())
+        .filter(|node_id| !rect_layouts[*node_id].is_horizontal_overflow_visible())
         .map(|node_id| (node_id, widths_content_ignored.solved_widths[node_id].total()))
         .collect::<BTreeMap<NodeId, f32>>();
 
-    // TODO: Filter all inline text blocks (prepare inline text layout run)
-    let inline_text_blocks = BTreeMap::<NodeId, InlineText>::new();
-
     // Resolve cached text IDs or break new, uncached strings into words / text runs
     let word_cache = create_word_cache(app_resources, node_data);
     // Scale the words to the correct size - TODO: Caching / GC!
-    let scaled_words = create_scaled_words(app_resources, &word_cache, display_rects);
+    let scaled_words = create_scaled_words(app_resources, &word_cache, display_rects, &resolve_context);
     // Layout all words as if there was no max-width constraint
     let word_positions_no_max_width = create_word_positions(
         &word_cache,
         &scaled_words,
         display_rects,
         &max_widths,
-        &inline_text_blocks
+        &resolve_context,
     );
 
+    // Measure the longest word (min-content) and the unbroken lines (max-content) of all texts
+    let intrinsic_text_widths = create_intrinsic_text_widths(&word_cache, &scaled_words, display_rects);
+
+    // Texts of the paragraphs, each with its own font and spacing
+    let inline_text_items = create_inline_text_items(&inline_contexts, &word_cache, &scaled_words, display_rects);
+
     // Determine the preferred **content** width, without any max-width restrictions -
     // For images that would be the image width / height, for text it would be the text
     // laid out without any width constraints.
-    let content_widths = node_data.transform(|node, node_id|
-        get_content_width(&node_id, &node.node_type, app_resources, &word_positions_no_max_width)
+    let mut content_widths = node_data.transform(|node, node_id|
+        get_content_width(&node_id, &node.node_type, app_resources, &word_positions_no_max_width, &intrinsic_text_widths)
     );
 
+    // The content width of a paragraph are its lines - inner paragraphs (boxes
+    // of an outer paragraph) have to be measured before the outer paragraphs
+    for (root_id, context) in inline_contexts.iter().rev() {
+
+        let box_widths = context.boxes().chain(context.floats.iter().cloned()).map(|box_id| {
+            let box_layout = &rect_layouts[box_id];
+            let box_width = match box_layout.width {
+                Some(LayoutWidth::Exact(width)) => width.to_pixels(),
+                _ => content_widths[box_id].map(|sizes| sizes.max_content).unwrap_or(0.0) + box_layout.get_horizontal_padding(),
+            };
+            (box_id, box_width + box_layout.get_horizontal_margin())
+        }).collect::<BTreeMap<NodeId, f32>>();
+
+        let get_box_size = |box_id: NodeId| LayoutSize::new(box_widths.get(&box_id).cloned().unwrap_or(0.0), 0.0);
+        let alignment_horz = determine_text_alignment(&display_rects[*root_id].style, &rect_layouts[*root_id]).0;
+
+        let max_content = layout_inline_formatting_context(
+            context, node_hierarchy, &rect_layouts, &inline_text_items, None, alignment_horz, &get_box_size,
+        ).content_size.width;
+
+        let preferred = match max_widths.get(root_id) {
+            Some(max_width) => layout_inline_formatting_context(
+                context, node_hierarchy, &rect_layouts, &inline_text_items,
+                Some(max_width - rect_layouts[*root_id].get_horizontal_padding()), alignment_horz, &get_box_size,
+            ).content_size.width,
+            None => max_content,
+        };
+
+        // A paragraph can't be narrower than its longest word or its largest box
+        let min_content = context.items.iter().map(|item| match item {
+            InlineContent::Text(text_id) => intrinsic_text_widths.get(text_id).map(|(min_content, _)| *min_content).unwrap_or(0.0),
+            InlineContent::Box(box_id) => box_widths[box_id],
+        }).chain(context.floats.iter().map(|float_id| box_widths[float_id])).fold(0.0, f32::max);
+
+        content_widths[*root_id] = Some(IntrinsicSizes { min_content, max_content, preferred });
+    }
+
     // Solve the widths again, this time incorporating the maximum widths
     let solved_widths = solve_flex_layout_width(
-        node_hierarchy,
-        &display_rects,
+        flex_hierarchy,
+        &flex_layouts,
         &content_widths,
         rect_size.width as f32,
     );
 
+    let rect_layouts = resolve_solved_percentages(
+        flex_hierarchy,
+        &unresolved_layouts,
+        &solved_widths.layout_only_arena,
+        &solved_widths.solved_widths.transform(|width, _| width.total()),
+        LayoutAxis::Horizontal,
+        rect_size.width as f32,
+    );
+
     // Layout all texts again with the resolved width constraints
     let proper_max_widths = solved_widths.solved_widths.linear_iter().map(|node_id| {
-        (node_id, solved_widths.solved_widths[node_id].total() - display_rects[node_id].layout.get_horizontal_padding())
-    }).collect();
+        (node_id, solved_widths.solved_widths[node_id].total() - rect_layouts[node_id].get_horizontal_padding())
+    }).collect::<BTreeMap<NodeId, f32>>();
 
     // Resolve the word positions relative to each divs upper left corner
-    let word_positions_with_max_width = create_word_positions(
+    let mut word_positions_with_max_width = create_word_positions(
         &word_cache,
         &scaled_words,
         display_rects,
         &proper_max_widths,
-        &inline_text_blocks
+        &resolve_context,
     );
 
     // Given the final width of a node and the height of the content, resolve the div
     // height and return whether the node content overflows its parent (width-in-height-out)
-    let content_heights = node_data.transform(|node, node_id| {
+    let mut content_heights = node_data.transform(|node, node_id| {
         let div_width = solved_widths.solved_widths[node_id].total();
         get_content_height(
             &node_id,
@@ -1286,40 +3042,144 @@ pub(crate) fn do_the_layout<'a,'b, T>(
             app_resources,
             &word_positions_with_max_width,
             div_width
-        ).map(|ch| ch.get_content_size())
+        ).map(|ch| IntrinsicSizes::fixed(ch.get_content_size()))
     });
 
+    // The lines of a paragraph depend on the heights of its boxes and floats
+    let has_inline_boxes = inline_contexts.values().any(|context| context.boxes().next().is_some() || !context.floats.is_empty());
+    let box_heights = if has_inline_boxes {
+        Some(solve_flex_layout_height(flex_hierarchy, &solved_widths, &content_heights, rect_size.height as f32))
+    } else {
+        None
+    };
+
+    // Layout the paragraphs with their final width, the height of a paragraph is the height of its lines and floats
+    let mut inline_layouts = BTreeMap::<NodeId, InlineFormattingContextLayout>::new();
+    for (root_id, context) in inline_contexts.iter().rev() {
+
+        let alignment_horz = determine_text_alignment(&display_rects[*root_id].style, &rect_layouts[*root_id]).0;
+
+        let inline_layout = {
+            let get_box_size = |box_id: NodeId| {
+                let box_layout = &rect_layouts[box_id];
+                // Inner paragraphs are as high as their lines, unless they have a fixed height
+                let box_height = match (box_layout.height, inline_layouts.get(&box_id)) {
+                    (None, Some(inner_layout)) => inner_layout.content_size.height + box_layout.get_vertical_padding(),
+                    _ => box_heights.as_ref().map(|heights| heights.solved_heights[box_id].total()).unwrap_or(0.0),
+                };
+                LayoutSize::new(
+                    solved_widths.solved_widths[box_id].total() + box_layout.get_horizontal_margin(),
+                    box_height + box_layout.get_vertical_margin(),
+                )
+            };
+            layout_inline_formatting_context(
+                context, node_hierarchy, &rect_layouts, &inline_text_items,
+                Some(proper_max_widths[root_id]), alignment_horz, &get_box_size,
+            )
+        };
+
+        content_heights[*root_id] = Some(IntrinsicSizes::fixed(inline_layout.content_size.height));
+        inline_layouts.insert(*root_id, inline_layout);
+    }
+
     // Given the final heights, resolve the heights for flexible-size divs
     // TODO: Fix justify-content:flex-start: The content height is not the final height!
     let solved_heights = solve_flex_layout_height(
-        node_hierarchy,
+        flex_hierarchy,
         &solved_widths,
         &content_heights,
         rect_size.height as f32,
     );
 
-    let x_positions = get_x_positions(&solved_widths, node_hierarchy, rect_offset.clone());
-    let y_positions = get_y_positions(&solved_heights, &solved_widths, node_hierarchy, rect_offset);
+    let rect_layouts = resolve_solved_percentages(
+        flex_hierarchy,
+        &rect_layouts,
+        &solved_heights.layout_only_arena,
+        &solved_heights.solved_heights.transform(|height, _| height.total()),
+        LayoutAxis::Vertical,
+        rect_size.height as f32,
+    );
+
+    let mut x_positions = get_x_positions(&solved_widths, &solved_heights, flex_hierarchy, rect_offset.clone());
+    let mut y_positions = get_y_positions(&solved_heights, &solved_widths, flex_hierarchy, rect_offset);
+
+    // Move the items of the paragraphs to their place in the lines, relative to the content box of the
+    // paragraph root. Outer paragraphs are positioned first, since they move the inner paragraphs.
+    let mut inline_bounds = BTreeMap::<NodeId, LayoutRect>::new();
+    let mut inline_texts = BTreeMap::<NodeId, LayoutPoint>::new();
+
+    for (root_id, context) in &inline_contexts {
+
+        let inline_layout = match inline_layouts.remove(root_id) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let root_padding = rect_layouts[*root_id].padding.unwrap_or_default();
+        let origin = LayoutPoint::new(
+            x_positions[*root_id].0 + root_padding.left.map(|left| left.to_pixels()).unwrap_or(0.0),
+            y_positions[*root_id].0 + root_padding.top.map(|top| top.to_pixels()).unwrap_or(0.0),
+        );
+
+        for (node_id, bounds) in inline_layout.bounds {
+            inline_bounds.insert(node_id, LayoutRect::new(LayoutPoint::new(bounds.origin.x + origin.x, bounds.origin.y + origin.y), bounds.size));
+        }
+
+        for box_id in context.boxes().chain(context.floats.iter().cloned()) {
+            let bounds = match inline_bounds.remove(&box_id) {
+                Some(s) => s,
+                None => continue,
+            };
+            let box_margin = rect_layouts[box_id].margin.unwrap_or_default();
+            let offset_x = bounds.origin.x + box_margin.left.map(|left| left.to_pixels()).unwrap_or(0.0) - x_positions[box_id].0;
+            let offset_y = bounds.origin.y + box_margin.top.map(|top| top.to_pixels()).unwrap_or(0.0) - y_positions[box_id].0;
+            for node_id in box_id.descendants(flex_hierarchy) {
+                x_positions[node_id].0 += offset_x;
+                y_positions[node_id].0 += offset_y;
+            }
+        }
+
+        for (text_id, word_positions) in inline_layout.word_positions {
+            if let Some((positioned_words, _)) = word_positions_with_max_width.get_mut(&text_id) {
+                *positioned_words = word_positions;
+                inline_texts.insert(text_id, origin);
+            }
+        }
+    }
 
     let layouted_rects = node_data.transform(|_node, node_id| {
+        if is_in_display_none_subtree(node_id, full_node_hierarchy, &display_layouts) {
+            return PositionedRectangle {
+                bounds: LayoutRect::zero(),
+                content_width: None,
+                content_height: None,
+            };
+        }
+
+        // Texts and spans of paragraphs are sized by their lines
+        let bounds = inline_bounds.get(&node_id).cloned().unwrap_or_else(|| LayoutRect::new(
+            LayoutPoint::new(x_positions[node_id].0, y_positions[node_id].0),
+            LayoutSize::new(
+                solved_widths.solved_widths[node_id].total(),
+                solved_heights.solved_heights[node_id].total(),
+            )
+        ));
+
         PositionedRectangle {
-            bounds: LayoutRect::new(
-                LayoutPoint::new(x_positions[node_id].0, y_positions[node_id].0),
-                LayoutSize::new(
-                    solved_widths.solved_widths[node_id].total(),
-                    solved_heights.solved_heights[node_id].total(),
-                )
-            ),
+            bounds,
             content_width: Some(proper_max_widths[&node_id]),
-            content_height: content_heights[node_id],
+            content_height: content_heights[node_id].map(|sizes| sizes.preferred),
         }
     });
 
     LayoutResult {
         rects: layouted_rects,
+        rect_layouts,
+        resolve_context,
         word_cache,
         scaled_words,
         positioned_word_cache: word_positions_with_max_width,
+        inline_texts,
         node_depths: solved_widths.non_leaf_nodes_sorted_by_depth,
     }
 }
@@ -1347,71 +3207,186 @@ fn create_scaled_words<'a>(
     app_resources: &AppResources,
     words: &BTreeMap<NodeId, Words>,
     display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
-) -> BTreeMap<NodeId, (ScaledWords, FontInstanceKey)> {
+    resolve_context: &PixelResolveContext,
+) -> BTreeMap<NodeId, (ScaledWords, Vec<FontInstanceKey>)> {
 
     use text_layout::words_to_scaled_words;
-    use app_resources::ImmediateFontId;
 
     words.iter().filter_map(|(node_id, words)| {
         let style = &display_rects[*node_id].style;
-        let font_size = get_font_size(&style);
-        let font_size_au = font_size_to_au(font_size);
-        let css_font_id = get_font_id(&style);
-        let font_id = match app_resources.get_css_font_id(css_font_id) {
-            Some(s) => ImmediateFontId::Resolved(*s),
-            None => ImmediateFontId::Unresolved(css_font_id.to_string()),
-        };
-
-        let loaded_font = app_resources.get_loaded_font(&font_id)?;
-        let font_instance_key = loaded_font.font_instances.get(&font_size_au)?;
-
-        let font_bytes = &loaded_font.font_bytes;
-        let font_index = loaded_font.font_index as u32;
+        let font_size = get_font_size_px(&style, resolve_context);
+        let font_size_au = px_to_au(font_size);
+
+        // The first loaded font is used for the text, the other fonts are only
+        // used for the characters that the first font doesn't contain
+        let loaded_fonts = get_font_ids(&style, app_resources).into_iter().filter_map(|font_id| {
+            let loaded_font = app_resources.get_loaded_font(&font_id)?;
+            let font_instance_key = loaded_font.font_instances.get(&font_size_au)?;
+            Some((loaded_font, *font_instance_key))
+        }).collect::<Vec<_>>();
+
+        let ((loaded_font, _), fallback_fonts) = loaded_fonts.split_first()?;
+        let fallback_font_bytes = fallback_fonts.iter()
+            .map(|(font, _)| (font.font_bytes.as_slice(), font.font_index as u32))
+            .collect::<Vec<_>>();
 
         let scaled_words = words_to_scaled_words(
             words,
-            font_bytes,
-            font_index,
-            font_size.0.to_pixels(),
+            &loaded_font.font_bytes,
+            loaded_font.font_index as u32,
+            font_size,
+            &fallback_font_bytes,
         );
-        Some((*node_id, (scaled_words, *font_instance_key)))
+        let font_instance_keys = loaded_fonts.iter().map(|(_, key)| *key).collect();
+        Some((*node_id, (scaled_words, font_instance_keys)))
+    }).collect()
+}
+
+/// Returns the min-content and max-content width of every text, see `text_layout::get_intrinsic_widths`
+fn create_intrinsic_text_widths<'a>(
+    words: &BTreeMap<NodeId, Words>,
+    scaled_words: &BTreeMap<NodeId, (ScaledWords, Vec<FontInstanceKey>)>,
+    display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
+) -> BTreeMap<NodeId, (f32, f32)> {
+
+    use text_layout::get_intrinsic_widths;
+
+    words.iter().filter_map(|(node_id, words)| {
+        let (scaled_words, _) = scaled_words.get(&node_id)?;
+        let text_layout_options = get_text_layout_options(&display_rects[*node_id], None, None, Vec::new());
+        Some((*node_id, get_intrinsic_widths(words, scaled_words, &text_layout_options)))
     }).collect()
 }
 
 fn create_word_positions<'a>(
     words: &BTreeMap<NodeId, Words>,
-    scaled_words: &BTreeMap<NodeId, (ScaledWords, FontInstanceKey)>,
+    scaled_words: &BTreeMap<NodeId, (ScaledWords, Vec<FontInstanceKey>)>,
     display_rects: &NodeDataContainer<DisplayRectangle<'a>>,
     max_widths: &BTreeMap<NodeId, PixelSize>,
-    inline_texts: &BTreeMap<NodeId, InlineText>,
-) -> BTreeMap<NodeId, (WordPositions, FontInstanceKey)> {
+    resolve_context: &PixelResolveContext,
+) -> BTreeMap<NodeId, (WordPositions, Vec<FontInstanceKey>)> {
 
     use text_layout;
 
     words.iter().filter_map(|(node_id, words)| {
 
         let rect = &display_rects[*node_id];
-        let (scaled_words, font_instance_key) = scaled_words.get(&node_id)?;
+        let (scaled_words, font_instance_keys) = scaled_words.get(&node_id)?;
 
-        let font_size = get_font_size(&rect.style).0;
+        let font_size = get_font_size_px(&rect.style, resolve_context);
         let max_horizontal_width = max_widths.get(&node_id).cloned();
-        let leading = inline_texts.get(&node_id).map(|inline_text| inline_text.horizontal_margin + inline_text.horizontal_padding);
 
         // TODO: Make this configurable
         let text_holes = Vec::new();
-        let text_layout_options = get_text_layout_options(&rect, max_horizontal_width, leading, text_holes);
+        let text_layout_options = get_text_layout_options(&rect, max_horizontal_width, None, text_holes);
 
         // TODO: handle overflow / scrollbar_style !
         let positioned_words = text_layout::position_words(
             words, scaled_words,
             &text_layout_options,
-            font_size.to_pixels()
+            font_size,
         );
 
-        Some((*node_id, (positioned_words, *font_instance_key)))
+        Some((*node_id, (positioned_words, font_instance_keys.clone())))
+    }).collect()
+}
+
+/// Returns the texts of all paragraphs as items for `layout_inline_formatting_context`
+fn create_inline_text_items<'a, 'b>(
+    inline_contexts: &BTreeMap<NodeId, InlineFormattingContext>,
+    words: &'a BTreeMap<NodeId, Words>,
+    scaled_words: &'a BTreeMap<NodeId, (ScaledWords, Vec<FontInstanceKey>)>,
+    display_rects: &NodeDataContainer<DisplayRectangle<'b>>,
+) -> BTreeMap<NodeId, InlineItem<'a>> {
+    inline_contexts.values().flat_map(|context| context.items.iter()).filter_map(|item| {
+        let text_id = match item {
+            InlineContent::Text(text_id) => *text_id,
+            InlineContent::Box(_) => return None,
+        };
+        let (scaled_words, _) = scaled_words.get(&text_id)?;
+        Some((text_id, InlineItem::Text {
+            words: words.get(&text_id)?,
+            scaled_words,
+            text_layout_options: get_text_layout_options(&display_rects[text_id], None, None, Vec::new()),
+        }))
     }).collect()
 }
 
+/// Layout of a paragraph, relative to the top left corner of the content box of the paragraph root
+#[derive(Debug, Clone)]
+struct InlineFormattingContextLayout {
+    /// Bounds of the texts, boxes, spans and floats (for boxes and floats including their margin)
+    bounds: BTreeMap<NodeId, LayoutRect>,
+    /// Word positions of the texts, see `InlineLayout::word_positions`
+    word_positions: BTreeMap<NodeId, WordPositions>,
+    /// Size of the lines and floats
+    content_size: LayoutSize,
+}
+
+/// Lays out a paragraph: the floats are placed at the top of the paragraph (see `place_floats`),
+/// the texts and boxes flow around them. `max_width` is the inner width of the paragraph root, on
+/// `None` the lines are only broken at the line breaks of the texts. `get_box_size` has to return
+/// the size of a box or float, including its margin.
+fn layout_inline_formatting_context<'a, F: Fn(NodeId) -> LayoutSize>(
+    context: &InlineFormattingContext,
+    node_hierarchy: &NodeHierarchy,
+    rect_layouts: &NodeDataContainer<RectLayout>,
+    text_items: &BTreeMap<NodeId, InlineItem<'a>>,
+    max_width: Option<f32>,
+    alignment_horz: StyleTextAlignmentHorz,
+    get_box_size: F,
+) -> InlineFormattingContextLayout {
+
+    use text_layout::position_inline_items;
+
+    let float_sizes = context.floats.iter()
+        .map(|float_id| (rect_layouts[*float_id].float.unwrap_or_default(), get_box_size(*float_id)))
+        .collect::<Vec<_>>();
+
+    // Without a maximum width, the floats are placed next to each other
+    let floats_width = float_sizes.iter().map(|(_, size)| size.width).sum();
+    let holes = place_floats(&float_sizes, max_width.unwrap_or(floats_width));
+
+    // Texts without a loaded font are skipped
+    let (item_ids, items): (Vec<NodeId>, Vec<InlineItem>) = context.items.iter().filter_map(|item| match item {
+        InlineContent::Text(text_id) => text_items.get(text_id).map(|text_item| (*text_id, text_item.clone())),
+        InlineContent::Box(box_id) => Some((*box_id, InlineItem::Block(get_box_size(*box_id)))),
+    }).unzip();
+
+    let inline_layout = position_inline_items(&items, max_width, &holes, alignment_horz);
+
+    let mut bounds = item_ids.iter().cloned().zip(inline_layout.item_bounds.into_iter()).collect::<BTreeMap<NodeId, LayoutRect>>();
+
+    let word_positions = item_ids.iter().cloned().zip(inline_layout.word_positions.into_iter())
+        .filter_map(|(text_id, word_positions)| Some((text_id, word_positions?)))
+        .collect();
+
+    // Spans enclose their children - the inner spans come after the outer spans
+    for span_id in context.spans.iter().rev() {
+        let span_bounds = span_id.children(node_hierarchy)
+            .filter_map(|child_id| bounds.get(&child_id).cloned())
+            .fold(None, |span_bounds: Option<LayoutRect>, child_bounds| Some(match span_bounds {
+                Some(span_bounds) => span_bounds.union(&child_bounds),
+                None => child_bounds,
+            }));
+        if let Some(span_bounds) = span_bounds {
+            bounds.insert(*span_id, span_bounds);
+        }
+    }
+
+    let content_size = holes.iter().fold(inline_layout.content_size, |content_size, hole| {
+        LayoutSize::new(content_size.width.max(hole.max_x()), content_size.height.max(hole.max_y()))
+    });
+
+    bounds.extend(context.floats.iter().cloned().zip(holes.into_iter()));
+
+    InlineFormattingContextLayout {
+        bounds,
+        word_positions,
+        content_size,
+    }
+}
+
 fn get_text_layout_options(
     rect: &DisplayRectangle,
     max_horizontal_width: Option<f32>,
@@ -1544,7 +3519,7 @@ mod layout_tests {
         let arena = get_testing_hierarchy();
         let mut arena_data = vec![RectLayout::default(); arena.len()];
         for (id, rect) in constraints {
-            arena_data[*id] = *rect;
+            arena_data[*id] = rect.clone();
         }
         (arena, NodeDataContainer { internal: arena_data })
     }
@@ -1562,7 +3537,7 @@ mod layout_tests {
         assert_eq!(determine_preferred_width(&layout, None), WhConstraint::Unconstrained);
 
         let layout = RectLayout {
-            width: Some(LayoutWidth(PixelValue::px(500.0))),
+            width: Some(LayoutWidth::Exact(PixelValue::px(500.0))),
             min_width: None,
             max_width: None,
             .. Default::default()
@@ -1570,7 +3545,7 @@ mod layout_tests {
         assert_eq!(determine_preferred_width(&layout, None), WhConstraint::EqualTo(500.0));
 
         let layout = RectLayout {
-            width: Some(LayoutWidth(PixelValue::px(500.0))),
+            width: Some(LayoutWidth::Exact(PixelValue::px(500.0))),
             min_width: Some(LayoutMinWidth(PixelValue::px(600.0))),
             max_width: None,
             .. Default::default()
@@ -1578,7 +3553,7 @@ mod layout_tests {
         assert_eq!(determine_preferred_width(&layout, None), WhConstraint::EqualTo(600.0));
 
         let layout = RectLayout {
-            width: Some(LayoutWidth(PixelValue::px(10000.0))),
+            width: Some(LayoutWidth::Exact(PixelValue::px(10000.0))),
             min_width: Some(LayoutMinWidth(PixelValue::px(600.0))),
             max_width: Some(LayoutMaxWidth(PixelValue::px(800.0))),
             .. Default::default()
@@ -1602,7 +3577,7 @@ mod layout_tests {
         assert_eq!(determine_preferred_width(&layout, None), WhConstraint::Between(0.0, 800.0));
 
         let layout = RectLayout {
-            width: Some(LayoutWidth(PixelValue::px(1000.0))),
+            width: Some(LayoutWidth::Exact(PixelValue::px(1000.0))),
             min_width: None,
             max_width: Some(LayoutMaxWidth(PixelValue::px(800.0))),
             .. Default::default()
@@ -1610,7 +3585,7 @@ mod layout_tests {
         assert_eq!(determine_preferred_width(&layout, None), WhConstraint::EqualTo(800.0));
 
         let layout = RectLayout {
-            width: Some(LayoutWidth(PixelValue::px(1200.0))),
+            width: Some(LayoutWidth::Exact(PixelValue::px(1200.0))),
             min_width: Some(LayoutMinWidth(PixelValue::px(1000.0))),
             max_width: Some(LayoutMaxWidth(PixelValue::px(800.0))),
             .. Default::default()
@@ -1618,7 +3593,7 @@ mod layout_tests {
         assert_eq!(determine_preferred_width(&layout, None), WhConstraint::EqualTo(800.0));
 
         let layout = RectLayout {
-            width: Some(LayoutWidth(PixelValue::px(1200.0))),
+            width: Some(LayoutWidth::Exact(PixelValue::px(1200.0))),
             min_width: Some(LayoutMinWidth(PixelValue::px(1000.0))),
             max_width: Some(LayoutMaxWidth(PixelValue::px(400.0))),
             .. Default::default()
@@ -1730,7 +3705,7 @@ mod layout_tests {
         //    '   '-- 4     -- [] - expecting width to stretch to 80px (half of 160)
         //    '-- 5         -- [] - expecting width to stretch to 554px (754 - 200px max-width of earlier sibling)
 
-        width_filled_out_data.apply_flex_grow(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth, window_width);
+        width_filled_out_data.apply_flex_grow(&node_hierarchy, &node_data, &mut node_data.clone(), &non_leaf_nodes_sorted_by_depth, window_width, &FlexLines::new());
 
         assert_eq!(width_filled_out_data[NodeId::new(0)].solved_result(), WidthSolvedResult {
             min_width: 40.0,
@@ -1757,4 +3732,601 @@ mod layout_tests {
             space_added: window_width - 200.0,
         });
     }
+
+    /// In the testing hierarchy, node 5 is not linked as the next sibling of node 2,
+    /// so `children()` of node 1 only yields node 2 - links node 5 as the second child of node 1
+    fn link_children_of_node_1(node_hierarchy: &mut NodeHierarchy) {
+        node_hierarchy[NodeId::new(1)].next_sibling = None;
+        node_hierarchy[NodeId::new(1)].last_child = Some(NodeId::new(5));
+        node_hierarchy[NodeId::new(2)].next_sibling = Some(NodeId::new(5));
+    }
+
+    #[test]
+    fn test_break_into_flex_lines() {
+
+        use azul_css::*;
+
+        let (mut node_hierarchy, node_data) = get_display_rectangle_arena(&[]);
+        link_children_of_node_1(&mut node_hierarchy);
+
+        // Node 1 has the children 2 and 5, each child is 60px wide
+        let get_item_size = |_| 60.0;
+
+        assert_eq!(
+            break_into_flex_lines(NodeId::new(1), &node_hierarchy, &node_data, 150.0, 0.0, get_item_size),
+            vec![vec![NodeId::new(2), NodeId::new(5)]]
+        );
+        assert_eq!(
+            break_into_flex_lines(NodeId::new(1), &node_hierarchy, &node_data, 100.0, 0.0, get_item_size),
+            vec![vec![NodeId::new(2)], vec![NodeId::new(5)]]
+        );
+
+        // An item that is larger than the container still gets its own line
+        assert_eq!(
+            break_into_flex_lines(NodeId::new(1), &node_hierarchy, &node_data, 10.0, 0.0, get_item_size),
+            vec![vec![NodeId::new(2)], vec![NodeId::new(5)]]
+        );
+
+        // Absolute positioned items are not part of any line
+        let (mut node_hierarchy, node_data) = get_display_rectangle_arena(&[
+            (5, RectLayout {
+                position: Some(LayoutPosition::Absolute),
+                .. Default::default()
+            }),
+        ]);
+        link_children_of_node_1(&mut node_hierarchy);
+
+        assert_eq!(
+            break_into_flex_lines(NodeId::new(1), &node_hierarchy, &node_data, 100.0, 0.0, get_item_size),
+            vec![vec![NodeId::new(2)]]
+        );
+    }
+
+    #[test]
+    fn test_align_flex_lines() {
+
+        use azul_css::LayoutAlignContent;
+
+        let mut line_sizes = [20.0, 30.0];
+        assert_eq!(align_flex_lines(LayoutAlignContent::Start, &mut line_sizes, 100.0), (0.0, 0.0));
+        assert_eq!(align_flex_lines(LayoutAlignContent::End, &mut line_sizes, 100.0), (50.0, 0.0));
+        assert_eq!(align_flex_lines(LayoutAlignContent::Center, &mut line_sizes, 100.0), (25.0, 0.0));
+        assert_eq!(align_flex_lines(LayoutAlignContent::SpaceBetween, &mut line_sizes, 100.0), (0.0, 50.0));
+        assert_eq!(align_flex_lines(LayoutAlignContent::SpaceAround, &mut line_sizes, 100.0), (12.5, 25.0));
+        assert_eq!(line_sizes, [20.0, 30.0]);
+
+        // Stretch grows the lines instead of adding space between them
+        assert_eq!(align_flex_lines(LayoutAlignContent::Stretch, &mut line_sizes, 100.0), (0.0, 0.0));
+        assert_eq!(line_sizes, [45.0, 55.0]);
+
+        // Overflowing lines can't be stretched
+        let mut line_sizes = [80.0, 80.0];
+        assert_eq!(align_flex_lines(LayoutAlignContent::Stretch, &mut line_sizes, 100.0), (0.0, 0.0));
+        assert_eq!(line_sizes, [80.0, 80.0]);
+    }
+
+    /// Tests that the children of a `flex-wrap: wrap` container are broken into lines
+    #[test]
+    fn test_flex_wrap_width() {
+
+        use azul_css::*;
+
+        let (mut node_hierarchy, node_data) = get_display_rectangle_arena(&[
+            (1, RectLayout {
+                width: Some(LayoutWidth::Exact(PixelValue::px(200.0))),
+                direction: Some(LayoutDirection::Row),
+                wrap: Some(LayoutWrap::Wrap),
+                .. Default::default()
+            }),
+            (2, RectLayout {
+                width: Some(LayoutWidth::Exact(PixelValue::px(120.0))),
+                .. Default::default()
+            }),
+            (5, RectLayout {
+                width: Some(LayoutWidth::Exact(PixelValue::px(120.0))),
+                .. Default::default()
+            }),
+        ]);
+
+        link_children_of_node_1(&mut node_hierarchy);
+
+        let preferred_widths = node_data.transform(|_, _| None);
+        let mut width_filled_out_data = NodeDataContainer::<WidthCalculatedRect>::from_rect_layout_arena(&node_data, &preferred_widths);
+        let non_leaf_nodes_sorted_by_depth = node_hierarchy.get_parents_sorted_by_depth();
+
+        width_filled_out_data.bubble_preferred_widths_to_parents(&node_hierarchy, &node_data, &non_leaf_nodes_sorted_by_depth);
+        let (flex_lines, _) = width_filled_out_data.apply_flex_grow(&node_hierarchy, &node_data, &mut node_data.clone(), &non_leaf_nodes_sorted_by_depth, 754.0, &FlexLines::new());
+
+        // 120px + 120px don't fit into 200px, so node 5 has to wrap into a second line
+        assert_eq!(flex_lines.get(&NodeId::new(1)), Some(&vec![vec![NodeId::new(2)], vec![NodeId::new(5)]]));
+
+        // A multi-line container only needs to be as wide as its largest item
+        assert_eq!(width_filled_out_data.max_children_flex_basis(NodeId::new(1), &node_hierarchy, &node_data), 120.0);
+        assert_eq!(width_filled_out_data[NodeId::new(1)].solved_result().total(), 200.0);
+        assert_eq!(width_filled_out_data[NodeId::new(2)].solved_result().total(), 120.0);
+        assert_eq!(width_filled_out_data[NodeId::new(5)].solved_result().total(), 120.0);
+    }
+
+    /// Tests that `display: none` nodes are unlinked from the hierarchy and don't take up any space
+    #[test]
+    fn test_remove_display_none_nodes() {
+
+        use azul_css::{LayoutDirection, LayoutDisplay};
+
+        // 0
+        // '- 1
+        // '- 2
+        // '- 3
+        let node_hierarchy = NodeHierarchy {
+            internal: vec![
+                Node { first_child: Some(NodeId::new(1)), last_child: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), next_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(1)), next_sibling: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(2)), .. Default::default() },
+            ]
+        };
+
+        let mut node_data = NodeDataContainer { internal: vec![RectLayout::default(); 4] };
+        node_data[NodeId::new(0)].direction = Some(LayoutDirection::Row);
+        node_data[NodeId::new(2)].display = Some(LayoutDisplay::None);
+
+        let pruned_hierarchy = remove_display_none_nodes(&node_hierarchy, &node_data);
+        assert_eq!(NodeId::new(0).children(&pruned_hierarchy).collect::<Vec<_>>(), vec![NodeId::new(1), NodeId::new(3)]);
+        assert_eq!(pruned_hierarchy[NodeId::new(3)].previous_sibling, Some(NodeId::new(1)));
+        assert_eq!(pruned_hierarchy[NodeId::new(2)].parent, None);
+        assert!(is_in_display_none_subtree(NodeId::new(2), &node_hierarchy, &node_data));
+        assert!(!is_in_display_none_subtree(NodeId::new(3), &node_hierarchy, &node_data));
+
+        // The remaining two nodes share the space of the parent
+        let solved_widths = solve_flex_layout_width(&pruned_hierarchy, &node_data, &node_data.transform(|_, _| None), 300.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(1)].total(), 150.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(3)].total(), 150.0);
+
+        // Removing the first and last child updates the parent
+        node_data[NodeId::new(1)].display = Some(LayoutDisplay::None);
+        node_data[NodeId::new(3)].display = Some(LayoutDisplay::None);
+        let pruned_hierarchy = remove_display_none_nodes(&node_hierarchy, &node_data);
+        assert_eq!(pruned_hierarchy[NodeId::new(0)].first_child, None);
+        assert_eq!(pruned_hierarchy[NodeId::new(0)].last_child, None);
+    }
+
+    #[test]
+    fn test_grid_layout() {
+
+        use azul_css::{LayoutDisplay, LayoutGridColumn, FloatValue};
+
+        // 0
+        // '- 1
+        // '- 2
+        // '- 3
+        // '- 4
+        let node_hierarchy = NodeHierarchy {
+            internal: vec![
+                Node { first_child: Some(NodeId::new(1)), last_child: Some(NodeId::new(4)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), next_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(1)), next_sibling: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(2)), next_sibling: Some(NodeId::new(4)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(3)), .. Default::default() },
+            ]
+        };
+
+        let px = |value| GridTrackSize::Breadth(GridTrackBreadth::Length(PixelValue::px(value)));
+        let fr = |value| GridTrackSize::Breadth(GridTrackBreadth::Fraction(FloatValue::new(value)));
+
+        // grid-template-columns: 100px 1fr 2fr; grid-gap: 5px 10px;
+        let mut node_data = NodeDataContainer { internal: vec![RectLayout::default(); 5] };
+        node_data[NodeId::new(0)].display = Some(LayoutDisplay::Grid);
+        node_data[NodeId::new(0)].grid_template_columns = Some(LayoutGridTemplateColumns(vec![px(100.0), fr(1.0), fr(2.0)]));
+        node_data[NodeId::new(0)].grid_gap = Some(LayoutGridGap { row: PixelValue::px(5.0), column: PixelValue::px(10.0) });
+        // grid-column: span 2;
+        node_data[NodeId::new(2)].grid_column = Some(LayoutGridColumn { start: GridLine::Span(2), end: GridLine::Auto });
+        node_data[NodeId::new(3)].height = Some(LayoutHeight::Exact(PixelValue::px(50.0)));
+        // grid-column: 3;
+        node_data[NodeId::new(4)].grid_column = Some(LayoutGridColumn { start: GridLine::Line(3), end: GridLine::Auto });
+
+        let area = |row_start, row_end, column_start, column_end| GridArea { row_start, row_end, column_start, column_end };
+        assert_eq!(place_grid_items(NodeId::new(0), &node_hierarchy, &node_data), GridPlacement {
+            column_count: 3,
+            row_count: 2,
+            items: vec![
+                (NodeId::new(1), area(0, 1, 0, 1)),
+                (NodeId::new(2), area(0, 1, 1, 3)),
+                (NodeId::new(3), area(1, 2, 0, 1)),
+                (NodeId::new(4), area(1, 2, 2, 3)),
+            ],
+        });
+
+        // 300px - 100px - 2 * 10px = 180px for 3fr
+        let solved_widths = solve_flex_layout_width(&node_hierarchy, &node_data, &node_data.transform(|_, _| None), 300.0);
+        assert_eq!(solved_widths.grids[&NodeId::new(0)].1.sizes, vec![100.0, 60.0, 120.0]);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(1)].total(), 100.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(2)].total(), 190.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(4)].total(), 120.0);
+
+        // The two `auto` rows share the leftover space, item 3 keeps its fixed height
+        let solved_heights = solve_flex_layout_height(&node_hierarchy, &solved_widths, &node_data.transform(|_, _| None), 200.0);
+        assert_eq!(solved_heights.grids[&NodeId::new(0)].1.sizes, vec![72.5, 122.5]);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(3)].total(), 50.0);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(4)].total(), 122.5);
+
+        let x_positions = get_x_positions(&solved_widths, &solved_heights, &node_hierarchy, LayoutPoint::zero());
+        let y_positions = get_y_positions(&solved_heights, &solved_widths, &node_hierarchy, LayoutPoint::zero());
+        assert_eq!(x_positions[NodeId::new(2)].0, 110.0);
+        assert_eq!(x_positions[NodeId::new(4)].0, 180.0);
+        assert_eq!(y_positions[NodeId::new(3)].0, 77.5);
+    }
+
+    #[test]
+    fn test_flex_item_properties() {
+
+        use azul_css::{LayoutDirection, LayoutFlexGrow, LayoutOrder, LayoutAlignSelf, FloatValue};
+
+        // 0
+        // '- 1
+        // '- 2
+        // '- 3
+        let node_hierarchy = NodeHierarchy {
+            internal: vec![
+                Node { first_child: Some(NodeId::new(1)), last_child: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), next_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(1)), next_sibling: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(2)), .. Default::default() },
+            ]
+        };
+
+        // flex-direction: row; column-gap: 10px;
+        let mut node_data = NodeDataContainer { internal: vec![RectLayout::default(); 4] };
+        node_data[NodeId::new(0)].direction = Some(LayoutDirection::Row);
+        node_data[NodeId::new(0)].gap = Some(LayoutGap { row: None, column: Some(PixelValue::px(10.0)) });
+        // flex: 0 0 100px;
+        node_data[NodeId::new(1)].flex_basis = Some(LayoutFlexBasis::Exact(PixelValue::px(100.0)));
+        node_data[NodeId::new(1)].flex_grow = Some(LayoutFlexGrow(FloatValue::new(0.0)));
+        // order: -1; align-self: flex-end;
+        node_data[NodeId::new(2)].order = Some(LayoutOrder(-1));
+        node_data[NodeId::new(2)].width = Some(LayoutWidth::Exact(PixelValue::px(50.0)));
+        node_data[NodeId::new(2)].height = Some(LayoutHeight::Exact(PixelValue::px(30.0)));
+        node_data[NodeId::new(2)].align_self = Some(LayoutAlignSelf::End);
+        // align-self: center;
+        node_data[NodeId::new(3)].height = Some(LayoutHeight::Exact(PixelValue::px(40.0)));
+        node_data[NodeId::new(3)].align_self = Some(LayoutAlignSelf::Center);
+
+        // 300px - 100px - 50px - 2 * 10px = 130px for the only growing item
+        let solved_widths = solve_flex_layout_width(&node_hierarchy, &node_data, &node_data.transform(|_, _| None), 300.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(1)].total(), 100.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(2)].total(), 50.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(3)].total(), 130.0);
+
+        // Only the item without an `align-self` is stretched
+        let solved_heights = solve_flex_layout_height(&node_hierarchy, &solved_widths, &node_data.transform(|_, _| None), 200.0);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(1)].total(), 200.0);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(2)].total(), 30.0);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(3)].total(), 40.0);
+
+        // Item 2 comes first because of its `order`
+        let x_positions = get_x_positions(&solved_widths, &solved_heights, &node_hierarchy, LayoutPoint::zero());
+        let y_positions = get_y_positions(&solved_heights, &solved_widths, &node_hierarchy, LayoutPoint::zero());
+        assert_eq!(x_positions[NodeId::new(2)].0, 0.0);
+        assert_eq!(x_positions[NodeId::new(1)].0, 60.0);
+        assert_eq!(x_positions[NodeId::new(3)].0, 170.0);
+        assert_eq!(y_positions[NodeId::new(1)].0, 0.0);
+        assert_eq!(y_positions[NodeId::new(2)].0, 170.0);
+        assert_eq!(y_positions[NodeId::new(3)].0, 80.0);
+    }
+
+    #[test]
+    fn test_positioning() {
+
+        use azul_css::{LayoutDirection, LayoutJustifyContent};
+
+        // 0
+        // '- 1
+        // '- 2
+        // |  '- 4
+        // '- 3
+        let node_hierarchy = NodeHierarchy {
+            internal: vec![
+                Node { first_child: Some(NodeId::new(1)), last_child: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), next_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(1)), next_sibling: Some(NodeId::new(3)), first_child: Some(NodeId::new(4)), last_child: Some(NodeId::new(4)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(2)), .. Default::default() },
+            ]
+        };
+
+        // flex-direction: row; justify-content: space-between;
+        let mut node_data = NodeDataContainer { internal: vec![RectLayout::default(); 5] };
+        node_data[NodeId::new(0)].direction = Some(LayoutDirection::Row);
+        node_data[NodeId::new(0)].justify_content = Some(LayoutJustifyContent::SpaceBetween);
+        node_data[NodeId::new(1)].width = Some(LayoutWidth::Exact(PixelValue::px(100.0)));
+        node_data[NodeId::new(1)].height = Some(LayoutHeight::Exact(PixelValue::px(50.0)));
+        // position: relative; left: 10px; top: 5px;
+        node_data[NodeId::new(2)].position = Some(LayoutPosition::Relative);
+        node_data[NodeId::new(2)].width = Some(LayoutWidth::Exact(PixelValue::px(100.0)));
+        node_data[NodeId::new(2)].height = Some(LayoutHeight::Exact(PixelValue::px(50.0)));
+        node_data[NodeId::new(2)].left = Some(LayoutLeft(PixelValue::px(10.0)));
+        node_data[NodeId::new(2)].top = Some(LayoutTop(PixelValue::px(5.0)));
+        // position: fixed; right: 0px; width: 50px;
+        node_data[NodeId::new(3)].position = Some(LayoutPosition::Fixed);
+        node_data[NodeId::new(3)].width = Some(LayoutWidth::Exact(PixelValue::px(50.0)));
+        node_data[NodeId::new(3)].right = Some(LayoutRight(PixelValue::px(0.0)));
+        // position: absolute; left: 20px; right: 30px;
+        node_data[NodeId::new(4)].position = Some(LayoutPosition::Absolute);
+        node_data[NodeId::new(4)].left = Some(LayoutLeft(PixelValue::px(20.0)));
+        node_data[NodeId::new(4)].right = Some(LayoutRight(PixelValue::px(30.0)));
+
+        // The absolute item is stretched between its insets, inside of its positioned parent
+        let solved_widths = solve_flex_layout_width(&node_hierarchy, &node_data, &node_data.transform(|_, _| None), 400.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(3)].total(), 50.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(4)].total(), 50.0);
+
+        // The fixed item takes the height of the root, the absolute item the height of its positioned parent
+        let solved_heights = solve_flex_layout_height(&node_hierarchy, &solved_widths, &node_data.transform(|_, _| None), 300.0);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(3)].total(), 300.0);
+        assert_eq!(solved_heights.solved_heights[NodeId::new(4)].total(), 50.0);
+
+        let x_positions = get_x_positions(&solved_widths, &solved_heights, &node_hierarchy, LayoutPoint::zero());
+        let y_positions = get_y_positions(&solved_heights, &solved_widths, &node_hierarchy, LayoutPoint::zero());
+
+        // 400px - 2 * 100px = 200px between the two in-flow items
+        assert_eq!(x_positions[NodeId::new(1)].0, 0.0);
+        // The relative item is shifted by its offsets, without affecting its siblings
+        assert_eq!(x_positions[NodeId::new(2)].0, 310.0);
+        assert_eq!(y_positions[NodeId::new(2)].0, 5.0);
+        // The fixed item is aligned to the right edge of the root
+        assert_eq!(x_positions[NodeId::new(3)].0, 350.0);
+        assert_eq!(y_positions[NodeId::new(3)].0, 0.0);
+        // The absolute item moves along with its positioned parent
+        assert_eq!(x_positions[NodeId::new(4)].0, 330.0);
+        assert_eq!(y_positions[NodeId::new(4)].0, 5.0);
+    }
+
+    #[test]
+    fn test_intrinsic_sizes() {
+
+        use azul_css::LayoutDirection;
+
+        let text = |min_content, max_content| Some(IntrinsicSizes { min_content, max_content, preferred: max_content });
+
+        // 0
+        // '- 1
+        // '- 2
+        // '- 3
+        let node_hierarchy = NodeHierarchy {
+            internal: vec![
+                Node { first_child: Some(NodeId::new(1)), last_child: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), next_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(1)), next_sibling: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(2)), .. Default::default() },
+            ]
+        };
+
+        let mut node_data = NodeDataContainer { internal: vec![RectLayout::default(); 4] };
+        node_data[NodeId::new(0)].direction = Some(LayoutDirection::Row);
+        node_data[NodeId::new(1)].width = Some(LayoutWidth::MinContent);
+        node_data[NodeId::new(2)].width = Some(LayoutWidth::MaxContent);
+        node_data[NodeId::new(3)].width = Some(LayoutWidth::FitContent);
+        let content_widths = NodeDataContainer { internal: vec![None, text(30.0, 80.0), text(30.0, 80.0), text(30.0, 500.0)] };
+
+        // The fit-content item grows into the remaining 400px - 30px - 80px, but not beyond 500px
+        let solved_widths = solve_flex_layout_width(&node_hierarchy, &node_data, &content_widths, 400.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(1)].total(), 30.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(2)].total(), 80.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(3)].total(), 290.0);
+
+        // 0
+        // '- 1
+        // |  '- 2
+        // |  '- 3
+        // '- 4
+        //    '- 5
+        //    '- 6
+        let node_hierarchy = NodeHierarchy {
+            internal: vec![
+                Node { first_child: Some(NodeId::new(1)), last_child: Some(NodeId::new(4)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), next_sibling: Some(NodeId::new(4)), first_child: Some(NodeId::new(2)), last_child: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(1)), next_sibling: Some(NodeId::new(3)), .. Default::default() },
+                Node { parent: Some(NodeId::new(1)), previous_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(1)), first_child: Some(NodeId::new(5)), last_child: Some(NodeId::new(6)), .. Default::default() },
+                Node { parent: Some(NodeId::new(4)), next_sibling: Some(NodeId::new(6)), .. Default::default() },
+                Node { parent: Some(NodeId::new(4)), previous_sibling: Some(NodeId::new(5)), .. Default::default() },
+            ]
+        };
+
+        let mut node_data = NodeDataContainer { internal: vec![RectLayout::default(); 7] };
+        node_data[NodeId::new(0)].direction = Some(LayoutDirection::Row);
+        // flex-direction: row; width: max-content;
+        node_data[NodeId::new(1)].direction = Some(LayoutDirection::Row);
+        node_data[NodeId::new(1)].width = Some(LayoutWidth::MaxContent);
+        node_data[NodeId::new(2)].width = Some(LayoutWidth::Exact(PixelValue::px(20.0)));
+        node_data[NodeId::new(2)].margin = Some(LayoutMargin { left: Some(PixelValue::px(5.0)), .. Default::default() });
+        // flex-direction: column; width: min-content;
+        node_data[NodeId::new(4)].direction = Some(LayoutDirection::Column);
+        node_data[NodeId::new(4)].width = Some(LayoutWidth::MinContent);
+        node_data[NodeId::new(6)].width = Some(LayoutWidth::Exact(PixelValue::px(50.0)));
+        let content_widths = NodeDataContainer { internal: vec![None, None, None, text(40.0, 60.0), None, text(40.0, 60.0), None] };
+
+        // Along the main axis the children are added up: 5px + 20px + 60px,
+        // along the cross axis the largest child wins: 50px > 40px
+        let solved_widths = solve_flex_layout_width(&node_hierarchy, &node_data, &content_widths, 400.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(1)].total(), 85.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(3)].total(), 60.0);
+        assert_eq!(solved_widths.solved_widths[NodeId::new(4)].total(), 50.0);
+    }
+
+    #[test]
+    fn test_grid_track_sizing() {
+
+        use azul_css::{LayoutDisplay, LayoutGridTemplateAreas, LayoutGridRow, LayoutGridColumn, FloatValue};
+
+        // 0
+        // '- 1
+        // '- 2
+        let node_hierarchy = NodeHierarchy {
+            internal: vec![
+                Node { first_child: Some(NodeId::new(1)), last_child: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), next_sibling: Some(NodeId::new(2)), .. Default::default() },
+                Node { parent: Some(NodeId::new(0)), previous_sibling: Some(NodeId::new(1)), .. Default::default() },
+            ]
+        };
+
+        let px = |value| GridTrackBreadth::Length(PixelValue::px(value));
+        let fr = |value| GridTrackBreadth::Fraction(FloatValue::new(value));
+
+        // grid-template-areas: "sidebar main"; grid-template-columns: minmax(50px, 100px) auto 1fr;
+        let mut node_data = NodeDataContainer { internal: vec![RectLayout::default(); 3] };
+        node_data[NodeId::new(0)].display = Some(LayoutDisplay::Grid);
+        node_data[NodeId::new(0)].grid_template_areas = Some(LayoutGridTemplateAreas {
+            areas: vec![
+                ("sidebar".into(), GridArea { row_start: 0, row_end: 1, column_start: 0, column_end: 1 }),
+                ("main".into(), GridArea { row_start: 0, row_end: 1, column_start: 1, column_end: 2 }),
+            ],
+            row_count: 1,
+            column_count: 2,
+        });
+        node_data[NodeId::new(0)].grid_template_columns = Some(LayoutGridTemplateColumns(vec![
+            GridTrackSize::MinMax(px(50.0), px(100.0)),
+            GridTrackSize::Breadth(GridTrackBreadth::Auto),
+            GridTrackSize::Breadth(fr(1.0)),
+        ]));
+
+        // grid-area: main; is placed into the second column, the other item is auto-placed into the first free cell
+        node_data[NodeId::new(1)].grid_row = Some(LayoutGridRow { start: GridLine::Area("main".into()), end: GridLine::Area("main".into()) });
+        node_data[NodeId::new(1)].grid_column = Some(LayoutGridColumn { start: GridLine::Area("main".into()), end: GridLine::Area("main".into()) });
+
+        let placement = place_grid_items(NodeId::new(0), &node_hierarchy, &node_data);
+        assert_eq!(placement.items[0].1, GridArea { row_start: 0, row_end: 1, column_start: 1, column_end: 2 });
+        assert_eq!(placement.items[1].1, GridArea { row_start: 0, row_end: 1, column_start: 0, column_end: 1 });
+
+        // The `auto` column is as large as its item, the fixed maximum is reached before the `fr` column grows
+        let item_size = |node_id: NodeId| if node_id == NodeId::new(1) { 30.0 } else { 0.0 };
+        let get_tracks = |available_space| solve_grid_tracks(&node_data[NodeId::new(0)], &placement, LayoutAxis::Horizontal, available_space, item_size).sizes;
+        assert_eq!(get_tracks(None), vec![50.0, 30.0, 0.0]);
+        assert_eq!(get_tracks(Some(100.0)), vec![70.0, 30.0, 0.0]);
+        assert_eq!(get_tracks(Some(300.0)), vec![100.0, 30.0, 170.0]);
+
+        // Negative lines count from the end of the explicit grid, spans are resolved against the definite line
+        let no_areas = |_: &str| None;
+        assert_eq!(resolve_grid_span(&GridLine::Line(1), &GridLine::Line(-1), 3, no_areas), (Some(0), 3));
+        assert_eq!(resolve_grid_span(&GridLine::Span(2), &GridLine::Line(4), 3, no_areas), (Some(1), 2));
+        assert_eq!(resolve_grid_span(&GridLine::Auto, &GridLine::Span(3), 3, no_areas), (None, 3));
+        assert_eq!(resolve_grid_span(&GridLine::Line(3), &GridLine::Line(1), 3, no_areas), (Some(0), 2));
+    }
+
+    #[test]
+    fn test_resolve_relative_units() {
+
+        let (node_hierarchy, node_data) = get_display_rectangle_arena(&[
+            (1, RectLayout {
+                width: Some(LayoutWidth::Exact(PixelValue::percent(50.0))),
+                padding: Some(LayoutPadding {
+                    left: Some(PixelValue::percent(10.0)),
+                    right: Some(PixelValue::percent(10.0)),
+                    .. Default::default()
+                }),
+                .. Default::default()
+            }),
+            (2, RectLayout {
+                width: Some(LayoutWidth::Exact(PixelValue::percent(50.0))),
+                min_height: Some(LayoutMinHeight(PixelValue::vh(50.0))),
+                margin: Some(LayoutMargin {
+                    left: Some(PixelValue::calc(&[(SizeMetric::Percent, 50.0), (SizeMetric::Rem, 1.0)])),
+                    .. Default::default()
+                }),
+                .. Default::default()
+            }),
+            (5, RectLayout {
+                width: Some(LayoutWidth::Exact(PixelValue::rem(2.0))),
+                .. Default::default()
+            }),
+        ]);
+
+        let resolve_context = PixelResolveContext {
+            root_font_size: 16.0,
+            viewport_size: ::azul_css::LayoutSize::new(800.0, 600.0),
+        };
+
+        // Percentages are kept until the size of the containing block is known
+        let rect_layouts = node_data.transform(|layout, _| resolve_rect_layout(layout, &resolve_context));
+        assert_eq!(rect_layouts[NodeId::new(2)].width, Some(LayoutWidth::Exact(PixelValue::percent(50.0))));
+        assert_eq!(rect_layouts[NodeId::new(2)].min_height, Some(LayoutMinHeight(PixelValue::px(300.0))));
+        assert_eq!(rect_layouts[NodeId::new(2)].margin.unwrap().left, Some(PixelValue::calc(&[(SizeMetric::Percent, 50.0), (SizeMetric::Px, 16.0)])));
+        assert_eq!(rect_layouts[NodeId::new(5)].width, Some(LayoutWidth::Exact(PixelValue::px(32.0))));
+
+        let solved_widths = solve_flex_layout_width(&node_hierarchy, &rect_layouts, &node_data.transform(|_, _| None), 800.0);
+
+        // Node 1 is 50% of the window, its padding refers to the window as well
+        assert_eq!(solved_widths.solved_widths[NodeId::new(1)].total(), 400.0);
+        assert_eq!(solved_widths.layout_only_arena[NodeId::new(1)].padding.unwrap().left, Some(PixelValue::px(80.0)));
+
+        // Node 2 is 50% of the content box of node 1 (400px - 2 * 80px padding)
+        assert_eq!(solved_widths.solved_widths[NodeId::new(2)].total(), 120.0);
+        assert_eq!(solved_widths.layout_only_arena[NodeId::new(2)].margin.unwrap().left, Some(PixelValue::px(136.0)));
+
+        let solved_heights = solve_flex_layout_height(&node_hierarchy, &solved_widths, &node_data.transform(|_, _| None), 600.0);
+        assert_eq!(solved_heights.layout_only_arena[NodeId::new(2)].width, Some(LayoutWidth::Exact(PixelValue::px(120.0))));
+    }
+
+    #[test]
+    fn test_inline_formatting_context() {
+        use azul_css::LayoutDisplay;
+
+        // Node 2 is a span around the text 3 and the floating node 4, node 5 is an image
+        let (mut node_hierarchy, node_data) = get_display_rectangle_arena(&[
+            (2, RectLayout {
+                display: Some(LayoutDisplay::Inline),
+                .. Default::default()
+            }),
+            (4, RectLayout {
+                float: Some(LayoutFloat::Left),
+                width: Some(LayoutWidth::Exact(PixelValue::px(30.0))),
+                .. Default::default()
+            }),
+            (5, RectLayout {
+                margin: Some(LayoutMargin {
+                    top: Some(PixelValue::px(10.0)),
+                    .. Default::default()
+                }),
+                .. Default::default()
+            }),
+        ]);
+        link_children_of_node_1(&mut node_hierarchy);
+
+        let inline_contexts = get_inline_formatting_contexts(&node_hierarchy, &node_data, |node_id| node_id == NodeId::new(3));
+        assert_eq!(inline_contexts.keys().cloned().collect::<Vec<_>>(), vec![NodeId::new(1)]);
+
+        let context = &inline_contexts[&NodeId::new(1)];
+        assert_eq!(context.items, vec![InlineContent::Text(NodeId::new(3)), InlineContent::Box(NodeId::new(5))]);
+        assert_eq!(context.spans, vec![NodeId::new(2)]);
+        assert_eq!(context.floats, vec![NodeId::new(4)]);
+
+        // The flex solver only sees the float and the image as children of the paragraph
+        let flex_hierarchy = get_inline_layout_hierarchy(&node_hierarchy, &inline_contexts);
+        assert_eq!(NodeId::new(1).children(&flex_hierarchy).collect::<Vec<_>>(), vec![NodeId::new(4), NodeId::new(5)]);
+        assert_eq!(flex_hierarchy[NodeId::new(2)], Node::default());
+        assert_eq!(flex_hierarchy[NodeId::new(3)], Node::default());
+        assert_eq!(flex_hierarchy[NodeId::new(4)].parent, Some(NodeId::new(1)));
+
+        let flex_layouts = get_inline_flex_layouts(&node_data, &inline_contexts);
+        assert_eq!(flex_layouts[NodeId::new(1)].position, Some(LayoutPosition::Relative));
+        assert_eq!(flex_layouts[NodeId::new(4)].position, Some(LayoutPosition::Absolute));
+        assert_eq!(flex_layouts[NodeId::new(4)].width, Some(LayoutWidth::Exact(PixelValue::px(30.0))));
+        assert_eq!(flex_layouts[NodeId::new(5)].height, Some(LayoutHeight::FitContent));
+        assert_eq!(flex_layouts[NodeId::new(5)].margin, None);
+    }
+
+    #[test]
+    fn test_place_floats() {
+        let floats = [
+            (LayoutFloat::Left, LayoutSize::new(30.0, 20.0)),
+            (LayoutFloat::Right, LayoutSize::new(40.0, 10.0)),
+            (LayoutFloat::Left, LayoutSize::new(50.0, 10.0)),
+        ];
+
+        // The third float doesn't fit between the first two floats anymore
+        assert_eq!(place_floats(&floats, 100.0), vec![
+            LayoutRect::new(LayoutPoint::new(0.0, 0.0), LayoutSize::new(30.0, 20.0)),
+            LayoutRect::new(LayoutPoint::new(60.0, 0.0), LayoutSize::new(40.0, 10.0)),
+            LayoutRect::new(LayoutPoint::new(0.0, 20.0), LayoutSize::new(50.0, 10.0)),
+        ]);
+    }
 }
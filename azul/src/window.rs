@@ -25,7 +25,7 @@ use glium::{
     backend::{Context as BackendContext, Facade, glutin::DisplayCreationError},
 };
 use gleam::gl::{self, Gl};
-use azul_css::{Css, ColorU};
+use azul_css::{Css, ColorU, ScrollbarOrientation};
 #[cfg(debug_assertions)]
 use azul_css::HotReloadHandler;
 use {
@@ -36,7 +36,9 @@ use {
         Callback, DefaultCallbackSystem, StackCheckedPointer,
         DefaultCallback, DefaultCallbackId, Texture,
     },
-    display_list::ScrolledNodes,
+    display_list::{ScrolledNodes, CachedLayout},
+    animation::AnimationStates,
+    id_tree::NodeId,
 };
 pub use webrender::api::HitTestItem;
 pub use window_state::*;
@@ -358,6 +360,10 @@ pub struct Window<T> {
     pub(crate) internal: WindowInternal,
     /// States of scrolling animations, updated every frame
     pub(crate) scroll_states: ScrollStates,
+    /// Scrollbar thumb that is currently dragged with the mouse, if any
+    pub(crate) scrollbar_drag: Option<ScrollbarDrag>,
+    /// States of CSS transitions and animations, updated every frame
+    pub(crate) animation_states: AnimationStates,
     // The background thread that is running for this window.
     // pub(crate) background_thread: Option<JoinHandle<()>>,
     /// The style applied to the current window
@@ -403,8 +409,20 @@ impl ScrollStates {
         }
     }
 
+    /// Scrolls the node to an absolute position, i.e. when dragging the thumb of a scrollbar
+    pub(crate) fn scroll_node_to(&mut self, scroll_id: &ExternalScrollId, scroll_x: f32, scroll_y: f32) {
+        if let Some(entry) = self.0.get_mut(scroll_id) {
+            entry.set(scroll_x, scroll_y);
+        }
+    }
+
+    /// Inserts the scroll state of a node if it doesn't exist yet. If it does exist, updates
+    /// the overflow, since the content of the node may have changed since the last frame.
     pub(crate) fn ensure_initialized_scroll_state(&mut self, scroll_id: ExternalScrollId, overflow_x: f32, overflow_y: f32) {
-        self.0.entry(scroll_id).or_insert_with(|| ScrollState::new(overflow_x, overflow_y));
+        let entry = self.0.entry(scroll_id).or_insert_with(|| ScrollState::new(overflow_x, overflow_y));
+        entry.overflow_x = overflow_x;
+        entry.overflow_y = overflow_y;
+        entry.add(0.0, 0.0);
     }
 
     /// Removes all scroll states that weren't used in the last frame
@@ -445,6 +463,25 @@ impl ScrollState {
         self.scroll_amount_x = self.overflow_x.min(self.scroll_amount_x + x).max(0.0);
         self.scroll_amount_y = self.overflow_y.min(self.scroll_amount_y + y).max(0.0);
     }
+
+    pub fn set(&mut self, x: f32, y: f32) {
+        self.scroll_amount_x = self.overflow_x.min(x).max(0.0);
+        self.scroll_amount_y = self.overflow_y.min(y).max(0.0);
+    }
+}
+
+/// Stores where the thumb of a scrollbar was grabbed, so that the node
+/// can be scrolled relative to that position while the mouse is moving
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct ScrollbarDrag {
+    /// The node that owns the scrollbar
+    pub(crate) node_id: NodeId,
+    pub(crate) scroll_id: ExternalScrollId,
+    pub(crate) orientation: ScrollbarOrientation,
+    /// Position of the cursor along the track when the thumb was grabbed
+    pub(crate) start_cursor_pos: f32,
+    /// Scroll offset along the track when the thumb was grabbed
+    pub(crate) start_scroll_offset: f32,
 }
 
 impl Default for ScrollState {
@@ -461,6 +498,8 @@ impl Default for ScrollState {
 
 pub(crate) struct WindowInternal {
     pub(crate) last_scrolled_nodes: ScrolledNodes,
+    /// Layout of the last frame, re-used if the next frame only changes paint properties
+    pub(crate) cached_layout: Option<CachedLayout>,
     pub(crate) epoch: Epoch,
     pub(crate) pipeline_id: PipelineId,
     pub(crate) document_id: DocumentId,
@@ -598,7 +637,9 @@ impl<'a, T> Window<T> {
             #[cfg(debug_assertions)]
             css_loader: None,
             scroll_states: ScrollStates::new(),
-            internal: WindowInternal { epoch, pipeline_id, document_id, last_scrolled_nodes },
+            scrollbar_drag: None,
+            animation_states: AnimationStates::default(),
+            internal: WindowInternal { epoch, pipeline_id, document_id, last_scrolled_nodes, cached_layout: None },
             marker: PhantomData,
         };
 
@@ -695,6 +736,9 @@ impl<'a, T> Window<T> {
             window.set_max_dimensions(new_state.size.max_dimensions.map(Into::into));
             old_state.size.max_dimensions = new_state.size.max_dimensions;
         }
+
+        // Only affects the styling, takes effect on the next relayout
+        old_state.color_scheme = new_state.color_scheme;
     }
 
     #[allow(unused_variables)]
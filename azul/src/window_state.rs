@@ -9,6 +9,7 @@ use glium::glutin::{
     ModifiersState, dpi::{LogicalPosition, LogicalSize},
 };
 use webrender::api::HitTestItem;
+use azul_css::{ColorScheme, MediaQueryContext};
 use {
     app::FrameEventInfo,
     dom::{EventFilter, NotEventFilter, HoverEventFilter, FocusEventFilter, WindowEventFilter},
@@ -225,6 +226,21 @@ pub struct WindowState {
     pub is_visible: bool,
     /// Is the window always on top?
     pub is_always_on_top: bool,
+    /// Color scheme used to evaluate `prefers-color-scheme` media queries. The system theme
+    /// can't be queried from the windowing library, so this is `Light` unless set manually.
+    pub color_scheme: ColorScheme,
+}
+
+impl WindowState {
+    /// Returns the current state of the window that `@media` queries are evaluated against
+    pub(crate) fn get_media_query_context(&self) -> MediaQueryContext {
+        MediaQueryContext {
+            width: self.size.dimensions.width as f32,
+            height: self.size.dimensions.height as f32,
+            hidpi_factor: self.size.hidpi_factor as f32,
+            color_scheme: self.color_scheme,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -282,6 +298,7 @@ impl Default for WindowState {
             has_decorations: true,
             is_visible: true,
             is_always_on_top: false,
+            color_scheme: ColorScheme::default(),
             debug_state: DebugState::default(),
         }
     }
@@ -942,6 +959,8 @@ pub(crate) fn window_should_close(event: &WindowEvent, frame_event_info: &mut Fr
         },
         WindowEvent::HiDpiFactorChanged(dpi) => {
             frame_event_info.new_dpi_factor = Some(*dpi);
+            // Relayout, so that `resolution` media queries are re-evaluated
+            frame_event_info.is_resize_event = true;
             frame_event_info.should_redraw_window = true;
         },
         WindowEvent::CloseRequested | WindowEvent::Destroyed => {
@@ -497,34 +497,23 @@ fn parse_component_arguments(input: &str) -> Result<ComponentArguments, Componen
 
 pub type FilteredComponentArguments = ComponentArguments;
 
-/// Filters the XML attributes of a component given XmlAttributeMap
-fn validate_and_filter_component_args(xml_attributes: &XmlAttributeMap, valid_args: &FilteredComponentArguments)
--> Result<FilteredComponentArguments, RenderDomError> {
+/// Attributes that are valid on every node and that are mapped to the ID, classes, etc. of the node
+const DEFAULT_ARGS: [&str;5] = ["id", "class", "tabindex", "draggable", "focusable"];
 
-    const DEFAULT_ARGS: [&str;5] = ["id", "class", "tabindex", "draggable", "focusable"];
+/// Filters the XML attributes of a component given XmlAttributeMap - attributes that are
+/// neither arguments of the component nor default arguments are later set as DOM attributes
+fn validate_and_filter_component_args(xml_attributes: &XmlAttributeMap, valid_args: &FilteredComponentArguments)
+-> FilteredComponentArguments {
 
     let mut map = FilteredComponentArguments::default();
 
-    for (xml_attribute_name, xml_attribute_value) in xml_attributes.iter() {
-
-        let arg_value = match valid_args.get(xml_attribute_name) {
-            Some(s) => Some(s),
-            None => {
-                if DEFAULT_ARGS.contains(&xml_attribute_name.as_str()) {
-                    None // no error, but don't insert the attribute name
-                } else {
-                    let keys = valid_args.keys().cloned().collect();
-                    return Err(RenderDomError::UselessFunctionArgument(xml_attribute_name.clone(), xml_attribute_value.clone(), keys));
-                }
-            }
-        };
-
-        if let Some(value) = arg_value {
+    for xml_attribute_name in xml_attributes.keys() {
+        if let Some(value) = valid_args.get(xml_attribute_name) {
             map.insert(xml_attribute_name.clone(), value.clone());
         }
     }
 
-    Ok(map)
+    map
 }
 
 /// Normalizes input such as `abcDef`, `AbcDef`, `abc-def` to the normalized form of `abc_def`
@@ -670,7 +659,7 @@ fn render_dom_from_app_node_inner<T>(
 
     // Arguments of the current node
     let available_function_args = renderer.get_available_arguments();
-    let mut filtered_xml_attributes = validate_and_filter_component_args(&xml_node.attributes, &available_function_args)?;
+    let mut filtered_xml_attributes = validate_and_filter_component_args(&xml_node.attributes, &available_function_args);
 
     if *inherit_variables {
         // Append all variables that are in scope for the parent node
@@ -685,7 +674,7 @@ fn render_dom_from_app_node_inner<T>(
     let text = xml_node.text.as_ref().map(|t| format_args_dynamic(t, &filtered_xml_attributes));
 
     let mut dom = renderer.render_dom(component_map, &filtered_xml_attributes, &text)?;
-    set_attributes(&mut dom, &xml_node.attributes, &filtered_xml_attributes);
+    set_attributes(&mut dom, &xml_node.attributes, &available_function_args, &filtered_xml_attributes);
 
     for child_node in &xml_node.children {
         dom.add_child(render_dom_from_app_node_inner(child_node, component_map, &filtered_xml_attributes)?);
@@ -694,7 +683,12 @@ fn render_dom_from_app_node_inner<T>(
     Ok(dom)
 }
 
-fn set_attributes<T>(dom: &mut Dom<T>, xml_attributes: &XmlAttributeMap, filtered_xml_attributes: &FilteredComponentArguments) {
+fn set_attributes<T>(
+    dom: &mut Dom<T>,
+    xml_attributes: &XmlAttributeMap,
+    component_args: &ComponentArguments,
+    filtered_xml_attributes: &FilteredComponentArguments,
+) {
 
     use dom::{TabIndex, DomString};
 
@@ -737,6 +731,14 @@ fn set_attributes<T>(dom: &mut Dom<T>, xml_attributes: &XmlAttributeMap, filtere
             _ => dom.set_tab_index(TabIndex::NoKeyboardFocus),
         }
     }
+
+    // All other attributes (that aren't consumed by the component) are stored on the node
+    for (key, value) in xml_attributes {
+        if DEFAULT_ARGS.contains(&key.as_str()) || component_args.contains_key(key) {
+            continue;
+        }
+        dom.add_attribute(DomString::Heap(key.clone()), DomString::Heap(format_args_dynamic(value, &filtered_xml_attributes)));
+    }
 }
 
 /// Given a string and a key => value mapping, replaces parts of the string with the value, i.e.:
@@ -857,6 +859,10 @@ fn render_single_dom_node_to_string<T>(dom: &Dom<T>, existing_str: &mut String)
         existing_str.push_str(&format!(".with_class({})", class));
     }
 
+    for (key, value) in &head.attributes {
+        existing_str.push_str(&format!(".with_attribute({:?}, {:?})", key.as_str(), value.as_str()));
+    }
+
     if let Some(tab_index) = &head.tab_index {
         use dom::TabIndex::*;
         existing_str.push_str(&format!(".with_tab_index({})", match tab_index {
@@ -1157,4 +1163,10 @@ fn test_prepare_string_2() {
 
     let output = prepare_string(input1);
     assert_eq!(output, String::from("Hello, 123\nTest Test2\nTest3\nTest4"));
-}
\ No newline at end of file
+}
+#[test]
+fn test_xml_attributes_round_trip() {
+    struct Dummy;
+    let dom: Dom<Dummy> = Dom::div().with_class("a").with_attribute("role", "button").with_attribute("data-index", "5");
+    dom.assert_eq("<div class='a' role='button' data-index='5' />");
+}